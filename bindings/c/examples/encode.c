@@ -0,0 +1,39 @@
+#include <stdio.h>
+
+#include "tokenizers.h"
+
+int main(int argc, char **argv) {
+  if (argc != 3) {
+    fprintf(stderr, "usage: %s <tokenizer.json> <text>\n", argv[0]);
+    return 1;
+  }
+
+  Tokenizer *tokenizer = tokenizer_from_file(argv[1]);
+  if (tokenizer == NULL) {
+    fprintf(stderr, "could not load the tokenizer: %s\n", tokenizers_last_error());
+    return 1;
+  }
+
+  Encoding *encoding = tokenizer_encode(tokenizer, argv[2], true);
+  if (encoding == NULL) {
+    fprintf(stderr, "could not encode: %s\n", tokenizers_last_error());
+    tokenizer_free(tokenizer);
+    return 1;
+  }
+
+  size_t length = encoding_get_length(encoding);
+  const uint32_t *ids = encoding_get_ids(encoding);
+  const TokenOffsets *offsets = encoding_get_offsets(encoding);
+  for (size_t i = 0; i < length; i++) {
+    printf("%u\t%s\t(%zu, %zu)\n", ids[i], encoding_get_token(encoding, i), offsets[i].start,
+           offsets[i].end);
+  }
+
+  char *decoded = tokenizer_decode(tokenizer, ids, length, true);
+  printf("%s\n", decoded);
+
+  tokenizers_string_free(decoded);
+  encoding_free(encoding);
+  tokenizer_free(tokenizer);
+  return 0;
+}
@@ -0,0 +1,221 @@
+#ifndef TOKENIZERS_H
+#define TOKENIZERS_H
+
+/* Generated by cbindgen, do not edit: run `make header` instead */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * The output of a `Tokenizer`. All the arrays it exposes have `encoding_get_length`
+ * elements, and stay valid until the `Encoding` is freed.
+ */
+typedef struct Encoding Encoding;
+
+/**
+ * A `Tokenizer`, as loaded from its JSON serialization.
+ *
+ * It is never modified by the functions taking a `const Tokenizer *`, which can be called
+ * from several threads at once.
+ */
+typedef struct Tokenizer Tokenizer;
+
+/**
+ * The offsets of a token in the original input, in bytes
+ */
+typedef struct TokenOffsets {
+  size_t start;
+  size_t end;
+} TokenOffsets;
+
+#ifdef __cplusplus
+extern "C" {
+#endif  // __cplusplus
+
+/**
+ * Frees an `Encoding`
+ *
+ * # Safety
+ * `encoding` must be NULL or an `Encoding` returned by the library, that has not been
+ * freed yet.
+ */
+void encoding_free(Encoding *encoding);
+
+/**
+ * Returns the attention mask, which is 0 for the padding tokens and 1 for the others
+ *
+ * # Safety
+ * `encoding` must be an `Encoding` returned by the library, that has not been freed yet.
+ */
+const uint32_t *encoding_get_attention_mask(const Encoding *encoding);
+
+/**
+ * Returns the ids of the tokens
+ *
+ * # Safety
+ * `encoding` must be an `Encoding` returned by the library, that has not been freed yet.
+ */
+const uint32_t *encoding_get_ids(const Encoding *encoding);
+
+/**
+ * Returns the number of tokens in the `Encoding`
+ *
+ * # Safety
+ * `encoding` must be an `Encoding` returned by the library, that has not been freed yet.
+ */
+size_t encoding_get_length(const Encoding *encoding);
+
+/**
+ * Returns the offsets of the tokens in the original input
+ *
+ * # Safety
+ * `encoding` must be an `Encoding` returned by the library, that has not been freed yet.
+ */
+const TokenOffsets *encoding_get_offsets(const Encoding *encoding);
+
+/**
+ * Returns the special tokens mask, which is 1 for the special tokens and 0 for the others
+ *
+ * # Safety
+ * `encoding` must be an `Encoding` returned by the library, that has not been freed yet.
+ */
+const uint32_t *encoding_get_special_tokens_mask(const Encoding *encoding);
+
+/**
+ * Returns the token at `index`, or NULL if it is out of range. The string stays valid
+ * until the `Encoding` is freed.
+ *
+ * # Safety
+ * `encoding` must be an `Encoding` returned by the library, that has not been freed yet.
+ */
+const char *encoding_get_token(const Encoding *encoding, size_t index);
+
+/**
+ * Returns the type ids of the tokens, which tell the sequence each token belongs to
+ *
+ * # Safety
+ * `encoding` must be an `Encoding` returned by the library, that has not been freed yet.
+ */
+const uint32_t *encoding_get_type_ids(const Encoding *encoding);
+
+/**
+ * Decodes the `len` given ids back to a string, or returns NULL on failure. The string
+ * must be freed with `tokenizers_string_free`.
+ *
+ * # Safety
+ * `tokenizer` must be a `Tokenizer` returned by the library, that has not been freed yet,
+ * and `ids` must point to `len` ids. It may be NULL when `len` is 0.
+ */
+char *tokenizer_decode(const Tokenizer *tokenizer,
+                       const uint32_t *ids,
+                       size_t len,
+                       bool skip_special_tokens);
+
+/**
+ * Encodes the given sequence, or returns NULL on failure. The `Encoding` must be freed
+ * with `encoding_free`.
+ *
+ * # Safety
+ * `tokenizer` must be a `Tokenizer` returned by the library, that has not been freed yet,
+ * and `sequence` a nul terminated string.
+ */
+Encoding *tokenizer_encode(const Tokenizer *tokenizer,
+                           const char *sequence,
+                           bool add_special_tokens);
+
+/**
+ * Encodes the given pair of sequences, or returns NULL on failure. The `Encoding` must be
+ * freed with `encoding_free`.
+ *
+ * # Safety
+ * `tokenizer` must be a `Tokenizer` returned by the library, that has not been freed yet,
+ * and `sequence` and `pair` nul terminated strings.
+ */
+Encoding *tokenizer_encode_pair(const Tokenizer *tokenizer,
+                                const char *sequence,
+                                const char *pair,
+                                bool add_special_tokens);
+
+/**
+ * Frees a `Tokenizer`
+ *
+ * # Safety
+ * `tokenizer` must be NULL or a `Tokenizer` returned by the library, that has not been
+ * freed yet.
+ */
+void tokenizer_free(Tokenizer *tokenizer);
+
+/**
+ * Loads a `Tokenizer` from the JSON file at `path`, or returns NULL on failure
+ *
+ * # Safety
+ * `path` must be a nul terminated string.
+ */
+Tokenizer *tokenizer_from_file(const char *path);
+
+/**
+ * Loads a `Tokenizer` from its JSON serialization, or returns NULL on failure
+ *
+ * # Safety
+ * `json` must be a nul terminated string.
+ */
+Tokenizer *tokenizer_from_str(const char *json);
+
+/**
+ * Returns the size of the vocabulary, with or without the added tokens
+ *
+ * # Safety
+ * `tokenizer` must be a `Tokenizer` returned by the library, that has not been freed yet.
+ */
+size_t tokenizer_get_vocab_size(const Tokenizer *tokenizer, bool with_added_tokens);
+
+/**
+ * Returns the token with the given id, or NULL if there is none. The string must be freed
+ * with `tokenizers_string_free`.
+ *
+ * # Safety
+ * `tokenizer` must be a `Tokenizer` returned by the library, that has not been freed yet.
+ */
+char *tokenizer_id_to_token(const Tokenizer *tokenizer, uint32_t id);
+
+/**
+ * Serializes the `Tokenizer` to JSON, or returns NULL on failure. The string must be freed
+ * with `tokenizers_string_free`.
+ *
+ * # Safety
+ * `tokenizer` must be a `Tokenizer` returned by the library, that has not been freed yet.
+ */
+char *tokenizer_to_string(const Tokenizer *tokenizer, bool pretty);
+
+/**
+ * Writes the id of `token` to `id`, returning whether the token is in the vocabulary
+ *
+ * # Safety
+ * `tokenizer` must be a `Tokenizer` returned by the library, that has not been freed yet,
+ * `token` a nul terminated string, and `id` point to a writable `uint32_t`.
+ */
+bool tokenizer_token_to_id(const Tokenizer *tokenizer, const char *token, uint32_t *id);
+
+/**
+ * Returns the message of the error raised by the last call made on this thread, or NULL
+ * if it succeeded.
+ *
+ * The message is owned by the library and stays valid until the next call on this thread.
+ */
+const char *tokenizers_last_error(void);
+
+/**
+ * Frees a string returned by the library.
+ *
+ * # Safety
+ * `s` must be NULL or a string returned by the library, that has not been freed yet.
+ */
+void tokenizers_string_free(char *s);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* TOKENIZERS_H */
@@ -0,0 +1,139 @@
+use crate::error::{borrow, guard};
+use std::ffi::CString;
+use std::os::raw::c_char;
+
+/// The offsets of a token in the original input, in bytes
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TokenOffsets {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// The output of a `Tokenizer`. All the arrays it exposes have `encoding_get_length`
+/// elements, and stay valid until the `Encoding` is freed.
+pub struct Encoding {
+    encoding: tk::Encoding,
+    offsets: Vec<TokenOffsets>,
+    tokens: Vec<CString>,
+}
+
+impl From<tk::Encoding> for Encoding {
+    fn from(encoding: tk::Encoding) -> Self {
+        let offsets = encoding
+            .get_offsets()
+            .iter()
+            .map(|&(start, end)| TokenOffsets { start, end })
+            .collect();
+        let tokens = encoding
+            .get_tokens()
+            .iter()
+            .map(|token| crate::c_string(token.clone()))
+            .collect();
+        Self {
+            encoding,
+            offsets,
+            tokens,
+        }
+    }
+}
+
+/// Runs `f` on the given `Encoding`, returning `default` if it fails
+unsafe fn with_encoding<T>(
+    encoding: *const Encoding,
+    default: T,
+    f: impl FnOnce(&Encoding) -> T,
+) -> T {
+    guard(|| borrow(encoding, "encoding").map(f)).unwrap_or(default)
+}
+
+/// Returns the number of tokens in the `Encoding`
+///
+/// # Safety
+/// `encoding` must be an `Encoding` returned by the library, that has not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn encoding_get_length(encoding: *const Encoding) -> usize {
+    with_encoding(encoding, 0, |e| e.encoding.len())
+}
+
+/// Returns the ids of the tokens
+///
+/// # Safety
+/// `encoding` must be an `Encoding` returned by the library, that has not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn encoding_get_ids(encoding: *const Encoding) -> *const u32 {
+    with_encoding(encoding, std::ptr::null(), |e| {
+        e.encoding.get_ids().as_ptr()
+    })
+}
+
+/// Returns the type ids of the tokens, which tell the sequence each token belongs to
+///
+/// # Safety
+/// `encoding` must be an `Encoding` returned by the library, that has not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn encoding_get_type_ids(encoding: *const Encoding) -> *const u32 {
+    with_encoding(encoding, std::ptr::null(), |e| {
+        e.encoding.get_type_ids().as_ptr()
+    })
+}
+
+/// Returns the attention mask, which is 0 for the padding tokens and 1 for the others
+///
+/// # Safety
+/// `encoding` must be an `Encoding` returned by the library, that has not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn encoding_get_attention_mask(encoding: *const Encoding) -> *const u32 {
+    with_encoding(encoding, std::ptr::null(), |e| {
+        e.encoding.get_attention_mask().as_ptr()
+    })
+}
+
+/// Returns the special tokens mask, which is 1 for the special tokens and 0 for the others
+///
+/// # Safety
+/// `encoding` must be an `Encoding` returned by the library, that has not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn encoding_get_special_tokens_mask(encoding: *const Encoding) -> *const u32 {
+    with_encoding(encoding, std::ptr::null(), |e| {
+        e.encoding.get_special_tokens_mask().as_ptr()
+    })
+}
+
+/// Returns the offsets of the tokens in the original input
+///
+/// # Safety
+/// `encoding` must be an `Encoding` returned by the library, that has not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn encoding_get_offsets(encoding: *const Encoding) -> *const TokenOffsets {
+    with_encoding(encoding, std::ptr::null(), |e| e.offsets.as_ptr())
+}
+
+/// Returns the token at `index`, or NULL if it is out of range. The string stays valid
+/// until the `Encoding` is freed.
+///
+/// # Safety
+/// `encoding` must be an `Encoding` returned by the library, that has not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn encoding_get_token(
+    encoding: *const Encoding,
+    index: usize,
+) -> *const c_char {
+    with_encoding(encoding, std::ptr::null(), |e| {
+        e.tokens
+            .get(index)
+            .map_or(std::ptr::null(), |token| token.as_ptr())
+    })
+}
+
+/// Frees an `Encoding`
+///
+/// # Safety
+/// `encoding` must be NULL or an `Encoding` returned by the library, that has not been
+/// freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn encoding_free(encoding: *mut Encoding) {
+    if !encoding.is_null() {
+        drop(Box::from_raw(encoding));
+    }
+}
@@ -0,0 +1,77 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::ffi::{CStr, CString};
+use std::fmt::Display;
+use std::os::raw::c_char;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use tk::Result;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: impl Display) {
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(crate::c_string(message.to_string())));
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        format!("tokenizers panicked: {message}")
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        format!("tokenizers panicked: {message}")
+    } else {
+        "tokenizers panicked".into()
+    }
+}
+
+/// Runs `f` on behalf of an exported function. Both the errors and the panics are caught,
+/// to be reported by `tokenizers_last_error` instead of unwinding into the host.
+pub(crate) fn guard<T>(f: impl FnOnce() -> Result<T>) -> Option<T> {
+    LAST_ERROR.with(|last| *last.borrow_mut() = None);
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(value)) => Some(value),
+        Ok(Err(e)) => {
+            set_last_error(e);
+            None
+        }
+        Err(payload) => {
+            set_last_error(panic_message(payload));
+            None
+        }
+    }
+}
+
+/// Borrows the given pointer, failing with an error naming `what` when it is null
+///
+/// # Safety
+/// `ptr` must be null or valid for the returned lifetime.
+pub(crate) unsafe fn borrow<'a, T>(ptr: *const T, what: &str) -> Result<&'a T> {
+    ptr.as_ref()
+        .ok_or_else(|| format!("`{what}` must not be NULL").into())
+}
+
+/// Borrows the given nul terminated string, which must be valid UTF-8
+///
+/// # Safety
+/// `ptr` must be null or a nul terminated string valid for the returned lifetime.
+pub(crate) unsafe fn borrow_str<'a>(ptr: *const c_char, what: &str) -> Result<&'a str> {
+    if ptr.is_null() {
+        return Err(format!("`{what}` must not be NULL").into());
+    }
+    CStr::from_ptr(ptr)
+        .to_str()
+        .map_err(|e| format!("`{what}` is not valid UTF-8: {e}").into())
+}
+
+/// Returns the message of the error raised by the last call made on this thread, or NULL
+/// if it succeeded.
+///
+/// The message is owned by the library and stays valid until the next call on this thread.
+#[no_mangle]
+pub extern "C" fn tokenizers_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map_or(std::ptr::null(), |message| message.as_ptr())
+    })
+}
@@ -0,0 +1,87 @@
+//! C bindings for the tokenizers library.
+//!
+//! Every function is exported with a stable C ABI, and declared in `include/tokenizers.h`,
+//! generated by `cbindgen` with `make header`.
+//!
+//! The functions returning a pointer return NULL on failure, in which case
+//! `tokenizers_last_error` describes what went wrong. The objects and strings returned by
+//! the library are owned by the caller, and must be released with the matching `*_free`
+//! function.
+#![warn(clippy::all)]
+
+extern crate tokenizers as tk;
+
+mod encoding;
+mod error;
+mod tokenizer;
+
+pub use encoding::*;
+pub use error::tokenizers_last_error;
+pub use tokenizer::*;
+
+use std::ffi::CString;
+use std::os::raw::c_char;
+
+/// Converts `s` to a C string. C strings can't contain any nul byte, so these are dropped.
+pub(crate) fn c_string(s: String) -> CString {
+    CString::new(s).unwrap_or_else(|e| {
+        let bytes = e.into_vec().into_iter().filter(|b| *b != 0).collect();
+        // SAFETY: All the nul bytes have just been removed
+        unsafe { CString::from_vec_unchecked(bytes) }
+    })
+}
+
+/// Frees a string returned by the library.
+///
+/// # Safety
+/// `s` must be NULL or a string returned by the library, that has not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn c_string_drops_nul_bytes() {
+        assert_eq!(c_string("a\0b".into()).as_bytes(), b"ab");
+        assert_eq!(c_string("ab".into()).as_bytes(), b"ab");
+    }
+
+    #[test]
+    fn header_declares_every_function() {
+        let header = include_str!("../include/tokenizers.h");
+        let sources = [
+            include_str!("lib.rs"),
+            include_str!("error.rs"),
+            include_str!("encoding.rs"),
+            include_str!("tokenizer.rs"),
+        ];
+        let exported = sources
+            .iter()
+            .flat_map(|source| source.split("extern \"C\" fn ").skip(1))
+            .map(|rest| &rest[..rest.find('(').unwrap()])
+            .collect::<Vec<_>>();
+
+        assert!(exported.len() > 10);
+        for name in exported {
+            assert!(
+                header.contains(&format!("{name}(")),
+                "`{name}` is missing from the header, run `make header`"
+            );
+        }
+    }
+
+    #[test]
+    fn string_free() {
+        let s = c_string("ok".into()).into_raw();
+        unsafe {
+            tokenizers_string_free(s);
+            tokenizers_string_free(std::ptr::null_mut());
+        }
+    }
+}
@@ -0,0 +1,351 @@
+use crate::encoding::Encoding;
+use crate::error::{borrow, borrow_str, guard};
+use std::os::raw::c_char;
+use std::str::FromStr;
+use tk::{EncodeInput, Result};
+
+/// A `Tokenizer`, as loaded from its JSON serialization.
+///
+/// It is never modified by the functions taking a `const Tokenizer *`, which can be called
+/// from several threads at once.
+pub struct Tokenizer(tk::Tokenizer);
+
+fn into_raw<T>(value: Option<T>) -> *mut T {
+    value.map_or(std::ptr::null_mut(), |value| Box::into_raw(Box::new(value)))
+}
+
+fn into_raw_string(value: Option<String>) -> *mut c_char {
+    value.map_or(std::ptr::null_mut(), |value| {
+        crate::c_string(value).into_raw()
+    })
+}
+
+/// Loads a `Tokenizer` from the JSON file at `path`, or returns NULL on failure
+///
+/// # Safety
+/// `path` must be a nul terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizer_from_file(path: *const c_char) -> *mut Tokenizer {
+    into_raw(guard(|| {
+        let path = borrow_str(path, "path")?;
+        Ok(Tokenizer(tk::Tokenizer::from_file(path)?))
+    }))
+}
+
+/// Loads a `Tokenizer` from its JSON serialization, or returns NULL on failure
+///
+/// # Safety
+/// `json` must be a nul terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizer_from_str(json: *const c_char) -> *mut Tokenizer {
+    into_raw(guard(|| {
+        let json = borrow_str(json, "json")?;
+        Ok(Tokenizer(tk::Tokenizer::from_str(json)?))
+    }))
+}
+
+/// Serializes the `Tokenizer` to JSON, or returns NULL on failure. The string must be freed
+/// with `tokenizers_string_free`.
+///
+/// # Safety
+/// `tokenizer` must be a `Tokenizer` returned by the library, that has not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizer_to_string(
+    tokenizer: *const Tokenizer,
+    pretty: bool,
+) -> *mut c_char {
+    into_raw_string(guard(|| {
+        borrow(tokenizer, "tokenizer")?.0.to_string(pretty)
+    }))
+}
+
+/// Frees a `Tokenizer`
+///
+/// # Safety
+/// `tokenizer` must be NULL or a `Tokenizer` returned by the library, that has not been
+/// freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizer_free(tokenizer: *mut Tokenizer) {
+    if !tokenizer.is_null() {
+        drop(Box::from_raw(tokenizer));
+    }
+}
+
+unsafe fn encode<'s>(
+    tokenizer: *const Tokenizer,
+    input: impl FnOnce() -> Result<EncodeInput<'s>>,
+    add_special_tokens: bool,
+) -> *mut Encoding {
+    into_raw(guard(|| {
+        let tokenizer = &borrow(tokenizer, "tokenizer")?.0;
+        Ok(tokenizer.encode(input()?, add_special_tokens)?.into())
+    }))
+}
+
+/// Encodes the given sequence, or returns NULL on failure. The `Encoding` must be freed
+/// with `encoding_free`.
+///
+/// # Safety
+/// `tokenizer` must be a `Tokenizer` returned by the library, that has not been freed yet,
+/// and `sequence` a nul terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizer_encode(
+    tokenizer: *const Tokenizer,
+    sequence: *const c_char,
+    add_special_tokens: bool,
+) -> *mut Encoding {
+    encode(
+        tokenizer,
+        || Ok(borrow_str(sequence, "sequence")?.into()),
+        add_special_tokens,
+    )
+}
+
+/// Encodes the given pair of sequences, or returns NULL on failure. The `Encoding` must be
+/// freed with `encoding_free`.
+///
+/// # Safety
+/// `tokenizer` must be a `Tokenizer` returned by the library, that has not been freed yet,
+/// and `sequence` and `pair` nul terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizer_encode_pair(
+    tokenizer: *const Tokenizer,
+    sequence: *const c_char,
+    pair: *const c_char,
+    add_special_tokens: bool,
+) -> *mut Encoding {
+    encode(
+        tokenizer,
+        || {
+            let sequence = borrow_str(sequence, "sequence")?;
+            let pair = borrow_str(pair, "pair")?;
+            Ok((sequence, pair).into())
+        },
+        add_special_tokens,
+    )
+}
+
+/// Decodes the `len` given ids back to a string, or returns NULL on failure. The string
+/// must be freed with `tokenizers_string_free`.
+///
+/// # Safety
+/// `tokenizer` must be a `Tokenizer` returned by the library, that has not been freed yet,
+/// and `ids` must point to `len` ids. It may be NULL when `len` is 0.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizer_decode(
+    tokenizer: *const Tokenizer,
+    ids: *const u32,
+    len: usize,
+    skip_special_tokens: bool,
+) -> *mut c_char {
+    into_raw_string(guard(|| {
+        let tokenizer = &borrow(tokenizer, "tokenizer")?.0;
+        let ids = if len == 0 {
+            &[]
+        } else {
+            std::slice::from_raw_parts(borrow(ids, "ids")?, len)
+        };
+        tokenizer.decode(ids, skip_special_tokens)
+    }))
+}
+
+/// Writes the id of `token` to `id`, returning whether the token is in the vocabulary
+///
+/// # Safety
+/// `tokenizer` must be a `Tokenizer` returned by the library, that has not been freed yet,
+/// `token` a nul terminated string, and `id` point to a writable `uint32_t`.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizer_token_to_id(
+    tokenizer: *const Tokenizer,
+    token: *const c_char,
+    id: *mut u32,
+) -> bool {
+    guard(|| {
+        let tokenizer = &borrow(tokenizer, "tokenizer")?.0;
+        let token = borrow_str(token, "token")?;
+        if id.is_null() {
+            return Err("`id` must not be NULL".into());
+        }
+        Ok(tokenizer
+            .token_to_id(token)
+            .map(|found| *id = found)
+            .is_some())
+    })
+    .unwrap_or(false)
+}
+
+/// Returns the token with the given id, or NULL if there is none. The string must be freed
+/// with `tokenizers_string_free`.
+///
+/// # Safety
+/// `tokenizer` must be a `Tokenizer` returned by the library, that has not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizer_id_to_token(
+    tokenizer: *const Tokenizer,
+    id: u32,
+) -> *mut c_char {
+    into_raw_string(guard(|| Ok(borrow(tokenizer, "tokenizer")?.0.id_to_token(id))).flatten())
+}
+
+/// Returns the size of the vocabulary, with or without the added tokens
+///
+/// # Safety
+/// `tokenizer` must be a `Tokenizer` returned by the library, that has not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizer_get_vocab_size(
+    tokenizer: *const Tokenizer,
+    with_added_tokens: bool,
+) -> usize {
+    guard(|| {
+        Ok(borrow(tokenizer, "tokenizer")?
+            .0
+            .get_vocab_size(with_added_tokens))
+    })
+    .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::*;
+    use crate::error::tokenizers_last_error;
+    use crate::tokenizers_string_free;
+    use std::ffi::{CStr, CString};
+    use tk::models::wordlevel::WordLevel;
+    use tk::pre_tokenizers::whitespace::WhitespaceSplit;
+    use tk::AddedToken;
+
+    fn tokenizer() -> *mut Tokenizer {
+        let vocab = [("[UNK]", 0), ("hello", 1), ("world", 2)]
+            .into_iter()
+            .map(|(token, id)| (token.to_string(), id))
+            .collect();
+        let model = WordLevel::builder()
+            .vocab(vocab)
+            .unk_token("[UNK]".into())
+            .build()
+            .unwrap();
+        let mut tokenizer = tk::Tokenizer::new(model);
+        tokenizer.with_pre_tokenizer(Some(WhitespaceSplit));
+        tokenizer.add_special_tokens(&[AddedToken::from("[SEP]", true)]);
+
+        let json = CString::new(tokenizer.to_string(false).unwrap()).unwrap();
+        let tokenizer = unsafe { tokenizer_from_str(json.as_ptr()) };
+        assert!(!tokenizer.is_null());
+        tokenizer
+    }
+
+    fn last_error() -> Option<String> {
+        let error = tokenizers_last_error();
+        (!error.is_null()).then(|| unsafe { CStr::from_ptr(error) }.to_str().unwrap().into())
+    }
+
+    unsafe fn take_string(s: *mut c_char) -> String {
+        let owned = CStr::from_ptr(s).to_str().unwrap().to_owned();
+        tokenizers_string_free(s);
+        owned
+    }
+
+    #[test]
+    fn encode_and_decode() {
+        let tokenizer = tokenizer();
+        let sequence = CString::new("hello world [SEP] there").unwrap();
+        unsafe {
+            let encoding = tokenizer_encode(tokenizer, sequence.as_ptr(), true);
+            assert!(!encoding.is_null());
+            let len = encoding_get_length(encoding);
+            assert_eq!(len, 4);
+            let ids = std::slice::from_raw_parts(encoding_get_ids(encoding), len);
+            assert_eq!(ids, [1, 2, 3, 0]);
+            let mask = std::slice::from_raw_parts(encoding_get_attention_mask(encoding), len);
+            assert_eq!(mask, [1, 1, 1, 1]);
+            let offsets = std::slice::from_raw_parts(encoding_get_offsets(encoding), len);
+            assert_eq!(offsets[1], TokenOffsets { start: 6, end: 11 });
+            let token = CStr::from_ptr(encoding_get_token(encoding, 3));
+            assert_eq!(token.to_str().unwrap(), "[UNK]");
+            assert!(encoding_get_token(encoding, 4).is_null());
+
+            let decoded = tokenizer_decode(tokenizer, ids.as_ptr(), len, true);
+            assert_eq!(take_string(decoded), "hello world [UNK]");
+            encoding_free(encoding);
+
+            let encoding =
+                tokenizer_encode_pair(tokenizer, sequence.as_ptr(), sequence.as_ptr(), false);
+            let len = encoding_get_length(encoding);
+            let type_ids = std::slice::from_raw_parts(encoding_get_type_ids(encoding), len);
+            assert_eq!(type_ids, [0, 0, 0, 0, 1, 1, 1, 1]);
+            encoding_free(encoding);
+
+            assert_eq!(
+                take_string(tokenizer_decode(tokenizer, std::ptr::null(), 0, true)),
+                ""
+            );
+            tokenizer_free(tokenizer);
+        }
+    }
+
+    #[test]
+    fn vocab() {
+        let tokenizer = tokenizer();
+        let token = CString::new("world").unwrap();
+        let missing = CString::new("there").unwrap();
+        let mut id = 0;
+        unsafe {
+            assert!(tokenizer_token_to_id(tokenizer, token.as_ptr(), &mut id));
+            assert_eq!(id, 2);
+            assert!(!tokenizer_token_to_id(tokenizer, missing.as_ptr(), &mut id));
+            assert_eq!(id, 2);
+            assert_eq!(last_error(), None);
+
+            assert_eq!(take_string(tokenizer_id_to_token(tokenizer, 3)), "[SEP]");
+            assert!(tokenizer_id_to_token(tokenizer, 4).is_null());
+            assert_eq!(tokenizer_get_vocab_size(tokenizer, false), 3);
+            assert_eq!(tokenizer_get_vocab_size(tokenizer, true), 4);
+            tokenizer_free(tokenizer);
+        }
+    }
+
+    #[test]
+    fn serialization() {
+        let tokenizer = tokenizer();
+        let path =
+            std::env::temp_dir().join(format!("tokenizers-cabi-{}.json", std::process::id()));
+        unsafe {
+            let json = take_string(tokenizer_to_string(tokenizer, true));
+            std::fs::write(&path, json).unwrap();
+            let path = CString::new(path.to_str().unwrap()).unwrap();
+            let loaded = tokenizer_from_file(path.as_ptr());
+            assert!(!loaded.is_null());
+            assert_eq!(tokenizer_get_vocab_size(loaded, true), 4);
+
+            tokenizer_free(loaded);
+            tokenizer_free(tokenizer);
+        }
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn errors() {
+        let invalid = CString::new("{").unwrap();
+        let missing = CString::new("/does/not/exist.json").unwrap();
+        unsafe {
+            assert!(tokenizer_from_str(invalid.as_ptr()).is_null());
+            assert!(last_error().unwrap().contains("EOF"));
+            assert!(tokenizer_from_file(missing.as_ptr()).is_null());
+            assert!(last_error().is_some());
+            assert!(tokenizer_from_file(std::ptr::null()).is_null());
+            assert_eq!(last_error().unwrap(), "`path` must not be NULL");
+
+            let tokenizer = tokenizer();
+            assert_eq!(last_error(), None);
+            let encoding = tokenizer_encode(tokenizer, std::ptr::null(), true);
+            assert!(encoding.is_null());
+            assert_eq!(last_error().unwrap(), "`sequence` must not be NULL");
+            assert!(tokenizer_decode(tokenizer, std::ptr::null(), 1, true).is_null());
+            assert_eq!(last_error().unwrap(), "`ids` must not be NULL");
+            assert_eq!(encoding_get_length(std::ptr::null()), 0);
+            assert_eq!(last_error().unwrap(), "`encoding` must not be NULL");
+            tokenizer_free(tokenizer);
+        }
+    }
+}
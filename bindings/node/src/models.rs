@@ -121,6 +121,16 @@ impl tk::Model for Model {
       .get_vocab_size()
   }
 
+  fn token_ids_with_prefix(&self, prefix: &[u8]) -> Vec<u32> {
+    self
+      .model
+      .as_ref()
+      .expect("Uninitialized Model")
+      .read()
+      .unwrap()
+      .token_ids_with_prefix(prefix)
+  }
+
   fn save(&self, folder: &Path, name: Option<&str>) -> tk::Result<Vec<PathBuf>> {
     self
       .model
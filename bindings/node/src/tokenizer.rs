@@ -133,6 +133,7 @@ impl TryFrom<TruncationOptions> for tk::TruncationParams {
       strategy: value.strategy.map(|s| s.into()).unwrap_or_default(),
       direction,
       stride: value.stride.unwrap_or_default() as usize,
+      ..Default::default()
     })
   }
 }
@@ -78,14 +78,23 @@ class SplitDelimiterBehavior(Enum):
 from .tokenizers import (
     AddedToken,
     Encoding,
+    IncompatibleDecoderError,
+    LimitExceededError,
+    MissingUnkTokenError,
     NormalizedString,
+    PipelineError,
+    PaddingParams,
     PreTokenizedString,
     Regex,
+    ShadowTokenizer,
     Token,
+    TokenPattern,
     Tokenizer,
+    TruncationParams,
     decoders,
     models,
     normalizers,
+    offsets,
     pre_tokenizers,
     processors,
     trainers,
@@ -33,8 +33,28 @@ class AddedToken:
         special (:obj:`bool`, defaults to :obj:`False` with :meth:`~tokenizers.Tokenizer.add_tokens` and :obj:`False` with :meth:`~tokenizers.Tokenizer.add_special_tokens`):
             Defines whether this token should be skipped when decoding.
 
+        pattern (:obj:`bool`, defaults to :obj:`False`):
+            Defines whether the content is a regex pattern, matching a whole family of tokens
+            like ``<extra_id_[0-9]+>``. Any text matching it is extracted as a single token, with
+            the id of this text if it is part of the vocabulary, or the id of the pattern token
+            otherwise.
+
+        unicode_boundaries (:obj:`bool`, defaults to :obj:`False`):
+            Defines whether :obj:`single_word` uses the Unicode word segmentation to find the
+            word boundaries, which handles the scripts that do not separate words with spaces.
+
     """
-    def __init__(self, content, single_word=False, lstrip=False, rstrip=False, normalized=True, special=False):
+    def __init__(
+        self,
+        content,
+        single_word=False,
+        lstrip=False,
+        rstrip=False,
+        normalized=True,
+        special=False,
+        pattern=False,
+        unicode_boundaries=False,
+    ):
         pass
 
     @property
@@ -58,6 +78,13 @@ class AddedToken:
         """
         pass
 
+    @property
+    def pattern(self):
+        """
+        Get the value of the :obj:`pattern` option
+        """
+        pass
+
     @property
     def rstrip(self):
         """
@@ -79,6 +106,13 @@ class AddedToken:
         """
         pass
 
+    @property
+    def unicode_boundaries(self):
+        """
+        Get the value of the :obj:`unicode_boundaries` option
+        """
+        pass
+
 class Encoding:
     """
     The :class:`~tokenizers.Encoding` represents the output of a :class:`~tokenizers.Tokenizer`.
@@ -101,6 +135,10 @@ class Encoding:
         """
         Get the token that contains the char at the given position in the input sequence.
 
+        The chars inserted by the normalizer share the offsets of the char they were inserted
+        next to, so when several tokens contain the given char, the first one is returned.
+        The chars removed by the normalizer are not contained by any token.
+
         Args:
             char_pos (:obj:`int`):
                 The position of a char in the input string
@@ -217,6 +255,28 @@ class Encoding:
         """
         pass
 
+    def redact(self, spans, placeholder, sequence_index=0):
+        """
+        Replace the tokens overlapping each of the given spans by the tokens of the placeholder
+
+        This removes some personal information, like names or emails, while keeping the
+        alignment with the original document. The tokens of each placeholder get the offsets of
+        all the tokens they replace, and form a single word, the following words of the
+        sequence being renumbered. The spans that overlap the same tokens get a single
+        placeholder. Everything else is identical, including the overflowing encodings, which
+        get their own placeholders.
+
+        Args:
+            spans (:obj:`List[Tuple[int, int]]`):
+                The spans of chars to redact in the input string
+            placeholder (:class:`~tokenizers.Encoding`):
+                The encoding of the placeholder, like :obj:`tokenizer.encode("[REDACTED]",
+                add_special_tokens=False)`
+            sequence_index (:obj:`int`, defaults to :obj:`0`):
+                The index of the sequence that contains the spans
+        """
+        pass
+
     @property
     def sequence_ids(self):
         """
@@ -252,6 +312,26 @@ class Encoding:
         """
         pass
 
+    def span_to_tokens(self, span, sequence_index=0):
+        """
+        Get the encoded tokens overlapping the given span of chars in the input sequence.
+
+        A token cut by the span is included entirely, just like the tokens made of the chars
+        inserted by the normalizer next to a char of the span.
+
+        Args:
+            span (:obj:`Tuple[int, int]`):
+                The span of chars in the input string: :obj:`(start, end)`
+            sequence_index (:obj:`int`, defaults to :obj:`0`):
+                The index of the sequence that contains the target span
+
+        Returns:
+            :obj:`Tuple[int, int]`: The range of tokens: :obj:`(first, last + 1)`, or :obj:`None`
+            if no token overlaps the span, which happens with an empty span, or when all its chars
+            have been removed by the normalizer
+        """
+        pass
+
     def token_to_chars(self, token_index):
         """
         Get the offsets of the token at the given index.
@@ -364,6 +444,20 @@ class Encoding:
         """
         pass
 
+    @property
+    def word_metadata(self):
+        """
+        The metadata of the word associated to each token
+
+        They are attached with :meth:`~tokenizers.Tokenizer.encode_with_word_metadata`, like the
+        timestamps of the words in an audio, and follow the tokens through the truncation and
+        the padding. The tokens without any word, or without any metadata, get :obj:`None`
+
+        Returns:
+            A :obj:`List` of :obj:`Optional[List[float]]`: The metadata of the word of each token
+        """
+        pass
+
     def word_to_chars(self, word_index, sequence_index=0):
         """
         Get the offsets of the word at the given index in one of the input sequences.
@@ -447,6 +541,36 @@ class NormalizedString:
         """
         pass
 
+    def filter_batch(self, func, chunk_size=None):
+        """
+        Filter the characters of the string, calling the given func once per chunk
+
+        Unlike `filter`, the func receives a whole chunk of the string, and returns a list with
+        a bool for each of its characters, telling whether to keep it.
+
+        Args:
+            func: Callable[[str], List[bool]]:
+                The function deciding which characters to keep
+
+            chunk_size: Optional[int]:
+                The maximum number of characters of each chunk. By default, the whole string
+                is given at once
+        """
+        pass
+
+    def filter_graphemes(self, func):
+        """
+        Filter each grapheme cluster of the string using the given func
+
+        Unlike `filter`, the func receives whole user-perceived characters, like a letter
+        along with its combining marks, so they are either kept or removed together.
+
+        Args:
+            func: Callable[[str], bool]:
+                The function deciding which grapheme clusters to keep
+        """
+        pass
+
     def for_each(self, func):
         """
         Calls the given function for each character of the string
@@ -474,6 +598,36 @@ class NormalizedString:
         """
         pass
 
+    def map_batch(self, func, chunk_size=None):
+        """
+        Replace the characters of the string, calling the given func once per chunk
+
+        Unlike `map`, the func receives a whole chunk of the string, and returns a str with the
+        replacement of each of its characters, so with the same number of characters.
+
+        Args:
+            func: Callable[[str], str]:
+                The function replacing the characters
+
+            chunk_size: Optional[int]:
+                The maximum number of characters of each chunk. By default, the whole string
+                is given at once
+        """
+        pass
+
+    def map_graphemes(self, func):
+        """
+        Calls the given function for each grapheme cluster of the string
+
+        Replaces each grapheme cluster using the returned value, which can be a str of any
+        length, aligned with the whole original cluster. Use an empty str to remove it.
+
+        Args:
+            func: Callable[[str], str]:
+                The function replacing the grapheme clusters
+        """
+        pass
+
     def nfc(self):
         """
         Runs the NFC normalization
@@ -560,12 +714,131 @@ class NormalizedString:
         """
         pass
 
+    def transform(self, offsets, new_text, initial_offset=0):
+        """
+        Replace the string with the given text, using the given alignment
+
+        This applies precomputed edits at once, keeping track of the alignments with the
+        original string.
+
+        Args:
+            offsets: List[int]:
+                The change of each character of `new_text`, compared to the current string:
+                `1` if it is a new character, `0` if it replaces the next character, and `-N`
+                if it replaces the next character and removes the `N` following ones
+
+            new_text: str:
+                The new content of the string
+
+            initial_offset: int:
+                The number of characters removed at the very beginning of the string
+        """
+        pass
+
     def uppercase(self):
         """
         Uppercase the string
         """
         pass
 
+class PaddingParams:
+    """
+    The parameters used to pad the encodings, as set with
+    :meth:`~tokenizers.Tokenizer.enable_padding`
+
+    Once built, these parameters can be given to a :class:`~tokenizers.Tokenizer` by assigning
+    its :attr:`~tokenizers.Tokenizer.padding` property.
+
+    Args:
+        direction (:obj:`str`, defaults to :obj:`right`):
+            The direction in which to pad. Can be either ``right`` or ``left``
+
+        pad_id (:obj:`int`, defaults to 0):
+            The id to be used when padding
+
+        pad_type_id (:obj:`int`, defaults to 0):
+            The type id to be used when padding
+
+        pad_token (:obj:`str`, defaults to :obj:`[PAD]`):
+            The pad token to be used when padding
+
+        length (:obj:`int`, `optional`):
+            If specified, the length at which to pad. If not specified we pad using the size of
+            the longest sequence in a batch.
+
+        pad_to_multiple_of (:obj:`int`, `optional`):
+            If specified, the padding length should always snap to the next multiple of the
+            given value.
+    """
+    def __init__(
+        self, direction="right", pad_id=0, pad_type_id=0, pad_token="[PAD]", length=None, pad_to_multiple_of=None
+    ):
+        pass
+
+    @property
+    def direction(self):
+        """
+        The direction in which to pad: ``left`` or ``right``
+        """
+        pass
+
+    def get(self, key, default=None):
+        """
+        Get the value of the given parameter, or ``default`` if it does not exist
+        """
+        pass
+
+    def keys(self):
+        """
+        The names of the parameters, which lets ``enable_padding(**params)`` work
+        """
+        pass
+
+    @property
+    def length(self):
+        """
+        The length at which to pad, or :obj:`None` to pad to the longest sequence in a batch
+        """
+        pass
+
+    @property
+    def pad_id(self):
+        """
+        The id used when padding
+        """
+        pass
+
+    @property
+    def pad_to_multiple_of(self):
+        """
+        The multiple the padding length snaps to, if any
+        """
+        pass
+
+    @property
+    def pad_token(self):
+        """
+        The pad token used when padding
+        """
+        pass
+
+    @property
+    def pad_type_id(self):
+        """
+        The type id used when padding
+        """
+        pass
+
+    def to_dict(self):
+        """
+        Convert these parameters to a dict, as accepted by
+        :meth:`~tokenizers.Tokenizer.enable_padding`
+
+        Returns:
+            :obj:`Dict`: The parameters
+        """
+        pass
+
 class PreTokenizedString:
     """
     PreTokenizedString
@@ -667,18 +940,202 @@ class PreTokenizedString:
 class Regex:
     """
     Instantiate a new Regex with the given pattern
+
+    The pattern is compiled once, and shared by all the components using this Regex, like
+    the :class:`~tokenizers.pre_tokenizers.Split` pre-tokenizer or the
+    :class:`~tokenizers.normalizers.Replace` normalizer.
     """
     def __init__(self, pattern):
         pass
 
+    @staticmethod
+    def escape(text):
+        """
+        Escape the given string, so that it gets matched literally once used in a pattern
+
+        Args:
+            text (:obj:`str`):
+                The string to escape
+
+        Returns:
+            :obj:`str`: The escaped string
+        """
+        pass
+
+    @property
+    def pattern(self):
+        """
+        The pattern of this Regex
+        """
+        pass
+
+class ShadowTokenizer:
+    """
+    Run a candidate tokenizer in the shadow of the primary one, to check an upgrade on the
+    real traffic before switching to it
+
+    Everything gets encoded by the primary tokenizer, whose results are returned as usual.
+    The same inputs are then encoded by the candidate tokenizer on a background thread, and
+    the inputs getting other ids are recorded in a report. The candidate never slows the
+    primary down: when it lags behind by more than ``queue_size`` calls, the new inputs are
+    skipped instead of waiting for it. Both tokenizers are used as they are when creating the
+    :class:`~tokenizers.ShadowTokenizer`, their later changes are ignored.
+
+    Args:
+        primary (:class:`~tokenizers.Tokenizer`):
+            The tokenizer whose results are returned
+
+        candidate (:class:`~tokenizers.Tokenizer`):
+            The tokenizer compared to the primary one
+
+        queue_size (:obj:`int`, defaults to :obj:`1024`):
+            The maximum number of calls waiting for the candidate
+
+        max_examples (:obj:`int`, defaults to :obj:`100`):
+            The number of divergences kept in the report
+    """
+    def __init__(self, primary, candidate, queue_size=1024, max_examples=100):
+        pass
+
+    def encode(self, sequence, pair=None, is_pretokenized=False, add_special_tokens=True):
+        """
+        Encode the given sequence and pair with the primary tokenizer, like
+        :meth:`~tokenizers.Tokenizer.encode`, and queue them for the candidate
+
+        Args:
+            sequence (:obj:`~tokenizers.InputSequence`):
+                The main input sequence, as accepted by :meth:`~tokenizers.Tokenizer.encode`
+
+            pair (:obj:`~tokenizers.InputSequence`, `optional`):
+                An optional input sequence. The expected format is the same that for ``sequence``.
+
+            is_pretokenized (:obj:`bool`, defaults to :obj:`False`):
+                Whether the input is already pre-tokenized
+
+            add_special_tokens (:obj:`bool`, defaults to :obj:`True`):
+                Whether to add the special tokens
+
+        Returns:
+            :class:`~tokenizers.Encoding`: The encoding of the primary tokenizer
+        """
+        pass
+
+    def encode_batch(self, input, is_pretokenized=False, add_special_tokens=True):
+        """
+        Encode the given batch of inputs with the primary tokenizer, like
+        :meth:`~tokenizers.Tokenizer.encode_batch`, and queue them for the candidate
+
+        Args:
+            input (A :obj:`List`/:obj:`Tuple` of :obj:`~tokenizers.EncodeInput`):
+                The batch of inputs, as accepted by :meth:`~tokenizers.Tokenizer.encode_batch`
+
+            is_pretokenized (:obj:`bool`, defaults to :obj:`False`):
+                Whether the input is already pre-tokenized
+
+            add_special_tokens (:obj:`bool`, defaults to :obj:`True`):
+                Whether to add the special tokens
+
+        Returns:
+            A :obj:`List` of :class:`~tokenizers.Encoding`: The encodings of the primary
+            tokenizer
+        """
+        pass
+
+    def flush(self):
+        """
+        Wait for the candidate to compare all the inputs queued so far
+        """
+        pass
+
+    def report(self, reset=False):
+        """
+        The report of the divergences found so far
+
+        The inputs still waiting for the candidate are not part of it, unless
+        :meth:`~tokenizers.ShadowTokenizer.flush` was called first.
+
+        Args:
+            reset (:obj:`bool`, defaults to :obj:`False`):
+                Whether to start a new report
+
+        Returns:
+            :obj:`Dict`: The number of inputs ``compared``, of the ones that ``diverged``,
+            and of the ones ``skipped`` because the candidate was lagging behind, the
+            ``divergence_rate``, and the first ``examples`` of divergences, each with its
+            ``input``, the ``primary`` :class:`~tokenizers.Encoding`, and the ``candidate``
+            one, or the error message of the candidate tokenizer
+        """
+        pass
+
 class Token:
     pass
 
+class TokenPattern:
+    """
+    A pattern of token ids, to find in the :class:`~tokenizers.Encoding` without decoding it
+
+    Each element of the pattern matches some tokens:
+
+    - an :obj:`int` matches the token with this id
+    - a :obj:`List[int]` matches any token with one of these ids, like the ids of some
+      added tokens, from :meth:`~tokenizers.Tokenizer.get_added_tokens_decoder`
+    - :obj:`"any"` matches any token, and :obj:`"special"` any special token
+    - a :obj:`Tuple[int, int]` matches between ``min`` and ``max`` tokens, as few as
+      possible
+
+    Each token of a match belongs to the same input sequence, or was added by the
+    post-processor, and the padding tokens never match.
+
+    Args:
+        pattern (:obj:`List[Union[int, List[int], str, Tuple[int, int]]]`):
+            The elements to match one after the other
+    """
+    def __init__(self, pattern):
+        pass
+
+    def find_all(self, encoding):
+        """
+        Find all the occurrences of the pattern, from left to right and without overlaps
+
+        Args:
+            encoding (:class:`~tokenizers.Encoding`):
+                The encoding to scan. Its overflowing encodings are ignored
+
+        Returns:
+            :obj:`List[Dict]`: For each match, the range of its ``tokens``, the index of their
+            input ``sequence``, and the ``offsets`` of the chars they cover in this sequence.
+            The last two are :obj:`None` when all the tokens were added by the post-processor
+        """
+        pass
+
+    def is_match(self, encoding):
+        """
+        Whether the pattern occurs in the given :class:`~tokenizers.Encoding`
+
+        Args:
+            encoding (:class:`~tokenizers.Encoding`):
+                The encoding to scan. Its overflowing encodings are ignored
+
+        Returns:
+            :obj:`bool`
+        """
+        pass
+
 class Tokenizer:
     """
     A :obj:`Tokenizer` works as a pipeline. It processes some raw text as input
     and outputs an :class:`~tokenizers.Encoding`.
 
+    A :obj:`Tokenizer` can be shared by several threads. The methods encoding or decoding
+    batches release the GIL, and work on a snapshot of the tokenizer taken when they start:
+    the mutations made meanwhile by other threads, like :meth:`add_tokens`,
+    :meth:`enable_padding` or setting :attr:`normalizer`, never fail nor wait for them, and
+    an encode in flight sees either all of a mutation, or none of it. Only the training is
+    exclusive: using the tokenizer from another thread while it trains raises an error. The
+    components themselves are shared with the snapshots, so updating one of their attributes
+    in place, like ``tokenizer.normalizer.lowercase = False``, applies right away to the
+    encodes in flight. Replace the component instead to update it atomically.
+
     Args:
         model (:class:`~tokenizers.models.Model`):
             The core algorithm that this :obj:`Tokenizer` should be using.
@@ -687,7 +1144,7 @@ class Tokenizer:
     def __init__(self, model):
         pass
 
-    def add_special_tokens(self, tokens):
+    def add_special_tokens(self, tokens, id_assignment="insertion", hash_range=None):
         """
         Add the given special tokens to the Tokenizer.
 
@@ -703,12 +1160,19 @@ class Tokenizer:
                 be a string or an instance of :class:`~tokenizers.AddedToken` for more
                 customization.
 
+            id_assignment (:obj:`str`, defaults to :obj:`insertion`):
+                How the new tokens get their ids: ``insertion``, ``alphabetical`` or
+                ``hash_stable``. See :meth:`~tokenizers.Tokenizer.add_tokens`
+
+            hash_range (:obj:`int`, `optional`):
+                The number of ids used by ``hash_stable``
+
         Returns:
             :obj:`int`: The number of tokens that were created in the vocabulary
         """
         pass
 
-    def add_tokens(self, tokens):
+    def add_tokens(self, tokens, id_assignment="insertion", hash_range=None):
         """
         Add the given tokens to the vocabulary
 
@@ -720,32 +1184,203 @@ class Tokenizer:
                 The list of tokens we want to add to the vocabulary. Each token can be either a
                 string or an instance of :class:`~tokenizers.AddedToken` for more customization.
 
+            id_assignment (:obj:`str`, defaults to :obj:`insertion`):
+                How the new tokens get their ids. With ``insertion``, they follow the vocabulary
+                in the given order. With ``alphabetical``, they follow it in the alphabetical
+                order, whatever the given order. With ``hash_stable``, each id is derived from a
+                stable hash of the token, among the ``hash_range`` ids following the model's
+                vocabulary, so that tokenizers built separately agree on the ids of their tokens
+                even when added in different batches. Colliding tokens get the next free id.
+
+            hash_range (:obj:`int`, `optional`):
+                The number of ids used by ``hash_stable``, which should be much larger than the
+                number of added tokens
+
         Returns:
             :obj:`int`: The number of tokens that were created in the vocabulary
         """
         pass
 
-    def decode(self, ids, skip_special_tokens=True):
+    def audit_sampling(self, sequences, strength, samples=10):
         """
-        Decode the given list of ids back to a string
+        Compare the deterministic segmentation of each sequence with sampled ones
 
-        This is used to decode anything coming back from a Language Model
+        This helps picking the dropout (BPE) or alpha (Unigram) used for subword
+        regularization: the sampled segmentations should differ enough to regularize the
+        training, without drifting too far from the deterministic one seen at inference.
+        No special tokens are added, and the added tokens are never sampled.
 
         Args:
-            ids (A :obj:`List/Tuple` of :obj:`int`):
-                The list of ids that we want to decode
+            sequences (:obj:`List[str]`):
+                The corpus to audit
 
-            skip_special_tokens (:obj:`bool`, defaults to :obj:`True`):
-                Whether the special tokens should be removed from the decoded string
+            strength (:obj:`float`):
+                The dropout probability for BPE, or the smoothing parameter ``alpha`` for
+                Unigram
+
+            samples (:obj:`int`, defaults to :obj:`10`):
+                The number of segmentations to sample for each sequence
 
         Returns:
-            :obj:`str`: The decoded string
+            :obj:`dict`: The statistics of the audit: ``sequences``, ``samples``, ``tokens``
+            (of the deterministic segmentation), ``divergence_rate`` (the fraction of samples
+            differing from the deterministic segmentation), ``boundary_agreement`` (the mean
+            Jaccard similarity of the token boundaries), ``length_ratio`` (sampled tokens per
+            deterministic token) and ``unique_segmentations`` (per sequence)
         """
         pass
 
-    def decode_batch(self, sequences, skip_special_tokens=True):
+    @staticmethod
+    def chain(first, second):
         """
-        Decode a batch of ids back to their corresponding string
+        Chain two tokenizers: the tokens found by the model of the first one become the
+        pre-tokens fed to the model of the second one, like the morphemes of a morphological
+        tokenizer fed to a :class:`~tokenizers.models.BPE`.
+
+        The text goes through the normalizer and the pre-tokenizer of :obj:`first`, then
+        through its model, with the normalizer of :obj:`second` applied to each of the tokens,
+        and finally through the pre-tokenizer and the model of :obj:`second`. The offsets refer
+        to the original text, and each token of :obj:`first` is a word of its own.
+
+        Everything else comes from :obj:`second`, like the added tokens, the post-processor
+        and the decoder. Neither tokenizer can use custom components.
+
+        Args:
+            first (:class:`~tokenizers.Tokenizer`):
+                The tokenizer splitting the text into the pre-tokens of the second one
+
+            second (:class:`~tokenizers.Tokenizer`):
+                The tokenizer producing the final tokens
+
+        Returns:
+            :class:`~tokenizers.Tokenizer`: The new tokenizer
+        """
+        pass
+
+    def check_embeddings(self, size, id_dtype=None, reserved_ids=None, padding_idx=None):
+        """
+        Check that the embedding table of a model matches this :class:`~tokenizers.Tokenizer`,
+        before training them together
+
+        This verifies that the ids of the model and of the added tokens, which come after the
+        base vocabulary, all have a row in the table and fit in the integer type storing them,
+        that no token uses the reserved rows, and that the padding id has a row, which is the
+        padding row of the table if it has one. A table with more rows than ids is fine.
+
+        Args:
+            size (:obj:`int`):
+                The number of rows of the embedding table
+
+            id_dtype (:obj:`str`, `optional`):
+                The integer type storing the ids, like :obj:`"uint16"` for compact datasets
+
+            reserved_ids (:obj:`List[int]`, `optional`):
+                The rows reserved for something else, that no token may use
+
+            padding_idx (:obj:`int`, `optional`):
+                The padding row of the table, like the ``padding_idx`` of a PyTorch ``Embedding``
+
+        Returns:
+            :obj:`List[str]`: The description of each issue found, with how to fix it
+        """
+        pass
+
+    def configure_limits(self, max_pre_tokens=None, max_splits_per_pre_token=None, max_overflowing=None):
+        """
+        Set hard caps on the intermediate products of the encoding, bounding the work and the
+        memory of each input. Going over one of them raises a
+        :class:`~tokenizers.LimitExceededError` instead of silently keeping a part of the
+        result. Calling this method again replaces all the limits.
+
+        Args:
+            max_pre_tokens (:obj:`int`, `optional`):
+                The maximum number of pre-tokens of a sequence, over all its parts when
+                pre-tokenized. The pre-tokenizer stops splitting as soon as it goes over
+
+            max_splits_per_pre_token (:obj:`int`, `optional`):
+                The maximum number of tokens the model splits a single pre-token into. These
+                are only counted once the model has built them all, so this bounds the building
+                of the encoding, but not the work of the model on that pre-token
+
+            max_overflowing (:obj:`int`, `optional`):
+                The maximum number of overflowing parts of an encoding. Contrarily to the
+                ``max_overflowing`` of the truncation, which keeps the first parts, this is an
+                error.
+        """
+        pass
+
+    def configure_parallelism(self, enabled=True, num_threads=None):
+        """
+        Configure where the batch methods (:meth:`~tokenizers.Tokenizer.encode_batch`,
+        :meth:`~tokenizers.Tokenizer.decode_batch`, ...) run
+
+        The ``TOKENIZERS_PARALLELISM`` environment variable keeps the final word: when it
+        disables parallelism, everything runs on the calling thread.
+
+        Args:
+            enabled (:obj:`bool`, defaults to :obj:`True`):
+                Whether to use multiple threads at all. When :obj:`False`, everything runs on
+                the calling thread, which avoids contention with multi-worker data loaders.
+
+            num_threads (:obj:`int`, `optional`):
+                The number of threads of a pool dedicated to this tokenizer. When not
+                specified, the thread pool shared with the rest of the process is used.
+        """
+        pass
+
+    def convert_offsets_batch(self, sequences, offsets, referential="normalized"):
+        """
+        Convert the offsets of spans between the original sequences and the normalized ones
+
+        The sequences are normalized by the normalizer of this tokenizer, like with
+        :meth:`~tokenizers.normalizers.Normalizer.normalize_str`, and the offsets of their spans
+        converted from the given referential to the other one, for the whole batch at once. This
+        lets span-annotation pipelines match the spans found on the normalized text with the
+        original one, without a loop over each span. The offsets count the characters.
+
+        Args:
+            sequences (:obj:`List[str]`):
+                The original sequences
+
+            offsets (:obj:`List[List[Tuple[int, int]]]`):
+                The offsets of the spans of each sequence
+
+            referential (:obj:`str`, defaults to :obj:`normalized`):
+                The referential of the given offsets, either ``"normalized"`` to convert them to
+                the original sequences, or ``"original"`` to convert them to the normalized ones
+
+        Returns:
+            :obj:`List[List[Optional[Tuple[int, int]]]]`: The converted offsets, :obj:`None` for
+            the spans out of range
+        """
+        pass
+
+    def decode(self, ids, skip_special_tokens=True, preserve_whitespace=False):
+        """
+        Decode the given list of ids back to a string
+
+        This is used to decode anything coming back from a Language Model
+
+        Args:
+            ids (A :obj:`List/Tuple` of :obj:`int`):
+                The list of ids that we want to decode
+
+            skip_special_tokens (:obj:`bool`, defaults to :obj:`True`):
+                Whether the special tokens should be removed from the decoded string
+
+            preserve_whitespace (:obj:`bool`, defaults to :obj:`False`):
+                Whether to reconstruct the whitespace exactly, as needed for code. The decoders then skip
+                the heuristics altering the spaces, only removing the prefix space added by the
+                pre-tokenizer
+
+        Returns:
+            :obj:`str`: The decoded string
+        """
+        pass
+
+    def decode_batch(self, sequences, skip_special_tokens=True):
+        """
+        Decode a batch of ids back to their corresponding string
 
         Args:
             sequences (:obj:`List` of :obj:`List[int]`):
@@ -759,6 +1394,29 @@ class Tokenizer:
         """
         pass
 
+    def decode_bytes(self, ids, skip_special_tokens=True):
+        """
+        Decode the given list of ids back to raw bytes
+
+        Contrarily to :meth:`~tokenizers.Tokenizer.decode`, the byte tokens that don't form
+        valid UTF-8 are returned as is, whatever the policy of the
+        :class:`~tokenizers.decoders.ByteFallback` decoder, or the replacement characters of
+        the :class:`~tokenizers.decoders.ByteLevel` one. This lets the caller handle partial
+        byte sequences, when the ids stop in the middle of a character, and the models of
+        binary data round-trip their content.
+
+        Args:
+            ids (A :obj:`List/Tuple` of :obj:`int`):
+                The list of ids that we want to decode
+
+            skip_special_tokens (:obj:`bool`, defaults to :obj:`True`):
+                Whether the special tokens should be removed from the decoded bytes
+
+        Returns:
+            :obj:`bytes`: The decoded bytes
+        """
+        pass
+
     @property
     def decoder(self):
         """
@@ -796,7 +1454,16 @@ class Tokenizer:
         """
         pass
 
-    def enable_truncation(self, max_length, stride=0, strategy="longest_first", direction="right"):
+    def enable_truncation(
+        self,
+        max_length,
+        stride=0,
+        strategy="longest_first",
+        direction="right",
+        first_budget=None,
+        special_tokens="reserve",
+        max_overflowing=None,
+    ):
         """
         Enable truncation
 
@@ -809,11 +1476,28 @@ class Tokenizer:
                 sequence
 
             strategy (:obj:`str`, `optional`, defaults to :obj:`longest_first`):
-                The strategy used to truncation. Can be one of ``longest_first``, ``only_first`` or
-                ``only_second``.
+                The strategy used to truncation. Can be one of ``longest_first``, ``only_first``,
+                ``only_second`` or ``budget``.
 
             direction (:obj:`str`, defaults to :obj:`right`):
                 Truncate direction
+
+            first_budget (:obj:`Union[int, float]`, `optional`):
+                Required by the ``budget`` strategy: the maximum number of tokens of the first
+                sequence of a pair, either as a number of tokens or as a ratio of the max length.
+                The second sequence gets the rest, giving back to the first one the room it
+                doesn't use
+
+            special_tokens (:obj:`str`, defaults to :obj:`reserve`):
+                How the special tokens added by the post-processor count against the max length.
+                With ``reserve``, their room is kept in every encoding, including pairs and
+                overflowing windows, so they always fit. With ``exempt``, they are not counted and
+                get added on top of the max length
+
+            max_overflowing (:obj:`int`, `optional`):
+                The maximum number of overflowing windows kept for each sequence. The windows
+                beyond are never built, which saves time on long documents. All of them are kept
+                by default
         """
         pass
 
@@ -856,7 +1540,7 @@ class Tokenizer:
         """
         pass
 
-    def encode_batch(self, input, is_pretokenized=False, add_special_tokens=True):
+    def encode_batch(self, input, is_pretokenized=False, add_special_tokens=True, max_lengths=None):
         """
         Encode the given batch of inputs. This method accept both raw text sequences
         as well as already pre-tokenized sequences. The reason we use `PySequence` is
@@ -888,6 +1572,11 @@ class Tokenizer:
             add_special_tokens (:obj:`bool`, defaults to :obj:`True`):
                 Whether to add the special tokens
 
+            max_lengths (A :obj:`List` of :obj:`int`, `optional`):
+                The length at which to truncate each input, including the special tokens. It
+                replaces the ``max_length`` of the truncation parameters, and applies even when
+                truncation is disabled. :obj:`None` items use the truncation of the tokenizer.
+
         Returns:
             A :obj:`List` of :class:`~tokenizers.Encoding`: The encoded batch
 
@@ -930,6 +1619,202 @@ class Tokenizer:
         """
         pass
 
+    def encode_batch_windows(self, input, is_pretokenized=False, add_special_tokens=True):
+        """
+        Encode the given batch of inputs, returning all the windows of all the inputs as
+        a single list. This is mostly useful for long documents, with truncation enabled:
+        each input is then chunked into windows of at most ``max_length`` tokens, overlapping
+        by ``stride`` tokens.
+
+        Along with the windows, this returns the ``overflow_to_sample_mapping``, which gives,
+        for each window, the index of the input it comes from. The offsets of each window
+        always refer to the original input.
+
+        Example:
+            Here is how the windows can be mapped back to their documents::
+
+                tokenizer.enable_truncation(384, stride=128)
+                windows, mapping = tokenizer.encode_batch_windows(documents)
+                for window, index in zip(windows, mapping):
+                    start, end = window.offsets[0][0], window.offsets[-1][1]
+                    print(documents[index][start:end])
+
+        Args:
+            input (A :obj:`List`/:obj:`Tuple` of :obj:`~tokenizers.EncodeInput`):
+                A list of single sequences or pair sequences to encode. Each sequence
+                can be either raw text or pre-tokenized, according to the ``is_pretokenized``
+                argument:
+
+                - If ``is_pretokenized=False``: :class:`~tokenizers.TextEncodeInput`
+                - If ``is_pretokenized=True``: :class:`~tokenizers.PreTokenizedEncodeInput`
+
+            is_pretokenized (:obj:`bool`, defaults to :obj:`False`):
+                Whether the input is already pre-tokenized
+
+            add_special_tokens (:obj:`bool`, defaults to :obj:`True`):
+                Whether to add the special tokens
+
+        Returns:
+            A :obj:`Tuple` with the :obj:`List` of :class:`~tokenizers.Encoding` windows, and
+            the ``overflow_to_sample_mapping`` (:obj:`List[int]`)
+
+        """
+        pass
+
+    def encode_batch_with_prefix(self, prefix, suffixes, add_special_tokens=True):
+        """
+        Encode the given prefix followed by each of the suffixes, like :meth:`encode_batch`
+        would encode the concatenated sequences, but tokenizing the prefix only once.
+
+        Only the last two words of the prefix are tokenized again with each suffix, as the
+        suffix may merge with them. When the suffix reaches further back into the prefix, the
+        whole sequence gets encoded instead, so the encodings are always the same.
+
+        Example:
+            A few-shot prompt only gets tokenized once for the whole evaluation set::
+
+                encodings = tokenizer.encode_batch_with_prefix(prompt, questions)
+
+        Args:
+            prefix (:obj:`str`):
+                The sequence shared by all the inputs
+
+            suffixes (:obj:`List[str]`):
+                The rest of each input, appended to the prefix as is
+
+            add_special_tokens (:obj:`bool`, defaults to :obj:`True`):
+                Whether to add the special tokens
+
+        Returns:
+            A :obj:`List` of :class:`~tokenizers.Encoding`: The encoded batch
+
+        """
+        pass
+
+    def encode_chat(self, turns, is_pretokenized=False, add_special_tokens=True):
+        """
+        Encode a conversation made of any number of ``(role, content)`` turns, using the
+        :class:`~tokenizers.processors.ChatTemplate` set as post-processor.
+
+        Example:
+            Here is an example of the accepted input::
+
+                encode_chat([
+                    ("system", "You are a helpful assistant"),
+                    ("user", "Hi!"),
+                    ("assistant", "Hello, how can I help you?"),
+                ])
+
+        Args:
+            turns (:obj:`List[Tuple[str, InputSequence]]`):
+                The turns of the conversation, each one with the name of its role. The offsets
+                of each turn are relative to its own content.
+
+            is_pretokenized (:obj:`bool`, defaults to :obj:`False`):
+                Whether the contents are already pre-tokenized
+
+            add_special_tokens (:obj:`bool`, defaults to :obj:`True`):
+                Whether to add the special tokens
+
+        Returns:
+            A :obj:`Tuple` with the :class:`~tokenizers.Encoding` of the whole conversation,
+            the loss mask (:obj:`List[int]`), and the index of the role owning each token
+            (:obj:`List[Optional[int]]`)
+        """
+        pass
+
+    def encode_edit(self, text, encoding, edit, replacement):
+        """
+        Encode the text after an edit, reusing its previous encoding to only tokenize again
+        the words around the edit
+
+        This suits the editors, re-tokenizing a document for each keystroke. The words touched
+        by the edit are tokenized again with the words around them, which must keep the same
+        tokens to prove that the edit doesn't reach further, otherwise more words get tokenized
+        again. The result is always the same as encoding the edited text from scratch.
+
+        Args:
+            text (:obj:`str`):
+                The text before the edit
+
+            encoding (:class:`~tokenizers.Encoding`):
+                The encoding of :obj:`text`, made by :meth:`encode` without the special tokens
+
+            edit (:obj:`Tuple[int, int]`):
+                The range of chars of :obj:`text` that gets replaced
+
+            replacement (:obj:`str`):
+                The new content of this range
+
+        Returns:
+            :obj:`Dict`: The ``encoding`` of the edited text, along with the range of the tokens
+            ``replaced`` in the previous encoding, and the range of the ones ``inserted`` in
+            the new one
+        """
+        pass
+
+    def encode_nbest(self, sequence, n, pair=None, is_pretokenized=False, add_special_tokens=True):
+        """
+        Encode the given sequence and pair like :meth:`~tokenizers.Tokenizer.encode`,
+        returning the ``n`` best segmentations with their scores
+
+        The segmentations of the words are combined, the score of a segmentation being the
+        sum of the log probabilities of its tokens. Only the :class:`~tokenizers.models.Unigram`
+        model has several segmentations per word, while the
+        :class:`~tokenizers.models.WordLevel` one only has its single segmentation.
+
+        Args:
+            sequence (:obj:`~tokenizers.InputSequence`):
+                The main input sequence, as accepted by :meth:`~tokenizers.Tokenizer.encode`
+
+            n (:obj:`int`):
+                The number of segmentations to return. Fewer are returned when there are not
+                as many
+
+            pair (:obj:`~tokenizers.InputSequence`, `optional`):
+                An optional input sequence. The expected format is the same that for ``sequence``.
+
+            is_pretokenized (:obj:`bool`, defaults to :obj:`False`):
+                Whether the input is already pre-tokenized
+
+            add_special_tokens (:obj:`bool`, defaults to :obj:`True`):
+                Whether to add the special tokens
+
+        Returns:
+            A :obj:`List` of :obj:`Tuple` with each :class:`~tokenizers.Encoding` and its
+            score, best first
+        """
+        pass
+
+    def encode_sample(self, sequence, strength, pair=None, is_pretokenized=False, add_special_tokens=True):
+        """
+        Encode the given sequence and pair like :meth:`~tokenizers.Tokenizer.encode`,
+        sampling the segmentation of each word, as used for subword regularization
+
+        The added tokens are never sampled.
+
+        Args:
+            sequence (:obj:`~tokenizers.InputSequence`):
+                The main input sequence, as accepted by :meth:`~tokenizers.Tokenizer.encode`
+
+            strength (:obj:`float`):
+                The dropout probability for BPE, or the smoothing parameter ``alpha`` for
+                Unigram
+
+            pair (:obj:`~tokenizers.InputSequence`, `optional`):
+                An optional input sequence. The expected format is the same that for ``sequence``.
+
+            is_pretokenized (:obj:`bool`, defaults to :obj:`False`):
+                Whether the input is already pre-tokenized
+
+            add_special_tokens (:obj:`bool`, defaults to :obj:`True`):
+                Whether to add the special tokens
+
+        Returns:
+            :class:`~tokenizers.Encoding`: The encoded result
+        """
+        pass
+
     @property
     def encode_special_tokens(self):
         """
@@ -943,6 +1828,92 @@ class Tokenizer:
         """
         pass
 
+    def encode_with_prefix_constraint(self, sequence, add_special_tokens=True):
+        """
+        Encode the given text for token healing
+
+        When the text stops in the middle of what would be a single token, its last token is
+        removed from the returned :class:`~tokenizers.Encoding`, so that the generation can
+        start by re-generating it, constrained on the returned list of ids.
+
+        Nothing gets removed if the last token does not reach the end of the text.
+
+        Args:
+            sequence (:obj:`str`):
+                The text to encode
+
+            add_special_tokens (:obj:`bool`, defaults to :obj:`True`):
+                Whether to add the special tokens
+
+        Returns:
+            A :obj:`Tuple` with the :class:`~tokenizers.Encoding`, the removed
+            :class:`~tokenizers.Token` (:obj:`None` if nothing was removed), and the sorted ids
+            of all the tokens starting with the removed one (:obj:`List[int]`)
+        """
+        pass
+
+    def encode_with_word_metadata(
+        self, sequence, word_metadata, pair=None, pair_word_metadata=None, add_special_tokens=True
+    ):
+        """
+        Encode the given pre-tokenized sequence and pair, attaching to the tokens of each word
+        its metadata, like the timestamps of the words in an audio for forced alignment
+
+        The metadata follow the tokens through the truncation, the padding and the overflowing
+        encodings. They are available with :attr:`~tokenizers.Encoding.word_metadata`.
+
+        Example:
+            Here is an example with the start and end time of each word::
+
+                encode_with_word_metadata(["Hello", "there"], [[0.0, 0.4], [0.5, 1.2]])
+
+        Args:
+            sequence (:obj:`~tokenizers.PreTokenizedInputSequence`):
+                The main pre-tokenized input sequence
+
+            word_metadata (:obj:`List[List[float]]`):
+                The metadata of each word of the sequence
+
+            pair (:obj:`~tokenizers.PreTokenizedInputSequence`, `optional`):
+                An optional pre-tokenized input sequence
+
+            pair_word_metadata (:obj:`List[List[float]]`, `optional`):
+                The metadata of each word of the pair, required with a pair
+
+            add_special_tokens (:obj:`bool`, defaults to :obj:`True`):
+                Whether to add the special tokens
+
+        Returns:
+            :class:`~tokenizers.Encoding`: The encoded result
+        """
+        pass
+
+    def estimate_training(self, sample, corpus_size, trainer=None):
+        """
+        Estimate the resources needed to train the Tokenizer on a whole corpus, from a sample of
+        this corpus, before committing to the full run
+
+        A copy of the model gets trained on the sample with a copy of the trainer, and the
+        number of unique words, the peak memory and the duration are extrapolated to the size
+        of the corpus. These are rough estimates, meant to size the machines of the training.
+
+        Args:
+            sample (:obj:`List[str]`):
+                A sample of the corpus, ideally made of random sequences
+
+            corpus_size (:obj:`int`):
+                The size of the whole corpus, in bytes
+
+            trainer (:obj:`~tokenizers.trainers.Trainer`, `optional`):
+                The trainer that will be used to train the model
+
+        Returns:
+            :obj:`dict`: The estimate: ``sample_bytes``, ``sample_unique_words``,
+            ``unique_words`` (in the corpus), ``peak_memory`` (in bytes) and ``training_time``
+            (in seconds)
+        """
+        pass
+
     @staticmethod
     def from_buffer(buffer):
         """
@@ -957,6 +1928,42 @@ class Tokenizer:
         """
         pass
 
+    @staticmethod
+    def from_components(model, normalizer=None, pre_tokenizer=None, post_processor=None, decoder=None):
+        """
+        Instantiate a new :class:`~tokenizers.Tokenizer` from its components, checking that
+        they work together.
+
+        Contrarily to setting each component as an attribute, misconfigurations are reported
+        right away: a :class:`~tokenizers.MissingUnkTokenError` is raised when the unknown
+        token is missing from the vocabulary of the model, and an
+        :class:`~tokenizers.IncompatibleDecoderError` when the decoder can't revert what the
+        model and the pre-tokenizer produce (a :class:`~tokenizers.pre_tokenizers.ByteLevel`
+        pre-tokenizer without its decoder, a different WordPiece prefix, a byte fallback model
+        without :class:`~tokenizers.decoders.ByteFallback`...). Both are
+        :class:`~tokenizers.PipelineError`, a :obj:`ValueError`. Custom components are trusted.
+
+        Args:
+            model (:class:`~tokenizers.models.Model`):
+                The model
+
+            normalizer (:class:`~tokenizers.normalizers.Normalizer`, `optional`):
+                The normalizer
+
+            pre_tokenizer (:class:`~tokenizers.pre_tokenizers.PreTokenizer`, `optional`):
+                The pre-tokenizer
+
+            post_processor (:class:`~tokenizers.processors.PostProcessor`, `optional`):
+                The post-processor
+
+            decoder (:class:`~tokenizers.decoders.Decoder`, `optional`):
+                The decoder
+
+        Returns:
+            :class:`~tokenizers.Tokenizer`: The new tokenizer
+        """
+        pass
+
     @staticmethod
     def from_file(path):
         """
@@ -972,6 +1979,28 @@ class Tokenizer:
         """
         pass
 
+    @staticmethod
+    def from_file_mmap(path):
+        """
+        Instantiate a new :class:`~tokenizers.Tokenizer` from the binary file at the given
+        path, saved with :meth:`~tokenizers.Tokenizer.save_mmap`.
+
+        The file is memory mapped, and the vocab of the model, with the merges of a
+        :class:`~tokenizers.models.BPE`, is read in place instead of being loaded, sharing its
+        memory with every process using the same file. The file must not be modified, nor
+        truncated, while the tokenizer or any of its copies is in use: this would crash the
+        process, or give it corrupted tokens.
+
+        Args:
+            path (:obj:`str`):
+                A path to a local binary file representing a previously serialized
+                :class:`~tokenizers.Tokenizer`
+
+        Returns:
+            :class:`~tokenizers.Tokenizer`: The new tokenizer
+        """
+        pass
+
     @staticmethod
     def from_pretrained(identifier, revision="main", token=None):
         """
@@ -993,6 +2022,24 @@ class Tokenizer:
         """
         pass
 
+    @staticmethod
+    def from_subword_nmt(codes):
+        """
+        Instantiate a new :class:`~tokenizers.Tokenizer` from a subword-nmt :obj:`codes` file.
+
+        The model is the :class:`~tokenizers.models.BPE` of
+        :meth:`~tokenizers.models.BPE.from_subword_nmt`. Like subword-nmt, the tokenizer
+        splits the words on whitespace, and its decoder removes the :obj:`</w>` suffix.
+
+        Args:
+            codes (:obj:`str`):
+                The path to a :obj:`codes` file, as written by :obj:`learn_bpe.py`
+
+        Returns:
+            :class:`~tokenizers.Tokenizer`: The new tokenizer
+        """
+        pass
+
     @staticmethod
     def from_str(json):
         """
@@ -1043,6 +2090,23 @@ class Tokenizer:
         """
         pass
 
+    def gpt2_files(self):
+        """
+        Get the :obj:`vocab.json` and :obj:`merges.txt` files used by GPT-2, still required
+        by some runtimes, for a tokenizer using a :class:`~tokenizers.models.BPE` without
+        :obj:`continuing_subword_prefix` nor :obj:`end_of_word_suffix`.
+
+        The tokens of a tokenizer using :class:`~tokenizers.pre_tokenizers.ByteLevel` are
+        written as they are. The other ones are converted to the characters of
+        :class:`~tokenizers.pre_tokenizers.ByteLevel`, with new merges building the
+        multi-bytes characters, and new ids for the missing bytes. The tokens of the
+        :obj:`byte_fallback`, like :obj:`<0x41>`, become the character of their byte.
+
+        Returns:
+            :obj:`Dict[str, bytes]`: The content of each file, by its name
+        """
+        pass
+
     def id_to_token(self, id):
         """
         Convert the given id to its corresponding token if it exists
@@ -1056,6 +2120,51 @@ class Tokenizer:
         """
         pass
 
+    def ids_to_add(self, tokens, id_assignment="insertion", hash_range=None):
+        """
+        Get the ids the given tokens would have once added, without adding them
+
+        Args:
+            tokens (A :obj:`List` of :class:`~tokenizers.AddedToken` or :obj:`str`):
+                The tokens that would be added
+
+            id_assignment (:obj:`str`, defaults to :obj:`insertion`):
+                How the new tokens would get their ids: ``insertion``, ``alphabetical`` or
+                ``hash_stable``. See :meth:`~tokenizers.Tokenizer.add_tokens`
+
+            hash_range (:obj:`int`, `optional`):
+                The number of ids used by ``hash_stable``
+
+        Returns:
+            A :obj:`List` of :obj:`Optional[int]`: The id of each token, which is its current
+            id if it is already part of the vocabulary, or :obj:`None` if it would be ignored
+        """
+        pass
+
+    def ids_to_tokens(self, ids):
+        """
+        Convert all the given ids to their corresponding tokens
+
+        Args:
+            ids (:obj:`List[int]`):
+                The ids to convert
+
+        Returns:
+            :obj:`List[Optional[str]]`: The tokens, :obj:`None` for each id out of vocabulary
+        """
+        pass
+
+    @property
+    def limits(self):
+        """
+        The limits set with :meth:`~tokenizers.Tokenizer.configure_limits`
+
+        Returns:
+            :obj:`Dict[str, Optional[int]]`: The ``max_pre_tokens``,
+            ``max_splits_per_pre_token`` and ``max_overflowing`` limits
+        """
+        pass
+
     @property
     def model(self):
         """
@@ -1075,6 +2184,22 @@ class Tokenizer:
         """
         pass
 
+    def normalize_batch(self, sequences):
+        """
+        Normalize a batch of sequences in parallel, without encoding them
+
+        Only the normalizer runs, on the whole sequences, so the added tokens get normalized
+        like everything else.
+
+        Args:
+            sequences (:obj:`List[str]`):
+                The sequences to normalize
+
+        Returns:
+            :obj:`List[str]`: The normalized sequences
+        """
+        pass
+
     @property
     def normalizer(self):
         """
@@ -1085,8 +2210,26 @@ class Tokenizer:
     def num_special_tokens_to_add(self, is_pair):
         """
         Return the number of special tokens that would be added for single/pair sentences.
-        :param is_pair: Boolean indicating if the input would be a single sentence or a pair
-        :return:
+
+        With a :class:`~tokenizers.processors.Sequence` of post-processors, this is the total
+        added by all of them. The truncation reserves this room when adding the special tokens.
+
+        Args:
+            is_pair (:obj:`bool`):
+                Whether the input would be a pair of sequences
+
+        Returns:
+            :obj:`int`: The number of tokens to add
+        """
+        pass
+
+    @property
+    def num_threads(self):
+        """
+        The maximum number of threads used by the batch methods
+
+        Returns:
+            :obj:`int`: The number of threads
         """
         pass
 
@@ -1095,11 +2238,12 @@ class Tokenizer:
         """
         Get the current padding parameters
 
-        `Cannot be set, use` :meth:`~tokenizers.Tokenizer.enable_padding` `instead`
+        This is a copy of the parameters: to change them, assign the modified parameters back,
+        or :obj:`None` to disable padding
 
         Returns:
-            (:obj:`dict`, `optional`):
-                A dict with the current padding parameters if padding is enabled
+            (:class:`~tokenizers.PaddingParams`, `optional`):
+                The current padding parameters if padding is enabled
         """
         pass
 
@@ -1137,6 +2281,23 @@ class Tokenizer:
         """
         pass
 
+    def pre_tokenize_batch(self, sequences):
+        """
+        Normalize and pre-tokenize a batch of sequences in parallel, without encoding them
+
+        The pre-tokens are the splits the model would get when encoding, added tokens
+        included.
+
+        Args:
+            sequences (:obj:`List[str]`):
+                The sequences to pre-tokenize
+
+        Returns:
+            :obj:`List[List[Tuple[str, Offsets]]]`: The pre-tokens of each sequence, with their
+            offsets in the original sequence
+        """
+        pass
+
     @property
     def pre_tokenizer(self):
         """
@@ -1157,6 +2318,72 @@ class Tokenizer:
         """
         pass
 
+    def save_mmap(self, path):
+        """
+        Save the :class:`~tokenizers.Tokenizer` to a binary file at the given path, to be
+        loaded with :meth:`~tokenizers.Tokenizer.from_file_mmap`.
+
+        The vocab of a :class:`~tokenizers.models.BPE`, :class:`~tokenizers.models.WordLevel`
+        or :class:`~tokenizers.models.WordPiece`, and the merges of a BPE, are written as tables
+        that can be read in place, everything else is written as JSON.
+
+        Args:
+            path (:obj:`str`):
+                A path to a file in which to save the serialized tokenizer.
+        """
+        pass
+
+    def suggest_added_tokens(self, sequences, n=100):
+        """
+        Suggest the words of a corpus worth adding to the vocabulary the most
+
+        The words are the ones produced by the pre-tokenizer and split by the model in
+        several tokens, ranked by the number of tokens they would save on the corpus: their
+        frequency times the number of tokens they'd save on each occurrence. The sequences
+        are encoded without truncation nor special tokens, and the words containing added
+        tokens are ignored.
+
+        Example:
+            Adapt a tokenizer to a new domain::
+
+                suggestions = tokenizer.suggest_added_tokens(corpus, 100)
+                tokenizer.add_tokens([s["token"] for s in suggestions])
+
+        Args:
+            sequences (:obj:`List[str]`):
+                The corpus to scan
+
+            n (:obj:`int`, defaults to :obj:`100`):
+                The maximum number of suggestions
+
+        Returns:
+            :obj:`List[dict]`: The suggestions, best first: the ``token`` to add, an
+            :class:`~tokenizers.AddedToken` matching the word as a whole word of the original
+            text, the ``count`` of occurrences of the word, their total number of ``tokens``
+            with the current vocabulary, and the number of tokens ``saved`` by adding it
+        """
+        pass
+
+    def to_pipeline_graph(self, format="json"):
+        """
+        Describe the components of this :class:`~tokenizers.Tokenizer` and their parameters,
+        in execution order, as a graph.
+
+        Each node has an :obj:`id`, a :obj:`stage`, like :obj:`normalizer`, the :obj:`type` of
+        the component, its :obj:`params`, and the :obj:`parent` :obj:`Sequence` containing it,
+        if any. The edges link each component to the next one to run, the decoder following
+        the model. Unlike the serialized tokenizer, the structure is the same for all the
+        components, which makes pipelines easy to render or compare.
+
+        Args:
+            format (:obj:`str`, defaults to :obj:`"json"`):
+                Either :obj:`"json"`, or :obj:`"dot"` for the DOT language of Graphviz
+
+        Returns:
+            :obj:`str`: The graph, in the given format
+        """
+        pass
+
     def to_str(self, pretty=False):
         """
         Gets a serialized string representing this :class:`~tokenizers.Tokenizer`.
@@ -1170,6 +2397,24 @@ class Tokenizer:
         """
         pass
 
+    def token_ids_with_prefix(self, prefix):
+        """
+        Get the ids of all the tokens of the vocabulary (added tokens included) starting
+        with the given prefix
+
+        The prefix is compared with the tokens as they appear in the vocabulary, so it must
+        use the same representation (``Ġ`` for spaces with byte-level models, ``##`` for
+        WordPiece continuations, ...).
+
+        Args:
+            prefix (:obj:`Union[str, bytes]`):
+                The prefix. A :obj:`bytes` prefix does not have to be valid UTF-8
+
+        Returns:
+            :obj:`List[int]`: The ids, in ascending order
+        """
+        pass
+
     def token_to_id(self, token):
         """
         Convert the given token to its corresponding id if it exists
@@ -1224,15 +2469,216 @@ class Tokenizer:
         """
         pass
 
+    def train_tagged_from_iterator(self, iterator, trainer=None, length=None):
+        """
+        Train the Tokenizer on sequences carrying an optional tag, like their language or their
+        domain, and report what the sequences of each tag contributed to the trained model
+
+        This helps balancing the corpora mixed to train a multilingual tokenizer: a tag with
+        many more ``tokens`` than ``words`` is badly covered by the vocabulary, while its
+        ``exclusive_tokens`` are the part of the vocabulary it alone brought.
+
+        Args:
+            iterator (:obj:`Iterator`):
+                Any iterator over ``(tag, sequence)`` tuples, the tag being a :obj:`str`, or
+                :obj:`None` for an untagged sequence
+
+            trainer (:obj:`~tokenizers.trainers.Trainer`, `optional`):
+                An optional trainer that should be used to train our Model
+
+            length (:obj:`int`, `optional`):
+                The total number of sequences in the iterator. This is used to
+                provide meaningful progress tracking
+
+        Returns:
+            :obj:`List[dict]`: The statistics of each tag, in order of first appearance:
+            ``tag``, ``sequences``, ``words``, ``unique_words``, ``tokens`` (the number of
+            tokens of these words with the trained model), ``multi_char_tokens`` (the tokens
+            covering more than one char used by these words) and ``exclusive_tokens`` (the
+            multi-char tokens used by no other tag)
+        """
+        pass
+
     @property
     def truncation(self):
         """
         Get the currently set truncation parameters
 
-        `Cannot set, use` :meth:`~tokenizers.Tokenizer.enable_truncation` `instead`
+        This is a copy of the parameters: to change them, assign the modified parameters back,
+        or :obj:`None` to disable truncation
+
+        Returns:
+            (:class:`~tokenizers.TruncationParams`, `optional`):
+                The current truncation parameters if truncation is enabled
+        """
+        pass
+
+    @property
+    def warn_on_artifacts(self):
+        """
+        Whether to warn about the inputs that look already tokenized
+
+        A debug mode catching the inputs encoded to tokens upstream, and joined back into a
+        string instead of being decoded. Such inputs contain artifacts like the ``▁`` of
+        :class:`~tokenizers.pre_tokenizers.Metaspace`, the ``Ġ`` of
+        :class:`~tokenizers.pre_tokenizers.ByteLevel` or the ``<0x0A>`` byte tokens, which
+        silently corrupt the data once tokenized again. When enabled,
+        :meth:`~tokenizers.Tokenizer.encode`, :meth:`~tokenizers.Tokenizer.encode_batch` and
+        :meth:`~tokenizers.Tokenizer.encode_batch_fast` emit a :obj:`UserWarning` for each of
+        them. This is a heuristic, disabled by default.
+        """
+        pass
+
+class TruncationParams:
+    """
+    The parameters used to truncate the encodings, as set with
+    :meth:`~tokenizers.Tokenizer.enable_truncation`
+
+    Once built, these parameters can be given to a :class:`~tokenizers.Tokenizer` by assigning
+    its :attr:`~tokenizers.Tokenizer.truncation` property.
+
+    Args:
+        max_length (:obj:`int`):
+            The max length at which to truncate
+
+        stride (:obj:`int`, defaults to :obj:`0`):
+            The length of the previous first sequence to be included in the overflowing
+            sequence
+
+        strategy (:obj:`str`, defaults to :obj:`longest_first`):
+            The strategy used to truncation. Can be one of ``longest_first``, ``only_first``,
+            ``only_second`` or ``budget``.
+
+        direction (:obj:`str`, defaults to :obj:`right`):
+            Truncate direction. Can be either ``right`` or ``left``
+
+        first_budget (:obj:`Union[int, float]`, `optional`):
+            Required by the ``budget`` strategy: the maximum number of tokens of the first
+            sequence of a pair, either as a number of tokens or as a ratio of the max length.
+            The second sequence gets the rest, giving back to the first one the room it
+            doesn't use
+
+        special_tokens (:obj:`str`, defaults to :obj:`reserve`):
+            How the special tokens added by the post-processor count against the max length.
+            With ``reserve``, their room is kept in every encoding, including pairs and
+            overflowing windows, so they always fit. With ``exempt``, they are not counted and
+            get added on top of the max length
+
+        max_overflowing (:obj:`int`, `optional`):
+            The maximum number of overflowing windows kept for each sequence. The windows beyond
+            are never built, which saves time on long documents. All of them are kept by default
+    """
+    def __init__(
+        self,
+        max_length,
+        stride=0,
+        strategy="longest_first",
+        direction="right",
+        first_budget=None,
+        special_tokens="reserve",
+        max_overflowing=None,
+    ):
+        pass
+
+    @property
+    def direction(self):
+        """
+        The truncation direction: ``left`` or ``right``
+        """
+        pass
+
+    @property
+    def first_budget(self):
+        """
+        The budget of the first sequence with the ``budget`` strategy, as a number of tokens
+        or as a ratio of the max length. Setting it switches to the ``budget`` strategy, and
+        :obj:`None` switches back to ``longest_first``
+        """
+        pass
+
+    def get(self, key, default=None):
+        """
+        Get the value of the given parameter, or ``default`` if it does not exist
+        """
+        pass
+
+    def keys(self):
+        """
+        The names of the parameters, which lets ``enable_truncation(**params)`` work
+        """
+        pass
+
+    @property
+    def max_length(self):
+        """
+        The max length at which to truncate
+        """
+        pass
+
+    @property
+    def max_overflowing(self):
+        """
+        The maximum number of overflowing windows kept for each sequence, or :obj:`None` to
+        keep them all
+        """
+        pass
+
+    @property
+    def special_tokens(self):
+        """
+        How the special tokens count against the max length: ``reserve`` or ``exempt``
+        """
+        pass
+
+    @property
+    def strategy(self):
+        """
+        The truncation strategy: ``longest_first``, ``only_first``, ``only_second`` or
+        ``budget``
+        """
+        pass
+
+    @property
+    def stride(self):
+        """
+        The length of the previous first sequence included in the overflowing sequence
+        """
+        pass
+
+    def to_dict(self):
+        """
+        Convert these parameters to a dict, as accepted by
+        :meth:`~tokenizers.Tokenizer.enable_truncation`
 
         Returns:
-            (:obj:`dict`, `optional`):
-                A dict with the current truncation parameters if truncation is enabled
+            :obj:`Dict`: The parameters
         """
         pass
+
+class PipelineError(ValueError):
+    """
+    Raised when the components of a Tokenizer can't work together
+    """
+
+    pass
+
+class IncompatibleDecoderError(PipelineError):
+    """
+    Raised when the decoder can't revert what the other components produce
+    """
+
+    pass
+
+class MissingUnkTokenError(PipelineError):
+    """
+    Raised when the model can't represent the unknown token it needs
+    """
+
+    pass
+
+class LimitExceededError(ValueError):
+    """
+    Raised when an encoding goes over one of the limits set with `Tokenizer.configure_limits`
+    """
+
+    pass
@@ -12,4 +12,5 @@ Metaspace = decoders.Metaspace
 BPEDecoder = decoders.BPEDecoder
 CTC = decoders.CTC
 Sequence = decoders.Sequence
+RunLength = decoders.RunLength
 DecodeStream = decoders.DecodeStream
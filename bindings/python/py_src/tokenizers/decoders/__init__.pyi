@@ -35,8 +35,13 @@ class BPEDecoder(Decoder):
         suffix (:obj:`str`, `optional`, defaults to :obj:`</w>`):
             The suffix that was used to characterize an end-of-word. This suffix will
             be replaced by whitespaces during the decoding
+
+        prefix (:obj:`str`, `optional`):
+            The prefix that was used to characterize the continuing subwords, if any. This
+            prefix will be removed, and a whitespace added before the other subwords. With an
+            empty :obj:`suffix`, only the prefix is used
     """
-    def __init__(self, suffix="</w>"):
+    def __init__(self, suffix="</w>", prefix=None):
         pass
 
     def decode(self, tokens):
@@ -59,8 +64,13 @@ class ByteFallback(Decoder):
     to pure bytes, and attempts to make them into a string. If the tokens
     cannot be decoded you will get � instead for each inconvertible byte token
 
+    Args:
+        invalid_utf8 (:obj:`str`, `optional`, defaults to :obj:`"replace"`):
+            What to do with the byte tokens that don't form valid UTF-8.
+            Choices: "replace" (one � per byte), "ignore" (drop the bytes), "strict" (raise
+            an error). :meth:`~tokenizers.Tokenizer.decode_bytes` can be used to get the raw bytes
     """
-    def __init__(self):
+    def __init__(self, invalid_utf8="replace"):
         pass
 
     def decode(self, tokens):
@@ -205,6 +215,37 @@ class Replace(Decoder):
         """
         pass
 
+class RunLength(Decoder):
+    """
+    RunLength Decoder
+
+    This decoder is to be used in tandem with the
+    :class:`~tokenizers.pre_tokenizers.RunLength` pre-tokenizer, and expands the count tokens
+    back to the runs of characters they replaced.
+
+    Args:
+        max_run (:obj:`int`, `optional`, defaults to :obj:`8`):
+            The length of the longest run kept as is by the pre-tokenizer
+
+        marker (:obj:`str`, `optional`, defaults to :obj:`⟳`):
+            The character around the count tokens. Must be exactly one character.
+    """
+    def __init__(self, max_run=8, marker="⟳"):
+        pass
+
+    def decode(self, tokens):
+        """
+        Decode the given list of tokens to a final string
+
+        Args:
+            tokens (:obj:`List[str]`):
+                The list of tokens to decode
+
+        Returns:
+            :obj:`str`: The decoded string
+        """
+        pass
+
 class Sequence(Decoder):
     """
     Sequence Decoder
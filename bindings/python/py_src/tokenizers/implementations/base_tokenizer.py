@@ -1,6 +1,6 @@
 from typing import Dict, List, Optional, Tuple, Union
 
-from tokenizers import AddedToken, EncodeInput, Encoding, InputSequence, Tokenizer
+from tokenizers import AddedToken, EncodeInput, Encoding, InputSequence, PaddingParams, Tokenizer, TruncationParams
 from tokenizers.decoders import Decoder
 from tokenizers.models import Model
 from tokenizers.normalizers import Normalizer
@@ -109,16 +109,23 @@ class BaseTokenizer:
         return self._tokenizer.no_padding()
 
     @property
-    def padding(self) -> Optional[dict]:
+    def padding(self) -> Optional[PaddingParams]:
         """Get the current padding parameters
 
         Returns:
-            None if padding is disabled, a dict with the currently set parameters
+            None if padding is disabled, the currently set :class:`~tokenizers.PaddingParams`
             if the padding is enabled.
         """
         return self._tokenizer.padding
 
-    def enable_truncation(self, max_length: int, stride: Optional[int] = 0, strategy: Optional[str] = "longest_first"):
+    def enable_truncation(
+        self,
+        max_length: int,
+        stride: Optional[int] = 0,
+        strategy: Optional[str] = "longest_first",
+        first_budget: Optional[Union[int, float]] = None,
+        special_tokens: Optional[str] = "reserve",
+    ):
         """Change the truncation options
 
         Args:
@@ -130,21 +137,35 @@ class BaseTokenizer:
                 in the overflowing sequence
 
             strategy: (`optional`) str:
-                Can be one of `longest_first`, `only_first` or `only_second`
+                Can be one of `longest_first`, `only_first`, `only_second` or `budget`
+
+            first_budget: (`optional`) int or float:
+                Required by the `budget` strategy: the maximum number of tokens of the
+                first sequence of a pair, or its ratio of the max length
+
+            special_tokens: (`optional`) str:
+                Either `reserve`, to keep room for the special tokens within the max length,
+                or `exempt`, to add them on top of it
         """
-        return self._tokenizer.enable_truncation(max_length, stride=stride, strategy=strategy)
+        return self._tokenizer.enable_truncation(
+            max_length,
+            stride=stride,
+            strategy=strategy,
+            first_budget=first_budget,
+            special_tokens=special_tokens,
+        )
 
     def no_truncation(self):
         """Disable truncation"""
         return self._tokenizer.no_truncation()
 
     @property
-    def truncation(self) -> Optional[dict]:
+    def truncation(self) -> Optional[TruncationParams]:
         """Get the current truncation parameters
 
         Returns:
-            None if truncation is disabled, a dict with the current truncation parameters if
-            truncation is enabled
+            None if truncation is disabled, the current :class:`~tokenizers.TruncationParams`
+            if truncation is enabled
         """
         return self._tokenizer.truncation
 
@@ -227,6 +248,7 @@ class BaseTokenizer:
         inputs: List[EncodeInput],
         is_pretokenized: bool = False,
         add_special_tokens: bool = True,
+        max_lengths: Optional[List[Optional[int]]] = None,
     ) -> List[Encoding]:
         """Encode the given inputs. This method accept both raw text sequences as well as already
         pre-tokenized sequences.
@@ -250,6 +272,9 @@ class BaseTokenizer:
             add_special_tokens: bool:
                 Whether to add the special tokens while encoding.
 
+            max_lengths: (`optional`) List[Optional[int]]:
+                The length at which to truncate each input, overriding the truncation `max_length`.
+
         Returns:
             A list of Encoding
         """
@@ -257,9 +282,11 @@ class BaseTokenizer:
         if inputs is None:
             raise ValueError("encode_batch: `inputs` can't be `None`")
 
-        return self._tokenizer.encode_batch(inputs, is_pretokenized, add_special_tokens)
+        return self._tokenizer.encode_batch(inputs, is_pretokenized, add_special_tokens, max_lengths)
 
-    def decode(self, ids: List[int], skip_special_tokens: Optional[bool] = True) -> str:
+    def decode(
+        self, ids: List[int], skip_special_tokens: Optional[bool] = True, preserve_whitespace: Optional[bool] = False
+    ) -> str:
         """Decode the given list of ids to a string sequence
 
         Args:
@@ -269,13 +296,18 @@ class BaseTokenizer:
             skip_special_tokens: (`optional`) boolean:
                 Whether to remove all the special tokens from the output string
 
+            preserve_whitespace: (`optional`) boolean:
+                Whether to reconstruct the whitespace exactly, as needed for code
+
         Returns:
             The decoded string
         """
         if ids is None:
             raise ValueError("None input is not valid. Should be a list of integers.")
 
-        return self._tokenizer.decode(ids, skip_special_tokens=skip_special_tokens)
+        return self._tokenizer.decode(
+            ids, skip_special_tokens=skip_special_tokens, preserve_whitespace=preserve_whitespace
+        )
 
     def decode_batch(self, sequences: List[List[int]], skip_special_tokens: Optional[bool] = True) -> str:
         """Decode the list of sequences to a list of string sequences
@@ -3,6 +3,8 @@ from .. import models
 
 Model = models.Model
 BPE = models.BPE
+Hybrid = models.Hybrid
+MaxMatch = models.MaxMatch
 Unigram = models.Unigram
 WordLevel = models.WordLevel
 WordPiece = models.WordPiece
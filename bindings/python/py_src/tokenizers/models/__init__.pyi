@@ -8,6 +8,19 @@ class Model:
 
     This class cannot be constructed directly. Please use one of the concrete models.
     """
+    def files(self):
+        """
+        Get the content of the files written by :meth:`~tokenizers.models.Model.save`
+
+        This allows embedding them elsewhere, like in a wheel or a container, without going
+        through the filesystem.
+
+        Returns:
+            :obj:`Dict[str, bytes]`: The content of each file, by its default name like
+            :obj:`vocab.json`
+        """
+        pass
+
     def get_trainer(self):
         """
         Get the associated :class:`~tokenizers.trainers.Trainer`
@@ -33,7 +46,7 @@ class Model:
         """
         pass
 
-    def save(self, folder, prefix):
+    def save(self, folder, prefix=None, suffix=None, filenames=None):
         """
         Save the current model
 
@@ -48,6 +61,14 @@ class Model:
             prefix (:obj:`str`, `optional`):
                 An optional prefix, used to prefix each file name
 
+            suffix (:obj:`str`, `optional`):
+                An optional suffix, added to each file name before its extension, like
+                :obj:`vocab-uncased.txt`
+
+            filenames (:obj:`Dict[str, str]`, `optional`):
+                The paths of some files, replacing their default names like :obj:`merges.txt`.
+                The relative paths are relative to :obj:`folder`
+
         Returns:
             :obj:`List[str]`: The list of saved files
         """
@@ -131,6 +152,19 @@ class BPE(Model):
     ):
         pass
 
+    def files(self):
+        """
+        Get the content of the files written by :meth:`~tokenizers.models.Model.save`
+
+        This allows embedding them elsewhere, like in a wheel or a container, without going
+        through the filesystem.
+
+        Returns:
+            :obj:`Dict[str, bytes]`: The content of each file, by its default name like
+            :obj:`vocab.json`
+        """
+        pass
+
     @staticmethod
     def from_file(cls, vocab, merge, **kwargs):
         """
@@ -157,6 +191,30 @@ class BPE(Model):
         """
         pass
 
+    @staticmethod
+    def from_subword_nmt(cls, codes, **kwargs):
+        """
+        Instantiate a BPE model from a subword-nmt :obj:`codes` file
+
+        The vocab is made of the characters found in the merges, each with and without the
+        :obj:`</w>` suffix used by subword-nmt, followed by the tokens produced by the merges,
+        and :obj:`<unk>` for the other characters. Only the version 0.2 of the format is
+        supported.
+
+        Args:
+            codes (:obj:`str`):
+                The path to a :obj:`codes` file, as written by :obj:`learn_bpe.py`
+
+            kwargs:
+                The other options of the :class:`~tokenizers.models.BPE`. The
+                :obj:`end_of_word_suffix` defaults to :obj:`</w>`, and the :obj:`unk_token` to
+                :obj:`<unk>`
+
+        Returns:
+            :class:`~tokenizers.models.BPE`: An instance of BPE loaded from this file
+        """
+        pass
+
     def get_trainer(self):
         """
         Get the associated :class:`~tokenizers.trainers.Trainer`
@@ -182,6 +240,27 @@ class BPE(Model):
         """
         pass
 
+    @staticmethod
+    def read_bytes(vocab, merges):
+        """
+        Read the content of a :obj:`vocab.json` and a :obj:`merges.txt` files
+
+        Like :meth:`~tokenizers.models.BPE.read_file`, for files that were loaded in memory,
+        like the ones given by :meth:`~tokenizers.models.Model.files`.
+
+        Args:
+            vocab (:obj:`bytes`):
+                The content of a :obj:`vocab.json` file
+
+            merges (:obj:`bytes`):
+                The content of a :obj:`merges.txt` file
+
+        Returns:
+            A :obj:`Tuple` with the vocab and the merges:
+                The vocabulary and merges loaded into memory
+        """
+        pass
+
     @staticmethod
     def read_file(self, vocab, merges):
         """
@@ -204,7 +283,7 @@ class BPE(Model):
         """
         pass
 
-    def save(self, folder, prefix):
+    def save(self, folder, prefix=None, suffix=None, filenames=None):
         """
         Save the current model
 
@@ -219,6 +298,315 @@ class BPE(Model):
             prefix (:obj:`str`, `optional`):
                 An optional prefix, used to prefix each file name
 
+            suffix (:obj:`str`, `optional`):
+                An optional suffix, added to each file name before its extension, like
+                :obj:`vocab-uncased.txt`
+
+            filenames (:obj:`Dict[str, str]`, `optional`):
+                The paths of some files, replacing their default names like :obj:`merges.txt`.
+                The relative paths are relative to :obj:`folder`
+
+        Returns:
+            :obj:`List[str]`: The list of saved files
+        """
+        pass
+
+    def token_to_id(self, tokens):
+        """
+        Get the ID associated to a token
+
+        Args:
+            token (:obj:`str`):
+                A token to convert to an ID
+
+        Returns:
+            :obj:`int`: The ID associated to the token
+        """
+        pass
+
+    def tokenize(self, sequence):
+        """
+        Tokenize a sequence
+
+        Args:
+            sequence (:obj:`str`):
+                A sequence to tokenize
+
+        Returns:
+            A :obj:`List` of :class:`~tokenizers.Token`: The generated tokens
+        """
+        pass
+
+class Hybrid(Model):
+    """
+    A model looking each word up in a lexicon before falling back to another model
+
+    The words found as a whole in the lexicon become a single token, while all the others get
+    tokenized by the fallback model, usually a subword one. This keeps the exact matches for
+    some known words, like product references, with subwords for everything else.
+
+    Args:
+        lexicon (:obj:`Dict[str, int]`, `optional`):
+            The words kept as a whole, with their ids. Each of them must either be unknown to
+            the fallback model, with an id it doesn't use, or have the same id in both.
+
+        fallback (:class:`~tokenizers.models.Model`, `optional`):
+            The model tokenizing the words missing from the lexicon, an empty
+            :class:`~tokenizers.models.BPE` by default
+    """
+    def __init__(self, lexicon, fallback):
+        pass
+
+    def files(self):
+        """
+        Get the content of the files written by :meth:`~tokenizers.models.Model.save`
+
+        This allows embedding them elsewhere, like in a wheel or a container, without going
+        through the filesystem.
+
+        Returns:
+            :obj:`Dict[str, bytes]`: The content of each file, by its default name like
+            :obj:`vocab.json`
+        """
+        pass
+
+    def get_trainer(self):
+        """
+        Get the associated :class:`~tokenizers.trainers.Trainer`
+
+        Retrieve the :class:`~tokenizers.trainers.Trainer` associated to this
+        :class:`~tokenizers.models.Model`.
+
+        Returns:
+            :class:`~tokenizers.trainers.Trainer`: The Trainer used to train this model
+        """
+        pass
+
+    def id_to_token(self, id):
+        """
+        Get the token associated to an ID
+
+        Args:
+            id (:obj:`int`):
+                An ID to convert to a token
+
+        Returns:
+            :obj:`str`: The token associated to the ID
+        """
+        pass
+
+    @staticmethod
+    def read_bytes(lexicon):
+        """
+        Read the content of a :obj:`lexicon.json` file
+
+        Like :meth:`~tokenizers.models.Hybrid.read_file`, for a file that was loaded in
+        memory, like the ones given by :meth:`~tokenizers.models.Model.files`.
+
+        Args:
+            lexicon (:obj:`bytes`):
+                The content of a :obj:`lexicon.json` file
+
+        Returns:
+            :obj:`Dict[str, int]`: The lexicon as a :obj:`dict`
+        """
+        pass
+
+    @staticmethod
+    def read_file(lexicon):
+        """
+        Read a :obj:`lexicon.json`, in the same format as the :obj:`vocab.json` of a
+        :class:`~tokenizers.models.WordLevel`
+
+        Args:
+            lexicon (:obj:`str`):
+                The path to a :obj:`lexicon.json` file
+
+        Returns:
+            :obj:`Dict[str, int]`: The lexicon as a :obj:`dict`
+        """
+        pass
+
+    def save(self, folder, prefix=None, suffix=None, filenames=None):
+        """
+        Save the current model
+
+        Save the current model in the given folder, using the given prefix for the various
+        files that will get created.
+        Any file with the same name that already exists in this folder will be overwritten.
+
+        Args:
+            folder (:obj:`str`):
+                The path to the target folder in which to save the various files
+
+            prefix (:obj:`str`, `optional`):
+                An optional prefix, used to prefix each file name
+
+            suffix (:obj:`str`, `optional`):
+                An optional suffix, added to each file name before its extension, like
+                :obj:`vocab-uncased.txt`
+
+            filenames (:obj:`Dict[str, str]`, `optional`):
+                The paths of some files, replacing their default names like :obj:`merges.txt`.
+                The relative paths are relative to :obj:`folder`
+
+        Returns:
+            :obj:`List[str]`: The list of saved files
+        """
+        pass
+
+    def token_to_id(self, tokens):
+        """
+        Get the ID associated to a token
+
+        Args:
+            token (:obj:`str`):
+                A token to convert to an ID
+
+        Returns:
+            :obj:`int`: The ID associated to the token
+        """
+        pass
+
+    def tokenize(self, sequence):
+        """
+        Tokenize a sequence
+
+        Args:
+            sequence (:obj:`str`):
+                A sequence to tokenize
+
+        Returns:
+            A :obj:`List` of :class:`~tokenizers.Token`: The generated tokens
+        """
+        pass
+
+class MaxMatch(Model):
+    """
+    An implementation of the greedy longest-match (MaxMatch) algorithm
+
+    Tokenizes each word from left to right, by taking at each position the longest token
+    of the vocabulary it starts with. It uses no merges nor scores, which makes it a fast
+    option for the lexicon-based tokenization, and a simple baseline. Each character
+    matching no token becomes the unknown token.
+
+    Args:
+        vocab (:obj:`Dict[str, int]`, `optional`):
+            A dictionary of string keys and their ids :obj:`{"am": 0,...}`
+
+        unk_token (:obj:`str`, `optional`):
+            The unknown token to be used by the model.
+    """
+    def __init__(self, vocab, unk_token):
+        pass
+
+    def files(self):
+        """
+        Get the content of the files written by :meth:`~tokenizers.models.Model.save`
+
+        This allows embedding them elsewhere, like in a wheel or a container, without going
+        through the filesystem.
+
+        Returns:
+            :obj:`Dict[str, bytes]`: The content of each file, by its default name like
+            :obj:`vocab.json`
+        """
+        pass
+
+    @staticmethod
+    def from_file(vocab, unk_token):
+        """
+        Instantiate a MaxMatch model from the given :obj:`vocab.json` file
+
+        Args:
+            vocab (:obj:`str`):
+                The path to a :obj:`vocab.json` file
+
+        Returns:
+            :class:`~tokenizers.models.MaxMatch`: An instance of MaxMatch loaded from file
+        """
+        pass
+
+    def get_trainer(self):
+        """
+        Get the associated :class:`~tokenizers.trainers.Trainer`
+
+        Retrieve the :class:`~tokenizers.trainers.Trainer` associated to this
+        :class:`~tokenizers.models.Model`.
+
+        Returns:
+            :class:`~tokenizers.trainers.Trainer`: The Trainer used to train this model
+        """
+        pass
+
+    def id_to_token(self, id):
+        """
+        Get the token associated to an ID
+
+        Args:
+            id (:obj:`int`):
+                An ID to convert to a token
+
+        Returns:
+            :obj:`str`: The token associated to the ID
+        """
+        pass
+
+    @staticmethod
+    def read_bytes(vocab):
+        """
+        Read the content of a :obj:`vocab.json` file
+
+        Like :meth:`~tokenizers.models.MaxMatch.read_file`, for a file that was loaded in
+        memory, like the ones given by :meth:`~tokenizers.models.Model.files`.
+
+        Args:
+            vocab (:obj:`bytes`):
+                The content of a :obj:`vocab.json` file
+
+        Returns:
+            :obj:`Dict[str, int]`: The vocabulary as a :obj:`dict`
+        """
+        pass
+
+    @staticmethod
+    def read_file(vocab):
+        """
+        Read a :obj:`vocab.json`, in the same format as the one of a
+        :class:`~tokenizers.models.WordLevel`
+
+        Args:
+            vocab (:obj:`str`):
+                The path to a :obj:`vocab.json` file
+
+        Returns:
+            :obj:`Dict[str, int]`: The vocabulary as a :obj:`dict`
+        """
+        pass
+
+    def save(self, folder, prefix=None, suffix=None, filenames=None):
+        """
+        Save the current model
+
+        Save the current model in the given folder, using the given prefix for the various
+        files that will get created.
+        Any file with the same name that already exists in this folder will be overwritten.
+
+        Args:
+            folder (:obj:`str`):
+                The path to the target folder in which to save the various files
+
+            prefix (:obj:`str`, `optional`):
+                An optional prefix, used to prefix each file name
+
+            suffix (:obj:`str`, `optional`):
+                An optional suffix, added to each file name before its extension, like
+                :obj:`vocab-uncased.txt`
+
+            filenames (:obj:`Dict[str, str]`, `optional`):
+                The paths of some files, replacing their default names like :obj:`merges.txt`.
+                The relative paths are relative to :obj:`folder`
+
         Returns:
             :obj:`List[str]`: The list of saved files
         """
@@ -257,8 +645,33 @@ class Unigram(Model):
     Args:
         vocab (:obj:`List[Tuple[str, float]]`, `optional`, `optional`):
             A list of vocabulary items and their relative score [("am", -0.2442),...]
+
+        beam_width (:obj:`int`, `optional`):
+            The maximum number of pieces starting at each position considered during the
+            segmentation. The ones with the best scores are kept, along with the single
+            character piece. This bounds the latency on extremely long strings without spaces
+            (URLs, DNA, ...), but can miss the best segmentation when it needs a dropped piece
+
+        lenient_scores (:obj:`bool`, defaults to :obj:`False`):
+            Whether to clamp the scores that aren't finite, like the :obj:`-inf` found in some
+            converted models, instead of raising an error. :obj:`NaN` and :obj:`-inf` become
+            the lowest score minus the penalty of the unknown token, and :obj:`inf` becomes the
+            highest score. The serialized models are always loaded this way, with a warning.
     """
-    def __init__(self, vocab, unk_id, byte_fallback):
+    def __init__(self, vocab, unk_id, byte_fallback, beam_width=None, lenient_scores=False):
+        pass
+
+    def files(self):
+        """
+        Get the content of the files written by :meth:`~tokenizers.models.Model.save`
+
+        This allows embedding them elsewhere, like in a wheel or a container, without going
+        through the filesystem.
+
+        Returns:
+            :obj:`Dict[str, bytes]`: The content of each file, by its default name like
+            :obj:`vocab.json`
+        """
         pass
 
     def get_trainer(self):
@@ -286,7 +699,38 @@ class Unigram(Model):
         """
         pass
 
-    def save(self, folder, prefix):
+    def lattice_dot(self, sentence):
+        """
+        Export the segmentation lattice of the given sentence in the DOT language
+
+        The lattice contains all the pieces that could be used to segment the sentence, with
+        their score, and highlights the best segmentation. It can be rendered with Graphviz.
+
+        Args:
+            sentence (:obj:`str`):
+                The sentence to segment
+
+        Returns:
+            :obj:`str`: The lattice, in the DOT language
+        """
+        pass
+
+    def lattice_json(self, sentence):
+        """
+        Export the segmentation lattice of the given sentence as JSON
+
+        Args:
+            sentence (:obj:`str`):
+                The sentence to segment
+
+        Returns:
+            :obj:`str`: The lattice, as a JSON string with its ``nodes`` (each with its piece,
+            offsets, score and best previous node), and the ``node_id`` of each piece in the
+            ``best_path``
+        """
+        pass
+
+    def save(self, folder, prefix=None, suffix=None, filenames=None):
         """
         Save the current model
 
@@ -301,6 +745,14 @@ class Unigram(Model):
             prefix (:obj:`str`, `optional`):
                 An optional prefix, used to prefix each file name
 
+            suffix (:obj:`str`, `optional`):
+                An optional suffix, added to each file name before its extension, like
+                :obj:`vocab-uncased.txt`
+
+            filenames (:obj:`Dict[str, str]`, `optional`):
+                The paths of some files, replacing their default names like :obj:`merges.txt`.
+                The relative paths are relative to :obj:`folder`
+
         Returns:
             :obj:`List[str]`: The list of saved files
         """
@@ -348,6 +800,19 @@ class WordLevel(Model):
     def __init__(self, vocab, unk_token):
         pass
 
+    def files(self):
+        """
+        Get the content of the files written by :meth:`~tokenizers.models.Model.save`
+
+        This allows embedding them elsewhere, like in a wheel or a container, without going
+        through the filesystem.
+
+        Returns:
+            :obj:`Dict[str, bytes]`: The content of each file, by its default name like
+            :obj:`vocab.json`
+        """
+        pass
+
     @staticmethod
     def from_file(vocab, unk_token):
         """
@@ -396,6 +861,23 @@ class WordLevel(Model):
         """
         pass
 
+    @staticmethod
+    def read_bytes(vocab):
+        """
+        Read the content of a :obj:`vocab.json` file
+
+        Like :meth:`~tokenizers.models.WordLevel.read_file`, for a file that was loaded in
+        memory, like the ones given by :meth:`~tokenizers.models.Model.files`.
+
+        Args:
+            vocab (:obj:`bytes`):
+                The content of a :obj:`vocab.json` file
+
+        Returns:
+            :obj:`Dict[str, int]`: The vocabulary as a :obj:`dict`
+        """
+        pass
+
     @staticmethod
     def read_file(vocab):
         """
@@ -414,7 +896,7 @@ class WordLevel(Model):
         """
         pass
 
-    def save(self, folder, prefix):
+    def save(self, folder, prefix=None, suffix=None, filenames=None):
         """
         Save the current model
 
@@ -429,6 +911,14 @@ class WordLevel(Model):
             prefix (:obj:`str`, `optional`):
                 An optional prefix, used to prefix each file name
 
+            suffix (:obj:`str`, `optional`):
+                An optional suffix, added to each file name before its extension, like
+                :obj:`vocab-uncased.txt`
+
+            filenames (:obj:`Dict[str, str]`, `optional`):
+                The paths of some files, replacing their default names like :obj:`merges.txt`.
+                The relative paths are relative to :obj:`folder`
+
         Returns:
             :obj:`List[str]`: The list of saved files
         """
@@ -473,10 +963,26 @@ class WordPiece(Model):
 
         max_input_chars_per_word (:obj:`int`, `optional`):
             The maximum number of characters to authorize in a single word.
+
+        end_of_word_suffix (:obj:`str`, `optional`):
+            The suffix attached to the subword units that represent an end of word.
     """
     def __init__(self, vocab, unk_token, max_input_chars_per_word):
         pass
 
+    def files(self):
+        """
+        Get the content of the files written by :meth:`~tokenizers.models.Model.save`
+
+        This allows embedding them elsewhere, like in a wheel or a container, without going
+        through the filesystem.
+
+        Returns:
+            :obj:`Dict[str, bytes]`: The content of each file, by its default name like
+            :obj:`vocab.json`
+        """
+        pass
+
     @staticmethod
     def from_file(vocab, **kwargs):
         """
@@ -525,6 +1031,23 @@ class WordPiece(Model):
         """
         pass
 
+    @staticmethod
+    def read_bytes(vocab):
+        """
+        Read the content of a :obj:`vocab.txt` file
+
+        Like :meth:`~tokenizers.models.WordPiece.read_file`, for a file that was loaded in
+        memory, like the ones given by :meth:`~tokenizers.models.Model.files`.
+
+        Args:
+            vocab (:obj:`bytes`):
+                The content of a :obj:`vocab.txt` file
+
+        Returns:
+            :obj:`Dict[str, int]`: The vocabulary as a :obj:`dict`
+        """
+        pass
+
     @staticmethod
     def read_file(vocab):
         """
@@ -544,7 +1067,7 @@ class WordPiece(Model):
         """
         pass
 
-    def save(self, folder, prefix):
+    def save(self, folder, prefix=None, suffix=None, filenames=None):
         """
         Save the current model
 
@@ -559,6 +1082,14 @@ class WordPiece(Model):
             prefix (:obj:`str`, `optional`):
                 An optional prefix, used to prefix each file name
 
+            suffix (:obj:`str`, `optional`):
+                An optional suffix, added to each file name before its extension, like
+                :obj:`vocab-uncased.txt`
+
+            filenames (:obj:`Dict[str, str]`, `optional`):
+                The paths of some files, replacing their default names like :obj:`merges.txt`.
+                The relative paths are relative to :obj:`folder`
+
         Returns:
             :obj:`List[str]`: The list of saved files
         """
@@ -3,6 +3,7 @@ from .. import normalizers
 
 Normalizer = normalizers.Normalizer
 BertNormalizer = normalizers.BertNormalizer
+BidiControls = normalizers.BidiControls
 NFD = normalizers.NFD
 NFKD = normalizers.NFKD
 NFC = normalizers.NFC
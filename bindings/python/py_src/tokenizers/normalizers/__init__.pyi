@@ -99,6 +99,63 @@ class BertNormalizer(Normalizer):
         """
         pass
 
+class BidiControls(Normalizer):
+    """
+    BidiControls Normalizer
+
+    Handles the invisible controls of the Unicode bidirectional algorithm that a
+    right-to-left text (Arabic, Hebrew, ...) may contain: the marks (ALM, LRM, RLM), the
+    embeddings and overrides (LRE, RLE, PDF, LRO, RLO), and the isolates (LRI, RLI, FSI, PDI).
+    Without it, they end up in the tokens, or are unknown to the model.
+
+    The offsets of a right-to-left text are in logical order, like all the offsets: they are
+    indices in the text as stored, where its first char is also its first char read, the
+    rightmost one once displayed. Highlighting a span only needs slicing the original text
+    with its offsets, the display of the slice being left to the bidi algorithm.
+
+    Args:
+        handling (:obj:`str`, `optional`, defaults to :obj:`"remove"`):
+            What to do with the controls. Choices: "remove" removes them and joins the text
+            around them, "space" replaces each of them by a space, splitting the text around
+            them like a whitespace.
+    """
+    def __init__(self, handling="remove"):
+        pass
+
+    def normalize(self, normalized):
+        """
+        Normalize a :class:`~tokenizers.NormalizedString` in-place
+
+        This method allows to modify a :class:`~tokenizers.NormalizedString` to
+        keep track of the alignment information. If you just want to see the result
+        of the normalization on a raw string, you can use
+        :meth:`~tokenizers.normalizers.Normalizer.normalize_str`
+
+        Args:
+            normalized (:class:`~tokenizers.NormalizedString`):
+                The normalized string on which to apply this
+                :class:`~tokenizers.normalizers.Normalizer`
+        """
+        pass
+
+    def normalize_str(self, sequence):
+        """
+        Normalize the given string
+
+        This method provides a way to visualize the effect of a
+        :class:`~tokenizers.normalizers.Normalizer` but it does not keep track of the alignment
+        information. If you need to get/convert offsets, you can use
+        :meth:`~tokenizers.normalizers.Normalizer.normalize`
+
+        Args:
+            sequence (:obj:`str`):
+                A string to normalize
+
+        Returns:
+            :obj:`str`: A string after normalization
+        """
+        pass
+
 class ByteLevel(Normalizer):
     """
     Bytelevel Normalizer
@@ -394,6 +451,24 @@ class Precompiled(Normalizer):
     def __init__(self, precompiled_charsmap):
         pass
 
+    @staticmethod
+    def from_rules(rules):
+        """
+        Build a Precompiled normalizer from a list of ``(source, replacement)`` rules,
+        instead of a ``precompiled_charsmap`` extracted from a SentencePiece model.
+
+        Just like with SentencePiece, the rules are looked up for each grapheme first,
+        and then for each of its characters.
+
+        Args:
+            rules (:obj:`List[Tuple[str, str]]`):
+                The rules to apply
+
+        Returns:
+            :class:`~tokenizers.normalizers.Precompiled`: The normalizer
+        """
+        pass
+
     def normalize(self, normalized):
         """
         Normalize a :class:`~tokenizers.NormalizedString` in-place
@@ -0,0 +1,6 @@
+# Generated content DO NOT EDIT
+from .. import offsets
+
+OffsetConverter = offsets.OffsetConverter
+bytes_to_char = offsets.bytes_to_char
+char_to_bytes = offsets.char_to_bytes
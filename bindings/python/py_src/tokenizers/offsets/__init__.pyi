@@ -0,0 +1,84 @@
+# Generated content DO NOT EDIT
+def bytes_to_char(text, offsets):
+    """
+    Convert the given offsets from bytes to chars
+
+    Args:
+        text (:obj:`str`):
+            The string the offsets refer to
+
+        offsets (:obj:`Tuple[int, int]`):
+            The byte offsets to convert
+
+    Returns:
+        :obj:`Tuple[int, int]`: The char offsets, or :obj:`None` if the given offsets do not
+        fall on char boundaries
+    """
+    pass
+
+def char_to_bytes(text, offsets):
+    """
+    Convert the given offsets from chars to bytes
+
+    Args:
+        text (:obj:`str`):
+            The string the offsets refer to
+
+        offsets (:obj:`Tuple[int, int]`):
+            The char offsets to convert
+
+    Returns:
+        :obj:`Tuple[int, int]`: The byte offsets, or :obj:`None` if the given offsets start
+        past the end of the string. An end past the end of the string gets clamped
+    """
+    pass
+
+class OffsetConverter:
+    """
+    Converts offsets between bytes and chars for a given string
+
+    The char boundaries are computed once, which makes it the best option when
+    converting many offsets relative to the same string.
+
+    Args:
+        text (:obj:`str`):
+            The string the offsets refer to
+    """
+    def __init__(self, text):
+        pass
+
+    def byte_to_char(self, index):
+        """
+        Convert the given byte index to a char index
+
+        Returns:
+            :obj:`int`: The char index, or :obj:`None` if not on a char boundary
+        """
+        pass
+
+    def bytes_to_char(self, offsets):
+        """
+        Convert the given offsets from bytes to chars
+
+        Returns:
+            :obj:`Tuple[int, int]`: The char offsets, or :obj:`None` if not on char boundaries
+        """
+        pass
+
+    def char_to_byte(self, index):
+        """
+        Convert the given char index to a byte index
+
+        Returns:
+            :obj:`int`: The byte index, or :obj:`None` if past the end of the string
+        """
+        pass
+
+    def char_to_bytes(self, offsets):
+        """
+        Convert the given offsets from chars to bytes
+
+        Returns:
+            :obj:`Tuple[int, int]`: The byte offsets, or :obj:`None` if past the end of the string
+        """
+        pass
@@ -1,16 +1,23 @@
 # Generated content DO NOT EDIT
 from .. import pre_tokenizers
 
+PreTokenizeStream = pre_tokenizers.PreTokenizeStream
 PreTokenizer = pre_tokenizers.PreTokenizer
 BertPreTokenizer = pre_tokenizers.BertPreTokenizer
 ByteLevel = pre_tokenizers.ByteLevel
 CharDelimiterSplit = pre_tokenizers.CharDelimiterSplit
 Digits = pre_tokenizers.Digits
 FixedLength = pre_tokenizers.FixedLength
+Indentation = pre_tokenizers.Indentation
 Metaspace = pre_tokenizers.Metaspace
+ModelSplit = pre_tokenizers.ModelSplit
 Punctuation = pre_tokenizers.Punctuation
+RegexSplit = pre_tokenizers.RegexSplit
+RunLength = pre_tokenizers.RunLength
+ScriptSplit = pre_tokenizers.ScriptSplit
 Sequence = pre_tokenizers.Sequence
 Split = pre_tokenizers.Split
 UnicodeScripts = pre_tokenizers.UnicodeScripts
+UnicodeWords = pre_tokenizers.UnicodeWords
 Whitespace = pre_tokenizers.Whitespace
 WhitespaceSplit = pre_tokenizers.WhitespaceSplit
@@ -1,4 +1,10 @@
 # Generated content DO NOT EDIT
+class PreTokenizeStream:
+    """
+    The pre-tokens of a string, returned by
+    :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize_str_streaming`
+    """
+
 class PreTokenizer:
     """
     Base class for all pre-tokenizers
@@ -43,6 +49,33 @@ class PreTokenizer:
         """
         pass
 
+    def pre_tokenize_str_streaming(self, sequence, chunk_size=1048576):
+        """
+        Pre tokenize the given string chunk by chunk, yielding the pre-tokens as they come
+
+        Like :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize_str`, but only a
+        single chunk of the string is pre-tokenized at once, which bounds the memory used for
+        very long strings, like whole books. Each chunk ends right before some whitespace, so
+        the pre-tokens are the same for the pre-tokenizers splitting on whitespace, except for
+        the words longer than a chunk, which get split. A chunk starting elsewhere than before
+        a space, like before a newline or within a long word, is pre-tokenized as the start of
+        a string: the pre-tokenizers adding a prefix space, like
+        :class:`~tokenizers.pre_tokenizers.ByteLevel`, add one there too.
+
+        Args:
+            sequence (:obj:`str`):
+                A string to pre-tokenize
+
+            chunk_size (:obj:`int`, defaults to :obj:`1048576`):
+                The approximate size of the chunks, in bytes
+
+        Returns:
+            :class:`~tokenizers.pre_tokenizers.PreTokenizeStream`:
+                An iterator over the tuples of the pre-tokenized parts and their offsets in the
+                whole string
+        """
+        pass
+
 class BertPreTokenizer(PreTokenizer):
     """
     BertPreTokenizer
@@ -90,6 +123,33 @@ class BertPreTokenizer(PreTokenizer):
         """
         pass
 
+    def pre_tokenize_str_streaming(self, sequence, chunk_size=1048576):
+        """
+        Pre tokenize the given string chunk by chunk, yielding the pre-tokens as they come
+
+        Like :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize_str`, but only a
+        single chunk of the string is pre-tokenized at once, which bounds the memory used for
+        very long strings, like whole books. Each chunk ends right before some whitespace, so
+        the pre-tokens are the same for the pre-tokenizers splitting on whitespace, except for
+        the words longer than a chunk, which get split. A chunk starting elsewhere than before
+        a space, like before a newline or within a long word, is pre-tokenized as the start of
+        a string: the pre-tokenizers adding a prefix space, like
+        :class:`~tokenizers.pre_tokenizers.ByteLevel`, add one there too.
+
+        Args:
+            sequence (:obj:`str`):
+                A string to pre-tokenize
+
+            chunk_size (:obj:`int`, defaults to :obj:`1048576`):
+                The approximate size of the chunks, in bytes
+
+        Returns:
+            :class:`~tokenizers.pre_tokenizers.PreTokenizeStream`:
+                An iterator over the tuples of the pre-tokenized parts and their offsets in the
+                whole string
+        """
+        pass
+
 class ByteLevel(PreTokenizer):
     """
     ByteLevel PreTokenizer
@@ -159,6 +219,33 @@ class ByteLevel(PreTokenizer):
         """
         pass
 
+    def pre_tokenize_str_streaming(self, sequence, chunk_size=1048576):
+        """
+        Pre tokenize the given string chunk by chunk, yielding the pre-tokens as they come
+
+        Like :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize_str`, but only a
+        single chunk of the string is pre-tokenized at once, which bounds the memory used for
+        very long strings, like whole books. Each chunk ends right before some whitespace, so
+        the pre-tokens are the same for the pre-tokenizers splitting on whitespace, except for
+        the words longer than a chunk, which get split. A chunk starting elsewhere than before
+        a space, like before a newline or within a long word, is pre-tokenized as the start of
+        a string: the pre-tokenizers adding a prefix space, like
+        :class:`~tokenizers.pre_tokenizers.ByteLevel`, add one there too.
+
+        Args:
+            sequence (:obj:`str`):
+                A string to pre-tokenize
+
+            chunk_size (:obj:`int`, defaults to :obj:`1048576`):
+                The approximate size of the chunks, in bytes
+
+        Returns:
+            :class:`~tokenizers.pre_tokenizers.PreTokenizeStream`:
+                An iterator over the tuples of the pre-tokenized parts and their offsets in the
+                whole string
+        """
+        pass
+
 class CharDelimiterSplit(PreTokenizer):
     """
     This pre-tokenizer simply splits on the provided char. Works like `.split(delimiter)`
@@ -204,6 +291,33 @@ class CharDelimiterSplit(PreTokenizer):
         """
         pass
 
+    def pre_tokenize_str_streaming(self, sequence, chunk_size=1048576):
+        """
+        Pre tokenize the given string chunk by chunk, yielding the pre-tokens as they come
+
+        Like :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize_str`, but only a
+        single chunk of the string is pre-tokenized at once, which bounds the memory used for
+        very long strings, like whole books. Each chunk ends right before some whitespace, so
+        the pre-tokens are the same for the pre-tokenizers splitting on whitespace, except for
+        the words longer than a chunk, which get split. A chunk starting elsewhere than before
+        a space, like before a newline or within a long word, is pre-tokenized as the start of
+        a string: the pre-tokenizers adding a prefix space, like
+        :class:`~tokenizers.pre_tokenizers.ByteLevel`, add one there too.
+
+        Args:
+            sequence (:obj:`str`):
+                A string to pre-tokenize
+
+            chunk_size (:obj:`int`, defaults to :obj:`1048576`):
+                The approximate size of the chunks, in bytes
+
+        Returns:
+            :class:`~tokenizers.pre_tokenizers.PreTokenizeStream`:
+                An iterator over the tuples of the pre-tokenized parts and their offsets in the
+                whole string
+        """
+        pass
+
 class Digits(PreTokenizer):
     """
     This pre-tokenizer simply splits using the digits in separate tokens
@@ -258,6 +372,33 @@ class Digits(PreTokenizer):
         """
         pass
 
+    def pre_tokenize_str_streaming(self, sequence, chunk_size=1048576):
+        """
+        Pre tokenize the given string chunk by chunk, yielding the pre-tokens as they come
+
+        Like :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize_str`, but only a
+        single chunk of the string is pre-tokenized at once, which bounds the memory used for
+        very long strings, like whole books. Each chunk ends right before some whitespace, so
+        the pre-tokens are the same for the pre-tokenizers splitting on whitespace, except for
+        the words longer than a chunk, which get split. A chunk starting elsewhere than before
+        a space, like before a newline or within a long word, is pre-tokenized as the start of
+        a string: the pre-tokenizers adding a prefix space, like
+        :class:`~tokenizers.pre_tokenizers.ByteLevel`, add one there too.
+
+        Args:
+            sequence (:obj:`str`):
+                A string to pre-tokenize
+
+            chunk_size (:obj:`int`, defaults to :obj:`1048576`):
+                The approximate size of the chunks, in bytes
+
+        Returns:
+            :class:`~tokenizers.pre_tokenizers.PreTokenizeStream`:
+                An iterator over the tuples of the pre-tokenized parts and their offsets in the
+                whole string
+        """
+        pass
+
 class FixedLength(PreTokenizer):
     """
     This pre-tokenizer splits the text into fixed length chunks as used
@@ -310,6 +451,119 @@ class FixedLength(PreTokenizer):
         """
         pass
 
+    def pre_tokenize_str_streaming(self, sequence, chunk_size=1048576):
+        """
+        Pre tokenize the given string chunk by chunk, yielding the pre-tokens as they come
+
+        Like :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize_str`, but only a
+        single chunk of the string is pre-tokenized at once, which bounds the memory used for
+        very long strings, like whole books. Each chunk ends right before some whitespace, so
+        the pre-tokens are the same for the pre-tokenizers splitting on whitespace, except for
+        the words longer than a chunk, which get split. A chunk starting elsewhere than before
+        a space, like before a newline or within a long word, is pre-tokenized as the start of
+        a string: the pre-tokenizers adding a prefix space, like
+        :class:`~tokenizers.pre_tokenizers.ByteLevel`, add one there too.
+
+        Args:
+            sequence (:obj:`str`):
+                A string to pre-tokenize
+
+            chunk_size (:obj:`int`, defaults to :obj:`1048576`):
+                The approximate size of the chunks, in bytes
+
+        Returns:
+            :class:`~tokenizers.pre_tokenizers.PreTokenizeStream`:
+                An iterator over the tuples of the pre-tokenized parts and their offsets in the
+                whole string
+        """
+        pass
+
+class Indentation(PreTokenizer):
+    """
+    This pre-tokenizer splits each indentation unit found at the start of a line into its
+    own piece, so that the model can represent the indentation of code with dedicated tokens.
+    The rest of the text, including the leftover whitespace that doesn't make a full unit,
+    is kept as is, to be split by the next pre-tokenizer::
+
+        "if x:\\n        y" -> "if x:\\n", "    ", "    ", "y"
+
+    The pre-tokenizers removing the whitespace, like
+    :class:`~tokenizers.pre_tokenizers.WhitespaceSplit`, would drop the indentation pieces,
+    so it is best followed by :class:`~tokenizers.pre_tokenizers.ByteLevel` or
+    :class:`~tokenizers.pre_tokenizers.Metaspace`.
+
+    Args:
+        unit (:obj:`str`, `optional`, defaults to :obj:`"four_spaces"`):
+            The indentation unit, one of :obj:`"tab"`, :obj:`"two_spaces"` and
+            :obj:`"four_spaces"`
+    """
+    def __init__(self, unit="four_spaces"):
+        pass
+
+    def pre_tokenize(self, pretok):
+        """
+        Pre-tokenize a :class:`~tokenizers.PyPreTokenizedString` in-place
+
+        This method allows to modify a :class:`~tokenizers.PreTokenizedString` to
+        keep track of the pre-tokenization, and leverage the capabilities of the
+        :class:`~tokenizers.PreTokenizedString`. If you just want to see the result of
+        the pre-tokenization of a raw string, you can use
+        :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize_str`
+
+        Args:
+            pretok (:class:`~tokenizers.PreTokenizedString):
+                The pre-tokenized string on which to apply this
+                :class:`~tokenizers.pre_tokenizers.PreTokenizer`
+        """
+        pass
+
+    def pre_tokenize_str(self, sequence):
+        """
+        Pre tokenize the given string
+
+        This method provides a way to visualize the effect of a
+        :class:`~tokenizers.pre_tokenizers.PreTokenizer` but it does not keep track of the
+        alignment, nor does it provide all the capabilities of the
+        :class:`~tokenizers.PreTokenizedString`. If you need some of these, you can use
+        :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize`
+
+        Args:
+            sequence (:obj:`str`):
+                A string to pre-tokeize
+
+        Returns:
+            :obj:`List[Tuple[str, Offsets]]`:
+                A list of tuple with the pre-tokenized parts and their offsets
+        """
+        pass
+
+    def pre_tokenize_str_streaming(self, sequence, chunk_size=1048576):
+        """
+        Pre tokenize the given string chunk by chunk, yielding the pre-tokens as they come
+
+        Like :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize_str`, but only a
+        single chunk of the string is pre-tokenized at once, which bounds the memory used for
+        very long strings, like whole books. Each chunk ends right before some whitespace, so
+        the pre-tokens are the same for the pre-tokenizers splitting on whitespace, except for
+        the words longer than a chunk, which get split. A chunk starting elsewhere than before
+        a space, like before a newline or within a long word, is pre-tokenized as the start of
+        a string: the pre-tokenizers adding a prefix space, like
+        :class:`~tokenizers.pre_tokenizers.ByteLevel`, add one there too.
+
+        Args:
+            sequence (:obj:`str`):
+                A string to pre-tokenize
+
+            chunk_size (:obj:`int`, defaults to :obj:`1048576`):
+                The approximate size of the chunks, in bytes
+
+        Returns:
+            :class:`~tokenizers.pre_tokenizers.PreTokenizeStream`:
+                An iterator over the tuples of the pre-tokenized parts and their offsets in the
+                whole string
+        """
+        pass
+
 class Metaspace(PreTokenizer):
     """
     Metaspace pre-tokenizer
@@ -369,6 +623,116 @@ class Metaspace(PreTokenizer):
         """
         pass
 
+    def pre_tokenize_str_streaming(self, sequence, chunk_size=1048576):
+        """
+        Pre tokenize the given string chunk by chunk, yielding the pre-tokens as they come
+
+        Like :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize_str`, but only a
+        single chunk of the string is pre-tokenized at once, which bounds the memory used for
+        very long strings, like whole books. Each chunk ends right before some whitespace, so
+        the pre-tokens are the same for the pre-tokenizers splitting on whitespace, except for
+        the words longer than a chunk, which get split. A chunk starting elsewhere than before
+        a space, like before a newline or within a long word, is pre-tokenized as the start of
+        a string: the pre-tokenizers adding a prefix space, like
+        :class:`~tokenizers.pre_tokenizers.ByteLevel`, add one there too.
+
+        Args:
+            sequence (:obj:`str`):
+                A string to pre-tokenize
+
+            chunk_size (:obj:`int`, defaults to :obj:`1048576`):
+                The approximate size of the chunks, in bytes
+
+        Returns:
+            :class:`~tokenizers.pre_tokenizers.PreTokenizeStream`:
+                An iterator over the tuples of the pre-tokenized parts and their offsets in the
+                whole string
+        """
+        pass
+
+class ModelSplit(PreTokenizer):
+    """
+    ModelSplit PreTokenizer
+
+    Splits each pre-token into the tokens found by a model, keeping their offsets in the
+    original text. This is how :meth:`~tokenizers.Tokenizer.chain` feeds the tokens of a
+    tokenizer to the model of another one.
+
+    Args:
+        model (:class:`~tokenizers.models.Model`):
+            The model used to split the pre-tokens. It is copied, so changing it afterwards
+            has no effect on this pre-tokenizer
+
+        normalizer (:class:`~tokenizers.normalizers.Normalizer`, `optional`):
+            A normalizer applied to each of the pieces
+    """
+    def __init__(self, model, normalizer=None):
+        pass
+
+    def pre_tokenize(self, pretok):
+        """
+        Pre-tokenize a :class:`~tokenizers.PyPreTokenizedString` in-place
+
+        This method allows to modify a :class:`~tokenizers.PreTokenizedString` to
+        keep track of the pre-tokenization, and leverage the capabilities of the
+        :class:`~tokenizers.PreTokenizedString`. If you just want to see the result of
+        the pre-tokenization of a raw string, you can use
+        :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize_str`
+
+        Args:
+            pretok (:class:`~tokenizers.PreTokenizedString):
+                The pre-tokenized string on which to apply this
+                :class:`~tokenizers.pre_tokenizers.PreTokenizer`
+        """
+        pass
+
+    def pre_tokenize_str(self, sequence):
+        """
+        Pre tokenize the given string
+
+        This method provides a way to visualize the effect of a
+        :class:`~tokenizers.pre_tokenizers.PreTokenizer` but it does not keep track of the
+        alignment, nor does it provide all the capabilities of the
+        :class:`~tokenizers.PreTokenizedString`. If you need some of these, you can use
+        :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize`
+
+        Args:
+            sequence (:obj:`str`):
+                A string to pre-tokeize
+
+        Returns:
+            :obj:`List[Tuple[str, Offsets]]`:
+                A list of tuple with the pre-tokenized parts and their offsets
+        """
+        pass
+
+    def pre_tokenize_str_streaming(self, sequence, chunk_size=1048576):
+        """
+        Pre tokenize the given string chunk by chunk, yielding the pre-tokens as they come
+
+        Like :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize_str`, but only a
+        single chunk of the string is pre-tokenized at once, which bounds the memory used for
+        very long strings, like whole books. Each chunk ends right before some whitespace, so
+        the pre-tokens are the same for the pre-tokenizers splitting on whitespace, except for
+        the words longer than a chunk, which get split. A chunk starting elsewhere than before
+        a space, like before a newline or within a long word, is pre-tokenized as the start of
+        a string: the pre-tokenizers adding a prefix space, like
+        :class:`~tokenizers.pre_tokenizers.ByteLevel`, add one there too.
+
+        Args:
+            sequence (:obj:`str`):
+                A string to pre-tokenize
+
+            chunk_size (:obj:`int`, defaults to :obj:`1048576`):
+                The approximate size of the chunks, in bytes
+
+        Returns:
+            :class:`~tokenizers.pre_tokenizers.PreTokenizeStream`:
+                An iterator over the tuples of the pre-tokenized parts and their offsets in the
+                whole string
+        """
+        pass
+
 class Punctuation(PreTokenizer):
     """
     This pre-tokenizer simply splits on punctuation as individual characters.
@@ -419,6 +783,292 @@ class Punctuation(PreTokenizer):
         """
         pass
 
+    def pre_tokenize_str_streaming(self, sequence, chunk_size=1048576):
+        """
+        Pre tokenize the given string chunk by chunk, yielding the pre-tokens as they come
+
+        Like :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize_str`, but only a
+        single chunk of the string is pre-tokenized at once, which bounds the memory used for
+        very long strings, like whole books. Each chunk ends right before some whitespace, so
+        the pre-tokens are the same for the pre-tokenizers splitting on whitespace, except for
+        the words longer than a chunk, which get split. A chunk starting elsewhere than before
+        a space, like before a newline or within a long word, is pre-tokenized as the start of
+        a string: the pre-tokenizers adding a prefix space, like
+        :class:`~tokenizers.pre_tokenizers.ByteLevel`, add one there too.
+
+        Args:
+            sequence (:obj:`str`):
+                A string to pre-tokenize
+
+            chunk_size (:obj:`int`, defaults to :obj:`1048576`):
+                The approximate size of the chunks, in bytes
+
+        Returns:
+            :class:`~tokenizers.pre_tokenizers.PreTokenizeStream`:
+                An iterator over the tuples of the pre-tokenized parts and their offsets in the
+                whole string
+        """
+        pass
+
+class RegexSplit(PreTokenizer):
+    """
+    This pre-tokenizer splits the text on the matches of a full regex pattern, just like
+    `re.findall` would, which makes it possible to use the pre-tokenization patterns of
+    GPT-4 or LLaMA 3 as is, lookarounds included.
+
+    Each match becomes a piece. When the pattern has capture groups, each group that
+    participated in the match becomes a piece instead, and the rest of the match is removed.
+    The nested groups, and those reaching out of the match through a lookaround, are
+    ignored. Contrarily to `re.findall`, the text that doesn't match is never lost, and gets
+    its own pieces.
+
+    Args:
+        pattern (:obj:`str`):
+            The regex pattern
+    """
+    def __init__(self, pattern):
+        pass
+
+    def pre_tokenize(self, pretok):
+        """
+        Pre-tokenize a :class:`~tokenizers.PyPreTokenizedString` in-place
+
+        This method allows to modify a :class:`~tokenizers.PreTokenizedString` to
+        keep track of the pre-tokenization, and leverage the capabilities of the
+        :class:`~tokenizers.PreTokenizedString`. If you just want to see the result of
+        the pre-tokenization of a raw string, you can use
+        :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize_str`
+
+        Args:
+            pretok (:class:`~tokenizers.PreTokenizedString):
+                The pre-tokenized string on which to apply this
+                :class:`~tokenizers.pre_tokenizers.PreTokenizer`
+        """
+        pass
+
+    def pre_tokenize_str(self, sequence):
+        """
+        Pre tokenize the given string
+
+        This method provides a way to visualize the effect of a
+        :class:`~tokenizers.pre_tokenizers.PreTokenizer` but it does not keep track of the
+        alignment, nor does it provide all the capabilities of the
+        :class:`~tokenizers.PreTokenizedString`. If you need some of these, you can use
+        :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize`
+
+        Args:
+            sequence (:obj:`str`):
+                A string to pre-tokeize
+
+        Returns:
+            :obj:`List[Tuple[str, Offsets]]`:
+                A list of tuple with the pre-tokenized parts and their offsets
+        """
+        pass
+
+    def pre_tokenize_str_streaming(self, sequence, chunk_size=1048576):
+        """
+        Pre tokenize the given string chunk by chunk, yielding the pre-tokens as they come
+
+        Like :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize_str`, but only a
+        single chunk of the string is pre-tokenized at once, which bounds the memory used for
+        very long strings, like whole books. Each chunk ends right before some whitespace, so
+        the pre-tokens are the same for the pre-tokenizers splitting on whitespace, except for
+        the words longer than a chunk, which get split. A chunk starting elsewhere than before
+        a space, like before a newline or within a long word, is pre-tokenized as the start of
+        a string: the pre-tokenizers adding a prefix space, like
+        :class:`~tokenizers.pre_tokenizers.ByteLevel`, add one there too.
+
+        Args:
+            sequence (:obj:`str`):
+                A string to pre-tokenize
+
+            chunk_size (:obj:`int`, defaults to :obj:`1048576`):
+                The approximate size of the chunks, in bytes
+
+        Returns:
+            :class:`~tokenizers.pre_tokenizers.PreTokenizeStream`:
+                An iterator over the tuples of the pre-tokenized parts and their offsets in the
+                whole string
+        """
+        pass
+
+class RunLength(PreTokenizer):
+    """
+    This pre-tokenizer collapses the runs of a same character longer than ``max_run`` into
+    the character itself, followed by a count token made of the length of the run between two
+    ``marker``::
+
+        "wait!!!!!!!!!!!!" -> "wait", "!", "⟳12⟳"
+
+    This protects the model from the arbitrarily long runs found in adversarial inputs. The
+    :class:`~tokenizers.decoders.RunLength` decoder expands the count tokens back to the
+    original runs.
+
+    Args:
+        max_run (:obj:`int`, `optional`, defaults to :obj:`8`):
+            The length of the longest run kept as is
+
+        marker (:obj:`str`, `optional`, defaults to :obj:`⟳`):
+            The character around the count tokens. Must be exactly one character, best
+            chosen out of the expected inputs.
+    """
+    def __init__(self, max_run=8, marker="⟳"):
+        pass
+
+    def pre_tokenize(self, pretok):
+        """
+        Pre-tokenize a :class:`~tokenizers.PyPreTokenizedString` in-place
+
+        This method allows to modify a :class:`~tokenizers.PreTokenizedString` to
+        keep track of the pre-tokenization, and leverage the capabilities of the
+        :class:`~tokenizers.PreTokenizedString`. If you just want to see the result of
+        the pre-tokenization of a raw string, you can use
+        :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize_str`
+
+        Args:
+            pretok (:class:`~tokenizers.PreTokenizedString):
+                The pre-tokenized string on which to apply this
+                :class:`~tokenizers.pre_tokenizers.PreTokenizer`
+        """
+        pass
+
+    def pre_tokenize_str(self, sequence):
+        """
+        Pre tokenize the given string
+
+        This method provides a way to visualize the effect of a
+        :class:`~tokenizers.pre_tokenizers.PreTokenizer` but it does not keep track of the
+        alignment, nor does it provide all the capabilities of the
+        :class:`~tokenizers.PreTokenizedString`. If you need some of these, you can use
+        :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize`
+
+        Args:
+            sequence (:obj:`str`):
+                A string to pre-tokeize
+
+        Returns:
+            :obj:`List[Tuple[str, Offsets]]`:
+                A list of tuple with the pre-tokenized parts and their offsets
+        """
+        pass
+
+    def pre_tokenize_str_streaming(self, sequence, chunk_size=1048576):
+        """
+        Pre tokenize the given string chunk by chunk, yielding the pre-tokens as they come
+
+        Like :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize_str`, but only a
+        single chunk of the string is pre-tokenized at once, which bounds the memory used for
+        very long strings, like whole books. Each chunk ends right before some whitespace, so
+        the pre-tokens are the same for the pre-tokenizers splitting on whitespace, except for
+        the words longer than a chunk, which get split. A chunk starting elsewhere than before
+        a space, like before a newline or within a long word, is pre-tokenized as the start of
+        a string: the pre-tokenizers adding a prefix space, like
+        :class:`~tokenizers.pre_tokenizers.ByteLevel`, add one there too.
+
+        Args:
+            sequence (:obj:`str`):
+                A string to pre-tokenize
+
+            chunk_size (:obj:`int`, defaults to :obj:`1048576`):
+                The approximate size of the chunks, in bytes
+
+        Returns:
+            :class:`~tokenizers.pre_tokenizers.PreTokenizeStream`:
+                An iterator over the tuples of the pre-tokenized parts and their offsets in the
+                whole string
+        """
+        pass
+
+class ScriptSplit(PreTokenizer):
+    """
+    This pre-tokenizer splits the text where the Unicode script changes, so that no piece
+    mixes several scripts. This keeps apart the words of different languages written without
+    spaces between them, and stops the strings mixing Latin and Cyrillic homoglyphs from
+    forming merges of their own::
+
+        "pаypal" (with a Cyrillic "а") -> "p", "а", "ypal"
+
+    The combining marks always stay with the character before them.
+
+    Args:
+        granularity (:obj:`str`, `optional`, defaults to :obj:`"writing_system"`):
+            Which scripts are told apart. With :obj:`"script"`, each Unicode script is, while
+            :obj:`"writing_system"` keeps together the scripts used by a single writing
+            system, like Han, Hiragana and Katakana for Japanese
+
+        common (:obj:`str`, `optional`, defaults to :obj:`"merged"`):
+            What to do with the characters of the Common script, like the spaces, the
+            punctuation and the digits. With :obj:`"merged"`, they join the script before them,
+            while with :obj:`"isolated"` each run of them is split on its own
+    """
+    def __init__(self, granularity="writing_system", common="merged"):
+        pass
+
+    def pre_tokenize(self, pretok):
+        """
+        Pre-tokenize a :class:`~tokenizers.PyPreTokenizedString` in-place
+
+        This method allows to modify a :class:`~tokenizers.PreTokenizedString` to
+        keep track of the pre-tokenization, and leverage the capabilities of the
+        :class:`~tokenizers.PreTokenizedString`. If you just want to see the result of
+        the pre-tokenization of a raw string, you can use
+        :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize_str`
+
+        Args:
+            pretok (:class:`~tokenizers.PreTokenizedString):
+                The pre-tokenized string on which to apply this
+                :class:`~tokenizers.pre_tokenizers.PreTokenizer`
+        """
+        pass
+
+    def pre_tokenize_str(self, sequence):
+        """
+        Pre tokenize the given string
+
+        This method provides a way to visualize the effect of a
+        :class:`~tokenizers.pre_tokenizers.PreTokenizer` but it does not keep track of the
+        alignment, nor does it provide all the capabilities of the
+        :class:`~tokenizers.PreTokenizedString`. If you need some of these, you can use
+        :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize`
+
+        Args:
+            sequence (:obj:`str`):
+                A string to pre-tokeize
+
+        Returns:
+            :obj:`List[Tuple[str, Offsets]]`:
+                A list of tuple with the pre-tokenized parts and their offsets
+        """
+        pass
+
+    def pre_tokenize_str_streaming(self, sequence, chunk_size=1048576):
+        """
+        Pre tokenize the given string chunk by chunk, yielding the pre-tokens as they come
+
+        Like :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize_str`, but only a
+        single chunk of the string is pre-tokenized at once, which bounds the memory used for
+        very long strings, like whole books. Each chunk ends right before some whitespace, so
+        the pre-tokens are the same for the pre-tokenizers splitting on whitespace, except for
+        the words longer than a chunk, which get split. A chunk starting elsewhere than before
+        a space, like before a newline or within a long word, is pre-tokenized as the start of
+        a string: the pre-tokenizers adding a prefix space, like
+        :class:`~tokenizers.pre_tokenizers.ByteLevel`, add one there too.
+
+        Args:
+            sequence (:obj:`str`):
+                A string to pre-tokenize
+
+            chunk_size (:obj:`int`, defaults to :obj:`1048576`):
+                The approximate size of the chunks, in bytes
+
+        Returns:
+            :class:`~tokenizers.pre_tokenizers.PreTokenizeStream`:
+                An iterator over the tuples of the pre-tokenized parts and their offsets in the
+                whole string
+        """
+        pass
+
 class Sequence(PreTokenizer):
     """
     This pre-tokenizer composes other pre_tokenizers and applies them in sequence
@@ -463,6 +1113,33 @@ class Sequence(PreTokenizer):
         """
         pass
 
+    def pre_tokenize_str_streaming(self, sequence, chunk_size=1048576):
+        """
+        Pre tokenize the given string chunk by chunk, yielding the pre-tokens as they come
+
+        Like :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize_str`, but only a
+        single chunk of the string is pre-tokenized at once, which bounds the memory used for
+        very long strings, like whole books. Each chunk ends right before some whitespace, so
+        the pre-tokens are the same for the pre-tokenizers splitting on whitespace, except for
+        the words longer than a chunk, which get split. A chunk starting elsewhere than before
+        a space, like before a newline or within a long word, is pre-tokenized as the start of
+        a string: the pre-tokenizers adding a prefix space, like
+        :class:`~tokenizers.pre_tokenizers.ByteLevel`, add one there too.
+
+        Args:
+            sequence (:obj:`str`):
+                A string to pre-tokenize
+
+            chunk_size (:obj:`int`, defaults to :obj:`1048576`):
+                The approximate size of the chunks, in bytes
+
+        Returns:
+            :class:`~tokenizers.pre_tokenizers.PreTokenizeStream`:
+                An iterator over the tuples of the pre-tokenized parts and their offsets in the
+                whole string
+        """
+        pass
+
 class Split(PreTokenizer):
     """
     Split PreTokenizer
@@ -526,6 +1203,33 @@ class Split(PreTokenizer):
         """
         pass
 
+    def pre_tokenize_str_streaming(self, sequence, chunk_size=1048576):
+        """
+        Pre tokenize the given string chunk by chunk, yielding the pre-tokens as they come
+
+        Like :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize_str`, but only a
+        single chunk of the string is pre-tokenized at once, which bounds the memory used for
+        very long strings, like whole books. Each chunk ends right before some whitespace, so
+        the pre-tokens are the same for the pre-tokenizers splitting on whitespace, except for
+        the words longer than a chunk, which get split. A chunk starting elsewhere than before
+        a space, like before a newline or within a long word, is pre-tokenized as the start of
+        a string: the pre-tokenizers adding a prefix space, like
+        :class:`~tokenizers.pre_tokenizers.ByteLevel`, add one there too.
+
+        Args:
+            sequence (:obj:`str`):
+                A string to pre-tokenize
+
+            chunk_size (:obj:`int`, defaults to :obj:`1048576`):
+                The approximate size of the chunks, in bytes
+
+        Returns:
+            :class:`~tokenizers.pre_tokenizers.PreTokenizeStream`:
+                An iterator over the tuples of the pre-tokenized parts and their offsets in the
+                whole string
+        """
+        pass
+
 class UnicodeScripts(PreTokenizer):
     """
     This pre-tokenizer splits on characters that belong to different language family
@@ -573,6 +1277,120 @@ class UnicodeScripts(PreTokenizer):
         """
         pass
 
+    def pre_tokenize_str_streaming(self, sequence, chunk_size=1048576):
+        """
+        Pre tokenize the given string chunk by chunk, yielding the pre-tokens as they come
+
+        Like :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize_str`, but only a
+        single chunk of the string is pre-tokenized at once, which bounds the memory used for
+        very long strings, like whole books. Each chunk ends right before some whitespace, so
+        the pre-tokens are the same for the pre-tokenizers splitting on whitespace, except for
+        the words longer than a chunk, which get split. A chunk starting elsewhere than before
+        a space, like before a newline or within a long word, is pre-tokenized as the start of
+        a string: the pre-tokenizers adding a prefix space, like
+        :class:`~tokenizers.pre_tokenizers.ByteLevel`, add one there too.
+
+        Args:
+            sequence (:obj:`str`):
+                A string to pre-tokenize
+
+            chunk_size (:obj:`int`, defaults to :obj:`1048576`):
+                The approximate size of the chunks, in bytes
+
+        Returns:
+            :class:`~tokenizers.pre_tokenizers.PreTokenizeStream`:
+                An iterator over the tuples of the pre-tokenized parts and their offsets in the
+                whole string
+        """
+        pass
+
+class UnicodeWords(PreTokenizer):
+    """
+    This pre-tokenizer splits on the word boundaries of Unicode (UAX #29), removing the
+    whitespace. The words stay whole across the scripts: the marks stay on their letters,
+    ``"can't"`` and ``"3.14"`` stay one word, and so do the emoji sequences joined with
+    ZWJ. Each punctuation character gets split on its own.
+
+    The scripts written without spaces (Chinese, Japanese, Thai, Lao, Khmer and Myanmar)
+    have no word boundaries in UAX #29, which splits them character by character.
+
+    Args:
+        cjk (:obj:`bool`, `optional`, defaults to :obj:`False`):
+            Whether to keep each run of these scripts whole instead, or split it with the
+            ``segmenter`` when one is set
+
+        segmenter (:obj:`Callable[[str], List[int]]`, `optional`):
+            A dictionary segmenter, receiving a run and returning the character offsets of
+            the boundaries between its words. It is not serialized, and must be set again
+            after loading.
+    """
+    def __init__(self, cjk=False, segmenter=None):
+        pass
+
+    def pre_tokenize(self, pretok):
+        """
+        Pre-tokenize a :class:`~tokenizers.PyPreTokenizedString` in-place
+
+        This method allows to modify a :class:`~tokenizers.PreTokenizedString` to
+        keep track of the pre-tokenization, and leverage the capabilities of the
+        :class:`~tokenizers.PreTokenizedString`. If you just want to see the result of
+        the pre-tokenization of a raw string, you can use
+        :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize_str`
+
+        Args:
+            pretok (:class:`~tokenizers.PreTokenizedString):
+                The pre-tokenized string on which to apply this
+                :class:`~tokenizers.pre_tokenizers.PreTokenizer`
+        """
+        pass
+
+    def pre_tokenize_str(self, sequence):
+        """
+        Pre tokenize the given string
+
+        This method provides a way to visualize the effect of a
+        :class:`~tokenizers.pre_tokenizers.PreTokenizer` but it does not keep track of the
+        alignment, nor does it provide all the capabilities of the
+        :class:`~tokenizers.PreTokenizedString`. If you need some of these, you can use
+        :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize`
+
+        Args:
+            sequence (:obj:`str`):
+                A string to pre-tokeize
+
+        Returns:
+            :obj:`List[Tuple[str, Offsets]]`:
+                A list of tuple with the pre-tokenized parts and their offsets
+        """
+        pass
+
+    def pre_tokenize_str_streaming(self, sequence, chunk_size=1048576):
+        """
+        Pre tokenize the given string chunk by chunk, yielding the pre-tokens as they come
+
+        Like :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize_str`, but only a
+        single chunk of the string is pre-tokenized at once, which bounds the memory used for
+        very long strings, like whole books. Each chunk ends right before some whitespace, so
+        the pre-tokens are the same for the pre-tokenizers splitting on whitespace, except for
+        the words longer than a chunk, which get split. A chunk starting elsewhere than before
+        a space, like before a newline or within a long word, is pre-tokenized as the start of
+        a string: the pre-tokenizers adding a prefix space, like
+        :class:`~tokenizers.pre_tokenizers.ByteLevel`, add one there too.
+
+        Args:
+            sequence (:obj:`str`):
+                A string to pre-tokenize
+
+            chunk_size (:obj:`int`, defaults to :obj:`1048576`):
+                The approximate size of the chunks, in bytes
+
+        Returns:
+            :class:`~tokenizers.pre_tokenizers.PreTokenizeStream`:
+                An iterator over the tuples of the pre-tokenized parts and their offsets in the
+                whole string
+        """
+        pass
+
 class Whitespace(PreTokenizer):
     """
     This pre-tokenizer splits on word boundaries according to the `\w+|[^\w\s]+`
@@ -644,6 +1462,33 @@ class Whitespace(PreTokenizer):
         """
         pass
 
+    def pre_tokenize_str_streaming(self, sequence, chunk_size=1048576):
+        """
+        Pre tokenize the given string chunk by chunk, yielding the pre-tokens as they come
+
+        Like :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize_str`, but only a
+        single chunk of the string is pre-tokenized at once, which bounds the memory used for
+        very long strings, like whole books. Each chunk ends right before some whitespace, so
+        the pre-tokens are the same for the pre-tokenizers splitting on whitespace, except for
+        the words longer than a chunk, which get split. A chunk starting elsewhere than before
+        a space, like before a newline or within a long word, is pre-tokenized as the start of
+        a string: the pre-tokenizers adding a prefix space, like
+        :class:`~tokenizers.pre_tokenizers.ByteLevel`, add one there too.
+
+        Args:
+            sequence (:obj:`str`):
+                A string to pre-tokenize
+
+            chunk_size (:obj:`int`, defaults to :obj:`1048576`):
+                The approximate size of the chunks, in bytes
+
+        Returns:
+            :class:`~tokenizers.pre_tokenizers.PreTokenizeStream`:
+                An iterator over the tuples of the pre-tokenized parts and their offsets in the
+                whole string
+        """
+        pass
+
 class WhitespaceSplit(PreTokenizer):
     """
     This pre-tokenizer simply splits on the whitespace. Works like `.split()`
@@ -687,3 +1532,30 @@ class WhitespaceSplit(PreTokenizer):
                 A list of tuple with the pre-tokenized parts and their offsets
         """
         pass
+
+    def pre_tokenize_str_streaming(self, sequence, chunk_size=1048576):
+        """
+        Pre tokenize the given string chunk by chunk, yielding the pre-tokens as they come
+
+        Like :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize_str`, but only a
+        single chunk of the string is pre-tokenized at once, which bounds the memory used for
+        very long strings, like whole books. Each chunk ends right before some whitespace, so
+        the pre-tokens are the same for the pre-tokenizers splitting on whitespace, except for
+        the words longer than a chunk, which get split. A chunk starting elsewhere than before
+        a space, like before a newline or within a long word, is pre-tokenized as the start of
+        a string: the pre-tokenizers adding a prefix space, like
+        :class:`~tokenizers.pre_tokenizers.ByteLevel`, add one there too.
+
+        Args:
+            sequence (:obj:`str`):
+                A string to pre-tokenize
+
+            chunk_size (:obj:`int`, defaults to :obj:`1048576`):
+                The approximate size of the chunks, in bytes
+
+        Returns:
+            :class:`~tokenizers.pre_tokenizers.PreTokenizeStream`:
+                An iterator over the tuples of the pre-tokenized parts and their offsets in the
+                whole string
+        """
+        pass
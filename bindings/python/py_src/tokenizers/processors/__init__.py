@@ -4,6 +4,7 @@ from .. import processors
 PostProcessor = processors.PostProcessor
 BertProcessing = processors.BertProcessing
 ByteLevel = processors.ByteLevel
+ChatTemplate = processors.ChatTemplate
 RobertaProcessing = processors.RobertaProcessing
 Sequence = processors.Sequence
 TemplateProcessing = processors.TemplateProcessing
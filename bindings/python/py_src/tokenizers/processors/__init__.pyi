@@ -134,6 +134,78 @@ class ByteLevel(PostProcessor):
         """
         pass
 
+class ChatTemplate(PostProcessor):
+    """
+    Provides a way to assemble conversations made of any number of role-tagged turns,
+    each turn being surrounded by the special tokens of its role.
+
+    Conversations are encoded with :meth:`~tokenizers.Tokenizer.encode_chat`, which also
+    returns a loss mask identifying the tokens of the roles marked with ``loss=True``.
+    When used through :meth:`~tokenizers.Tokenizer.encode`, the i-th sequence uses the i-th role.
+
+    Example::
+
+        ChatTemplate(
+            roles=[
+                {"name": "user", "prefix": ["<|user|>"], "suffix": ["</s>"]},
+                {"name": "assistant", "prefix": ["<|assistant|>"], "suffix": ["</s>"], "loss": True},
+            ],
+            special_tokens=[("<|user|>", 1), ("<|assistant|>", 2), ("</s>", 3)],
+        )
+
+    Args:
+        roles (:obj:`List[dict]`):
+            The roles of the conversation. Each dict expects the following keys:
+
+                - "name": :obj:`str` => The name of the role
+                - "prefix": :obj:`List[str]`, `optional` => The special tokens added before each turn
+                - "suffix": :obj:`List[str]`, `optional` => The special tokens added after each turn
+                - "type_id": :obj:`int`, defaults to 0 => The type id of the tokens of each turn
+                - "loss": :obj:`bool`, defaults to :obj:`False` => Whether tokens of this role
+                  (except the prefix) are part of the loss mask
+
+        special_tokens (:obj:`Tokens`):
+            The list of special tokens used by the roles, with the same format as
+            :class:`~tokenizers.processors.TemplateProcessing`
+
+        prefix (:obj:`List[str]`, `optional`):
+            The special tokens added once, at the beginning of the conversation
+    """
+    def __init__(self, roles, special_tokens, prefix=None):
+        pass
+
+    def num_special_tokens_to_add(self, is_pair):
+        """
+        Return the number of special tokens that would be added for single/pair sentences.
+
+        Args:
+            is_pair (:obj:`bool`):
+                Whether the input would be a pair of sequences
+
+        Returns:
+            :obj:`int`: The number of tokens to add
+        """
+        pass
+
+    def process(self, encoding, pair=None, add_special_tokens=True):
+        """
+        Post-process the given encodings, generating the final one
+
+        Args:
+            encoding (:class:`~tokenizers.Encoding`):
+                The encoding for the first sequence
+
+            pair (:class:`~tokenizers.Encoding`, `optional`):
+                The encoding for the pair sequence
+
+            add_special_tokens (:obj:`bool`):
+                Whether to add the special tokens
+
+        Return:
+            :class:`~tokenizers.Encoding`: The final encoding
+        """
+        pass
+
 class RobertaProcessing(PostProcessor):
     """
     This post-processor takes care of adding the special tokens needed by
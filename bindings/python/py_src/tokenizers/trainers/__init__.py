@@ -3,6 +3,7 @@ from .. import trainers
 
 Trainer = trainers.Trainer
 BpeTrainer = trainers.BpeTrainer
+MaxMatchTrainer = trainers.MaxMatchTrainer
 UnigramTrainer = trainers.UnigramTrainer
 WordLevelTrainer = trainers.WordLevelTrainer
 WordPieceTrainer = trainers.WordPieceTrainer
@@ -6,6 +6,23 @@ class Trainer:
     This class is not supposed to be instantiated directly. Instead, any implementation of a
     Trainer will return an instance of this class when instantiated.
     """
+    def set_progress_callback(self, callback=None):
+        """
+        Set a callback reporting the progress of the training
+
+        The callback replaces the progress bar. It gets called at the end of each phase of
+        the training, and after each step of the iterative phases (merges, EM iterations).
+        If the callback raises an exception, the training stops and raises it.
+
+        Args:
+            callback (:obj:`Callable[[Dict], Optional[bool]]`, `optional`):
+                Called with a dict containing the ``phase``, ``iteration``, ``total``,
+                ``vocab_size`` and ``loss`` of the training. Returning :obj:`False` stops the
+                training early, except for the trainers learning everything in a single phase, like
+                the :class:`~tokenizers.trainers.WordLevelTrainer`, whose training fails instead.
+                :obj:`None` removes any previously set callback
+        """
+        pass
 
 class BpeTrainer(Trainer):
     """
@@ -44,6 +61,46 @@ class BpeTrainer(Trainer):
             This can help with reducing polluting your vocabulary with
             highly repetitive tokens like `======` for wikipedia
 
+        max_memory (:obj:`int`, `optional`):
+            A hint, in bytes, of the memory the word counts may use while feeding the trainer.
+            Above it, the counts get spilled to disk and merged back once all the data has
+            been seen. The training itself still holds all the counts in memory
+
+        script_weights (:obj:`Dict[str, float]`, `optional`):
+            The weight of the words of each Unicode script in the counts of the pairs, like
+            :obj:`{"Latin": 0.5}` to make the merges that only help English look half as
+            frequent as they are, and balance the compression of a multilingual vocabulary.
+            The script of a word is the one of most of its characters, ignoring the digits
+            and punctuation, and decoding the byte-level words first. The names must be those
+            of the Unicode scripts. The words of the scripts without weight keep their counts
+
+        lexicographic_ties (:obj:`bool`, `optional`, defaults to :obj:`False`):
+            Whether to merge the pairs with the same count in the lexicographic order of their
+            tokens, instead of an order that may differ between platforms or versions, so that
+            the same corpus always gives the same tokenizer
+
+    """
+
+class MaxMatchTrainer(Trainer):
+    """
+    Trainer capable of training a MaxMatch model
+
+    The vocabulary is made of the special tokens, followed by the characters and then the
+    words seen at least :obj:`min_frequency` times, the most frequent first.
+
+    Args:
+        vocab_size (:obj:`int`, `optional`):
+            The size of the final vocabulary, including all tokens and alphabet.
+
+        min_frequency (:obj:`int`, `optional`):
+            The minimum frequency a character or a word should have to be part of the
+            vocabulary.
+
+        show_progress (:obj:`bool`, `optional`):
+            Whether to show progress bars while training.
+
+        special_tokens (:obj:`List[Union[str, AddedToken]]`):
+            A list of special tokens the model should know of.
     """
 
 class UnigramTrainer(Trainer):
@@ -54,6 +111,9 @@ class UnigramTrainer(Trainer):
         vocab_size (:obj:`int`):
             The size of the final vocabulary, including all tokens and alphabet.
 
+        min_frequency (:obj:`int`):
+            The minimum frequency a word should have in order to be used for training.
+
         show_progress (:obj:`bool`):
             Whether to show progress bars while training.
 
@@ -83,6 +143,7 @@ class UnigramTrainer(Trainer):
     def __init__(
         self,
         vocab_size=8000,
+        min_frequency=0,
         show_progress=True,
         special_tokens=[],
         shrinking_factor=0.75,
@@ -9,11 +9,12 @@ use pyo3::types::*;
 use serde::de::Error;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use tk::decoders::bpe::BPEDecoder;
-use tk::decoders::byte_fallback::ByteFallback;
+use tk::decoders::byte_fallback::{ByteFallback, InvalidUtf8};
 use tk::decoders::byte_level::ByteLevel;
 use tk::decoders::ctc::CTC;
 use tk::decoders::fuse::Fuse;
 use tk::decoders::metaspace::{Metaspace, PrependScheme};
+use tk::decoders::run_length::RunLength;
 use tk::decoders::sequence::Sequence;
 use tk::decoders::strip::Strip;
 use tk::decoders::wordpiece::WordPiece;
@@ -85,6 +86,10 @@ impl PyDecoder {
                     .into_pyobject(py)?
                     .into_any()
                     .into(),
+                DecoderWrapper::RunLength(_) => Py::new(py, (PyRunLengthDec {}, base))?
+                    .into_pyobject(py)?
+                    .into_any()
+                    .into(),
             },
         })
     }
@@ -94,6 +99,14 @@ impl Decoder for PyDecoder {
     fn decode_chain(&self, tokens: Vec<String>) -> tk::Result<Vec<String>> {
         self.decoder.decode_chain(tokens)
     }
+
+    fn decode_bytes(&self, tokens: Vec<String>) -> tk::Result<Vec<u8>> {
+        self.decoder.decode_bytes(tokens)
+    }
+
+    fn decode_preserving_whitespace(&self, tokens: Vec<String>) -> tk::Result<String> {
+        self.decoder.decode_preserving_whitespace(tokens)
+    }
 }
 
 #[pymethods]
@@ -211,10 +224,11 @@ impl PyReplaceDec {
     #[new]
     #[pyo3(text_signature = "(self, pattern, content)")]
     fn new(pattern: PyPattern, content: String) -> PyResult<(Self, PyDecoder)> {
-        Ok((
-            PyReplaceDec {},
-            ToPyResult(Replace::new(pattern, content)).into_py()?.into(),
-        ))
+        let replace = match pattern.regex() {
+            Some(regex) => Replace::from_regex(regex, content),
+            None => ToPyResult(Replace::new(pattern, content)).into_py()?,
+        };
+        Ok((PyReplaceDec {}, replace.into()))
     }
 }
 
@@ -258,19 +272,52 @@ impl PyWordPieceDec {
     }
 }
 
+fn invalid_utf8_from_string(string: String) -> PyResult<InvalidUtf8> {
+    let policy = match string.as_str() {
+        "replace" => InvalidUtf8::Replace,
+        "ignore" => InvalidUtf8::Ignore,
+        "strict" => InvalidUtf8::Strict,
+        _ => {
+            return Err(exceptions::PyValueError::new_err(format!(
+                "{string} is an unknown variant, should be one of ['replace', 'ignore', 'strict']"
+            )));
+        }
+    };
+    Ok(policy)
+}
+
 /// ByteFallback Decoder
 /// ByteFallback is a simple trick which converts tokens looking like `<0x61>`
 /// to pure bytes, and attempts to make them into a string. If the tokens
 /// cannot be decoded you will get � instead for each inconvertible byte token
 ///
+/// Args:
+///     invalid_utf8 (:obj:`str`, `optional`, defaults to :obj:`"replace"`):
+///         What to do with the byte tokens that don't form valid UTF-8.
+///         Choices: "replace" (one � per byte), "ignore" (drop the bytes), "strict" (raise
+///         an error). :meth:`~tokenizers.Tokenizer.decode_bytes` can be used to get the raw bytes
 #[pyclass(extends=PyDecoder, module = "tokenizers.decoders", name = "ByteFallback")]
 pub struct PyByteFallbackDec {}
 #[pymethods]
 impl PyByteFallbackDec {
+    #[getter]
+    fn get_invalid_utf8(self_: PyRef<Self>) -> String {
+        getter!(self_, ByteFallback, get_invalid_utf8()).to_string()
+    }
+
+    #[setter]
+    fn set_invalid_utf8(self_: PyRef<Self>, invalid_utf8: String) -> PyResult<()> {
+        let policy = invalid_utf8_from_string(invalid_utf8)?;
+        setter!(self_, ByteFallback, @set_invalid_utf8, policy);
+        Ok(())
+    }
+
     #[new]
-    #[pyo3(signature = (), text_signature = "(self)")]
-    fn new() -> (Self, PyDecoder) {
-        (PyByteFallbackDec {}, ByteFallback::new().into())
+    #[pyo3(signature = (invalid_utf8 = String::from("replace")), text_signature = "(self, invalid_utf8=\"replace\")")]
+    fn new(invalid_utf8: String) -> PyResult<(Self, PyDecoder)> {
+        let mut decoder = ByteFallback::new();
+        decoder.set_invalid_utf8(invalid_utf8_from_string(invalid_utf8)?);
+        Ok((PyByteFallbackDec {}, decoder.into()))
     }
 }
 
@@ -404,6 +451,11 @@ impl PyMetaspaceDec {
 ///     suffix (:obj:`str`, `optional`, defaults to :obj:`</w>`):
 ///         The suffix that was used to characterize an end-of-word. This suffix will
 ///         be replaced by whitespaces during the decoding
+///
+///     prefix (:obj:`str`, `optional`):
+///         The prefix that was used to characterize the continuing subwords, if any. This
+///         prefix will be removed, and a whitespace added before the other subwords. With an
+///         empty :obj:`suffix`, only the prefix is used
 #[pyclass(extends=PyDecoder, module = "tokenizers.decoders", name = "BPEDecoder")]
 pub struct PyBPEDecoder {}
 #[pymethods]
@@ -418,10 +470,65 @@ impl PyBPEDecoder {
         setter!(self_, BPE, suffix, suffix);
     }
 
+    #[getter]
+    fn get_prefix(self_: PyRef<Self>) -> Option<String> {
+        getter!(self_, BPE, prefix.clone())
+    }
+
+    #[setter]
+    fn set_prefix(self_: PyRef<Self>, prefix: Option<String>) {
+        setter!(self_, BPE, prefix, prefix);
+    }
+
+    #[new]
+    #[pyo3(signature = (suffix = String::from("</w>"), prefix = None), text_signature = "(self, suffix=\"</w>\", prefix=None)")]
+    fn new(suffix: String, prefix: Option<String>) -> (Self, PyDecoder) {
+        let mut decoder = BPEDecoder::new(suffix);
+        decoder.prefix = prefix;
+        (PyBPEDecoder {}, decoder.into())
+    }
+}
+
+/// RunLength Decoder
+///
+/// This decoder is to be used in tandem with the
+/// :class:`~tokenizers.pre_tokenizers.RunLength` pre-tokenizer, and expands the count tokens
+/// back to the runs of characters they replaced.
+///
+/// Args:
+///     max_run (:obj:`int`, `optional`, defaults to :obj:`8`):
+///         The length of the longest run kept as is by the pre-tokenizer
+///
+///     marker (:obj:`str`, `optional`, defaults to :obj:`⟳`):
+///         The character around the count tokens. Must be exactly one character.
+#[pyclass(extends=PyDecoder, module = "tokenizers.decoders", name = "RunLength")]
+pub struct PyRunLengthDec {}
+#[pymethods]
+impl PyRunLengthDec {
+    #[getter]
+    fn get_max_run(self_: PyRef<Self>) -> usize {
+        getter!(self_, RunLength, max_run)
+    }
+
+    #[setter]
+    fn set_max_run(self_: PyRef<Self>, max_run: usize) {
+        setter!(self_, RunLength, max_run, max_run);
+    }
+
+    #[getter]
+    fn get_marker(self_: PyRef<Self>) -> String {
+        getter!(self_, RunLength, marker).to_string()
+    }
+
+    #[setter]
+    fn set_marker(self_: PyRef<Self>, marker: char) {
+        setter!(self_, RunLength, marker, marker);
+    }
+
     #[new]
-    #[pyo3(signature = (suffix = String::from("</w>")), text_signature = "(self, suffix=\"</w>\")")]
-    fn new(suffix: String) -> (Self, PyDecoder) {
-        (PyBPEDecoder {}, BPEDecoder::new(suffix).into())
+    #[pyo3(signature = (max_run = 8, marker = '⟳'), text_signature = "(self, max_run=8, marker=\"⟳\")")]
+    fn new(max_run: usize, marker: char) -> (Self, PyDecoder) {
+        (PyRunLengthDec {}, RunLength::new(max_run, marker).into())
     }
 }
 
@@ -599,6 +706,24 @@ impl Decoder for PyDecoderWrapper {
             PyDecoderWrapper::Custom(inner) => inner.read().unwrap().decode_chain(tokens),
         }
     }
+
+    fn decode_bytes(&self, tokens: Vec<String>) -> tk::Result<Vec<u8>> {
+        match self {
+            PyDecoderWrapper::Wrapped(inner) => inner.read().unwrap().decode_bytes(tokens),
+            PyDecoderWrapper::Custom(inner) => inner.read().unwrap().decode_bytes(tokens),
+        }
+    }
+
+    fn decode_preserving_whitespace(&self, tokens: Vec<String>) -> tk::Result<String> {
+        match self {
+            PyDecoderWrapper::Wrapped(inner) => {
+                inner.read().unwrap().decode_preserving_whitespace(tokens)
+            }
+            PyDecoderWrapper::Custom(inner) => {
+                inner.read().unwrap().decode_preserving_whitespace(tokens)
+            }
+        }
+    }
 }
 
 /// Decoders Module
@@ -615,6 +740,7 @@ pub fn decoders(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PyBPEDecoder>()?;
     m.add_class::<PyCTCDecoder>()?;
     m.add_class::<PySequenceDecoder>()?;
+    m.add_class::<PyRunLengthDec>()?;
     m.add_class::<PyDecodeStream>()?;
     Ok(())
 }
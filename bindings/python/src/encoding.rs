@@ -171,6 +171,24 @@ impl PyEncoding {
         self.encoding.get_word_ids().to_vec()
     }
 
+    /// The metadata of the word associated to each token
+    ///
+    /// They are attached with :meth:`~tokenizers.Tokenizer.encode_with_word_metadata`, like the
+    /// timestamps of the words in an audio, and follow the tokens through the truncation and
+    /// the padding. The tokens without any word, or without any metadata, get :obj:`None`
+    ///
+    /// Returns:
+    ///     A :obj:`List` of :obj:`Optional[List[float]]`: The metadata of the word of each token
+    #[getter]
+    fn get_word_metadata(&self) -> Vec<Option<Vec<f64>>> {
+        let metadata = self.encoding.get_word_metadata();
+        if metadata.is_empty() {
+            vec![None; self.encoding.len()]
+        } else {
+            metadata.to_vec()
+        }
+    }
+
     /// The generated sequence indices.
     ///
     /// They represent the index of the input sequence associated to each token.
@@ -338,6 +356,10 @@ impl PyEncoding {
 
     /// Get the token that contains the char at the given position in the input sequence.
     ///
+    /// The chars inserted by the normalizer share the offsets of the char they were inserted
+    /// next to, so when several tokens contain the given char, the first one is returned.
+    /// The chars removed by the normalizer are not contained by any token.
+    ///
     /// Args:
     ///     char_pos (:obj:`int`):
     ///         The position of a char in the input string
@@ -352,6 +374,27 @@ impl PyEncoding {
         self.encoding.char_to_token(char_pos, sequence_index)
     }
 
+    /// Get the encoded tokens overlapping the given span of chars in the input sequence.
+    ///
+    /// A token cut by the span is included entirely, just like the tokens made of the chars
+    /// inserted by the normalizer next to a char of the span.
+    ///
+    /// Args:
+    ///     span (:obj:`Tuple[int, int]`):
+    ///         The span of chars in the input string: :obj:`(start, end)`
+    ///     sequence_index (:obj:`int`, defaults to :obj:`0`):
+    ///         The index of the sequence that contains the target span
+    ///
+    /// Returns:
+    ///     :obj:`Tuple[int, int]`: The range of tokens: :obj:`(first, last + 1)`, or :obj:`None`
+    ///     if no token overlaps the span, which happens with an empty span, or when all its chars
+    ///     have been removed by the normalizer
+    #[pyo3(signature = (span, sequence_index = 0))]
+    #[pyo3(text_signature = "(self, span, sequence_index=0)")]
+    fn span_to_tokens(&self, span: Offsets, sequence_index: usize) -> Option<(usize, usize)> {
+        self.encoding.span_to_tokens(span, sequence_index)
+    }
+
     /// Get the word that contains the char at the given position in the input sequence.
     ///
     /// Args:
@@ -368,6 +411,35 @@ impl PyEncoding {
         self.encoding.char_to_word(char_pos, sequence_index)
     }
 
+    /// Replace the tokens overlapping each of the given spans by the tokens of the placeholder
+    ///
+    /// This removes some personal information, like names or emails, while keeping the
+    /// alignment with the original document. The tokens of each placeholder get the offsets of
+    /// all the tokens they replace, and form a single word, the following words of the
+    /// sequence being renumbered. The spans that overlap the same tokens get a single
+    /// placeholder. Everything else is identical, including the overflowing encodings, which
+    /// get their own placeholders.
+    ///
+    /// Args:
+    ///     spans (:obj:`List[Tuple[int, int]]`):
+    ///         The spans of chars to redact in the input string
+    ///     placeholder (:class:`~tokenizers.Encoding`):
+    ///         The encoding of the placeholder, like :obj:`tokenizer.encode("[REDACTED]",
+    ///         add_special_tokens=False)`
+    ///     sequence_index (:obj:`int`, defaults to :obj:`0`):
+    ///         The index of the sequence that contains the spans
+    #[pyo3(signature = (spans, placeholder, sequence_index = 0))]
+    #[pyo3(text_signature = "(self, spans, placeholder, sequence_index=0)")]
+    fn redact(
+        &mut self,
+        spans: Vec<Offsets>,
+        placeholder: PyRef<PyEncoding>,
+        sequence_index: usize,
+    ) {
+        self.encoding
+            .redact(&spans, &placeholder.encoding, sequence_index);
+    }
+
     /// Pad the :class:`~tokenizers.Encoding` at the given length
     ///
     /// Args:
@@ -453,3 +525,120 @@ impl PyEncoding {
         Ok(())
     }
 }
+
+#[derive(FromPyObject)]
+enum PyTokenMatcher {
+    #[pyo3(annotation = "int")]
+    Id(u32),
+    #[pyo3(annotation = "Tuple[int, int]")]
+    Gap((usize, usize)),
+    #[pyo3(annotation = "List[int]")]
+    OneOf(Vec<u32>),
+    #[pyo3(annotation = "str")]
+    Class(String),
+}
+
+impl TryFrom<PyTokenMatcher> for tk::TokenMatcher {
+    type Error = PyErr;
+
+    fn try_from(matcher: PyTokenMatcher) -> PyResult<Self> {
+        Ok(match matcher {
+            PyTokenMatcher::Id(id) => Self::Id(id),
+            PyTokenMatcher::Gap((min, max)) => Self::Gap { min, max },
+            PyTokenMatcher::OneOf(ids) => Self::OneOf(ids),
+            PyTokenMatcher::Class(class) => match class.as_str() {
+                "any" => Self::Any,
+                "special" => Self::Special,
+                _ => {
+                    return Err(PyError(format!(
+                        "Unknown token class `{class}`, expected `any` or `special`"
+                    ))
+                    .into_pyerr::<exceptions::PyValueError>())
+                }
+            },
+        })
+    }
+}
+
+/// A pattern of token ids, to find in the :class:`~tokenizers.Encoding` without decoding it
+///
+/// Each element of the pattern matches some tokens:
+///
+/// - an :obj:`int` matches the token with this id
+/// - a :obj:`List[int]` matches any token with one of these ids, like the ids of some
+///   added tokens, from :meth:`~tokenizers.Tokenizer.get_added_tokens_decoder`
+/// - :obj:`"any"` matches any token, and :obj:`"special"` any special token
+/// - a :obj:`Tuple[int, int]` matches between ``min`` and ``max`` tokens, as few as
+///   possible
+///
+/// Each token of a match belongs to the same input sequence, or was added by the
+/// post-processor, and the padding tokens never match.
+///
+/// Args:
+///     pattern (:obj:`List[Union[int, List[int], str, Tuple[int, int]]]`):
+///         The elements to match one after the other
+#[pyclass(module = "tokenizers", name = "TokenPattern")]
+pub struct PyTokenPattern {
+    pattern: tk::TokenPattern,
+}
+
+impl PyTokenPattern {
+    fn to_match<'py>(py: Python<'py>, found: tk::TokenMatch) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("tokens", (found.tokens.start, found.tokens.end))?;
+        dict.set_item("sequence", found.sequence)?;
+        dict.set_item("offsets", found.offsets)?;
+        Ok(dict)
+    }
+}
+
+#[pymethods]
+impl PyTokenPattern {
+    #[new]
+    #[pyo3(text_signature = "(self, pattern)")]
+    fn new(pattern: Vec<PyTokenMatcher>) -> PyResult<Self> {
+        let matchers = pattern
+            .into_iter()
+            .map(tk::TokenMatcher::try_from)
+            .collect::<PyResult<Vec<_>>>()?;
+        let pattern = tk::TokenPattern::new(matchers)
+            .map_err(|e| PyError(e.to_string()).into_pyerr::<exceptions::PyValueError>())?;
+        Ok(Self { pattern })
+    }
+
+    /// Find all the occurrences of the pattern, from left to right and without overlaps
+    ///
+    /// Args:
+    ///     encoding (:class:`~tokenizers.Encoding`):
+    ///         The encoding to scan. Its overflowing encodings are ignored
+    ///
+    /// Returns:
+    ///     :obj:`List[Dict]`: For each match, the range of its ``tokens``, the index of their
+    ///     input ``sequence``, and the ``offsets`` of the chars they cover in this sequence.
+    ///     The last two are :obj:`None` when all the tokens were added by the post-processor
+    #[pyo3(text_signature = "(self, encoding)")]
+    fn find_all<'py>(
+        &self,
+        py: Python<'py>,
+        encoding: PyRef<PyEncoding>,
+    ) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        self.pattern
+            .find_all(&encoding.encoding)
+            .into_iter()
+            .map(|found| Self::to_match(py, found))
+            .collect()
+    }
+
+    /// Whether the pattern occurs in the given :class:`~tokenizers.Encoding`
+    ///
+    /// Args:
+    ///     encoding (:class:`~tokenizers.Encoding`):
+    ///         The encoding to scan. Its overflowing encodings are ignored
+    ///
+    /// Returns:
+    ///     :obj:`bool`
+    #[pyo3(text_signature = "(self, encoding)")]
+    fn is_match(&self, encoding: PyRef<PyEncoding>) -> bool {
+        self.pattern.is_match(&encoding.encoding)
+    }
+}
@@ -1,9 +1,10 @@
+use pyo3::create_exception;
 use pyo3::exceptions;
 use pyo3::prelude::*;
 use pyo3::type_object::PyTypeInfo;
 use std::ffi::CString;
 use std::fmt::{Display, Formatter, Result as FmtResult};
-use tokenizers::tokenizer::Result;
+use tokenizers::tokenizer::{LimitError, Result};
 
 #[derive(Debug)]
 pub struct PyError(pub String);
@@ -23,10 +24,46 @@ impl Display for PyError {
 }
 impl std::error::Error for PyError {}
 
+create_exception!(
+    tokenizers,
+    PipelineError,
+    exceptions::PyValueError,
+    "Raised when the components of a Tokenizer can't work together"
+);
+create_exception!(
+    tokenizers,
+    MissingUnkTokenError,
+    PipelineError,
+    "Raised when the model can't represent the unknown token it needs"
+);
+create_exception!(
+    tokenizers,
+    IncompatibleDecoderError,
+    PipelineError,
+    "Raised when the decoder can't revert what the other components produce"
+);
+
+create_exception!(
+    tokenizers,
+    LimitExceededError,
+    exceptions::PyValueError,
+    "Raised when an encoding goes over one of the limits set with `Tokenizer.configure_limits`"
+);
+
 pub struct ToPyResult<T>(pub Result<T>);
 impl<T> From<ToPyResult<T>> for PyResult<T> {
     fn from(v: ToPyResult<T>) -> Self {
-        v.0.map_err(|e| exceptions::PyException::new_err(format!("{e}")))
+        v.0.map_err(|e| {
+            if e.downcast_ref::<LimitError>().is_some() {
+                LimitExceededError::new_err(format!("{e}"))
+            } else {
+                // Raise the Python errors again, like those of the callbacks
+                match e.downcast::<PyErr>() {
+                    Ok(err) => *err,
+                    Err(e) => exceptions::PyException::new_err(format!("{e}")),
+                }
+            }
+        })
     }
 }
 impl<T> ToPyResult<T> {
@@ -10,6 +10,7 @@ mod encoding;
 mod error;
 mod models;
 mod normalizers;
+mod offsets;
 mod pre_tokenizers;
 mod processors;
 mod token;
@@ -59,18 +60,36 @@ pub fn tokenizers(m: &Bound<'_, PyModule>) -> PyResult<()> {
     }
 
     m.add_class::<tokenizer::PyTokenizer>()?;
+    m.add_class::<tokenizer::PyShadowTokenizer>()?;
     m.add_class::<tokenizer::PyAddedToken>()?;
+    m.add_class::<tokenizer::PyTruncationParams>()?;
+    m.add_class::<tokenizer::PyPaddingParams>()?;
     m.add_class::<token::PyToken>()?;
     m.add_class::<encoding::PyEncoding>()?;
+    m.add_class::<encoding::PyTokenPattern>()?;
     m.add_class::<utils::PyRegex>()?;
     m.add_class::<utils::PyNormalizedString>()?;
     m.add_class::<utils::PyPreTokenizedString>()?;
+    m.add("PipelineError", m.py().get_type::<error::PipelineError>())?;
+    m.add(
+        "MissingUnkTokenError",
+        m.py().get_type::<error::MissingUnkTokenError>(),
+    )?;
+    m.add(
+        "IncompatibleDecoderError",
+        m.py().get_type::<error::IncompatibleDecoderError>(),
+    )?;
+    m.add(
+        "LimitExceededError",
+        m.py().get_type::<error::LimitExceededError>(),
+    )?;
     m.add_wrapped(wrap_pymodule!(models::models))?;
     m.add_wrapped(wrap_pymodule!(pre_tokenizers::pre_tokenizers))?;
     m.add_wrapped(wrap_pymodule!(decoders::decoders))?;
     m.add_wrapped(wrap_pymodule!(processors::processors))?;
     m.add_wrapped(wrap_pymodule!(normalizers::normalizers))?;
     m.add_wrapped(wrap_pymodule!(trainers::trainers))?;
+    m.add_wrapped(wrap_pymodule!(offsets::offsets))?;
     m.add("__version__", env!("CARGO_PKG_VERSION"))?;
     Ok(())
 }
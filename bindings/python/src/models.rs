@@ -10,11 +10,14 @@ use pyo3::prelude::*;
 use pyo3::types::*;
 use serde::{Deserialize, Serialize};
 use tk::models::bpe::{BpeBuilder, Merges, BPE};
+use tk::models::files::FileNaming;
+use tk::models::hybrid::Hybrid;
+use tk::models::maxmatch::MaxMatch;
 use tk::models::unigram::Unigram;
 use tk::models::wordlevel::WordLevel;
 use tk::models::wordpiece::{WordPiece, WordPieceBuilder};
 use tk::models::ModelWrapper;
-use tk::{Model, Token};
+use tk::{Model, NBestModel, SamplingModel, Token};
 use tokenizers as tk;
 
 use super::error::{deprecation_warning, ToPyResult};
@@ -52,10 +55,33 @@ impl PyModel {
                 .into_pyobject(py)?
                 .into_any()
                 .into(),
+            ModelWrapper::MaxMatch(_) => Py::new(py, (PyMaxMatch {}, base))?
+                .into_pyobject(py)?
+                .into_any()
+                .into(),
+            ModelWrapper::Hybrid(_) => Py::new(py, (PyHybrid {}, base))?
+                .into_pyobject(py)?
+                .into_any()
+                .into(),
         })
     }
 }
 
+impl SamplingModel for PyModel {
+    fn tokenize_sampled(&self, sequence: &str, strength: f64) -> tk::Result<Vec<Token>> {
+        self.model
+            .read()
+            .unwrap()
+            .tokenize_sampled(sequence, strength)
+    }
+}
+
+impl NBestModel for PyModel {
+    fn tokenize_nbest(&self, sequence: &str, n: usize) -> tk::Result<Vec<(Vec<Token>, f64)>> {
+        self.model.read().unwrap().tokenize_nbest(sequence, n)
+    }
+}
+
 impl Model for PyModel {
     type Trainer = PyTrainer;
 
@@ -79,6 +105,10 @@ impl Model for PyModel {
         self.model.read().unwrap().get_vocab_size()
     }
 
+    fn token_ids_with_prefix(&self, prefix: &[u8]) -> Vec<u32> {
+        self.model.read().unwrap().token_ids_with_prefix(prefix)
+    }
+
     fn save(&self, folder: &Path, name: Option<&str>) -> tk::Result<Vec<PathBuf>> {
         self.model.read().unwrap().save(folder, name)
     }
@@ -188,15 +218,28 @@ impl PyModel {
     ///     prefix (:obj:`str`, `optional`):
     ///         An optional prefix, used to prefix each file name
     ///
+    ///     suffix (:obj:`str`, `optional`):
+    ///         An optional suffix, added to each file name before its extension, like
+    ///         :obj:`vocab-uncased.txt`
+    ///
+    ///     filenames (:obj:`Dict[str, str]`, `optional`):
+    ///         The paths of some files, replacing their default names like :obj:`merges.txt`.
+    ///         The relative paths are relative to :obj:`folder`
+    ///
     /// Returns:
     ///     :obj:`List[str]`: The list of saved files
-    #[pyo3(signature = (folder, prefix=None, name=None), text_signature = "(self, folder, prefix)")]
+    #[pyo3(
+        signature = (folder, prefix=None, name=None, suffix=None, filenames=None),
+        text_signature = "(self, folder, prefix=None, suffix=None, filenames=None)"
+    )]
     fn save<'a>(
         &self,
         py: Python<'_>,
         folder: &str,
         mut prefix: Option<&'a str>,
         name: Option<&'a str>,
+        suffix: Option<String>,
+        filenames: Option<HashMap<String, PathBuf>>,
     ) -> PyResult<Vec<String>> {
         if name.is_some() {
             deprecation_warning(
@@ -209,8 +252,18 @@ impl PyModel {
             }
         }
 
-        let saved: PyResult<Vec<_>> =
-            ToPyResult(self.model.read().unwrap().save(Path::new(folder), prefix)).into();
+        let naming = FileNaming {
+            prefix: prefix.map(str::to_owned),
+            suffix,
+            renamed: filenames.unwrap_or_default(),
+        };
+        let saved: PyResult<Vec<_>> = ToPyResult(
+            self.model
+                .read()
+                .unwrap()
+                .save_with(Path::new(folder), &naming),
+        )
+        .into();
 
         Ok(saved?
             .into_iter()
@@ -218,6 +271,24 @@ impl PyModel {
             .collect())
     }
 
+    /// Get the content of the files written by :meth:`~tokenizers.models.Model.save`
+    ///
+    /// This allows embedding them elsewhere, like in a wheel or a container, without going
+    /// through the filesystem.
+    ///
+    /// Returns:
+    ///     :obj:`Dict[str, bytes]`: The content of each file, by its default name like
+    ///     :obj:`vocab.json`
+    #[pyo3(text_signature = "(self)")]
+    fn files<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let files = ToPyResult(self.model.read().unwrap().files()).into_py()?;
+        let dict = PyDict::new(py);
+        for file in files {
+            dict.set_item(file.name, PyBytes::new(py, &file.content))?;
+        }
+        Ok(dict)
+    }
+
     /// Get the associated :class:`~tokenizers.trainers.Trainer`
     ///
     /// Retrieve the :class:`~tokenizers.trainers.Trainer` associated to this
@@ -502,6 +573,33 @@ impl PyBPE {
         Ok((vocab, merges))
     }
 
+    /// Read the content of a :obj:`vocab.json` and a :obj:`merges.txt` files
+    ///
+    /// Like :meth:`~tokenizers.models.BPE.read_file`, for files that were loaded in memory,
+    /// like the ones given by :meth:`~tokenizers.models.Model.files`.
+    ///
+    /// Args:
+    ///     vocab (:obj:`bytes`):
+    ///         The content of a :obj:`vocab.json` file
+    ///
+    ///     merges (:obj:`bytes`):
+    ///         The content of a :obj:`merges.txt` file
+    ///
+    /// Returns:
+    ///     A :obj:`Tuple` with the vocab and the merges:
+    ///         The vocabulary and merges loaded into memory
+    #[staticmethod]
+    #[pyo3(text_signature = "(vocab, merges)")]
+    fn read_bytes(vocab: &[u8], merges: &[u8]) -> PyResult<(HashMap<String, u32>, Merges)> {
+        let (vocab, merges) = BPE::read_bytes(vocab, merges).map_err(|e| {
+            exceptions::PyException::new_err(format!(
+                "Error while reading vocab & merges files: {e}"
+            ))
+        })?;
+        let vocab = vocab.into_iter().collect();
+        Ok((vocab, merges))
+    }
+
     /// Instantiate a BPE model from the given files.
     ///
     /// This method is roughly equivalent to doing::
@@ -547,6 +645,58 @@ impl PyBPE {
         )
     }
 
+    /// Instantiate a BPE model from a subword-nmt :obj:`codes` file
+    ///
+    /// The vocab is made of the characters found in the merges, each with and without the
+    /// :obj:`</w>` suffix used by subword-nmt, followed by the tokens produced by the merges,
+    /// and :obj:`<unk>` for the other characters. Only the version 0.2 of the format is
+    /// supported.
+    ///
+    /// Args:
+    ///     codes (:obj:`str`):
+    ///         The path to a :obj:`codes` file, as written by :obj:`learn_bpe.py`
+    ///
+    ///     kwargs:
+    ///         The other options of the :class:`~tokenizers.models.BPE`. The
+    ///         :obj:`end_of_word_suffix` defaults to :obj:`</w>`, and the :obj:`unk_token` to
+    ///         :obj:`<unk>`
+    ///
+    /// Returns:
+    ///     :class:`~tokenizers.models.BPE`: An instance of BPE loaded from this file
+    #[classmethod]
+    #[pyo3(signature = (codes, **kwargs))]
+    #[pyo3(text_signature = "(cls, codes, **kwargs)")]
+    fn from_subword_nmt(
+        _cls: &Bound<'_, PyType>,
+        py: Python,
+        codes: &str,
+        kwargs: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<Py<Self>> {
+        let (vocab, merges) = BPE::read_subword_nmt(codes).map_err(|e| {
+            exceptions::PyException::new_err(format!("Error while reading codes file: {e}"))
+        })?;
+        let kwargs = match kwargs {
+            Some(kwargs) => kwargs.copy()?,
+            None => PyDict::new(py),
+        };
+        if !kwargs.contains("end_of_word_suffix")? {
+            kwargs.set_item("end_of_word_suffix", "</w>")?;
+        }
+        if !kwargs.contains("unk_token")? {
+            kwargs.set_item("unk_token", "<unk>")?;
+        }
+        let vocab = vocab.into_iter().collect();
+        Py::new(
+            py,
+            PyBPE::new(
+                py,
+                Some(PyVocab::Vocab(vocab)),
+                Some(PyMerges::Merges(merges)),
+                Some(&kwargs),
+            )?,
+        )
+    }
+
     /// Clears the internal cache
     #[pyo3(signature = ())]
     #[pyo3(text_signature = "(self)")]
@@ -583,6 +733,9 @@ impl PyBPE {
 ///
 ///     max_input_chars_per_word (:obj:`int`, `optional`):
 ///         The maximum number of characters to authorize in a single word.
+///
+///     end_of_word_suffix (:obj:`str`, `optional`):
+///         The suffix attached to the subword units that represent an end of word.
 #[pyclass(extends=PyModel, module = "tokenizers.models", name = "WordPiece")]
 pub struct PyWordPiece {}
 
@@ -604,6 +757,11 @@ impl PyWordPiece {
                     "continuing_subword_prefix" => {
                         builder = builder.continuing_subword_prefix(val.extract()?);
                     }
+                    "end_of_word_suffix" => {
+                        if let Some(suffix) = val.extract()? {
+                            builder = builder.end_of_word_suffix(suffix);
+                        }
+                    }
                     _ => println!("Ignored unknown kwargs option {key}"),
                 }
             }
@@ -645,6 +803,16 @@ impl PyWordPiece {
         );
     }
 
+    #[getter]
+    fn get_end_of_word_suffix(self_: PyRef<Self>) -> Option<String> {
+        getter!(self_, WordPiece, end_of_word_suffix.clone())
+    }
+
+    #[setter]
+    fn set_end_of_word_suffix(self_: PyRef<Self>, end_of_word_suffix: Option<String>) {
+        setter!(self_, WordPiece, end_of_word_suffix, end_of_word_suffix);
+    }
+
     #[getter]
     fn get_max_input_chars_per_word(self_: PyRef<Self>) -> usize {
         getter!(self_, WordPiece, max_input_chars_per_word)
@@ -684,6 +852,26 @@ impl PyWordPiece {
         PyWordPiece::with_builder(builder, kwargs)
     }
 
+    /// Read the content of a :obj:`vocab.txt` file
+    ///
+    /// Like :meth:`~tokenizers.models.WordPiece.read_file`, for a file that was loaded in
+    /// memory, like the ones given by :meth:`~tokenizers.models.Model.files`.
+    ///
+    /// Args:
+    ///     vocab (:obj:`bytes`):
+    ///         The content of a :obj:`vocab.txt` file
+    ///
+    /// Returns:
+    ///     :obj:`Dict[str, int]`: The vocabulary as a :obj:`dict`
+    #[staticmethod]
+    #[pyo3(text_signature = "(vocab)")]
+    fn read_bytes(vocab: &[u8]) -> PyResult<HashMap<String, u32>> {
+        let vocab = WordPiece::read_bytes(vocab).map_err(|e| {
+            exceptions::PyException::new_err(format!("Error while reading WordPiece file: {e}"))
+        })?;
+        Ok(vocab.into_iter().collect())
+    }
+
     /// Read a :obj:`vocab.txt` file
     ///
     /// This method provides a way to read and parse the content of a standard `vocab.txt`
@@ -807,6 +995,26 @@ impl PyWordLevel {
         ))
     }
 
+    /// Read the content of a :obj:`vocab.json` file
+    ///
+    /// Like :meth:`~tokenizers.models.WordLevel.read_file`, for a file that was loaded in
+    /// memory, like the ones given by :meth:`~tokenizers.models.Model.files`.
+    ///
+    /// Args:
+    ///     vocab (:obj:`bytes`):
+    ///         The content of a :obj:`vocab.json` file
+    ///
+    /// Returns:
+    ///     :obj:`Dict[str, int]`: The vocabulary as a :obj:`dict`
+    #[staticmethod]
+    #[pyo3(text_signature = "(vocab)")]
+    fn read_bytes(vocab: &[u8]) -> PyResult<HashMap<String, u32>> {
+        let vocab = WordLevel::read_bytes(vocab).map_err(|e| {
+            exceptions::PyException::new_err(format!("Error while reading WordLevel file: {e}"))
+        })?;
+        Ok(vocab.into_iter().collect())
+    }
+
     /// Read a :obj:`vocab.json`
     ///
     /// This method provides a way to read and parse the content of a vocabulary file,
@@ -866,38 +1074,280 @@ impl PyWordLevel {
     }
 }
 
+/// An implementation of the greedy longest-match (MaxMatch) algorithm
+///
+/// Tokenizes each word from left to right, by taking at each position the longest token
+/// of the vocabulary it starts with. It uses no merges nor scores, which makes it a fast
+/// option for the lexicon-based tokenization, and a simple baseline. Each character
+/// matching no token becomes the unknown token.
+///
+/// Args:
+///     vocab (:obj:`Dict[str, int]`, `optional`):
+///         A dictionary of string keys and their ids :obj:`{"am": 0,...}`
+///
+///     unk_token (:obj:`str`, `optional`):
+///         The unknown token to be used by the model.
+#[pyclass(extends=PyModel, module = "tokenizers.models", name = "MaxMatch")]
+pub struct PyMaxMatch {}
+
+#[pymethods]
+impl PyMaxMatch {
+    #[getter]
+    fn get_unk_token(self_: PyRef<Self>) -> String {
+        getter!(self_, MaxMatch, unk_token.clone())
+    }
+
+    #[setter]
+    fn set_unk_token(self_: PyRef<Self>, unk_token: String) {
+        setter!(self_, MaxMatch, unk_token, unk_token);
+    }
+
+    #[new]
+    #[pyo3(signature = (vocab=None, unk_token = None), text_signature = "(self, vocab, unk_token)")]
+    fn new(
+        vocab: Option<HashMap<String, u32>>,
+        unk_token: Option<String>,
+    ) -> PyResult<(Self, PyModel)> {
+        let mut builder = MaxMatch::builder();
+        if let Some(vocab) = vocab {
+            builder = builder.vocab(vocab.into_iter().collect());
+        }
+        if let Some(unk_token) = unk_token {
+            builder = builder.unk_token(unk_token);
+        }
+
+        Ok((
+            PyMaxMatch {},
+            builder
+                .build()
+                .map_err(|e| exceptions::PyException::new_err(e.to_string()))?
+                .into(),
+        ))
+    }
+
+    /// Read the content of a :obj:`vocab.json` file
+    ///
+    /// Like :meth:`~tokenizers.models.MaxMatch.read_file`, for a file that was loaded in
+    /// memory, like the ones given by :meth:`~tokenizers.models.Model.files`.
+    ///
+    /// Args:
+    ///     vocab (:obj:`bytes`):
+    ///         The content of a :obj:`vocab.json` file
+    ///
+    /// Returns:
+    ///     :obj:`Dict[str, int]`: The vocabulary as a :obj:`dict`
+    #[staticmethod]
+    #[pyo3(text_signature = "(vocab)")]
+    fn read_bytes(vocab: &[u8]) -> PyResult<HashMap<String, u32>> {
+        let vocab = MaxMatch::read_bytes(vocab).map_err(|e| {
+            exceptions::PyException::new_err(format!("Error while reading MaxMatch file: {e}"))
+        })?;
+        Ok(vocab.into_iter().collect())
+    }
+
+    /// Read a :obj:`vocab.json`, in the same format as the one of a
+    /// :class:`~tokenizers.models.WordLevel`
+    ///
+    /// Args:
+    ///     vocab (:obj:`str`):
+    ///         The path to a :obj:`vocab.json` file
+    ///
+    /// Returns:
+    ///     :obj:`Dict[str, int]`: The vocabulary as a :obj:`dict`
+    #[staticmethod]
+    #[pyo3(text_signature = "(vocab)")]
+    fn read_file(vocab: &str) -> PyResult<HashMap<String, u32>> {
+        let vocab = MaxMatch::read_file(vocab).map_err(|e| {
+            exceptions::PyException::new_err(format!("Error while reading MaxMatch file: {e}"))
+        })?;
+        Ok(vocab.into_iter().collect())
+    }
+
+    /// Instantiate a MaxMatch model from the given :obj:`vocab.json` file
+    ///
+    /// Args:
+    ///     vocab (:obj:`str`):
+    ///         The path to a :obj:`vocab.json` file
+    ///
+    /// Returns:
+    ///     :class:`~tokenizers.models.MaxMatch`: An instance of MaxMatch loaded from file
+    #[classmethod]
+    #[pyo3(signature = (vocab, unk_token = None))]
+    #[pyo3(text_signature = "(vocab, unk_token)")]
+    fn from_file(
+        _cls: &Bound<'_, PyType>,
+        py: Python,
+        vocab: &str,
+        unk_token: Option<String>,
+    ) -> PyResult<Py<Self>> {
+        let vocab = Self::read_file(vocab)?;
+        Py::new(py, PyMaxMatch::new(Some(vocab), unk_token)?)
+    }
+}
+
+/// A model looking each word up in a lexicon before falling back to another model
+///
+/// The words found as a whole in the lexicon become a single token, while all the others get
+/// tokenized by the fallback model, usually a subword one. This keeps the exact matches for
+/// some known words, like product references, with subwords for everything else.
+///
+/// Args:
+///     lexicon (:obj:`Dict[str, int]`, `optional`):
+///         The words kept as a whole, with their ids. Each of them must either be unknown to
+///         the fallback model, with an id it doesn't use, or have the same id in both.
+///
+///     fallback (:class:`~tokenizers.models.Model`, `optional`):
+///         The model tokenizing the words missing from the lexicon, an empty
+///         :class:`~tokenizers.models.BPE` by default
+#[pyclass(extends=PyModel, module = "tokenizers.models", name = "Hybrid")]
+pub struct PyHybrid {}
+
+#[pymethods]
+impl PyHybrid {
+    #[new]
+    #[pyo3(signature = (lexicon = None, fallback = None), text_signature = "(self, lexicon, fallback)")]
+    fn new(
+        lexicon: Option<HashMap<String, u32>>,
+        fallback: Option<PyRef<PyModel>>,
+    ) -> PyResult<(Self, PyModel)> {
+        let lexicon = lexicon.unwrap_or_default().into_iter().collect();
+        let fallback = match fallback {
+            Some(fallback) => fallback.model.read().unwrap().clone(),
+            None => BPE::default().into(),
+        };
+        let hybrid = Hybrid::new(lexicon, fallback)
+            .map_err(|e| exceptions::PyValueError::new_err(e.to_string()))?;
+        Ok((PyHybrid {}, hybrid.into()))
+    }
+
+    #[getter]
+    fn get_lexicon(self_: PyRef<Self>) -> HashMap<String, u32> {
+        getter!(
+            self_,
+            Hybrid,
+            lexicon()
+                .iter()
+                .map(|(token, id)| (token.clone(), *id))
+                .collect()
+        )
+    }
+
+    /// A copy of the fallback model
+    #[getter]
+    fn get_fallback(self_: PyRef<Self>, py: Python) -> PyResult<PyObject> {
+        let fallback: PyModel = getter!(self_, Hybrid, fallback().clone()).into();
+        fallback.get_as_subtype(py)
+    }
+
+    /// Read the content of a :obj:`lexicon.json` file
+    ///
+    /// Like :meth:`~tokenizers.models.Hybrid.read_file`, for a file that was loaded in
+    /// memory, like the ones given by :meth:`~tokenizers.models.Model.files`.
+    ///
+    /// Args:
+    ///     lexicon (:obj:`bytes`):
+    ///         The content of a :obj:`lexicon.json` file
+    ///
+    /// Returns:
+    ///     :obj:`Dict[str, int]`: The lexicon as a :obj:`dict`
+    #[staticmethod]
+    #[pyo3(text_signature = "(lexicon)")]
+    fn read_bytes(lexicon: &[u8]) -> PyResult<HashMap<String, u32>> {
+        let lexicon = Hybrid::read_bytes(lexicon).map_err(|e| {
+            exceptions::PyException::new_err(format!("Error while reading Hybrid file: {e}"))
+        })?;
+        Ok(lexicon.into_iter().collect())
+    }
+
+    /// Read a :obj:`lexicon.json`, in the same format as the :obj:`vocab.json` of a
+    /// :class:`~tokenizers.models.WordLevel`
+    ///
+    /// Args:
+    ///     lexicon (:obj:`str`):
+    ///         The path to a :obj:`lexicon.json` file
+    ///
+    /// Returns:
+    ///     :obj:`Dict[str, int]`: The lexicon as a :obj:`dict`
+    #[staticmethod]
+    #[pyo3(text_signature = "(lexicon)")]
+    fn read_file(lexicon: &str) -> PyResult<HashMap<String, u32>> {
+        let lexicon = Hybrid::read_file(lexicon).map_err(|e| {
+            exceptions::PyException::new_err(format!("Error while reading Hybrid file: {e}"))
+        })?;
+        Ok(lexicon.into_iter().collect())
+    }
+}
+
 /// An implementation of the Unigram algorithm
 ///
 /// Args:
 ///     vocab (:obj:`List[Tuple[str, float]]`, `optional`, `optional`):
 ///         A list of vocabulary items and their relative score [("am", -0.2442),...]
+///
+///     beam_width (:obj:`int`, `optional`):
+///         The maximum number of pieces starting at each position considered during the
+///         segmentation. The ones with the best scores are kept, along with the single
+///         character piece. This bounds the latency on extremely long strings without spaces
+///         (URLs, DNA, ...), but can miss the best segmentation when it needs a dropped piece
+///
+///     lenient_scores (:obj:`bool`, defaults to :obj:`False`):
+///         Whether to clamp the scores that aren't finite, like the :obj:`-inf` found in some
+///         converted models, instead of raising an error. :obj:`NaN` and :obj:`-inf` become
+///         the lowest score minus the penalty of the unknown token, and :obj:`inf` becomes the
+///         highest score. The serialized models are always loaded this way, with a warning.
 #[pyclass(extends=PyModel, module = "tokenizers.models", name = "Unigram")]
 pub struct PyUnigram {}
 
 #[pymethods]
 impl PyUnigram {
     #[new]
-    #[pyo3(signature = (vocab=None, unk_id=None, byte_fallback=None), text_signature = "(self, vocab, unk_id, byte_fallback)")]
+    #[pyo3(signature = (vocab=None, unk_id=None, byte_fallback=None, beam_width=None, lenient_scores=false), text_signature = "(self, vocab, unk_id, byte_fallback, beam_width=None, lenient_scores=False)")]
     fn new(
         vocab: Option<Vec<(String, f64)>>,
         unk_id: Option<usize>,
         byte_fallback: Option<bool>,
+        beam_width: Option<usize>,
+        lenient_scores: bool,
     ) -> PyResult<(Self, PyModel)> {
-        match (vocab, unk_id, byte_fallback) {
+        let mut model = match (vocab, unk_id, byte_fallback) {
             (Some(vocab), unk_id, byte_fallback) => {
-                let model =
-                    Unigram::from(vocab, unk_id, byte_fallback.unwrap_or(false)).map_err(|e| {
-                        exceptions::PyException::new_err(format!(
-                            "Error while loading Unigram: {e}"
-                        ))
-                    })?;
-                Ok((PyUnigram {}, model.into()))
+                let byte_fallback = byte_fallback.unwrap_or(false);
+                if lenient_scores {
+                    Unigram::from_lenient(vocab, unk_id, byte_fallback)
+                } else {
+                    Unigram::from(vocab, unk_id, byte_fallback)
+                }
+                .map_err(|e| {
+                    exceptions::PyException::new_err(format!("Error while loading Unigram: {e}"))
+                })?
+            }
+            (None, None, _) => Unigram::default(),
+            _ => {
+                return Err(exceptions::PyValueError::new_err(
+                    "`vocab` and `unk_id` must be both specified",
+                ))
             }
-            (None, None, _) => Ok((PyUnigram {}, Unigram::default().into())),
-            _ => Err(exceptions::PyValueError::new_err(
-                "`vocab` and `unk_id` must be both specified",
-            )),
+        };
+        model
+            .set_beam_width(beam_width)
+            .map_err(|e| exceptions::PyValueError::new_err(e.to_string()))?;
+        Ok((PyUnigram {}, model.into()))
+    }
+
+    #[getter]
+    fn get_beam_width(self_: PyRef<Self>) -> Option<usize> {
+        getter!(self_, Unigram, beam_width())
+    }
+
+    #[setter]
+    fn set_beam_width(self_: PyRef<Self>, beam_width: Option<usize>) -> PyResult<()> {
+        let super_ = self_.as_ref();
+        let mut model = super_.model.write().unwrap();
+        if let ModelWrapper::Unigram(ref mut mo) = *model {
+            mo.set_beam_width(beam_width)
+                .map_err(|e| exceptions::PyValueError::new_err(e.to_string()))?;
         }
+        Ok(())
     }
 
     /// Clears the internal cache
@@ -923,6 +1373,37 @@ impl PyUnigram {
         model.resize_cache(capacity);
         Ok(())
     }
+
+    /// Export the segmentation lattice of the given sentence in the DOT language
+    ///
+    /// The lattice contains all the pieces that could be used to segment the sentence, with
+    /// their score, and highlights the best segmentation. It can be rendered with Graphviz.
+    ///
+    /// Args:
+    ///     sentence (:obj:`str`):
+    ///         The sentence to segment
+    ///
+    /// Returns:
+    ///     :obj:`str`: The lattice, in the DOT language
+    #[pyo3(text_signature = "(self, sentence)")]
+    fn lattice_dot(self_: PyRef<Self>, sentence: &str) -> String {
+        getter!(self_, Unigram, lattice_dot(sentence))
+    }
+
+    /// Export the segmentation lattice of the given sentence as JSON
+    ///
+    /// Args:
+    ///     sentence (:obj:`str`):
+    ///         The sentence to segment
+    ///
+    /// Returns:
+    ///     :obj:`str`: The lattice, as a JSON string with its ``nodes`` (each with its piece,
+    ///     offsets, score and best previous node), and the ``node_id`` of each piece in the
+    ///     ``best_path``
+    #[pyo3(text_signature = "(self, sentence)")]
+    fn lattice_json(self_: PyRef<Self>, sentence: &str) -> PyResult<String> {
+        ToPyResult(getter!(self_, Unigram, lattice_json(sentence))).into()
+    }
 }
 
 /// Models Module
@@ -932,6 +1413,8 @@ pub fn models(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PyBPE>()?;
     m.add_class::<PyWordPiece>()?;
     m.add_class::<PyWordLevel>()?;
+    m.add_class::<PyMaxMatch>()?;
+    m.add_class::<PyHybrid>()?;
     m.add_class::<PyUnigram>()?;
     Ok(())
 }
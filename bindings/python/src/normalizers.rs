@@ -7,9 +7,10 @@ use crate::error::ToPyResult;
 use crate::utils::{PyNormalizedString, PyNormalizedStringRefMut, PyPattern};
 use serde::ser::SerializeStruct;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use tk::normalizers::precompiled::PrecompiledBuilder;
 use tk::normalizers::{
-    BertNormalizer, ByteLevel, Lowercase, Nmt, NormalizerWrapper, Precompiled, Prepend, Replace,
-    Strip, StripAccents, NFC, NFD, NFKC, NFKD,
+    BertNormalizer, BidiControls, BidiControlsHandling, ByteLevel, Lowercase, Nmt,
+    NormalizerWrapper, Precompiled, Prepend, Replace, Strip, StripAccents, NFC, NFD, NFKC, NFKD,
 };
 use tk::{NormalizedString, Normalizer};
 use tokenizers as tk;
@@ -90,6 +91,12 @@ impl PyNormalizer {
                         .into_pyobject(py)?
                         .into_any()
                         .into(),
+                    NormalizerWrapper::BidiControls(_) => {
+                        Py::new(py, (PyBidiControls {}, base))?
+                            .into_pyobject(py)?
+                            .into_any()
+                            .into()
+                    }
                     NormalizerWrapper::StripAccents(_) => Py::new(py, (PyStripAccents {}, base))?
                         .into_pyobject(py)?
                         .into_any()
@@ -534,6 +541,60 @@ impl PyPrepend {
     }
 }
 
+fn bidi_controls_handling(handling: &str) -> PyResult<BidiControlsHandling> {
+    match handling {
+        "remove" => Ok(BidiControlsHandling::Remove),
+        "space" => Ok(BidiControlsHandling::Space),
+        _ => Err(exceptions::PyValueError::new_err(format!(
+            "{handling} is an unknown variant, should be one of ['remove', 'space']"
+        ))),
+    }
+}
+
+/// BidiControls Normalizer
+///
+/// Handles the invisible controls of the Unicode bidirectional algorithm that a
+/// right-to-left text (Arabic, Hebrew, ...) may contain: the marks (ALM, LRM, RLM), the
+/// embeddings and overrides (LRE, RLE, PDF, LRO, RLO), and the isolates (LRI, RLI, FSI, PDI).
+/// Without it, they end up in the tokens, or are unknown to the model.
+///
+/// The offsets of a right-to-left text are in logical order, like all the offsets: they are
+/// indices in the text as stored, where its first char is also its first char read, the
+/// rightmost one once displayed. Highlighting a span only needs slicing the original text
+/// with its offsets, the display of the slice being left to the bidi algorithm.
+///
+/// Args:
+///     handling (:obj:`str`, `optional`, defaults to :obj:`"remove"`):
+///         What to do with the controls. Choices: "remove" removes them and joins the text
+///         around them, "space" replaces each of them by a space, splitting the text around
+///         them like a whitespace.
+#[pyclass(extends=PyNormalizer, module = "tokenizers.normalizers", name = "BidiControls")]
+pub struct PyBidiControls {}
+#[pymethods]
+impl PyBidiControls {
+    #[getter]
+    fn get_handling(self_: PyRef<Self>) -> &'static str {
+        match getter!(self_, BidiControls, handling) {
+            BidiControlsHandling::Remove => "remove",
+            BidiControlsHandling::Space => "space",
+        }
+    }
+
+    #[setter]
+    fn set_handling(self_: PyRef<Self>, handling: &str) -> PyResult<()> {
+        let handling = bidi_controls_handling(handling)?;
+        setter!(self_, BidiControls, handling, handling);
+        Ok(())
+    }
+
+    #[new]
+    #[pyo3(signature = (handling = "remove"), text_signature = "(self, handling=\"remove\")")]
+    fn new(handling: &str) -> PyResult<(Self, PyNormalizer)> {
+        let handling = bidi_controls_handling(handling)?;
+        Ok((PyBidiControls {}, BidiControls::new(handling).into()))
+    }
+}
+
 /// Bytelevel Normalizer
 #[pyclass(extends=PyNormalizer, module = "tokenizers.normalizers", name = "ByteLevel")]
 pub struct PyByteLevel {}
@@ -591,6 +652,32 @@ impl PyPrecompiled {
                 .into(),
         ))
     }
+
+    /// Build a Precompiled normalizer from a list of ``(source, replacement)`` rules,
+    /// instead of a ``precompiled_charsmap`` extracted from a SentencePiece model.
+    ///
+    /// Just like with SentencePiece, the rules are looked up for each grapheme first,
+    /// and then for each of its characters.
+    ///
+    /// Args:
+    ///     rules (:obj:`List[Tuple[str, str]]`):
+    ///         The rules to apply
+    ///
+    /// Returns:
+    ///     :class:`~tokenizers.normalizers.Precompiled`: The normalizer
+    #[staticmethod]
+    #[pyo3(text_signature = "(rules)")]
+    fn from_rules(py: Python<'_>, rules: Vec<(String, String)>) -> PyResult<Py<Self>> {
+        let precompiled = PrecompiledBuilder::new()
+            .rules(rules)
+            .build()
+            .map_err(|e| {
+                exceptions::PyException::new_err(format!(
+                    "Error while attempting to build Precompiled normalizer: {e}"
+                ))
+            })?;
+        Py::new(py, (PyPrecompiled {}, precompiled.into()))
+    }
 }
 
 /// Replace normalizer
@@ -601,10 +688,11 @@ impl PyReplace {
     #[new]
     #[pyo3(text_signature = "(self, pattern, content)")]
     fn new(pattern: PyPattern, content: String) -> PyResult<(Self, PyNormalizer)> {
-        Ok((
-            PyReplace {},
-            ToPyResult(Replace::new(pattern, content)).into_py()?.into(),
-        ))
+        let replace = match pattern.regex() {
+            Some(regex) => Replace::from_regex(regex, content),
+            None => ToPyResult(Replace::new(pattern, content)).into_py()?,
+        };
+        Ok((PyReplace {}, replace.into()))
     }
 
     #[getter]
@@ -809,6 +897,7 @@ pub fn normalizers(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PyStripAccents>()?;
     m.add_class::<PyPrepend>()?;
     m.add_class::<PyByteLevel>()?;
+    m.add_class::<PyBidiControls>()?;
     m.add_class::<PyNmt>()?;
     m.add_class::<PyPrecompiled>()?;
     m.add_class::<PyReplace>()?;
@@ -0,0 +1,108 @@
+use pyo3::prelude::*;
+use tk::utils::offsets as tk_offsets;
+use tk::Offsets;
+
+/// Convert the given offsets from bytes to chars
+///
+/// Args:
+///     text (:obj:`str`):
+///         The string the offsets refer to
+///
+///     offsets (:obj:`Tuple[int, int]`):
+///         The byte offsets to convert
+///
+/// Returns:
+///     :obj:`Tuple[int, int]`: The char offsets, or :obj:`None` if the given offsets do not
+///     fall on char boundaries
+#[pyfunction]
+#[pyo3(text_signature = "(text, offsets)")]
+fn bytes_to_char(text: &str, offsets: Offsets) -> Option<Offsets> {
+    tk_offsets::bytes_to_char(text, offsets.0..offsets.1).map(|r| (r.start, r.end))
+}
+
+/// Convert the given offsets from chars to bytes
+///
+/// Args:
+///     text (:obj:`str`):
+///         The string the offsets refer to
+///
+///     offsets (:obj:`Tuple[int, int]`):
+///         The char offsets to convert
+///
+/// Returns:
+///     :obj:`Tuple[int, int]`: The byte offsets, or :obj:`None` if the given offsets start
+///     past the end of the string. An end past the end of the string gets clamped
+#[pyfunction]
+#[pyo3(text_signature = "(text, offsets)")]
+fn char_to_bytes(text: &str, offsets: Offsets) -> Option<Offsets> {
+    tk_offsets::char_to_bytes(text, offsets.0..offsets.1).map(|r| (r.start, r.end))
+}
+
+/// Converts offsets between bytes and chars for a given string
+///
+/// The char boundaries are computed once, which makes it the best option when
+/// converting many offsets relative to the same string.
+///
+/// Args:
+///     text (:obj:`str`):
+///         The string the offsets refer to
+#[pyclass(module = "tokenizers.offsets", name = "OffsetConverter")]
+pub struct PyOffsetConverter {
+    inner: tk_offsets::OffsetConverter,
+}
+
+#[pymethods]
+impl PyOffsetConverter {
+    #[new]
+    #[pyo3(text_signature = "(self, text)")]
+    fn new(text: &str) -> Self {
+        Self {
+            inner: tk_offsets::OffsetConverter::new(text),
+        }
+    }
+
+    /// Convert the given byte index to a char index
+    ///
+    /// Returns:
+    ///     :obj:`int`: The char index, or :obj:`None` if not on a char boundary
+    #[pyo3(text_signature = "(self, index)")]
+    fn byte_to_char(&self, index: usize) -> Option<usize> {
+        self.inner.byte_to_char(index)
+    }
+
+    /// Convert the given offsets from bytes to chars
+    ///
+    /// Returns:
+    ///     :obj:`Tuple[int, int]`: The char offsets, or :obj:`None` if not on char boundaries
+    #[pyo3(text_signature = "(self, offsets)")]
+    fn bytes_to_char(&self, offsets: Offsets) -> Option<Offsets> {
+        self.inner.bytes_to_char(offsets)
+    }
+
+    /// Convert the given char index to a byte index
+    ///
+    /// Returns:
+    ///     :obj:`int`: The byte index, or :obj:`None` if past the end of the string
+    #[pyo3(text_signature = "(self, index)")]
+    fn char_to_byte(&self, index: usize) -> Option<usize> {
+        self.inner.char_to_byte(index)
+    }
+
+    /// Convert the given offsets from chars to bytes
+    ///
+    /// Returns:
+    ///     :obj:`Tuple[int, int]`: The byte offsets, or :obj:`None` if past the end of the string
+    #[pyo3(text_signature = "(self, offsets)")]
+    fn char_to_bytes(&self, offsets: Offsets) -> Option<Offsets> {
+        self.inner.char_to_bytes(offsets)
+    }
+}
+
+/// Offsets Module
+#[pymodule]
+pub fn offsets(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(bytes_to_char, m)?)?;
+    m.add_function(wrap_pyfunction!(char_to_bytes, m)?)?;
+    m.add_class::<PyOffsetConverter>()?;
+    Ok(())
+}
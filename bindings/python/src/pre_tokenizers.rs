@@ -7,16 +7,23 @@ use pyo3::types::*;
 use serde::ser::SerializeStruct;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
+use tk::models::wordpiece::WordPiece;
 use tk::normalizer::SplitDelimiterBehavior;
 use tk::pre_tokenizers::bert::BertPreTokenizer;
 use tk::pre_tokenizers::byte_level::ByteLevel;
 use tk::pre_tokenizers::delimiter::CharDelimiterSplit;
 use tk::pre_tokenizers::digits::Digits;
 use tk::pre_tokenizers::fixed_length::FixedLength;
+use tk::pre_tokenizers::indentation::{IndentUnit, Indentation};
 use tk::pre_tokenizers::metaspace::{Metaspace, PrependScheme};
+use tk::pre_tokenizers::model_split::ModelSplit;
 use tk::pre_tokenizers::punctuation::Punctuation;
+use tk::pre_tokenizers::regex_split::RegexSplit;
+use tk::pre_tokenizers::run_length::RunLength;
+use tk::pre_tokenizers::script_split::{CommonScript, ScriptGranularity, ScriptSplit};
 use tk::pre_tokenizers::split::Split;
 use tk::pre_tokenizers::unicode_scripts::UnicodeScripts;
+use tk::pre_tokenizers::unicode_words::{UnicodeWords, WordSegmenter};
 use tk::pre_tokenizers::whitespace::{Whitespace, WhitespaceSplit};
 use tk::pre_tokenizers::PreTokenizerWrapper;
 use tk::tokenizer::Offsets;
@@ -24,6 +31,8 @@ use tk::{PreTokenizedString, PreTokenizer};
 use tokenizers as tk;
 
 use super::error::ToPyResult;
+use super::models::PyModel;
+use super::normalizers::PyNormalizer;
 use super::utils::*;
 
 /// Base class for all pre-tokenizers
@@ -125,6 +134,42 @@ impl PyPreTokenizer {
                                 .into_any()
                                 .into()
                         }
+                        PreTokenizerWrapper::RegexSplit(_) => {
+                            Py::new(py, (PyRegexSplit {}, base))?
+                                .into_pyobject(py)?
+                                .into_any()
+                                .into()
+                        }
+                        PreTokenizerWrapper::Indentation(_) => {
+                            Py::new(py, (PyIndentation {}, base))?
+                                .into_pyobject(py)?
+                                .into_any()
+                                .into()
+                        }
+                        PreTokenizerWrapper::RunLength(_) => {
+                            Py::new(py, (PyRunLength {}, base))?
+                                .into_pyobject(py)?
+                                .into_any()
+                                .into()
+                        }
+                        PreTokenizerWrapper::UnicodeWords(_) => {
+                            Py::new(py, (PyUnicodeWords {}, base))?
+                                .into_pyobject(py)?
+                                .into_any()
+                                .into()
+                        }
+                        PreTokenizerWrapper::ScriptSplit(_) => {
+                            Py::new(py, (PyScriptSplit {}, base))?
+                                .into_pyobject(py)?
+                                .into_any()
+                                .into()
+                        }
+                        PreTokenizerWrapper::ModelSplit(_) => {
+                            Py::new(py, (PyModelSplit {}, base))?
+                                .into_pyobject(py)?
+                                .into_any()
+                                .into()
+                        }
                     },
                 }
             }
@@ -216,6 +261,45 @@ impl PyPreTokenizer {
             .collect())
     }
 
+    /// Pre tokenize the given string chunk by chunk, yielding the pre-tokens as they come
+    ///
+    /// Like :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize_str`, but only a
+    /// single chunk of the string is pre-tokenized at once, which bounds the memory used for
+    /// very long strings, like whole books. Each chunk ends right before some whitespace, so
+    /// the pre-tokens are the same for the pre-tokenizers splitting on whitespace, except for
+    /// the words longer than a chunk, which get split. A chunk starting elsewhere than before
+    /// a space, like before a newline or within a long word, is pre-tokenized as the start of
+    /// a string: the pre-tokenizers adding a prefix space, like
+    /// :class:`~tokenizers.pre_tokenizers.ByteLevel`, add one there too.
+    ///
+    /// Args:
+    ///     sequence (:obj:`str`):
+    ///         A string to pre-tokenize
+    ///
+    ///     chunk_size (:obj:`int`, defaults to :obj:`1048576`):
+    ///         The approximate size of the chunks, in bytes
+    ///
+    /// Returns:
+    ///     :class:`~tokenizers.pre_tokenizers.PreTokenizeStream`:
+    ///         An iterator over the tuples of the pre-tokenized parts and their offsets in the
+    ///         whole string
+    #[pyo3(signature = (sequence, chunk_size = 1 << 20))]
+    #[pyo3(text_signature = "(self, sequence, chunk_size=1048576)")]
+    fn pre_tokenize_str_streaming(
+        &self,
+        sequence: String,
+        chunk_size: usize,
+    ) -> PyPreTokenizeStream {
+        PyPreTokenizeStream {
+            stream: tk::PreTokenizeStream::new(
+                self.pretok.clone(),
+                sequence,
+                chunk_size,
+                tk::OffsetType::Char,
+            ),
+        }
+    }
+
     fn __repr__(&self) -> PyResult<String> {
         crate::utils::serde_pyo3::repr(self)
             .map_err(|e| exceptions::PyException::new_err(e.to_string()))
@@ -227,6 +311,27 @@ impl PyPreTokenizer {
     }
 }
 
+/// The pre-tokens of a string, returned by
+/// :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize_str_streaming`
+#[pyclass(module = "tokenizers.pre_tokenizers", name = "PreTokenizeStream")]
+pub struct PyPreTokenizeStream {
+    stream: tk::PreTokenizeStream<PyPreTokenizerTypeWrapper, String>,
+}
+
+#[pymethods]
+impl PyPreTokenizeStream {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self) -> PyResult<Option<(String, Offsets)>> {
+        self.stream
+            .next()
+            .map(|pre_token| ToPyResult(pre_token).into_py())
+            .transpose()
+    }
+}
+
 macro_rules! getter {
     ($self: ident, $variant: ident, $($name: tt)+) => {{
         let super_ = $self.as_ref();
@@ -428,12 +533,11 @@ impl PySplit {
         behavior: PySplitDelimiterBehavior,
         invert: bool,
     ) -> PyResult<(Self, PyPreTokenizer)> {
-        Ok((
-            PySplit {},
-            ToPyResult(Split::new(pattern, behavior.into(), invert))
-                .into_py()?
-                .into(),
-        ))
+        let split = match pattern.regex() {
+            Some(regex) => Split::from_regex(regex, behavior.into(), invert),
+            None => ToPyResult(Split::new(pattern, behavior.into(), invert)).into_py()?,
+        };
+        Ok((PySplit {}, split.into()))
     }
 
     fn __getnewargs__<'p>(&self, py: Python<'p>) -> PyResult<Bound<'p, PyTuple>> {
@@ -487,6 +591,42 @@ impl PySplit {
     }
 }
 
+/// This pre-tokenizer splits the text on the matches of a full regex pattern, just like
+/// `re.findall` would, which makes it possible to use the pre-tokenization patterns of
+/// GPT-4 or LLaMA 3 as is, lookarounds included.
+///
+/// Each match becomes a piece. When the pattern has capture groups, each group that
+/// participated in the match becomes a piece instead, and the rest of the match is removed.
+/// The nested groups, and those reaching out of the match through a lookaround, are
+/// ignored. Contrarily to `re.findall`, the text that doesn't match is never lost, and gets
+/// its own pieces.
+///
+/// Args:
+///     pattern (:obj:`str`):
+///         The regex pattern
+#[pyclass(extends=PyPreTokenizer, module = "tokenizers.pre_tokenizers", name = "RegexSplit")]
+pub struct PyRegexSplit {}
+#[pymethods]
+impl PyRegexSplit {
+    #[new]
+    #[pyo3(text_signature = "(self, pattern)")]
+    fn new(pattern: &str) -> PyResult<(Self, PyPreTokenizer)> {
+        Ok((
+            PyRegexSplit {},
+            ToPyResult(RegexSplit::new(pattern)).into_py()?.into(),
+        ))
+    }
+
+    fn __getnewargs__<'p>(&self, py: Python<'p>) -> PyResult<Bound<'p, PyTuple>> {
+        PyTuple::new(py, [""])
+    }
+
+    #[getter]
+    fn get_pattern(self_: PyRef<Self>) -> String {
+        getter!(self_, RegexSplit, get_pattern().to_owned())
+    }
+}
+
 /// This pre-tokenizer simply splits on the provided char. Works like `.split(delimiter)`
 ///
 /// Args:
@@ -796,6 +936,108 @@ impl PyFixedLength {
     }
 }
 
+fn indent_unit_from_string(string: String) -> Result<IndentUnit, PyErr> {
+    let unit = match string.as_str() {
+        "tab" => IndentUnit::Tab,
+        "two_spaces" => IndentUnit::TwoSpaces,
+        "four_spaces" => IndentUnit::FourSpaces,
+        _ => {
+            return Err(exceptions::PyValueError::new_err(format!(
+                "{string} is an unknown variant, should be one of ['tab', 'two_spaces', 'four_spaces']"
+            )));
+        }
+    };
+    Ok(unit)
+}
+
+/// This pre-tokenizer splits each indentation unit found at the start of a line into its
+/// own piece, so that the model can represent the indentation of code with dedicated tokens.
+/// The rest of the text, including the leftover whitespace that doesn't make a full unit,
+/// is kept as is, to be split by the next pre-tokenizer::
+///
+///     "if x:\n        y" -> "if x:\n", "    ", "    ", "y"
+///
+/// The pre-tokenizers removing the whitespace, like
+/// :class:`~tokenizers.pre_tokenizers.WhitespaceSplit`, would drop the indentation pieces,
+/// so it is best followed by :class:`~tokenizers.pre_tokenizers.ByteLevel` or
+/// :class:`~tokenizers.pre_tokenizers.Metaspace`.
+///
+/// Args:
+///     unit (:obj:`str`, `optional`, defaults to :obj:`"four_spaces"`):
+///         The indentation unit, one of :obj:`"tab"`, :obj:`"two_spaces"` and
+///         :obj:`"four_spaces"`
+#[pyclass(extends=PyPreTokenizer, module = "tokenizers.pre_tokenizers", name = "Indentation")]
+pub struct PyIndentation {}
+#[pymethods]
+impl PyIndentation {
+    #[getter]
+    fn get_unit(self_: PyRef<Self>) -> String {
+        getter!(self_, Indentation, unit).to_string()
+    }
+
+    #[setter]
+    fn set_unit(self_: PyRef<Self>, unit: String) -> PyResult<()> {
+        let unit = indent_unit_from_string(unit)?;
+        setter!(self_, Indentation, unit, unit);
+        Ok(())
+    }
+
+    #[new]
+    #[pyo3(signature = (unit = String::from("four_spaces")), text_signature = "(self, unit=\"four_spaces\")")]
+    fn new(unit: String) -> PyResult<(Self, PyPreTokenizer)> {
+        let unit = indent_unit_from_string(unit)?;
+        Ok((PyIndentation {}, Indentation::new(unit).into()))
+    }
+}
+
+/// This pre-tokenizer collapses the runs of a same character longer than ``max_run`` into
+/// the character itself, followed by a count token made of the length of the run between two
+/// ``marker``::
+///
+///     "wait!!!!!!!!!!!!" -> "wait", "!", "⟳12⟳"
+///
+/// This protects the model from the arbitrarily long runs found in adversarial inputs. The
+/// :class:`~tokenizers.decoders.RunLength` decoder expands the count tokens back to the
+/// original runs.
+///
+/// Args:
+///     max_run (:obj:`int`, `optional`, defaults to :obj:`8`):
+///         The length of the longest run kept as is
+///
+///     marker (:obj:`str`, `optional`, defaults to :obj:`⟳`):
+///         The character around the count tokens. Must be exactly one character, best
+///         chosen out of the expected inputs.
+#[pyclass(extends=PyPreTokenizer, module = "tokenizers.pre_tokenizers", name = "RunLength")]
+pub struct PyRunLength {}
+#[pymethods]
+impl PyRunLength {
+    #[getter]
+    fn get_max_run(self_: PyRef<Self>) -> usize {
+        getter!(self_, RunLength, max_run)
+    }
+
+    #[setter]
+    fn set_max_run(self_: PyRef<Self>, max_run: usize) {
+        setter!(self_, RunLength, max_run, max_run);
+    }
+
+    #[getter]
+    fn get_marker(self_: PyRef<Self>) -> String {
+        getter!(self_, RunLength, marker).to_string()
+    }
+
+    #[setter]
+    fn set_marker(self_: PyRef<Self>, marker: char) {
+        setter!(self_, RunLength, marker, marker);
+    }
+
+    #[new]
+    #[pyo3(signature = (max_run = 8, marker = '⟳'), text_signature = "(self, max_run=8, marker=\"⟳\")")]
+    fn new(max_run: usize, marker: char) -> (Self, PyPreTokenizer) {
+        (PyRunLength {}, RunLength::new(max_run, marker).into())
+    }
+}
+
 /// This pre-tokenizer splits on characters that belong to different language family
 /// It roughly follows https://github.com/google/sentencepiece/blob/master/data/Scripts.txt
 /// Actually Hiragana and Katakana are fused with Han, and 0x30FC is Han too.
@@ -811,6 +1053,202 @@ impl PyUnicodeScripts {
     }
 }
 
+/// Wrap a Python callable as a `WordSegmenter`, converting the char offsets it
+/// returns to byte offsets
+fn word_segmenter(segmenter: PyObject) -> WordSegmenter {
+    WordSegmenter::new(move |run| {
+        let offsets: Vec<usize> = Python::with_gil(|py| segmenter.call1(py, (run,))?.extract(py))?;
+        let bytes = run
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain(std::iter::once(run.len()))
+            .collect::<Vec<_>>();
+        offsets
+            .into_iter()
+            .map(|offset| {
+                bytes.get(offset).copied().ok_or_else(|| {
+                    format!("The word segmenter gave the offset {offset}, out of \"{run}\"").into()
+                })
+            })
+            .collect()
+    })
+}
+
+/// This pre-tokenizer splits on the word boundaries of Unicode (UAX #29), removing the
+/// whitespace. The words stay whole across the scripts: the marks stay on their letters,
+/// ``"can't"`` and ``"3.14"`` stay one word, and so do the emoji sequences joined with
+/// ZWJ. Each punctuation character gets split on its own.
+///
+/// The scripts written without spaces (Chinese, Japanese, Thai, Lao, Khmer and Myanmar)
+/// have no word boundaries in UAX #29, which splits them character by character.
+///
+/// Args:
+///     cjk (:obj:`bool`, `optional`, defaults to :obj:`False`):
+///         Whether to keep each run of these scripts whole instead, or split it with the
+///         ``segmenter`` when one is set
+///
+///     segmenter (:obj:`Callable[[str], List[int]]`, `optional`):
+///         A dictionary segmenter, receiving a run and returning the character offsets of
+///         the boundaries between its words. It is not serialized, and must be set again
+///         after loading.
+#[pyclass(extends=PyPreTokenizer, module = "tokenizers.pre_tokenizers", name = "UnicodeWords")]
+pub struct PyUnicodeWords {}
+#[pymethods]
+impl PyUnicodeWords {
+    #[getter]
+    fn get_cjk(self_: PyRef<Self>) -> bool {
+        getter!(self_, UnicodeWords, cjk)
+    }
+
+    #[setter]
+    fn set_cjk(self_: PyRef<Self>, cjk: bool) {
+        setter!(self_, UnicodeWords, cjk, cjk);
+    }
+
+    #[setter]
+    fn set_segmenter(self_: PyRef<Self>, segmenter: Option<PyObject>) {
+        setter!(
+            self_,
+            UnicodeWords,
+            segmenter,
+            segmenter.map(word_segmenter)
+        );
+    }
+
+    #[new]
+    #[pyo3(signature = (cjk = false, segmenter = None), text_signature = "(self, cjk=False, segmenter=None)")]
+    fn new(cjk: bool, segmenter: Option<PyObject>) -> (Self, PyPreTokenizer) {
+        let pretok = UnicodeWords::new(cjk).with_segmenter(segmenter.map(word_segmenter));
+        (PyUnicodeWords {}, pretok.into())
+    }
+}
+
+fn script_granularity_from_string(string: String) -> Result<ScriptGranularity, PyErr> {
+    let granularity = match string.as_str() {
+        "script" => ScriptGranularity::Script,
+        "writing_system" => ScriptGranularity::WritingSystem,
+        _ => {
+            return Err(exceptions::PyValueError::new_err(format!(
+                "{string} is an unknown variant, should be one of ['script', 'writing_system']"
+            )));
+        }
+    };
+    Ok(granularity)
+}
+
+fn common_script_from_string(string: String) -> Result<CommonScript, PyErr> {
+    let common = match string.as_str() {
+        "merged" => CommonScript::Merged,
+        "isolated" => CommonScript::Isolated,
+        _ => {
+            return Err(exceptions::PyValueError::new_err(format!(
+                "{string} is an unknown variant, should be one of ['merged', 'isolated']"
+            )));
+        }
+    };
+    Ok(common)
+}
+
+/// This pre-tokenizer splits the text where the Unicode script changes, so that no piece
+/// mixes several scripts. This keeps apart the words of different languages written without
+/// spaces between them, and stops the strings mixing Latin and Cyrillic homoglyphs from
+/// forming merges of their own::
+///
+///     "pаypal" (with a Cyrillic "а") -> "p", "а", "ypal"
+///
+/// The combining marks always stay with the character before them.
+///
+/// Args:
+///     granularity (:obj:`str`, `optional`, defaults to :obj:`"writing_system"`):
+///         Which scripts are told apart. With :obj:`"script"`, each Unicode script is, while
+///         :obj:`"writing_system"` keeps together the scripts used by a single writing
+///         system, like Han, Hiragana and Katakana for Japanese
+///
+///     common (:obj:`str`, `optional`, defaults to :obj:`"merged"`):
+///         What to do with the characters of the Common script, like the spaces, the
+///         punctuation and the digits. With :obj:`"merged"`, they join the script before them,
+///         while with :obj:`"isolated"` each run of them is split on its own
+#[pyclass(extends=PyPreTokenizer, module = "tokenizers.pre_tokenizers", name = "ScriptSplit")]
+pub struct PyScriptSplit {}
+#[pymethods]
+impl PyScriptSplit {
+    #[getter]
+    fn get_granularity(self_: PyRef<Self>) -> String {
+        getter!(self_, ScriptSplit, granularity).to_string()
+    }
+
+    #[setter]
+    fn set_granularity(self_: PyRef<Self>, granularity: String) -> PyResult<()> {
+        let granularity = script_granularity_from_string(granularity)?;
+        setter!(self_, ScriptSplit, granularity, granularity);
+        Ok(())
+    }
+
+    #[getter]
+    fn get_common(self_: PyRef<Self>) -> String {
+        getter!(self_, ScriptSplit, common).to_string()
+    }
+
+    #[setter]
+    fn set_common(self_: PyRef<Self>, common: String) -> PyResult<()> {
+        let common = common_script_from_string(common)?;
+        setter!(self_, ScriptSplit, common, common);
+        Ok(())
+    }
+
+    #[new]
+    #[pyo3(signature = (granularity = String::from("writing_system"), common = String::from("merged")), text_signature = "(self, granularity=\"writing_system\", common=\"merged\")")]
+    fn new(granularity: String, common: String) -> PyResult<(Self, PyPreTokenizer)> {
+        let granularity = script_granularity_from_string(granularity)?;
+        let common = common_script_from_string(common)?;
+        Ok((
+            PyScriptSplit {},
+            ScriptSplit::new(granularity, common).into(),
+        ))
+    }
+}
+
+/// ModelSplit PreTokenizer
+///
+/// Splits each pre-token into the tokens found by a model, keeping their offsets in the
+/// original text. This is how :meth:`~tokenizers.Tokenizer.chain` feeds the tokens of a
+/// tokenizer to the model of another one.
+///
+/// Args:
+///     model (:class:`~tokenizers.models.Model`):
+///         The model used to split the pre-tokens. It is copied, so changing it afterwards
+///         has no effect on this pre-tokenizer
+///
+///     normalizer (:class:`~tokenizers.normalizers.Normalizer`, `optional`):
+///         A normalizer applied to each of the pieces
+#[pyclass(extends=PyPreTokenizer, module = "tokenizers.pre_tokenizers", name = "ModelSplit")]
+pub struct PyModelSplit {}
+#[pymethods]
+impl PyModelSplit {
+    #[new]
+    #[pyo3(signature = (model, normalizer = None), text_signature = "(self, model, normalizer=None)")]
+    fn new(
+        model: PyRef<PyModel>,
+        normalizer: Option<PyRef<PyNormalizer>>,
+    ) -> PyResult<(Self, PyPreTokenizer)> {
+        let model = model.model.read().unwrap().clone();
+        let normalizer = normalizer
+            .map(|normalizer| serde_json::to_value(&*normalizer).and_then(serde_json::from_value))
+            .transpose()
+            .map_err(|e| {
+                exceptions::PyException::new_err(format!(
+                    "Cannot use this normalizer in a ModelSplit: {e}"
+                ))
+            })?;
+        Ok((PyModelSplit {}, ModelSplit::new(model, normalizer).into()))
+    }
+
+    fn __getnewargs__<'p>(&self, py: Python<'p>) -> PyResult<Bound<'p, PyTuple>> {
+        let model = PyModel::from(WordPiece::default()).get_as_subtype(py)?;
+        PyTuple::new(py, [model])
+    }
+}
+
 #[derive(Clone)]
 pub(crate) struct CustomPreTokenizer {
     inner: PyObject,
@@ -976,10 +1414,14 @@ impl PreTokenizer for PyPreTokenizerWrapper {
 #[pymodule]
 pub fn pre_tokenizers(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PyPreTokenizer>()?;
+    m.add_class::<PyPreTokenizeStream>()?;
     m.add_class::<PyByteLevel>()?;
     m.add_class::<PyWhitespace>()?;
     m.add_class::<PyWhitespaceSplit>()?;
     m.add_class::<PySplit>()?;
+    m.add_class::<PyRegexSplit>()?;
+    m.add_class::<PyIndentation>()?;
+    m.add_class::<PyRunLength>()?;
     m.add_class::<PyBertPreTokenizer>()?;
     m.add_class::<PyMetaspace>()?;
     m.add_class::<PyCharDelimiterSplit>()?;
@@ -987,6 +1429,9 @@ pub fn pre_tokenizers(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PySequence>()?;
     m.add_class::<PyDigits>()?;
     m.add_class::<PyUnicodeScripts>()?;
+    m.add_class::<PyUnicodeWords>()?;
+    m.add_class::<PyScriptSplit>()?;
+    m.add_class::<PyModelSplit>()?;
     m.add_class::<PyFixedLength>()?;
     Ok(())
 }
@@ -14,6 +14,7 @@ use serde::Serializer;
 use serde::{Deserialize, Serialize};
 use tk::processors::bert::BertProcessing;
 use tk::processors::byte_level::ByteLevel;
+use tk::processors::chat::{ChatRole, ChatTemplate};
 use tk::processors::roberta::RobertaProcessing;
 use tk::processors::template::{SpecialToken, Template};
 use tk::processors::PostProcessorWrapper;
@@ -85,6 +86,10 @@ impl PyPostProcessor {
                     .into_pyobject(py)?
                     .into_any()
                     .into(),
+                PostProcessorWrapper::ChatTemplate(_) => Py::new(py, (PyChatTemplate {}, base))?
+                    .into_pyobject(py)?
+                    .into_any()
+                    .into(),
             }
                 }
             }
@@ -92,6 +97,23 @@ impl PyPostProcessor {
     }
 }
 
+impl PyPostProcessor {
+    /// Get a copy of the inner `ChatTemplate`, if this is one
+    pub(crate) fn get_chat_template(&self) -> PyResult<Option<ChatTemplate>> {
+        match &self.processor {
+            PyPostProcessorTypeWrapper::Single(inner) => match &*inner.read().map_err(|_| {
+                PyException::new_err(
+                    "RwLock synchronisation primitive is poisoned, cannot get PyPostProcessor",
+                )
+            })? {
+                PostProcessorWrapper::ChatTemplate(template) => Ok(Some(template.clone())),
+                _ => Ok(None),
+            },
+            PyPostProcessorTypeWrapper::Sequence(_) => Ok(None),
+        }
+    }
+}
+
 impl PostProcessor for PyPostProcessor {
     // TODO: update signature to `tk::Result<usize>`
     fn added_tokens(&self, is_pair: bool) -> usize {
@@ -341,7 +363,7 @@ impl PyBertProcessing {
     }
 
     #[getter]
-    fn get_sep(self_: PyRef<Self>) -> Result<Bound<'_, PyTuple>, PyErr> {
+    fn get_sep(self_: PyRef<'_, Self>) -> Result<Bound<'_, PyTuple>, PyErr> {
         let py = self_.py();
         let (tok, id) = getter!(self_, Bert, get_sep_copy());
         PyTuple::new(
@@ -358,7 +380,7 @@ impl PyBertProcessing {
     }
 
     #[getter]
-    fn get_cls(self_: PyRef<Self>) -> Result<Bound<'_, PyTuple>, PyErr> {
+    fn get_cls(self_: PyRef<'_, Self>) -> Result<Bound<'_, PyTuple>, PyErr> {
         let py = self_.py();
         let (tok, id) = getter!(self_, Bert, get_cls_copy());
         PyTuple::new(
@@ -422,7 +444,7 @@ impl PyRobertaProcessing {
     }
 
     #[getter]
-    fn get_sep(self_: PyRef<Self>) -> Result<Bound<'_, PyTuple>, PyErr> {
+    fn get_sep(self_: PyRef<'_, Self>) -> Result<Bound<'_, PyTuple>, PyErr> {
         let py = self_.py();
         let (tok, id) = getter!(self_, Roberta, get_sep_copy());
         PyTuple::new(
@@ -439,7 +461,7 @@ impl PyRobertaProcessing {
     }
 
     #[getter]
-    fn get_cls(self_: PyRef<Self>) -> Result<Bound<'_, PyTuple>, PyErr> {
+    fn get_cls(self_: PyRef<'_, Self>) -> Result<Bound<'_, PyTuple>, PyErr> {
         let py = self_.py();
         let (tok, id) = getter!(self_, Roberta, get_cls_copy());
         PyTuple::new(
@@ -724,6 +746,117 @@ impl PyTemplateProcessing {
     }
 }
 
+#[derive(Clone, Debug)]
+pub struct PyChatRole(ChatRole);
+
+impl From<PyChatRole> for ChatRole {
+    fn from(v: PyChatRole) -> Self {
+        v.0
+    }
+}
+
+impl FromPyObject<'_> for PyChatRole {
+    fn extract_bound(ob: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let d = ob
+            .downcast::<PyDict>()
+            .map_err(|_| exceptions::PyTypeError::new_err("Expected a dict describing a role"))?;
+        let name = d
+            .get_item("name")?
+            .ok_or_else(|| exceptions::PyValueError::new_err("`name` must be specified"))?
+            .extract::<String>()?;
+        let prefix = d
+            .get_item("prefix")?
+            .map_or(Ok(vec![]), |v| v.extract::<Vec<String>>())?;
+        let suffix = d
+            .get_item("suffix")?
+            .map_or(Ok(vec![]), |v| v.extract::<Vec<String>>())?;
+        let type_id = d
+            .get_item("type_id")?
+            .map_or(Ok(0), |v| v.extract::<u32>())?;
+        let loss = d
+            .get_item("loss")?
+            .map_or(Ok(false), |v| v.extract::<bool>())?;
+
+        Ok(Self(ChatRole::new(name, prefix, suffix, type_id, loss)))
+    }
+}
+
+/// Provides a way to assemble conversations made of any number of role-tagged turns,
+/// each turn being surrounded by the special tokens of its role.
+///
+/// Conversations are encoded with :meth:`~tokenizers.Tokenizer.encode_chat`, which also
+/// returns a loss mask identifying the tokens of the roles marked with ``loss=True``.
+/// When used through :meth:`~tokenizers.Tokenizer.encode`, the i-th sequence uses the i-th role.
+///
+/// Example::
+///
+///     ChatTemplate(
+///         roles=[
+///             {"name": "user", "prefix": ["<|user|>"], "suffix": ["</s>"]},
+///             {"name": "assistant", "prefix": ["<|assistant|>"], "suffix": ["</s>"], "loss": True},
+///         ],
+///         special_tokens=[("<|user|>", 1), ("<|assistant|>", 2), ("</s>", 3)],
+///     )
+///
+/// Args:
+///     roles (:obj:`List[dict]`):
+///         The roles of the conversation. Each dict expects the following keys:
+///
+///             - "name": :obj:`str` => The name of the role
+///             - "prefix": :obj:`List[str]`, `optional` => The special tokens added before each turn
+///             - "suffix": :obj:`List[str]`, `optional` => The special tokens added after each turn
+///             - "type_id": :obj:`int`, defaults to 0 => The type id of the tokens of each turn
+///             - "loss": :obj:`bool`, defaults to :obj:`False` => Whether tokens of this role
+///               (except the prefix) are part of the loss mask
+///
+///     special_tokens (:obj:`Tokens`):
+///         The list of special tokens used by the roles, with the same format as
+///         :class:`~tokenizers.processors.TemplateProcessing`
+///
+///     prefix (:obj:`List[str]`, `optional`):
+///         The special tokens added once, at the beginning of the conversation
+#[pyclass(extends=PyPostProcessor, module = "tokenizers.processors", name = "ChatTemplate")]
+pub struct PyChatTemplate {}
+#[pymethods]
+impl PyChatTemplate {
+    #[new]
+    #[pyo3(signature = (roles, special_tokens, prefix = None), text_signature = "(self, roles, special_tokens, prefix=None)")]
+    fn new(
+        roles: Vec<PyChatRole>,
+        special_tokens: Vec<PySpecialToken>,
+        prefix: Option<Vec<String>>,
+    ) -> PyResult<(Self, PyPostProcessor)> {
+        let template = ChatTemplate::new(
+            prefix.unwrap_or_default(),
+            roles.into_iter().map(|r| r.into()).collect(),
+            special_tokens,
+        )
+        .map_err(|e| exceptions::PyValueError::new_err(e.to_string()))?;
+
+        Ok((PyChatTemplate {}, template.into()))
+    }
+
+    fn __getnewargs__<'p>(&self, py: Python<'p>) -> PyResult<Bound<'p, PyTuple>> {
+        let role = PyDict::new(py);
+        role.set_item("name", "")?;
+        PyTuple::new(py, [PyList::new(py, [role])?, PyList::empty(py)])
+    }
+
+    #[getter]
+    fn get_prefix(self_: PyRef<Self>) -> Vec<String> {
+        getter!(self_, ChatTemplate, get_prefix().to_vec())
+    }
+
+    #[getter]
+    fn get_roles(self_: PyRef<Self>) -> Vec<String> {
+        getter!(
+            self_,
+            ChatTemplate,
+            get_roles().iter().map(|r| r.name.clone()).collect()
+        )
+    }
+}
+
 /// Sequence Processor
 ///
 /// Args:
@@ -814,6 +947,7 @@ pub fn processors(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PyRobertaProcessing>()?;
     m.add_class::<PyByteLevel>()?;
     m.add_class::<PyTemplateProcessing>()?;
+    m.add_class::<PyChatTemplate>()?;
     m.add_class::<PySequence>()?;
     Ok(())
 }
@@ -1,6 +1,8 @@
 use serde::Serialize;
 use std::collections::{hash_map::DefaultHasher, HashMap};
+use std::ffi::CString;
 use std::hash::{Hash, Hasher};
+use std::sync::Arc;
 
 use numpy::{npyffi, PyArray1, PyArrayMethods};
 use pyo3::class::basic::CompareOp;
@@ -8,23 +10,29 @@ use pyo3::exceptions;
 use pyo3::intern;
 use pyo3::prelude::*;
 use pyo3::types::*;
+use tk::decoders::DecoderWrapper;
 use tk::models::bpe::BPE;
+use tk::models::ModelWrapper;
+use tk::pre_tokenizers::PreTokenizerWrapper;
 use tk::tokenizer::{
-    Model, PaddingDirection, PaddingParams, PaddingStrategy, PostProcessor, TokenizerImpl,
-    TruncationDirection, TruncationParams, TruncationStrategy,
+    IdAssignment, Model, PaddingDirection, PaddingParams, PaddingStrategy, PairBudget,
+    SpecialTokensTruncation, StageLimits, TokenizerImpl, TruncationDirection, TruncationParams,
+    TruncationStrategy,
 };
 use tk::utils::iter::ResultShunt;
+use tk::utils::parallelism::Parallelism;
 use tokenizers as tk;
 
-use super::decoders::PyDecoder;
+use super::decoders::{PyDecoder, PyDecoderWrapper};
 use super::encoding::PyEncoding;
-use super::error::{PyError, ToPyResult};
+use super::error::{IncompatibleDecoderError, MissingUnkTokenError, PyError, ToPyResult};
 use super::models::PyModel;
 use super::normalizers::PyNormalizer;
-use super::pre_tokenizers::PyPreTokenizer;
+use super::pre_tokenizers::{PyPreTokenizer, PyPreTokenizerTypeWrapper, PyPreTokenizerWrapper};
+use super::token::PyToken;
 use super::trainers::PyTrainer;
 use crate::processors::PyPostProcessor;
-use crate::utils::{MaybeSizedIterator, PyBufferedIterator};
+use crate::utils::{MaybeSizedIterator, PyBufferedIterator, PyOffsetReferential};
 use std::collections::BTreeMap;
 
 /// Represents a token that can be be added to a :class:`~tokenizers.Tokenizer`.
@@ -59,6 +67,16 @@ use std::collections::BTreeMap;
 ///     special (:obj:`bool`, defaults to :obj:`False` with :meth:`~tokenizers.Tokenizer.add_tokens` and :obj:`False` with :meth:`~tokenizers.Tokenizer.add_special_tokens`):
 ///         Defines whether this token should be skipped when decoding.
 ///
+///     pattern (:obj:`bool`, defaults to :obj:`False`):
+///         Defines whether the content is a regex pattern, matching a whole family of tokens
+///         like ``<extra_id_[0-9]+>``. Any text matching it is extracted as a single token, with
+///         the id of this text if it is part of the vocabulary, or the id of the pattern token
+///         otherwise.
+///
+///     unicode_boundaries (:obj:`bool`, defaults to :obj:`False`):
+///         Defines whether :obj:`single_word` uses the Unicode word segmentation to find the
+///         word boundaries, which handles the scripts that do not separate words with spaces.
+///
 #[pyclass(dict, module = "tokenizers", name = "AddedToken")]
 pub struct PyAddedToken {
     pub content: String,
@@ -67,6 +85,8 @@ pub struct PyAddedToken {
     pub lstrip: Option<bool>,
     pub rstrip: Option<bool>,
     pub normalized: Option<bool>,
+    pub pattern: bool,
+    pub unicode_boundaries: bool,
 }
 impl PyAddedToken {
     pub fn from<S: Into<String>>(content: S, special: Option<bool>) -> Self {
@@ -77,6 +97,8 @@ impl PyAddedToken {
             lstrip: None,
             rstrip: None,
             normalized: None,
+            pattern: false,
+            unicode_boundaries: false,
         }
     }
 
@@ -97,6 +119,8 @@ impl PyAddedToken {
         }
 
         token
+            .pattern(self.pattern)
+            .unicode_boundaries(self.unicode_boundaries)
     }
 
     pub fn as_pydict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
@@ -109,6 +133,8 @@ impl PyAddedToken {
         dict.set_item("rstrip", token.rstrip)?;
         dict.set_item("normalized", token.normalized)?;
         dict.set_item("special", token.special)?;
+        dict.set_item("pattern", token.pattern)?;
+        dict.set_item("unicode_boundaries", token.unicode_boundaries)?;
 
         Ok(dict)
     }
@@ -123,6 +149,8 @@ impl From<tk::AddedToken> for PyAddedToken {
             rstrip: Some(token.rstrip),
             normalized: Some(token.normalized),
             special: token.special,
+            pattern: token.pattern,
+            unicode_boundaries: token.unicode_boundaries,
         }
     }
 }
@@ -130,7 +158,7 @@ impl From<tk::AddedToken> for PyAddedToken {
 #[pymethods]
 impl PyAddedToken {
     #[new]
-    #[pyo3(signature = (content=None, **kwargs), text_signature = "(self, content, single_word=False, lstrip=False, rstrip=False, normalized=True, special=False)")]
+    #[pyo3(signature = (content=None, **kwargs), text_signature = "(self, content, single_word=False, lstrip=False, rstrip=False, normalized=True, special=False, pattern=False, unicode_boundaries=False)")]
     fn __new__(content: Option<&str>, kwargs: Option<&Bound<'_, PyDict>>) -> PyResult<Self> {
         let mut token = PyAddedToken::from(content.unwrap_or(""), None);
 
@@ -143,6 +171,8 @@ impl PyAddedToken {
                     "rstrip" => token.rstrip = Some(value.extract()?),
                     "normalized" => token.normalized = Some(value.extract()?),
                     "special" => token.special = value.extract()?,
+                    "pattern" => token.pattern = value.extract()?,
+                    "unicode_boundaries" => token.unicode_boundaries = value.extract()?,
                     _ => println!("Ignored unknown kwarg option {key}"),
                 }
             }
@@ -167,6 +197,8 @@ impl PyAddedToken {
                         "rstrip" => self.rstrip = Some(value.extract()?),
                         "normalized" => self.normalized = Some(value.extract()?),
                         "special" => self.special = value.extract()?,
+                        "pattern" => self.pattern = value.extract()?,
+                        "unicode_boundaries" => self.unicode_boundaries = value.extract()?,
                         _ => {}
                     }
                 }
@@ -223,6 +255,18 @@ impl PyAddedToken {
         self.special = special;
     }
 
+    /// Get the value of the :obj:`pattern` option
+    #[getter]
+    fn get_pattern(&self) -> bool {
+        self.pattern
+    }
+
+    /// Get the value of the :obj:`unicode_boundaries` option
+    #[getter]
+    fn get_unicode_boundaries(&self) -> bool {
+        self.unicode_boundaries
+    }
+
     fn __str__(&self) -> PyResult<&str> {
         Ok(&self.content)
     }
@@ -234,14 +278,23 @@ impl PyAddedToken {
         };
 
         let token = self.get_token();
+        // The newer options only show up when set
+        let mut extra = String::new();
+        if token.pattern {
+            extra.push_str(", pattern=True");
+        }
+        if token.unicode_boundaries {
+            extra.push_str(", unicode_boundaries=True");
+        }
         Ok(format!(
-            "AddedToken(\"{}\", rstrip={}, lstrip={}, single_word={}, normalized={}, special={})",
+            "AddedToken(\"{}\", rstrip={}, lstrip={}, single_word={}, normalized={}, special={}{})",
             self.content,
             bool_to_python(token.rstrip),
             bool_to_python(token.lstrip),
             bool_to_python(token.single_word),
             bool_to_python(token.normalized),
-            bool_to_python(token.special)
+            bool_to_python(token.special),
+            extra
         ))
     }
 
@@ -261,6 +314,635 @@ impl PyAddedToken {
     }
 }
 
+fn truncation_strategy(value: &str) -> PyResult<TruncationStrategy> {
+    match value {
+        "longest_first" => Ok(TruncationStrategy::LongestFirst),
+        "only_first" => Ok(TruncationStrategy::OnlyFirst),
+        "only_second" => Ok(TruncationStrategy::OnlySecond),
+        _ => Err(PyError(format!(
+            "Unknown `strategy`: `{value}`. Use \
+             one of `longest_first`, `only_first`, `only_second` or `budget`"
+        ))
+        .into_pyerr::<exceptions::PyValueError>()),
+    }
+}
+
+// An int is a fixed number of tokens, a float a ratio of the max length
+fn truncation_budget(value: &Bound<'_, PyAny>) -> PyResult<PairBudget> {
+    if value.is_instance_of::<PyInt>() {
+        return Ok(PairBudget::Fixed(value.extract()?));
+    }
+    let ratio: f64 = value.extract()?;
+    if (0.0..=1.0).contains(&ratio) {
+        Ok(PairBudget::Ratio(ratio))
+    } else {
+        Err(exceptions::PyValueError::new_err(format!(
+            "`first_budget` must be between 0 and 1 when given as a ratio, got {ratio}"
+        )))
+    }
+}
+
+fn truncation_strategy_with_budget(
+    strategy: &str,
+    first_budget: Option<&Bound<'_, PyAny>>,
+) -> PyResult<TruncationStrategy> {
+    match (strategy, first_budget) {
+        ("budget", Some(budget)) => Ok(TruncationStrategy::Budget(truncation_budget(budget)?)),
+        ("budget", None) => Err(exceptions::PyValueError::new_err(
+            "The `budget` strategy requires a `first_budget`",
+        )),
+        (strategy, None) => truncation_strategy(strategy),
+        (_, Some(_)) => Err(exceptions::PyValueError::new_err(
+            "`first_budget` can only be used with the `budget` strategy",
+        )),
+    }
+}
+
+fn truncation_budget_object(py: Python<'_>, strategy: &TruncationStrategy) -> PyResult<PyObject> {
+    Ok(match strategy {
+        TruncationStrategy::Budget(PairBudget::Fixed(length)) => {
+            length.into_pyobject(py)?.into_any().unbind()
+        }
+        TruncationStrategy::Budget(PairBudget::Ratio(ratio)) => {
+            ratio.into_pyobject(py)?.into_any().unbind()
+        }
+        _ => py.None(),
+    })
+}
+
+fn truncation_direction(value: &str) -> PyResult<TruncationDirection> {
+    match value {
+        "left" => Ok(TruncationDirection::Left),
+        "right" => Ok(TruncationDirection::Right),
+        _ => Err(PyError(format!(
+            "Unknown `direction`: `{value}`. Use \
+             one of `left` or `right`."
+        ))
+        .into_pyerr::<exceptions::PyValueError>()),
+    }
+}
+
+fn truncation_special_tokens(value: &str) -> PyResult<SpecialTokensTruncation> {
+    match value {
+        "reserve" => Ok(SpecialTokensTruncation::Reserve),
+        "exempt" => Ok(SpecialTokensTruncation::Exempt),
+        _ => Err(PyError(format!(
+            "Unknown `special_tokens`: `{value}`. Use \
+             one of `reserve` or `exempt`."
+        ))
+        .into_pyerr::<exceptions::PyValueError>()),
+    }
+}
+
+fn added_tokens_id_assignment(value: &str, hash_range: Option<u32>) -> PyResult<IdAssignment> {
+    match (value, hash_range) {
+        ("insertion", None) => Ok(IdAssignment::Insertion),
+        ("alphabetical", None) => Ok(IdAssignment::Alphabetical),
+        ("hash_stable", Some(range)) if range > 0 => Ok(IdAssignment::HashStable { range }),
+        ("hash_stable", _) => Err(exceptions::PyValueError::new_err(
+            "The `hash_stable` id assignment requires a strictly positive `hash_range`",
+        )),
+        ("insertion" | "alphabetical", Some(_)) => Err(exceptions::PyValueError::new_err(
+            "`hash_range` can only be used with the `hash_stable` id assignment",
+        )),
+        _ => Err(PyError(format!(
+            "Unknown `id_assignment`: `{value}`. Use \
+             one of `insertion`, `alphabetical` or `hash_stable`."
+        ))
+        .into_pyerr::<exceptions::PyValueError>()),
+    }
+}
+
+/// Extract the tokens given to `add_tokens`, which are not special unless specified
+fn added_tokens(tokens: &Bound<'_, PyList>) -> PyResult<Vec<tk::AddedToken>> {
+    tokens
+        .into_iter()
+        .map(|token| {
+            if let Ok(content) = token.extract::<String>() {
+                Ok(PyAddedToken::from(content, Some(false)).get_token())
+            } else if let Ok(token) = token.extract::<PyRefMut<PyAddedToken>>() {
+                Ok(token.get_token())
+            } else {
+                Err(exceptions::PyTypeError::new_err(
+                    "Input must be a List[Union[str, AddedToken]]",
+                ))
+            }
+        })
+        .collect()
+}
+
+fn padding_direction(value: &str) -> PyResult<PaddingDirection> {
+    match value {
+        "left" => Ok(PaddingDirection::Left),
+        "right" => Ok(PaddingDirection::Right),
+        _ => Err(PyError(format!(
+            "Unknown `direction`: `{value}`. Use \
+             one of `left` or `right`"
+        ))
+        .into_pyerr::<exceptions::PyValueError>()),
+    }
+}
+
+// The params classes can still be used like the dicts that `Tokenizer.truncation` and
+// `Tokenizer.padding` used to return: `enable_truncation(**params)`, `params["max_length"]`,
+// `params == {...}`, ...
+fn params_richcmp(
+    py: Python<'_>,
+    dict: Bound<'_, PyDict>,
+    same: Option<bool>,
+    other: &Bound<'_, PyAny>,
+    op: CompareOp,
+) -> PyResult<PyObject> {
+    let equal = match same {
+        Some(equal) => equal,
+        None if other.is_instance_of::<PyDict>() => dict.eq(other)?,
+        None => return Ok(py.NotImplemented()),
+    };
+    Ok(match op {
+        CompareOp::Eq => equal.into_pyobject(py)?.to_owned().into_any().unbind(),
+        CompareOp::Ne => (!equal).into_pyobject(py)?.to_owned().into_any().unbind(),
+        _ => py.NotImplemented(),
+    })
+}
+
+fn params_repr(name: &str, dict: Bound<'_, PyDict>) -> PyResult<String> {
+    let fields = dict
+        .iter()
+        .map(|(key, value)| Ok(format!("{}={}", key, value.repr()?)))
+        .collect::<PyResult<Vec<_>>>()?;
+    Ok(format!("{}({})", name, fields.join(", ")))
+}
+
+fn params_getitem<'py>(dict: Bound<'py, PyDict>, key: &str) -> PyResult<Bound<'py, PyAny>> {
+    dict.get_item(key)?
+        .ok_or_else(|| exceptions::PyKeyError::new_err(key.to_string()))
+}
+
+/// The parameters used to truncate the encodings, as set with
+/// :meth:`~tokenizers.Tokenizer.enable_truncation`
+///
+/// Once built, these parameters can be given to a :class:`~tokenizers.Tokenizer` by assigning
+/// its :attr:`~tokenizers.Tokenizer.truncation` property.
+///
+/// Args:
+///     max_length (:obj:`int`):
+///         The max length at which to truncate
+///
+///     stride (:obj:`int`, defaults to :obj:`0`):
+///         The length of the previous first sequence to be included in the overflowing
+///         sequence
+///
+///     strategy (:obj:`str`, defaults to :obj:`longest_first`):
+///         The strategy used to truncation. Can be one of ``longest_first``, ``only_first``,
+///         ``only_second`` or ``budget``.
+///
+///     direction (:obj:`str`, defaults to :obj:`right`):
+///         Truncate direction. Can be either ``right`` or ``left``
+///
+///     first_budget (:obj:`Union[int, float]`, `optional`):
+///         Required by the ``budget`` strategy: the maximum number of tokens of the first
+///         sequence of a pair, either as a number of tokens or as a ratio of the max length.
+///         The second sequence gets the rest, giving back to the first one the room it
+///         doesn't use
+///
+///     special_tokens (:obj:`str`, defaults to :obj:`reserve`):
+///         How the special tokens added by the post-processor count against the max length.
+///         With ``reserve``, their room is kept in every encoding, including pairs and
+///         overflowing windows, so they always fit. With ``exempt``, they are not counted and
+///         get added on top of the max length
+///
+///     max_overflowing (:obj:`int`, `optional`):
+///         The maximum number of overflowing windows kept for each sequence. The windows beyond
+///         are never built, which saves time on long documents. All of them are kept by default
+#[pyclass(module = "tokenizers", name = "TruncationParams")]
+#[derive(Clone)]
+pub struct PyTruncationParams {
+    pub params: TruncationParams,
+}
+
+impl From<TruncationParams> for PyTruncationParams {
+    fn from(params: TruncationParams) -> Self {
+        Self { params }
+    }
+}
+
+impl PyTruncationParams {
+    fn as_pydict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+
+        dict.set_item("max_length", self.params.max_length)?;
+        dict.set_item("stride", self.params.stride)?;
+        dict.set_item("strategy", self.params.strategy.as_ref())?;
+        dict.set_item("direction", self.params.direction.as_ref())?;
+        if let TruncationStrategy::Budget(_) = self.params.strategy {
+            dict.set_item(
+                "first_budget",
+                truncation_budget_object(py, &self.params.strategy)?,
+            )?;
+        }
+        if self.params.special_tokens != SpecialTokensTruncation::default() {
+            dict.set_item("special_tokens", self.params.special_tokens.as_ref())?;
+        }
+        if let Some(max_overflowing) = self.params.max_overflowing {
+            dict.set_item("max_overflowing", max_overflowing)?;
+        }
+
+        Ok(dict)
+    }
+}
+
+#[pymethods]
+impl PyTruncationParams {
+    #[new]
+    #[pyo3(signature = (
+        max_length,
+        stride = 0,
+        strategy = "longest_first",
+        direction = "right",
+        first_budget = None,
+        special_tokens = "reserve",
+        max_overflowing = None
+    ))]
+    #[pyo3(
+        text_signature = "(self, max_length, stride=0, strategy='longest_first', direction='right', first_budget=None, special_tokens='reserve', max_overflowing=None)"
+    )]
+    fn new(
+        max_length: usize,
+        stride: usize,
+        strategy: &str,
+        direction: &str,
+        first_budget: Option<&Bound<'_, PyAny>>,
+        special_tokens: &str,
+        max_overflowing: Option<usize>,
+    ) -> PyResult<Self> {
+        Ok(Self {
+            params: TruncationParams {
+                max_length,
+                stride,
+                strategy: truncation_strategy_with_budget(strategy, first_budget)?,
+                direction: truncation_direction(direction)?,
+                special_tokens: truncation_special_tokens(special_tokens)?,
+                max_overflowing,
+            },
+        })
+    }
+
+    /// The max length at which to truncate
+    #[getter]
+    fn get_max_length(&self) -> usize {
+        self.params.max_length
+    }
+
+    #[setter]
+    fn set_max_length(&mut self, max_length: usize) {
+        self.params.max_length = max_length;
+    }
+
+    /// The length of the previous first sequence included in the overflowing sequence
+    #[getter]
+    fn get_stride(&self) -> usize {
+        self.params.stride
+    }
+
+    #[setter]
+    fn set_stride(&mut self, stride: usize) {
+        self.params.stride = stride;
+    }
+
+    /// The truncation strategy: ``longest_first``, ``only_first``, ``only_second`` or
+    /// ``budget``
+    #[getter]
+    fn get_strategy(&self) -> &str {
+        self.params.strategy.as_ref()
+    }
+
+    #[setter]
+    fn set_strategy(&mut self, strategy: &str) -> PyResult<()> {
+        if strategy != self.params.strategy.as_ref() {
+            self.params.strategy = truncation_strategy_with_budget(strategy, None)?;
+        }
+        Ok(())
+    }
+
+    /// The budget of the first sequence with the ``budget`` strategy, as a number of tokens
+    /// or as a ratio of the max length. Setting it switches to the ``budget`` strategy, and
+    /// :obj:`None` switches back to ``longest_first``
+    #[getter]
+    fn get_first_budget(&self, py: Python<'_>) -> PyResult<PyObject> {
+        truncation_budget_object(py, &self.params.strategy)
+    }
+
+    #[setter]
+    fn set_first_budget(&mut self, first_budget: Option<&Bound<'_, PyAny>>) -> PyResult<()> {
+        self.params.strategy = match first_budget {
+            Some(budget) => TruncationStrategy::Budget(truncation_budget(budget)?),
+            None => TruncationStrategy::LongestFirst,
+        };
+        Ok(())
+    }
+
+    /// The truncation direction: ``left`` or ``right``
+    #[getter]
+    fn get_direction(&self) -> &str {
+        self.params.direction.as_ref()
+    }
+
+    #[setter]
+    fn set_direction(&mut self, direction: &str) -> PyResult<()> {
+        self.params.direction = truncation_direction(direction)?;
+        Ok(())
+    }
+
+    /// How the special tokens count against the max length: ``reserve`` or ``exempt``
+    #[getter]
+    fn get_special_tokens(&self) -> &str {
+        self.params.special_tokens.as_ref()
+    }
+
+    #[setter]
+    fn set_special_tokens(&mut self, special_tokens: &str) -> PyResult<()> {
+        self.params.special_tokens = truncation_special_tokens(special_tokens)?;
+        Ok(())
+    }
+
+    /// The maximum number of overflowing windows kept for each sequence, or :obj:`None` to
+    /// keep them all
+    #[getter]
+    fn get_max_overflowing(&self) -> Option<usize> {
+        self.params.max_overflowing
+    }
+
+    #[setter]
+    fn set_max_overflowing(&mut self, max_overflowing: Option<usize>) {
+        self.params.max_overflowing = max_overflowing;
+    }
+
+    /// Convert these parameters to a dict, as accepted by
+    /// :meth:`~tokenizers.Tokenizer.enable_truncation`
+    ///
+    /// Returns:
+    ///     :obj:`Dict`: The parameters
+    #[pyo3(text_signature = "(self)")]
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        self.as_pydict(py)
+    }
+
+    /// The names of the parameters, which lets ``enable_truncation(**params)`` work
+    #[pyo3(text_signature = "(self)")]
+    fn keys<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyList>> {
+        Ok(self.as_pydict(py)?.keys())
+    }
+
+    /// Get the value of the given parameter, or ``default`` if it does not exist
+    #[pyo3(signature = (key, default = None), text_signature = "(self, key, default=None)")]
+    fn get<'py>(
+        &self,
+        py: Python<'py>,
+        key: &str,
+        default: Option<Bound<'py, PyAny>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        Ok(self
+            .as_pydict(py)?
+            .get_item(key)?
+            .unwrap_or_else(|| default.unwrap_or_else(|| py.None().into_bound(py))))
+    }
+
+    fn __getitem__<'py>(&self, py: Python<'py>, key: &str) -> PyResult<Bound<'py, PyAny>> {
+        params_getitem(self.as_pydict(py)?, key)
+    }
+
+    fn __richcmp__(
+        &self,
+        py: Python<'_>,
+        other: &Bound<'_, PyAny>,
+        op: CompareOp,
+    ) -> PyResult<PyObject> {
+        let same = other
+            .downcast::<PyTruncationParams>()
+            .ok()
+            .map(|other| self.params == other.borrow().params);
+        params_richcmp(py, self.as_pydict(py)?, same, other, op)
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        params_repr("TruncationParams", self.as_pydict(py)?)
+    }
+}
+
+/// The parameters used to pad the encodings, as set with
+/// :meth:`~tokenizers.Tokenizer.enable_padding`
+///
+/// Once built, these parameters can be given to a :class:`~tokenizers.Tokenizer` by assigning
+/// its :attr:`~tokenizers.Tokenizer.padding` property.
+///
+/// Args:
+///     direction (:obj:`str`, defaults to :obj:`right`):
+///         The direction in which to pad. Can be either ``right`` or ``left``
+///
+///     pad_id (:obj:`int`, defaults to 0):
+///         The id to be used when padding
+///
+///     pad_type_id (:obj:`int`, defaults to 0):
+///         The type id to be used when padding
+///
+///     pad_token (:obj:`str`, defaults to :obj:`[PAD]`):
+///         The pad token to be used when padding
+///
+///     length (:obj:`int`, `optional`):
+///         If specified, the length at which to pad. If not specified we pad using the size of
+///         the longest sequence in a batch.
+///
+///     pad_to_multiple_of (:obj:`int`, `optional`):
+///         If specified, the padding length should always snap to the next multiple of the
+///         given value.
+#[pyclass(module = "tokenizers", name = "PaddingParams")]
+#[derive(Clone)]
+pub struct PyPaddingParams {
+    pub params: PaddingParams,
+}
+
+impl From<PaddingParams> for PyPaddingParams {
+    fn from(params: PaddingParams) -> Self {
+        Self { params }
+    }
+}
+
+impl PyPaddingParams {
+    fn as_pydict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+
+        dict.set_item("length", self.get_length())?;
+        dict.set_item("pad_to_multiple_of", self.params.pad_to_multiple_of)?;
+        dict.set_item("pad_id", self.params.pad_id)?;
+        dict.set_item("pad_token", &self.params.pad_token)?;
+        dict.set_item("pad_type_id", self.params.pad_type_id)?;
+        dict.set_item("direction", self.params.direction.as_ref())?;
+
+        Ok(dict)
+    }
+}
+
+#[pymethods]
+impl PyPaddingParams {
+    #[new]
+    #[pyo3(signature = (
+        direction = "right",
+        pad_id = 0,
+        pad_type_id = 0,
+        pad_token = String::from("[PAD]"),
+        length = None,
+        pad_to_multiple_of = None
+    ))]
+    #[pyo3(
+        text_signature = "(self, direction='right', pad_id=0, pad_type_id=0, pad_token='[PAD]', length=None, pad_to_multiple_of=None)"
+    )]
+    fn new(
+        direction: &str,
+        pad_id: u32,
+        pad_type_id: u32,
+        pad_token: String,
+        length: Option<usize>,
+        pad_to_multiple_of: Option<usize>,
+    ) -> PyResult<Self> {
+        let mut params = Self {
+            params: PaddingParams {
+                direction: padding_direction(direction)?,
+                pad_id,
+                pad_type_id,
+                pad_token,
+                pad_to_multiple_of,
+                ..Default::default()
+            },
+        };
+        params.set_length(length);
+        Ok(params)
+    }
+
+    /// The direction in which to pad: ``left`` or ``right``
+    #[getter]
+    fn get_direction(&self) -> &str {
+        self.params.direction.as_ref()
+    }
+
+    #[setter]
+    fn set_direction(&mut self, direction: &str) -> PyResult<()> {
+        self.params.direction = padding_direction(direction)?;
+        Ok(())
+    }
+
+    /// The id used when padding
+    #[getter]
+    fn get_pad_id(&self) -> u32 {
+        self.params.pad_id
+    }
+
+    #[setter]
+    fn set_pad_id(&mut self, pad_id: u32) {
+        self.params.pad_id = pad_id;
+    }
+
+    /// The type id used when padding
+    #[getter]
+    fn get_pad_type_id(&self) -> u32 {
+        self.params.pad_type_id
+    }
+
+    #[setter]
+    fn set_pad_type_id(&mut self, pad_type_id: u32) {
+        self.params.pad_type_id = pad_type_id;
+    }
+
+    /// The pad token used when padding
+    #[getter]
+    fn get_pad_token(&self) -> &str {
+        &self.params.pad_token
+    }
+
+    #[setter]
+    fn set_pad_token(&mut self, pad_token: String) {
+        self.params.pad_token = pad_token;
+    }
+
+    /// The length at which to pad, or :obj:`None` to pad to the longest sequence in a batch
+    #[getter]
+    fn get_length(&self) -> Option<usize> {
+        match self.params.strategy {
+            PaddingStrategy::BatchLongest => None,
+            PaddingStrategy::Fixed(size) => Some(size),
+        }
+    }
+
+    #[setter]
+    fn set_length(&mut self, length: Option<usize>) {
+        self.params.strategy = match length {
+            Some(length) => PaddingStrategy::Fixed(length),
+            None => PaddingStrategy::BatchLongest,
+        };
+    }
+
+    /// The multiple the padding length snaps to, if any
+    #[getter]
+    fn get_pad_to_multiple_of(&self) -> Option<usize> {
+        self.params.pad_to_multiple_of
+    }
+
+    #[setter]
+    fn set_pad_to_multiple_of(&mut self, pad_to_multiple_of: Option<usize>) {
+        self.params.pad_to_multiple_of = pad_to_multiple_of;
+    }
+
+    /// Convert these parameters to a dict, as accepted by
+    /// :meth:`~tokenizers.Tokenizer.enable_padding`
+    ///
+    /// Returns:
+    ///     :obj:`Dict`: The parameters
+    #[pyo3(text_signature = "(self)")]
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        self.as_pydict(py)
+    }
+
+    /// The names of the parameters, which lets ``enable_padding(**params)`` work
+    #[pyo3(text_signature = "(self)")]
+    fn keys<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyList>> {
+        Ok(self.as_pydict(py)?.keys())
+    }
+
+    /// Get the value of the given parameter, or ``default`` if it does not exist
+    #[pyo3(signature = (key, default = None), text_signature = "(self, key, default=None)")]
+    fn get<'py>(
+        &self,
+        py: Python<'py>,
+        key: &str,
+        default: Option<Bound<'py, PyAny>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        Ok(self
+            .as_pydict(py)?
+            .get_item(key)?
+            .unwrap_or_else(|| default.unwrap_or_else(|| py.None().into_bound(py))))
+    }
+
+    fn __getitem__<'py>(&self, py: Python<'py>, key: &str) -> PyResult<Bound<'py, PyAny>> {
+        params_getitem(self.as_pydict(py)?, key)
+    }
+
+    fn __richcmp__(
+        &self,
+        py: Python<'_>,
+        other: &Bound<'_, PyAny>,
+        op: CompareOp,
+    ) -> PyResult<PyObject> {
+        let same = other
+            .downcast::<PyPaddingParams>()
+            .ok()
+            .map(|other| self.params == other.borrow().params);
+        params_richcmp(py, self.as_pydict(py)?, same, other, op)
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        params_repr("PaddingParams", self.as_pydict(py)?)
+    }
+}
+
 struct TextInputSequence<'s>(tk::InputSequence<'s>);
 impl<'s> FromPyObject<'s> for TextInputSequence<'s> {
     fn extract_bound(ob: &Bound<'s, PyAny>) -> PyResult<Self> {
@@ -460,6 +1142,16 @@ type Tokenizer = TokenizerImpl<PyModel, PyNormalizer, PyPreTokenizer, PyPostProc
 /// A :obj:`Tokenizer` works as a pipeline. It processes some raw text as input
 /// and outputs an :class:`~tokenizers.Encoding`.
 ///
+/// A :obj:`Tokenizer` can be shared by several threads. The methods encoding or decoding
+/// batches release the GIL, and work on a snapshot of the tokenizer taken when they start:
+/// the mutations made meanwhile by other threads, like :meth:`add_tokens`,
+/// :meth:`enable_padding` or setting :attr:`normalizer`, never fail nor wait for them, and
+/// an encode in flight sees either all of a mutation, or none of it. Only the training is
+/// exclusive: using the tokenizer from another thread while it trains raises an error. The
+/// components themselves are shared with the snapshots, so updating one of their attributes
+/// in place, like ``tokenizer.normalizer.lowercase = False``, applies right away to the
+/// encodes in flight. Replace the component instead to update it atomically.
+///
 /// Args:
 ///     model (:class:`~tokenizers.models.Model`):
 ///         The core algorithm that this :obj:`Tokenizer` should be using.
@@ -468,20 +1160,275 @@ type Tokenizer = TokenizerImpl<PyModel, PyNormalizer, PyPreTokenizer, PyPostProc
 #[derive(Clone, Serialize)]
 #[serde(transparent)]
 pub struct PyTokenizer {
-    pub(crate) tokenizer: Tokenizer,
+    /// The current state of the tokenizer, shared with the encodes in flight.
+    ///
+    /// The methods releasing the GIL take a snapshot of this state, and release their borrow
+    /// of the `PyTokenizer` before running. The mutations then never conflict with them:
+    /// they copy the state while it's shared (see [`PyTokenizer::tokenizer_mut`]), so each
+    /// encode sees either all of a mutation, or none of it.
+    pub(crate) tokenizer: Arc<Tokenizer>,
+    /// Whether to warn about the inputs that look already tokenized. This replaces the
+    /// logging of the core tokenizer, so that each input gets a single warning.
+    #[serde(skip)]
+    warn_on_artifacts: bool,
 }
 
 impl PyTokenizer {
     fn new(tokenizer: Tokenizer) -> Self {
-        PyTokenizer { tokenizer }
+        PyTokenizer {
+            tokenizer: Arc::new(tokenizer),
+            warn_on_artifacts: false,
+        }
+    }
+
+    /// The state of the tokenizer to mutate, copied first if an encode in flight uses it
+    fn tokenizer_mut(&mut self) -> &mut Tokenizer {
+        Arc::make_mut(&mut self.tokenizer)
+    }
+
+    /// Take a snapshot of the state of the tokenizer, releasing the borrow of `slf`
+    fn snapshot(slf: PyRef<'_, Self>) -> Arc<Tokenizer> {
+        slf.tokenizer.clone()
     }
 
     fn from_model(model: PyModel) -> Self {
         PyTokenizer::new(TokenizerImpl::new(model))
     }
-}
 
-#[pymethods]
+    /// Emit a warning for each of the inputs that look already tokenized, when enabled
+    fn warn_artifacts(&self, py: Python<'_>, inputs: &[tk::EncodeInput]) -> PyResult<()> {
+        if !self.warn_on_artifacts {
+            return Ok(());
+        }
+        let user_warning = py.get_type::<exceptions::PyUserWarning>();
+        for input in inputs {
+            let warning = match input {
+                tk::EncodeInput::Single(sequence) => sequence.artifacts_warning(),
+                tk::EncodeInput::Dual(sequence, pair) => sequence
+                    .artifacts_warning()
+                    .or_else(|| pair.artifacts_warning()),
+            };
+            if let Some(warning) = warning {
+                PyErr::warn(py, &user_warning, &CString::new(warning)?, 1)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Extract the input of the encode methods from the given sequence and pair
+    fn encode_input<'s>(
+        sequence: &Bound<'s, PyAny>,
+        pair: Option<&Bound<'s, PyAny>>,
+        is_pretokenized: bool,
+    ) -> PyResult<tk::EncodeInput<'s>> {
+        let extract = |sequence: &Bound<'s, PyAny>| -> PyResult<tk::InputSequence<'s>> {
+            Ok(if is_pretokenized {
+                sequence.extract::<PreTokenizedInputSequence>()?.into()
+            } else {
+                sequence.extract::<TextInputSequence>()?.into()
+            })
+        };
+        Ok(match pair {
+            Some(pair) => tk::EncodeInput::Dual(extract(sequence)?, extract(pair)?),
+            None => tk::EncodeInput::Single(extract(sequence)?),
+        })
+    }
+}
+
+/// Flatten the given pre-tokenizer, returning `None` if it contains a custom one
+fn pre_tokenizer_leaves(pre_tokenizer: &PyPreTokenizer) -> Option<Vec<PreTokenizerWrapper>> {
+    fn flatten(pre_tokenizer: &PreTokenizerWrapper, leaves: &mut Vec<PreTokenizerWrapper>) {
+        match pre_tokenizer {
+            PreTokenizerWrapper::Sequence(seq) => {
+                seq.as_ref().iter().for_each(|p| flatten(p, leaves))
+            }
+            p => leaves.push(p.clone()),
+        }
+    }
+
+    let wrappers = match &pre_tokenizer.pretok {
+        PyPreTokenizerTypeWrapper::Sequence(seq) => seq.clone(),
+        PyPreTokenizerTypeWrapper::Single(p) => vec![p.clone()],
+    };
+    let mut leaves = vec![];
+    for wrapper in wrappers {
+        match &*wrapper.read().unwrap() {
+            PyPreTokenizerWrapper::Custom(_) => return None,
+            PyPreTokenizerWrapper::Wrapped(p) => flatten(p, &mut leaves),
+        }
+    }
+    Some(leaves)
+}
+
+/// Flatten the given decoder, returning `None` if it is a custom one
+fn decoder_leaves(decoder: &PyDecoder) -> Option<Vec<DecoderWrapper>> {
+    fn flatten(decoder: &DecoderWrapper, leaves: &mut Vec<DecoderWrapper>) {
+        match decoder {
+            DecoderWrapper::Sequence(seq) => {
+                seq.get_decoders().iter().for_each(|d| flatten(d, leaves))
+            }
+            d => leaves.push(d.clone()),
+        }
+    }
+
+    match &decoder.decoder {
+        PyDecoderWrapper::Custom(_) => None,
+        PyDecoderWrapper::Wrapped(d) => {
+            let mut leaves = vec![];
+            flatten(&d.read().unwrap(), &mut leaves);
+            Some(leaves)
+        }
+    }
+}
+
+/// Check that the model can represent the unknown token it needs, and that the decoder
+/// reverts what the model and the pre-tokenizer produce. The custom components are trusted.
+fn check_components(
+    model: &PyModel,
+    pre_tokenizer: Option<&PyPreTokenizer>,
+    decoder: Option<&PyDecoder>,
+) -> PyResult<()> {
+    let guard = model.model.read().unwrap();
+    // A hybrid model uses the unknown token and the decoders of its fallback
+    let mut model = &*guard;
+    while let ModelWrapper::Hybrid(hybrid) = model {
+        model = hybrid.fallback();
+    }
+    let unk_token = match model {
+        ModelWrapper::BPE(bpe) => bpe.unk_token.clone(),
+        ModelWrapper::WordPiece(wp) => Some(wp.unk_token.clone()),
+        ModelWrapper::WordLevel(wl) => Some(wl.unk_token.clone()),
+        ModelWrapper::MaxMatch(mm) => Some(mm.unk_token.clone()),
+        ModelWrapper::Unigram(unigram) => {
+            if unigram.unk_id().is_none() && !unigram.byte_fallback() {
+                return Err(MissingUnkTokenError::new_err(
+                    "The Unigram model needs an `unk_id` to encode unknown characters",
+                ));
+            }
+            None
+        }
+        ModelWrapper::Hybrid(_) => None,
+    };
+    if let Some(unk_token) = unk_token {
+        if model.token_to_id(&unk_token).is_none() {
+            return Err(MissingUnkTokenError::new_err(format!(
+                "The unknown token `{unk_token}` is not part of the vocabulary"
+            )));
+        }
+    }
+
+    let decoders = match decoder {
+        Some(decoder) => match decoder_leaves(decoder) {
+            Some(decoders) => decoders,
+            None => return Ok(()),
+        },
+        None => vec![],
+    };
+    let incompatible = |message: String| Err(IncompatibleDecoderError::new_err(message));
+
+    let byte_fallback = match model {
+        ModelWrapper::BPE(bpe) => bpe.byte_fallback,
+        ModelWrapper::Unigram(unigram) => unigram.byte_fallback(),
+        _ => false,
+    };
+    if byte_fallback
+        && !decoders
+            .iter()
+            .any(|d| matches!(d, DecoderWrapper::ByteFallback(_)))
+    {
+        return incompatible(
+            "The model uses byte fallback, which needs a ByteFallback decoder".into(),
+        );
+    }
+    for d in &decoders {
+        match (model, d) {
+            (ModelWrapper::WordPiece(wp), DecoderWrapper::WordPiece(d))
+                if wp.continuing_subword_prefix != d.prefix =>
+            {
+                return incompatible(format!(
+                    "The WordPiece decoder uses the prefix `{}`, but the model uses `{}`",
+                    d.prefix, wp.continuing_subword_prefix
+                ));
+            }
+            (ModelWrapper::BPE(bpe), DecoderWrapper::BPE(d))
+                if bpe
+                    .end_of_word_suffix
+                    .as_ref()
+                    .is_some_and(|suffix| *suffix != d.suffix) =>
+            {
+                return incompatible(format!(
+                    "The BPE decoder uses the suffix `{}`, but the model uses `{}`",
+                    d.suffix,
+                    bpe.end_of_word_suffix.as_deref().unwrap_or_default()
+                ));
+            }
+            (ModelWrapper::WordPiece(wp), DecoderWrapper::BPE(d))
+                if wp
+                    .end_of_word_suffix
+                    .as_ref()
+                    .is_some_and(|suffix| *suffix != d.suffix) =>
+            {
+                return incompatible(format!(
+                    "The BPE decoder uses the suffix `{}`, but the model uses `{}`",
+                    d.suffix,
+                    wp.end_of_word_suffix.as_deref().unwrap_or_default()
+                ));
+            }
+            (ModelWrapper::BPE(bpe), DecoderWrapper::BPE(d))
+                if d.prefix.is_some() && bpe.continuing_subword_prefix != d.prefix =>
+            {
+                return incompatible(format!(
+                    "The BPE decoder uses the prefix `{}`, but the model uses `{}`",
+                    d.prefix.as_deref().unwrap_or_default(),
+                    bpe.continuing_subword_prefix.as_deref().unwrap_or_default()
+                ));
+            }
+            (ModelWrapper::WordPiece(wp), DecoderWrapper::BPE(d))
+                if d.prefix
+                    .as_ref()
+                    .is_some_and(|prefix| *prefix != wp.continuing_subword_prefix) =>
+            {
+                return incompatible(format!(
+                    "The BPE decoder uses the prefix `{}`, but the model uses `{}`",
+                    d.prefix.as_deref().unwrap_or_default(),
+                    wp.continuing_subword_prefix
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    let pre_tokenizers = pre_tokenizer
+        .and_then(pre_tokenizer_leaves)
+        .unwrap_or_default();
+    for p in &pre_tokenizers {
+        match p {
+            PreTokenizerWrapper::ByteLevel(_)
+                if !decoders
+                    .iter()
+                    .any(|d| matches!(d, DecoderWrapper::ByteLevel(_))) =>
+            {
+                return incompatible(
+                    "The ByteLevel pre-tokenizer needs a ByteLevel decoder".into(),
+                );
+            }
+            PreTokenizerWrapper::Metaspace(m) => {
+                let replacement = m.get_replacement();
+                if !decoders.iter().any(|d| {
+                    matches!(d, DecoderWrapper::Metaspace(d) if d.get_replacement() == replacement)
+                }) {
+                    return incompatible(format!(
+                        "The Metaspace pre-tokenizer needs a Metaspace decoder using `{replacement}`"
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+#[pymethods]
 impl PyTokenizer {
     #[new]
     #[pyo3(text_signature = "(self, model)")]
@@ -520,6 +1467,60 @@ impl PyTokenizer {
         PyTuple::new(py, vec![model])
     }
 
+    /// Instantiate a new :class:`~tokenizers.Tokenizer` from its components, checking that
+    /// they work together.
+    ///
+    /// Contrarily to setting each component as an attribute, misconfigurations are reported
+    /// right away: a :class:`~tokenizers.MissingUnkTokenError` is raised when the unknown
+    /// token is missing from the vocabulary of the model, and an
+    /// :class:`~tokenizers.IncompatibleDecoderError` when the decoder can't revert what the
+    /// model and the pre-tokenizer produce (a :class:`~tokenizers.pre_tokenizers.ByteLevel`
+    /// pre-tokenizer without its decoder, a different WordPiece prefix, a byte fallback model
+    /// without :class:`~tokenizers.decoders.ByteFallback`...). Both are
+    /// :class:`~tokenizers.PipelineError`, a :obj:`ValueError`. Custom components are trusted.
+    ///
+    /// Args:
+    ///     model (:class:`~tokenizers.models.Model`):
+    ///         The model
+    ///
+    ///     normalizer (:class:`~tokenizers.normalizers.Normalizer`, `optional`):
+    ///         The normalizer
+    ///
+    ///     pre_tokenizer (:class:`~tokenizers.pre_tokenizers.PreTokenizer`, `optional`):
+    ///         The pre-tokenizer
+    ///
+    ///     post_processor (:class:`~tokenizers.processors.PostProcessor`, `optional`):
+    ///         The post-processor
+    ///
+    ///     decoder (:class:`~tokenizers.decoders.Decoder`, `optional`):
+    ///         The decoder
+    ///
+    /// Returns:
+    ///     :class:`~tokenizers.Tokenizer`: The new tokenizer
+    #[staticmethod]
+    #[pyo3(signature = (model, normalizer = None, pre_tokenizer = None, post_processor = None, decoder = None))]
+    #[pyo3(
+        text_signature = "(model, normalizer=None, pre_tokenizer=None, post_processor=None, decoder=None)"
+    )]
+    fn from_components(
+        model: PyRef<PyModel>,
+        normalizer: Option<PyRef<PyNormalizer>>,
+        pre_tokenizer: Option<PyRef<PyPreTokenizer>>,
+        post_processor: Option<PyRef<PyPostProcessor>>,
+        decoder: Option<PyRef<PyDecoder>>,
+    ) -> PyResult<Self> {
+        check_components(&model, pre_tokenizer.as_deref(), decoder.as_deref())?;
+
+        let mut tokenizer = Self::from_model(model.clone());
+        tokenizer
+            .tokenizer_mut()
+            .with_normalizer(normalizer.map(|n| n.clone()))
+            .with_pre_tokenizer(pre_tokenizer.map(|p| p.clone()))
+            .with_post_processor(post_processor.map(|p| p.clone()))
+            .with_decoder(decoder.map(|d| d.clone()));
+        Ok(tokenizer)
+    }
+
     /// Instantiate a new :class:`~tokenizers.Tokenizer` from the given JSON string.
     ///
     /// Args:
@@ -552,6 +1553,51 @@ impl PyTokenizer {
         Ok(Self::new(tokenizer?))
     }
 
+    /// Instantiate a new :class:`~tokenizers.Tokenizer` from the binary file at the given
+    /// path, saved with :meth:`~tokenizers.Tokenizer.save_mmap`.
+    ///
+    /// The file is memory mapped, and the vocab of the model, with the merges of a
+    /// :class:`~tokenizers.models.BPE`, is read in place instead of being loaded, sharing its
+    /// memory with every process using the same file. The file must not be modified, nor
+    /// truncated, while the tokenizer or any of its copies is in use: this would crash the
+    /// process, or give it corrupted tokens.
+    ///
+    /// Args:
+    ///     path (:obj:`str`):
+    ///         A path to a local binary file representing a previously serialized
+    ///         :class:`~tokenizers.Tokenizer`
+    ///
+    /// Returns:
+    ///     :class:`~tokenizers.Tokenizer`: The new tokenizer
+    #[staticmethod]
+    #[pyo3(text_signature = "(path)")]
+    fn from_file_mmap(path: &str) -> PyResult<Self> {
+        // SAFETY: The contract of the mapped file is documented for the Python users, who
+        // can't express it otherwise
+        let loaded = ToPyResult(unsafe { tk::Tokenizer::from_file_mmap(path) }).into_py()?;
+        let mut tokenizer = TokenizerImpl::new(PyModel::from(loaded.get_model().clone()));
+        tokenizer
+            .with_normalizer(loaded.get_normalizer().cloned())
+            .with_pre_tokenizer(loaded.get_pre_tokenizer().cloned())
+            .with_post_processor(loaded.get_post_processor().cloned())
+            .with_decoder(loaded.get_decoder().cloned())
+            .with_padding(loaded.get_padding().cloned());
+        ToPyResult(tokenizer.with_truncation(loaded.get_truncation().cloned())).into_py()?;
+
+        // The ids of the added tokens are given back by adding them in order
+        let mut added_tokens = loaded
+            .get_added_tokens_decoder()
+            .into_iter()
+            .collect::<Vec<_>>();
+        added_tokens.sort_unstable_by_key(|(id, _)| *id);
+        let added_tokens = added_tokens
+            .into_iter()
+            .map(|(_, token)| token)
+            .collect::<Vec<_>>();
+        ToPyResult(tokenizer.try_add_tokens(&added_tokens)).into_py()?;
+        Ok(Self::new(tokenizer))
+    }
+
     /// Instantiate a new :class:`~tokenizers.Tokenizer` from the given buffer.
     ///
     /// Args:
@@ -568,7 +1614,7 @@ impl PyTokenizer {
                 "Cannot instantiate Tokenizer from buffer: {e}"
             ))
         })?;
-        Ok(Self { tokenizer })
+        Ok(Self::new(tokenizer))
     }
 
     /// Instantiate a new :class:`~tokenizers.Tokenizer` from an existing file on the
@@ -614,6 +1660,177 @@ impl PyTokenizer {
         Ok(Self::new(tokenizer?))
     }
 
+    /// Chain two tokenizers: the tokens found by the model of the first one become the
+    /// pre-tokens fed to the model of the second one, like the morphemes of a morphological
+    /// tokenizer fed to a :class:`~tokenizers.models.BPE`.
+    ///
+    /// The text goes through the normalizer and the pre-tokenizer of :obj:`first`, then
+    /// through its model, with the normalizer of :obj:`second` applied to each of the tokens,
+    /// and finally through the pre-tokenizer and the model of :obj:`second`. The offsets refer
+    /// to the original text, and each token of :obj:`first` is a word of its own.
+    ///
+    /// Everything else comes from :obj:`second`, like the added tokens, the post-processor
+    /// and the decoder. Neither tokenizer can use custom components.
+    ///
+    /// Args:
+    ///     first (:class:`~tokenizers.Tokenizer`):
+    ///         The tokenizer splitting the text into the pre-tokens of the second one
+    ///
+    ///     second (:class:`~tokenizers.Tokenizer`):
+    ///         The tokenizer producing the final tokens
+    ///
+    /// Returns:
+    ///     :class:`~tokenizers.Tokenizer`: The new tokenizer
+    #[staticmethod]
+    #[pyo3(text_signature = "(first, second)")]
+    fn chain(first: PyRef<Self>, second: PyRef<Self>) -> PyResult<Self> {
+        let to_tokenizer = |tokenizer: &Self| -> PyResult<tk::Tokenizer> {
+            let json = ToPyResult(tokenizer.tokenizer.to_string(false)).into_py()?;
+            ToPyResult(json.parse()).into_py()
+        };
+        let chained = tk::Tokenizer::chain(to_tokenizer(&first)?, to_tokenizer(&second)?);
+        let json = ToPyResult(chained.to_string(false)).into_py()?;
+        Self::from_str(&json)
+    }
+
+    /// Instantiate a new :class:`~tokenizers.Tokenizer` from a subword-nmt :obj:`codes` file.
+    ///
+    /// The model is the :class:`~tokenizers.models.BPE` of
+    /// :meth:`~tokenizers.models.BPE.from_subword_nmt`. Like subword-nmt, the tokenizer
+    /// splits the words on whitespace, and its decoder removes the :obj:`</w>` suffix.
+    ///
+    /// Args:
+    ///     codes (:obj:`str`):
+    ///         The path to a :obj:`codes` file, as written by :obj:`learn_bpe.py`
+    ///
+    /// Returns:
+    ///     :class:`~tokenizers.Tokenizer`: The new tokenizer
+    #[staticmethod]
+    #[pyo3(text_signature = "(codes)")]
+    fn from_subword_nmt(codes: &str) -> PyResult<Self> {
+        let loaded = ToPyResult(tk::Tokenizer::from_subword_nmt(codes)).into_py()?;
+        let json = ToPyResult(loaded.to_string(false)).into_py()?;
+        Self::from_str(&json)
+    }
+
+    /// Get the :obj:`vocab.json` and :obj:`merges.txt` files used by GPT-2, still required
+    /// by some runtimes, for a tokenizer using a :class:`~tokenizers.models.BPE` without
+    /// :obj:`continuing_subword_prefix` nor :obj:`end_of_word_suffix`.
+    ///
+    /// The tokens of a tokenizer using :class:`~tokenizers.pre_tokenizers.ByteLevel` are
+    /// written as they are. The other ones are converted to the characters of
+    /// :class:`~tokenizers.pre_tokenizers.ByteLevel`, with new merges building the
+    /// multi-bytes characters, and new ids for the missing bytes. The tokens of the
+    /// :obj:`byte_fallback`, like :obj:`<0x41>`, become the character of their byte.
+    ///
+    /// Returns:
+    ///     :obj:`Dict[str, bytes]`: The content of each file, by its name
+    #[pyo3(text_signature = "(self)")]
+    fn gpt2_files<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let json = ToPyResult(self.tokenizer.to_string(false)).into_py()?;
+        let tokenizer: tk::Tokenizer = ToPyResult(json.parse()).into_py()?;
+        let files = ToPyResult(tokenizer.gpt2_files()).into_py()?;
+        let dict = PyDict::new(py);
+        for file in files {
+            dict.set_item(file.name, PyBytes::new(py, &file.content))?;
+        }
+        Ok(dict)
+    }
+
+    /// Check that the embedding table of a model matches this :class:`~tokenizers.Tokenizer`,
+    /// before training them together
+    ///
+    /// This verifies that the ids of the model and of the added tokens, which come after the
+    /// base vocabulary, all have a row in the table and fit in the integer type storing them,
+    /// that no token uses the reserved rows, and that the padding id has a row, which is the
+    /// padding row of the table if it has one. A table with more rows than ids is fine.
+    ///
+    /// Args:
+    ///     size (:obj:`int`):
+    ///         The number of rows of the embedding table
+    ///
+    ///     id_dtype (:obj:`str`, `optional`):
+    ///         The integer type storing the ids, like :obj:`"uint16"` for compact datasets
+    ///
+    ///     reserved_ids (:obj:`List[int]`, `optional`):
+    ///         The rows reserved for something else, that no token may use
+    ///
+    ///     padding_idx (:obj:`int`, `optional`):
+    ///         The padding row of the table, like the ``padding_idx`` of a PyTorch ``Embedding``
+    ///
+    /// Returns:
+    ///     :obj:`List[str]`: The description of each issue found, with how to fix it
+    #[pyo3(signature = (size, id_dtype = None, reserved_ids = None, padding_idx = None))]
+    #[pyo3(text_signature = "(self, size, id_dtype=None, reserved_ids=None, padding_idx=None)")]
+    fn check_embeddings(
+        &self,
+        size: usize,
+        id_dtype: Option<&str>,
+        reserved_ids: Option<Vec<u32>>,
+        padding_idx: Option<u32>,
+    ) -> PyResult<Vec<String>> {
+        let id_dtype = id_dtype
+            .map(|dtype| match dtype {
+                "int8" => Ok(tk::IdDtype::Int8),
+                "uint8" => Ok(tk::IdDtype::UInt8),
+                "int16" => Ok(tk::IdDtype::Int16),
+                "uint16" => Ok(tk::IdDtype::UInt16),
+                "int32" => Ok(tk::IdDtype::Int32),
+                "uint32" => Ok(tk::IdDtype::UInt32),
+                "int64" => Ok(tk::IdDtype::Int64),
+                _ => Err(exceptions::PyValueError::new_err(format!(
+                    "Unknown id dtype `{dtype}`, expected an integer type like `int32`"
+                ))),
+            })
+            .transpose()?;
+        let table = tk::EmbeddingTable {
+            size,
+            id_dtype,
+            reserved_ids: reserved_ids.unwrap_or_default(),
+            padding_idx,
+        };
+
+        let json = ToPyResult(self.tokenizer.to_string(false)).into_py()?;
+        let tokenizer: tk::Tokenizer = ToPyResult(json.parse()).into_py()?;
+        Ok(tokenizer
+            .check_embeddings(&table)
+            .iter()
+            .map(|issue| issue.to_string())
+            .collect())
+    }
+
+    /// Describe the components of this :class:`~tokenizers.Tokenizer` and their parameters,
+    /// in execution order, as a graph.
+    ///
+    /// Each node has an :obj:`id`, a :obj:`stage`, like :obj:`normalizer`, the :obj:`type` of
+    /// the component, its :obj:`params`, and the :obj:`parent` :obj:`Sequence` containing it,
+    /// if any. The edges link each component to the next one to run, the decoder following
+    /// the model. Unlike the serialized tokenizer, the structure is the same for all the
+    /// components, which makes pipelines easy to render or compare.
+    ///
+    /// Args:
+    ///     format (:obj:`str`, defaults to :obj:`"json"`):
+    ///         Either :obj:`"json"`, or :obj:`"dot"` for the DOT language of Graphviz
+    ///
+    /// Returns:
+    ///     :obj:`str`: The graph, in the given format
+    #[pyo3(signature = (format = "json"))]
+    #[pyo3(text_signature = "(self, format=\"json\")")]
+    fn to_pipeline_graph(&self, format: &str) -> PyResult<String> {
+        let json = ToPyResult(self.tokenizer.to_string(false)).into_py()?;
+        let tokenizer: tk::Tokenizer = ToPyResult(json.parse()).into_py()?;
+        let graph = ToPyResult(tokenizer.to_pipeline_graph()).into_py()?;
+        match format {
+            "json" => serde_json::to_string(&graph).map_err(|e| {
+                exceptions::PyException::new_err(format!("Error while serializing the graph: {e}"))
+            }),
+            "dot" => Ok(graph.to_dot()),
+            _ => Err(exceptions::PyValueError::new_err(format!(
+                "Unknown format `{format}`, expected one of `json` or `dot`"
+            ))),
+        }
+    }
+
     /// Gets a serialized string representing this :class:`~tokenizers.Tokenizer`.
     ///
     /// Args:
@@ -642,6 +1859,21 @@ impl PyTokenizer {
         ToPyResult(self.tokenizer.save(path, pretty)).into()
     }
 
+    /// Save the :class:`~tokenizers.Tokenizer` to a binary file at the given path, to be
+    /// loaded with :meth:`~tokenizers.Tokenizer.from_file_mmap`.
+    ///
+    /// The vocab of a :class:`~tokenizers.models.BPE`, :class:`~tokenizers.models.WordLevel`
+    /// or :class:`~tokenizers.models.WordPiece`, and the merges of a BPE, are written as tables
+    /// that can be read in place, everything else is written as JSON.
+    ///
+    /// Args:
+    ///     path (:obj:`str`):
+    ///         A path to a file in which to save the serialized tokenizer.
+    #[pyo3(text_signature = "(self, path)")]
+    fn save_mmap(&self, path: &str) -> PyResult<()> {
+        ToPyResult(self.tokenizer.save_mmap(path)).into()
+    }
+
     fn __repr__(&self) -> PyResult<String> {
         crate::utils::serde_pyo3::repr(self)
             .map_err(|e| exceptions::PyException::new_err(e.to_string()))
@@ -653,13 +1885,19 @@ impl PyTokenizer {
     }
 
     /// Return the number of special tokens that would be added for single/pair sentences.
-    /// :param is_pair: Boolean indicating if the input would be a single sentence or a pair
-    /// :return:
+    ///
+    /// With a :class:`~tokenizers.processors.Sequence` of post-processors, this is the total
+    /// added by all of them. The truncation reserves this room when adding the special tokens.
+    ///
+    /// Args:
+    ///     is_pair (:obj:`bool`):
+    ///         Whether the input would be a pair of sequences
+    ///
+    /// Returns:
+    ///     :obj:`int`: The number of tokens to add
     #[pyo3(text_signature = "(self, is_pair)")]
     fn num_special_tokens_to_add(&self, is_pair: bool) -> usize {
-        self.tokenizer
-            .get_post_processor()
-            .map_or(0, |p| p.added_tokens(is_pair))
+        self.tokenizer.num_special_tokens_to_add(is_pair)
     }
 
     /// Get the underlying vocabulary
@@ -717,14 +1955,31 @@ impl PyTokenizer {
     ///         sequence
     ///
     ///     strategy (:obj:`str`, `optional`, defaults to :obj:`longest_first`):
-    ///         The strategy used to truncation. Can be one of ``longest_first``, ``only_first`` or
-    ///         ``only_second``.
+    ///         The strategy used to truncation. Can be one of ``longest_first``, ``only_first``,
+    ///         ``only_second`` or ``budget``.
     ///
     ///     direction (:obj:`str`, defaults to :obj:`right`):
     ///         Truncate direction
+    ///
+    ///     first_budget (:obj:`Union[int, float]`, `optional`):
+    ///         Required by the ``budget`` strategy: the maximum number of tokens of the first
+    ///         sequence of a pair, either as a number of tokens or as a ratio of the max length.
+    ///         The second sequence gets the rest, giving back to the first one the room it
+    ///         doesn't use
+    ///
+    ///     special_tokens (:obj:`str`, defaults to :obj:`reserve`):
+    ///         How the special tokens added by the post-processor count against the max length.
+    ///         With ``reserve``, their room is kept in every encoding, including pairs and
+    ///         overflowing windows, so they always fit. With ``exempt``, they are not counted and
+    ///         get added on top of the max length
+    ///
+    ///     max_overflowing (:obj:`int`, `optional`):
+    ///         The maximum number of overflowing windows kept for each sequence. The windows
+    ///         beyond are never built, which saves time on long documents. All of them are kept
+    ///         by default
     #[pyo3(signature = (max_length, **kwargs))]
     #[pyo3(
-        text_signature = "(self, max_length, stride=0, strategy='longest_first', direction='right')"
+        text_signature = "(self, max_length, stride=0, strategy='longest_first', direction='right', first_budget=None, special_tokens='reserve', max_overflowing=None)"
     )]
     fn enable_truncation(
         &mut self,
@@ -737,73 +1992,152 @@ impl PyTokenizer {
         };
 
         if let Some(kwargs) = kwargs {
+            let mut strategy = String::from("longest_first");
+            let mut first_budget = None;
             for (key, value) in kwargs {
                 let key: String = key.extract()?;
                 match key.as_ref() {
                     "stride" => params.stride = value.extract()?,
-                    "strategy" => {
-                        let value: String = value.extract()?;
-                        params.strategy = match value.as_ref() {
-                            "longest_first" => Ok(TruncationStrategy::LongestFirst),
-                            "only_first" => Ok(TruncationStrategy::OnlyFirst),
-                            "only_second" => Ok(TruncationStrategy::OnlySecond),
-                            _ => Err(PyError(format!(
-                                "Unknown `strategy`: `{value}`. Use \
-                                 one of `longest_first`, `only_first`, or `only_second`"
-                            ))
-                            .into_pyerr::<exceptions::PyValueError>()),
-                        }?
-                    }
+                    "strategy" => strategy = value.extract()?,
                     "direction" => {
-                        let value: String = value.extract()?;
-                        params.direction = match value.as_ref() {
-                            "left" => Ok(TruncationDirection::Left),
-                            "right" => Ok(TruncationDirection::Right),
-                            _ => Err(PyError(format!(
-                                "Unknown `direction`: `{value}`. Use \
-                                 one of `left` or `right`."
-                            ))
-                            .into_pyerr::<exceptions::PyValueError>()),
-                        }?
+                        params.direction = truncation_direction(&value.extract::<String>()?)?
+                    }
+                    "first_budget" if !value.is_none() => first_budget = Some(value),
+                    "first_budget" => {}
+                    "special_tokens" => {
+                        params.special_tokens =
+                            truncation_special_tokens(&value.extract::<String>()?)?
                     }
+                    "max_overflowing" => params.max_overflowing = value.extract()?,
                     _ => println!("Ignored unknown kwarg option {key}"),
                 }
             }
+            params.strategy = truncation_strategy_with_budget(&strategy, first_budget.as_ref())?;
         }
 
-        if let Err(error_message) = self.tokenizer.with_truncation(Some(params)) {
+        if let Err(error_message) = self.tokenizer_mut().with_truncation(Some(params)) {
             return Err(PyError(error_message.to_string()).into_pyerr::<exceptions::PyValueError>());
         }
         Ok(())
     }
 
+    /// Configure where the batch methods (:meth:`~tokenizers.Tokenizer.encode_batch`,
+    /// :meth:`~tokenizers.Tokenizer.decode_batch`, ...) run
+    ///
+    /// The ``TOKENIZERS_PARALLELISM`` environment variable keeps the final word: when it
+    /// disables parallelism, everything runs on the calling thread.
+    ///
+    /// Args:
+    ///     enabled (:obj:`bool`, defaults to :obj:`True`):
+    ///         Whether to use multiple threads at all. When :obj:`False`, everything runs on
+    ///         the calling thread, which avoids contention with multi-worker data loaders.
+    ///
+    ///     num_threads (:obj:`int`, `optional`):
+    ///         The number of threads of a pool dedicated to this tokenizer. When not
+    ///         specified, the thread pool shared with the rest of the process is used.
+    #[pyo3(signature = (enabled = true, num_threads = None))]
+    #[pyo3(text_signature = "(self, enabled=True, num_threads=None)")]
+    fn configure_parallelism(&mut self, enabled: bool, num_threads: Option<usize>) -> PyResult<()> {
+        let parallelism = match (enabled, num_threads) {
+            (false, _) => Parallelism::Disabled,
+            (true, None) => Parallelism::Global,
+            (true, Some(num_threads)) => Parallelism::dedicated(num_threads)
+                .map_err(|e| exceptions::PyValueError::new_err(e.to_string()))?,
+        };
+        self.tokenizer_mut().with_parallelism(parallelism);
+        Ok(())
+    }
+
+    /// The maximum number of threads used by the batch methods
+    ///
+    /// Returns:
+    ///     :obj:`int`: The number of threads
+    #[getter]
+    fn get_num_threads(&self) -> usize {
+        self.tokenizer.get_parallelism().num_threads()
+    }
+
+    /// Set hard caps on the intermediate products of the encoding, bounding the work and the
+    /// memory of each input. Going over one of them raises a
+    /// :class:`~tokenizers.LimitExceededError` instead of silently keeping a part of the
+    /// result. Calling this method again replaces all the limits.
+    ///
+    /// Args:
+    ///     max_pre_tokens (:obj:`int`, `optional`):
+    ///         The maximum number of pre-tokens of a sequence, over all its parts when
+    ///         pre-tokenized. The pre-tokenizer stops splitting as soon as it goes over
+    ///
+    ///     max_splits_per_pre_token (:obj:`int`, `optional`):
+    ///         The maximum number of tokens the model splits a single pre-token into. These
+    ///         are only counted once the model has built them all, so this bounds the building
+    ///         of the encoding, but not the work of the model on that pre-token
+    ///
+    ///     max_overflowing (:obj:`int`, `optional`):
+    ///         The maximum number of overflowing parts of an encoding. Contrarily to the
+    ///         ``max_overflowing`` of the truncation, which keeps the first parts, this is an
+    ///         error.
+    #[pyo3(signature = (max_pre_tokens = None, max_splits_per_pre_token = None, max_overflowing = None))]
+    #[pyo3(
+        text_signature = "(self, max_pre_tokens=None, max_splits_per_pre_token=None, max_overflowing=None)"
+    )]
+    fn configure_limits(
+        &mut self,
+        max_pre_tokens: Option<usize>,
+        max_splits_per_pre_token: Option<usize>,
+        max_overflowing: Option<usize>,
+    ) {
+        self.tokenizer_mut().with_limits(StageLimits {
+            max_pre_tokens,
+            max_splits_per_pre_token,
+            max_overflowing,
+        });
+    }
+
+    /// The limits set with :meth:`~tokenizers.Tokenizer.configure_limits`
+    ///
+    /// Returns:
+    ///     :obj:`Dict[str, Optional[int]]`: The ``max_pre_tokens``,
+    ///     ``max_splits_per_pre_token`` and ``max_overflowing`` limits
+    #[getter]
+    fn get_limits(&self) -> BTreeMap<&'static str, Option<usize>> {
+        let limits = self.tokenizer.get_limits();
+        BTreeMap::from([
+            ("max_pre_tokens", limits.max_pre_tokens),
+            ("max_splits_per_pre_token", limits.max_splits_per_pre_token),
+            ("max_overflowing", limits.max_overflowing),
+        ])
+    }
+
     /// Disable truncation
     #[pyo3(text_signature = "(self)")]
     fn no_truncation(&mut self) {
-        self.tokenizer
+        self.tokenizer_mut()
             .with_truncation(None)
             .expect("Failed to set truncation to `None`! This should never happen");
     }
 
     /// Get the currently set truncation parameters
     ///
-    /// `Cannot set, use` :meth:`~tokenizers.Tokenizer.enable_truncation` `instead`
+    /// This is a copy of the parameters: to change them, assign the modified parameters back,
+    /// or :obj:`None` to disable truncation
     ///
     /// Returns:
-    ///     (:obj:`dict`, `optional`):
-    ///         A dict with the current truncation parameters if truncation is enabled
+    ///     (:class:`~tokenizers.TruncationParams`, `optional`):
+    ///         The current truncation parameters if truncation is enabled
     #[getter]
-    fn get_truncation<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyDict>>> {
-        self.tokenizer.get_truncation().map_or(Ok(None), |params| {
-            let dict = PyDict::new(py);
-
-            dict.set_item("max_length", params.max_length)?;
-            dict.set_item("stride", params.stride)?;
-            dict.set_item("strategy", params.strategy.as_ref())?;
-            dict.set_item("direction", params.direction.as_ref())?;
+    fn get_truncation(&self) -> Option<PyTruncationParams> {
+        self.tokenizer.get_truncation().cloned().map(Into::into)
+    }
 
-            Ok(Some(dict))
-        })
+    #[setter]
+    fn set_truncation(&mut self, params: Option<PyRef<PyTruncationParams>>) -> PyResult<()> {
+        if let Err(error_message) = self
+            .tokenizer_mut()
+            .with_truncation(params.map(|params| params.params.clone()))
+        {
+            return Err(PyError(error_message.to_string()).into_pyerr::<exceptions::PyValueError>());
+        }
+        Ok(())
     }
 
     /// Enable the padding
@@ -841,16 +2175,7 @@ impl PyTokenizer {
                 let key: String = key.extract()?;
                 match key.as_ref() {
                     "direction" => {
-                        let value: String = value.extract()?;
-                        params.direction = match value.as_ref() {
-                            "left" => Ok(PaddingDirection::Left),
-                            "right" => Ok(PaddingDirection::Right),
-                            other => Err(PyError(format!(
-                                "Unknown `direction`: `{other}`. Use \
-                                 one of `left` or `right`"
-                            ))
-                            .into_pyerr::<exceptions::PyValueError>()),
-                        }?;
+                        params.direction = padding_direction(&value.extract::<String>()?)?
                     }
                     "pad_to_multiple_of" => {
                         if let Some(multiple) = value.extract()? {
@@ -883,7 +2208,7 @@ impl PyTokenizer {
             }
         }
 
-        self.tokenizer.with_padding(Some(params));
+        self.tokenizer_mut().with_padding(Some(params));
 
         Ok(())
     }
@@ -891,36 +2216,26 @@ impl PyTokenizer {
     /// Disable padding
     #[pyo3(text_signature = "(self)")]
     fn no_padding(&mut self) {
-        self.tokenizer.with_padding(None);
+        self.tokenizer_mut().with_padding(None);
     }
 
     /// Get the current padding parameters
     ///
-    /// `Cannot be set, use` :meth:`~tokenizers.Tokenizer.enable_padding` `instead`
+    /// This is a copy of the parameters: to change them, assign the modified parameters back,
+    /// or :obj:`None` to disable padding
     ///
     /// Returns:
-    ///     (:obj:`dict`, `optional`):
-    ///         A dict with the current padding parameters if padding is enabled
+    ///     (:class:`~tokenizers.PaddingParams`, `optional`):
+    ///         The current padding parameters if padding is enabled
     #[getter]
-    fn get_padding<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyDict>>> {
-        self.tokenizer.get_padding().map_or(Ok(None), |params| {
-            let dict = PyDict::new(py);
-
-            dict.set_item(
-                "length",
-                match params.strategy {
-                    tk::PaddingStrategy::BatchLongest => None,
-                    tk::PaddingStrategy::Fixed(size) => Some(size),
-                },
-            )?;
-            dict.set_item("pad_to_multiple_of", params.pad_to_multiple_of)?;
-            dict.set_item("pad_id", params.pad_id)?;
-            dict.set_item("pad_token", &params.pad_token)?;
-            dict.set_item("pad_type_id", params.pad_type_id)?;
-            dict.set_item("direction", params.direction.as_ref())?;
+    fn get_padding(&self) -> Option<PyPaddingParams> {
+        self.tokenizer.get_padding().cloned().map(Into::into)
+    }
 
-            Ok(Some(dict))
-        })
+    #[setter]
+    fn set_padding(&mut self, params: Option<PyRef<PyPaddingParams>>) {
+        self.tokenizer_mut()
+            .with_padding(params.map(|params| params.params.clone()));
     }
 
     /// Encode the given sequence and pair. This method can process raw text sequences
@@ -968,22 +2283,8 @@ impl PyTokenizer {
         is_pretokenized: bool,
         add_special_tokens: bool,
     ) -> PyResult<PyEncoding> {
-        let sequence: tk::InputSequence = if is_pretokenized {
-            sequence.extract::<PreTokenizedInputSequence>()?.into()
-        } else {
-            sequence.extract::<TextInputSequence>()?.into()
-        };
-        let input = match pair {
-            Some(pair) => {
-                let pair: tk::InputSequence = if is_pretokenized {
-                    pair.extract::<PreTokenizedInputSequence>()?.into()
-                } else {
-                    pair.extract::<TextInputSequence>()?.into()
-                };
-                tk::EncodeInput::Dual(sequence, pair)
-            }
-            None => tk::EncodeInput::Single(sequence),
-        };
+        let input = PyTokenizer::encode_input(sequence, pair, is_pretokenized)?;
+        self.warn_artifacts(sequence.py(), std::slice::from_ref(&input))?;
 
         ToPyResult(
             self.tokenizer
@@ -993,29 +2294,24 @@ impl PyTokenizer {
         .into()
     }
 
-    /// Encode the given batch of inputs. This method accept both raw text sequences
-    /// as well as already pre-tokenized sequences. The reason we use `PySequence` is
-    /// because it allows type checking with zero-cost (according to PyO3) as we don't
-    /// have to convert to check.
-    ///
-    /// Example:
-    ///     Here are some examples of the inputs that are accepted::
+    /// Encode the given sequence and pair like :meth:`~tokenizers.Tokenizer.encode`,
+    /// returning the ``n`` best segmentations with their scores
     ///
-    ///         encode_batch([
-    ///             "A single sequence",
-    ///             ("A tuple with a sequence", "And its pair"),
-    ///             [ "A", "pre", "tokenized", "sequence" ],
-    ///             ([ "A", "pre", "tokenized", "sequence" ], "And its pair")
-    ///         ])
+    /// The segmentations of the words are combined, the score of a segmentation being the
+    /// sum of the log probabilities of its tokens. Only the :class:`~tokenizers.models.Unigram`
+    /// model has several segmentations per word, while the
+    /// :class:`~tokenizers.models.WordLevel` one only has its single segmentation.
     ///
     /// Args:
-    ///     input (A :obj:`List`/:obj:`Tuple` of :obj:`~tokenizers.EncodeInput`):
-    ///         A list of single sequences or pair sequences to encode. Each sequence
-    ///         can be either raw text or pre-tokenized, according to the ``is_pretokenized``
-    ///         argument:
+    ///     sequence (:obj:`~tokenizers.InputSequence`):
+    ///         The main input sequence, as accepted by :meth:`~tokenizers.Tokenizer.encode`
     ///
-    ///         - If ``is_pretokenized=False``: :class:`~tokenizers.TextEncodeInput`
-    ///         - If ``is_pretokenized=True``: :class:`~tokenizers.PreTokenizedEncodeInput`
+    ///     n (:obj:`int`):
+    ///         The number of segmentations to return. Fewer are returned when there are not
+    ///         as many
+    ///
+    ///     pair (:obj:`~tokenizers.InputSequence`, `optional`):
+    ///         An optional input sequence. The expected format is the same that for ``sequence``.
     ///
     ///     is_pretokenized (:obj:`bool`, defaults to :obj:`False`):
     ///         Whether the input is already pre-tokenized
@@ -1024,38 +2320,330 @@ impl PyTokenizer {
     ///         Whether to add the special tokens
     ///
     /// Returns:
-    ///     A :obj:`List` of :class:`~tokenizers.Encoding`: The encoded batch
-    ///
-    #[pyo3(signature = (input, is_pretokenized = false, add_special_tokens = true))]
-    #[pyo3(text_signature = "(self, input, is_pretokenized=False, add_special_tokens=True)")]
-    fn encode_batch(
+    ///     A :obj:`List` of :obj:`Tuple` with each :class:`~tokenizers.Encoding` and its
+    ///     score, best first
+    #[pyo3(signature = (sequence, n, pair = None, is_pretokenized = false, add_special_tokens = true))]
+    #[pyo3(
+        text_signature = "(self, sequence, n, pair=None, is_pretokenized=False, add_special_tokens=True)"
+    )]
+    fn encode_nbest(
         &self,
-        py: Python<'_>,
-        input: Vec<Bound<'_, PyAny>>,
+        sequence: &Bound<'_, PyAny>,
+        n: usize,
+        pair: Option<&Bound<'_, PyAny>>,
         is_pretokenized: bool,
         add_special_tokens: bool,
-    ) -> PyResult<Vec<PyEncoding>> {
-        let mut items = Vec::<tk::EncodeInput>::with_capacity(input.len());
-        for item in &input {
-            let item: tk::EncodeInput = if is_pretokenized {
-                item.extract::<PreTokenizedEncodeInput>()?.into()
-            } else {
-                item.extract::<TextEncodeInput>()?.into()
-            };
-            items.push(item);
-        }
-        py.allow_threads(|| {
-            ToPyResult(
-                self.tokenizer
-                    .encode_batch_char_offsets(items, add_special_tokens)
-                    .map(|encodings| encodings.into_iter().map(|e| e.into()).collect()),
-            )
-            .into()
-        })
+    ) -> PyResult<Vec<(PyEncoding, f64)>> {
+        let input = PyTokenizer::encode_input(sequence, pair, is_pretokenized)?;
+
+        ToPyResult(
+            self.tokenizer
+                .encode_nbest_char_offsets(input, n, add_special_tokens)
+                .map(|nbest| {
+                    nbest
+                        .into_iter()
+                        .map(|(encoding, score)| (encoding.into(), score))
+                        .collect()
+                }),
+        )
+        .into()
     }
 
-    /// Encode the given batch of inputs. This method is faster than `encode_batch`
-    /// because it doesn't keep track of offsets, they will be all zeros.
+    /// Encode the given sequence and pair like :meth:`~tokenizers.Tokenizer.encode`,
+    /// sampling the segmentation of each word, as used for subword regularization
+    ///
+    /// The added tokens are never sampled.
+    ///
+    /// Args:
+    ///     sequence (:obj:`~tokenizers.InputSequence`):
+    ///         The main input sequence, as accepted by :meth:`~tokenizers.Tokenizer.encode`
+    ///
+    ///     strength (:obj:`float`):
+    ///         The dropout probability for BPE, or the smoothing parameter ``alpha`` for
+    ///         Unigram
+    ///
+    ///     pair (:obj:`~tokenizers.InputSequence`, `optional`):
+    ///         An optional input sequence. The expected format is the same that for ``sequence``.
+    ///
+    ///     is_pretokenized (:obj:`bool`, defaults to :obj:`False`):
+    ///         Whether the input is already pre-tokenized
+    ///
+    ///     add_special_tokens (:obj:`bool`, defaults to :obj:`True`):
+    ///         Whether to add the special tokens
+    ///
+    /// Returns:
+    ///     :class:`~tokenizers.Encoding`: The encoded result
+    #[pyo3(signature = (sequence, strength, pair = None, is_pretokenized = false, add_special_tokens = true))]
+    #[pyo3(
+        text_signature = "(self, sequence, strength, pair=None, is_pretokenized=False, add_special_tokens=True)"
+    )]
+    fn encode_sample(
+        &self,
+        sequence: &Bound<'_, PyAny>,
+        strength: f64,
+        pair: Option<&Bound<'_, PyAny>>,
+        is_pretokenized: bool,
+        add_special_tokens: bool,
+    ) -> PyResult<PyEncoding> {
+        let input = PyTokenizer::encode_input(sequence, pair, is_pretokenized)?;
+
+        ToPyResult(
+            self.tokenizer
+                .encode_sample_char_offsets(input, strength, add_special_tokens)
+                .map(|e| e.into()),
+        )
+        .into()
+    }
+
+    /// Encode the given pre-tokenized sequence and pair, attaching to the tokens of each word
+    /// its metadata, like the timestamps of the words in an audio for forced alignment
+    ///
+    /// The metadata follow the tokens through the truncation, the padding and the overflowing
+    /// encodings. They are available with :attr:`~tokenizers.Encoding.word_metadata`.
+    ///
+    /// Example:
+    ///     Here is an example with the start and end time of each word::
+    ///
+    ///         encode_with_word_metadata(["Hello", "there"], [[0.0, 0.4], [0.5, 1.2]])
+    ///
+    /// Args:
+    ///     sequence (:obj:`~tokenizers.PreTokenizedInputSequence`):
+    ///         The main pre-tokenized input sequence
+    ///
+    ///     word_metadata (:obj:`List[List[float]]`):
+    ///         The metadata of each word of the sequence
+    ///
+    ///     pair (:obj:`~tokenizers.PreTokenizedInputSequence`, `optional`):
+    ///         An optional pre-tokenized input sequence
+    ///
+    ///     pair_word_metadata (:obj:`List[List[float]]`, `optional`):
+    ///         The metadata of each word of the pair, required with a pair
+    ///
+    ///     add_special_tokens (:obj:`bool`, defaults to :obj:`True`):
+    ///         Whether to add the special tokens
+    ///
+    /// Returns:
+    ///     :class:`~tokenizers.Encoding`: The encoded result
+    #[pyo3(signature = (sequence, word_metadata, pair = None, pair_word_metadata = None, add_special_tokens = true))]
+    #[pyo3(
+        text_signature = "(self, sequence, word_metadata, pair=None, pair_word_metadata=None, add_special_tokens=True)"
+    )]
+    fn encode_with_word_metadata(
+        &self,
+        sequence: &Bound<'_, PyAny>,
+        word_metadata: Vec<Vec<f64>>,
+        pair: Option<&Bound<'_, PyAny>>,
+        pair_word_metadata: Option<Vec<Vec<f64>>>,
+        add_special_tokens: bool,
+    ) -> PyResult<PyEncoding> {
+        let input = PyTokenizer::encode_input(sequence, pair, true)?;
+        let word_metadata = match (pair, pair_word_metadata) {
+            (Some(_), Some(pair_word_metadata)) => vec![word_metadata, pair_word_metadata],
+            (None, None) => vec![word_metadata],
+            (Some(_), None) => {
+                return Err(exceptions::PyValueError::new_err(
+                    "`pair_word_metadata` is required with a pair",
+                ))
+            }
+            (None, Some(_)) => {
+                return Err(exceptions::PyValueError::new_err(
+                    "`pair_word_metadata` needs a pair",
+                ))
+            }
+        };
+
+        ToPyResult(
+            self.tokenizer
+                .encode_with_word_metadata(
+                    input,
+                    &word_metadata,
+                    add_special_tokens,
+                    tk::OffsetType::Char,
+                )
+                .map(|e| e.into()),
+        )
+        .into()
+    }
+
+    /// Encode a conversation made of any number of ``(role, content)`` turns, using the
+    /// :class:`~tokenizers.processors.ChatTemplate` set as post-processor.
+    ///
+    /// Example:
+    ///     Here is an example of the accepted input::
+    ///
+    ///         encode_chat([
+    ///             ("system", "You are a helpful assistant"),
+    ///             ("user", "Hi!"),
+    ///             ("assistant", "Hello, how can I help you?"),
+    ///         ])
+    ///
+    /// Args:
+    ///     turns (:obj:`List[Tuple[str, InputSequence]]`):
+    ///         The turns of the conversation, each one with the name of its role. The offsets
+    ///         of each turn are relative to its own content.
+    ///
+    ///     is_pretokenized (:obj:`bool`, defaults to :obj:`False`):
+    ///         Whether the contents are already pre-tokenized
+    ///
+    ///     add_special_tokens (:obj:`bool`, defaults to :obj:`True`):
+    ///         Whether to add the special tokens
+    ///
+    /// Returns:
+    ///     A :obj:`Tuple` with the :class:`~tokenizers.Encoding` of the whole conversation,
+    ///     the loss mask (:obj:`List[int]`), and the index of the role owning each token
+    ///     (:obj:`List[Optional[int]]`)
+    #[pyo3(signature = (turns, is_pretokenized = false, add_special_tokens = true))]
+    #[pyo3(text_signature = "(self, turns, is_pretokenized=False, add_special_tokens=True)")]
+    fn encode_chat(
+        &self,
+        turns: Vec<(String, Bound<'_, PyAny>)>,
+        is_pretokenized: bool,
+        add_special_tokens: bool,
+    ) -> PyResult<(PyEncoding, Vec<u32>, Vec<Option<u32>>)> {
+        let template = self
+            .tokenizer
+            .get_post_processor()
+            .map(|p| p.get_chat_template())
+            .transpose()?
+            .flatten()
+            .ok_or_else(|| {
+                exceptions::PyValueError::new_err(
+                    "`encode_chat` requires a `ChatTemplate` post-processor",
+                )
+            })?;
+
+        let mut items = Vec::<(String, tk::InputSequence)>::with_capacity(turns.len());
+        for (role, content) in &turns {
+            let content: tk::InputSequence = if is_pretokenized {
+                content.extract::<PreTokenizedInputSequence>()?.into()
+            } else {
+                content.extract::<TextInputSequence>()?.into()
+            };
+            items.push((role.clone(), content));
+        }
+
+        ToPyResult(
+            self.tokenizer
+                .encode_chat_char_offsets(&template, items, add_special_tokens)
+                .map(|chat| (chat.encoding.into(), chat.loss_mask, chat.role_ids)),
+        )
+        .into()
+    }
+
+    /// Encode the given text for token healing
+    ///
+    /// When the text stops in the middle of what would be a single token, its last token is
+    /// removed from the returned :class:`~tokenizers.Encoding`, so that the generation can
+    /// start by re-generating it, constrained on the returned list of ids.
+    ///
+    /// Nothing gets removed if the last token does not reach the end of the text.
+    ///
+    /// Args:
+    ///     sequence (:obj:`str`):
+    ///         The text to encode
+    ///
+    ///     add_special_tokens (:obj:`bool`, defaults to :obj:`True`):
+    ///         Whether to add the special tokens
+    ///
+    /// Returns:
+    ///     A :obj:`Tuple` with the :class:`~tokenizers.Encoding`, the removed
+    ///     :class:`~tokenizers.Token` (:obj:`None` if nothing was removed), and the sorted ids
+    ///     of all the tokens starting with the removed one (:obj:`List[int]`)
+    #[pyo3(signature = (sequence, add_special_tokens = true))]
+    #[pyo3(text_signature = "(self, sequence, add_special_tokens=True)")]
+    fn encode_with_prefix_constraint(
+        &self,
+        sequence: &str,
+        add_special_tokens: bool,
+    ) -> PyResult<(PyEncoding, Option<PyToken>, Vec<u32>)> {
+        ToPyResult(
+            self.tokenizer
+                .encode_with_prefix_constraint_char_offsets(sequence, add_special_tokens)
+                .map(|(encoding, constraint)| match constraint {
+                    Some(c) => (encoding.into(), Some(c.token.into()), c.allowed_ids),
+                    None => (encoding.into(), None, vec![]),
+                }),
+        )
+        .into()
+    }
+
+    /// Encode the given batch of inputs. This method accept both raw text sequences
+    /// as well as already pre-tokenized sequences. The reason we use `PySequence` is
+    /// because it allows type checking with zero-cost (according to PyO3) as we don't
+    /// have to convert to check.
+    ///
+    /// Example:
+    ///     Here are some examples of the inputs that are accepted::
+    ///
+    ///         encode_batch([
+    ///             "A single sequence",
+    ///             ("A tuple with a sequence", "And its pair"),
+    ///             [ "A", "pre", "tokenized", "sequence" ],
+    ///             ([ "A", "pre", "tokenized", "sequence" ], "And its pair")
+    ///         ])
+    ///
+    /// Args:
+    ///     input (A :obj:`List`/:obj:`Tuple` of :obj:`~tokenizers.EncodeInput`):
+    ///         A list of single sequences or pair sequences to encode. Each sequence
+    ///         can be either raw text or pre-tokenized, according to the ``is_pretokenized``
+    ///         argument:
+    ///
+    ///         - If ``is_pretokenized=False``: :class:`~tokenizers.TextEncodeInput`
+    ///         - If ``is_pretokenized=True``: :class:`~tokenizers.PreTokenizedEncodeInput`
+    ///
+    ///     is_pretokenized (:obj:`bool`, defaults to :obj:`False`):
+    ///         Whether the input is already pre-tokenized
+    ///
+    ///     add_special_tokens (:obj:`bool`, defaults to :obj:`True`):
+    ///         Whether to add the special tokens
+    ///
+    ///     max_lengths (A :obj:`List` of :obj:`int`, `optional`):
+    ///         The length at which to truncate each input, including the special tokens. It
+    ///         replaces the ``max_length`` of the truncation parameters, and applies even when
+    ///         truncation is disabled. :obj:`None` items use the truncation of the tokenizer.
+    ///
+    /// Returns:
+    ///     A :obj:`List` of :class:`~tokenizers.Encoding`: The encoded batch
+    ///
+    #[pyo3(signature = (input, is_pretokenized = false, add_special_tokens = true, max_lengths = None))]
+    #[pyo3(
+        text_signature = "(self, input, is_pretokenized=False, add_special_tokens=True, max_lengths=None)"
+    )]
+    fn encode_batch(
+        slf: PyRef<'_, Self>,
+        py: Python<'_>,
+        input: Vec<Bound<'_, PyAny>>,
+        is_pretokenized: bool,
+        add_special_tokens: bool,
+        max_lengths: Option<Vec<Option<usize>>>,
+    ) -> PyResult<Vec<PyEncoding>> {
+        let mut items = Vec::<tk::EncodeInput>::with_capacity(input.len());
+        for item in &input {
+            let item: tk::EncodeInput = if is_pretokenized {
+                item.extract::<PreTokenizedEncodeInput>()?.into()
+            } else {
+                item.extract::<TextEncodeInput>()?.into()
+            };
+            items.push(item);
+        }
+        slf.warn_artifacts(py, &items)?;
+        let tokenizer = Self::snapshot(slf);
+        py.allow_threads(|| {
+            let encodings = match max_lengths {
+                Some(max_lengths) => tokenizer.encode_batch_char_offsets_with_max_lengths(
+                    items,
+                    &max_lengths,
+                    add_special_tokens,
+                ),
+                None => tokenizer.encode_batch_char_offsets(items, add_special_tokens),
+            };
+            ToPyResult(encodings.map(|encodings| encodings.into_iter().map(|e| e.into()).collect()))
+                .into()
+        })
+    }
+
+    /// Encode the given batch of inputs. This method is faster than `encode_batch`
+    /// because it doesn't keep track of offsets, they will be all zeros.
     ///
     /// Example:
     ///     Here are some examples of the inputs that are accepted::
@@ -1088,7 +2676,7 @@ impl PyTokenizer {
     #[pyo3(signature = (input, is_pretokenized = false, add_special_tokens = true))]
     #[pyo3(text_signature = "(self, input, is_pretokenized=False, add_special_tokens=True)")]
     fn encode_batch_fast(
-        &self,
+        slf: PyRef<'_, Self>,
         py: Python<'_>,
         input: Vec<Bound<'_, PyAny>>,
         is_pretokenized: bool,
@@ -1103,9 +2691,11 @@ impl PyTokenizer {
             };
             items.push(item);
         }
+        slf.warn_artifacts(py, &items)?;
+        let tokenizer = Self::snapshot(slf);
         py.allow_threads(|| {
             ToPyResult(
-                self.tokenizer
+                tokenizer
                     .encode_batch_fast(items, add_special_tokens)
                     .map(|encodings| encodings.into_iter().map(|e| e.into()).collect()),
             )
@@ -1113,6 +2703,312 @@ impl PyTokenizer {
         })
     }
 
+    /// Encode the given prefix followed by each of the suffixes, like :meth:`encode_batch`
+    /// would encode the concatenated sequences, but tokenizing the prefix only once.
+    ///
+    /// Only the last two words of the prefix are tokenized again with each suffix, as the
+    /// suffix may merge with them. When the suffix reaches further back into the prefix, the
+    /// whole sequence gets encoded instead, so the encodings are always the same.
+    ///
+    /// Example:
+    ///     A few-shot prompt only gets tokenized once for the whole evaluation set::
+    ///
+    ///         encodings = tokenizer.encode_batch_with_prefix(prompt, questions)
+    ///
+    /// Args:
+    ///     prefix (:obj:`str`):
+    ///         The sequence shared by all the inputs
+    ///
+    ///     suffixes (:obj:`List[str]`):
+    ///         The rest of each input, appended to the prefix as is
+    ///
+    ///     add_special_tokens (:obj:`bool`, defaults to :obj:`True`):
+    ///         Whether to add the special tokens
+    ///
+    /// Returns:
+    ///     A :obj:`List` of :class:`~tokenizers.Encoding`: The encoded batch
+    ///
+    #[pyo3(signature = (prefix, suffixes, add_special_tokens = true))]
+    #[pyo3(text_signature = "(self, prefix, suffixes, add_special_tokens=True)")]
+    fn encode_batch_with_prefix(
+        slf: PyRef<'_, Self>,
+        py: Python<'_>,
+        prefix: &str,
+        suffixes: Vec<String>,
+        add_special_tokens: bool,
+    ) -> PyResult<Vec<PyEncoding>> {
+        let tokenizer = Self::snapshot(slf);
+        py.allow_threads(|| {
+            ToPyResult(
+                tokenizer
+                    .encode_batch_char_offsets_with_prefix(prefix, suffixes, add_special_tokens)
+                    .map(|encodings| encodings.into_iter().map(|e| e.into()).collect()),
+            )
+            .into()
+        })
+    }
+
+    /// Encode the text after an edit, reusing its previous encoding to only tokenize again
+    /// the words around the edit
+    ///
+    /// This suits the editors, re-tokenizing a document for each keystroke. The words touched
+    /// by the edit are tokenized again with the words around them, which must keep the same
+    /// tokens to prove that the edit doesn't reach further, otherwise more words get tokenized
+    /// again. The result is always the same as encoding the edited text from scratch.
+    ///
+    /// Args:
+    ///     text (:obj:`str`):
+    ///         The text before the edit
+    ///
+    ///     encoding (:class:`~tokenizers.Encoding`):
+    ///         The encoding of :obj:`text`, made by :meth:`encode` without the special tokens
+    ///
+    ///     edit (:obj:`Tuple[int, int]`):
+    ///         The range of chars of :obj:`text` that gets replaced
+    ///
+    ///     replacement (:obj:`str`):
+    ///         The new content of this range
+    ///
+    /// Returns:
+    ///     :obj:`Dict`: The ``encoding`` of the edited text, along with the range of the tokens
+    ///     ``replaced`` in the previous encoding, and the range of the ones ``inserted`` in
+    ///     the new one
+    #[pyo3(text_signature = "(self, text, encoding, edit, replacement)")]
+    fn encode_edit<'py>(
+        &self,
+        py: Python<'py>,
+        text: &str,
+        encoding: PyRef<PyEncoding>,
+        edit: (usize, usize),
+        replacement: &str,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let patch: tk::EncodingPatch = ToPyResult(self.tokenizer.encode_edit_char_offsets(
+            text,
+            &encoding.encoding,
+            edit.0..edit.1,
+            replacement,
+        ))
+        .into_py()?;
+        let dict = PyDict::new(py);
+        dict.set_item("replaced", (patch.replaced.start, patch.replaced.end))?;
+        dict.set_item("inserted", (patch.inserted.start, patch.inserted.end))?;
+        dict.set_item("encoding", PyEncoding::from(patch.encoding))?;
+        Ok(dict)
+    }
+
+    /// Encode the given batch of inputs, returning all the windows of all the inputs as
+    /// a single list. This is mostly useful for long documents, with truncation enabled:
+    /// each input is then chunked into windows of at most ``max_length`` tokens, overlapping
+    /// by ``stride`` tokens.
+    ///
+    /// Along with the windows, this returns the ``overflow_to_sample_mapping``, which gives,
+    /// for each window, the index of the input it comes from. The offsets of each window
+    /// always refer to the original input.
+    ///
+    /// Example:
+    ///     Here is how the windows can be mapped back to their documents::
+    ///
+    ///         tokenizer.enable_truncation(384, stride=128)
+    ///         windows, mapping = tokenizer.encode_batch_windows(documents)
+    ///         for window, index in zip(windows, mapping):
+    ///             start, end = window.offsets[0][0], window.offsets[-1][1]
+    ///             print(documents[index][start:end])
+    ///
+    /// Args:
+    ///     input (A :obj:`List`/:obj:`Tuple` of :obj:`~tokenizers.EncodeInput`):
+    ///         A list of single sequences or pair sequences to encode. Each sequence
+    ///         can be either raw text or pre-tokenized, according to the ``is_pretokenized``
+    ///         argument:
+    ///
+    ///         - If ``is_pretokenized=False``: :class:`~tokenizers.TextEncodeInput`
+    ///         - If ``is_pretokenized=True``: :class:`~tokenizers.PreTokenizedEncodeInput`
+    ///
+    ///     is_pretokenized (:obj:`bool`, defaults to :obj:`False`):
+    ///         Whether the input is already pre-tokenized
+    ///
+    ///     add_special_tokens (:obj:`bool`, defaults to :obj:`True`):
+    ///         Whether to add the special tokens
+    ///
+    /// Returns:
+    ///     A :obj:`Tuple` with the :obj:`List` of :class:`~tokenizers.Encoding` windows, and
+    ///     the ``overflow_to_sample_mapping`` (:obj:`List[int]`)
+    ///
+    #[pyo3(signature = (input, is_pretokenized = false, add_special_tokens = true))]
+    #[pyo3(text_signature = "(self, input, is_pretokenized=False, add_special_tokens=True)")]
+    fn encode_batch_windows(
+        slf: PyRef<'_, Self>,
+        py: Python<'_>,
+        input: Vec<Bound<'_, PyAny>>,
+        is_pretokenized: bool,
+        add_special_tokens: bool,
+    ) -> PyResult<(Vec<PyEncoding>, Vec<usize>)> {
+        let tokenizer = Self::snapshot(slf);
+        let mut items = Vec::<tk::EncodeInput>::with_capacity(input.len());
+        for item in &input {
+            let item: tk::EncodeInput = if is_pretokenized {
+                item.extract::<PreTokenizedEncodeInput>()?.into()
+            } else {
+                item.extract::<TextEncodeInput>()?.into()
+            };
+            items.push(item);
+        }
+        py.allow_threads(|| {
+            ToPyResult(
+                tokenizer
+                    .encode_batch_windows_char_offsets(items, add_special_tokens)
+                    .map(|(windows, mapping)| {
+                        (windows.into_iter().map(|e| e.into()).collect(), mapping)
+                    }),
+            )
+            .into()
+        })
+    }
+
+    /// Compare the deterministic segmentation of each sequence with sampled ones
+    ///
+    /// This helps picking the dropout (BPE) or alpha (Unigram) used for subword
+    /// regularization: the sampled segmentations should differ enough to regularize the
+    /// training, without drifting too far from the deterministic one seen at inference.
+    /// No special tokens are added, and the added tokens are never sampled.
+    ///
+    /// Args:
+    ///     sequences (:obj:`List[str]`):
+    ///         The corpus to audit
+    ///
+    ///     strength (:obj:`float`):
+    ///         The dropout probability for BPE, or the smoothing parameter ``alpha`` for
+    ///         Unigram
+    ///
+    ///     samples (:obj:`int`, defaults to :obj:`10`):
+    ///         The number of segmentations to sample for each sequence
+    ///
+    /// Returns:
+    ///     :obj:`dict`: The statistics of the audit: ``sequences``, ``samples``, ``tokens``
+    ///     (of the deterministic segmentation), ``divergence_rate`` (the fraction of samples
+    ///     differing from the deterministic segmentation), ``boundary_agreement`` (the mean
+    ///     Jaccard similarity of the token boundaries), ``length_ratio`` (sampled tokens per
+    ///     deterministic token) and ``unique_segmentations`` (per sequence)
+    #[pyo3(signature = (sequences, strength, samples = 10))]
+    #[pyo3(text_signature = "(self, sequences, strength, samples=10)")]
+    fn audit_sampling<'py>(
+        slf: PyRef<'_, Self>,
+        py: Python<'py>,
+        sequences: Vec<String>,
+        strength: f64,
+        samples: usize,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let tokenizer = Self::snapshot(slf);
+        let audit: tk::SamplingAudit = py
+            .allow_threads(|| ToPyResult(tokenizer.audit_sampling(&sequences, strength, samples)))
+            .into_py()?;
+
+        let dict = PyDict::new(py);
+        dict.set_item("sequences", audit.sequences)?;
+        dict.set_item("samples", audit.samples)?;
+        dict.set_item("tokens", audit.tokens)?;
+        dict.set_item("divergence_rate", audit.divergence_rate)?;
+        dict.set_item("boundary_agreement", audit.boundary_agreement)?;
+        dict.set_item("length_ratio", audit.length_ratio)?;
+        dict.set_item("unique_segmentations", audit.unique_segmentations)?;
+        Ok(dict)
+    }
+
+    /// Suggest the words of a corpus worth adding to the vocabulary the most
+    ///
+    /// The words are the ones produced by the pre-tokenizer and split by the model in
+    /// several tokens, ranked by the number of tokens they would save on the corpus: their
+    /// frequency times the number of tokens they'd save on each occurrence. The sequences
+    /// are encoded without truncation nor special tokens, and the words containing added
+    /// tokens are ignored.
+    ///
+    /// Example:
+    ///     Adapt a tokenizer to a new domain::
+    ///
+    ///         suggestions = tokenizer.suggest_added_tokens(corpus, 100)
+    ///         tokenizer.add_tokens([s["token"] for s in suggestions])
+    ///
+    /// Args:
+    ///     sequences (:obj:`List[str]`):
+    ///         The corpus to scan
+    ///
+    ///     n (:obj:`int`, defaults to :obj:`100`):
+    ///         The maximum number of suggestions
+    ///
+    /// Returns:
+    ///     :obj:`List[dict]`: The suggestions, best first: the ``token`` to add, an
+    ///     :class:`~tokenizers.AddedToken` matching the word as a whole word of the original
+    ///     text, the ``count`` of occurrences of the word, their total number of ``tokens``
+    ///     with the current vocabulary, and the number of tokens ``saved`` by adding it
+    #[pyo3(signature = (sequences, n = 100))]
+    #[pyo3(text_signature = "(self, sequences, n=100)")]
+    fn suggest_added_tokens<'py>(
+        slf: PyRef<'_, Self>,
+        py: Python<'py>,
+        sequences: Vec<String>,
+        n: usize,
+    ) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        let tokenizer = Self::snapshot(slf);
+        let suggestions: Vec<tk::TokenSuggestion> = py
+            .allow_threads(|| ToPyResult(tokenizer.suggest_added_tokens(&sequences, n)))
+            .into_py()?;
+
+        suggestions
+            .into_iter()
+            .map(|suggestion| {
+                let token: PyAddedToken = suggestion.to_added_token().into();
+                let dict = PyDict::new(py);
+                dict.set_item("token", token)?;
+                dict.set_item("count", suggestion.count)?;
+                dict.set_item("tokens", suggestion.tokens)?;
+                dict.set_item("saved", suggestion.saved)?;
+                Ok(dict)
+            })
+            .collect()
+    }
+
+    /// Convert the offsets of spans between the original sequences and the normalized ones
+    ///
+    /// The sequences are normalized by the normalizer of this tokenizer, like with
+    /// :meth:`~tokenizers.normalizers.Normalizer.normalize_str`, and the offsets of their spans
+    /// converted from the given referential to the other one, for the whole batch at once. This
+    /// lets span-annotation pipelines match the spans found on the normalized text with the
+    /// original one, without a loop over each span. The offsets count the characters.
+    ///
+    /// Args:
+    ///     sequences (:obj:`List[str]`):
+    ///         The original sequences
+    ///
+    ///     offsets (:obj:`List[List[Tuple[int, int]]]`):
+    ///         The offsets of the spans of each sequence
+    ///
+    ///     referential (:obj:`str`, defaults to :obj:`normalized`):
+    ///         The referential of the given offsets, either ``"normalized"`` to convert them to
+    ///         the original sequences, or ``"original"`` to convert them to the normalized ones
+    ///
+    /// Returns:
+    ///     :obj:`List[List[Optional[Tuple[int, int]]]]`: The converted offsets, :obj:`None` for
+    ///     the spans out of range
+    #[pyo3(signature = (sequences, offsets, referential = PyOffsetReferential(tk::OffsetReferential::Normalized)))]
+    #[pyo3(text_signature = "(self, sequences, offsets, referential=\"normalized\")")]
+    fn convert_offsets_batch(
+        slf: PyRef<'_, Self>,
+        py: Python<'_>,
+        sequences: Vec<String>,
+        offsets: Vec<Vec<tk::Offsets>>,
+        referential: PyOffsetReferential,
+    ) -> PyResult<Vec<Vec<Option<tk::Offsets>>>> {
+        let tokenizer = Self::snapshot(slf);
+        py.allow_threads(|| {
+            ToPyResult(tokenizer.convert_offsets_batch(
+                &sequences,
+                &offsets,
+                referential.0,
+                tk::OffsetType::Char,
+            ))
+            .into()
+        })
+    }
+
     /// Decode the given list of ids back to a string
     ///
     /// This is used to decode anything coming back from a Language Model
@@ -1124,12 +3020,61 @@ impl PyTokenizer {
     ///     skip_special_tokens (:obj:`bool`, defaults to :obj:`True`):
     ///         Whether the special tokens should be removed from the decoded string
     ///
+    ///     preserve_whitespace (:obj:`bool`, defaults to :obj:`False`):
+    ///         Whether to reconstruct the whitespace exactly, as needed for code. The decoders then skip
+    ///         the heuristics altering the spaces, only removing the prefix space added by the
+    ///         pre-tokenizer
+    ///
     /// Returns:
     ///     :obj:`str`: The decoded string
+    #[pyo3(signature = (ids, skip_special_tokens = true, preserve_whitespace = false))]
+    #[pyo3(text_signature = "(self, ids, skip_special_tokens=True, preserve_whitespace=False)")]
+    fn decode(
+        &self,
+        ids: Vec<u32>,
+        skip_special_tokens: bool,
+        preserve_whitespace: bool,
+    ) -> PyResult<String> {
+        if preserve_whitespace {
+            ToPyResult(
+                self.tokenizer
+                    .decode_preserving_whitespace(&ids, skip_special_tokens),
+            )
+            .into()
+        } else {
+            ToPyResult(self.tokenizer.decode(&ids, skip_special_tokens)).into()
+        }
+    }
+
+    /// Decode the given list of ids back to raw bytes
+    ///
+    /// Contrarily to :meth:`~tokenizers.Tokenizer.decode`, the byte tokens that don't form
+    /// valid UTF-8 are returned as is, whatever the policy of the
+    /// :class:`~tokenizers.decoders.ByteFallback` decoder, or the replacement characters of
+    /// the :class:`~tokenizers.decoders.ByteLevel` one. This lets the caller handle partial
+    /// byte sequences, when the ids stop in the middle of a character, and the models of
+    /// binary data round-trip their content.
+    ///
+    /// Args:
+    ///     ids (A :obj:`List/Tuple` of :obj:`int`):
+    ///         The list of ids that we want to decode
+    ///
+    ///     skip_special_tokens (:obj:`bool`, defaults to :obj:`True`):
+    ///         Whether the special tokens should be removed from the decoded bytes
+    ///
+    /// Returns:
+    ///     :obj:`bytes`: The decoded bytes
     #[pyo3(signature = (ids, skip_special_tokens = true))]
     #[pyo3(text_signature = "(self, ids, skip_special_tokens=True)")]
-    fn decode(&self, ids: Vec<u32>, skip_special_tokens: bool) -> PyResult<String> {
-        ToPyResult(self.tokenizer.decode(&ids, skip_special_tokens)).into()
+    fn decode_bytes(
+        &self,
+        py: Python<'_>,
+        ids: Vec<u32>,
+        skip_special_tokens: bool,
+    ) -> PyResult<Py<PyBytes>> {
+        let bytes: Vec<u8> =
+            ToPyResult(self.tokenizer.decode_bytes(&ids, skip_special_tokens)).into_py()?;
+        Ok(PyBytes::new(py, &bytes).into())
     }
 
     /// Decode a batch of ids back to their corresponding string
@@ -1146,14 +3091,77 @@ impl PyTokenizer {
     #[pyo3(signature = (sequences, skip_special_tokens = true))]
     #[pyo3(text_signature = "(self, sequences, skip_special_tokens=True)")]
     fn decode_batch(
-        &self,
+        slf: PyRef<'_, Self>,
         py: Python<'_>,
         sequences: Vec<Vec<u32>>,
         skip_special_tokens: bool,
     ) -> PyResult<Vec<String>> {
+        let tokenizer = Self::snapshot(slf);
         py.allow_threads(|| {
             let slices = sequences.iter().map(|v| &v[..]).collect::<Vec<&[u32]>>();
-            ToPyResult(self.tokenizer.decode_batch(&slices, skip_special_tokens)).into()
+            ToPyResult(tokenizer.decode_batch(&slices, skip_special_tokens)).into()
+        })
+    }
+
+    /// Convert all the given ids to their corresponding tokens
+    ///
+    /// Args:
+    ///     ids (:obj:`List[int]`):
+    ///         The ids to convert
+    ///
+    /// Returns:
+    ///     :obj:`List[Optional[str]]`: The tokens, :obj:`None` for each id out of vocabulary
+    #[pyo3(text_signature = "(self, ids)")]
+    fn ids_to_tokens(slf: PyRef<'_, Self>, py: Python<'_>, ids: Vec<u32>) -> Vec<Option<String>> {
+        let tokenizer = Self::snapshot(slf);
+        py.allow_threads(|| tokenizer.ids_to_tokens(&ids))
+    }
+
+    /// Normalize a batch of sequences in parallel, without encoding them
+    ///
+    /// Only the normalizer runs, on the whole sequences, so the added tokens get normalized
+    /// like everything else.
+    ///
+    /// Args:
+    ///     sequences (:obj:`List[str]`):
+    ///         The sequences to normalize
+    ///
+    /// Returns:
+    ///     :obj:`List[str]`: The normalized sequences
+    #[pyo3(text_signature = "(self, sequences)")]
+    fn normalize_batch(
+        slf: PyRef<'_, Self>,
+        py: Python<'_>,
+        sequences: Vec<String>,
+    ) -> PyResult<Vec<String>> {
+        let tokenizer = Self::snapshot(slf);
+        py.allow_threads(|| {
+            let normalized = ToPyResult(tokenizer.normalize_batch(&sequences)).into_py()?;
+            Ok(normalized.iter().map(|n| n.get().to_owned()).collect())
+        })
+    }
+
+    /// Normalize and pre-tokenize a batch of sequences in parallel, without encoding them
+    ///
+    /// The pre-tokens are the splits the model would get when encoding, added tokens
+    /// included.
+    ///
+    /// Args:
+    ///     sequences (:obj:`List[str]`):
+    ///         The sequences to pre-tokenize
+    ///
+    /// Returns:
+    ///     :obj:`List[List[Tuple[str, Offsets]]]`: The pre-tokens of each sequence, with their
+    ///     offsets in the original sequence
+    #[pyo3(text_signature = "(self, sequences)")]
+    fn pre_tokenize_batch(
+        slf: PyRef<'_, Self>,
+        py: Python<'_>,
+        sequences: Vec<String>,
+    ) -> PyResult<Vec<Vec<(String, tk::Offsets)>>> {
+        let tokenizer = Self::snapshot(slf);
+        py.allow_threads(|| {
+            ToPyResult(tokenizer.pre_tokenize_batch(&sequences, tk::OffsetType::Char)).into()
         })
     }
 
@@ -1183,6 +3191,29 @@ impl PyTokenizer {
         self.tokenizer.id_to_token(id)
     }
 
+    /// Get the ids of all the tokens of the vocabulary (added tokens included) starting
+    /// with the given prefix
+    ///
+    /// The prefix is compared with the tokens as they appear in the vocabulary, so it must
+    /// use the same representation (``Ġ`` for spaces with byte-level models, ``##`` for
+    /// WordPiece continuations, ...).
+    ///
+    /// Args:
+    ///     prefix (:obj:`Union[str, bytes]`):
+    ///         The prefix. A :obj:`bytes` prefix does not have to be valid UTF-8
+    ///
+    /// Returns:
+    ///     :obj:`List[int]`: The ids, in ascending order
+    #[pyo3(text_signature = "(self, prefix)")]
+    fn token_ids_with_prefix(&self, prefix: &Bound<'_, PyAny>) -> PyResult<Vec<u32>> {
+        if let Ok(bytes) = prefix.downcast::<PyBytes>() {
+            Ok(self.tokenizer.token_ids_with_prefix(bytes.as_bytes()))
+        } else {
+            let prefix = prefix.extract::<String>()?;
+            Ok(self.tokenizer.token_ids_with_prefix(prefix.as_bytes()))
+        }
+    }
+
     /// Modifies the tokenizer in order to use or not the special tokens
     /// during encoding.
     ///
@@ -1192,7 +3223,7 @@ impl PyTokenizer {
     ///
     #[setter]
     fn set_encode_special_tokens(&mut self, value: bool) {
-        self.tokenizer.set_encode_special_tokens(value);
+        self.tokenizer_mut().set_encode_special_tokens(value);
     }
     /// Get the value of the `encode_special_tokens` attribute
     ///
@@ -1202,6 +3233,26 @@ impl PyTokenizer {
     fn get_encode_special_tokens(&self) -> bool {
         self.tokenizer.get_encode_special_tokens()
     }
+
+    /// Whether to warn about the inputs that look already tokenized
+    ///
+    /// A debug mode catching the inputs encoded to tokens upstream, and joined back into a
+    /// string instead of being decoded. Such inputs contain artifacts like the ``▁`` of
+    /// :class:`~tokenizers.pre_tokenizers.Metaspace`, the ``Ġ`` of
+    /// :class:`~tokenizers.pre_tokenizers.ByteLevel` or the ``<0x0A>`` byte tokens, which
+    /// silently corrupt the data once tokenized again. When enabled,
+    /// :meth:`~tokenizers.Tokenizer.encode`, :meth:`~tokenizers.Tokenizer.encode_batch` and
+    /// :meth:`~tokenizers.Tokenizer.encode_batch_fast` emit a :obj:`UserWarning` for each of
+    /// them. This is a heuristic, disabled by default.
+    #[getter]
+    fn get_warn_on_artifacts(&self) -> bool {
+        self.warn_on_artifacts
+    }
+
+    #[setter]
+    fn set_warn_on_artifacts(&mut self, value: bool) {
+        self.warn_on_artifacts = value;
+    }
     /// Add the given tokens to the vocabulary
     ///
     /// The given tokens are added only if they don't already exist in the vocabulary.
@@ -1212,26 +3263,31 @@ impl PyTokenizer {
     ///         The list of tokens we want to add to the vocabulary. Each token can be either a
     ///         string or an instance of :class:`~tokenizers.AddedToken` for more customization.
     ///
+    ///     id_assignment (:obj:`str`, defaults to :obj:`insertion`):
+    ///         How the new tokens get their ids. With ``insertion``, they follow the vocabulary
+    ///         in the given order. With ``alphabetical``, they follow it in the alphabetical
+    ///         order, whatever the given order. With ``hash_stable``, each id is derived from a
+    ///         stable hash of the token, among the ``hash_range`` ids following the model's
+    ///         vocabulary, so that tokenizers built separately agree on the ids of their tokens
+    ///         even when added in different batches. Colliding tokens get the next free id.
+    ///
+    ///     hash_range (:obj:`int`, `optional`):
+    ///         The number of ids used by ``hash_stable``, which should be much larger than the
+    ///         number of added tokens
+    ///
     /// Returns:
     ///     :obj:`int`: The number of tokens that were created in the vocabulary
-    #[pyo3(text_signature = "(self, tokens)")]
-    fn add_tokens(&mut self, tokens: &Bound<'_, PyList>) -> PyResult<usize> {
-        let tokens = tokens
-            .into_iter()
-            .map(|token| {
-                if let Ok(content) = token.extract::<String>() {
-                    Ok(PyAddedToken::from(content, Some(false)).get_token())
-                } else if let Ok(token) = token.extract::<PyRefMut<PyAddedToken>>() {
-                    Ok(token.get_token())
-                } else {
-                    Err(exceptions::PyTypeError::new_err(
-                        "Input must be a List[Union[str, AddedToken]]",
-                    ))
-                }
-            })
-            .collect::<PyResult<Vec<_>>>()?;
-
-        Ok(self.tokenizer.add_tokens(&tokens))
+    #[pyo3(signature = (tokens, id_assignment = "insertion", hash_range = None))]
+    #[pyo3(text_signature = "(self, tokens, id_assignment='insertion', hash_range=None)")]
+    fn add_tokens(
+        &mut self,
+        tokens: &Bound<'_, PyList>,
+        id_assignment: &str,
+        hash_range: Option<u32>,
+    ) -> PyResult<usize> {
+        let tokens = added_tokens(tokens)?;
+        let assignment = added_tokens_id_assignment(id_assignment, hash_range)?;
+        ToPyResult(self.tokenizer_mut().add_tokens_with(&tokens, assignment)).into()
     }
 
     /// Add the given special tokens to the Tokenizer.
@@ -1248,10 +3304,23 @@ impl PyTokenizer {
     ///         be a string or an instance of :class:`~tokenizers.AddedToken` for more
     ///         customization.
     ///
+    ///     id_assignment (:obj:`str`, defaults to :obj:`insertion`):
+    ///         How the new tokens get their ids: ``insertion``, ``alphabetical`` or
+    ///         ``hash_stable``. See :meth:`~tokenizers.Tokenizer.add_tokens`
+    ///
+    ///     hash_range (:obj:`int`, `optional`):
+    ///         The number of ids used by ``hash_stable``
+    ///
     /// Returns:
     ///     :obj:`int`: The number of tokens that were created in the vocabulary
-    #[pyo3(text_signature = "(self, tokens)")]
-    fn add_special_tokens(&mut self, tokens: &Bound<'_, PyList>) -> PyResult<usize> {
+    #[pyo3(signature = (tokens, id_assignment = "insertion", hash_range = None))]
+    #[pyo3(text_signature = "(self, tokens, id_assignment='insertion', hash_range=None)")]
+    fn add_special_tokens(
+        &mut self,
+        tokens: &Bound<'_, PyList>,
+        id_assignment: &str,
+        hash_range: Option<u32>,
+    ) -> PyResult<usize> {
         let tokens = tokens
             .into_iter()
             .map(|token| {
@@ -1268,7 +3337,40 @@ impl PyTokenizer {
             })
             .collect::<PyResult<Vec<_>>>()?;
 
-        Ok(self.tokenizer.add_special_tokens(&tokens))
+        let assignment = added_tokens_id_assignment(id_assignment, hash_range)?;
+        ToPyResult(self.tokenizer_mut().add_tokens_with(&tokens, assignment)).into()
+    }
+
+    /// Get the ids the given tokens would have once added, without adding them
+    ///
+    /// Args:
+    ///     tokens (A :obj:`List` of :class:`~tokenizers.AddedToken` or :obj:`str`):
+    ///         The tokens that would be added
+    ///
+    ///     id_assignment (:obj:`str`, defaults to :obj:`insertion`):
+    ///         How the new tokens would get their ids: ``insertion``, ``alphabetical`` or
+    ///         ``hash_stable``. See :meth:`~tokenizers.Tokenizer.add_tokens`
+    ///
+    ///     hash_range (:obj:`int`, `optional`):
+    ///         The number of ids used by ``hash_stable``
+    ///
+    /// Returns:
+    ///     A :obj:`List` of :obj:`Optional[int]`: The id of each token, which is its current
+    ///     id if it is already part of the vocabulary, or :obj:`None` if it would be ignored
+    #[pyo3(signature = (tokens, id_assignment = "insertion", hash_range = None))]
+    #[pyo3(text_signature = "(self, tokens, id_assignment='insertion', hash_range=None)")]
+    fn ids_to_add(
+        &self,
+        tokens: &Bound<'_, PyList>,
+        id_assignment: &str,
+        hash_range: Option<u32>,
+    ) -> PyResult<Vec<Option<u32>>> {
+        let tokens = added_tokens(tokens)?;
+        ToPyResult(self.tokenizer.ids_to_add(
+            &tokens,
+            added_tokens_id_assignment(id_assignment, hash_range)?,
+        ))
+        .into()
     }
 
     /// Train the Tokenizer using the given files.
@@ -1291,7 +3393,7 @@ impl PyTokenizer {
         Python::with_gil(|py| {
             py.allow_threads(|| {
                 ToPyResult(
-                    self.tokenizer
+                    self.tokenizer_mut()
                         .train_from_files(&mut trainer, files)
                         .map(|_| {}),
                 )
@@ -1355,14 +3457,133 @@ impl PyTokenizer {
 
         py.allow_threads(|| {
             ResultShunt::process(buffered_iter, |iter| {
-                self.tokenizer
-                    .train(&mut trainer, MaybeSizedIterator::new(iter, length))
-                    .map(|_| {})
-                    .map_err(|e| exceptions::PyException::new_err(e.to_string()))
+                ToPyResult(
+                    self.tokenizer_mut()
+                        .train(&mut trainer, MaybeSizedIterator::new(iter, length))
+                        .map(|_| {}),
+                )
+                .into_py()
             })?
         })
     }
 
+    /// Train the Tokenizer on sequences carrying an optional tag, like their language or their
+    /// domain, and report what the sequences of each tag contributed to the trained model
+    ///
+    /// This helps balancing the corpora mixed to train a multilingual tokenizer: a tag with
+    /// many more ``tokens`` than ``words`` is badly covered by the vocabulary, while its
+    /// ``exclusive_tokens`` are the part of the vocabulary it alone brought.
+    ///
+    /// Args:
+    ///     iterator (:obj:`Iterator`):
+    ///         Any iterator over ``(tag, sequence)`` tuples, the tag being a :obj:`str`, or
+    ///         :obj:`None` for an untagged sequence
+    ///
+    ///     trainer (:obj:`~tokenizers.trainers.Trainer`, `optional`):
+    ///         An optional trainer that should be used to train our Model
+    ///
+    ///     length (:obj:`int`, `optional`):
+    ///         The total number of sequences in the iterator. This is used to
+    ///         provide meaningful progress tracking
+    ///
+    /// Returns:
+    ///     :obj:`List[dict]`: The statistics of each tag, in order of first appearance:
+    ///     ``tag``, ``sequences``, ``words``, ``unique_words``, ``tokens`` (the number of
+    ///     tokens of these words with the trained model), ``multi_char_tokens`` (the tokens
+    ///     covering more than one char used by these words) and ``exclusive_tokens`` (the
+    ///     multi-char tokens used by no other tag)
+    #[pyo3(signature = (iterator, trainer = None, length = None))]
+    #[pyo3(text_signature = "(self, iterator, trainer=None, length=None)")]
+    fn train_tagged_from_iterator<'py>(
+        &mut self,
+        py: Python<'py>,
+        iterator: &Bound<'_, PyAny>,
+        trainer: Option<&mut PyTrainer>,
+        length: Option<usize>,
+    ) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        let mut trainer =
+            trainer.map_or_else(|| self.tokenizer.get_model().get_trainer(), |t| t.clone());
+
+        let buffered_iter = PyBufferedIterator::new(
+            iterator,
+            |element| std::iter::once(element.extract::<(Option<String>, String)>()),
+            256,
+        )?;
+
+        let statistics = py.allow_threads(|| {
+            ResultShunt::process(buffered_iter, |iter| {
+                ToPyResult(
+                    self.tokenizer_mut()
+                        .train_tagged(&mut trainer, MaybeSizedIterator::new(iter, length)),
+                )
+                .into_py()
+            })?
+        })?;
+
+        statistics
+            .into_iter()
+            .map(|stats| {
+                let dict = PyDict::new(py);
+                dict.set_item("tag", stats.tag)?;
+                dict.set_item("sequences", stats.sequences)?;
+                dict.set_item("words", stats.words)?;
+                dict.set_item("unique_words", stats.unique_words)?;
+                dict.set_item("tokens", stats.tokens)?;
+                dict.set_item("multi_char_tokens", stats.multi_char_tokens)?;
+                dict.set_item("exclusive_tokens", stats.exclusive_tokens)?;
+                Ok(dict)
+            })
+            .collect()
+    }
+
+    /// Estimate the resources needed to train the Tokenizer on a whole corpus, from a sample of
+    /// this corpus, before committing to the full run
+    ///
+    /// A copy of the model gets trained on the sample with a copy of the trainer, and the
+    /// number of unique words, the peak memory and the duration are extrapolated to the size
+    /// of the corpus. These are rough estimates, meant to size the machines of the training.
+    ///
+    /// Args:
+    ///     sample (:obj:`List[str]`):
+    ///         A sample of the corpus, ideally made of random sequences
+    ///
+    ///     corpus_size (:obj:`int`):
+    ///         The size of the whole corpus, in bytes
+    ///
+    ///     trainer (:obj:`~tokenizers.trainers.Trainer`, `optional`):
+    ///         The trainer that will be used to train the model
+    ///
+    /// Returns:
+    ///     :obj:`dict`: The estimate: ``sample_bytes``, ``sample_unique_words``,
+    ///     ``unique_words`` (in the corpus), ``peak_memory`` (in bytes) and ``training_time``
+    ///     (in seconds)
+    #[pyo3(signature = (sample, corpus_size, trainer = None))]
+    #[pyo3(text_signature = "(self, sample, corpus_size, trainer=None)")]
+    fn estimate_training<'py>(
+        slf: PyRef<'_, Self>,
+        py: Python<'py>,
+        sample: Vec<String>,
+        corpus_size: u64,
+        trainer: Option<PyRef<PyTrainer>>,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let trainer =
+            trainer.map_or_else(|| slf.tokenizer.get_model().get_trainer(), |t| t.clone());
+        let tokenizer = Self::snapshot(slf);
+        let estimate: tk::TrainingEstimate = py
+            .allow_threads(|| {
+                ToPyResult(tokenizer.estimate_training(&trainer, sample.iter(), corpus_size))
+            })
+            .into_py()?;
+
+        let dict = PyDict::new(py);
+        dict.set_item("sample_bytes", estimate.sample_bytes)?;
+        dict.set_item("sample_unique_words", estimate.sample_unique_words)?;
+        dict.set_item("unique_words", estimate.unique_words)?;
+        dict.set_item("peak_memory", estimate.peak_memory)?;
+        dict.set_item("training_time", estimate.training_time.as_secs_f64())?;
+        Ok(dict)
+    }
+
     /// Apply all the post-processing steps to the given encodings.
     ///
     /// The various steps are:
@@ -1414,7 +3635,7 @@ impl PyTokenizer {
     /// Set the :class:`~tokenizers.models.Model`
     #[setter]
     fn set_model(&mut self, model: PyRef<PyModel>) {
-        self.tokenizer.with_model(model.clone());
+        self.tokenizer_mut().with_model(model.clone());
     }
 
     /// The `optional` :class:`~tokenizers.normalizers.Normalizer` in use by the Tokenizer
@@ -1431,7 +3652,7 @@ impl PyTokenizer {
     #[setter]
     fn set_normalizer(&mut self, normalizer: Option<PyRef<PyNormalizer>>) {
         let normalizer_option = normalizer.map(|norm| norm.clone());
-        self.tokenizer.with_normalizer(normalizer_option);
+        self.tokenizer_mut().with_normalizer(normalizer_option);
     }
 
     /// The `optional` :class:`~tokenizers.pre_tokenizers.PreTokenizer` in use by the Tokenizer
@@ -1447,7 +3668,7 @@ impl PyTokenizer {
     /// Set the :class:`~tokenizers.normalizers.Normalizer`
     #[setter]
     fn set_pre_tokenizer(&mut self, pretok: Option<PyRef<PyPreTokenizer>>) {
-        self.tokenizer
+        self.tokenizer_mut()
             .with_pre_tokenizer(pretok.map(|pre| pre.clone()));
     }
 
@@ -1464,7 +3685,7 @@ impl PyTokenizer {
     /// Set the :class:`~tokenizers.processors.PostProcessor`
     #[setter]
     fn set_post_processor(&mut self, processor: Option<PyRef<PyPostProcessor>>) {
-        self.tokenizer
+        self.tokenizer_mut()
             .with_post_processor(processor.map(|p| p.clone()));
     }
 
@@ -1481,7 +3702,209 @@ impl PyTokenizer {
     /// Set the :class:`~tokenizers.decoders.Decoder`
     #[setter]
     fn set_decoder(&mut self, decoder: Option<PyRef<PyDecoder>>) {
-        self.tokenizer.with_decoder(decoder.map(|d| d.clone()));
+        self.tokenizer_mut()
+            .with_decoder(decoder.map(|d| d.clone()));
+    }
+}
+
+/// Run a candidate tokenizer in the shadow of the primary one, to check an upgrade on the
+/// real traffic before switching to it
+///
+/// Everything gets encoded by the primary tokenizer, whose results are returned as usual.
+/// The same inputs are then encoded by the candidate tokenizer on a background thread, and
+/// the inputs getting other ids are recorded in a report. The candidate never slows the
+/// primary down: when it lags behind by more than ``queue_size`` calls, the new inputs are
+/// skipped instead of waiting for it. Both tokenizers are used as they are when creating the
+/// :class:`~tokenizers.ShadowTokenizer`, their later changes are ignored.
+///
+/// Args:
+///     primary (:class:`~tokenizers.Tokenizer`):
+///         The tokenizer whose results are returned
+///
+///     candidate (:class:`~tokenizers.Tokenizer`):
+///         The tokenizer compared to the primary one
+///
+///     queue_size (:obj:`int`, defaults to :obj:`1024`):
+///         The maximum number of calls waiting for the candidate
+///
+///     max_examples (:obj:`int`, defaults to :obj:`100`):
+///         The number of divergences kept in the report
+#[pyclass(module = "tokenizers", name = "ShadowTokenizer")]
+pub struct PyShadowTokenizer {
+    shadow: tk::ShadowTokenizer<PyModel, PyNormalizer, PyPreTokenizer, PyPostProcessor, PyDecoder>,
+}
+
+#[pymethods]
+impl PyShadowTokenizer {
+    #[new]
+    #[pyo3(signature = (primary, candidate, queue_size = 1024, max_examples = 100))]
+    #[pyo3(text_signature = "(self, primary, candidate, queue_size=1024, max_examples=100)")]
+    fn new(
+        primary: PyRef<PyTokenizer>,
+        candidate: PyRef<PyTokenizer>,
+        queue_size: usize,
+        max_examples: usize,
+    ) -> Self {
+        PyShadowTokenizer {
+            shadow: tk::ShadowTokenizer::with_limits(
+                PyTokenizer::snapshot(primary),
+                PyTokenizer::snapshot(candidate),
+                queue_size,
+                max_examples,
+            ),
+        }
+    }
+
+    /// Encode the given sequence and pair with the primary tokenizer, like
+    /// :meth:`~tokenizers.Tokenizer.encode`, and queue them for the candidate
+    ///
+    /// Args:
+    ///     sequence (:obj:`~tokenizers.InputSequence`):
+    ///         The main input sequence, as accepted by :meth:`~tokenizers.Tokenizer.encode`
+    ///
+    ///     pair (:obj:`~tokenizers.InputSequence`, `optional`):
+    ///         An optional input sequence. The expected format is the same that for ``sequence``.
+    ///
+    ///     is_pretokenized (:obj:`bool`, defaults to :obj:`False`):
+    ///         Whether the input is already pre-tokenized
+    ///
+    ///     add_special_tokens (:obj:`bool`, defaults to :obj:`True`):
+    ///         Whether to add the special tokens
+    ///
+    /// Returns:
+    ///     :class:`~tokenizers.Encoding`: The encoding of the primary tokenizer
+    #[pyo3(signature = (sequence, pair = None, is_pretokenized = false, add_special_tokens = true))]
+    #[pyo3(
+        text_signature = "(self, sequence, pair=None, is_pretokenized=False, add_special_tokens=True)"
+    )]
+    fn encode(
+        &self,
+        sequence: &Bound<'_, PyAny>,
+        pair: Option<&Bound<'_, PyAny>>,
+        is_pretokenized: bool,
+        add_special_tokens: bool,
+    ) -> PyResult<PyEncoding> {
+        let input = PyTokenizer::encode_input(sequence, pair, is_pretokenized)?;
+        ToPyResult(
+            self.shadow
+                .encode_char_offsets(input, add_special_tokens)
+                .map(|e| e.into()),
+        )
+        .into()
+    }
+
+    /// Encode the given batch of inputs with the primary tokenizer, like
+    /// :meth:`~tokenizers.Tokenizer.encode_batch`, and queue them for the candidate
+    ///
+    /// Args:
+    ///     input (A :obj:`List`/:obj:`Tuple` of :obj:`~tokenizers.EncodeInput`):
+    ///         The batch of inputs, as accepted by :meth:`~tokenizers.Tokenizer.encode_batch`
+    ///
+    ///     is_pretokenized (:obj:`bool`, defaults to :obj:`False`):
+    ///         Whether the input is already pre-tokenized
+    ///
+    ///     add_special_tokens (:obj:`bool`, defaults to :obj:`True`):
+    ///         Whether to add the special tokens
+    ///
+    /// Returns:
+    ///     A :obj:`List` of :class:`~tokenizers.Encoding`: The encodings of the primary
+    ///     tokenizer
+    #[pyo3(signature = (input, is_pretokenized = false, add_special_tokens = true))]
+    #[pyo3(text_signature = "(self, input, is_pretokenized=False, add_special_tokens=True)")]
+    fn encode_batch(
+        &self,
+        py: Python<'_>,
+        input: Vec<Bound<'_, PyAny>>,
+        is_pretokenized: bool,
+        add_special_tokens: bool,
+    ) -> PyResult<Vec<PyEncoding>> {
+        let mut items = Vec::<tk::EncodeInput>::with_capacity(input.len());
+        for item in &input {
+            let item: tk::EncodeInput = if is_pretokenized {
+                item.extract::<PreTokenizedEncodeInput>()?.into()
+            } else {
+                item.extract::<TextEncodeInput>()?.into()
+            };
+            items.push(item);
+        }
+        py.allow_threads(|| {
+            ToPyResult(
+                self.shadow
+                    .encode_batch_char_offsets(items, add_special_tokens)
+                    .map(|encodings| encodings.into_iter().map(|e| e.into()).collect()),
+            )
+            .into()
+        })
+    }
+
+    /// Wait for the candidate to compare all the inputs queued so far
+    #[pyo3(text_signature = "(self)")]
+    fn flush(&self, py: Python<'_>) {
+        // The candidate may need the GIL for its custom components
+        py.allow_threads(|| self.shadow.flush())
+    }
+
+    /// The report of the divergences found so far
+    ///
+    /// The inputs still waiting for the candidate are not part of it, unless
+    /// :meth:`~tokenizers.ShadowTokenizer.flush` was called first.
+    ///
+    /// Args:
+    ///     reset (:obj:`bool`, defaults to :obj:`False`):
+    ///         Whether to start a new report
+    ///
+    /// Returns:
+    ///     :obj:`Dict`: The number of inputs ``compared``, of the ones that ``diverged``,
+    ///     and of the ones ``skipped`` because the candidate was lagging behind, the
+    ///     ``divergence_rate``, and the first ``examples`` of divergences, each with its
+    ///     ``input``, the ``primary`` :class:`~tokenizers.Encoding`, and the ``candidate``
+    ///     one, or the error message of the candidate tokenizer
+    #[pyo3(signature = (reset = false))]
+    #[pyo3(text_signature = "(self, reset=False)")]
+    fn report<'py>(&self, py: Python<'py>, reset: bool) -> PyResult<Bound<'py, PyDict>> {
+        let report = if reset {
+            self.shadow.take_report()
+        } else {
+            self.shadow.report()
+        };
+        let sequence = |sequence: tk::InputSequence| -> PyResult<PyObject> {
+            Ok(match sequence {
+                tk::InputSequence::Raw(s) => s.into_pyobject(py)?.into_any().unbind(),
+                tk::InputSequence::PreTokenized(words) => words.into_pyobject(py)?.unbind(),
+                tk::InputSequence::PreTokenizedOwned(words) => words.into_pyobject(py)?.unbind(),
+                tk::InputSequence::PreTokenizedCow(words) => words.into_pyobject(py)?.unbind(),
+            })
+        };
+        let dict = PyDict::new(py);
+        dict.set_item("compared", report.compared)?;
+        dict.set_item("diverged", report.diverged)?;
+        dict.set_item("skipped", report.skipped)?;
+        dict.set_item("divergence_rate", report.divergence_rate())?;
+
+        let examples = PyList::empty(py);
+        for example in report.examples {
+            let input = match example.input {
+                tk::EncodeInput::Single(s) => sequence(s)?,
+                tk::EncodeInput::Dual(s, pair) => (sequence(s)?, sequence(pair)?)
+                    .into_pyobject(py)?
+                    .into_any()
+                    .unbind(),
+            };
+            let candidate = match example.candidate {
+                Ok(encoding) => PyEncoding::from(encoding)
+                    .into_pyobject(py)?
+                    .into_any()
+                    .unbind(),
+                Err(error) => error.into_pyobject(py)?.into_any().unbind(),
+            };
+            let item = PyDict::new(py);
+            item.set_item("input", input)?;
+            item.set_item("primary", PyEncoding::from(example.primary))?;
+            item.set_item("candidate", candidate)?;
+            examples.append(item)?;
+        }
+        dict.set_item("examples", examples)?;
+        Ok(dict)
     }
 }
 
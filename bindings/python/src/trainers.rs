@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
 
 use crate::models::PyModel;
@@ -7,6 +8,8 @@ use pyo3::prelude::*;
 use pyo3::types::*;
 use serde::{Deserialize, Serialize};
 use tk::models::TrainerWrapper;
+use tk::utils::progress::ProgressCallback;
+use tk::utils::script_weights::ScriptWeights;
 use tk::Trainer;
 use tokenizers as tk;
 
@@ -45,6 +48,10 @@ impl PyTrainer {
                 .into_pyobject(py)?
                 .into_any()
                 .into(),
+            TrainerWrapper::MaxMatchTrainer(_) => Py::new(py, (PyMaxMatchTrainer {}, base))?
+                .into_pyobject(py)?
+                .into_any()
+                .into(),
         })
     }
 }
@@ -83,6 +90,55 @@ impl PyTrainer {
         crate::utils::serde_pyo3::to_string(self)
             .map_err(|e| exceptions::PyException::new_err(e.to_string()))
     }
+
+    /// Set a callback reporting the progress of the training
+    ///
+    /// The callback replaces the progress bar. It gets called at the end of each phase of
+    /// the training, and after each step of the iterative phases (merges, EM iterations).
+    /// If the callback raises an exception, the training stops and raises it.
+    ///
+    /// Args:
+    ///     callback (:obj:`Callable[[Dict], Optional[bool]]`, `optional`):
+    ///         Called with a dict containing the ``phase``, ``iteration``, ``total``,
+    ///         ``vocab_size`` and ``loss`` of the training. Returning :obj:`False` stops the
+    ///         training early, except for the trainers learning everything in a single phase, like
+    ///         the :class:`~tokenizers.trainers.WordLevelTrainer`, whose training fails instead.
+    ///         :obj:`None` removes any previously set callback
+    #[pyo3(signature = (callback=None), text_signature = "(self, callback=None)")]
+    fn set_progress_callback(&self, callback: Option<Bound<'_, PyAny>>) -> PyResult<()> {
+        let callback = match callback {
+            Some(callback) if !callback.is_callable() => {
+                return Err(exceptions::PyTypeError::new_err(
+                    "`callback` must be a callable",
+                ))
+            }
+            Some(callback) => {
+                let callback = callback.unbind();
+                Some(ProgressCallback::fallible(move |progress| {
+                    Python::with_gil(|py| {
+                        let dict = PyDict::new(py);
+                        dict.set_item("phase", progress.phase)?;
+                        dict.set_item("iteration", progress.iteration)?;
+                        dict.set_item("total", progress.total)?;
+                        dict.set_item("vocab_size", progress.vocab_size)?;
+                        dict.set_item("loss", progress.loss)?;
+                        let result = callback.call1(py, (dict,))?;
+                        Ok(!matches!(
+                            result.extract::<Option<bool>>(py),
+                            Ok(Some(false))
+                        ))
+                    })
+                    .map_err(|e: PyErr| e.into())
+                }))
+            }
+            None => None,
+        };
+        self.trainer
+            .write()
+            .unwrap()
+            .set_progress_callback(callback);
+        Ok(())
+    }
 }
 
 impl Trainer for PyTrainer {
@@ -107,6 +163,24 @@ impl Trainer for PyTrainer {
     {
         self.trainer.write().unwrap().feed(iterator, process)
     }
+
+    fn estimate<I, S, F>(
+        &self,
+        model: &PyModel,
+        sample: I,
+        corpus_bytes: u64,
+        process: F,
+    ) -> tk::Result<tk::TrainingEstimate>
+    where
+        I: Iterator<Item = S>,
+        S: AsRef<str>,
+        F: Fn(&str) -> tk::Result<Vec<String>> + Sync,
+    {
+        // Cloning a `PyTrainer` shares its trainer, which must not get fed the sample
+        let trainer = self.trainer.read().unwrap().clone();
+        let model = model.model.read().unwrap().clone();
+        trainer.estimate(&model, sample, corpus_bytes, process)
+    }
 }
 
 impl<I> From<I> for PyTrainer
@@ -146,6 +220,10 @@ macro_rules! setter {
     }};
 }
 
+fn script_weights(weights: HashMap<String, f64>) -> PyResult<ScriptWeights> {
+    ScriptWeights::new(weights).map_err(|e| exceptions::PyValueError::new_err(e.to_string()))
+}
+
 /// Trainer capable of training a BPE model
 ///
 /// Args:
@@ -181,6 +259,24 @@ macro_rules! setter {
 ///         This can help with reducing polluting your vocabulary with
 ///         highly repetitive tokens like `======` for wikipedia
 ///
+///     max_memory (:obj:`int`, `optional`):
+///         A hint, in bytes, of the memory the word counts may use while feeding the trainer.
+///         Above it, the counts get spilled to disk and merged back once all the data has
+///         been seen. The training itself still holds all the counts in memory
+///
+///     script_weights (:obj:`Dict[str, float]`, `optional`):
+///         The weight of the words of each Unicode script in the counts of the pairs, like
+///         :obj:`{"Latin": 0.5}` to make the merges that only help English look half as
+///         frequent as they are, and balance the compression of a multilingual vocabulary.
+///         The script of a word is the one of most of its characters, ignoring the digits
+///         and punctuation, and decoding the byte-level words first. The names must be those
+///         of the Unicode scripts. The words of the scripts without weight keep their counts
+///
+///     lexicographic_ties (:obj:`bool`, `optional`, defaults to :obj:`False`):
+///         Whether to merge the pairs with the same count in the lexicographic order of their
+///         tokens, instead of an order that may differ between platforms or versions, so that
+///         the same corpus always gives the same tokenizer
+///
 #[pyclass(extends=PyTrainer, module = "tokenizers.trainers", name = "BpeTrainer")]
 pub struct PyBpeTrainer {}
 #[pymethods]
@@ -272,6 +368,44 @@ impl PyBpeTrainer {
         setter!(self_, BpeTrainer, max_token_length, limit);
     }
 
+    #[getter]
+    fn get_max_memory(self_: PyRef<Self>) -> Option<usize> {
+        getter!(self_, BpeTrainer, max_memory)
+    }
+
+    #[setter]
+    fn set_max_memory(self_: PyRef<Self>, max_memory: Option<usize>) {
+        setter!(self_, BpeTrainer, max_memory, max_memory);
+    }
+
+    #[getter]
+    fn get_script_weights(self_: PyRef<Self>) -> HashMap<String, f64> {
+        getter!(
+            self_,
+            BpeTrainer,
+            script_weights
+                .iter()
+                .map(|(script, weight)| (script.to_owned(), weight))
+                .collect()
+        )
+    }
+
+    #[setter]
+    fn set_script_weights(self_: PyRef<Self>, weights: HashMap<String, f64>) -> PyResult<()> {
+        setter!(self_, BpeTrainer, script_weights, script_weights(weights)?);
+        Ok(())
+    }
+
+    #[getter]
+    fn get_lexicographic_ties(self_: PyRef<Self>) -> bool {
+        getter!(self_, BpeTrainer, lexicographic_ties)
+    }
+
+    #[setter]
+    fn set_lexicographic_ties(self_: PyRef<Self>, lexicographic: bool) {
+        setter!(self_, BpeTrainer, lexicographic_ties, lexicographic);
+    }
+
     #[getter]
     fn get_initial_alphabet(self_: PyRef<Self>) -> Vec<String> {
         getter!(
@@ -345,6 +479,11 @@ impl PyBpeTrainer {
                     }
                     "limit_alphabet" => builder = builder.limit_alphabet(val.extract()?),
                     "max_token_length" => builder = builder.max_token_length(val.extract()?),
+                    "max_memory" => builder = builder.max_memory(val.extract()?),
+                    "script_weights" => {
+                        builder = builder.script_weights(script_weights(val.extract()?)?)
+                    }
+                    "lexicographic_ties" => builder = builder.lexicographic_ties(val.extract()?),
                     "initial_alphabet" => {
                         let alphabet: Vec<String> = val.extract()?;
                         builder = builder.initial_alphabet(
@@ -712,12 +851,158 @@ impl PyWordLevelTrainer {
     }
 }
 
+/// Trainer capable of training a MaxMatch model
+///
+/// The vocabulary is made of the special tokens, followed by the characters and then the
+/// words seen at least :obj:`min_frequency` times, the most frequent first.
+///
+/// Args:
+///     vocab_size (:obj:`int`, `optional`):
+///         The size of the final vocabulary, including all tokens and alphabet.
+///
+///     min_frequency (:obj:`int`, `optional`):
+///         The minimum frequency a character or a word should have to be part of the
+///         vocabulary.
+///
+///     show_progress (:obj:`bool`, `optional`):
+///         Whether to show progress bars while training.
+///
+///     special_tokens (:obj:`List[Union[str, AddedToken]]`):
+///         A list of special tokens the model should know of.
+#[pyclass(extends=PyTrainer, module = "tokenizers.trainers", name = "MaxMatchTrainer")]
+pub struct PyMaxMatchTrainer {}
+#[pymethods]
+impl PyMaxMatchTrainer {
+    #[getter]
+    fn get_vocab_size(self_: PyRef<Self>) -> usize {
+        getter!(self_, MaxMatchTrainer, vocab_size)
+    }
+
+    #[setter]
+    fn set_vocab_size(self_: PyRef<Self>, vocab_size: usize) {
+        setter!(self_, MaxMatchTrainer, vocab_size, vocab_size);
+    }
+
+    #[getter]
+    fn get_min_frequency(self_: PyRef<Self>) -> u64 {
+        getter!(self_, MaxMatchTrainer, min_frequency)
+    }
+
+    #[setter]
+    fn set_min_frequency(self_: PyRef<Self>, freq: u64) {
+        setter!(self_, MaxMatchTrainer, min_frequency, freq);
+    }
+
+    #[getter]
+    fn get_show_progress(self_: PyRef<Self>) -> bool {
+        getter!(self_, MaxMatchTrainer, show_progress)
+    }
+
+    #[setter]
+    fn set_show_progress(self_: PyRef<Self>, show_progress: bool) {
+        setter!(self_, MaxMatchTrainer, show_progress, show_progress);
+    }
+
+    #[getter]
+    fn get_special_tokens(self_: PyRef<Self>) -> Vec<PyAddedToken> {
+        getter!(
+            self_,
+            MaxMatchTrainer,
+            special_tokens
+                .iter()
+                .map(|tok| tok.clone().into())
+                .collect()
+        )
+    }
+
+    #[setter]
+    fn set_special_tokens(self_: PyRef<Self>, special_tokens: &Bound<'_, PyList>) -> PyResult<()> {
+        setter!(
+            self_,
+            MaxMatchTrainer,
+            special_tokens,
+            special_tokens
+                .into_iter()
+                .map(|token| {
+                    if let Ok(content) = token.extract::<String>() {
+                        Ok(tk::tokenizer::AddedToken::from(content, true))
+                    } else if let Ok(mut token) = token.extract::<PyRefMut<PyAddedToken>>() {
+                        token.special = true;
+                        Ok(token.get_token())
+                    } else {
+                        Err(exceptions::PyTypeError::new_err(
+                            "Special tokens must be a List[Union[str, AddedToken]]",
+                        ))
+                    }
+                })
+                .collect::<PyResult<Vec<_>>>()?
+        );
+        Ok(())
+    }
+
+    #[new]
+    #[pyo3(signature = (**kwargs), text_signature = None)]
+    pub fn new(kwargs: Option<&Bound<'_, PyDict>>) -> PyResult<(Self, PyTrainer)> {
+        let mut builder = tk::models::maxmatch::MaxMatchTrainer::builder();
+
+        if let Some(kwargs) = kwargs {
+            for (key, val) in kwargs {
+                let key: String = key.extract()?;
+                match key.as_ref() {
+                    "vocab_size" => {
+                        builder.vocab_size(val.extract()?);
+                    }
+                    "min_frequency" => {
+                        builder.min_frequency(val.extract()?);
+                    }
+                    "show_progress" => {
+                        builder.show_progress(val.extract()?);
+                    }
+                    "special_tokens" => {
+                        builder.special_tokens(
+                            val.downcast::<PyList>()?
+                                .into_iter()
+                                .map(|token| {
+                                    if let Ok(content) = token.extract::<String>() {
+                                        Ok(PyAddedToken::from(content, Some(true)).get_token())
+                                    } else if let Ok(mut token) =
+                                        token.extract::<PyRefMut<PyAddedToken>>()
+                                    {
+                                        token.special = true;
+                                        Ok(token.get_token())
+                                    } else {
+                                        Err(exceptions::PyTypeError::new_err(
+                                            "special_tokens must be a List[Union[str, AddedToken]]",
+                                        ))
+                                    }
+                                })
+                                .collect::<PyResult<Vec<_>>>()?,
+                        );
+                    }
+                    _ => println!("Ignored unknown kwargs option {key}"),
+                }
+            }
+        }
+
+        Ok((
+            PyMaxMatchTrainer {},
+            builder
+                .build()
+                .expect("MaxMatchTrainerBuilder cannot fail")
+                .into(),
+        ))
+    }
+}
+
 /// Trainer capable of training a Unigram model
 ///
 /// Args:
 ///     vocab_size (:obj:`int`):
 ///         The size of the final vocabulary, including all tokens and alphabet.
 ///
+///     min_frequency (:obj:`int`):
+///         The minimum frequency a word should have in order to be used for training.
+///
 ///     show_progress (:obj:`bool`):
 ///         Whether to show progress bars while training.
 ///
@@ -757,6 +1042,16 @@ impl PyUnigramTrainer {
         setter!(self_, UnigramTrainer, vocab_size, vocab_size);
     }
 
+    #[getter]
+    fn get_min_frequency(self_: PyRef<Self>) -> u32 {
+        getter!(self_, UnigramTrainer, min_frequency)
+    }
+
+    #[setter]
+    fn set_min_frequency(self_: PyRef<Self>, freq: u32) {
+        setter!(self_, UnigramTrainer, min_frequency, freq);
+    }
+
     #[getter]
     fn get_show_progress(self_: PyRef<Self>) -> bool {
         getter!(self_, UnigramTrainer, show_progress)
@@ -826,7 +1121,7 @@ impl PyUnigramTrainer {
     #[new]
     #[pyo3(
         signature = (**kwargs),
-        text_signature = "(self, vocab_size=8000, show_progress=True, special_tokens=[], shrinking_factor=0.75, unk_token=None, max_piece_length=16, n_sub_iterations=2)"
+        text_signature = "(self, vocab_size=8000, min_frequency=0, show_progress=True, special_tokens=[], shrinking_factor=0.75, unk_token=None, max_piece_length=16, n_sub_iterations=2)"
     )]
     pub fn new(kwargs: Option<Bound<'_, PyDict>>) -> PyResult<(Self, PyTrainer)> {
         let mut builder = tk::models::unigram::UnigramTrainer::builder();
@@ -835,6 +1130,7 @@ impl PyUnigramTrainer {
                 let key: String = key.extract()?;
                 match key.as_ref() {
                     "vocab_size" => builder.vocab_size(val.extract()?),
+                    "min_frequency" => builder.min_frequency(val.extract()?),
                     "show_progress" => builder.show_progress(val.extract()?),
                     "n_sub_iterations" => builder.n_sub_iterations(val.extract()?),
                     "shrinking_factor" => builder.shrinking_factor(val.extract()?),
@@ -892,6 +1188,7 @@ pub fn trainers(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PyBpeTrainer>()?;
     m.add_class::<PyWordPieceTrainer>()?;
     m.add_class::<PyWordLevelTrainer>()?;
+    m.add_class::<PyMaxMatchTrainer>()?;
     m.add_class::<PyUnigramTrainer>()?;
     Ok(())
 }
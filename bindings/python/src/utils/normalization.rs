@@ -4,8 +4,11 @@ use crate::error::ToPyResult;
 use pyo3::exceptions;
 use pyo3::prelude::*;
 use pyo3::types::*;
-use tk::normalizer::{char_to_bytes, NormalizedString, Range, SplitDelimiterBehavior};
+use std::cell::Cell;
+use tk::normalizer::{NormalizedString, Range, SplitDelimiterBehavior};
 use tk::pattern::Pattern;
+use tk::utils::offsets::char_to_bytes;
+use tk::utils::SysRegex;
 
 /// Represents a Pattern as used by `NormalizedString`
 #[derive(FromPyObject)]
@@ -35,6 +38,16 @@ impl Pattern for PyPattern {
     }
 }
 
+impl PyPattern {
+    /// The compiled regex of a `Regex` pattern, to share it instead of compiling it again
+    pub fn regex(&self) -> Option<SysRegex> {
+        match self {
+            PyPattern::Str(_) => None,
+            PyPattern::Regex(r) => Some(Python::with_gil(|py| r.borrow(py).inner.clone())),
+        }
+    }
+}
+
 impl From<PyPattern> for tk::normalizers::replace::ReplacePattern {
     fn from(pattern: PyPattern) -> Self {
         match pattern {
@@ -172,6 +185,135 @@ fn map(normalized: &mut NormalizedString, func: &Bound<'_, PyAny>) -> PyResult<(
     }
 }
 
+fn filter_graphemes(normalized: &mut NormalizedString, func: &Bound<'_, PyAny>) -> PyResult<()> {
+    let err = "`filter_graphemes` expect a callable with the signature: `fn(str) -> bool`";
+
+    if !func.is_callable() {
+        Err(exceptions::PyTypeError::new_err(err))
+    } else {
+        normalized.filter_graphemes(|g| func.call1((g,)).expect(err).extract().expect(err));
+
+        Ok(())
+    }
+}
+
+fn map_graphemes(normalized: &mut NormalizedString, func: &Bound<'_, PyAny>) -> PyResult<()> {
+    let err = "`map_graphemes` expect a callable with the signature: `fn(str) -> str`";
+
+    if !func.is_callable() {
+        Err(exceptions::PyTypeError::new_err(err))
+    } else {
+        normalized.map_graphemes(|g| func.call1((g,)).expect(err).extract().expect(err));
+
+        Ok(())
+    }
+}
+
+/// Call `func` once per chunk of at most `chunk_size` chars of the normalized string, or once
+/// with the whole string, and collect the values it returns for each char of its chunks
+fn call_batch<T>(
+    normalized: &NormalizedString,
+    func: &Bound<'_, PyAny>,
+    chunk_size: Option<usize>,
+    extract: impl Fn(&Bound<'_, PyAny>) -> PyResult<Vec<T>>,
+    err: &str,
+) -> PyResult<Vec<T>> {
+    if !func.is_callable() {
+        return Err(exceptions::PyTypeError::new_err(err.to_owned()));
+    }
+    let chars = normalized.get().chars().collect::<Vec<_>>();
+    let chunk_size = match chunk_size {
+        Some(0) => {
+            return Err(exceptions::PyValueError::new_err(
+                "`chunk_size` must be greater than 0",
+            ))
+        }
+        Some(chunk_size) => chunk_size,
+        None => chars.len().max(1),
+    };
+
+    let mut values = Vec::with_capacity(chars.len());
+    for chunk in chars.chunks(chunk_size) {
+        let chunk_values = extract(&func.call1((chunk.iter().collect::<String>(),))?)
+            .map_err(|_| exceptions::PyTypeError::new_err(err.to_owned()))?;
+        if chunk_values.len() != chunk.len() {
+            return Err(exceptions::PyValueError::new_err(format!(
+                "{err}, with one value for each of the {} chars, got {}",
+                chunk.len(),
+                chunk_values.len()
+            )));
+        }
+        values.extend(chunk_values);
+    }
+    Ok(values)
+}
+
+fn filter_batch(
+    normalized: &mut NormalizedString,
+    func: &Bound<'_, PyAny>,
+    chunk_size: Option<usize>,
+) -> PyResult<()> {
+    let err = "`filter_batch` expect a callable with the signature: `fn(str) -> List[bool]`";
+    let keep = call_batch(normalized, func, chunk_size, |mask| mask.extract(), err)?;
+    let index = Cell::new(0);
+    normalized.filter(|_| {
+        index.set(index.get() + 1);
+        keep[index.get() - 1]
+    });
+    Ok(())
+}
+
+fn map_batch(
+    normalized: &mut NormalizedString,
+    func: &Bound<'_, PyAny>,
+    chunk_size: Option<usize>,
+) -> PyResult<()> {
+    let err = "`map_batch` expect a callable with the signature: `fn(str) -> str`";
+    let extract =
+        |mapped: &Bound<'_, PyAny>| Ok(mapped.extract::<String>()?.chars().collect::<Vec<_>>());
+    let mapped = call_batch(normalized, func, chunk_size, extract, err)?;
+    let index = Cell::new(0);
+    normalized.map(|_| {
+        index.set(index.get() + 1);
+        mapped[index.get() - 1]
+    });
+    Ok(())
+}
+
+fn transform(
+    normalized: &mut NormalizedString,
+    offsets: Vec<isize>,
+    new_text: &str,
+    initial_offset: usize,
+) -> PyResult<()> {
+    let n_chars = new_text.chars().count();
+    if offsets.len() != n_chars {
+        return Err(exceptions::PyValueError::new_err(format!(
+            "`offsets` must have one change for each of the {n_chars} chars of `new_text`, got {}",
+            offsets.len()
+        )));
+    }
+    // Each char replaces the next one and removes the `-change` following ones, or is inserted
+    let consumed = offsets
+        .iter()
+        .map(|change| {
+            if *change > 0 {
+                0
+            } else {
+                1 + change.unsigned_abs()
+            }
+        })
+        .fold(initial_offset, usize::saturating_add);
+    let len = normalized.get().chars().count();
+    if consumed != len {
+        return Err(exceptions::PyValueError::new_err(format!(
+            "The changes in `offsets` cover {consumed} chars, but the string has {len}"
+        )));
+    }
+    normalized.transform(new_text.chars().zip(offsets), initial_offset);
+    Ok(())
+}
+
 fn slice(
     normalized: &NormalizedString,
     range: &PyRange<'_>,
@@ -320,6 +462,92 @@ impl PyNormalizedString {
         map(&mut self.normalized, func)
     }
 
+    /// Filter each grapheme cluster of the string using the given func
+    ///
+    /// Unlike `filter`, the func receives whole user-perceived characters, like a letter
+    /// along with its combining marks, so they are either kept or removed together.
+    ///
+    /// Args:
+    ///     func: Callable[[str], bool]:
+    ///         The function deciding which grapheme clusters to keep
+    #[pyo3(text_signature = "(self, func)")]
+    fn filter_graphemes(&mut self, func: &Bound<'_, PyAny>) -> PyResult<()> {
+        filter_graphemes(&mut self.normalized, func)
+    }
+
+    /// Calls the given function for each grapheme cluster of the string
+    ///
+    /// Replaces each grapheme cluster using the returned value, which can be a str of any
+    /// length, aligned with the whole original cluster. Use an empty str to remove it.
+    ///
+    /// Args:
+    ///     func: Callable[[str], str]:
+    ///         The function replacing the grapheme clusters
+    #[pyo3(text_signature = "(self, func)")]
+    fn map_graphemes(&mut self, func: &Bound<'_, PyAny>) -> PyResult<()> {
+        map_graphemes(&mut self.normalized, func)
+    }
+
+    /// Filter the characters of the string, calling the given func once per chunk
+    ///
+    /// Unlike `filter`, the func receives a whole chunk of the string, and returns a list with
+    /// a bool for each of its characters, telling whether to keep it.
+    ///
+    /// Args:
+    ///     func: Callable[[str], List[bool]]:
+    ///         The function deciding which characters to keep
+    ///
+    ///     chunk_size: Optional[int]:
+    ///         The maximum number of characters of each chunk. By default, the whole string
+    ///         is given at once
+    #[pyo3(signature = (func, chunk_size = None), text_signature = "(self, func, chunk_size=None)")]
+    fn filter_batch(&mut self, func: &Bound<'_, PyAny>, chunk_size: Option<usize>) -> PyResult<()> {
+        filter_batch(&mut self.normalized, func, chunk_size)
+    }
+
+    /// Replace the characters of the string, calling the given func once per chunk
+    ///
+    /// Unlike `map`, the func receives a whole chunk of the string, and returns a str with the
+    /// replacement of each of its characters, so with the same number of characters.
+    ///
+    /// Args:
+    ///     func: Callable[[str], str]:
+    ///         The function replacing the characters
+    ///
+    ///     chunk_size: Optional[int]:
+    ///         The maximum number of characters of each chunk. By default, the whole string
+    ///         is given at once
+    #[pyo3(signature = (func, chunk_size = None), text_signature = "(self, func, chunk_size=None)")]
+    fn map_batch(&mut self, func: &Bound<'_, PyAny>, chunk_size: Option<usize>) -> PyResult<()> {
+        map_batch(&mut self.normalized, func, chunk_size)
+    }
+
+    /// Replace the string with the given text, using the given alignment
+    ///
+    /// This applies precomputed edits at once, keeping track of the alignments with the
+    /// original string.
+    ///
+    /// Args:
+    ///     offsets: List[int]:
+    ///         The change of each character of `new_text`, compared to the current string:
+    ///         `1` if it is a new character, `0` if it replaces the next character, and `-N`
+    ///         if it replaces the next character and removes the `N` following ones
+    ///
+    ///     new_text: str:
+    ///         The new content of the string
+    ///
+    ///     initial_offset: int:
+    ///         The number of characters removed at the very beginning of the string
+    #[pyo3(signature = (offsets, new_text, initial_offset = 0), text_signature = "(self, offsets, new_text, initial_offset=0)")]
+    fn transform(
+        &mut self,
+        offsets: Vec<isize>,
+        new_text: &str,
+        initial_offset: usize,
+    ) -> PyResult<()> {
+        transform(&mut self.normalized, offsets, new_text, initial_offset)
+    }
+
     /// Split the NormalizedString using the given pattern and the specified behavior
     ///
     /// Args:
@@ -577,6 +805,49 @@ impl PyNormalizedStringRefMut {
         Ok(())
     }
 
+    fn filter_graphemes(&mut self, func: &Bound<'_, PyAny>) -> PyResult<()> {
+        self.inner
+            .map_mut(|n| filter_graphemes(n, func))
+            .ok_or_else(PyNormalizedStringRefMut::destroyed_error)??;
+        Ok(())
+    }
+
+    fn map_graphemes(&mut self, func: &Bound<'_, PyAny>) -> PyResult<()> {
+        self.inner
+            .map_mut(|n| map_graphemes(n, func))
+            .ok_or_else(PyNormalizedStringRefMut::destroyed_error)??;
+        Ok(())
+    }
+
+    #[pyo3(signature = (func, chunk_size = None))]
+    fn filter_batch(&mut self, func: &Bound<'_, PyAny>, chunk_size: Option<usize>) -> PyResult<()> {
+        self.inner
+            .map_mut(|n| filter_batch(n, func, chunk_size))
+            .ok_or_else(PyNormalizedStringRefMut::destroyed_error)??;
+        Ok(())
+    }
+
+    #[pyo3(signature = (func, chunk_size = None))]
+    fn map_batch(&mut self, func: &Bound<'_, PyAny>, chunk_size: Option<usize>) -> PyResult<()> {
+        self.inner
+            .map_mut(|n| map_batch(n, func, chunk_size))
+            .ok_or_else(PyNormalizedStringRefMut::destroyed_error)??;
+        Ok(())
+    }
+
+    #[pyo3(signature = (offsets, new_text, initial_offset = 0))]
+    fn transform(
+        &mut self,
+        offsets: Vec<isize>,
+        new_text: &str,
+        initial_offset: usize,
+    ) -> PyResult<()> {
+        self.inner
+            .map_mut(|n| transform(n, offsets, new_text, initial_offset))
+            .ok_or_else(PyNormalizedStringRefMut::destroyed_error)??;
+        Ok(())
+    }
+
     fn split(
         &mut self,
         pattern: PyPattern,
@@ -67,7 +67,7 @@ fn tokenize(pretok: &mut PreTokenizedString, func: &Bound<'_, PyAny>) -> PyResul
 
 /// This is an enum
 #[derive(Clone)]
-pub struct PyOffsetReferential(OffsetReferential);
+pub struct PyOffsetReferential(pub(crate) OffsetReferential);
 impl FromPyObject<'_> for PyOffsetReferential {
     fn extract_bound(obj: &Bound<'_, PyAny>) -> PyResult<Self> {
         let s = obj.extract::<String>()?;
@@ -3,6 +3,10 @@ use pyo3::prelude::*;
 use tk::utils::SysRegex;
 
 /// Instantiate a new Regex with the given pattern
+///
+/// The pattern is compiled once, and shared by all the components using this Regex, like
+/// the :class:`~tokenizers.pre_tokenizers.Split` pre-tokenizer or the
+/// :class:`~tokenizers.normalizers.Replace` normalizer.
 #[pyclass(module = "tokenizers", name = "Regex")]
 pub struct PyRegex {
     pub inner: SysRegex,
@@ -20,4 +24,24 @@ impl PyRegex {
             pattern: s.to_owned(),
         })
     }
+
+    /// The pattern of this Regex
+    #[getter]
+    fn get_pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Escape the given string, so that it gets matched literally once used in a pattern
+    ///
+    /// Args:
+    ///     text (:obj:`str`):
+    ///         The string to escape
+    ///
+    /// Returns:
+    ///     :obj:`str`: The escaped string
+    #[staticmethod]
+    #[pyo3(text_signature = "(text)")]
+    fn escape(text: &str) -> String {
+        SysRegex::escape(text)
+    }
 }
@@ -93,7 +93,9 @@ def pyi_file(obj, indent=""):
         string += "\n\n"
 
     elif inspect.isbuiltin(obj):
-        string += f"{indent}@staticmethod\n"
+        # Module level functions are builtins too
+        if indent:
+            string += f"{indent}@staticmethod\n"
         string += function(obj, indent)
 
     elif inspect.ismethoddescriptor(obj):
@@ -9,6 +9,7 @@ from tokenizers.decoders import (
     ByteLevel,
     Decoder,
     Metaspace,
+    RunLength,
     Sequence,
     WordPiece,
     ByteFallback,
@@ -95,6 +96,26 @@ class TestByteFallback:
         assert decoder.decode(["<0xE5>", "<0x8f>", "a"]) == "��a"
         assert decoder.decode(["<0xE5>", "<0x8f>", "<0xab>", "a"]) == "叫a"
 
+    def test_invalid_utf8(self):
+        decoder = ByteFallback()
+        assert decoder.invalid_utf8 == "replace"
+
+        decoder = ByteFallback(invalid_utf8="ignore")
+        assert decoder.invalid_utf8 == "ignore"
+        assert decoder.decode(["<0xE5>", "<0x8f>", "a"]) == "a"
+        assert decoder.decode(["<0x61>", "<0xE5>"]) == "a"
+
+        decoder.invalid_utf8 = "strict"
+        assert decoder.decode(["<0xE5>", "<0x8f>", "<0xab>"]) == "叫"
+        with pytest.raises(Exception, match="Invalid UTF-8"):
+            decoder.decode(["<0xE5>", "<0x8f>", "a"])
+
+        assert isinstance(pickle.loads(pickle.dumps(decoder)), ByteFallback)
+        assert pickle.loads(pickle.dumps(decoder)).invalid_utf8 == "strict"
+
+        with pytest.raises(ValueError, match="unknown variant"):
+            ByteFallback(invalid_utf8="backslash")
+
 
 class TestFuse:
     def test_instantiate(self):
@@ -120,6 +141,27 @@ class TestStrip:
         assert decoder.decode(["_My", " na", "me", " _-", "__-"]) == "My name _-_-"
 
 
+class TestRunLength:
+    def test_instantiate(self):
+        assert RunLength() is not None
+        assert isinstance(RunLength(), Decoder)
+        assert isinstance(RunLength(), RunLength)
+        assert isinstance(pickle.loads(pickle.dumps(RunLength())), RunLength)
+        with pytest.raises(ValueError, match="expected a string of length 1"):
+            RunLength(marker="")
+
+    def test_decoding(self):
+        decoder = RunLength()
+        assert decoder.max_run == 8
+        assert decoder.marker == "⟳"
+        assert decoder.decode(["wait", "!", "⟳12⟳"]) == "wait" + "!" * 12
+        assert decoder.decode(["1", "0", "⟳9⟳", "1"]) == "1" + "0" * 9 + "1"
+
+        decoder.marker = "#"
+        assert decoder.marker == "#"
+        assert decoder.decode(["a", "#3#", " #b"]) == "aaa #b"
+
+
 class TestMetaspace:
     def test_instantiate(self):
         assert Metaspace() is not None
@@ -163,6 +205,10 @@ class TestBPEDecoder:
         assert decoder.decode(["My</w>", "na", "me</w>", "is</w>", "Jo", "hn</w>"]) == "My name is John"
         decoder = BPEDecoder(suffix="_")
         assert decoder.decode(["My_", "na", "me_", "is_", "Jo", "hn_"]) == "My name is John"
+        decoder = BPEDecoder(suffix="", prefix="##")
+        assert decoder.decode(["My", "na", "##me", "is", "Jo", "##hn"]) == "My name is John"
+        decoder = BPEDecoder(prefix="##")
+        assert decoder.decode(["My</w>", "na", "##me</w>", "Jo", "##hn</w>"]) == "My name John"
 
     def test_can_modify(self):
         decoder = BPEDecoder(suffix="123")
@@ -172,6 +218,9 @@ class TestBPEDecoder:
         # Modify these
         decoder.suffix = "</w>"
         assert decoder.suffix == "</w>"
+        assert decoder.prefix is None
+        decoder.prefix = "##"
+        assert decoder.prefix == "##"
 
 
 class TestCTCDecoder:
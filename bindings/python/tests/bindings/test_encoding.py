@@ -1,6 +1,8 @@
 import pytest
 
-from tokenizers import BertWordPieceTokenizer
+from tokenizers import BertWordPieceTokenizer, TokenPattern, Tokenizer, normalizers, pre_tokenizers
+from tokenizers.models import BPE, WordLevel
+from tokenizers.processors import TemplateProcessing
 
 from ..utils import bert_files, data_dir
 
@@ -98,6 +100,44 @@ class TestEncoding:
         assert pair.char_to_token(0, 1) == 6
         assert pair.char_to_token(2, 1) == None
 
+    def test_span_to_tokens(self, encodings):
+        single, pair = encodings
+
+        assert single.span_to_tokens((3, 10)) == (2, 4)
+        assert single.span_to_tokens((0, 18)) == (1, 5)
+        assert single.span_to_tokens((1, 2)) == None
+        assert single.span_to_tokens((3, 3)) == None
+        assert pair.span_to_tokens((0, 4), 1) == (6, 8)
+
+    def test_mappings_with_normalizer(self):
+        vocab = {t: i for i, t in enumerate(["▁", "f", "i", "x", "h"])}
+        tokenizer = Tokenizer(BPE(vocab, []))
+        tokenizer.normalizer = normalizers.Sequence([normalizers.Strip(), normalizers.NFKC()])
+        tokenizer.pre_tokenizer = pre_tokenizers.Metaspace()
+
+        # The spaces are stripped, "▁" is inserted before "ﬁ", which becomes "fi"
+        encoding = tokenizer.encode("  ﬁx hi ")
+        assert encoding.tokens == ["▁", "f", "i", "x", "▁", "h", "i"]
+        assert encoding.char_to_token(0) == None
+        assert encoding.char_to_token(2) == 0
+        assert encoding.token_to_chars(2) == (2, 3)
+        assert encoding.span_to_tokens((0, 2)) == None
+        assert encoding.span_to_tokens((0, 3)) == (0, 3)
+        assert encoding.span_to_tokens((3, 6)) == (3, 6)
+
+    def test_redact(self):
+        vocab = {t: i for i, t in enumerate(["[UNK]", "[PII]", "call", "jane", "doe", "now"])}
+        tokenizer = Tokenizer(WordLevel(vocab, unk_token="[UNK]"))
+        tokenizer.pre_tokenizer = pre_tokenizers.WhitespaceSplit()
+
+        encoding = tokenizer.encode("call jane doe now")
+        placeholder = tokenizer.encode("[PII]")
+        encoding.redact([(5, 9), (8, 13)], placeholder)
+        assert encoding.tokens == ["call", "[PII]", "now"]
+        assert encoding.ids == [2, 1, 5]
+        assert encoding.offsets == [(0, 4), (5, 13), (14, 17)]
+        assert encoding.word_ids == [0, 1, 2]
+
     def test_char_to_word(self, encodings):
         single, pair = encodings
 
@@ -119,3 +159,49 @@ class TestEncoding:
         with pytest.raises(ValueError) as excinfo:
             single.truncate(2, 1, "not_a_direction")
         assert "Invalid truncation direction value : not_a_direction" == str(excinfo.value)
+
+
+class TestTokenPattern:
+    @pytest.fixture(scope="class")
+    def tokenizer(self):
+        vocab = {t: i for i, t in enumerate(["[UNK]", "[CLS]", "[SEP]", "drop", "the", "table", "now"])}
+        tokenizer = Tokenizer(WordLevel(vocab, unk_token="[UNK]"))
+        tokenizer.pre_tokenizer = pre_tokenizers.WhitespaceSplit()
+        tokenizer.post_processor = TemplateProcessing(
+            single="[CLS] $A [SEP]",
+            pair="[CLS] $A [SEP] $B [SEP]",
+            special_tokens=[("[CLS]", 1), ("[SEP]", 2)],
+        )
+        return tokenizer
+
+    def test_find_all(self, tokenizer):
+        # drop, then at most one token, then table
+        pattern = TokenPattern([3, (0, 1), [5, 6]])
+        encoding = tokenizer.encode("drop the table now drop table")
+        assert pattern.is_match(encoding)
+        assert pattern.find_all(encoding) == [
+            {"tokens": (1, 4), "sequence": 0, "offsets": (0, 14)},
+            {"tokens": (5, 7), "sequence": 0, "offsets": (19, 29)},
+        ]
+
+        # The matches never cover two sequences
+        pattern = TokenPattern([5, "special", 3])
+        encoding = tokenizer.encode("drop table", "drop it")
+        assert not pattern.is_match(encoding)
+        assert TokenPattern(["special", "any"]).find_all(encoding) == [
+            {"tokens": (0, 2), "sequence": 0, "offsets": (0, 4)},
+            {"tokens": (3, 5), "sequence": 1, "offsets": (0, 4)},
+        ]
+        assert TokenPattern(["special"]).find_all(encoding)[0] == {
+            "tokens": (0, 1),
+            "sequence": None,
+            "offsets": None,
+        }
+
+    def test_invalid_pattern(self):
+        with pytest.raises(ValueError, match="must match at least one token"):
+            TokenPattern([(0, 2)])
+        with pytest.raises(ValueError, match="minimum 3 is above its maximum 1"):
+            TokenPattern([(3, 1)])
+        with pytest.raises(ValueError, match="Unknown token class `word`"):
+            TokenPattern([1, "word"])
@@ -1,8 +1,9 @@
+import json
 import pickle
 
 import pytest
 
-from tokenizers.models import BPE, Model, WordLevel, WordPiece
+from tokenizers.models import BPE, Hybrid, MaxMatch, Model, Unigram, WordLevel, WordPiece
 from ..utils import bert_files, data_dir, roberta_files
 
 
@@ -39,6 +40,33 @@ class TestBPE:
                 BPE,
             )
 
+    def test_files(self, tmp_path):
+        model = BPE({"a": 0, "b": 1, "ab": 2}, [("a", "b")])
+        files = model.files()
+        assert files == {"vocab.json": b'{"a":0,"b":1,"ab":2}', "merges.txt": b"#version: 0.2\na b\n"}
+        vocab, merges = BPE.read_bytes(files["vocab.json"], files["merges.txt"])
+        assert vocab == {"a": 0, "b": 1, "ab": 2}
+        assert merges == [("a", "b")]
+
+        other = tmp_path / "other"
+        other.mkdir()
+        saved = model.save(str(tmp_path), "gpt", suffix="v2", filenames={"merges.txt": str(other / "m.txt")})
+        assert saved == [str(tmp_path / "gpt-vocab-v2.json"), str(other / "m.txt")]
+        assert (other / "m.txt").read_bytes() == files["merges.txt"]
+
+    def test_from_subword_nmt(self, tmp_path):
+        codes = tmp_path / "codes"
+        codes.write_text("#version: 0.2\nl o\ne r</w>\nlo w\n")
+        model = BPE.from_subword_nmt(str(codes))
+        assert model.end_of_word_suffix == "</w>"
+        assert [t.value for t in model.tokenize("lower")] == ["low", "er</w>"]
+        assert [t.value for t in model.tokenize("lowé")] == ["low", "<unk>"]
+        assert BPE.from_subword_nmt(str(codes), dropout=0.5).dropout == 0.5
+
+        codes.write_text("l o\n")
+        with pytest.raises(Exception, match="version 0.1"):
+            BPE.from_subword_nmt(str(codes))
+
     def test_can_modify(self):
         model = BPE(
             dropout=0.5,
@@ -91,6 +119,12 @@ class TestWordPiece:
         with pytest.deprecated_call():
             assert isinstance(pickle.loads(pickle.dumps(WordPiece(bert_files["vocab"]))), WordPiece)
 
+    def test_files(self):
+        model = WordPiece({"[UNK]": 0, "a": 1, "##b": 2})
+        files = model.files()
+        assert files == {"vocab.txt": b"[UNK]\na\n##b\n"}
+        assert WordPiece.read_bytes(files["vocab.txt"]) == {"[UNK]": 0, "a": 1, "##b": 2}
+
     def test_can_modify(self):
         model = WordPiece(
             unk_token="<oov>",
@@ -109,6 +143,16 @@ class TestWordPiece:
         assert model.continuing_subword_prefix == "$$$"
         model.max_input_chars_per_word = 10
         assert model.max_input_chars_per_word == 10
+        assert model.end_of_word_suffix is None
+        model.end_of_word_suffix = "</w>"
+        assert model.end_of_word_suffix == "</w>"
+
+    def test_end_of_word_suffix(self):
+        vocab = {"[UNK]": 0, "low": 1, "low</w>": 2, "##er</w>": 3}
+        model = WordPiece(vocab, end_of_word_suffix="</w>")
+        assert [t.value for t in model.tokenize("lower")] == ["low", "##er</w>"]
+        assert [t.value for t in model.tokenize("low")] == ["low</w>"]
+        assert pickle.loads(pickle.dumps(model)).end_of_word_suffix == "</w>"
 
 
 class TestWordLevel:
@@ -136,3 +180,113 @@ class TestWordLevel:
         # Modify these
         model.unk_token = "<unk>"
         assert model.unk_token == "<unk>"
+
+
+class TestHybrid:
+    def test_instantiate(self):
+        fallback = WordPiece({"[UNK]": 0, "ab": 1, "##c": 2})
+        model = Hybrid({"SKU-42": 3}, fallback)
+        assert isinstance(model, Model)
+        assert isinstance(model, Hybrid)
+        assert model.lexicon == {"SKU-42": 3}
+        assert isinstance(model.fallback, WordPiece)
+
+        with pytest.raises(ValueError, match="in the fallback model"):
+            Hybrid({"SKU-42": 1}, fallback)
+
+    def test_tokenize(self):
+        model = Hybrid({"SKU-42": 3, "abc": 4}, WordPiece({"[UNK]": 0, "ab": 1, "##c": 2}))
+        assert [(t.id, t.value) for t in model.tokenize("SKU-42")] == [(3, "SKU-42")]
+        assert [(t.id, t.value) for t in model.tokenize("abc")] == [(4, "abc")]
+        assert [(t.id, t.value) for t in model.tokenize("abcc")] == [(1, "ab"), (2, "##c"), (2, "##c")]
+        assert model.token_to_id("##c") == 2
+        assert model.id_to_token(3) == "SKU-42"
+
+        files = model.files()
+        assert sorted(files) == ["lexicon.json", "vocab.txt"]
+        assert Hybrid.read_bytes(files["lexicon.json"]) == {"SKU-42": 3, "abc": 4}
+
+    def test_can_pickle(self):
+        model = Hybrid({"SKU-42": 3}, WordLevel({"<unk>": 0, "a": 1}, unk_token="<unk>"))
+        unpickled = pickle.loads(pickle.dumps(model))
+        assert isinstance(unpickled, Hybrid)
+        assert unpickled.lexicon == {"SKU-42": 3}
+
+
+class TestMaxMatch:
+    def test_instantiate(self, roberta_files):
+        assert isinstance(MaxMatch(), Model)
+        assert isinstance(MaxMatch(), MaxMatch)
+
+        vocab = {"a": 0, "b": 1, "ab": 2}
+        assert isinstance(MaxMatch(vocab), Model)
+        assert isinstance(MaxMatch(vocab), MaxMatch)
+        assert isinstance(MaxMatch.from_file(roberta_files["vocab"]), MaxMatch)
+
+    def test_tokenize(self):
+        model = MaxMatch({"<unk>": 0, "a": 1, "b": 2, "ab": 3})
+        tokens = model.tokenize("abbac")
+        assert [t.value for t in tokens] == ["ab", "b", "a", "<unk>"]
+        assert [t.offsets for t in tokens] == [(0, 2), (2, 3), (3, 4), (4, 5)]
+
+    def test_can_modify(self):
+        model = MaxMatch(unk_token="<oov>")
+
+        assert model.unk_token == "<oov>"
+
+        # Modify these
+        model.unk_token = "<unk>"
+        assert model.unk_token == "<unk>"
+
+    def test_can_pickle(self):
+        model = MaxMatch({"<unk>": 0, "ab": 1})
+        assert isinstance(pickle.loads(pickle.dumps(model)), MaxMatch)
+
+
+class TestUnigram:
+    def test_lattice(self):
+        model = Unigram([("<unk>", 0.0), ("a", -2.0), ("b", -2.0), ("ab", -1.0)], 0)
+
+        lattice = json.loads(model.lattice_json("ab"))
+        assert lattice["sentence"] == "ab"
+        assert [n["piece"] for n in lattice["nodes"]] == ["", "", "a", "ab", "b"]
+        assert [lattice["nodes"][i]["piece"] for i in lattice["best_path"]] == ["ab"]
+
+        dot = model.lattice_dot("ab")
+        assert dot.startswith("digraph Lattice {")
+        assert "n0 -> n3 [color=red, penwidth=2];" in dot
+
+    def test_beam_width(self):
+        vocab = [("<unk>", 0.0), ("a", -2.0), ("b", -2.0), ("ab", -3.0), ("abc", -5.5), ("c", -2.0)]
+        model = Unigram(vocab, 0)
+        assert model.beam_width is None
+        assert [t.value for t in model.tokenize("abc")] == ["ab", "c"]
+
+        model = Unigram(vocab, 0, beam_width=2)
+        assert model.beam_width == 2
+        assert [t.value for t in model.tokenize("abc")] == ["ab", "c"]
+        model.beam_width = 1
+        assert model.beam_width == 1
+        assert [t.value for t in model.tokenize("abc")] == ["a", "b", "c"]
+        model.beam_width = None
+        assert model.beam_width is None
+
+        with pytest.raises(ValueError):
+            model.beam_width = 0
+        with pytest.raises(ValueError):
+            Unigram(vocab, 0, beam_width=0)
+
+    def test_lenient_scores(self):
+        vocab = [("<unk>", 0.0), ("a", -2.0), ("b", float("-inf")), ("ab", float("nan"))]
+        with pytest.raises(Exception, match='token "b" \\(id 2\\) is -inf'):
+            Unigram(vocab, 0)
+
+        model = Unigram(vocab, 0, lenient_scores=True)
+        scores = [score for _, score in json.loads(model.__getstate__())["vocab"]]
+        assert scores == [0.0, -2.0, -12.0, -12.0]
+
+        # The serialized models get clamped when loaded
+        state = json.dumps({"type": "Unigram", "unk_id": 0, "vocab": [["<unk>", 0.0], ["a", -2.0], ["b", None]]})
+        model.__setstate__(state.encode())
+        scores = [score for _, score in json.loads(model.__getstate__())["vocab"]]
+        assert scores == [0.0, -2.0, -12.0]
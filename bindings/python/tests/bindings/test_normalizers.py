@@ -2,9 +2,10 @@ import pickle
 
 import pytest
 
-from tokenizers import NormalizedString
+from tokenizers import NormalizedString, Regex, Tokenizer
 from tokenizers.normalizers import (
     BertNormalizer,
+    BidiControls,
     Lowercase,
     Normalizer,
     Precompiled,
@@ -13,6 +14,8 @@ from tokenizers.normalizers import (
     Prepend,
     Replace,
 )
+from tokenizers.models import WordLevel
+from tokenizers.pre_tokenizers import Split, WhitespaceSplit
 
 
 class TestBertNormalizer:
@@ -201,6 +204,60 @@ class TestPrepend:
         assert normalizer.prepend == "-"
 
 
+class TestReplace:
+    def test_shared_regex(self):
+        regex = Regex(r"\s+")
+        assert regex.pattern == r"\s+"
+        normalizer = Replace(regex, " ")
+        pre_tokenizer = Split(regex, "removed")
+
+        assert normalizer.normalize_str("a \t b") == "a b"
+        assert pre_tokenizer.pre_tokenize_str("a \t b") == [("a", (0, 1)), ("b", (4, 5))]
+
+    def test_escape(self):
+        escaped = Regex.escape("1+1=2?")
+        assert escaped == r"1\+1=2\?"
+        assert Replace(Regex(escaped), "x").normalize_str("is 1+1=2?") == "is x"
+
+
+class TestBidiControls:
+    def test_instantiate(self):
+        assert isinstance(BidiControls(), Normalizer)
+        assert isinstance(BidiControls(), BidiControls)
+        assert isinstance(pickle.loads(pickle.dumps(BidiControls("space"))), BidiControls)
+        with pytest.raises(ValueError, match="unknown variant"):
+            BidiControls("keep")
+
+    def test_logical_offsets(self):
+        text = "שלום \u200fעולם\u2066ABC\u2069"
+        assert BidiControls().normalize_str(text) == "שלום עולםABC"
+
+        tokenizer = Tokenizer(WordLevel({"שלום": 0, "עולם": 1, "ABC": 2}, unk_token="[UNK]"))
+        tokenizer.normalizer = BidiControls(handling="space")
+        tokenizer.pre_tokenizer = WhitespaceSplit()
+        output = tokenizer.encode(text)
+        assert output.tokens == ["שלום", "עולם", "ABC"]
+        assert [text[start:end] for start, end in output.offsets] == output.tokens
+
+    def test_can_modify(self):
+        normalizer = BidiControls()
+        assert normalizer.handling == "remove"
+        normalizer.handling = "space"
+        assert normalizer.handling == "space"
+        assert normalizer.normalize_str("a\u200fb") == "a b"
+
+
+class TestPrecompiled:
+    def test_from_rules(self):
+        normalizer = Precompiled.from_rules([("ﬁ", "fi"), ("\u3000", " ")])
+        assert isinstance(normalizer, Normalizer)
+        assert isinstance(normalizer, Precompiled)
+        assert normalizer.normalize_str("ﬁne\u3000day") == "fine day"
+
+        with pytest.raises(Exception, match="can't have an empty source"):
+            Precompiled.from_rules([("", "a")])
+
+
 class TestCustomNormalizer:
     class BadCustomNormalizer:
         def normalize(self, normalized, wrong):
@@ -235,3 +292,87 @@ class TestCustomNormalizer:
 
         assert repr(normalized) == 'NormalizedString(original="Hey there!", normalized="Hey you!")'
         assert str(normalized) == "Hey you!"
+
+
+class TestNormalizedString:
+    def test_filter_batch(self):
+        calls = []
+
+        def keep_letters(chunk):
+            calls.append(chunk)
+            return [c.isalpha() for c in chunk]
+
+        normalized = NormalizedString("a1b2 c3d")
+        normalized.filter_batch(keep_letters)
+        assert normalized.normalized == "abcd"
+        assert calls == ["a1b2 c3d"]
+        assert normalized[1:3].original == "b2 c"
+
+        calls.clear()
+        normalized = NormalizedString("a1b2 c3d")
+        normalized.filter_batch(keep_letters, chunk_size=3)
+        assert normalized.normalized == "abcd"
+        assert calls == ["a1b", "2 c", "3d"]
+
+    def test_map_batch(self):
+        normalized = NormalizedString("Hey there")
+        normalized.map_batch(lambda chunk: chunk.swapcase(), chunk_size=4)
+        assert normalized.normalized == "hEY THERE"
+
+        with pytest.raises(ValueError, match="one value for each of the 9 chars, got 8"):
+            normalized.map_batch(lambda chunk: chunk[1:])
+        with pytest.raises(TypeError, match="fn\\(str\\) -> List\\[bool\\]"):
+            normalized.filter_batch(lambda chunk: 1)
+        with pytest.raises(ValueError, match="greater than 0"):
+            normalized.filter_batch(lambda chunk: [True] * len(chunk), chunk_size=0)
+        assert normalized.normalized == "hEY THERE"
+
+    def test_filter_graphemes(self):
+        normalized = NormalizedString("e\u0301te\u0301s")
+        normalized.filter_graphemes(lambda grapheme: grapheme != "e\u0301")
+        assert normalized.normalized == "ts"
+
+        # Filtering the characters leaves the orphan combining marks
+        normalized = NormalizedString("e\u0301te\u0301s")
+        normalized.filter(lambda c: c != "e")
+        assert normalized.normalized == "\u0301t\u0301s"
+
+    def test_map_graphemes(self):
+        graphemes = []
+
+        def compose(grapheme):
+            graphemes.append(grapheme)
+            return "\u00e9" if grapheme == "e\u0301" else grapheme * 2
+
+        normalized = NormalizedString("e\u0301te\u0301")
+        normalized.map_graphemes(compose)
+        assert graphemes == ["e\u0301", "t", "e\u0301"]
+        assert normalized.normalized == "\u00e9tt\u00e9"
+        assert normalized[0:1].original == "e\u0301"
+        assert normalized[1:3].original == "t"
+
+        with pytest.raises(TypeError, match="fn\\(str\\) -> str"):
+            normalized.map_graphemes(1)
+
+    def test_transform(self):
+        normalized = NormalizedString("__Hello  world")
+        # Remove the leading underscores, replace the spaces with a single "_", and add "!"
+        normalized.transform([0, 0, 0, 0, 0, -1, 0, 0, 0, 0, 0, 1], "Hello_world!", initial_offset=2)
+        assert normalized.normalized == "Hello_world!"
+        assert normalized[0:5].original == "Hello"
+        assert normalized[5:6].original == " "
+        assert normalized[6:12].original == "world"
+
+        with pytest.raises(ValueError, match="one change for each of the 3 chars"):
+            normalized.transform([0, 0], "abc")
+        with pytest.raises(ValueError, match="cover 3 chars, but the string has 12"):
+            normalized.transform([0, 0, 0], "abc")
+
+    def test_custom_normalizer(self):
+        class Normalize:
+            def normalize(self, normalized):
+                normalized.filter_batch(lambda chunk: [c != "-" for c in chunk])
+                normalized.map_batch(str.upper)
+
+        normalizer = Normalizer.custom(Normalize())
+        assert normalizer.normalize_str("a-b-c") == "ABC"
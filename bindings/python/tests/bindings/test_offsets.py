@@ -0,0 +1,31 @@
+from tokenizers.offsets import OffsetConverter, bytes_to_char, char_to_bytes
+
+
+class TestOffsets:
+    def test_functions(self):
+        text = "Hello 👋 there"
+        assert char_to_bytes(text, (6, 7)) == (6, 10)
+        assert bytes_to_char(text, (11, 16)) == (8, 13)
+        # Not on a char boundary
+        assert bytes_to_char(text, (7, 10)) is None
+        # Past the end of the string
+        assert char_to_bytes(text, (20, 22)) is None
+        assert char_to_bytes(text, (8, 20)) == (11, 16)
+
+    def test_converter(self):
+        text = "Ça va? 👋"
+        converter = OffsetConverter(text)
+        assert converter.byte_to_char(3) == 2
+        assert converter.byte_to_char(1) is None
+        assert converter.char_to_byte(7) == 8
+        assert converter.char_to_byte(9) is None
+        assert converter.bytes_to_char((8, 12)) == (7, 8)
+        assert converter.char_to_bytes((7, 8)) == (8, 12)
+        assert converter.char_to_bytes((8, 8)) == (12, 12)
+
+    def test_slice_utf8(self):
+        text = "Ça va? 👋"
+        data = text.encode("utf-8")
+        converter = OffsetConverter(text)
+        start, end = converter.char_to_bytes((7, 8))
+        assert data[start:end].decode("utf-8") == "👋"
@@ -3,18 +3,26 @@ import pickle
 
 import pytest
 
+from tokenizers.models import WordPiece
+from tokenizers.normalizers import Lowercase
 from tokenizers.pre_tokenizers import (
     BertPreTokenizer,
     ByteLevel,
     CharDelimiterSplit,
     Digits,
     FixedLength,
+    Indentation,
     Metaspace,
+    ModelSplit,
     PreTokenizer,
     Punctuation,
+    RegexSplit,
+    RunLength,
+    ScriptSplit,
     Sequence,
     Split,
     UnicodeScripts,
+    UnicodeWords,
     Whitespace,
     WhitespaceSplit,
 )
@@ -65,6 +73,35 @@ class TestSplit:
         assert isinstance(pickle.loads(pickle.dumps(Split(" ", "removed", True))), Split)
 
 
+class TestRegexSplit:
+    def test_instantiate(self):
+        pre_tokenizer = RegexSplit(r"\p{L}+")
+        assert pre_tokenizer is not None
+        assert isinstance(pre_tokenizer, PreTokenizer)
+        assert isinstance(pre_tokenizer, RegexSplit)
+        assert pre_tokenizer.pattern == r"\p{L}+"
+
+        pickled = pickle.loads(pickle.dumps(pre_tokenizer))
+        assert isinstance(pickled, RegexSplit)
+        assert pickled.pattern == r"\p{L}+"
+
+        with pytest.raises(Exception):
+            RegexSplit("(?<!")
+
+    def test_pre_tokenize(self):
+        pre_tokenizer = RegexSplit(r"'s| ?\p{L}+|\s+(?!\S)|\s+")
+        assert pre_tokenizer.pre_tokenize_str("it's  ok") == [
+            ("it", (0, 2)),
+            ("'s", (2, 4)),
+            (" ", (4, 5)),
+            (" ok", (5, 8)),
+        ]
+
+        # Only the capture groups are kept from the matches
+        pre_tokenizer = RegexSplit(r"(\w+)=")
+        assert pre_tokenizer.pre_tokenize_str("a=1") == [("a", (0, 1)), ("1", (2, 3))]
+
+
 class TestWhitespace:
     def test_instantiate(self):
         assert Whitespace() is not None
@@ -72,6 +109,22 @@ class TestWhitespace:
         assert isinstance(Whitespace(), Whitespace)
         assert isinstance(pickle.loads(pickle.dumps(Whitespace())), Whitespace)
 
+    def test_pre_tokenize_str_streaming(self):
+        text = "Le café  est servi, à   l'heure où  les élèves arrivent. " * 20
+        for pretok in [Whitespace(), ByteLevel()]:
+            stream = pretok.pre_tokenize_str_streaming(text, chunk_size=32)
+            assert list(stream) == pretok.pre_tokenize_str(text)
+
+        stream = Whitespace().pre_tokenize_str_streaming("abcdé fg", chunk_size=3)
+        assert next(stream) == ("abc", (0, 3))
+        assert list(stream) == [("dé", (3, 5)), ("fg", (6, 8))]
+
+        # The chunk cut before the newline gets its own prefix space
+        stream = ByteLevel().pre_tokenize_str_streaming("aaaa\nbbbb", chunk_size=6)
+        assert [pre_token for pre_token, _ in stream] == ["Ġaaaa", "Ġ", "Ċ", "bbbb"]
+        stream = ByteLevel(add_prefix_space=False).pre_tokenize_str_streaming("aaaa\nbbbb", chunk_size=6)
+        assert [pre_token for pre_token, _ in stream] == ["aaaa", "Ċ", "bbbb"]
+
 
 class TestWhitespaceSplit:
     def test_instantiate(self):
@@ -277,6 +330,105 @@ class TestFixedLength:
         ]
 
 
+class TestIndentation:
+    def test_instantiate(self):
+        assert Indentation() is not None
+        assert isinstance(Indentation(), PreTokenizer)
+        assert isinstance(Indentation(), Indentation)
+        assert isinstance(pickle.loads(pickle.dumps(Indentation("tab"))), Indentation)
+        assert pickle.loads(pickle.dumps(Indentation("tab"))).unit == "tab"
+
+        with pytest.raises(ValueError, match="unknown variant"):
+            Indentation("three_spaces")
+
+    def test_pre_tokenize_str(self):
+        pretok = Indentation()
+        assert pretok.unit == "four_spaces"
+        assert pretok.pre_tokenize_str("if x:\n      y") == [
+            ("if x:\n", (0, 6)),
+            ("    ", (6, 10)),
+            ("  y", (10, 13)),
+        ]
+
+        pretok.unit = "two_spaces"
+        assert pretok.unit == "two_spaces"
+        assert pretok.pre_tokenize_str("if x:\n      y") == [
+            ("if x:\n", (0, 6)),
+            ("  ", (6, 8)),
+            ("  ", (8, 10)),
+            ("  ", (10, 12)),
+            ("y", (12, 13)),
+        ]
+
+        pretok = Sequence([Indentation("tab"), ByteLevel(add_prefix_space=False)])
+        assert pretok.pre_tokenize_str("\t\tif x") == [("ĉ", (0, 1)), ("ĉ", (1, 2)), ("if", (2, 4)), ("Ġx", (4, 6))]
+
+
+class TestScriptSplit:
+    def test_instantiate(self):
+        assert ScriptSplit() is not None
+        assert isinstance(ScriptSplit(), PreTokenizer)
+        assert isinstance(ScriptSplit(), ScriptSplit)
+        pretok = pickle.loads(pickle.dumps(ScriptSplit("script", "isolated")))
+        assert isinstance(pretok, ScriptSplit)
+        assert pretok.granularity == "script"
+        assert pretok.common == "isolated"
+
+        with pytest.raises(ValueError, match="unknown variant"):
+            ScriptSplit("language")
+        with pytest.raises(ValueError, match="unknown variant"):
+            ScriptSplit(common="removed")
+
+    def test_pre_tokenize_str(self):
+        pretok = ScriptSplit()
+        assert pretok.granularity == "writing_system"
+        assert pretok.common == "merged"
+        assert pretok.pre_tokenize_str("p\u0430ypal, мир") == [
+            ("p", (0, 1)),
+            ("\u0430", (1, 2)),
+            ("ypal, ", (2, 8)),
+            ("мир", (8, 11)),
+        ]
+        assert pretok.pre_tokenize_str("東京タワー") == [("東京タワー", (0, 5))]
+
+        pretok.granularity = "script"
+        pretok.common = "isolated"
+        assert pretok.pre_tokenize_str("東京タワ!") == [("東京", (0, 2)), ("タワ", (2, 4)), ("!", (4, 5))]
+
+
+class TestModelSplit:
+    def test_instantiate(self):
+        model = WordPiece({"[UNK]": 0, "un": 1, "##do": 2})
+        assert ModelSplit(model) is not None
+        assert isinstance(ModelSplit(model), PreTokenizer)
+        assert isinstance(ModelSplit(model), ModelSplit)
+        assert isinstance(pickle.loads(pickle.dumps(ModelSplit(model, Lowercase()))), ModelSplit)
+
+    def test_pre_tokenize_str(self):
+        model = WordPiece({"[UNK]": 0, "un": 1, "##do": 2})
+        pretok = ModelSplit(model, Lowercase())
+        assert pretok.pre_tokenize_str("undo") == [("un", (0, 2)), ("do", (2, 4))]
+        assert pretok.pre_tokenize_str("Undo") == [("undo", (0, 4))]
+
+
+class TestRunLength:
+    def test_instantiate(self):
+        assert RunLength() is not None
+        assert isinstance(RunLength(), PreTokenizer)
+        assert isinstance(RunLength(), RunLength)
+        pickled = pickle.loads(pickle.dumps(RunLength(max_run=3, marker="#")))
+        assert isinstance(pickled, RunLength)
+        assert (pickled.max_run, pickled.marker) == (3, "#")
+
+    def test_pre_tokenize_str(self):
+        pretok = RunLength()
+        assert pretok.pre_tokenize_str("wait" + "!" * 12) == [("wait", (0, 4)), ("!", (4, 5)), ("⟳12⟳", (5, 16))]
+
+        pretok.max_run = 12
+        assert pretok.max_run == 12
+        assert pretok.pre_tokenize_str("wait" + "!" * 12) == [("wait" + "!" * 12, (0, 16))]
+
+
 class TestUnicodeScripts:
     def test_instantiate(self):
         assert UnicodeScripts() is not None
@@ -285,6 +437,38 @@ class TestUnicodeScripts:
         assert isinstance(pickle.loads(pickle.dumps(UnicodeScripts())), UnicodeScripts)
 
 
+class TestUnicodeWords:
+    def test_instantiate(self):
+        assert UnicodeWords() is not None
+        assert isinstance(UnicodeWords(), PreTokenizer)
+        assert isinstance(UnicodeWords(), UnicodeWords)
+        pickled = pickle.loads(pickle.dumps(UnicodeWords(cjk=True)))
+        assert isinstance(pickled, UnicodeWords)
+        assert pickled.cjk
+
+    def test_pre_tokenize_str(self):
+        pretok = UnicodeWords()
+        assert pretok.pre_tokenize_str("Hey, it's 東京") == [
+            ("Hey", (0, 3)),
+            (",", (3, 4)),
+            ("it's", (5, 9)),
+            ("東", (10, 11)),
+            ("京", (11, 12)),
+        ]
+
+        pretok.cjk = True
+        assert pretok.cjk
+        assert pretok.pre_tokenize_str("東京都へ") == [("東京都へ", (0, 4))]
+
+    def test_segmenter(self):
+        pretok = UnicodeWords(cjk=True, segmenter=lambda run: [2] if run.startswith("東京") else [])
+        assert pretok.pre_tokenize_str("東京都 ภาษา") == [("東京", (0, 2)), ("都", (2, 3)), ("ภาษา", (4, 8))]
+
+        pretok.segmenter = lambda run: [len(run) + 1]
+        with pytest.raises(Exception, match="out of"):
+            pretok.pre_tokenize_str("東京")
+
+
 class TestCustomPreTokenizer:
     class BadCustomPretok:
         def pre_tokenize(self, pretok, wrong):
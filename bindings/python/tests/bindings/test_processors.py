@@ -9,6 +9,7 @@ from tokenizers.pre_tokenizers import ByteLevel as ByteLevelPreTokenizer
 from tokenizers.processors import (
     BertProcessing,
     ByteLevel,
+    ChatTemplate,
     PostProcessor,
     RobertaProcessing,
     Sequence,
@@ -183,6 +184,47 @@ class TestTemplateProcessing:
         assert original.ids == template.ids
 
 
+class TestChatTemplate:
+    def get_template(self):
+        return ChatTemplate(
+            roles=[
+                {"name": "user", "prefix": ["<|user|>"], "suffix": ["</s>"]},
+                {"name": "assistant", "prefix": ["<|assistant|>"], "suffix": ["</s>"], "loss": True},
+            ],
+            special_tokens=[("<|user|>", 0), ("<|assistant|>", 1), ("</s>", 2)],
+        )
+
+    def test_instantiate(self):
+        template = self.get_template()
+        assert isinstance(template, PostProcessor)
+        assert isinstance(template, ChatTemplate)
+        assert isinstance(pickle.loads(pickle.dumps(template)), ChatTemplate)
+        assert template.roles == ["user", "assistant"]
+        assert template.prefix == []
+
+        with pytest.raises(ValueError, match="at least one role"):
+            ChatTemplate(roles=[], special_tokens=[])
+        with pytest.raises(ValueError, match="missing SpecialToken"):
+            ChatTemplate(roles=[{"name": "user", "prefix": ["<|user|>"]}], special_tokens=[])
+
+    def test_encode_chat(self):
+        tokenizer = Tokenizer(BPE())
+        tokenizer.add_special_tokens(["<|user|>", "<|assistant|>", "</s>"])
+        tokenizer.add_tokens(["hi", "there"])
+
+        with pytest.raises(ValueError, match="requires a `ChatTemplate`"):
+            tokenizer.encode_chat([("user", "hi")])
+
+        tokenizer.post_processor = self.get_template()
+        encoding, loss_mask, role_ids = tokenizer.encode_chat([("user", "hi"), ("assistant", "hi there")])
+        assert encoding.tokens == ["<|user|>", "hi", "</s>", "<|assistant|>", "hi", "there", "</s>"]
+        assert loss_mask == [0, 0, 0, 0, 1, 1, 1]
+        assert role_ids == [0, 0, 0, 1, 1, 1, 1]
+
+        with pytest.raises(Exception, match="unknown role"):
+            tokenizer.encode_chat([("system", "hi")])
+
+
 class TestSequenceProcessing:
     def test_sequence_processing(self):
         assert Sequence([]) is not None
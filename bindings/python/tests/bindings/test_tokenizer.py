@@ -1,13 +1,17 @@
+import json
 import pickle
+import threading
 
 import numpy as np
 import pytest
 
-from tokenizers import AddedToken, Encoding, Tokenizer
+from tokenizers import AddedToken, Encoding, IncompatibleDecoderError, LimitExceededError, MissingUnkTokenError, PipelineError, Tokenizer
+from tokenizers import PaddingParams, ShadowTokenizer, TruncationParams, decoders
 from tokenizers.implementations import BertWordPieceTokenizer
-from tokenizers.models import BPE, Model, Unigram
-from tokenizers.pre_tokenizers import ByteLevel, Metaspace
-from tokenizers.processors import RobertaProcessing, TemplateProcessing
+from tokenizers.models import BPE, Model, Unigram, WordLevel, WordPiece
+from tokenizers.pre_tokenizers import ByteLevel, Metaspace, ModelSplit, WhitespaceSplit
+from tokenizers.processors import ByteLevel as ByteLevelProcessing, RobertaProcessing, TemplateProcessing
+from tokenizers.processors import Sequence as ProcessorSequence
 from tokenizers.normalizers import Strip, Lowercase, Sequence
 from tokenizers.decoders import ByteFallback, DecodeStream, Metaspace as DecoderMetaspace
 
@@ -66,6 +70,17 @@ class TestAddedToken:
         assert added_token.single_word == False
         assert added_token.normalized == False
 
+    def test_can_set_pattern(self):
+        added_token = AddedToken("<extra_id_[0-9]+>", pattern=True, unicode_boundaries=True)
+        assert added_token.pattern == True
+        assert added_token.unicode_boundaries == True
+        assert added_token.single_word == False
+        assert repr(added_token) == (
+            'AddedToken("<extra_id_[0-9]+>", rstrip=False, lstrip=False, single_word=False, '
+            "normalized=True, special=False, pattern=True, unicode_boundaries=True)"
+        )
+        assert pickle.loads(pickle.dumps(added_token)).pattern == True
+
 
 class TestTokenizer:
     def test_has_expected_type_and_methods(self):
@@ -95,6 +110,58 @@ class TestTokenizer:
         assert tokenizer.decoder is None
         assert isinstance(pickle.loads(pickle.dumps(Tokenizer(BPE()))), Tokenizer)
 
+    def test_from_components(self):
+        tokenizer = Tokenizer.from_components(
+            model=BPE({"a": 0, "b": 1, "ab": 2}, [("a", "b")]),
+            normalizer=Lowercase(),
+            pre_tokenizer=ByteLevel(),
+            post_processor=RobertaProcessing(("</s>", 1), ("<s>", 0)),
+            decoder=decoders.ByteLevel(),
+        )
+        assert isinstance(tokenizer.normalizer, Lowercase)
+        assert isinstance(tokenizer.pre_tokenizer, ByteLevel)
+        assert isinstance(tokenizer.post_processor, RobertaProcessing)
+        assert isinstance(tokenizer.decoder, decoders.ByteLevel)
+        assert tokenizer.encode("AB", add_special_tokens=False).tokens == ["ab"]
+
+        # The decoder can be anywhere in a Sequence
+        Tokenizer.from_components(
+            Unigram([("<unk>", 0.0), ("<0x41>", -1.0)], 0, byte_fallback=True),
+            pre_tokenizer=Metaspace(),
+            decoder=decoders.Sequence([decoders.ByteFallback(), decoders.Metaspace()]),
+        )
+
+    def test_from_components_errors(self):
+        with pytest.raises(MissingUnkTokenError, match="`\\[UNK\\]` is not part of the vocabulary"):
+            Tokenizer.from_components(WordPiece({"a": 0}))
+        with pytest.raises(MissingUnkTokenError):
+            Tokenizer.from_components(BPE({"a": 0}, [], unk_token="<unk>"))
+        with pytest.raises(MissingUnkTokenError):
+            Tokenizer.from_components(Unigram([("a", 0.0)], None, byte_fallback=False))
+
+        with pytest.raises(IncompatibleDecoderError, match="needs a ByteLevel decoder"):
+            Tokenizer.from_components(BPE(), pre_tokenizer=ByteLevel())
+        with pytest.raises(IncompatibleDecoderError, match="needs a Metaspace decoder"):
+            Tokenizer.from_components(BPE(), pre_tokenizer=Metaspace(), decoder=decoders.Metaspace(replacement="_"))
+        with pytest.raises(IncompatibleDecoderError, match="prefix `##`"):
+            Tokenizer.from_components(
+                WordPiece({"[UNK]": 0}, continuing_subword_prefix="@@"), decoder=decoders.WordPiece()
+            )
+        with pytest.raises(IncompatibleDecoderError, match="suffix `_`, but the model uses `</w>`"):
+            Tokenizer.from_components(
+                WordPiece({"[UNK]": 0}, end_of_word_suffix="</w>"), decoder=decoders.BPEDecoder(suffix="_")
+            )
+        with pytest.raises(IncompatibleDecoderError, match="prefix `@@`, but the model uses `##`"):
+            Tokenizer.from_components(WordPiece({"[UNK]": 0}), decoder=decoders.BPEDecoder(prefix="@@"))
+        Tokenizer.from_components(
+            WordPiece({"[UNK]": 0}, end_of_word_suffix="</w>"), decoder=decoders.BPEDecoder(prefix="##")
+        )
+        with pytest.raises(IncompatibleDecoderError, match="byte fallback"):
+            Tokenizer.from_components(BPE(byte_fallback=True), decoder=decoders.Fuse())
+        with pytest.raises(PipelineError):
+            Tokenizer.from_components(BPE(), pre_tokenizer=ByteLevel(), decoder=decoders.WordPiece())
+        assert issubclass(PipelineError, ValueError)
+
     def test_add_tokens(self):
         tokenizer = Tokenizer(BPE())
         added = tokenizer.add_tokens(["my", "name", "is", "john"])
@@ -122,6 +189,44 @@ class TestTokenizer:
         assert tokens[0].normalized == False
         assert tokens[1].normalized == True
 
+    def test_added_tokens_id_assignment(self):
+        tokenizer = Tokenizer(BPE())
+        tokenizer.add_tokens(["my"])
+
+        tokens = ["name", "john", AddedToken(), "my"]
+        assert tokenizer.ids_to_add(tokens) == [1, 2, None, 0]
+        assert tokenizer.ids_to_add(tokens, id_assignment="alphabetical") == [2, 1, None, 0]
+        assert tokenizer.get_vocab_size() == 1
+        assert tokenizer.add_special_tokens(tokens, id_assignment="alphabetical") == 3
+        assert tokenizer.token_to_id("john") == 1
+
+        # The hashed ids do not depend on the batches
+        one, two = Tokenizer(BPE()), Tokenizer(BPE())
+        one.add_tokens(["a", "b"], id_assignment="hash_stable", hash_range=1000)
+        two.add_tokens(["b"], id_assignment="hash_stable", hash_range=1000)
+        two.add_tokens(["a"], id_assignment="hash_stable", hash_range=1000)
+        assert one.get_vocab() == two.get_vocab()
+
+        with pytest.raises(ValueError, match="requires a strictly positive `hash_range`"):
+            tokenizer.add_tokens(["a"], id_assignment="hash_stable")
+        with pytest.raises(ValueError, match="can only be used with the `hash_stable`"):
+            tokenizer.ids_to_add(["a"], hash_range=10)
+        with pytest.raises(ValueError, match="Unknown `id_assignment`"):
+            tokenizer.add_tokens(["a"], id_assignment="random")
+
+    def test_add_pattern_tokens(self):
+        tokenizer = Tokenizer(WordLevel({"<extra_id_0>": 0, "hello": 1, "[UNK]": 2}, unk_token="[UNK]"))
+        tokenizer.pre_tokenizer = WhitespaceSplit()
+        added = tokenizer.add_special_tokens([AddedToken("<extra_id_[0-9]+>", special=True, pattern=True)])
+        assert added == 1
+        pattern_id = tokenizer.token_to_id("<extra_id_[0-9]+>")
+        assert pattern_id == 3
+
+        output = tokenizer.encode("hello<extra_id_0> <extra_id_12>")
+        assert output.tokens == ["hello", "<extra_id_0>", "<extra_id_12>"]
+        assert output.ids == [1, 0, pattern_id]
+        assert tokenizer.decode(output.ids) == "hello"
+
     def test_encode(self):
         tokenizer = Tokenizer(BPE())
         tokenizer.add_tokens(["my", "name", "is", "john", "pair"])
@@ -328,6 +433,225 @@ class TestTokenizer:
         output = tokenizer.encode("my name is john", "pair")
         assert output.tokens == ["john", "pair"]
 
+    def test_encode_batch_max_lengths(self):
+        tokenizer = Tokenizer(BPE())
+        tokenizer.add_tokens(["my", "name", "is", "john"])
+        tokenizer.add_special_tokens(["[CLS]", "[SEP]"])
+        tokenizer.post_processor = TemplateProcessing(
+            single="[CLS] $0 [SEP]", pair="[CLS] $A [SEP] $B:1 [SEP]:1", special_tokens=[("[CLS]", 4), ("[SEP]", 5)]
+        )
+
+        # The lengths include the special tokens, and apply even without truncation
+        inputs = ["my name is john", "my name is john", ("my name", "is john")]
+        output = tokenizer.encode_batch(inputs, max_lengths=[3, None, 5])
+        assert [o.tokens for o in output] == [
+            ["[CLS]", "my", "[SEP]"],
+            ["[CLS]", "my", "name", "is", "john", "[SEP]"],
+            ["[CLS]", "my", "[SEP]", "is", "[SEP]"],
+        ]
+        assert output[0].overflowing[0].tokens == ["[CLS]", "name", "[SEP]"]
+
+        # The other truncation parameters are kept, `None` uses the tokenizer's max length
+        tokenizer.enable_truncation(4, direction="left")
+        output = tokenizer.encode_batch(inputs[:2], max_lengths=[3, None])
+        assert [o.tokens for o in output] == [["[CLS]", "john", "[SEP]"], ["[CLS]", "is", "john", "[SEP]"]]
+
+        with pytest.raises(Exception, match="Got 1 max lengths for 2 inputs"):
+            tokenizer.encode_batch(inputs[:2], max_lengths=[3])
+        tokenizer.enable_truncation(4, stride=1)
+        with pytest.raises(Exception, match="stride"):
+            tokenizer.encode_batch(inputs[:2], max_lengths=[3, 2])
+
+    def test_encode_batch_windows(self):
+        tokenizer = Tokenizer(BPE())
+        tokenizer.add_tokens(["my", "name", "is", "john", "pair"])
+        tokenizer.enable_truncation(3, stride=1)
+
+        documents = ["my name is john", "pair"]
+        windows, mapping = tokenizer.encode_batch_windows(documents)
+        assert [w.tokens for w in windows] == [["my", "name", "is"], ["is", "john"], ["pair"]]
+        assert mapping == [0, 0, 1]
+        assert all(len(w.overflowing) == 0 for w in windows)
+        # Offsets refer to the original documents
+        assert [documents[0][s:e] for (s, e) in windows[1].offsets] == ["is", "john"]
+
+    def test_encode_batch_with_prefix(self):
+        vocab = {"a": 0, "b": 1, "c": 2, "ab": 3, "abc": 4, "é": 5, "[CLS]": 6}
+        tokenizer = Tokenizer(BPE(vocab, [("a", "b"), ("ab", "c")]))
+        tokenizer.pre_tokenizer = WhitespaceSplit()
+        tokenizer.post_processor = TemplateProcessing(single="[CLS] $0", special_tokens=[("[CLS]", 6)])
+
+        prefix = "é ab ab a"
+        suffixes = ["bc", " c", "", "é"]
+        output = tokenizer.encode_batch_with_prefix(prefix, suffixes)
+        assert [o.tokens for o in output] == [
+            ["[CLS]", "é", "ab", "ab", "abc"],
+            ["[CLS]", "é", "ab", "ab", "a", "c"],
+            ["[CLS]", "é", "ab", "ab", "a"],
+            ["[CLS]", "é", "ab", "ab", "a", "é"],
+        ]
+        expected = tokenizer.encode_batch([prefix + s for s in suffixes])
+        assert [o.offsets for o in output] == [e.offsets for e in expected]
+        assert [o.word_ids for o in output] == [e.word_ids for e in expected]
+        assert output[0].offsets[-1] == (8, 11)
+
+    def test_encode_edit(self):
+        vocab = {"a": 0, "b": 1, "c": 2, "ab": 3, "abc": 4, "é": 5}
+        tokenizer = Tokenizer(BPE(vocab, [("a", "b"), ("ab", "c")]))
+        tokenizer.pre_tokenizer = WhitespaceSplit()
+
+        text = "é ab ab a é"
+        encoding = tokenizer.encode(text, add_special_tokens=False)
+        patch = tokenizer.encode_edit(text, encoding, (5, 7), "abc")
+        expected = tokenizer.encode("é ab abc a é", add_special_tokens=False)
+        assert patch["encoding"].tokens == ["é", "ab", "abc", "a", "é"]
+        assert patch["encoding"].offsets == expected.offsets
+        assert patch["encoding"].word_ids == expected.word_ids
+        assert patch["replaced"] == (2, 3)
+        assert patch["inserted"] == (2, 3)
+
+        with pytest.raises(Exception, match="Invalid edit range 5..20"):
+            tokenizer.encode_edit(text, encoding, (5, 20), "")
+
+    def test_encode_with_prefix_constraint(self):
+        vocab = {"a": 0, "b": 1, "c": 2, "ab": 3, "abc": 4, "ca": 5, "é": 6}
+        merges = [("a", "b"), ("ab", "c"), ("c", "a")]
+        tokenizer = Tokenizer(BPE(vocab, merges))
+
+        encoding, token, allowed = tokenizer.encode_with_prefix_constraint("éab")
+        assert encoding.tokens == ["é"]
+        assert (token.id, token.value, token.offsets) == (3, "ab", (1, 3))
+        assert allowed == [3, 4]
+
+        assert tokenizer.token_ids_with_prefix("c") == [2, 5]
+        assert tokenizer.token_ids_with_prefix(b"\xc3") == [6]
+        assert tokenizer.token_ids_with_prefix("") == list(range(7))
+
+    def test_encode_nbest_and_sample(self):
+        tokenizer = Tokenizer(Unigram([("<unk>", 0.0), ("a", -2.0), ("b", -2.0), ("ab", -1.0)], 0))
+        tokenizer.pre_tokenizer = WhitespaceSplit()
+
+        nbest = tokenizer.encode_nbest("ab", 3)
+        assert [(encoding.tokens, score) for encoding, score in nbest] == [(["ab"], -1.0), (["a", "b"], -4.0)]
+
+        nbest = tokenizer.encode_nbest("ab ab", 2)
+        assert [encoding.tokens for encoding, _ in nbest] == [["ab", "ab"], ["ab", "a", "b"]]
+        assert [score for _, score in nbest] == [-2.0, -5.0]
+
+        encoding = tokenizer.encode_sample("ab ab", 0.5)
+        assert "".join(encoding.tokens) == "abab"
+        assert encoding.offsets[0][0] == 0 and encoding.offsets[-1][1] == 5
+
+        with pytest.raises(Exception, match="n-best"):
+            Tokenizer(BPE()).encode_nbest("ab", 2)
+
+    def test_encode_with_word_metadata(self):
+        tokenizer = Tokenizer(WordLevel({"[UNK]": 0, "hello": 1, "there": 2, "[PAD]": 3}, unk_token="[UNK]"))
+        tokenizer.enable_truncation(1)
+        tokenizer.enable_padding(length=2, pad_id=3)
+
+        encoding = tokenizer.encode_with_word_metadata(["hello", "there"], [[0.0, 0.4], [0.5, 1.2]])
+        assert encoding.tokens == ["hello", "[PAD]"]
+        assert encoding.word_metadata == [[0.0, 0.4], None]
+        assert encoding.overflowing[0].word_metadata == [[0.5, 1.2], None]
+
+        tokenizer.no_truncation()
+        encoding = tokenizer.encode_with_word_metadata(["hello"], [[0.0]], pair=["there"], pair_word_metadata=[[1.0]])
+        assert encoding.word_metadata == [[0.0], [1.0]]
+        assert tokenizer.encode("hello").word_metadata == [None, None]
+
+        with pytest.raises(Exception, match="metadata of 1 words for the 2 words"):
+            tokenizer.encode_with_word_metadata(["hello", "there"], [[0.0]])
+        with pytest.raises(ValueError, match="pair_word_metadata"):
+            tokenizer.encode_with_word_metadata(["hello"], [[0.0]], pair=["there"])
+
+    def test_configure_parallelism(self):
+        tokenizer = Tokenizer(BPE())
+        tokenizer.add_tokens(["my", "name", "is", "john"])
+
+        tokenizer.configure_parallelism(num_threads=2)
+        assert tokenizer.num_threads == 2
+        output = tokenizer.encode_batch(["my name", "is john"])
+        assert [o.tokens for o in output] == [["my", "name"], ["is", "john"]]
+        assert tokenizer.decode_batch([o.ids for o in output]) == ["my name", "is john"]
+
+        tokenizer.configure_parallelism(enabled=False)
+        assert tokenizer.num_threads == 1
+        assert tokenizer.ids_to_tokens([0, 3, 42]) == ["my", "john", None]
+
+    def test_normalize_and_pre_tokenize_batch(self):
+        tokenizer = Tokenizer(BPE())
+        tokenizer.normalizer = Lowercase()
+        tokenizer.pre_tokenizer = WhitespaceSplit()
+        tokenizer.add_special_tokens(["[SEP]"])
+
+        assert tokenizer.normalize_batch(["Hello THERE", ""]) == ["hello there", ""]
+        assert tokenizer.pre_tokenize_batch(["Hé THERE[SEP]", "a  b"]) == [
+            [("hé", (0, 2)), ("there", (3, 8)), ("[SEP]", (8, 13))],
+            [("a", (0, 1)), ("b", (3, 4))],
+        ]
+
+    def test_warn_on_artifacts(self):
+        tokenizer = Tokenizer(WordLevel({"[UNK]": 0}, unk_token="[UNK]"))
+        assert tokenizer.warn_on_artifacts == False
+        tokenizer.encode("▁Hello▁world")
+
+        tokenizer.warn_on_artifacts = True
+        assert tokenizer.warn_on_artifacts == True
+        with pytest.warns(UserWarning, match="2 Metaspace artifact"):
+            tokenizer.encode("▁Hello▁world")
+        with pytest.warns(UserWarning, match="ByteLevel artifact"):
+            tokenizer.encode_batch(["Hello world", ("Hello", "HelloĠworld")])
+        with pytest.warns(UserWarning, match="ByteFallback artifact"):
+            tokenizer.encode_batch_fast(["a <0x0A>"])
+
+    def test_configure_limits(self):
+        tokenizer = Tokenizer(BPE({"a": 0, "b": 1, "c": 2}, []))
+        tokenizer.pre_tokenizer = WhitespaceSplit()
+        assert tokenizer.limits == {"max_pre_tokens": None, "max_splits_per_pre_token": None, "max_overflowing": None}
+
+        tokenizer.configure_limits(max_pre_tokens=3, max_splits_per_pre_token=2)
+        assert tokenizer.limits["max_pre_tokens"] == 3
+        assert len(tokenizer.encode("ab ba c")) == 5
+        with pytest.raises(LimitExceededError, match="4 pre-tokens, the limit is 3"):
+            tokenizer.encode("a b c a")
+        with pytest.raises(LimitExceededError, match="split into 3 tokens"):
+            tokenizer.encode_batch(["a", "abc"])
+        # The parts of a pre-tokenized sequence count together
+        with pytest.raises(LimitExceededError, match="4 pre-tokens"):
+            tokenizer.encode(["a b", "c a"], is_pretokenized=True)
+        assert issubclass(LimitExceededError, ValueError)
+
+        tokenizer.configure_limits(max_overflowing=2)
+        assert tokenizer.limits["max_pre_tokens"] == None
+        tokenizer.enable_truncation(2)
+        assert len(tokenizer.encode("a b c a b c").overflowing) == 2
+        with pytest.raises(LimitExceededError, match="3 overflowing parts"):
+            tokenizer.encode("a " * 1000)
+
+    def test_mutations_during_encode(self):
+        tokenizer = Tokenizer(BPE())
+        tokenizer.add_tokens(["my", "name", "is"])
+        batch = ["my name is john", "john is"] * 2000
+        lengths = []
+
+        def encode():
+            for _ in range(20):
+                lengths.append({len(encoding) for encoding in tokenizer.encode_batch(batch)})
+
+        thread = threading.Thread(target=encode)
+        thread.start()
+        for i in range(200):
+            tokenizer.enable_padding()
+            if i == 100:
+                tokenizer.add_tokens(["john"])
+            tokenizer.no_padding()
+        thread.join()
+
+        # Each batch sees the padding and the added tokens of a single state of the tokenizer
+        assert len(lengths) == 20
+        assert all(length in [{3, 1}, {3}, {4, 2}, {4}] for length in lengths)
+
     def test_padding(self):
         tokenizer = Tokenizer(BPE())
         tokenizer.add_tokens(["my", "name", "is", "john", "pair"])
@@ -352,6 +676,217 @@ class TestTokenizer:
         padding = tokenizer.padding
         tokenizer.enable_padding(**padding)
 
+    def test_truncation_params(self):
+        tokenizer = Tokenizer(BPE())
+        tokenizer.add_tokens(["my", "name", "is", "john"])
+        assert tokenizer.truncation is None
+
+        params = TruncationParams(2, strategy="only_first")
+        assert params.max_length == 2
+        assert params.stride == 0
+        assert params.direction == "right"
+        assert params == TruncationParams(2, stride=0, strategy="only_first", direction="right")
+        assert params != TruncationParams(3, strategy="only_first")
+        assert repr(params) == "TruncationParams(max_length=2, stride=0, strategy='only_first', direction='right')"
+        with pytest.raises(ValueError, match="Unknown `strategy`"):
+            TruncationParams(2, strategy="shortest_first")
+
+        # Round trip through the tokenizer
+        tokenizer.truncation = params
+        assert tokenizer.truncation == params
+        assert tokenizer.encode("my name is john").tokens == ["my", "name"]
+
+        # The getter returns a copy, that must be assigned back
+        params = tokenizer.truncation
+        params.direction = "left"
+        assert tokenizer.truncation.direction == "right"
+        tokenizer.truncation = params
+        assert tokenizer.encode("my name is john").tokens == ["is", "john"]
+
+        # Still usable like a dict
+        assert params == {"max_length": 2, "stride": 0, "strategy": "only_first", "direction": "left"}
+        assert params["stride"] == 0
+        assert params.get("unknown", 42) == 42
+        with pytest.raises(KeyError):
+            params["unknown"]
+        tokenizer.enable_truncation(**params)
+        assert tokenizer.truncation == params
+
+        with pytest.raises(ValueError, match="stride"):
+            tokenizer.truncation = TruncationParams(2, stride=3)
+
+        tokenizer.truncation = None
+        assert tokenizer.truncation is None
+        assert len(tokenizer.encode("my name is john")) == 4
+
+    def test_truncation_budget(self):
+        tokenizer = Tokenizer(BPE())
+        tokenizer.add_tokens(["my", "name", "is", "john", "pair"])
+
+        tokenizer.enable_truncation(5, strategy="budget", first_budget=1)
+        assert tokenizer.truncation.first_budget == 1
+        assert tokenizer.truncation == {
+            "max_length": 5,
+            "stride": 0,
+            "strategy": "budget",
+            "direction": "right",
+            "first_budget": 1,
+        }
+        output = tokenizer.encode("my name is", "john pair pair pair pair pair")
+        assert output.tokens == ["my", "john", "pair", "pair", "pair"]
+
+        # As a ratio of the max length
+        tokenizer.enable_truncation(5, strategy="budget", first_budget=0.4)
+        output = tokenizer.encode("my name is", "john pair pair pair pair pair")
+        assert output.tokens == ["my", "name", "john", "pair", "pair"]
+
+        # The room left by a short second sequence goes to the first one
+        tokenizer.enable_truncation(5, strategy="budget", first_budget=1)
+        output = tokenizer.encode("my name is john", "pair")
+        assert output.tokens == ["my", "name", "is", "john", "pair"]
+
+        params = TruncationParams(5, strategy="budget", first_budget=3)
+        assert repr(params) == (
+            "TruncationParams(max_length=5, stride=0, strategy='budget', direction='right', first_budget=3)"
+        )
+        params.first_budget = None
+        assert params.strategy == "longest_first"
+        params.first_budget = 0.5
+        assert params.strategy == "budget"
+
+        with pytest.raises(ValueError, match="requires a `first_budget`"):
+            tokenizer.enable_truncation(5, strategy="budget")
+        with pytest.raises(ValueError, match="only be used with the `budget` strategy"):
+            TruncationParams(5, first_budget=2)
+        with pytest.raises(ValueError, match="between 0 and 1"):
+            TruncationParams(5, strategy="budget", first_budget=1.5)
+        with pytest.raises(ValueError, match="requires a `first_budget`"):
+            TruncationParams(5).strategy = "budget"
+
+    def test_truncation_max_overflowing(self):
+        tokenizer = Tokenizer(BPE())
+        tokenizer.add_tokens(["my", "name", "is", "john"])
+
+        tokenizer.enable_truncation(1, max_overflowing=2)
+        assert tokenizer.truncation.max_overflowing == 2
+        assert tokenizer.truncation["max_overflowing"] == 2
+        output = tokenizer.encode("my name is john")
+        assert output.tokens == ["my"]
+        assert [o.tokens for o in output.overflowing] == [["name"], ["is"]]
+
+        params = TruncationParams(1, stride=0)
+        assert params.max_overflowing is None
+        assert "max_overflowing" not in params.keys()
+        tokenizer.truncation = params
+        assert len(tokenizer.encode("my name is john").overflowing) == 3
+        params.max_overflowing = 0
+        tokenizer.truncation = params
+        assert tokenizer.encode("my name is john").overflowing == []
+
+    def test_num_special_tokens_to_add(self):
+        tokenizer = Tokenizer(BPE())
+        assert tokenizer.num_special_tokens_to_add(False) == 0
+        assert tokenizer.num_special_tokens_to_add(True) == 0
+
+        template = TemplateProcessing(
+            single="[CLS] $0 [SEP]", pair="[CLS] $A [SEP] $B:1 [SEP]:1", special_tokens=[("[CLS]", 0), ("[SEP]", 1)]
+        )
+        tokenizer.post_processor = template
+        assert tokenizer.num_special_tokens_to_add(False) == 2
+        assert tokenizer.num_special_tokens_to_add(True) == 3
+
+        roberta = RobertaProcessing(("</s>", 2), ("<s>", 3))
+        tokenizer.post_processor = ProcessorSequence([ByteLevelProcessing(), template, roberta])
+        assert tokenizer.num_special_tokens_to_add(False) == 4
+        assert tokenizer.num_special_tokens_to_add(True) == 7
+
+    def test_truncation_special_tokens(self):
+        tokenizer = Tokenizer(BPE())
+        tokenizer.add_tokens(["my", "name", "is", "john"])
+        tokenizer.add_special_tokens(["[CLS]", "[SEP]"])
+        tokenizer.post_processor = TemplateProcessing(
+            single="[CLS] $0 [SEP]", pair="[CLS] $A [SEP] $B:1 [SEP]:1", special_tokens=[("[CLS]", 4), ("[SEP]", 5)]
+        )
+
+        # The special tokens fit in the max length, overflowing windows included
+        tokenizer.enable_truncation(4, stride=1)
+        assert tokenizer.truncation.special_tokens == "reserve"
+        output = tokenizer.encode("my name is john")
+        assert output.tokens == ["[CLS]", "my", "name", "[SEP]"]
+        assert [o.tokens for o in output.overflowing] == [
+            ["[CLS]", "name", "is", "[SEP]"],
+            ["[CLS]", "is", "john", "[SEP]"],
+        ]
+        tokenizer.enable_truncation(4)
+        assert len(tokenizer.encode("my name", "is john")) == 4
+
+        # Unless they are exempt
+        tokenizer.enable_truncation(2, special_tokens="exempt")
+        assert tokenizer.truncation == {
+            "max_length": 2,
+            "stride": 0,
+            "strategy": "longest_first",
+            "direction": "right",
+            "special_tokens": "exempt",
+        }
+        output = tokenizer.encode("my name is john")
+        assert output.tokens == ["[CLS]", "my", "name", "[SEP]"]
+        assert tokenizer.encode("my name", "is john").tokens == ["[CLS]", "my", "[SEP]", "is", "[SEP]"]
+
+        # A pair needs a max length with room for its special tokens
+        tokenizer.enable_truncation(2)
+        assert tokenizer.encode("my name is john").tokens == ["[CLS]", "[SEP]"]
+        with pytest.raises(Exception, match="cannot fit the 3 added special tokens"):
+            tokenizer.encode("my name", "is john")
+
+        params = TruncationParams(8, special_tokens="exempt")
+        params.special_tokens = "reserve"
+        assert "special_tokens" not in params.to_dict()
+        with pytest.raises(ValueError, match="Unknown `special_tokens`"):
+            params.special_tokens = "ignore"
+
+    def test_padding_params(self):
+        tokenizer = Tokenizer(BPE())
+        tokenizer.add_tokens(["my", "name", "is", "john"])
+        assert tokenizer.padding is None
+
+        params = PaddingParams(length=4, pad_token="<pad>")
+        assert params.length == 4
+        assert params.pad_id == 0
+        assert params.pad_to_multiple_of is None
+        assert params == PaddingParams(direction="right", pad_token="<pad>", length=4)
+        assert params != PaddingParams(pad_token="<pad>")
+        assert repr(params) == (
+            "PaddingParams(length=4, pad_to_multiple_of=None, pad_id=0, pad_token='<pad>', pad_type_id=0, "
+            "direction='right')"
+        )
+        with pytest.raises(ValueError, match="Unknown `direction`"):
+            PaddingParams(direction="up")
+
+        tokenizer.padding = params
+        assert tokenizer.padding == params
+        assert tokenizer.encode("my name").tokens == ["my", "name", "<pad>", "<pad>"]
+
+        params = tokenizer.padding
+        params.length = None
+        tokenizer.padding = params
+        output = tokenizer.encode_batch(["my", "my name is"])
+        assert [o.tokens for o in output] == [["my", "<pad>", "<pad>"], ["my", "name", "is"]]
+
+        assert params.to_dict() == {
+            "length": None,
+            "pad_to_multiple_of": None,
+            "pad_id": 0,
+            "pad_token": "<pad>",
+            "pad_type_id": 0,
+            "direction": "right",
+        }
+        tokenizer.enable_padding(**params)
+        assert tokenizer.padding == params
+
+        tokenizer.padding = None
+        assert tokenizer.padding is None
+
     def test_decode(self):
         tokenizer = Tokenizer(BPE())
         tokenizer.add_tokens(["my", "name", "is", "john", "pair"])
@@ -371,6 +906,35 @@ class TestTokenizer:
         assert stream.step(tokenizer, 2) == " is"
         assert stream.step(tokenizer, 3) == " john"
 
+    def test_decode_preserving_whitespace(self):
+        code = "def f(x):\n    if x:\n\treturn  x\n\n"
+
+        def tokenizer_for(pre_tokenizer, decoder):
+            pieces = [piece for piece, _ in pre_tokenizer.pre_tokenize_str(code)]
+            tokenizer = Tokenizer(WordLevel({piece: i for i, piece in enumerate(pieces)}, unk_token="[UNK]"))
+            tokenizer.pre_tokenizer = pre_tokenizer
+            tokenizer.decoder = decoder
+            return tokenizer
+
+        tokenizer = tokenizer_for(Metaspace(split=False), DecoderMetaspace(split=False))
+        ids = tokenizer.encode(code).ids
+        assert tokenizer.decode(ids) == "deff(x):\nifx:\n\treturnx\n\n"
+        assert tokenizer.decode(ids, preserve_whitespace=True) == code
+
+        tokenizer = tokenizer_for(Metaspace(), DecoderMetaspace())
+        assert tokenizer.decode(tokenizer.encode(code).ids, preserve_whitespace=True) == code
+
+        tokenizer = tokenizer_for(ByteLevel(add_prefix_space=False), decoders.ByteLevel())
+        ids = tokenizer.encode(code).ids
+        assert tokenizer.decode(ids) == code
+        assert tokenizer.decode(ids, preserve_whitespace=True) == code
+
+        tokenizer = Tokenizer(BPE())
+        tokenizer.add_tokens(["x", "!", "=", "y"])
+        tokenizer.decoder = decoders.WordPiece()
+        assert tokenizer.decode([0, 1, 2, 3]) == "x! = y"
+        assert tokenizer.decode([0, 1, 2, 3], preserve_whitespace=True) == "x ! = y"
+
     def test_decode_stream(self):
         vocab = [
             ("<unk>", 0.0),
@@ -395,6 +959,80 @@ class TestTokenizer:
         assert stream.step(tokenizer, 1) == "This"
         assert stream.step(tokenizer, 1) == " This"
 
+    def test_decode_bytes(self):
+        vocab = [
+            ("<unk>", 0.0),
+            ("a", -0.1),
+            ("<0xC3>", -0.2),
+            ("<0xA9>", -0.3),
+        ]
+        tokenizer = Tokenizer(Unigram(vocab, 0, byte_fallback=True))
+        tokenizer.decoder = ByteFallback(invalid_utf8="strict")
+        assert tokenizer.decode([1, 2, 3]) == "aé"
+        assert tokenizer.decode_bytes([1, 2, 3]) == "aé".encode()
+        with pytest.raises(Exception, match="Invalid UTF-8"):
+            tokenizer.decode([1, 2])
+        assert tokenizer.decode_bytes([1, 2]) == b"a\xc3"
+
+        # Streaming waits for the end of the char, whatever the policy
+        stream = DecodeStream(skip_special_tokens=False)
+        assert stream.step(tokenizer, 1) == "a"
+        assert stream.step(tokenizer, 2) == None
+        assert stream.step(tokenizer, 3) == "é"
+
+        # The byte-level chars of the bytes 0xFF and 0xE5, not valid UTF-8 on their own
+        tokenizer = Tokenizer(WordLevel({"a": 0, "ÿ": 1, "å": 2}, unk_token="a"))
+        tokenizer.decoder = decoders.ByteLevel()
+        assert tokenizer.decode([0, 1, 2]) == "a��"
+        assert tokenizer.decode_bytes([0, 1, 2]) == b"a\xff\xe5"
+
+    def test_audit_sampling(self):
+        vocab = [("<unk>", 0.0), ("a", -1.0), ("b", -1.0), ("ab", -0.5)]
+        tokenizer = Tokenizer(Unigram(vocab, 0, byte_fallback=False))
+        audit = tokenizer.audit_sampling(["abab", "ba"], 0.1, samples=20)
+        assert audit["sequences"] == 2
+        assert audit["samples"] == 20
+        assert audit["tokens"] == 4
+        assert 0 < audit["divergence_rate"] < 1
+        assert 0 < audit["boundary_agreement"] < 1
+        assert audit["length_ratio"] > 1
+        assert audit["unique_segmentations"] > 1
+
+        with pytest.raises(Exception):
+            tokenizer.audit_sampling(["ab"], 0.1, samples=0)
+        with pytest.raises(Exception, match="does not support sampling"):
+            Tokenizer(WordLevel({"a": 0}, unk_token="a")).audit_sampling(["ab"], 0.1)
+
+    def test_suggest_added_tokens(self):
+        tokenizer = Tokenizer(BPE({"a": 0, "b": 1, "c": 2, "ab": 3}, [("a", "b")]))
+        tokenizer.pre_tokenizer = WhitespaceSplit()
+        suggestions = tokenizer.suggest_added_tokens(["abc ab cc", "cc abc cc"])
+        assert [(s["token"].content, s["count"], s["tokens"], s["saved"]) for s in suggestions] == [
+            ("cc", 3, 6, 3),
+            ("abc", 2, 4, 2),
+        ]
+        assert suggestions[0]["token"].single_word
+        assert len(tokenizer.suggest_added_tokens(["abc ab cc"], n=1)) == 1
+
+        tokenizer.add_tokens([s["token"] for s in suggestions])
+        assert tokenizer.encode("abc cc").tokens == ["abc", "cc"]
+
+    def test_convert_offsets_batch(self):
+        tokenizer = Tokenizer(BPE())
+        tokenizer.normalizer = Sequence([Strip(), Lowercase()])
+        sequences = ["  Héllo World ", "Bye"]
+        # The spans of "world" and "bye" in "héllo world" and "bye"
+        original = tokenizer.convert_offsets_batch(sequences, [[(6, 11), (20, 22)], [(0, 3)]])
+        assert original == [[(8, 13), None], [(0, 3)]]
+        assert sequences[0][8:13] == "World"
+
+        normalized = tokenizer.convert_offsets_batch(sequences[:1], [[(8, 13)]], referential="original")
+        assert normalized == [[(6, 11)]]
+        with pytest.raises(ValueError, match="Wrong value for OffsetReferential"):
+            tokenizer.convert_offsets_batch(sequences, [[], []], referential="unknown")
+        with pytest.raises(Exception, match="Got 2 sequences"):
+            tokenizer.convert_offsets_batch(sequences, [])
+
     def test_get_vocab(self):
         tokenizer = Tokenizer(BPE())
         tokenizer.add_tokens(["my", "name", "is", "john", "pair"])
@@ -429,6 +1067,29 @@ class TestTokenizer:
         size = tokenizer.get_vocab_size(with_added_tokens=False)
         assert size == 0
 
+    def test_save_mmap(self, tmp_path):
+        vocab = {"<unk>": 0, "a": 1, "b": 2, "c": 3, "ab": 4, "abc": 5}
+        tokenizer = Tokenizer(BPE(vocab, [("a", "b"), ("ab", "c")], unk_token="<unk>"))
+        tokenizer.pre_tokenizer = WhitespaceSplit()
+        tokenizer.normalizer = Lowercase()
+        tokenizer.add_special_tokens(["<unk>"])
+        tokenizer.add_tokens(["[new]"])
+        tokenizer.enable_truncation(4)
+
+        path = str(tmp_path / "tokenizer.bin")
+        tokenizer.save_mmap(path)
+        mapped = Tokenizer.from_file_mmap(path)
+        assert isinstance(mapped.model, BPE)
+        assert mapped.to_str() == tokenizer.to_str()
+        assert mapped.get_vocab_size() == 7
+        assert mapped.token_to_id("abc") == 5
+        assert mapped.id_to_token(6) == "[new]"
+        assert mapped.encode("ABCab [NEW] abd").tokens == ["abc", "ab", "[new]", "ab"]
+
+        with pytest.raises(Exception, match="magic bytes are missing"):
+            tokenizer.save(path)
+            Tokenizer.from_file_mmap(path)
+
     def test_post_process(self):
         tokenizer = Tokenizer(BPE())
         tokenizer.add_tokens(["my", "name", "is", "john", "pair"])
@@ -592,6 +1253,125 @@ class TestTokenizer:
         tokenizer.pre_tokenizer = None
         assert tokenizer.pre_tokenizer == None
 
+    def test_chain(self):
+        first = Tokenizer(WordPiece({"[UNK]": 0, "un": 1, "##happi": 2, "##ness": 3}))
+        first.normalizer = Lowercase()
+        first.pre_tokenizer = WhitespaceSplit()
+        vocab = {"<unk>": 0, "u": 1, "n": 2, "h": 3, "a": 4, "p": 5, "i": 6, "e": 7, "s": 8, "pp": 9, "in": 10}
+        second = Tokenizer(BPE(vocab, [("p", "p"), ("i", "n")], unk_token="<unk>"))
+
+        # The merges never cross the morphemes
+        assert second.encode("unhappiness").tokens == ["u", "n", "h", "a", "pp", "in", "e", "s", "s"]
+        chained = Tokenizer.chain(first, second)
+        output = chained.encode("Unhappiness")
+        assert output.tokens == ["u", "n", "h", "a", "pp", "i", "n", "e", "s", "s"]
+        assert output.offsets[4:6] == [(4, 6), (6, 7)]
+        assert output.word_ids == [0, 0, 1, 1, 1, 1, 2, 2, 2, 2]
+
+        chained = Tokenizer.from_str(chained.to_str())
+        assert chained.encode("Unhappiness").tokens == output.tokens
+        assert isinstance(chained.pre_tokenizer[1], ModelSplit)
+
+    def test_gpt2_files(self):
+        vocab = {"<unk>": 0, "h": 1, "é": 2, "▁": 3, "hé": 4}
+        tokenizer = Tokenizer(BPE(vocab, [("h", "é")], unk_token="<unk>"))
+        tokenizer.pre_tokenizer = Metaspace()
+        files = tokenizer.gpt2_files()
+        assert files["merges.txt"].decode() == "#version: 0.2\nÃ ©\nh Ã©\n"
+
+        vocab, merges = BPE.read_bytes(files["vocab.json"], files["merges.txt"])
+        exported = Tokenizer(BPE(vocab, merges))
+        exported.pre_tokenizer = ByteLevel()
+        assert exported.encode("hé").ids == tokenizer.encode("hé").ids == [3, 4]
+
+        tokenizer = Tokenizer(WordPiece({"[UNK]": 0}))
+        with pytest.raises(Exception, match="BPE"):
+            tokenizer.gpt2_files()
+
+    def test_check_embeddings(self):
+        tokenizer = Tokenizer(WordLevel({"[UNK]": 0, "a": 1, "b": 2}, unk_token="[UNK]"))
+        tokenizer.add_special_tokens(["[PAD]"])
+        tokenizer.enable_padding(pad_id=3, pad_token="[PAD]")
+        assert tokenizer.check_embeddings(4) == []
+        assert tokenizer.check_embeddings(8, id_dtype="int8", padding_idx=3) == []
+
+        issues = tokenizer.check_embeddings(3)
+        assert issues[0].startswith("The added token `[PAD]` has the id 3, beyond the 3 rows")
+        assert tokenizer.check_embeddings(4, reserved_ids=[2]) == [
+            "The token `b` uses the reserved id 2: move it, or free this row"
+        ]
+        assert tokenizer.check_embeddings(4, padding_idx=0)[0].startswith(
+            "The tokenizer pads with the id 3, but the padding row of the embeddings is 0"
+        )
+
+        with pytest.raises(ValueError, match="float16"):
+            tokenizer.check_embeddings(4, id_dtype="float16")
+
+    def test_from_subword_nmt(self, tmp_path):
+        codes = tmp_path / "codes"
+        codes.write_text("#version: 0.2\nl o\ne r</w>\nlo w\n")
+        tokenizer = Tokenizer.from_subword_nmt(str(codes))
+        output = tokenizer.encode("lower  low")
+        assert output.tokens == ["low", "er</w>", "lo", "w</w>"]
+        assert tokenizer.decode(output.ids) == "lower low"
+
+    def test_to_pipeline_graph(self):
+        tokenizer = Tokenizer(BPE({"a": 0, "b": 1, "ab": 2}, [("a", "b")]))
+        tokenizer.normalizer = Sequence([Strip(), Lowercase()])
+        tokenizer.decoder = decoders.ByteLevel()
+        graph = json.loads(tokenizer.to_pipeline_graph())
+        assert [(n["stage"], n["type"], n["parent"]) for n in graph["nodes"]] == [
+            ("normalizer", "Sequence", None),
+            ("normalizer", "Strip", 0),
+            ("normalizer", "Lowercase", 0),
+            ("model", "BPE", None),
+            ("decoder", "ByteLevel", None),
+        ]
+        assert graph["edges"] == [[1, 2], [2, 3], [3, 4]]
+        assert graph["nodes"][3]["params"]["num_merges"] == 1
+
+        dot = tokenizer.to_pipeline_graph(format="dot")
+        assert dot.startswith("digraph pipeline {") and "subgraph cluster_0" in dot
+        with pytest.raises(ValueError, match="Unknown format"):
+            tokenizer.to_pipeline_graph(format="yaml")
+
+
+class TestShadowTokenizer:
+    def test_report(self):
+        primary = Tokenizer(WordLevel({"[UNK]": 0, "hello": 1}, unk_token="[UNK]"))
+        primary.pre_tokenizer = WhitespaceSplit()
+        candidate = Tokenizer.from_str(primary.to_str())
+        candidate.normalizer = Lowercase()
+
+        shadow = ShadowTokenizer(primary, candidate, max_examples=1)
+        assert shadow.encode("hello Hello").ids == [1, 0]
+        encodings = shadow.encode_batch(["hello", ("HELLO", "hello")])
+        assert [e.ids for e in encodings] == [[1], [0, 1]]
+
+        shadow.flush()
+        report = shadow.report(reset=True)
+        assert report["compared"] == 3
+        assert report["diverged"] == 2
+        assert report["skipped"] == 0
+        assert report["divergence_rate"] == 2 / 3
+        assert len(report["examples"]) == 1
+        example = report["examples"][0]
+        assert example["input"] == "hello Hello"
+        assert example["primary"].ids == [1, 0]
+        assert example["candidate"].ids == [1, 1]
+        assert shadow.report()["compared"] == 0
+
+    def test_candidate_errors(self):
+        primary = Tokenizer(WordLevel({"[UNK]": 0, "a": 1}, unk_token="[UNK]"))
+        candidate = Tokenizer(WordLevel({"x": 0, "a": 1}, unk_token="[UNK]"))
+        shadow = ShadowTokenizer(primary, candidate)
+        shadow.encode_batch([["a", "b"]], is_pretokenized=True)
+
+        shadow.flush()
+        example = shadow.report()["examples"][0]
+        assert example["input"] == ["a", "b"]
+        assert isinstance(example["candidate"], str)
+
 
 class TestTokenizerRepr:
     def test_repr(self):
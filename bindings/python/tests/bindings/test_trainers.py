@@ -1,4 +1,5 @@
 import copy
+import json
 import os
 import pickle
 
@@ -28,6 +29,7 @@ class TestBpeTrainer:
             initial_alphabet=["a", "b", "c"],
             continuing_subword_prefix="pref",
             end_of_word_suffix="suf",
+            max_memory=1024,
         )
 
         assert trainer.vocab_size == 12345
@@ -41,6 +43,7 @@ class TestBpeTrainer:
         assert sorted(trainer.initial_alphabet) == ["a", "b", "c"]
         assert trainer.continuing_subword_prefix == "pref"
         assert trainer.end_of_word_suffix == "suf"
+        assert trainer.max_memory == 1024
 
         # Modify these
         trainer.vocab_size = 20000
@@ -59,11 +62,102 @@ class TestBpeTrainer:
         assert trainer.continuing_subword_prefix == None
         trainer.end_of_word_suffix = None
         assert trainer.continuing_subword_prefix == None
+        trainer.max_memory = None
+        assert trainer.max_memory == None
+
+    def test_max_memory(self):
+        data = [f"word{i % 500} other{i % 7}" for i in range(5000)]
+
+        def train(**kwargs):
+            tokenizer = Tokenizer(models.BPE())
+            tokenizer.pre_tokenizer = pre_tokenizers.Whitespace()
+            trainer = trainers.BpeTrainer(vocab_size=100, show_progress=False, **kwargs)
+            tokenizer.train_from_iterator(data, trainer=trainer)
+            return tokenizer.get_vocab_size()
+
+        assert train(max_memory=1) == train()
+
+    def test_script_weights(self):
+        data = ["abab"] * 10 + ["вгвг"] * 6
+
+        def first_merge(**kwargs):
+            tokenizer = Tokenizer(models.BPE())
+            trainer = trainers.BpeTrainer(vocab_size=5, show_progress=False, **kwargs)
+            tokenizer.train_from_iterator(data, trainer=trainer)
+            return tokenizer.id_to_token(4)
+
+        assert first_merge() == "ab"
+        assert first_merge(script_weights={"Latin": 0.5}) == "вг"
+
+        trainer = trainers.BpeTrainer(script_weights={"Latin": 0.5})
+        assert trainer.script_weights == {"Latin": 0.5}
+        trainer.script_weights = {}
+        assert trainer.script_weights == {}
+        with pytest.raises(ValueError, match="must be finite and positive"):
+            trainers.BpeTrainer(script_weights={"Latin": -1.0})
+        with pytest.raises(ValueError, match="Unknown script latin"):
+            trainers.BpeTrainer(script_weights={"latin": 1.0})
+
+    def test_lexicographic_ties(self):
+        # All the pairs have the same count
+        data = ["dc", "ba", "bd"]
+        tokenizer = Tokenizer(models.BPE())
+        trainer = trainers.BpeTrainer(
+            show_progress=False, continuing_subword_prefix="##", lexicographic_ties=True
+        )
+        tokenizer.train_from_iterator(data, trainer=trainer)
+        merges = json.loads(tokenizer.to_str())["model"]["merges"]
+        assert merges == [["b", "##a"], ["b", "##d"], ["d", "##c"]]
+
+        assert trainer.lexicographic_ties
+        trainer.lexicographic_ties = False
+        assert not trainer.lexicographic_ties
+
+    def test_estimate_training(self):
+        tokenizer = Tokenizer(models.BPE())
+        tokenizer.pre_tokenizer = pre_tokenizers.WhitespaceSplit()
+        trainer = trainers.BpeTrainer(show_progress=False)
+
+        estimate = tokenizer.estimate_training(["a b c d", "e f g h"], 1500, trainer=trainer)
+        assert estimate["sample_bytes"] == 14
+        assert estimate["sample_unique_words"] == 8
+        assert estimate["unique_words"] == 857
+        assert estimate["peak_memory"] > 0
+        assert estimate["training_time"] >= 0.0
+
+        # Neither the tokenizer nor the trainer were trained
+        assert tokenizer.get_vocab_size() == 0
+        assert b'"words":{}' in trainer.__getstate__()
+        with pytest.raises(Exception, match="empty sample"):
+            tokenizer.estimate_training([], 1500)
+
+    def test_train_tagged(self):
+        tokenizer = Tokenizer(models.BPE())
+        tokenizer.pre_tokenizer = pre_tokenizers.WhitespaceSplit()
+        trainer = trainers.BpeTrainer(show_progress=False)
+
+        data = [("a", "aaaa ab"), (None, "c"), ("b", "bbbb ab"), ("a", "aaaa")]
+        statistics = tokenizer.train_tagged_from_iterator(iter(data), trainer=trainer)
+        assert [stats["tag"] for stats in statistics] == ["a", None, "b"]
+        assert statistics[0] == {
+            "tag": "a",
+            "sequences": 2,
+            "words": 3,
+            "unique_words": 2,
+            "tokens": 3,
+            "multi_char_tokens": 2,
+            "exclusive_tokens": 1,
+        }
+        assert statistics[1]["multi_char_tokens"] == 0
+        assert tokenizer.encode("aaaa bbbb").tokens == ["aaaa", "bbbb"]
+
+        with pytest.raises(Exception):
+            tokenizer.train_tagged_from_iterator(["not a tuple"], trainer=trainer)
 
     def test_can_pickle(self):
         assert (
             trainers.BpeTrainer(min_frequency=12).__getstate__()
-            == b"""{"BpeTrainer":{"min_frequency":12,"vocab_size":30000,"show_progress":true,"special_tokens":[],"limit_alphabet":null,"initial_alphabet":[],"continuing_subword_prefix":null,"end_of_word_suffix":null,"max_token_length":null,"words":{}}}"""
+            == b"""{"BpeTrainer":{"min_frequency":12,"vocab_size":30000,"show_progress":true,"special_tokens":[],"limit_alphabet":null,"initial_alphabet":[],"continuing_subword_prefix":null,"end_of_word_suffix":null,"max_token_length":null,"max_memory":null,"words":{}}}"""
         )
         assert isinstance(pickle.loads(pickle.dumps(trainers.BpeTrainer(min_frequency=12))), trainers.BpeTrainer)
 
@@ -73,6 +167,23 @@ class TestBpeTrainer:
             trainers.BpeTrainer(min_frequency=12)
         )
 
+    def test_progress_callback(self):
+        reports = []
+        trainer = trainers.BpeTrainer(show_progress=False, vocab_size=100)
+        trainer.set_progress_callback(lambda report: reports.append(report) or len(reports) < 4)
+
+        tokenizer = Tokenizer(models.BPE())
+        tokenizer.train_from_iterator(["roses are red"], trainer=trainer)
+        assert [r["phase"] for r in reports] == [
+            "Tokenize words",
+            "Count pairs",
+            "Compute merges",
+            "Compute merges",
+        ]
+        assert reports[-1]["iteration"] == 2
+        assert reports[-1]["loss"] is None
+        assert tokenizer.get_vocab_size() == reports[-1]["vocab_size"]
+
 
 class TestWordPieceTrainer:
     def test_can_modify(self):
@@ -148,6 +259,59 @@ class TestWordLevelTrainer:
     def test_can_pickle(self):
         assert isinstance(pickle.loads(pickle.dumps(trainers.WordLevelTrainer())), trainers.WordLevelTrainer)
 
+    def test_progress_callback(self):
+        reports = []
+        trainer = trainers.WordLevelTrainer(show_progress=False)
+        trainer.set_progress_callback(reports.append)
+
+        tokenizer = Tokenizer(models.WordLevel(unk_token="[UNK]"))
+        tokenizer.train_from_iterator(["roses are red"], trainer=trainer)
+        assert [r["phase"] for r in reports] == ["Compute vocabulary"]
+        assert reports[0]["vocab_size"] == tokenizer.get_vocab_size()
+
+        # The training has a single phase, and cannot stop early
+        trainer.set_progress_callback(lambda report: False)
+        with pytest.raises(Exception, match="Cannot stop the training early"):
+            tokenizer.train_from_iterator(["violets are blue"], trainer=trainer)
+        assert tokenizer.token_to_id("blue") is None
+
+
+class TestMaxMatchTrainer:
+    def test_can_modify(self):
+        trainer = trainers.MaxMatchTrainer(
+            vocab_size=12345, min_frequency=12, show_progress=False, special_tokens=["1", "2"]
+        )
+
+        assert trainer.vocab_size == 12345
+        assert trainer.min_frequency == 12
+        assert trainer.show_progress == False
+        assert trainer.special_tokens == [
+            AddedToken("1", special=True),
+            AddedToken("2", special=True),
+        ]
+
+        # Modify these
+        trainer.vocab_size = 20000
+        assert trainer.vocab_size == 20000
+        trainer.min_frequency = 1
+        assert trainer.min_frequency == 1
+        trainer.show_progress = True
+        assert trainer.show_progress == True
+        trainer.special_tokens = []
+        assert trainer.special_tokens == []
+
+    def test_can_pickle(self):
+        assert isinstance(pickle.loads(pickle.dumps(trainers.MaxMatchTrainer())), trainers.MaxMatchTrainer)
+
+    def test_train(self):
+        tokenizer = Tokenizer(models.MaxMatch(unk_token="<unk>"))
+        tokenizer.pre_tokenizer = pre_tokenizers.Whitespace()
+        trainer = trainers.MaxMatchTrainer(min_frequency=2, show_progress=False, special_tokens=["<unk>"])
+        tokenizer.train_from_iterator(["red roses", "red", "rare"], trainer=trainer)
+
+        # "a" and "t" are seen less than twice
+        assert tokenizer.encode("red rarest").tokens == ["red", "r", "<unk>", "r", "e", "s", "<unk>"]
+
 
 class TestUnigram:
     def test_train(self, train_files):
@@ -287,6 +451,52 @@ class TestUnigram:
         trainer.initial_alphabet = ["d", "z"]
         assert sorted(trainer.initial_alphabet) == ["d", "z"]
 
+    def test_min_frequency(self):
+        trainer = trainers.UnigramTrainer(show_progress=False, min_frequency=2)
+        assert trainer.min_frequency == 2
+        trainer.min_frequency = 3
+        assert trainer.min_frequency == 3
+
+        tokenizer = Tokenizer(models.Unigram())
+        tokenizer.pre_tokenizer = pre_tokenizers.Whitespace()
+        tokenizer.train_from_iterator(["are are The are"], trainer=trainer)
+        assert tokenizer.token_to_id("a") is not None
+        assert tokenizer.token_to_id("T") is None
+
+    def test_progress_callback(self):
+        reports = []
+        trainer = trainers.UnigramTrainer(show_progress=False)
+        trainer.set_progress_callback(reports.append)
+
+        tokenizer = Tokenizer(models.Unigram())
+        tokenizer.train_from_iterator(["The are", "are"], trainer=trainer)
+        assert [r["phase"] for r in reports] == ["Suffix array seeds", "EM training", "EM training"]
+        assert reports[-1]["iteration"] == 2
+        assert reports[-1]["loss"] is not None
+
+        # Returning False stops the training early
+        reports = []
+
+        def stop(report):
+            reports.append(report)
+            return report["phase"] != "EM training"
+
+        trainer.set_progress_callback(stop)
+        tokenizer.train_from_iterator(["The are", "are"], trainer=trainer)
+        assert [r["phase"] for r in reports] == ["Suffix array seeds", "EM training"]
+
+        # Raising stops the training with the exception
+        def fail(report):
+            raise ValueError("stop")
+
+        trainer.set_progress_callback(fail)
+        with pytest.raises(ValueError, match="stop"):
+            tokenizer.train_from_iterator(["The are", "are"], trainer=trainer)
+
+        trainer.set_progress_callback(None)
+        with pytest.raises(TypeError):
+            trainer.set_progress_callback(12)
+
     def test_continuing_prefix_trainer_mismatch(self, train_files):
         UNK = "[UNK]"
         special_tokens = [UNK]
@@ -0,0 +1,80 @@
+#[macro_use]
+extern crate criterion;
+
+use ahash::AHashMap;
+use criterion::{BatchSize, BenchmarkId, Criterion, Throughput};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use tokenizers::models::bpe::PairCounts;
+
+/// The number of distinct pairs being counted
+const PAIRS: u32 = 1 << 18;
+
+/// Some random updates of the pair counts, like the ones made after each merge
+fn updates(rng: &mut ChaCha8Rng, len: usize) -> Vec<((u32, u32), i64)> {
+    (0..len)
+        .map(|_| {
+            let pair = rng.random_range(0..PAIRS);
+            ((pair >> 9, pair & 511), rng.random_range(-8..8))
+        })
+        .collect()
+}
+
+/// The counts as updated before the sharding, in a single map, and the sharded counts applying
+/// the same updates in turn and in parallel. The sizes at which the parallel updates get faster
+/// than the others give the `PARALLEL_UPDATE_THRESHOLD` of the trainer
+fn bench_pair_counts(c: &mut Criterion) {
+    let mut rng = ChaCha8Rng::seed_from_u64(42);
+    let mut group = c.benchmark_group("bpe-pair-counts");
+    for len in [256, 1024, 4096, 16384, 65536] {
+        let updates = updates(&mut rng, len);
+        group.throughput(Throughput::Elements(len as u64));
+
+        let mut single = AHashMap::new();
+        group.bench_with_input(BenchmarkId::new("single map", len), &updates, |b, u| {
+            b.iter_batched(
+                || u.clone(),
+                |updates| {
+                    for (pair, delta) in updates {
+                        *single.entry(pair).or_insert(0i64) += delta;
+                    }
+                },
+                BatchSize::SmallInput,
+            )
+        });
+
+        let mut sharded = PairCounts::default();
+        group.bench_with_input(
+            BenchmarkId::new("sharded in turn", len),
+            &updates,
+            |b, u| {
+                b.iter_batched(
+                    || u.clone(),
+                    |updates| sharded.apply_in_turn(updates),
+                    BatchSize::SmallInput,
+                )
+            },
+        );
+
+        let mut sharded = PairCounts::default();
+        group.bench_with_input(
+            BenchmarkId::new("sharded in parallel", len),
+            &updates,
+            |b, u| {
+                b.iter_batched(
+                    || u.clone(),
+                    |updates| sharded.apply_in_parallel(updates),
+                    BatchSize::SmallInput,
+                )
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(20);
+    targets = bench_pair_counts
+}
+criterion_main!(benches);
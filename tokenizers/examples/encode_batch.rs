@@ -1,8 +1,8 @@
-   
-use tokenizers::{Tokenizer};
+use tokenizers::Tokenizer;
 
 fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let tokenizer = Tokenizer::from_file("/workspaces/tokenizers/tokenizers/examples/tokenizer.json")?;
+    let tokenizer =
+        Tokenizer::from_file("/workspaces/tokenizers/tokenizers/examples/tokenizer.json")?;
 
     let encoding = tokenizer.encode("hello, 🤗 [PAD] [UNK]", false)?;
     println!("out:{:?}", encoding.get_tokens());
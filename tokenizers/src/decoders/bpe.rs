@@ -5,15 +5,24 @@ use serde::{Deserialize, Serialize};
 #[derive(Deserialize, Clone, Debug, Serialize)]
 /// Allows decoding Original BPE by joining all the tokens and then replacing
 /// the suffix used to identify end-of-words by whitespaces
+///
+/// The `prefix` used to identify continuing subwords, if any, is removed from the tokens,
+/// and a whitespace is added before the other ones, so that the models using both markers,
+/// or only the prefix with an empty `suffix`, are decoded too.
 #[serde(tag = "type")]
 #[non_exhaustive]
 pub struct BPEDecoder {
     pub suffix: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
 }
 
 impl BPEDecoder {
     pub fn new(suffix: String) -> Self {
-        Self { suffix }
+        Self {
+            suffix,
+            prefix: None,
+        }
     }
 }
 
@@ -25,14 +34,91 @@ impl Default for BPEDecoder {
 
 impl Decoder for BPEDecoder {
     fn decode_chain(&self, tokens: Vec<String>) -> Result<Vec<String>> {
-        let n = tokens.len() - 1;
+        let n = tokens.len().saturating_sub(1);
+        let mut word_end = false;
         Ok(tokens
             .into_iter()
             .enumerate()
             .map(|(i, token)| {
-                let replacement = if i == n { "" } else { " " };
-                token.replace(&self.suffix, replacement)
+                let mut decoded = String::with_capacity(token.len() + 1);
+                let mut token = token.as_str();
+                if let Some(prefix) = &self.prefix {
+                    match token.strip_prefix(prefix.as_str()) {
+                        Some(rest) => token = rest,
+                        // The whitespace was already added after the end of the previous word
+                        None if i > 0 && !word_end => decoded.push(' '),
+                        None => {}
+                    }
+                }
+                word_end = false;
+                if !self.suffix.is_empty() {
+                    if let Some(rest) = token.strip_suffix(self.suffix.as_str()) {
+                        token = rest;
+                        word_end = true;
+                    }
+                }
+                decoded.push_str(token);
+                if word_end && i != n {
+                    decoded.push(' ');
+                }
+                decoded
             })
             .collect())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode() {
+        let decoder = BPEDecoder::default();
+        assert_eq!(
+            decoder
+                .decode(
+                    ["My</w>", "na", "me</w>", "is</w>", "Jo", "hn</w>"]
+                        .iter()
+                        .map(|t| t.to_string())
+                        .collect()
+                )
+                .unwrap(),
+            "My name is John"
+        );
+        assert_eq!(decoder.decode(vec![]).unwrap(), "");
+    }
+
+    #[test]
+    fn decode_prefix() {
+        let tokens = |tokens: &[&str]| tokens.iter().map(|t| t.to_string()).collect();
+        let mut decoder = BPEDecoder::new("".into());
+        decoder.prefix = Some("##".into());
+        assert_eq!(
+            decoder
+                .decode(tokens(&["My", "na", "##me", "is", "Jo", "##hn"]))
+                .unwrap(),
+            "My name is John"
+        );
+
+        // With both markers
+        decoder.suffix = "</w>".into();
+        assert_eq!(
+            decoder
+                .decode(tokens(&["My</w>", "na", "##me</w>", "Jo", "##hn</w>"]))
+                .unwrap(),
+            "My name John"
+        );
+    }
+
+    #[test]
+    fn serialization() {
+        let decoder = BPEDecoder::default();
+        let serialized = serde_json::to_string(&decoder).unwrap();
+        assert_eq!(serialized, r#"{"type":"BPEDecoder","suffix":"</w>"}"#);
+
+        let serialized = r###"{"type":"BPEDecoder","suffix":"</w>","prefix":"##"}"###;
+        let decoder: BPEDecoder = serde_json::from_str(serialized).unwrap();
+        assert_eq!(decoder.prefix.as_deref(), Some("##"));
+        assert_eq!(serde_json::to_string(&decoder).unwrap(), serialized);
+    }
+}
@@ -3,63 +3,176 @@ use monostate::MustBe;
 
 use serde::{Deserialize, Serialize};
 
+/// The policy applied by [`ByteFallback`] to byte tokens that don't form valid UTF-8
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InvalidUtf8 {
+    /// Replace each byte of the invalid sequence with �
+    #[default]
+    Replace,
+    /// Drop the invalid bytes, keeping the valid characters around them
+    Ignore,
+    /// Fail with an error
+    Strict,
+}
+
+impl InvalidUtf8 {
+    fn is_replace(&self) -> bool {
+        *self == Self::Replace
+    }
+}
+
+impl std::fmt::Display for InvalidUtf8 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.serialize(f)
+    }
+}
+
+/// A part of a token, when decoding the chain to bytes. The bytes that don't form valid UTF-8
+/// are carried out of the text, untouched by the decoders that follow.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum DecodedChunk {
+    /// Some text, decoded by the next decoders
+    Text(String),
+    /// Raw bytes, not valid UTF-8
+    Bytes(Vec<u8>),
+}
+
+impl DecodedChunk {
+    /// Split the bytes into their valid and invalid UTF-8 parts
+    pub(crate) fn from_bytes(bytes: &[u8]) -> Vec<Self> {
+        let mut chunks = vec![];
+        for chunk in bytes.utf8_chunks() {
+            if !chunk.valid().is_empty() {
+                chunks.push(Self::Text(chunk.valid().to_owned()));
+            }
+            if !chunk.invalid().is_empty() {
+                chunks.push(Self::Bytes(chunk.invalid().to_vec()));
+            }
+        }
+        chunks
+    }
+
+    /// Concatenate the tokens made of chunks to the raw bytes
+    pub(crate) fn concat(tokens: Vec<Vec<Self>>) -> Vec<u8> {
+        let mut bytes = vec![];
+        for chunk in tokens.into_iter().flatten() {
+            match chunk {
+                Self::Text(text) => bytes.extend_from_slice(text.as_bytes()),
+                Self::Bytes(raw) => bytes.extend(raw),
+            }
+        }
+        bytes
+    }
+}
+
 #[derive(Deserialize, Clone, Debug, Serialize, Default)]
 /// ByteFallback is a simple trick which converts tokens looking like `<0x61>`
 /// to pure bytes, and attempts to make them into a string. If the tokens
-/// cannot be decoded you will get � instead for each inconvertible byte token
+/// cannot be decoded, the [`InvalidUtf8`] policy applies: by default you will get �
+/// instead for each inconvertible byte token
 #[non_exhaustive]
 pub struct ByteFallback {
     #[serde(rename = "type")]
     type_: MustBe!("ByteFallback"),
+    #[serde(default, skip_serializing_if = "InvalidUtf8::is_replace")]
+    invalid_utf8: InvalidUtf8,
 }
 
 impl ByteFallback {
     pub fn new() -> Self {
         Self {
             type_: MustBe!("ByteFallback"),
+            invalid_utf8: InvalidUtf8::Replace,
         }
     }
-}
 
-impl Decoder for ByteFallback {
-    fn decode_chain(&self, tokens: Vec<String>) -> Result<Vec<String>> {
-        let mut new_tokens: Vec<String> = vec![];
-        let mut previous_byte_tokens: Vec<u8> = vec![];
+    pub fn get_invalid_utf8(&self) -> InvalidUtf8 {
+        self.invalid_utf8
+    }
+
+    pub fn set_invalid_utf8(&mut self, invalid_utf8: InvalidUtf8) {
+        self.invalid_utf8 = invalid_utf8;
+    }
+
+    /// Decode the chain, keeping the bytes that don't form valid UTF-8 as is instead of
+    /// applying the policy
+    pub(crate) fn decode_chunks(&self, tokens: Vec<String>) -> Vec<Vec<DecodedChunk>> {
+        self.byte_runs(tokens)
+            .into_iter()
+            .map(|chunk| match chunk {
+                DecodedChunk::Bytes(bytes) => DecodedChunk::from_bytes(&bytes),
+                text => vec![text],
+            })
+            .collect()
+    }
+
+    /// Merge the consecutive byte tokens, each run giving a single text chunk when it forms
+    /// valid UTF-8, or a bytes chunk otherwise
+    fn byte_runs(&self, tokens: Vec<String>) -> Vec<DecodedChunk> {
+        let mut chunks = vec![];
+        let mut run: Vec<u8> = vec![];
+        let flush = |run: &mut Vec<u8>, chunks: &mut Vec<DecodedChunk>| {
+            if !run.is_empty() {
+                chunks.push(match String::from_utf8(std::mem::take(run)) {
+                    Ok(string) => DecodedChunk::Text(string),
+                    Err(e) => DecodedChunk::Bytes(e.into_bytes()),
+                });
+            }
+        };
 
         for token in tokens {
-            let bytes = if token.len() == 6 && token.starts_with("<0x") && token.ends_with('>') {
+            let byte = if token.len() == 6 && token.starts_with("<0x") && token.ends_with('>') {
                 u8::from_str_radix(&token[3..5], 16).ok()
             } else {
                 None
             };
-            if let Some(bytes) = bytes {
-                previous_byte_tokens.push(bytes);
+            if let Some(byte) = byte {
+                run.push(byte);
             } else {
-                if !previous_byte_tokens.is_empty() {
-                    if let Ok(string) = String::from_utf8(previous_byte_tokens.clone()) {
+                flush(&mut run, &mut chunks);
+                chunks.push(DecodedChunk::Text(token));
+            }
+        }
+        flush(&mut run, &mut chunks);
+        chunks
+    }
+}
+
+impl Decoder for ByteFallback {
+    fn decode_chain(&self, tokens: Vec<String>) -> Result<Vec<String>> {
+        let mut new_tokens = vec![];
+        for chunk in self.byte_runs(tokens) {
+            let bytes = match chunk {
+                DecodedChunk::Text(token) => {
+                    new_tokens.push(token);
+                    continue;
+                }
+                DecodedChunk::Bytes(bytes) => bytes,
+            };
+            match self.invalid_utf8 {
+                InvalidUtf8::Replace => {
+                    for _ in 0..bytes.len() {
+                        new_tokens.push("�".into());
+                    }
+                }
+                InvalidUtf8::Ignore => {
+                    let string: String = bytes.utf8_chunks().map(|c| c.valid()).collect();
+                    if !string.is_empty() {
                         new_tokens.push(string);
-                    } else {
-                        for _ in 0..previous_byte_tokens.len() {
-                            new_tokens.push("�".into());
-                        }
                     }
-                    previous_byte_tokens.clear();
                 }
-                new_tokens.push(token);
-            }
-        }
-        if !previous_byte_tokens.is_empty() {
-            if let Ok(string) = String::from_utf8(previous_byte_tokens.clone()) {
-                new_tokens.push(string);
-            } else {
-                for _ in 0..previous_byte_tokens.len() {
-                    new_tokens.push("�".into());
+                InvalidUtf8::Strict => {
+                    return Err(format!("Invalid UTF-8 byte sequence: {bytes:02X?}").into())
                 }
             }
         }
-
         Ok(new_tokens)
     }
+
+    fn decode_bytes(&self, tokens: Vec<String>) -> Result<Vec<u8>> {
+        Ok(DecodedChunk::concat(self.decode_chunks(tokens)))
+    }
 }
 
 #[cfg(test)]
@@ -106,4 +219,58 @@ mod tests {
             .unwrap();
         assert_eq!(res, vec!["�", "�", "a"]);
     }
+
+    #[test]
+    fn decode_invalid_utf8() {
+        // "a" followed by the first two bytes of 叫
+        let tokens: Vec<String> = vec!["<0x61>".into(), "<0xE5>".into(), "<0x8f>".into()];
+        let mut decoder = ByteFallback::new();
+        assert_eq!(decoder.get_invalid_utf8(), InvalidUtf8::Replace);
+
+        decoder.set_invalid_utf8(InvalidUtf8::Ignore);
+        let res = decoder.decode_chain(tokens.clone()).unwrap();
+        assert_eq!(res, vec!["a"]);
+        let res = decoder.decode_chain(vec!["<0xE5>".into()]).unwrap();
+        assert!(res.is_empty());
+
+        decoder.set_invalid_utf8(InvalidUtf8::Strict);
+        assert!(decoder.decode_chain(tokens.clone()).is_err());
+        let res = decoder.decode_chain(vec!["<0x61>".into()]).unwrap();
+        assert_eq!(res, vec!["a"]);
+
+        // The raw bytes don't depend on the policy
+        for policy in [
+            InvalidUtf8::Replace,
+            InvalidUtf8::Ignore,
+            InvalidUtf8::Strict,
+        ] {
+            decoder.set_invalid_utf8(policy);
+            let res = decoder.decode_bytes(tokens.clone()).unwrap();
+            assert_eq!(res, vec![0x61, 0xE5, 0x8F]);
+        }
+    }
+
+    #[test]
+    fn decode_bytes_private_use() {
+        // A real char from the end of the Supplementary Private Use Area-B
+        let decoder = ByteFallback::new();
+        let tokens: Vec<String> = vec!["x\u{10FF41}".into(), "<0xE5>".into(), "y".into()];
+        assert_eq!(
+            decoder.decode_bytes(tokens).unwrap(),
+            b"x\xF4\x8F\xBD\x81\xE5y".to_vec()
+        );
+    }
+
+    #[test]
+    fn serialization() {
+        let mut decoder = ByteFallback::new();
+        let json = serde_json::to_string(&decoder).unwrap();
+        assert_eq!(json, r#"{"type":"ByteFallback"}"#);
+
+        decoder.set_invalid_utf8(InvalidUtf8::Strict);
+        let json = serde_json::to_string(&decoder).unwrap();
+        assert_eq!(json, r#"{"type":"ByteFallback","invalid_utf8":"strict"}"#);
+        let decoder: ByteFallback = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoder.get_invalid_utf8(), InvalidUtf8::Strict);
+    }
 }
@@ -9,11 +9,12 @@ pub mod wordpiece;
 // Re-export these as decoders
 pub use super::pre_tokenizers::byte_level;
 pub use super::pre_tokenizers::metaspace;
+pub use super::pre_tokenizers::run_length;
 
 use serde::{Deserialize, Deserializer, Serialize};
 
 use crate::decoders::bpe::BPEDecoder;
-use crate::decoders::byte_fallback::ByteFallback;
+use crate::decoders::byte_fallback::{ByteFallback, DecodedChunk};
 use crate::decoders::ctc::CTC;
 use crate::decoders::fuse::Fuse;
 use crate::decoders::sequence::Sequence;
@@ -22,6 +23,7 @@ use crate::decoders::wordpiece::WordPiece;
 use crate::normalizers::replace::Replace;
 use crate::pre_tokenizers::byte_level::ByteLevel;
 use crate::pre_tokenizers::metaspace::Metaspace;
+use crate::pre_tokenizers::run_length::RunLength;
 use crate::{Decoder, Result};
 
 #[derive(Serialize, Clone, Debug)]
@@ -37,6 +39,7 @@ pub enum DecoderWrapper {
     Fuse(Fuse),
     Strip(Strip),
     ByteFallback(ByteFallback),
+    RunLength(RunLength),
 }
 
 impl<'de> Deserialize<'de> for DecoderWrapper {
@@ -63,6 +66,7 @@ impl<'de> Deserialize<'de> for DecoderWrapper {
             Fuse,
             Strip,
             ByteFallback,
+            RunLength,
         }
 
         #[derive(Deserialize)]
@@ -85,6 +89,7 @@ impl<'de> Deserialize<'de> for DecoderWrapper {
             Fuse(Fuse),
             Strip(Strip),
             ByteFallback(ByteFallback),
+            RunLength(RunLength),
         }
 
         let helper = DecoderHelper::deserialize(deserializer).expect("Helper");
@@ -128,6 +133,9 @@ impl<'de> Deserialize<'de> for DecoderWrapper {
                     EnumType::ByteFallback => DecoderWrapper::ByteFallback(
                         serde_json::from_value(values).map_err(serde::de::Error::custom)?,
                     ),
+                    EnumType::RunLength => DecoderWrapper::RunLength(
+                        serde_json::from_value(values).map_err(serde::de::Error::custom)?,
+                    ),
                 }
             }
             DecoderHelper::Legacy(value) => {
@@ -143,6 +151,7 @@ impl<'de> Deserialize<'de> for DecoderWrapper {
                     DecoderUntagged::Fuse(dec) => DecoderWrapper::Fuse(dec),
                     DecoderUntagged::Strip(dec) => DecoderWrapper::Strip(dec),
                     DecoderUntagged::ByteFallback(dec) => DecoderWrapper::ByteFallback(dec),
+                    DecoderUntagged::RunLength(dec) => DecoderWrapper::RunLength(dec),
                 }
             }
         })
@@ -162,8 +171,164 @@ impl Decoder for DecoderWrapper {
             Self::ByteFallback(bf) => bf.decode_chain(tokens),
             Self::Strip(bf) => bf.decode_chain(tokens),
             Self::Fuse(bf) => bf.decode_chain(tokens),
+            Self::RunLength(rl) => rl.decode_chain(tokens),
+        }
+    }
+
+    fn decode_bytes(&self, tokens: Vec<String>) -> Result<Vec<u8>> {
+        let tokens = tokens
+            .into_iter()
+            .map(|token| vec![DecodedChunk::Text(token)])
+            .collect();
+        Ok(DecodedChunk::concat(self.decode_chunks(tokens)?))
+    }
+
+    fn decode_preserving_whitespace(&self, tokens: Vec<String>) -> Result<String> {
+        Ok(self.decode_chain_preserving_whitespace(tokens)?.concat())
+    }
+}
+
+impl DecoderWrapper {
+    /// Decode the chain, with any `ByteFallback` or `ByteLevel` keeping the invalid bytes out
+    /// of the text instead of applying its policy. These bytes go through the rest of the
+    /// chain untouched, as parts of the tokens.
+    fn decode_chunks(&self, tokens: Vec<Vec<DecodedChunk>>) -> Result<Vec<Vec<DecodedChunk>>> {
+        match self {
+            Self::ByteFallback(bf) => decode_text_runs(tokens, |run| Ok(bf.decode_chunks(run))),
+            Self::ByteLevel(_) => {
+                let mut bytes = vec![];
+                for chunk in tokens.into_iter().flatten() {
+                    match chunk {
+                        DecodedChunk::Text(text) => bytes.extend(ByteLevel::to_bytes(vec![text])),
+                        DecodedChunk::Bytes(raw) => bytes.extend(raw),
+                    }
+                }
+                Ok(vec![DecodedChunk::from_bytes(&bytes)])
+            }
+            Self::Fuse(_) => {
+                let mut fused: Vec<DecodedChunk> = vec![];
+                for chunk in tokens.into_iter().flatten() {
+                    match (fused.last_mut(), chunk) {
+                        (Some(DecodedChunk::Text(last)), DecodedChunk::Text(text)) => {
+                            last.push_str(&text)
+                        }
+                        (_, chunk) => fused.push(chunk),
+                    }
+                }
+                Ok(vec![fused])
+            }
+            // Only the start of the first part and the end of the last one get stripped
+            Self::Strip(strip) => tokens
+                .into_iter()
+                .map(|mut token| {
+                    let last = token.len().saturating_sub(1);
+                    for (i, chunk) in token.iter_mut().enumerate() {
+                        if let DecodedChunk::Text(text) = chunk {
+                            let start = if i == 0 { strip.start } else { 0 };
+                            let stop = if i == last { strip.stop } else { 0 };
+                            *text = Strip::new(strip.content, start, stop)
+                                .decode(vec![std::mem::take(text)])?;
+                        }
+                    }
+                    Ok(token)
+                })
+                .collect(),
+            Self::Sequence(seq) => seq
+                .get_decoders()
+                .iter()
+                .try_fold(tokens, |tokens, decoder| decoder.decode_chunks(tokens)),
+            _ => {
+                // The decoders giving one token per token decode each part of text as a token,
+                // seeing all of them at once. The others decode each run of text tokens, the
+                // tokens with bytes being kept as is.
+                if tokens.iter().all(|token| text_of(token).is_some()) {
+                    let tokens = tokens
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|chunk| match chunk {
+                            DecodedChunk::Text(text) => Some(text),
+                            DecodedChunk::Bytes(_) => None,
+                        });
+                    return Ok(self
+                        .decode_chain(tokens.collect())?
+                        .into_iter()
+                        .map(|token| vec![DecodedChunk::Text(token)])
+                        .collect());
+                }
+                let mut parts = vec![];
+                for chunk in tokens.iter().flatten() {
+                    if let DecodedChunk::Text(text) = chunk {
+                        parts.push(text.clone());
+                    }
+                }
+                let count = parts.len();
+                let decoded = self.decode_chain(parts)?;
+                if decoded.len() == count {
+                    let mut decoded = decoded.into_iter();
+                    let mut tokens = tokens;
+                    for chunk in tokens.iter_mut().flatten() {
+                        if let DecodedChunk::Text(text) = chunk {
+                            *text = decoded.next().unwrap_or_default();
+                        }
+                    }
+                    Ok(tokens)
+                } else {
+                    decode_text_runs(tokens, |run| {
+                        Ok(self
+                            .decode_chain(run)?
+                            .into_iter()
+                            .map(|token| vec![DecodedChunk::Text(token)])
+                            .collect())
+                    })
+                }
+            }
         }
     }
+
+    /// Decode the chain, without the heuristics altering the whitespace
+    fn decode_chain_preserving_whitespace(&self, tokens: Vec<String>) -> Result<Vec<String>> {
+        match self {
+            Self::Metaspace(ms) => Ok(ms.decode_tokens(tokens, true)),
+            Self::WordPiece(wp) => WordPiece::new(wp.prefix.clone(), false).decode_chain(tokens),
+            Self::Sequence(seq) => seq.decode_chain_preserving_whitespace(tokens),
+            _ => self.decode_chain(tokens),
+        }
+    }
+}
+
+/// The text of a token made of a single part of text
+fn text_of(token: &[DecodedChunk]) -> Option<&str> {
+    match token {
+        [DecodedChunk::Text(text)] => Some(text),
+        _ => None,
+    }
+}
+
+/// Decode each run of text tokens with `decode`, keeping the tokens with bytes in place
+fn decode_text_runs<F>(
+    tokens: Vec<Vec<DecodedChunk>>,
+    mut decode: F,
+) -> Result<Vec<Vec<DecodedChunk>>>
+where
+    F: FnMut(Vec<String>) -> Result<Vec<Vec<DecodedChunk>>>,
+{
+    let mut decoded = vec![];
+    let mut run = vec![];
+    for token in tokens {
+        match text_of(&token) {
+            Some(text) => run.push(text.to_owned()),
+            None => {
+                if !run.is_empty() {
+                    decoded.extend(decode(std::mem::take(&mut run))?);
+                }
+                decoded.push(token);
+            }
+        }
+    }
+    if !run.is_empty() {
+        decoded.extend(decode(run)?);
+    }
+    Ok(decoded)
 }
 
 impl_enum_from!(BPEDecoder, DecoderWrapper, BPE);
@@ -176,6 +341,7 @@ impl_enum_from!(WordPiece, DecoderWrapper, WordPiece);
 impl_enum_from!(CTC, DecoderWrapper, CTC);
 impl_enum_from!(Sequence, DecoderWrapper, Sequence);
 impl_enum_from!(Replace, DecoderWrapper, Replace);
+impl_enum_from!(RunLength, DecoderWrapper, RunLength);
 
 #[cfg(test)]
 mod tests {
@@ -193,6 +359,39 @@ mod tests {
         let serialized = serde_json::to_string(&decoder).unwrap();
         assert_eq!(serialized, json);
     }
+    #[test]
+    fn decode_bytes_sequence() {
+        let mut byte_fallback = ByteFallback::new();
+        byte_fallback.set_invalid_utf8(byte_fallback::InvalidUtf8::Strict);
+        let decoder = DecoderWrapper::Sequence(Sequence::new(vec![
+            DecoderWrapper::Replace(Replace::new("▁", " ").unwrap()),
+            DecoderWrapper::ByteFallback(byte_fallback),
+            DecoderWrapper::Fuse(Fuse::new()),
+            DecoderWrapper::Strip(Strip::new(' ', 1, 0)),
+        ]));
+        let tokens: Vec<String> = vec!["▁Hey", "▁", "<0xE5>", "<0x8F>"]
+            .into_iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        assert!(decoder.decode(tokens.clone()).is_err());
+        assert_eq!(
+            decoder.decode_bytes(tokens).unwrap(),
+            b"Hey \xE5\x8F".to_vec()
+        );
+
+        // The chars of the text are never taken for bytes, and the bytes don't split the
+        // fused text
+        let tokens: Vec<String> = vec!["▁x\u{10FF41}", "<0xE5>", "▁y"]
+            .into_iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(
+            decoder.decode_bytes(tokens).unwrap(),
+            b"x\xF4\x8F\xBD\x81\xE5 y".to_vec()
+        );
+    }
+
     #[test]
     fn decoder_serialization_other_no_arg() {
         let json = r#"{"type":"Sequence","decoders":[{"type":"Fuse"},{"type":"Metaspace","replacement":"▁","prepend_scheme":"always","split":true}]}"#;
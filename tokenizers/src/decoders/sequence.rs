@@ -21,6 +21,16 @@ impl Sequence {
     pub fn get_decoders_mut(&mut self) -> &mut [DecoderWrapper] {
         &mut self.decoders
     }
+
+    pub(crate) fn decode_chain_preserving_whitespace(
+        &self,
+        mut tokens: Vec<String>,
+    ) -> Result<Vec<String>> {
+        for decoder in &self.decoders {
+            tokens = decoder.decode_chain_preserving_whitespace(tokens)?;
+        }
+        Ok(tokens)
+    }
 }
 
 impl Decoder for Sequence {
@@ -30,13 +40,18 @@ impl Decoder for Sequence {
         }
         Ok(tokens)
     }
+
+    fn decode_preserving_whitespace(&self, tokens: Vec<String>) -> Result<String> {
+        Ok(self.decode_chain_preserving_whitespace(tokens)?.concat())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::decoders::ctc::CTC;
-    use crate::pre_tokenizers::metaspace::Metaspace;
+    use crate::decoders::wordpiece::WordPiece;
+    use crate::pre_tokenizers::metaspace::{Metaspace, PrependScheme};
 
     #[test]
     fn sequence_basic() {
@@ -52,4 +67,29 @@ mod tests {
         let out_tokens = decoder.decode(tokens).unwrap();
         assert_eq!(out_tokens, "Hi you");
     }
+
+    #[test]
+    fn sequence_preserving_whitespace() {
+        let decoders = vec![
+            DecoderWrapper::Metaspace(Metaspace::new('▁', PrependScheme::Always, false)),
+            DecoderWrapper::WordPiece(WordPiece::default()),
+        ];
+        let decoder = Sequence::new(decoders);
+        let tokens = vec!["▁x▁▁", "##,▁y"]
+            .into_iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(decoder.decode(tokens.clone()).unwrap(), "x, y");
+        assert_eq!(
+            decoder
+                .decode_preserving_whitespace(tokens.clone())
+                .unwrap(),
+            "x  , y"
+        );
+        let decoder = DecoderWrapper::Sequence(decoder);
+        assert_eq!(
+            decoder.decode_preserving_whitespace(tokens).unwrap(),
+            "x  , y"
+        );
+    }
 }
@@ -59,6 +59,12 @@ impl Decoder for WordPiece {
         }
         Ok(tokens)
     }
+
+    fn decode_preserving_whitespace(&self, tokens: Vec<String>) -> Result<String> {
+        Ok(Self::new(self.prefix.clone(), false)
+            .decode_chain(tokens)?
+            .join(""))
+    }
 }
 
 #[cfg(test)]
@@ -83,4 +89,18 @@ mod tests {
             "##uelo Araújo Noguera"
         );
     }
+
+    #[test]
+    fn decode_preserving_whitespace() {
+        let decoder = WordPiece::default();
+        let tokens = ["if", "x", "!", "=", "y", "?", "x", ":", "y"]
+            .iter()
+            .map(|t| t.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(decoder.decode(tokens.clone()).unwrap(), "if x! = y? x : y");
+        assert_eq!(
+            decoder.decode_preserving_whitespace(tokens).unwrap(),
+            "if x ! = y ? x : y"
+        );
+    }
 }
@@ -114,6 +114,13 @@
 //! }
 //! ```
 //!
+//! # Custom components
+//!
+//! Each step of the pipeline is a trait, that any type can implement to replace the built-in
+//! components. The [`prelude`] has everything needed to write them, like `NormalizedString`
+//! or `PreTokenizedString`, and is the part of the API that stays stable between minor
+//! versions.
+//!
 //! # Additional information
 //!
 //! - tokenizers is designed to leverage CPU parallelism when possible. The level of parallelism is determined
@@ -142,6 +149,7 @@ pub mod decoders;
 pub mod models;
 pub mod normalizers;
 pub mod pre_tokenizers;
+pub mod prelude;
 pub mod processors;
 pub mod tokenizer;
 
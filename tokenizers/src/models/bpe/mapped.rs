@@ -0,0 +1,213 @@
+use super::{MergeMap, MergeRanks, Pair};
+use crate::models::vocab_store::{at, push_u32, MappedVocab, VocabStore};
+use crate::tokenizer::Result;
+use crate::utils::mmap::{read_u32, MappedFile};
+use ahash::AHashMap;
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+const INVALID: &str = "Invalid vocab or merges tables";
+
+/// The vocab and merges of a `BPE`, read in place from the tables of a `MappedFile`
+/// instead of being parsed into maps. Nothing is indexed when loading: the tables are
+/// sorted, and searched on each lookup.
+///
+/// The vocab table is the one of a `MappedVocab`. The merges table follows, made of the
+/// number of merges and of the `(a, b, rank, new_id)` of each merge, sorted by `(a, b)`, all
+/// as little-endian `u32`.
+#[derive(Clone)]
+pub(crate) struct MappedBpe {
+    file: Arc<MappedFile>,
+    vocab: MappedVocab,
+    start: usize,
+    merges_len: usize,
+    merges: usize,
+    end: usize,
+}
+
+impl MappedBpe {
+    /// Read the tables starting at `start` in `file`, checking that they fit in it
+    pub(crate) fn parse(file: Arc<MappedFile>, start: usize) -> Result<Self> {
+        let vocab = MappedVocab::parse(file.clone(), start)?;
+        let merges_len = read_u32(&file, vocab.end()).ok_or(INVALID)? as usize;
+        let merges = vocab.end() + 4;
+        let end = at(merges, merges_len, 16).ok_or(INVALID)?;
+        if end > file.len() {
+            return Err(INVALID.into());
+        }
+
+        Ok(Self {
+            file,
+            vocab,
+            start,
+            merges_len,
+            merges,
+            end,
+        })
+    }
+
+    /// The offset right after the tables
+    pub(crate) fn end(&self) -> usize {
+        self.end
+    }
+
+    fn word(&self, offset: usize) -> u32 {
+        // Every offset we read from has been checked in `parse`
+        read_u32(&self.file, offset).unwrap_or_default()
+    }
+
+    /// The number of tokens in the vocab
+    pub(crate) fn len(&self) -> usize {
+        self.vocab.len()
+    }
+
+    /// The number of merges
+    pub(crate) fn merges_len(&self) -> usize {
+        self.merges_len
+    }
+
+    pub(crate) fn token_to_id(&self, token: &str) -> Option<u32> {
+        self.vocab.token_to_id(token)
+    }
+
+    pub(crate) fn id_to_token(&self, id: u32) -> Option<&str> {
+        self.vocab.id_to_token(id)
+    }
+
+    /// The ids of the tokens starting with the given bytes, in ascending order
+    pub(crate) fn ids_with_prefix(&self, prefix: &[u8]) -> Vec<u32> {
+        self.vocab.ids_with_prefix(prefix)
+    }
+
+    /// The `(pair, (rank, new_id))` of the merge at `index` in the table
+    fn merge_at(&self, index: usize) -> (Pair, (u32, u32)) {
+        let offset = self.merges + index * 16;
+        (
+            (self.word(offset), self.word(offset + 4)),
+            (self.word(offset + 8), self.word(offset + 12)),
+        )
+    }
+
+    /// Build the owned vocab, indexed by id
+    pub(crate) fn vocab_r(&self) -> AHashMap<u32, String> {
+        self.vocab
+            .iter()
+            .map(|(token, id)| (id, token.to_owned()))
+            .collect()
+    }
+
+    /// Build the owned merges
+    pub(crate) fn merge_map(&self) -> MergeMap {
+        (0..self.merges_len).map(|i| self.merge_at(i)).collect()
+    }
+
+    /// Write the tables of the given vocab and merges, in the format read by `parse`
+    pub(crate) fn write(
+        vocab_r: &AHashMap<u32, String>,
+        merges: &MergeMap,
+        out: &mut Vec<u8>,
+    ) -> Result<()> {
+        MappedVocab::write(vocab_r, out)?;
+        let mut merges = merges.iter().collect::<Vec<_>>();
+        merges.sort_unstable_by_key(|(pair, _)| **pair);
+        push_u32(out, merges.len())?;
+        for ((a, b), (rank, new_id)) in merges {
+            for word in [a, b, rank, new_id] {
+                push_u32(out, *word as usize)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl MergeRanks for MappedBpe {
+    fn merge_of(&self, pair: &Pair) -> Option<(u32, u32)> {
+        let (mut low, mut high) = (0, self.merges_len);
+        while low < high {
+            let middle = low + (high - low) / 2;
+            let (other, merge) = self.merge_at(middle);
+            match other.cmp(pair) {
+                Ordering::Less => low = middle + 1,
+                Ordering::Greater => high = middle,
+                Ordering::Equal => return Some(merge),
+            }
+        }
+        None
+    }
+}
+
+impl PartialEq for MappedBpe {
+    fn eq(&self, other: &Self) -> bool {
+        self.file[self.start..self.end] == other.file[other.start..other.end]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn mapped(vocab_r: &AHashMap<u32, String>, merges: &MergeMap) -> MappedBpe {
+        // Start after a few bytes, as the tables follow a header in the files
+        let mut content = b"header".to_vec();
+        MappedBpe::write(vocab_r, merges, &mut content).unwrap();
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(&content).unwrap();
+        // SAFETY: The temporary file is not modified while mapped
+        let file = unsafe { MappedFile::open(file.path()) }.unwrap();
+        let mapped = MappedBpe::parse(Arc::new(file), 6).unwrap();
+        assert_eq!(mapped.end(), content.len());
+        mapped
+    }
+
+    #[test]
+    fn lookups() {
+        // With a hole at id 3
+        let vocab_r: AHashMap<u32, String> = [(0, "b"), (1, "a"), (2, "ab"), (4, "é")]
+            .iter()
+            .map(|(id, token)| (*id, token.to_string()))
+            .collect();
+        let merges: MergeMap = [((1, 0), (0, 2))].iter().cloned().collect();
+        let mapped = mapped(&vocab_r, &merges);
+
+        assert_eq!(mapped.len(), 4);
+        assert_eq!(mapped.merges_len(), 1);
+        for (id, token) in &vocab_r {
+            assert_eq!(mapped.token_to_id(token), Some(*id));
+            assert_eq!(mapped.id_to_token(*id), Some(token.as_str()));
+        }
+        assert_eq!(mapped.token_to_id("c"), None);
+        assert_eq!(mapped.token_to_id(""), None);
+        assert_eq!(mapped.id_to_token(3), None);
+        assert_eq!(mapped.id_to_token(5), None);
+
+        assert_eq!(mapped.merge_of(&(1, 0)), Some((0, 2)));
+        assert_eq!(mapped.merge_of(&(0, 1)), None);
+        assert_eq!(mapped.ids_with_prefix(b"a"), vec![1, 2]);
+
+        assert_eq!(mapped.vocab_r(), vocab_r);
+        assert_eq!(mapped.merge_map(), merges);
+    }
+
+    #[test]
+    fn empty() {
+        let mapped = mapped(&AHashMap::new(), &MergeMap::new());
+        assert_eq!(mapped.len(), 0);
+        assert_eq!(mapped.token_to_id("a"), None);
+        assert_eq!(mapped.merge_of(&(0, 0)), None);
+    }
+
+    #[test]
+    fn truncated() {
+        let vocab_r: AHashMap<u32, String> = [(0, "a".to_string())].iter().cloned().collect();
+        let mut content = vec![];
+        MappedBpe::write(&vocab_r, &MergeMap::new(), &mut content).unwrap();
+        content.truncate(content.len() - 1);
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(&content).unwrap();
+        // SAFETY: The temporary file is not modified while mapped
+        let file = Arc::new(unsafe { MappedFile::open(file.path()) }.unwrap());
+        assert!(MappedBpe::parse(file, 0).is_err());
+    }
+}
@@ -1,8 +1,10 @@
 //! [Byte Pair Encoding](https://www.aclweb.org/anthology/P16-1162/) model.
 use std::{iter, mem};
 
+mod mapped;
 mod model;
 mod serialization;
+mod subword_nmt;
 pub mod trainer;
 mod word;
 
@@ -33,6 +35,9 @@ pub enum Error {
     /// Dropout not between 0 and 1.
     #[error("Dropout should be between 0 and 1, inclusive")]
     InvalidDropout,
+    /// When a subword-nmt codes file uses another version than 0.2
+    #[error("Codes of version {0} are not supported, only the version 0.2 is")]
+    UnsupportedCodesVersion(String),
 }
 
 /// Provides access to the `FirstLastIterator` to any Iterator
@@ -77,6 +82,7 @@ where
 }
 
 // Re-export
+pub(crate) use mapped::MappedBpe;
 pub use model::*;
 pub use trainer::*;
 use word::*;
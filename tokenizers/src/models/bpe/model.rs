@@ -1,5 +1,7 @@
-use super::{super::OrderedVocabIter, trainer::BpeTrainer, Error, Pair, Word};
-use crate::tokenizer::{Model, Result, Token};
+use super::{super::OrderedVocabIter, trainer::BpeTrainer, Error, MappedBpe, Pair, Word};
+use crate::models::files::{write_files, FileNaming, ModelFile};
+use crate::models::vocab_store::ids_with_prefix;
+use crate::tokenizer::{Model, Result, SamplingModel, Token};
 use crate::utils::cache::{Cache, DEFAULT_CACHE_CAPACITY, MAX_LENGTH};
 use crate::utils::iter::ResultShunt;
 use ahash::AHashMap;
@@ -8,9 +10,7 @@ use std::borrow::Cow;
 
 use std::collections::HashMap;
 use std::{
-    fs::File,
-    io::prelude::*,
-    io::{BufRead, BufReader},
+    io::BufRead,
     path::{Path, PathBuf},
 };
 
@@ -197,6 +197,7 @@ impl BpeBuilder {
             vocab,
             vocab_r,
             merges: merge_map,
+            mapped: None,
             cache,
             dropout: self.config.dropout,
             unk_token: self.config.unk_token,
@@ -218,6 +219,9 @@ pub struct BPE {
     pub(crate) vocab_r: VocabR,
     /// Contains the mapping between Pairs and their (rank, new_id).
     pub(crate) merges: MergeMap,
+    /// The vocab and merges read in place from a file loaded with `Tokenizer::from_file_mmap`,
+    /// used instead of the maps above, which are then empty.
+    pub(super) mapped: Option<MappedBpe>,
     /// Contains the cache for optimizing the encoding step.
     cache: Option<Cache<String, Word>>,
     /// Dropout probability for merges. 0.0 = no dropout is the default. At 1.0, tokenization will
@@ -247,8 +251,14 @@ impl std::fmt::Debug for BPE {
             .field("end_of_word_suffix", &self.end_of_word_suffix)
             .field("fuse_unk", &self.fuse_unk)
             .field("byte_fallback", &self.byte_fallback)
-            .field("vocab", &self.vocab.len())
-            .field("merges", &self.merges.len())
+            .field("vocab", &self.vocab_len())
+            .field(
+                "merges",
+                &self
+                    .mapped
+                    .as_ref()
+                    .map_or(self.merges.len(), |m| m.merges_len()),
+            )
             .field("ignore_merges", &self.ignore_merges)
             .finish()
     }
@@ -269,6 +279,7 @@ impl Clone for BPE {
             vocab: self.vocab.clone(),
             vocab_r: self.vocab_r.clone(),
             merges: self.merges.clone(),
+            mapped: self.mapped.clone(),
             cache: fresh_cache,
             dropout: self.dropout,
             unk_token: self.unk_token.clone(),
@@ -323,13 +334,12 @@ impl BPE {
 
     /// Read the given files to extract the vocab and merges
     pub fn read_file(vocab: &str, merges: &str) -> Result<(Vocab, Merges)> {
-        // Read vocab.json
-        let vocab_file = File::open(vocab)?;
-        let mut vocab_file = BufReader::new(vocab_file);
+        Self::read_bytes(&std::fs::read(vocab)?, &std::fs::read(merges)?)
+    }
 
-        let mut buffer = String::new();
-        vocab_file.read_to_string(&mut buffer)?;
-        let json: Value = serde_json::from_str(&buffer)?;
+    /// Extract the vocab and merges from the content of a `vocab.json` and a `merges.txt`
+    pub fn read_bytes(vocab: &[u8], merges: &[u8]) -> Result<(Vocab, Merges)> {
+        let json: Value = serde_json::from_slice(vocab)?;
         let mut vocab = AHashMap::new();
         match json {
             Value::Object(m) => {
@@ -343,16 +353,37 @@ impl BPE {
             _ => return Err(Box::new(Error::BadVocabulary)),
         };
 
-        // Read merges file
-        let merge_file = File::open(merges)?;
-        let merge_file = BufReader::new(merge_file);
-        let merges = ResultShunt::process(merge_file.lines(), |iter| {
+        let merges = ResultShunt::process(merges.lines(), |iter| {
             convert_merges_to_hashmap(iter, &vocab)
         })??;
 
         Ok((vocab, merges))
     }
 
+    /// The content of the `vocab.json` and `merges.txt` files written by
+    /// [`save`](Model::save)
+    pub fn files(&self) -> Result<Vec<ModelFile>> {
+        let vocab_r = self.owned_vocab_r();
+        let order_vocab_iter = OrderedVocabIter::new(&vocab_r);
+        let vocab = serde_json::to_string(&order_vocab_iter)?;
+
+        let merges = self.owned_merges();
+        let mut merges: Vec<(&Pair, &u32)> = merges
+            .iter()
+            .map(|(pair, (rank, _))| (pair, rank))
+            .collect();
+        merges.sort_unstable_by_key(|k| *k.1);
+        let mut merges_file = b"#version: 0.2\n".to_vec();
+        merges_file.extend(merges.into_iter().flat_map(|(pair, _)| {
+            format!("{} {}\n", vocab_r[&pair.0], vocab_r[&pair.1]).into_bytes()
+        }));
+
+        Ok(vec![
+            ModelFile::new("vocab.json", vocab),
+            ModelFile::new("merges.txt", merges_file),
+        ])
+    }
+
     /// Reset the cache.
     pub fn clear_cache(&self) {
         if let Some(ref cache) = self.cache {
@@ -368,7 +399,56 @@ impl BPE {
     }
 
     pub fn get_vocab(&self) -> HashMap<String, u32> {
-        self.vocab.clone().into_iter().collect()
+        match &self.mapped {
+            Some(mapped) => mapped
+                .vocab_r()
+                .into_iter()
+                .map(|(id, t)| (t, id))
+                .collect(),
+            None => self.vocab.clone().into_iter().collect(),
+        }
+    }
+
+    /// Use the given tables as vocab and merges, in place of the maps
+    pub(crate) fn set_mapped(&mut self, mapped: MappedBpe) {
+        self.vocab.clear();
+        self.vocab_r.clear();
+        self.merges.clear();
+        self.mapped = Some(mapped);
+    }
+
+    fn vocab_len(&self) -> usize {
+        self.mapped.as_ref().map_or(self.vocab.len(), |m| m.len())
+    }
+
+    fn vocab_get(&self, token: &str) -> Option<u32> {
+        match &self.mapped {
+            Some(mapped) => mapped.token_to_id(token),
+            None => self.vocab.get(token).copied(),
+        }
+    }
+
+    fn vocab_r_get(&self, id: u32) -> Option<&str> {
+        match &self.mapped {
+            Some(mapped) => mapped.id_to_token(id),
+            None => self.vocab_r.get(&id).map(String::as_str),
+        }
+    }
+
+    /// The reversed vocab, built from the tables if the model is mapped
+    pub(crate) fn owned_vocab_r(&self) -> Cow<'_, VocabR> {
+        match &self.mapped {
+            Some(mapped) => Cow::Owned(mapped.vocab_r()),
+            None => Cow::Borrowed(&self.vocab_r),
+        }
+    }
+
+    /// The merges, built from the tables if the model is mapped
+    pub(crate) fn owned_merges(&self) -> Cow<'_, MergeMap> {
+        match &self.mapped {
+            Some(mapped) => Cow::Owned(mapped.merge_map()),
+            None => Cow::Borrowed(&self.merges),
+        }
     }
 
     pub fn get_unk_token(&self) -> &Option<String> {
@@ -379,7 +459,11 @@ impl BPE {
         &self.continuing_subword_prefix
     }
 
-    fn merge_word(&self, w: &str) -> Result<Word> {
+    pub fn get_end_of_word_suffix(&self) -> &Option<String> {
+        &self.end_of_word_suffix
+    }
+
+    fn merge_word(&self, w: &str, dropout: Option<f32>) -> Result<Word> {
         let mut indices = w.char_indices().map(|(idx, _)| idx).peekable();
         let mut word = Word::with_capacity(w.len());
         let mut unk: Option<(u32, usize)> = None;
@@ -388,11 +472,11 @@ impl BPE {
             let is_first = i == 0;
             let is_last = end.is_none();
 
-            let mut s = if let Some(e) = end {
-                Cow::Borrowed(&w[i..*e])
-            } else {
-                Cow::Borrowed(&w[i..])
+            let c = match end {
+                Some(e) => &w[i..*e],
+                None => &w[i..],
             };
+            let mut s = Cow::Borrowed(c);
             let byte_len = s.len();
 
             // Add the `continuing_subword_prefix` if relevant
@@ -408,25 +492,26 @@ impl BPE {
                 }
             }
 
-            if let Some(id) = self.vocab.get(s.as_ref()) {
+            if let Some(id) = self.vocab_get(s.as_ref()) {
                 if let Some((unk_id, unk_len)) = unk {
                     word.add(unk_id, unk_len);
                     unk = None;
                 }
-                word.add(*id, byte_len);
+                word.add(id, byte_len);
             } else {
                 if self.byte_fallback {
-                    let tokens: Option<Vec<_>> = s
+                    // The bytes of the char only, without the markers
+                    let tokens: Option<Vec<_>> = c
                         .bytes()
-                        .map(|b| -> Option<&u32> {
+                        .map(|b| -> Option<u32> {
                             let code = format!("<{b:#04X}>");
 
-                            self.vocab.get(&code)
+                            self.vocab_get(&code)
                         })
                         .collect();
                     if let Some(tokens) = tokens {
                         for t in tokens {
-                            word.add(*t, 1);
+                            word.add(t, 1);
                         }
                         continue;
                     }
@@ -441,14 +526,14 @@ impl BPE {
                             // Do not fuse unk, add the previous one
                             word.add(unk_id, unk_len);
                             Some((
-                                *self.vocab.get(unk_token).ok_or_else(|| {
+                                self.vocab_get(unk_token).ok_or_else(|| {
                                     Error::UnkTokenOutOfVocabulary(unk_token.to_owned())
                                 })?,
                                 byte_len,
                             ))
                         }
                         _ => Some((
-                            *self.vocab.get(unk_token).ok_or_else(|| {
+                            self.vocab_get(unk_token).ok_or_else(|| {
                                 Error::UnkTokenOutOfVocabulary(unk_token.to_owned())
                             })?,
                             byte_len,
@@ -461,7 +546,10 @@ impl BPE {
             word.add(unk_id, unk_len);
         }
 
-        word.merge_all(&self.merges, self.dropout);
+        match &self.mapped {
+            Some(mapped) => word.merge_all(mapped, dropout),
+            None => word.merge_all(&self.merges, dropout),
+        }
 
         Ok(word)
     }
@@ -469,14 +557,17 @@ impl BPE {
     fn word_to_tokens<'a>(&'a self, word: &'a Word) -> impl Iterator<Item = Token> + 'a {
         word.get_chars_iter()
             .zip(word.get_offsets_iter())
-            .map(move |(id, offsets)| Token::new(id, self.vocab_r[&id].clone(), offsets))
+            .map(move |(id, offsets)| {
+                let value = self.vocab_r_get(id).expect("Token id out of vocabulary");
+                Token::new(id, value.to_owned(), offsets)
+            })
     }
 
     fn tokenize_with_cache(&self, sequence: &str) -> Result<Vec<Token>> {
         if self.ignore_merges {
-            if let Some(id) = self.vocab.get(sequence) {
+            if let Some(id) = self.vocab_get(sequence) {
                 return Ok(vec![Token::new(
-                    *id,
+                    id,
                     sequence.to_string(),
                     (0, sequence.len()),
                 )]);
@@ -485,7 +576,7 @@ impl BPE {
         if let Some(ref hit) = self.cache.as_ref().and_then(|c| c.get(sequence)) {
             return Ok(self.word_to_tokens(hit).collect());
         }
-        let word = self.merge_word(sequence)?;
+        let word = self.merge_word(sequence, self.dropout)?;
         let ret = self.word_to_tokens(&word).collect();
         if let Some(ref cache) = self.cache {
             if sequence.len() < MAX_LENGTH {
@@ -500,11 +591,21 @@ impl Model for BPE {
     type Trainer = BpeTrainer;
 
     fn get_vocab(&self) -> HashMap<String, u32> {
-        self.vocab.clone().into_iter().collect()
+        BPE::get_vocab(self)
     }
 
     fn get_vocab_size(&self) -> usize {
-        self.vocab.len()
+        self.vocab_len()
+    }
+
+    fn token_ids_with_prefix(&self, prefix: &[u8]) -> Vec<u32> {
+        match &self.mapped {
+            Some(mapped) => mapped.ids_with_prefix(prefix),
+            None => ids_with_prefix(
+                self.vocab.iter().map(|(token, id)| (token.as_str(), *id)),
+                prefix,
+            ),
+        }
     }
 
     fn tokenize(&self, sequence: &str) -> Result<Vec<Token>> {
@@ -515,61 +616,21 @@ impl Model for BPE {
         if self.dropout.is_none() || self.dropout == Some(0.0) {
             self.tokenize_with_cache(sequence)
         } else {
-            let word = self.merge_word(sequence)?;
+            let word = self.merge_word(sequence, self.dropout)?;
             Ok(self.word_to_tokens(&word).collect())
         }
     }
 
     fn token_to_id(&self, token: &str) -> Option<u32> {
-        self.vocab.get(token).copied()
+        self.vocab_get(token)
     }
 
     fn id_to_token(&self, id: u32) -> Option<String> {
-        self.vocab_r.get(&id).cloned()
+        self.vocab_r_get(id).map(str::to_owned)
     }
 
     fn save(&self, folder: &Path, name: Option<&str>) -> Result<Vec<PathBuf>> {
-        let vocab_file_name = match name {
-            Some(name) => format!("{name}-vocab.json"),
-            None => "vocab.json".to_string(),
-        };
-
-        // Write vocab.json
-        let vocab_path: PathBuf = [folder, Path::new(vocab_file_name.as_str())]
-            .iter()
-            .collect();
-        let mut vocab_file = File::create(&vocab_path)?;
-        let order_vocab_iter = OrderedVocabIter::new(&self.vocab_r);
-        let serialized = serde_json::to_string(&order_vocab_iter)?;
-        vocab_file.write_all(serialized.as_bytes())?;
-
-        // Write merges.txt
-        let merges_file_name = match name {
-            Some(name) => format!("{name}-merges.txt"),
-            None => "merges.txt".to_string(),
-        };
-
-        let merges_path: PathBuf = [folder, Path::new(merges_file_name.as_str())]
-            .iter()
-            .collect();
-        let mut merges_file = File::create(&merges_path)?;
-        let mut merges: Vec<(&Pair, &u32)> = self
-            .merges
-            .iter()
-            .map(|(pair, (rank, _))| (pair, rank))
-            .collect();
-        merges.sort_unstable_by_key(|k| *k.1);
-        merges_file.write_all(b"#version: 0.2\n")?;
-        merges_file.write_all(
-            &merges
-                .into_iter()
-                .flat_map(|(pair, _)| {
-                    format!("{} {}\n", self.vocab_r[&pair.0], self.vocab_r[&pair.1]).into_bytes()
-                })
-                .collect::<Vec<_>>()[..],
-        )?;
-
-        Ok(vec![vocab_path, merges_path])
+        write_files(&self.files()?, folder, &FileNaming::prefix(name))
     }
 
     fn get_trainer(&self) -> BpeTrainer {
@@ -577,9 +638,21 @@ impl Model for BPE {
     }
 }
 
+impl SamplingModel for BPE {
+    /// Tokenize with the given merges `dropout`, whatever the dropout of the model
+    fn tokenize_sampled(&self, sequence: &str, dropout: f64) -> Result<Vec<Token>> {
+        if sequence.is_empty() {
+            return Ok(vec![]);
+        }
+        let word = self.merge_word(sequence, Some(dropout as f32))?;
+        Ok(self.word_to_tokens(&word).collect())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::io::Write;
     use tempfile::NamedTempFile;
 
     #[test]
@@ -598,6 +671,36 @@ mod tests {
         assert_eq!(serialized, "{\"a\":0,\"b\":1,\"c\":2,\"ab\":3}");
     }
 
+    #[test]
+    fn test_files() {
+        let vocab: Vocab = [("a".into(), 0), ("b".into(), 1), ("ab".into(), 2)]
+            .iter()
+            .cloned()
+            .collect();
+        let bpe = BPE::new(vocab.clone(), vec![("a".into(), "b".into())]);
+        let files = bpe.files().unwrap();
+        assert_eq!(files[0].name, "vocab.json");
+        assert_eq!(files[0].content, br#"{"a":0,"b":1,"ab":2}"#);
+        assert_eq!(files[1].name, "merges.txt");
+        assert_eq!(files[1].content, b"#version: 0.2\na b\n");
+
+        let (read_vocab, merges) = BPE::read_bytes(&files[0].content, &files[1].content).unwrap();
+        assert_eq!(read_vocab, vocab);
+        assert_eq!(merges, vec![("a".to_string(), "b".to_string())]);
+    }
+
+    #[test]
+    fn test_token_ids_with_prefix() {
+        let vocab: Vocab = [("b".into(), 0), ("a".into(), 1), ("ab".into(), 2)]
+            .iter()
+            .cloned()
+            .collect();
+        let bpe = BPE::new(vocab, vec![("a".into(), "b".into())]);
+        assert_eq!(bpe.token_ids_with_prefix(b"a"), vec![1, 2]);
+        assert_eq!(bpe.token_ids_with_prefix(b""), vec![0, 1, 2]);
+        assert!(bpe.token_ids_with_prefix(b"c").is_empty());
+    }
+
     #[test]
     fn test_unk_not_fused() {
         let vocab: Vocab = [("<unk>".into(), 0), ("a".into(), 1), ("b".into(), 2)]
@@ -911,6 +1014,37 @@ mod tests {
         assert_eq!(tokens, vec![Token::new(1u32, "<0x0A>".into(), (0, 1)),]);
     }
 
+    #[test]
+    fn test_bpe_byte_fallback_markers() {
+        // The markers are not part of the bytes of the chars
+        let vocab: Vocab = [
+            ("<unk>".into(), 0),
+            ("<0x61>".into(), 1),
+            ("b".into(), 2),
+            ("<0x62>".into(), 3),
+        ]
+        .iter()
+        .cloned()
+        .collect();
+        let bpe = BpeBuilder::default()
+            .vocab_and_merges(vocab, vec![])
+            .unk_token("<unk>".to_string())
+            .continuing_subword_prefix("##".to_string())
+            .end_of_word_suffix("</w>".to_string())
+            .byte_fallback(true)
+            .build()
+            .unwrap();
+        let tokens = bpe.tokenize("bba").unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::new(2u32, "b".into(), (0, 1)),
+                Token::new(3u32, "<0x62>".into(), (1, 2)),
+                Token::new(1u32, "<0x61>".into(), (2, 3)),
+            ]
+        );
+    }
+
     #[test]
     fn test_ignore_merges() {
         // 0x0A == '\n' in bytes
@@ -0,0 +1,168 @@
+//! Loading of the `codes` files written by the `learn_bpe.py` script of
+//! [subword-nmt](https://github.com/rsennrich/subword-nmt).
+use super::{BpeBuilder, Error, Merges, Vocab, BPE};
+use crate::tokenizer::Result;
+use crate::utils::iter::ResultShunt;
+use ahash::AHashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+
+/// The suffix marking the last symbol of each word in the codes
+const END_OF_WORD: &str = "</w>";
+/// The token of the chars missing from the merges, which subword-nmt leaves as they are
+const UNK: &str = "<unk>";
+const VERSION: &str = "#version: ";
+
+/// Build the vocab and merges of the given lines of a codes file
+fn convert_codes<I: Iterator<Item = String>>(lines: I) -> Result<(Vocab, Merges)> {
+    let mut lines = lines.peekable();
+    // The files without a version are older ones, where `</w>` is a symbol of its own
+    let version = match lines.peek() {
+        Some(line) if line.starts_with(VERSION) => line[VERSION.len()..].trim().to_owned(),
+        _ => "0.1".to_owned(),
+    };
+    if version != "0.2" {
+        return Err(Error::UnsupportedCodesVersion(version).into());
+    }
+
+    let mut merges = vec![];
+    for (index, line) in lines.enumerate().skip(1) {
+        match line.split_whitespace().collect::<Vec<_>>()[..] {
+            [a, b] => {
+                merges.push((a.to_owned(), b.to_owned()));
+            }
+            _ => return Err(Error::BadMerges(index + 1).into()),
+        }
+    }
+
+    // Like the trainer does, the alphabet comes first, followed by the tokens of the merges
+    // in order. Each char can end a word, even if no merge uses it that way.
+    let mut vocab = AHashMap::new();
+    let mut add = |token: String| {
+        let id = vocab.len() as u32;
+        vocab.entry(token).or_insert(id);
+    };
+    for symbol in merges.iter().flat_map(|(a, b)| [a, b]) {
+        let symbol = symbol.strip_suffix(END_OF_WORD).unwrap_or(symbol);
+        for c in symbol.chars() {
+            add(c.to_string());
+            add(format!("{c}{END_OF_WORD}"));
+        }
+    }
+    for (a, b) in &merges {
+        add(a.clone());
+        add(b.clone());
+        add(format!("{a}{b}"));
+    }
+    add(UNK.to_owned());
+    Ok((vocab, merges))
+}
+
+impl BPE {
+    /// Read a subword-nmt `codes` file, returning the vocab and merges of the equivalent
+    /// `BPE`, which uses `</w>` as its `end_of_word_suffix`.
+    ///
+    /// The vocab is made of the chars found in the merges, each with and without the suffix,
+    /// followed by the tokens produced by the merges, and `<unk>` for the other chars. Only
+    /// the version 0.2 of the format is supported, as the older one uses a separate `</w>`
+    /// symbol.
+    pub fn read_subword_nmt(codes: &str) -> Result<(Vocab, Merges)> {
+        let codes = BufReader::new(File::open(codes)?);
+        ResultShunt::process(codes.lines(), |lines| convert_codes(lines))?
+    }
+
+    /// Initialize a `BpeBuilder` from a subword-nmt `codes` file, as read by
+    /// [`BPE::read_subword_nmt`], with `<unk>` as its `unk_token`. The `BPEDecoder` decodes
+    /// its tokens.
+    pub fn from_subword_nmt(codes: &str) -> Result<BpeBuilder> {
+        let (vocab, merges) = Self::read_subword_nmt(codes)?;
+        Ok(Self::builder()
+            .vocab_and_merges(vocab, merges)
+            .unk_token(UNK.to_owned())
+            .end_of_word_suffix(END_OF_WORD.to_owned()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokenizer::Model;
+
+    fn convert(codes: &str) -> Result<(Vocab, Merges)> {
+        convert_codes(codes.lines().map(|l| l.to_owned()))
+    }
+
+    #[test]
+    fn codes() {
+        let codes = "#version: 0.2\nl o\nlo w</w>\ne r</w>\nlo w\n";
+        let (vocab, merges) = convert(codes).unwrap();
+        assert_eq!(merges[1], ("lo".to_string(), "w</w>".to_string()));
+        assert_eq!(vocab["l"], 0);
+        assert_eq!(vocab["l</w>"], 1);
+        assert!(!vocab.contains_key("<"));
+        assert_eq!(vocab["er</w>"], vocab.len() as u32 - 3);
+        assert_eq!(vocab["low"], vocab.len() as u32 - 2);
+        assert_eq!(vocab[UNK], vocab.len() as u32 - 1);
+
+        let bpe = BPE::builder()
+            .vocab_and_merges(vocab, merges)
+            .end_of_word_suffix(END_OF_WORD.into())
+            .build()
+            .unwrap();
+        let tokens = |word| -> Vec<_> {
+            bpe.tokenize(word)
+                .unwrap()
+                .into_iter()
+                .map(|t| t.value)
+                .collect()
+        };
+        assert_eq!(tokens("low"), vec!["low</w>"]);
+        assert_eq!(tokens("lower"), vec!["low", "er</w>"]);
+        // Every char of the merges can end a word
+        assert_eq!(tokens("rol"), vec!["r", "o", "l</w>"]);
+
+        // Any whitespace separates the symbols
+        let (_, tabs) = convert("#version: 0.2\nl\to\r\nlo  w</w>\n").unwrap();
+        assert_eq!(tabs, merges_of(&[("l", "o"), ("lo", "w</w>")]));
+    }
+
+    fn merges_of(merges: &[(&str, &str)]) -> Merges {
+        merges
+            .iter()
+            .map(|(a, b)| (a.to_string(), b.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn tokenizer() {
+        let mut codes = tempfile::NamedTempFile::new().unwrap();
+        std::io::Write::write_all(&mut codes, b"#version: 0.2\nl o\ne r</w>\nlo w\n").unwrap();
+        let path = codes.path().to_str().unwrap();
+
+        let tokenizer = crate::Tokenizer::from_subword_nmt(path).unwrap();
+        let encoding = tokenizer.encode("lower  low", false).unwrap();
+        assert_eq!(encoding.get_tokens(), &["low", "er</w>", "lo", "w</w>"]);
+        // The chars missing from the merges are unknown
+        let unknown = tokenizer.encode("lowé", false).unwrap();
+        assert_eq!(unknown.get_tokens(), &["low", UNK]);
+        assert_eq!(
+            tokenizer.decode(encoding.get_ids(), false).unwrap(),
+            "lower low"
+        );
+        assert!(BPE::from_subword_nmt("missing.codes").is_err());
+    }
+
+    #[test]
+    fn versions() {
+        assert!(convert("l o\n")
+            .unwrap_err()
+            .to_string()
+            .contains("version 0.1"));
+        assert!(convert("#version: 0.3\nl o\n").is_err());
+        assert!(convert("#version: 0.2\n").unwrap().1.is_empty());
+        assert!(convert("#version: 0.2\nl o\nl\n")
+            .unwrap_err()
+            .to_string()
+            .contains("line 3"));
+    }
+}
@@ -3,19 +3,215 @@
 use super::{Pair, WithFirstLastIterator, Word, BPE};
 use crate::parallelism::*;
 use crate::tokenizer::{AddedToken, Result, Trainer};
-use crate::utils::progress::{ProgressBar, ProgressStyle};
+use crate::utils::progress::{ProgressBar, ProgressCallback, ProgressStyle, TrainingProgress};
+use crate::utils::script_weights::ScriptWeights;
 use ahash::{AHashMap, AHashSet};
 use compact_str::CompactString;
 use dary_heap::OctonaryHeap;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::collections::HashSet;
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+
+/// The indices of the words in which a pair appears, sorted and deduplicated
+type Positions = Vec<u32>;
+
+/// The pair counts are split in `1 << PAIR_SHARD_BITS` shards, updated in parallel
+const PAIR_SHARD_BITS: u32 = 6;
+const PAIR_SHARDS: usize = 1 << PAIR_SHARD_BITS;
+/// The number of changes from which the shards get updated in parallel, the smaller updates
+/// being applied in turn. On a single thread, the `bpe_pair_counts_benchmark` gives the costs
+/// of the parallel updates: about 15µs for the tasks, and a quarter of the cost of applying each
+/// change to bucket it. From these, the parallel updates should pay off from a few thousand
+/// changes with 4 threads or more, which was not measured. They never do on a single thread,
+/// where they are not used
+const PARALLEL_UPDATE_THRESHOLD: usize = 4096;
+/// The number of words handled by each task while counting the pairs
+const COUNT_CHUNK_SIZE: usize = 4096;
+/// The number of sequences processed at once by `feed` when it has a memory budget
+const FEED_CHUNK_SIZE: usize = 8192;
+/// The estimated memory used by each entry of the word counts, excluding the heap
+/// allocated for long words
+const WORD_ENTRY_SIZE: usize = std::mem::size_of::<(CompactString, u64)>() + 1;
+
+fn pair_shard(pair: &Pair) -> usize {
+    // Fibonacci hashing, the top bits of the product are well spread
+    let key = ((pair.0 as u64) << 32) | pair.1 as u64;
+    (key.wrapping_mul(0x9E37_79B9_7F4A_7C15) >> (64 - PAIR_SHARD_BITS)) as usize
+}
+
+/// The count of each pair, split in shards that can be updated in parallel. Only public to
+/// be measured by the `bpe_pair_counts_benchmark`
+#[doc(hidden)]
+pub struct PairCounts {
+    shards: Vec<AHashMap<Pair, i64>>,
+}
+
+impl Default for PairCounts {
+    fn default() -> Self {
+        Self {
+            shards: (0..PAIR_SHARDS).map(|_| Default::default()).collect(),
+        }
+    }
+}
+
+impl PairCounts {
+    pub fn get(&self, pair: &Pair) -> i64 {
+        self.shards[pair_shard(pair)]
+            .get(pair)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Add the given deltas to the pair counts, in parallel when there are enough of them
+    pub fn apply(&mut self, updates: Vec<(Pair, i64)>) {
+        if updates.len() < PARALLEL_UPDATE_THRESHOLD || current_num_threads() < 2 {
+            self.apply_in_turn(updates);
+        } else {
+            self.apply_in_parallel(updates);
+        }
+    }
+
+    /// Add the given deltas to the pair counts one after the other
+    pub fn apply_in_turn(&mut self, updates: Vec<(Pair, i64)>) {
+        for (pair, delta) in updates {
+            *self.shards[pair_shard(&pair)].entry(pair).or_default() += delta;
+        }
+    }
+
+    /// Add the given deltas to the pair counts, each shard being updated by its own task
+    pub fn apply_in_parallel(&mut self, updates: Vec<(Pair, i64)>) {
+        let mut buckets = vec![vec![]; PAIR_SHARDS];
+        for (pair, delta) in updates {
+            buckets[pair_shard(&pair)].push((pair, delta));
+        }
+        self.shards
+            .iter_mut()
+            .zip(buckets)
+            .collect::<Vec<_>>()
+            .into_maybe_par_iter()
+            .for_each(|(shard, bucket)| {
+                for (pair, delta) in bucket {
+                    *shard.entry(pair).or_default() += delta;
+                }
+            });
+    }
+}
+
+/// A run of word counts spilled to disk, removed once dropped
+struct SpilledWords {
+    path: PathBuf,
+}
+
+impl SpilledWords {
+    fn write(words: &AHashMap<CompactString, u64>) -> Result<Self> {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "tokenizers-bpe-words-{}-{}",
+            std::process::id(),
+            NEXT_ID.fetch_add(1, AtomicOrdering::Relaxed)
+        ));
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+        let spilled = Self { path };
+
+        let mut writer = BufWriter::new(file);
+        for (word, count) in words {
+            writer.write_all(&count.to_le_bytes())?;
+            writer.write_all(&(word.len() as u64).to_le_bytes())?;
+            writer.write_all(word.as_bytes())?;
+        }
+        writer.flush()?;
+        Ok(spilled)
+    }
+
+    /// Add the spilled counts to the given words
+    fn read_into(&self, words: &mut AHashMap<CompactString, u64>) -> Result<()> {
+        let mut reader = BufReader::new(File::open(&self.path)?);
+        let mut count = [0u8; 8];
+        let mut len = [0u8; 8];
+        loop {
+            match reader.read_exact(&mut count) {
+                Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(()),
+                r => r?,
+            }
+            reader.read_exact(&mut len)?;
+            let mut word = vec![0u8; u64::from_le_bytes(len) as usize];
+            reader.read_exact(&mut word)?;
+            *words.entry(CompactString::from_utf8(word)?).or_default() += u64::from_le_bytes(count);
+        }
+    }
+}
+
+impl Drop for SpilledWords {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Word counts spilled to disk whenever their estimated memory goes over the budget
+struct WordCounts {
+    max_memory: usize,
+    memory: usize,
+    words: AHashMap<CompactString, u64>,
+    spilled: Vec<SpilledWords>,
+}
+
+impl WordCounts {
+    fn new(max_memory: usize) -> Self {
+        Self {
+            max_memory,
+            memory: 0,
+            words: AHashMap::new(),
+            spilled: vec![],
+        }
+    }
+
+    fn extend(&mut self, words: AHashMap<CompactString, u64>) -> Result<()> {
+        let memory = &mut self.memory;
+        for (word, count) in words {
+            let len = word.len();
+            let entry = self.words.entry(word).or_insert_with(|| {
+                // Only the long words get allocated on the heap
+                *memory += WORD_ENTRY_SIZE;
+                if len > std::mem::size_of::<CompactString>() {
+                    *memory += len;
+                }
+                0
+            });
+            *entry += count;
+        }
+
+        if self.memory > self.max_memory {
+            self.spilled.push(SpilledWords::write(&self.words)?);
+            self.words = AHashMap::new();
+            self.memory = 0;
+        }
+        Ok(())
+    }
+
+    /// Merge back all the spilled counts
+    fn finish(mut self) -> Result<AHashMap<CompactString, u64>> {
+        for spilled in &self.spilled {
+            spilled.read_into(&mut self.words)?;
+        }
+        Ok(self.words)
+    }
+}
 
 #[derive(Debug, Eq)]
 struct Merge {
     pair: Pair,
     count: u64,
-    pos: AHashSet<usize>,
+    pos: Positions,
+    /// The tokens of the pair, to break the ties between the pairs with the same count in
+    /// lexicographic order rather than in the order of their ids
+    tokens: Option<(CompactString, CompactString)>,
 }
 impl PartialEq for Merge {
     fn eq(&self, other: &Self) -> bool {
@@ -31,6 +227,9 @@ impl Ord for Merge {
     fn cmp(&self, other: &Self) -> Ordering {
         if self.count != other.count {
             self.count.cmp(&other.count)
+        } else if let (Some(tokens), Some(other_tokens)) = (&self.tokens, &other.tokens) {
+            // Here we want ascending order
+            other_tokens.cmp(tokens)
         } else {
             // Here we want ascending order
             other.pair.cmp(&self.pair)
@@ -48,6 +247,10 @@ struct Config {
     continuing_subword_prefix: Option<String>,
     end_of_word_suffix: Option<String>,
     max_token_length: Option<usize>,
+    max_memory: Option<usize>,
+    script_weights: ScriptWeights,
+    lexicographic_ties: bool,
+    progress_callback: Option<ProgressCallback>,
 }
 
 /// A `BpeTrainerBuilder` can be used to create a `BpeTrainer` with a custom
@@ -69,6 +272,10 @@ impl Default for BpeTrainerBuilder {
                 continuing_subword_prefix: None,
                 end_of_word_suffix: None,
                 max_token_length: None,
+                max_memory: None,
+                script_weights: ScriptWeights::default(),
+                lexicographic_ties: false,
+                progress_callback: None,
             },
         }
     }
@@ -144,6 +351,37 @@ impl BpeTrainerBuilder {
         self
     }
 
+    /// Set the number of bytes the word counts may use while feeding, before getting
+    /// spilled to disk. The training itself still holds all the counts in memory
+    #[must_use]
+    pub fn max_memory(mut self, max_memory: Option<usize>) -> Self {
+        self.config.max_memory = max_memory;
+        self
+    }
+
+    /// Set the weights of the words of each script in the pair counts, balancing the
+    /// merges of the languages of a multilingual corpus. See [`ScriptWeights`]
+    #[must_use]
+    pub fn script_weights(mut self, weights: ScriptWeights) -> Self {
+        self.config.script_weights = weights;
+        self
+    }
+
+    /// Set whether to break the ties between the pairs with the same count in lexicographic
+    /// order, so that the same corpus gives the same tokenizer on any platform
+    #[must_use]
+    pub fn lexicographic_ties(mut self, lexicographic: bool) -> Self {
+        self.config.lexicographic_ties = lexicographic;
+        self
+    }
+
+    /// Set the callback reporting the progress of the training
+    #[must_use]
+    pub fn progress_callback(mut self, callback: ProgressCallback) -> Self {
+        self.config.progress_callback = Some(callback);
+        self
+    }
+
     /// Constructs the final BpeTrainer
     pub fn build(self) -> BpeTrainer {
         BpeTrainer {
@@ -156,6 +394,10 @@ impl BpeTrainerBuilder {
             continuing_subword_prefix: self.config.continuing_subword_prefix,
             end_of_word_suffix: self.config.end_of_word_suffix,
             max_token_length: self.config.max_token_length,
+            max_memory: self.config.max_memory,
+            script_weights: self.config.script_weights,
+            lexicographic_ties: self.config.lexicographic_ties,
+            progress_callback: self.config.progress_callback,
             words: AHashMap::new(),
         }
     }
@@ -199,6 +441,25 @@ pub struct BpeTrainer {
     pub end_of_word_suffix: Option<String>,
     /// An optional parameter to limit the max length of any single token
     pub max_token_length: Option<usize>,
+    /// An optional hint, in bytes, of the memory the word counts may use while feeding.
+    /// When going over it, the counts get spilled to disk and merged back at the end of
+    /// the feeding. The final counts still have to fit in memory for the training
+    pub max_memory: Option<usize>,
+    /// The weights of the words of each script in the pair counts, so that the merges of a
+    /// multilingual corpus don't all go to its highest-resource language. The weighted
+    /// counts are also the ones compared to the `min_frequency`
+    #[serde(default, skip_serializing_if = "ScriptWeights::is_empty")]
+    pub script_weights: ScriptWeights,
+    /// Whether to break the ties between the pairs with the same count in the lexicographic
+    /// order of their tokens. By default, they are broken in the order of the ids of their
+    /// tokens, which depends on the hashing of the words, and may differ between platforms
+    /// or versions. The lexicographic order also gives ids in a deterministic order to the
+    /// subwords of the initial alphabet, so the same corpus always gives the same tokenizer
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub lexicographic_ties: bool,
+    /// An optional callback reporting the progress, replacing the progress bar
+    #[serde(skip)]
+    pub progress_callback: Option<ProgressCallback>,
 
     words: AHashMap<CompactString, u64>,
 }
@@ -224,7 +485,7 @@ impl BpeTrainer {
 
     /// Setup a progress bar if asked to show progress
     fn setup_progress(&self) -> Option<ProgressBar> {
-        if self.show_progress {
+        if self.show_progress && self.progress_callback.is_none() {
             let p = ProgressBar::new(0);
             p.set_style(
                 ProgressStyle::default_bar()
@@ -255,6 +516,26 @@ impl BpeTrainer {
         }
     }
 
+    /// Report the progress to the callback, if any. Returns whether the training should go on
+    fn report_progress(
+        &self,
+        phase: &'static str,
+        iteration: usize,
+        total: usize,
+        vocab_size: usize,
+    ) -> Result<bool> {
+        match &self.progress_callback {
+            Some(callback) => callback.report(&TrainingProgress {
+                phase,
+                iteration,
+                total,
+                vocab_size,
+                loss: None,
+            }),
+            None => Ok(true),
+        }
+    }
+
     /// Add the provided special tokens to the initial vocabulary
     fn add_special_tokens(
         &self,
@@ -324,6 +605,16 @@ impl BpeTrainer {
         });
     }
 
+    /// The tokens of the given pair, when the ties get broken in lexicographic order
+    fn pair_tokens(
+        &self,
+        pair: &Pair,
+        id2w: &[CompactString],
+    ) -> Option<(CompactString, CompactString)> {
+        self.lexicographic_ties
+            .then(|| (id2w[pair.0 as usize].clone(), id2w[pair.1 as usize].clone()))
+    }
+
     /// Tokenize words and add subwords to the vocabulary when relevant
     fn tokenize_words(
         &self,
@@ -335,9 +626,14 @@ impl BpeTrainer {
         let mut words: Vec<Word> = Vec::with_capacity(wc.len());
         let mut counts: Vec<u64> = Vec::with_capacity(wc.len());
 
+        // The ids of the subwords follow the order of the words
+        let mut wc: Vec<_> = wc.iter().collect();
+        if self.lexicographic_ties {
+            wc.sort_unstable_by_key(|(word, _)| *word);
+        }
         for (word, count) in wc {
             let mut current_word = Word::new();
-            counts.push(*count);
+            counts.push(self.script_weights.weigh(word, *count));
 
             for (is_first, is_last, c) in word.chars().with_first_and_last() {
                 let mut s = c.to_string();
@@ -375,46 +671,74 @@ impl BpeTrainer {
         (words, counts)
     }
 
+    /// Count the pairs in the given words, along with the words in which each of them
+    /// appears. Both are split in shards, following `pair_shard`
     fn count_pairs(
         &self,
         words: &[Word],
         counts: &[u64],
         p: &Option<ProgressBar>,
-    ) -> (AHashMap<Pair, i32>, AHashMap<Pair, AHashSet<usize>>) {
-        words
-            .maybe_par_iter()
+    ) -> (PairCounts, Vec<AHashMap<Pair, Positions>>) {
+        // Each chunk of words gets counted separately, already split in shards
+        let chunks = words
+            .maybe_par_chunks(COUNT_CHUNK_SIZE)
             .enumerate()
-            .map(|(i, word)| {
-                let mut pair_counts = AHashMap::new();
-                let mut where_to_update: AHashMap<Pair, AHashSet<usize>> = AHashMap::new();
-
-                for window in word.get_chars().windows(2) {
-                    let cur_pair: Pair = (window[0], window[1]);
-
-                    // Initialize pair_counts and where_to_update for this pair if we just saw it
-                    // Then update counts
-                    *pair_counts.entry(cur_pair).or_default() += counts[i] as i32;
-                    where_to_update.entry(cur_pair).or_default().insert(i);
+            .map(|(c, chunk)| {
+                let mut shards: Vec<(AHashMap<Pair, i64>, AHashMap<Pair, Positions>)> =
+                    (0..PAIR_SHARDS).map(|_| Default::default()).collect();
+
+                for (j, word) in chunk.iter().enumerate() {
+                    let i = c * COUNT_CHUNK_SIZE + j;
+                    for window in word.get_chars().windows(2) {
+                        let cur_pair: Pair = (window[0], window[1]);
+                        let (pair_counts, where_to_update) = &mut shards[pair_shard(&cur_pair)];
+
+                        *pair_counts.entry(cur_pair).or_default() += counts[i] as i64;
+                        let pos = where_to_update.entry(cur_pair).or_default();
+                        if pos.last() != Some(&(i as u32)) {
+                            pos.push(i as u32);
+                        }
+                    }
                 }
 
                 if let Some(p) = &p {
-                    p.inc(1);
+                    p.inc(chunk.len() as u64);
                 }
 
-                (pair_counts, where_to_update)
+                shards
             })
-            .reduce(
-                || (AHashMap::new(), AHashMap::new()),
-                |(mut pair_counts, mut where_to_update), (pc, wtu)| {
+            .collect::<Vec<_>>();
+
+        // Then each shard gets merged in parallel. The chunks are merged in order, which
+        // keeps the positions sorted
+        let mut parts: Vec<Vec<_>> = (0..PAIR_SHARDS)
+            .map(|_| Vec::with_capacity(chunks.len()))
+            .collect();
+        for chunk in chunks {
+            for (shard, part) in parts.iter_mut().zip(chunk) {
+                shard.push(part);
+            }
+        }
+        let (shards, positions) = parts
+            .into_maybe_par_iter()
+            .map(|parts| {
+                let mut parts = parts.into_iter();
+                let (mut pair_counts, mut where_to_update) = parts.next().unwrap_or_default();
+                for (pc, wtu) in parts {
                     for (k, v) in pc {
                         *pair_counts.entry(k).or_default() += v;
                     }
                     for (k, v) in wtu {
                         where_to_update.entry(k).or_default().extend(v);
                     }
-                    (pair_counts, where_to_update)
-                },
-            )
+                }
+                (pair_counts, where_to_update)
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .unzip();
+
+        (PairCounts { shards }, positions)
     }
 
     pub fn do_train(
@@ -444,35 +768,49 @@ impl BpeTrainer {
         self.update_progress(&progress, word_counts.len(), "Tokenize words");
         let (mut words, counts) =
             self.tokenize_words(word_counts, &mut word_to_id, &mut id_to_word, &progress);
+        if words.len() > u32::MAX as usize {
+            return Err(format!(
+                "Cannot train on more than {} distinct words, got {}",
+                u32::MAX,
+                words.len()
+            )
+            .into());
+        }
         self.finalize_progress(&progress, words.len());
+        let mut keep_going =
+            self.report_progress("Tokenize words", words.len(), words.len(), word_to_id.len())?;
 
         //
         // 4. Count pairs in words
         //
         self.update_progress(&progress, words.len(), "Count pairs");
-        let (mut pair_counts, mut where_to_update) = self.count_pairs(&words, &counts, &progress);
+        let (mut pair_counts, positions) = self.count_pairs(&words, &counts, &progress);
         // Insert them in the queue
-        let mut queue = OctonaryHeap::with_capacity(pair_counts.len());
-        where_to_update.drain().for_each(|(pair, pos)| {
-            let count = pair_counts[&pair];
+        let mut queue = OctonaryHeap::with_capacity(positions.iter().map(|p| p.len()).sum());
+        positions.into_iter().flatten().for_each(|(pair, pos)| {
+            let count = pair_counts.get(&pair);
             if count > 0 {
                 queue.push(Merge {
                     pair,
                     count: count as u64,
                     pos,
+                    tokens: self.pair_tokens(&pair, &id_to_word),
                 });
             }
         });
         self.finalize_progress(&progress, words.len());
+        keep_going = keep_going
+            && self.report_progress("Count pairs", words.len(), words.len(), word_to_id.len())?;
 
         //
         // 5. Do merges
         //
         self.update_progress(&progress, self.vocab_size, "Compute merges");
         let mut merges: Vec<(Pair, u32)> = vec![];
+        let expected_merges = self.vocab_size.saturating_sub(word_to_id.len());
         loop {
-            // Stop as soon as we have a big enough vocabulary
-            if word_to_id.len() >= self.vocab_size {
+            // Stop as soon as we have a big enough vocabulary, or when asked to
+            if !keep_going || word_to_id.len() >= self.vocab_size {
                 break;
             }
 
@@ -480,8 +818,9 @@ impl BpeTrainer {
                 break;
             };
 
-            if top.count != pair_counts[&top.pair] as u64 {
-                top.count = pair_counts[&top.pair] as u64;
+            let count = pair_counts.get(&top.pair) as u64;
+            if top.count != count {
+                top.count = count;
                 queue.push(top);
                 continue;
             }
@@ -518,7 +857,8 @@ impl BpeTrainer {
             // Merge the new pair in every words
             // Safety: This is just a type assertion, the code below may no longer be safe
             // if the type of `pos` changes
-            let pos: &AHashSet<usize> = &top.pos;
+            let pos: &Positions = &top.pos;
+            debug_assert!(pos.windows(2).all(|w| w[0] < w[1]));
 
             let words_len = words.len();
             struct WordPtr(*mut Word);
@@ -530,8 +870,9 @@ impl BpeTrainer {
             let changes = pos
                 .maybe_par_iter()
                 .flat_map(|&i| {
+                    let i = i as usize;
                     // We can merge each of these words in parallel here because each position
-                    // can be there only once (AHashSet). So this is safe.
+                    // can be there only once (sorted and deduplicated). So this is safe.
                     unsafe {
                         assert!(i < words_len);
                         // This is words[i], but avoids needing to go through &T (which triggers UB)
@@ -546,21 +887,28 @@ impl BpeTrainer {
                 })
                 .collect::<Vec<_>>();
 
-            // Introduce new formed pairs
+            // Introduce new formed pairs. The changes follow the order of the positions, so
+            // the new positions stay sorted, and only the touched pairs get pushed again
+            let mut updates = Vec::with_capacity(changes.len());
+            let mut where_to_update: AHashMap<Pair, Positions> = AHashMap::new();
             for ((pair, change), iw) in changes {
-                let count = change * counts[iw] as i32;
-                *pair_counts.entry(pair).or_default() += count;
+                updates.push((pair, change as i64 * counts[iw] as i64));
                 if change > 0 {
-                    where_to_update.entry(pair).or_default().insert(iw);
+                    let pos = where_to_update.entry(pair).or_default();
+                    if pos.last() != Some(&(iw as u32)) {
+                        pos.push(iw as u32);
+                    }
                 }
             }
-            where_to_update.drain().for_each(|(pair, pos)| {
-                let count = pair_counts[&pair];
+            pair_counts.apply(updates);
+            where_to_update.into_iter().for_each(|(pair, pos)| {
+                let count = pair_counts.get(&pair);
                 if count > 0 {
                     queue.push(Merge {
                         pair,
                         count: count as u64,
                         pos,
+                        tokens: self.pair_tokens(&pair, &id_to_word),
                     });
                 }
             });
@@ -568,6 +916,12 @@ impl BpeTrainer {
             if let Some(p) = &progress {
                 p.inc(1);
             }
+            keep_going = self.report_progress(
+                "Compute merges",
+                merges.len(),
+                expected_merges,
+                word_to_id.len(),
+            )?;
         }
         self.finalize_progress(&progress, merges.len());
 
@@ -588,6 +942,7 @@ impl BpeTrainer {
             .enumerate()
             .map(|(i, (pair, new_token_id))| (pair, (i as u32, new_token_id)))
             .collect();
+        model.mapped = None;
 
         model.continuing_subword_prefix = self.continuing_subword_prefix.clone();
         model.end_of_word_suffix = self.end_of_word_suffix.clone();
@@ -615,37 +970,65 @@ impl Trainer for BpeTrainer {
         S: AsRef<str> + Send,
         F: Fn(&str) -> Result<Vec<String>> + Sync,
     {
-        let words: Result<AHashMap<CompactString, u64>> = iterator
-            .maybe_par_bridge()
-            .map(|sequence| {
-                let words = process(sequence.as_ref())?;
-                let mut map = AHashMap::new();
-                for word in words {
-                    *map.entry(CompactString::from(word)).or_default() += 1;
-                }
-                Ok(map)
-            })
-            .reduce(
-                || Ok(AHashMap::new()),
-                |acc, ws| {
-                    let mut acc = acc?;
-                    for (k, v) in ws? {
-                        *acc.entry(k).or_default() += v;
-                    }
-                    Ok(acc)
-                },
-            );
+        let count = |sequence: S| -> Result<AHashMap<CompactString, u64>> {
+            let words = process(sequence.as_ref())?;
+            let mut map = AHashMap::new();
+            for word in words {
+                *map.entry(CompactString::from(word)).or_default() += 1;
+            }
+            Ok(map)
+        };
+        let merge = |acc: Result<AHashMap<CompactString, u64>>,
+                     ws: Result<AHashMap<CompactString, u64>>| {
+            let mut acc = acc?;
+            for (k, v) in ws? {
+                *acc.entry(k).or_default() += v;
+            }
+            Ok(acc)
+        };
+
+        let Some(max_memory) = self.max_memory else {
+            let words = iterator
+                .maybe_par_bridge()
+                .map(count)
+                .reduce(|| Ok(AHashMap::new()), merge);
+            self.words = words?;
+            return Ok(());
+        };
+
+        // With a memory budget, the sequences get processed by chunks so that the word
+        // counts can be spilled in between
+        let mut iterator = iterator;
+        let mut words = WordCounts::new(max_memory);
+        loop {
+            let chunk = iterator.by_ref().take(FEED_CHUNK_SIZE).collect::<Vec<_>>();
+            if chunk.is_empty() {
+                break;
+            }
+            words.extend(
+                chunk
+                    .into_maybe_par_iter()
+                    .map(count)
+                    .reduce(|| Ok(AHashMap::new()), merge)?,
+            )?;
+        }
 
-        self.words = words?;
+        self.words = words.finish()?;
         Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{BpeTrainer, Pair, BPE};
+    use super::{
+        BpeTrainer, Pair, PairCounts, Word, BPE, COUNT_CHUNK_SIZE, PARALLEL_UPDATE_THRESHOLD,
+    };
+    use crate::tokenizer::Trainer;
+    use crate::utils::progress::ProgressCallback;
+    use crate::utils::script_weights::ScriptWeights;
     use ahash::AHashMap;
     use compact_str::CompactString;
+    use std::sync::{Arc, Mutex};
 
     #[test]
     fn test_train() {
@@ -720,6 +1103,52 @@ mod tests {
         .collect();
         assert_eq!(model.merges, expected_merges);
     }
+
+    #[test]
+    fn test_train_progress_callback() {
+        let word_counts: AHashMap<CompactString, u64> =
+            [("roses".into(), 1), ("are".into(), 2), ("red".into(), 1)]
+                .iter()
+                .cloned()
+                .collect();
+
+        let reports = Arc::new(Mutex::new(vec![]));
+        let reports_ = reports.clone();
+        let trainer = BpeTrainer::builder()
+            .show_progress(false)
+            .vocab_size(100)
+            .progress_callback(ProgressCallback::new(move |p| {
+                reports_.lock().unwrap().push(p.clone());
+                p.phase != "Compute merges" || p.iteration < 2
+            }))
+            .build();
+        let mut model = BPE::default();
+        trainer.do_train(&word_counts, &mut model).unwrap();
+
+        let reports = reports.lock().unwrap();
+        let phases = reports.iter().map(|p| p.phase).collect::<Vec<_>>();
+        assert_eq!(
+            phases,
+            vec![
+                "Tokenize words",
+                "Count pairs",
+                "Compute merges",
+                "Compute merges"
+            ]
+        );
+        // The alphabet has 6 chars, and we stopped after 2 merges
+        assert_eq!(reports[3].vocab_size, 8);
+        assert_eq!(reports[3].total, 94);
+        assert_eq!(model.merges.len(), 2);
+
+        // The errors of a fallible callback fail the training
+        let trainer = BpeTrainer::builder()
+            .show_progress(false)
+            .progress_callback(ProgressCallback::fallible(|_| Err("interrupted".into())))
+            .build();
+        let err = trainer.do_train(&word_counts, &mut model).unwrap_err();
+        assert_eq!(err.to_string(), "interrupted");
+    }
     #[test]
     fn bpe_test_max_token_length_16() {
         /* bpe_test_max_token_length series of tests test the max_token_length flag of bpetrainer
@@ -831,4 +1260,214 @@ mod tests {
         .collect();
         assert_eq!(trained_vocab, expected_vocab)
     }
+
+    #[test]
+    fn test_count_pairs_sharded() {
+        let n = COUNT_CHUNK_SIZE * 2 + 7;
+        let words = (0..n)
+            .map(|i| {
+                let mut word = Word::new();
+                for c in [i % 5, i % 3, i % 5, i % 3].iter() {
+                    word.add(*c as u32, 1);
+                }
+                word
+            })
+            .collect::<Vec<_>>();
+        let counts = (0..n).map(|i| (i % 4) as u64 + 1).collect::<Vec<_>>();
+
+        let mut expected_counts: AHashMap<Pair, i64> = AHashMap::new();
+        let mut expected_positions: AHashMap<Pair, Vec<u32>> = AHashMap::new();
+        for (i, word) in words.iter().enumerate() {
+            for w in word.get_chars().windows(2) {
+                *expected_counts.entry((w[0], w[1])).or_default() += counts[i] as i64;
+                let pos = expected_positions.entry((w[0], w[1])).or_default();
+                if !pos.contains(&(i as u32)) {
+                    pos.push(i as u32);
+                }
+            }
+        }
+
+        let trainer = BpeTrainer::builder().show_progress(false).build();
+        let (pair_counts, positions) = trainer.count_pairs(&words, &counts, &None);
+        let positions = positions.into_iter().flatten().collect::<AHashMap<_, _>>();
+        assert_eq!(positions, expected_positions);
+        for (pair, count) in expected_counts {
+            assert_eq!(pair_counts.get(&pair), count);
+        }
+    }
+
+    #[test]
+    fn test_train_many_words() {
+        // Enough words for the pair counts to be updated in parallel
+        let word_counts: AHashMap<CompactString, u64> = (0..PARALLEL_UPDATE_THRESHOLD * 2)
+            .map(|i| (format!("ab{i}").into(), 1))
+            .collect();
+        let trainer = BpeTrainer::builder()
+            .show_progress(false)
+            .vocab_size(13)
+            .build();
+        let mut model = BPE::default();
+        trainer.do_train(&word_counts, &mut model).unwrap();
+        // The 10 digits, 'a' and 'b', then 'ab'
+        assert_eq!(model.vocab.len(), 13);
+        assert_eq!(model.vocab["ab"], 12);
+    }
+
+    #[test]
+    fn test_pair_counts_in_parallel() {
+        // Every shard gets some updates, some of them on the same pairs
+        let updates = (0..PARALLEL_UPDATE_THRESHOLD as u32)
+            .map(|i| ((i % 1000, i % 7), i64::from(i % 5) - 2))
+            .collect::<Vec<_>>();
+        let mut in_turn = PairCounts::default();
+        in_turn.apply_in_turn(updates.clone());
+        let mut in_parallel = PairCounts::default();
+        in_parallel.apply_in_parallel(updates);
+        assert_eq!(in_parallel.shards, in_turn.shards);
+    }
+
+    #[test]
+    fn test_train_large_counts() {
+        // The counts do not fit in 32 bits
+        let word_counts: AHashMap<CompactString, u64> =
+            [("ab".into(), 3_000_000_000), ("bc".into(), 2_000_000_000)]
+                .iter()
+                .cloned()
+                .collect();
+        let trainer = BpeTrainer::builder()
+            .show_progress(false)
+            .min_frequency(2_500_000_000)
+            .build();
+        let mut model = BPE::default();
+        trainer.do_train(&word_counts, &mut model).unwrap();
+        let merges = model.merges.keys().copied().collect::<Vec<_>>();
+        assert_eq!(merges, vec![(model.vocab["a"], model.vocab["b"])]);
+    }
+
+    #[test]
+    fn test_train_script_weights() {
+        let word_counts: AHashMap<CompactString, u64> = [("abab".into(), 10), ("вгвг".into(), 6)]
+            .iter()
+            .cloned()
+            .collect();
+        let first_merge = |weights: ScriptWeights| {
+            let trainer = BpeTrainer::builder()
+                .show_progress(false)
+                .vocab_size(5)
+                .script_weights(weights)
+                .build();
+            let mut model = BPE::default();
+            trainer.do_train(&word_counts, &mut model).unwrap();
+            model.vocab_r[&4].clone()
+        };
+        // The English merges win, until the Latin words weigh half as much
+        assert_eq!(first_merge(ScriptWeights::default()), "ab");
+        let weights = ScriptWeights::new([("Latin", 0.5)]).unwrap();
+        assert_eq!(first_merge(weights.clone()), "вг");
+        assert_eq!(
+            first_merge(ScriptWeights::new([("Cyrillic", 2.0)]).unwrap()),
+            "вг"
+        );
+
+        let trainer = BpeTrainer::builder().script_weights(weights).build();
+        let json = serde_json::to_string(&trainer).unwrap();
+        assert!(json.contains(r#""script_weights":{"Latin":0.5}"#));
+        assert_eq!(serde_json::from_str::<BpeTrainer>(&json).unwrap(), trainer);
+        let json = serde_json::to_string(&BpeTrainer::default()).unwrap();
+        assert!(!json.contains("script_weights"));
+    }
+
+    #[test]
+    fn test_train_script_weights_byte_level() {
+        use crate::pre_tokenizers::byte_level::ByteLevel;
+        use crate::{Model, Tokenizer};
+
+        // With a ByteLevel pre-tokenizer, the Cyrillic words are made of byte-level chars
+        // like `Ð`, that would look like Latin ones
+        let first_merge = |weights: ScriptWeights| {
+            let mut tokenizer = Tokenizer::new(BPE::default());
+            tokenizer.with_pre_tokenizer(Some(ByteLevel::default().add_prefix_space(false)));
+            let mut trainer: crate::models::TrainerWrapper = BpeTrainer::builder()
+                .show_progress(false)
+                .vocab_size(6)
+                .script_weights(weights)
+                .build()
+                .into();
+            let sequences = vec!["abab"; 10].into_iter().chain(vec!["вгвг"; 6]);
+            tokenizer.train(&mut trainer, sequences).unwrap();
+            tokenizer.get_model().id_to_token(5).unwrap()
+        };
+        assert_eq!(first_merge(ScriptWeights::default()), "ab");
+        assert_ne!(
+            first_merge(ScriptWeights::new([("Latin", 0.5)]).unwrap()),
+            "ab"
+        );
+    }
+
+    #[test]
+    fn test_train_lexicographic_ties() {
+        // All the pairs have the same count
+        let word_counts: AHashMap<CompactString, u64> =
+            [("dc".into(), 1), ("ba".into(), 1), ("bd".into(), 1)]
+                .iter()
+                .cloned()
+                .collect();
+        let train = |lexicographic| {
+            let trainer = BpeTrainer::builder()
+                .show_progress(false)
+                .continuing_subword_prefix("##".into())
+                .lexicographic_ties(lexicographic)
+                .build();
+            let mut model = BPE::default();
+            trainer.do_train(&word_counts, &mut model).unwrap();
+            model
+        };
+        let model = train(true);
+        let mut merges = model.merges.iter().collect::<Vec<_>>();
+        merges.sort_by_key(|(_, (rank, _))| *rank);
+        let merges = merges
+            .into_iter()
+            .map(|(pair, _)| (&model.vocab_r[&pair.0], &model.vocab_r[&pair.1]))
+            .map(|(a, b)| format!("{a} {b}"))
+            .collect::<Vec<_>>();
+        assert_eq!(merges, ["b ##a", "b ##d", "d ##c"]);
+        // The subwords get their ids in the order of the words
+        assert_eq!(model.vocab["##a"], 4);
+        assert_eq!(model.vocab["##d"], 5);
+        assert_eq!(model.vocab["##c"], 6);
+        assert_eq!(train(false).vocab.len(), model.vocab.len());
+
+        let trainer = BpeTrainer::builder().lexicographic_ties(true).build();
+        let json = serde_json::to_string(&trainer).unwrap();
+        assert!(json.contains(r#""lexicographic_ties":true"#));
+        assert_eq!(serde_json::from_str::<BpeTrainer>(&json).unwrap(), trainer);
+        let json = serde_json::to_string(&BpeTrainer::default()).unwrap();
+        assert!(!json.contains("lexicographic_ties"));
+    }
+
+    #[test]
+    fn test_feed_max_memory() {
+        let sequences = (0..20_000)
+            .map(|i| format!("w{} w{} common", i % 3000, i % 11))
+            .collect::<Vec<_>>();
+        let process = |s: &str| Ok(s.split(' ').map(|w| w.to_owned()).collect());
+
+        let mut trainer = BpeTrainer::builder().show_progress(false).build();
+        trainer.feed(sequences.iter(), process).unwrap();
+        // Spill as soon as possible
+        let mut spilling = BpeTrainer::builder()
+            .show_progress(false)
+            .max_memory(Some(1))
+            .build();
+        spilling.feed(sequences.iter(), process).unwrap();
+
+        assert_eq!(spilling.words, trainer.words);
+        assert_eq!(spilling.words["common"], 20_000);
+
+        let mut model = BPE::default();
+        let mut spilled_model = BPE::default();
+        trainer.train(&mut model).unwrap();
+        spilling.train(&mut spilled_model).unwrap();
+        assert_eq!(spilled_model.vocab.len(), model.vocab.len());
+    }
 }
@@ -4,6 +4,18 @@ use dary_heap::QuaternaryHeap;
 use rand::{rng, Rng};
 use std::cmp::Ordering;
 
+/// The merges a `Word` can be merged with
+pub(super) trait MergeRanks {
+    /// The `(rank, new_id)` of the merge of the given pair, if any
+    fn merge_of(&self, pair: &Pair) -> Option<(u32, u32)>;
+}
+
+impl MergeRanks for AHashMap<Pair, (u32, u32)> {
+    fn merge_of(&self, pair: &Pair) -> Option<(u32, u32)> {
+        self.get(pair).copied()
+    }
+}
+
 #[derive(Debug, Eq)]
 struct Merge {
     pos: usize,
@@ -159,7 +171,7 @@ impl Word {
         changes
     }
 
-    pub(super) fn merge_all(&mut self, merges: &AHashMap<Pair, (u32, u32)>, dropout: Option<f32>) {
+    pub(super) fn merge_all<M: MergeRanks>(&mut self, merges: &M, dropout: Option<f32>) {
         let mut queue = QuaternaryHeap::with_capacity(self.symbols.len());
         let mut skip = Vec::with_capacity(queue.len());
 
@@ -169,7 +181,7 @@ impl Word {
                 .enumerate()
                 .filter_map(|(index, window)| {
                     let pair = (window[0].c, window[1].c);
-                    merges.get(&pair).map(|m| Merge {
+                    merges.merge_of(&pair).map(|m| Merge {
                         pos: index,
                         rank: m.0,
                         new_id: m.1,
@@ -198,8 +210,8 @@ impl Word {
                 // Make sure we are not processing an expired queue entry
                 let target_new_pair = (self.symbols[top.pos].c, right.c);
                 if merges
-                    .get(&target_new_pair)
-                    .is_none_or(|(_, new_id)| *new_id != top.new_id)
+                    .merge_of(&target_new_pair)
+                    .is_none_or(|(_, new_id)| new_id != top.new_id)
                 {
                     continue;
                 }
@@ -220,11 +232,11 @@ impl Word {
                     let prev = current.prev as usize;
                     let prev_symbol = self.symbols[prev];
                     let new_pair = (prev_symbol.c, current.c);
-                    if let Some((rank, new_id)) = merges.get(&new_pair) {
+                    if let Some((rank, new_id)) = merges.merge_of(&new_pair) {
                         queue.push(Merge {
                             pos: current.prev as usize,
-                            rank: *rank,
-                            new_id: *new_id,
+                            rank,
+                            new_id,
                         });
                     }
                 }
@@ -234,11 +246,11 @@ impl Word {
                 if next < self.symbols.len() {
                     let next_symbol = self.symbols[next];
                     let new_pair = (current.c, next_symbol.c);
-                    if let Some((rank, new_id)) = merges.get(&new_pair) {
+                    if let Some((rank, new_id)) = merges.merge_of(&new_pair) {
                         queue.push(Merge {
                             pos: top.pos,
-                            rank: *rank,
-                            new_id: *new_id,
+                            rank,
+                            new_id,
                         });
                     }
                 }
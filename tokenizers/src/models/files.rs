@@ -0,0 +1,150 @@
+//! The legacy files of the models, like the `vocab.json` and `merges.txt` of `BPE`, kept in
+//! memory so that they can be embedded elsewhere, or written under other names.
+use crate::tokenizer::Result;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// A legacy file of a model, with its content
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ModelFile {
+    /// The name given to the file by [`Model::save`](crate::Model::save) without prefix,
+    /// like `vocab.json`
+    pub name: String,
+    pub content: Vec<u8>,
+}
+
+impl ModelFile {
+    pub(crate) fn new(name: &str, content: impl Into<Vec<u8>>) -> Self {
+        Self {
+            name: name.to_owned(),
+            content: content.into(),
+        }
+    }
+}
+
+/// The names of the files written by [`write_files`]: `{prefix}-{stem}-{suffix}.{extension}`,
+/// like `bert-vocab-uncased.txt` for the `vocab.txt` of a `WordPiece`, unless the file was
+/// renamed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FileNaming {
+    pub prefix: Option<String>,
+    pub suffix: Option<String>,
+    /// The paths replacing the default names, like `merges.txt`. The relative paths are
+    /// relative to the folder given to [`write_files`].
+    pub renamed: HashMap<String, PathBuf>,
+}
+
+impl FileNaming {
+    /// The naming used by [`Model::save`](crate::Model::save)
+    pub fn prefix(prefix: Option<&str>) -> Self {
+        Self {
+            prefix: prefix.map(str::to_owned),
+            ..Default::default()
+        }
+    }
+
+    /// Give the path of the file with the given default name
+    pub fn rename(mut self, name: impl Into<String>, path: impl Into<PathBuf>) -> Self {
+        self.renamed.insert(name.into(), path.into());
+        self
+    }
+
+    /// The path of the file with the given default name, relative to the folder
+    pub fn path(&self, name: &str) -> PathBuf {
+        if let Some(path) = self.renamed.get(name) {
+            return path.clone();
+        }
+        let (stem, extension) = match name.rsplit_once('.') {
+            Some((stem, extension)) => (stem, Some(extension)),
+            None => (name, None),
+        };
+        let mut path = String::new();
+        if let Some(prefix) = &self.prefix {
+            path.push_str(prefix);
+            path.push('-');
+        }
+        path.push_str(stem);
+        if let Some(suffix) = &self.suffix {
+            path.push('-');
+            path.push_str(suffix);
+        }
+        if let Some(extension) = extension {
+            path.push('.');
+            path.push_str(extension);
+        }
+        path.into()
+    }
+}
+
+/// Write the given files in the folder, named according to `naming`, returning their paths.
+/// Any file with the same name that already exists will be overwritten.
+pub fn write_files(
+    files: &[ModelFile],
+    folder: &Path,
+    naming: &FileNaming,
+) -> Result<Vec<PathBuf>> {
+    files
+        .iter()
+        .map(|file| {
+            let path = folder.join(naming.path(&file.name));
+            std::fs::write(&path, &file.content)?;
+            Ok(path)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn naming() {
+        assert_eq!(
+            FileNaming::default().path("vocab.json"),
+            Path::new("vocab.json")
+        );
+        assert_eq!(
+            FileNaming::prefix(Some("bert")).path("vocab.txt"),
+            Path::new("bert-vocab.txt")
+        );
+        let naming = FileNaming {
+            prefix: Some("bert".into()),
+            suffix: Some("uncased".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            naming.path("vocab.txt"),
+            Path::new("bert-vocab-uncased.txt")
+        );
+        assert_eq!(naming.path("unigram"), Path::new("bert-unigram-uncased"));
+
+        let naming = naming.rename("merges.txt", "/models/merges-v2.txt");
+        assert_eq!(
+            naming.path("merges.txt"),
+            Path::new("/models/merges-v2.txt")
+        );
+        assert_eq!(
+            Path::new("/tmp").join(naming.path("merges.txt")),
+            Path::new("/models/merges-v2.txt")
+        );
+    }
+
+    #[test]
+    fn write() {
+        let folder = tempfile::tempdir().unwrap();
+        let files = vec![
+            ModelFile::new("vocab.json", "{}"),
+            ModelFile::new("merges.txt", "#version: 0.2\n"),
+        ];
+        let naming = FileNaming::prefix(Some("model")).rename("merges.txt", "sub.txt");
+        let paths = write_files(&files, folder.path(), &naming).unwrap();
+        assert_eq!(
+            paths,
+            vec![
+                folder.path().join("model-vocab.json"),
+                folder.path().join("sub.txt")
+            ]
+        );
+        assert_eq!(std::fs::read(&paths[1]).unwrap(), b"#version: 0.2\n");
+    }
+}
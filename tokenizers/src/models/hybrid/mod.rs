@@ -0,0 +1,307 @@
+use super::files::{write_files, FileNaming, ModelFile};
+use super::wordlevel::WordLevel;
+use super::{ModelWrapper, TrainerWrapper};
+use crate::tokenizer::{Model, Result, Token};
+use ahash::AHashMap;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+mod serialization;
+
+type Vocab = AHashMap<String, u32>;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(
+        "Hybrid error: the id {id} of `{token}` in the lexicon is `{other}` in the fallback model"
+    )]
+    IdConflict {
+        token: String,
+        id: u32,
+        other: String,
+    },
+    #[error(
+        "Hybrid error: `{token}` has the id {id} in the lexicon but {other} in the fallback model"
+    )]
+    TokenConflict { token: String, id: u32, other: u32 },
+}
+
+/// A model looking each word up in a lexicon before falling back to another model.
+///
+/// The words found as a whole in the `lexicon` become a single token, like with a
+/// `WordLevel`, while all the others get tokenized by the `fallback` model, usually a subword
+/// one. This keeps the exact matches for some known words, like product references or names,
+/// with subwords for everything else. The ids of the lexicon must not be used by the fallback
+/// model for other tokens.
+///
+/// The `Hybrid` can't be trained, training it fails with an error, but its fallback model can
+/// be trained beforehand.
+#[derive(PartialEq, Clone)]
+pub struct Hybrid {
+    lexicon: Vocab,
+    lexicon_r: AHashMap<u32, String>,
+    fallback: Box<ModelWrapper>,
+}
+
+impl std::fmt::Debug for Hybrid {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.debug_struct("Hybrid")
+            .field("lexicon", &self.lexicon.len())
+            .field("fallback", &self.fallback)
+            .finish()
+    }
+}
+
+impl Hybrid {
+    /// Look the words up in `lexicon` before tokenizing them with `fallback`. Each token of
+    /// the lexicon must either be unknown to the fallback model, with an id it doesn't use,
+    /// or have the same id in both.
+    pub fn new(lexicon: Vocab, fallback: ModelWrapper) -> Result<Self> {
+        for (token, id) in &lexicon {
+            if let Some(other) = fallback.id_to_token(*id).filter(|other| other != token) {
+                return Err(Error::IdConflict {
+                    token: token.clone(),
+                    id: *id,
+                    other,
+                }
+                .into());
+            }
+            if let Some(other) = fallback.token_to_id(token).filter(|other| other != id) {
+                return Err(Error::TokenConflict {
+                    token: token.clone(),
+                    id: *id,
+                    other,
+                }
+                .into());
+            }
+        }
+        let lexicon_r = lexicon
+            .iter()
+            .map(|(token, id)| (*id, token.clone()))
+            .collect();
+        Ok(Self {
+            lexicon,
+            lexicon_r,
+            fallback: Box::new(fallback),
+        })
+    }
+
+    /// The words kept as a whole, with their ids
+    pub fn lexicon(&self) -> &Vocab {
+        &self.lexicon
+    }
+
+    /// The model tokenizing the words missing from the lexicon
+    pub fn fallback(&self) -> &ModelWrapper {
+        &self.fallback
+    }
+
+    pub(crate) fn fallback_mut(&mut self) -> &mut ModelWrapper {
+        &mut self.fallback
+    }
+
+    /// Read a `lexicon.json` file, in the same format as the `vocab.json` of `WordLevel`
+    pub fn read_file(lexicon_path: &str) -> Result<Vocab> {
+        WordLevel::read_file(lexicon_path)
+    }
+
+    /// Extract the lexicon from the content of a `lexicon.json`, like [`WordLevel::read_bytes`]
+    pub fn read_bytes(lexicon_json: &[u8]) -> Result<Vocab> {
+        WordLevel::read_bytes(lexicon_json)
+    }
+
+    /// The files of the fallback model, with the `lexicon.json` written by
+    /// [`save`](Model::save)
+    pub fn files(&self) -> Result<Vec<ModelFile>> {
+        let mut files = self.fallback.files()?;
+        let serialized = serde_json::to_string(&serialization::OrderedLexicon::new(&self.lexicon))?;
+        files.push(ModelFile::new("lexicon.json", serialized));
+        Ok(files)
+    }
+}
+
+impl Model for Hybrid {
+    type Trainer = TrainerWrapper;
+
+    fn tokenize(&self, sequence: &str) -> Result<Vec<Token>> {
+        match self.lexicon.get(sequence) {
+            Some(id) => Ok(vec![Token {
+                id: *id,
+                value: sequence.to_owned(),
+                offsets: (0, sequence.len()),
+            }]),
+            None => self.fallback.tokenize(sequence),
+        }
+    }
+
+    fn token_to_id(&self, token: &str) -> Option<u32> {
+        self.lexicon
+            .get(token)
+            .copied()
+            .or_else(|| self.fallback.token_to_id(token))
+    }
+
+    fn id_to_token(&self, id: u32) -> Option<String> {
+        self.lexicon_r
+            .get(&id)
+            .cloned()
+            .or_else(|| self.fallback.id_to_token(id))
+    }
+
+    fn get_vocab(&self) -> HashMap<String, u32> {
+        let mut vocab = self.fallback.get_vocab();
+        vocab.extend(self.lexicon.iter().map(|(token, id)| (token.clone(), *id)));
+        vocab
+    }
+
+    /// Like for the other models, the size leaves room for all the ids, even those
+    /// skipped by the lexicon
+    fn get_vocab_size(&self) -> usize {
+        let lexicon = self.lexicon_r.keys().max().map_or(0, |id| *id as usize + 1);
+        self.fallback.get_vocab_size().max(lexicon)
+    }
+
+    fn token_ids_with_prefix(&self, prefix: &[u8]) -> Vec<u32> {
+        let mut ids = self.fallback.token_ids_with_prefix(prefix);
+        ids.extend(
+            self.lexicon
+                .iter()
+                .filter(|(token, _)| token.as_bytes().starts_with(prefix))
+                .map(|(_, id)| *id),
+        );
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+
+    fn save(&self, folder: &Path, name: Option<&str>) -> Result<Vec<PathBuf>> {
+        write_files(&self.files()?, folder, &FileNaming::prefix(name))
+    }
+
+    fn get_trainer(&self) -> Self::Trainer {
+        self.fallback.get_trainer()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::wordpiece::WordPiece;
+    use crate::{AddedToken, Tokenizer};
+
+    fn model() -> Hybrid {
+        let vocab: Vocab = [("[UNK]", 0), ("ab", 1), ("##c", 2), ("c", 3)]
+            .iter()
+            .map(|(token, id)| (token.to_string(), *id))
+            .collect();
+        let wordpiece = WordPiece::builder().vocab(vocab).build().unwrap();
+        let lexicon = [("SKU-42", 10), ("abc", 11), ("c", 3)]
+            .iter()
+            .map(|(token, id)| (token.to_string(), *id))
+            .collect();
+        Hybrid::new(lexicon, wordpiece.into()).unwrap()
+    }
+
+    #[test]
+    fn tokenize() {
+        let model = model();
+        assert_eq!(
+            model.tokenize("SKU-42").unwrap(),
+            vec![Token::new(10, "SKU-42".into(), (0, 6))]
+        );
+        assert_eq!(
+            model.tokenize("abc").unwrap(),
+            vec![Token::new(11, "abc".into(), (0, 3))]
+        );
+        // Only the whole words are looked up
+        assert_eq!(
+            model.tokenize("abcc").unwrap(),
+            vec![
+                Token::new(1, "ab".into(), (0, 2)),
+                Token::new(2, "##c".into(), (2, 3)),
+                Token::new(2, "##c".into(), (3, 4)),
+            ]
+        );
+        assert_eq!(
+            model.tokenize("SKU").unwrap(),
+            vec![Token::new(0, "[UNK]".into(), (0, 3))]
+        );
+    }
+
+    #[test]
+    fn vocab() {
+        let model = model();
+        assert_eq!(model.token_to_id("SKU-42"), Some(10));
+        assert_eq!(model.token_to_id("##c"), Some(2));
+        assert_eq!(model.id_to_token(11), Some("abc".into()));
+        assert_eq!(model.id_to_token(1), Some("ab".into()));
+        assert_eq!(model.get_vocab_size(), 12);
+        assert_eq!(model.get_vocab().len(), 6);
+        assert_eq!(model.token_ids_with_prefix(b"ab"), vec![1, 11]);
+    }
+
+    #[test]
+    fn added_tokens() {
+        let vocab: Vocab = [("<unk>", 0), ("a", 1), ("b", 2), ("c", 3)]
+            .iter()
+            .map(|(token, id)| (token.to_string(), *id))
+            .collect();
+        let wordlevel = WordLevel::builder()
+            .vocab(vocab)
+            .unk_token("<unk>".into())
+            .build()
+            .unwrap();
+        let lexicon = [("SKU-1", 4), ("SKU-2", 6)]
+            .iter()
+            .map(|(token, id)| (token.to_string(), *id))
+            .collect();
+        let hybrid = Hybrid::new(lexicon, wordlevel.into()).unwrap();
+        assert_eq!(hybrid.get_vocab_size(), 7);
+
+        // The added tokens don't collide with the ids of the lexicon
+        let mut tokenizer = Tokenizer::new(hybrid);
+        tokenizer.add_special_tokens(&[AddedToken::from("[PAD]", true)]);
+        assert_eq!(tokenizer.token_to_id("[PAD]"), Some(7));
+        assert_eq!(tokenizer.id_to_token(6), Some("SKU-2".into()));
+    }
+
+    #[test]
+    fn train() {
+        let mut tokenizer = Tokenizer::new(model());
+        let mut trainer = tokenizer.get_model().get_trainer();
+        assert!(tokenizer.train(&mut trainer, ["a b"].iter()).is_err());
+    }
+
+    #[test]
+    fn conflicts() {
+        let fallback: ModelWrapper = model().fallback().clone();
+        let lexicon: Vocab = [("SKU-42".into(), 1)].iter().cloned().collect();
+        let error = Hybrid::new(lexicon, fallback.clone()).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<Error>(),
+            Some(Error::IdConflict { .. })
+        ));
+
+        let lexicon: Vocab = [("ab".into(), 10)].iter().cloned().collect();
+        let error = Hybrid::new(lexicon, fallback).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<Error>(),
+            Some(Error::TokenConflict { .. })
+        ));
+    }
+
+    #[test]
+    fn files() {
+        let files = model().files().unwrap();
+        let names: Vec<_> = files.iter().map(|file| file.name.as_str()).collect();
+        assert_eq!(names, vec!["vocab.txt", "lexicon.json"]);
+        assert_eq!(
+            files[1].content,
+            br#"{"c":3,"SKU-42":10,"abc":11}"#.to_vec()
+        );
+        assert_eq!(
+            Hybrid::read_bytes(&files[1].content).unwrap(),
+            *model().lexicon()
+        );
+    }
+}
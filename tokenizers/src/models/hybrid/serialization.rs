@@ -0,0 +1,138 @@
+use super::{Hybrid, Vocab};
+use crate::models::ModelWrapper;
+use ahash::AHashSet;
+use serde::{
+    de::{MapAccess, Visitor},
+    ser::SerializeStruct,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+/// The lexicon, serialized in order of token ID. Unlike the vocabularies, its ids usually
+/// have some holes, the ones of the fallback model.
+pub(super) struct OrderedLexicon<'a> {
+    lexicon: Vec<(&'a String, &'a u32)>,
+}
+
+impl<'a> OrderedLexicon<'a> {
+    pub(super) fn new(lexicon: &'a Vocab) -> Self {
+        let mut lexicon: Vec<_> = lexicon.iter().collect();
+        lexicon.sort_unstable_by_key(|(_, id)| **id);
+        Self { lexicon }
+    }
+}
+
+impl Serialize for OrderedLexicon<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_map(self.lexicon.iter().copied())
+    }
+}
+
+impl Serialize for Hybrid {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut model = serializer.serialize_struct("Hybrid", 3)?;
+        model.serialize_field("type", "Hybrid")?;
+        model.serialize_field("lexicon", &OrderedLexicon::new(&self.lexicon))?;
+        model.serialize_field("fallback", &self.fallback)?;
+        model.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Hybrid {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_struct("Hybrid", &["type", "lexicon", "fallback"], HybridVisitor)
+    }
+}
+
+struct HybridVisitor;
+impl<'de> Visitor<'de> for HybridVisitor {
+    type Value = Hybrid;
+
+    fn expecting(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(fmt, "struct Hybrid")
+    }
+
+    fn visit_map<V>(self, mut map: V) -> std::result::Result<Self::Value, V::Error>
+    where
+        V: MapAccess<'de>,
+    {
+        let mut lexicon: Option<Vocab> = None;
+        let mut fallback: Option<ModelWrapper> = None;
+        let mut missing_fields = vec!["lexicon", "fallback"]
+            .into_iter()
+            .collect::<AHashSet<_>>();
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_ref() {
+                "lexicon" => lexicon = Some(map.next_value()?),
+                "fallback" => fallback = Some(map.next_value()?),
+                "type" => match map.next_value()? {
+                    "Hybrid" => {}
+                    u => {
+                        return Err(serde::de::Error::invalid_value(
+                            serde::de::Unexpected::Str(u),
+                            &"Hybrid",
+                        ))
+                    }
+                },
+                _ => {}
+            }
+            missing_fields.remove::<str>(&key);
+        }
+
+        match (lexicon, fallback) {
+            (Some(lexicon), Some(fallback)) => {
+                Hybrid::new(lexicon, fallback).map_err(serde::de::Error::custom)
+            }
+            _ => Err(serde::de::Error::missing_field(
+                missing_fields.iter().next().unwrap(),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::hybrid::{Hybrid, Vocab};
+    use crate::models::wordlevel::WordLevel;
+    use crate::models::ModelWrapper;
+
+    #[test]
+    fn serde() {
+        let vocab = [("<unk>".into(), 0), ("a".into(), 1)]
+            .iter()
+            .cloned()
+            .collect();
+        let wordlevel = WordLevel::builder().vocab(vocab).build().unwrap();
+        let lexicon: Vocab = [("SKU-42".into(), 5)].iter().cloned().collect();
+        let model = Hybrid::new(lexicon, wordlevel.into()).unwrap();
+        let model_s = r#"{"type":"Hybrid","lexicon":{"SKU-42":5},"fallback":{"type":"WordLevel","vocab":{"<unk>":0,"a":1},"unk_token":"<unk>"}}"#;
+        assert_eq!(serde_json::to_string(&model).unwrap(), model_s);
+        assert_eq!(serde_json::from_str::<Hybrid>(model_s).unwrap(), model);
+
+        let wrapper: ModelWrapper = serde_json::from_str(model_s).unwrap();
+        assert_eq!(wrapper, ModelWrapper::Hybrid(model));
+    }
+
+    #[test]
+    fn deserialization_should_fail() {
+        let conflict = r#"{"type":"Hybrid","lexicon":{"SKU-42":1},"fallback":{"type":"WordLevel","vocab":{"<unk>":0,"a":1},"unk_token":"<unk>"}}"#;
+        assert!(serde_json::from_str::<Hybrid>(conflict)
+            .unwrap_err()
+            .to_string()
+            .contains("the id 1 of `SKU-42` in the lexicon is `a` in the fallback model"));
+
+        let missing = r#"{"type":"Hybrid","lexicon":{}}"#;
+        assert!(serde_json::from_str::<Hybrid>(missing)
+            .unwrap_err()
+            .to_string()
+            .starts_with("missing field `fallback`"));
+    }
+}
@@ -0,0 +1,276 @@
+use super::files::{write_files, FileNaming, ModelFile};
+use super::vocab_store::ids_with_prefix;
+use super::wordlevel::WordLevel;
+use super::OrderedVocabIter;
+use crate::tokenizer::{Model, Result, Token};
+use ahash::AHashMap;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+mod serialization;
+mod trainer;
+
+// Re-export
+pub use trainer::*;
+
+type Vocab = AHashMap<String, u32>;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("MaxMatch error: Missing [UNK] token from the vocabulary")]
+    MissingUnkToken,
+}
+
+struct Config {
+    vocab: Vocab,
+    unk_token: String,
+}
+
+/// A `MaxMatchBuilder` can be used to create a `MaxMatch`
+/// model with a custom configuration.
+pub struct MaxMatchBuilder {
+    config: Config,
+}
+
+impl Default for MaxMatchBuilder {
+    fn default() -> Self {
+        Self {
+            config: Config {
+                vocab: AHashMap::new(),
+                unk_token: String::from("<unk>"),
+            },
+        }
+    }
+}
+
+impl MaxMatchBuilder {
+    /// Construct a new `MaxMatchBuilder`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the vocab (token -> ID) mapping.
+    #[must_use]
+    pub fn vocab(mut self, vocab: Vocab) -> Self {
+        self.config.vocab = vocab;
+        self
+    }
+
+    /// Set the `UNK` token for the vocab.
+    #[must_use]
+    pub fn unk_token(mut self, unk_token: String) -> Self {
+        self.config.unk_token = unk_token;
+        self
+    }
+
+    /// Constructs a `MaxMatch` model that uses the `MaxMatchBuilder`'s configuration.
+    pub fn build(self) -> Result<MaxMatch> {
+        let vocab_r = self
+            .config
+            .vocab
+            .iter()
+            .map(|(key, val)| (*val, key.to_owned()))
+            .collect();
+        let max_token_len = self.config.vocab.keys().map(String::len).max().unwrap_or(0);
+
+        Ok(MaxMatch {
+            vocab: self.config.vocab,
+            vocab_r,
+            max_token_len,
+            unk_token: self.config.unk_token,
+        })
+    }
+}
+
+/// A greedy longest-match model: each word is tokenized from left to right, by taking at
+/// each position the longest token of the vocabulary it starts with. There are no merges
+/// nor scores, which makes it a fast option for the lexicon-based tokenization tasks, and a
+/// simple baseline. Each char matching no token becomes the `unk_token`.
+#[derive(PartialEq, Clone, Eq)]
+pub struct MaxMatch {
+    vocab: Vocab,
+    vocab_r: AHashMap<u32, String>,
+    /// The length of the longest token, in bytes, bounding the search at each position
+    max_token_len: usize,
+    pub unk_token: String,
+}
+
+impl std::fmt::Debug for MaxMatch {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.debug_struct("MaxMatch")
+            .field("unk_token", &self.unk_token)
+            .field("vocab", &self.vocab.len())
+            .finish()
+    }
+}
+
+impl MaxMatch {
+    pub fn builder() -> MaxMatchBuilder {
+        MaxMatchBuilder::new()
+    }
+
+    /// Read a `vocab.json` file, in the same format as the one of `WordLevel`
+    pub fn read_file(vocab_path: &str) -> Result<Vocab> {
+        WordLevel::read_file(vocab_path)
+    }
+
+    /// Extract the vocab from the content of a `vocab.json`, like [`WordLevel::read_bytes`]
+    pub fn read_bytes(vocab_json: &[u8]) -> Result<Vocab> {
+        WordLevel::read_bytes(vocab_json)
+    }
+
+    /// The content of the `vocab.json` file written by [`save`](Model::save)
+    pub fn files(&self) -> Result<Vec<ModelFile>> {
+        let order_vocab_iter = OrderedVocabIter::new(&self.vocab_r);
+        let serialized = serde_json::to_string(&order_vocab_iter)?;
+        Ok(vec![ModelFile::new("vocab.json", serialized)])
+    }
+
+    /// Initialize a MaxMatch model from a vocab file.
+    pub fn from_file(vocab_path: &str, unk_token: String) -> Result<MaxMatch> {
+        let vocab = MaxMatch::read_file(vocab_path)?;
+        Self::builder().vocab(vocab).unk_token(unk_token).build()
+    }
+}
+
+impl Default for MaxMatch {
+    fn default() -> Self {
+        Self::builder().build().unwrap()
+    }
+}
+
+impl Model for MaxMatch {
+    type Trainer = MaxMatchTrainer;
+
+    fn tokenize(&self, sequence: &str) -> Result<Vec<Token>> {
+        let mut tokens = vec![];
+        let mut start = 0;
+        while start < sequence.len() {
+            let rest = &sequence[start..];
+            let longest = rest
+                .char_indices()
+                .skip(1)
+                .map(|(end, _)| end)
+                .chain(std::iter::once(rest.len()))
+                .take_while(|end| *end <= self.max_token_len)
+                .filter_map(|end| self.vocab.get(&rest[..end]).map(|id| (end, *id)))
+                .last();
+
+            let (len, id, value) = match longest {
+                Some((len, id)) => (len, id, rest[..len].to_owned()),
+                None => {
+                    let id = *self
+                        .vocab
+                        .get(&self.unk_token)
+                        .ok_or(Error::MissingUnkToken)?;
+                    let len = rest.chars().next().map_or(0, char::len_utf8);
+                    (len, id, self.unk_token.clone())
+                }
+            };
+            tokens.push(Token {
+                id,
+                value,
+                offsets: (start, start + len),
+            });
+            start += len;
+        }
+        Ok(tokens)
+    }
+
+    fn token_to_id(&self, token: &str) -> Option<u32> {
+        self.vocab.get(token).copied()
+    }
+
+    fn id_to_token(&self, id: u32) -> Option<String> {
+        self.vocab_r.get(&id).cloned()
+    }
+
+    fn get_vocab(&self) -> HashMap<String, u32> {
+        self.vocab.clone().into_iter().collect()
+    }
+
+    fn get_vocab_size(&self) -> usize {
+        self.vocab.keys().len()
+    }
+
+    fn token_ids_with_prefix(&self, prefix: &[u8]) -> Vec<u32> {
+        ids_with_prefix(
+            self.vocab.iter().map(|(token, id)| (token.as_str(), *id)),
+            prefix,
+        )
+    }
+
+    fn save(&self, folder: &Path, name: Option<&str>) -> Result<Vec<PathBuf>> {
+        write_files(&self.files()?, folder, &FileNaming::prefix(name))
+    }
+
+    fn get_trainer(&self) -> Self::Trainer {
+        MaxMatchTrainer::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model() -> MaxMatch {
+        let vocab: Vocab = [
+            ("<unk>".into(), 0),
+            ("a".into(), 1),
+            ("b".into(), 2),
+            ("ab".into(), 3),
+            ("abc".into(), 4),
+            ("é".into(), 5),
+            ("cé".into(), 6),
+        ]
+        .iter()
+        .cloned()
+        .collect();
+        MaxMatch::builder().vocab(vocab).build().unwrap()
+    }
+
+    #[test]
+    fn test_tokenize_longest_match() {
+        let model = model();
+        assert_eq!(
+            model.tokenize("abab").unwrap(),
+            vec![
+                Token::new(3, "ab".into(), (0, 2)),
+                Token::new(3, "ab".into(), (2, 4)),
+            ]
+        );
+        // Greedy: "abc" is taken even though "ab" + "cé" would cover "é" too
+        assert_eq!(
+            model.tokenize("abcé").unwrap(),
+            vec![
+                Token::new(4, "abc".into(), (0, 3)),
+                Token::new(5, "é".into(), (3, 5)),
+            ]
+        );
+        assert_eq!(model.tokenize("").unwrap(), vec![]);
+    }
+
+    #[test]
+    fn test_tokenize_unk() {
+        let model = model();
+        assert_eq!(
+            model.tokenize("xabyy").unwrap(),
+            vec![
+                Token::new(0, "<unk>".into(), (0, 1)),
+                Token::new(3, "ab".into(), (1, 3)),
+                Token::new(0, "<unk>".into(), (3, 4)),
+                Token::new(0, "<unk>".into(), (4, 5)),
+            ]
+        );
+        assert_eq!(
+            model.tokenize("ñ").unwrap(),
+            vec![Token::new(0, "<unk>".into(), (0, 2))]
+        );
+
+        let vocab: Vocab = [("a".into(), 0)].iter().cloned().collect();
+        let model = MaxMatch::builder().vocab(vocab).build().unwrap();
+        assert_eq!(model.tokenize("aa").unwrap().len(), 2);
+        let error = model.tokenize("b").unwrap_err();
+        assert!(error.is::<Error>());
+    }
+}
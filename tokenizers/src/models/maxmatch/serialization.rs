@@ -0,0 +1,115 @@
+use super::{super::OrderedVocabIter, MaxMatch, MaxMatchBuilder};
+use ahash::AHashSet;
+use serde::{
+    de::{MapAccess, Visitor},
+    ser::SerializeStruct,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+impl Serialize for MaxMatch {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut model = serializer.serialize_struct("MaxMatch", 3)?;
+        let ordered_vocab = OrderedVocabIter::new(&self.vocab_r);
+        model.serialize_field("type", "MaxMatch")?;
+        model.serialize_field("vocab", &ordered_vocab)?;
+        model.serialize_field("unk_token", &self.unk_token)?;
+        model.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for MaxMatch {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_struct(
+            "MaxMatch",
+            &["type", "vocab", "unk_token"],
+            MaxMatchVisitor,
+        )
+    }
+}
+
+struct MaxMatchVisitor;
+impl<'de> Visitor<'de> for MaxMatchVisitor {
+    type Value = MaxMatch;
+
+    fn expecting(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(fmt, "struct MaxMatch")
+    }
+
+    fn visit_map<V>(self, mut map: V) -> std::result::Result<Self::Value, V::Error>
+    where
+        V: MapAccess<'de>,
+    {
+        let mut builder = MaxMatchBuilder::new();
+        let mut missing_fields = vec!["unk_token", "vocab"]
+            .into_iter()
+            .collect::<AHashSet<_>>();
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_ref() {
+                "vocab" => builder = builder.vocab(map.next_value()?),
+                "unk_token" => builder = builder.unk_token(map.next_value()?),
+                "type" => match map.next_value()? {
+                    "MaxMatch" => {}
+                    u => {
+                        return Err(serde::de::Error::invalid_value(
+                            serde::de::Unexpected::Str(u),
+                            &"MaxMatch",
+                        ))
+                    }
+                },
+                _ => {}
+            }
+            missing_fields.remove::<str>(&key);
+        }
+
+        if !missing_fields.is_empty() {
+            Err(serde::de::Error::missing_field(
+                missing_fields.iter().next().unwrap(),
+            ))
+        } else {
+            Ok(builder.build().map_err(serde::de::Error::custom)?)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::maxmatch::{MaxMatch, Vocab};
+    use crate::models::ModelWrapper;
+
+    #[test]
+    fn serde() {
+        let vocab: Vocab = [("<unk>".into(), 0), ("ab".into(), 1)]
+            .iter()
+            .cloned()
+            .collect();
+        let model = MaxMatch::builder().vocab(vocab).build().unwrap();
+        let model_s = r#"{"type":"MaxMatch","vocab":{"<unk>":0,"ab":1},"unk_token":"<unk>"}"#;
+        assert_eq!(serde_json::to_string(&model).unwrap(), model_s);
+        assert_eq!(serde_json::from_str::<MaxMatch>(model_s).unwrap(), model);
+
+        let wrapper: ModelWrapper = serde_json::from_str(model_s).unwrap();
+        assert_eq!(wrapper, ModelWrapper::MaxMatch(model));
+    }
+
+    #[test]
+    fn deserialization_should_fail() {
+        // Without a "type", the fields are the same as the ones of a WordLevel
+        let untagged = r#"{"vocab":{},"unk_token":"<unk>"}"#;
+        assert!(matches!(
+            serde_json::from_str::<ModelWrapper>(untagged).unwrap(),
+            ModelWrapper::WordLevel(_)
+        ));
+
+        let wrong_type = r#"{"type":"WordLevel","vocab":{},"unk_token":"<unk>"}"#;
+        assert!(serde_json::from_str::<MaxMatch>(wrong_type)
+            .unwrap_err()
+            .to_string()
+            .starts_with("invalid value: string \"WordLevel\", expected MaxMatch"));
+    }
+}
@@ -0,0 +1,213 @@
+use super::MaxMatch;
+use crate::utils::parallelism::*;
+use crate::utils::progress::{ProgressCallback, TrainingProgress};
+use crate::{AddedToken, Result, Trainer};
+use ahash::{AHashMap, AHashSet};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+
+/// Trains a `MaxMatch` model. The vocabulary is made of the special tokens, followed by the
+/// chars and then the words seen at least `min_frequency` times, the most frequent first.
+/// The chars make sure that the rare words can still be tokenized, char by char.
+#[non_exhaustive]
+#[derive(Debug, Clone, Builder, Serialize, Deserialize)]
+pub struct MaxMatchTrainer {
+    /// The minimum frequency a char or a word must have to be part of the vocabulary
+    #[builder(default = "0")]
+    pub min_frequency: u64,
+    /// The target vocabulary size
+    #[builder(default = "30_000")]
+    pub vocab_size: usize,
+    /// Whether to show progress while training
+    #[builder(default = "true")]
+    pub show_progress: bool,
+    /// A list of special tokens that the model should know of
+    #[builder(default)]
+    pub special_tokens: Vec<AddedToken>,
+    /// An optional callback reporting the progress
+    #[builder(default)]
+    #[serde(skip)]
+    pub progress_callback: Option<ProgressCallback>,
+
+    #[builder(default, private)]
+    words: AHashMap<String, u64>,
+}
+
+impl Default for MaxMatchTrainer {
+    fn default() -> Self {
+        Self::builder().build().unwrap()
+    }
+}
+
+impl MaxMatchTrainer {
+    pub fn builder() -> MaxMatchTrainerBuilder {
+        MaxMatchTrainerBuilder::default()
+    }
+
+    /// The entries seen at least `min_frequency` times, sorted by decreasing count, and
+    /// then by value to keep the order deterministic
+    fn frequent(&self, counts: impl Iterator<Item = (String, u64)>) -> Vec<String> {
+        let mut counts = counts
+            .filter(|(_, count)| *count >= self.min_frequency)
+            .collect::<Vec<_>>();
+        counts.sort_by(|(l, l_count), (r, r_count)| {
+            (Reverse(l_count), l).cmp(&(Reverse(r_count), r))
+        });
+        counts.into_iter().map(|(value, _)| value).collect()
+    }
+
+    fn do_train(
+        &self,
+        word_counts: &AHashMap<String, u64>,
+        model: &mut MaxMatch,
+    ) -> Result<Vec<AddedToken>> {
+        let mut char_counts: AHashMap<String, u64> = AHashMap::new();
+        for (word, count) in word_counts {
+            for c in word.chars() {
+                *char_counts.entry(c.to_string()).or_default() += count;
+            }
+        }
+
+        let mut seen = AHashSet::new();
+        let vocab = self
+            .special_tokens
+            .iter()
+            .map(|token| token.content.clone())
+            .chain(self.frequent(char_counts.into_iter()))
+            .chain(self.frequent(word_counts.iter().map(|(w, c)| (w.clone(), *c))))
+            .filter(|token| seen.insert(token.clone()))
+            .take(self.vocab_size)
+            .enumerate()
+            .map(|(i, token)| (token, i as u32))
+            .collect();
+        let max_match = MaxMatch::builder()
+            .vocab(vocab)
+            .unk_token(model.unk_token.clone())
+            .build()?;
+
+        if let Some(callback) = &self.progress_callback {
+            callback.report_single_phase(&TrainingProgress {
+                phase: "Compute vocabulary",
+                iteration: 1,
+                total: 1,
+                vocab_size: max_match.vocab.len(),
+                loss: None,
+            })?;
+        }
+
+        *model = max_match;
+        Ok(self.special_tokens.clone())
+    }
+}
+
+impl Trainer for MaxMatchTrainer {
+    type Model = MaxMatch;
+
+    /// Train a MaxMatch model
+    fn train(&self, model: &mut MaxMatch) -> Result<Vec<AddedToken>> {
+        self.do_train(&self.words, model)
+    }
+
+    /// Whether we should show progress
+    fn should_show_progress(&self) -> bool {
+        self.show_progress
+    }
+
+    fn feed<I, S, F>(&mut self, iterator: I, process: F) -> Result<()>
+    where
+        I: Iterator<Item = S> + Send,
+        S: AsRef<str> + Send,
+        F: Fn(&str) -> Result<Vec<String>> + Sync,
+    {
+        let words: Result<AHashMap<String, u64>> = iterator
+            .maybe_par_bridge()
+            .map(|sequence| {
+                let words = process(sequence.as_ref())?;
+                let mut map = AHashMap::new();
+                for word in words {
+                    *map.entry(word).or_default() += 1;
+                }
+                Ok(map)
+            })
+            .reduce(
+                || Ok(AHashMap::new()),
+                |acc, ws| {
+                    let mut acc = acc?;
+                    for (k, v) in ws? {
+                        *acc.entry(k).or_default() += v;
+                    }
+                    Ok(acc)
+                },
+            );
+
+        self.words = words?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Model;
+
+    #[test]
+    fn test_train() {
+        let word_counts: AHashMap<String, u64> = [
+            ("ab".into(), 10),
+            ("abc".into(), 4),
+            ("b".into(), 3),
+            ("d".into(), 1),
+        ]
+        .iter()
+        .cloned()
+        .collect();
+
+        let mut trainer = MaxMatchTrainer {
+            special_tokens: vec![AddedToken::from("<unk>", true)],
+            ..Default::default()
+        };
+        let mut model = MaxMatch::default();
+        trainer.do_train(&word_counts, &mut model).unwrap();
+        // b: 17, a: 14, c: 4, d: 1, then the words with "b" and "d" as chars already
+        let expected: AHashMap<String, u32> = [
+            ("<unk>".into(), 0),
+            ("b".into(), 1),
+            ("a".into(), 2),
+            ("c".into(), 3),
+            ("d".into(), 4),
+            ("ab".into(), 5),
+            ("abc".into(), 6),
+        ]
+        .iter()
+        .cloned()
+        .collect();
+        assert_eq!(model.vocab, expected);
+        assert_eq!(model.tokenize("abcab").unwrap().len(), 2);
+
+        // With a frequency cutoff
+        trainer.min_frequency = 4;
+        trainer.vocab_size = 5;
+        let mut model = MaxMatch::default();
+        trainer.do_train(&word_counts, &mut model).unwrap();
+        let expected: AHashMap<String, u32> = [
+            ("<unk>".into(), 0),
+            ("b".into(), 1),
+            ("a".into(), 2),
+            ("c".into(), 3),
+            ("ab".into(), 4),
+        ]
+        .iter()
+        .cloned()
+        .collect();
+        assert_eq!(model.vocab, expected);
+        assert_eq!(
+            model
+                .tokenize("abcd")
+                .unwrap()
+                .into_iter()
+                .map(|t| t.value)
+                .collect::<Vec<_>>(),
+            vec!["ab", "c", "<unk>"]
+        );
+    }
+}
@@ -1,7 +1,11 @@
 //! Popular tokenizer models.
 
 pub mod bpe;
+pub mod files;
+pub mod hybrid;
+pub mod maxmatch;
 pub mod unigram;
+pub mod vocab_store;
 pub mod wordlevel;
 pub mod wordpiece;
 
@@ -12,10 +16,14 @@ use std::path::{Path, PathBuf};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::models::bpe::{BpeTrainer, BPE};
+use crate::models::files::{write_files, FileNaming, ModelFile};
+use crate::models::hybrid::Hybrid;
+use crate::models::maxmatch::{MaxMatch, MaxMatchTrainer};
 use crate::models::unigram::{Unigram, UnigramTrainer};
 use crate::models::wordlevel::{WordLevel, WordLevelTrainer};
 use crate::models::wordpiece::{WordPiece, WordPieceTrainer};
-use crate::{AddedToken, Model, Result, Token, Trainer};
+use crate::utils::progress::ProgressCallback;
+use crate::{AddedToken, Model, NBestModel, Result, SamplingModel, Token, Trainer};
 
 /// Wraps a vocab mapping (ID -> token) to a struct that will be serialized in order
 /// of token ID, smallest to largest.
@@ -67,6 +75,8 @@ pub enum ModelWrapper {
     WordPiece(WordPiece),
     WordLevel(WordLevel),
     Unigram(Unigram),
+    MaxMatch(MaxMatch),
+    Hybrid(Hybrid),
 }
 
 impl<'de> Deserialize<'de> for ModelWrapper {
@@ -87,6 +97,8 @@ impl<'de> Deserialize<'de> for ModelWrapper {
             WordPiece,
             WordLevel,
             Unigram,
+            MaxMatch,
+            Hybrid,
         }
 
         #[derive(Deserialize)]
@@ -122,6 +134,12 @@ impl<'de> Deserialize<'de> for ModelWrapper {
                 EnumType::Unigram => ModelWrapper::Unigram(
                     serde_json::from_value(model.rest).map_err(serde::de::Error::custom)?,
                 ),
+                EnumType::MaxMatch => ModelWrapper::MaxMatch(
+                    serde_json::from_value(model.rest).map_err(serde::de::Error::custom)?,
+                ),
+                EnumType::Hybrid => ModelWrapper::Hybrid(
+                    serde_json::from_value(model.rest).map_err(serde::de::Error::custom)?,
+                ),
             },
             ModelHelper::Legacy(value) => {
                 let untagged = serde_json::from_value(value).map_err(serde::de::Error::custom)?;
@@ -140,6 +158,8 @@ impl_enum_from!(WordLevel, ModelWrapper, WordLevel);
 impl_enum_from!(WordPiece, ModelWrapper, WordPiece);
 impl_enum_from!(BPE, ModelWrapper, BPE);
 impl_enum_from!(Unigram, ModelWrapper, Unigram);
+impl_enum_from!(MaxMatch, ModelWrapper, MaxMatch);
+impl_enum_from!(Hybrid, ModelWrapper, Hybrid);
 
 impl Model for ModelWrapper {
     type Trainer = TrainerWrapper;
@@ -150,6 +170,8 @@ impl Model for ModelWrapper {
             Self::WordPiece(t) => t.tokenize(tokens),
             Self::BPE(t) => t.tokenize(tokens),
             Self::Unigram(t) => t.tokenize(tokens),
+            Self::MaxMatch(t) => t.tokenize(tokens),
+            Self::Hybrid(t) => t.tokenize(tokens),
         }
     }
 
@@ -159,6 +181,8 @@ impl Model for ModelWrapper {
             Self::WordPiece(t) => t.token_to_id(token),
             Self::BPE(t) => t.token_to_id(token),
             Self::Unigram(t) => t.token_to_id(token),
+            Self::MaxMatch(t) => t.token_to_id(token),
+            Self::Hybrid(t) => t.token_to_id(token),
         }
     }
 
@@ -168,6 +192,8 @@ impl Model for ModelWrapper {
             Self::WordPiece(t) => t.id_to_token(id),
             Self::BPE(t) => t.id_to_token(id),
             Self::Unigram(t) => t.id_to_token(id),
+            Self::MaxMatch(t) => t.id_to_token(id),
+            Self::Hybrid(t) => t.id_to_token(id),
         }
     }
 
@@ -177,6 +203,8 @@ impl Model for ModelWrapper {
             Self::WordPiece(t) => t.get_vocab(),
             Self::BPE(t) => t.get_vocab(),
             Self::Unigram(t) => t.get_vocab(),
+            Self::MaxMatch(t) => t.get_vocab(),
+            Self::Hybrid(t) => t.get_vocab(),
         }
     }
 
@@ -186,6 +214,19 @@ impl Model for ModelWrapper {
             Self::WordPiece(t) => t.get_vocab_size(),
             Self::BPE(t) => t.get_vocab_size(),
             Self::Unigram(t) => t.get_vocab_size(),
+            Self::MaxMatch(t) => t.get_vocab_size(),
+            Self::Hybrid(t) => t.get_vocab_size(),
+        }
+    }
+
+    fn token_ids_with_prefix(&self, prefix: &[u8]) -> Vec<u32> {
+        match self {
+            Self::WordLevel(t) => t.token_ids_with_prefix(prefix),
+            Self::WordPiece(t) => t.token_ids_with_prefix(prefix),
+            Self::BPE(t) => t.token_ids_with_prefix(prefix),
+            Self::Unigram(t) => t.token_ids_with_prefix(prefix),
+            Self::MaxMatch(t) => t.token_ids_with_prefix(prefix),
+            Self::Hybrid(t) => t.token_ids_with_prefix(prefix),
         }
     }
 
@@ -195,6 +236,8 @@ impl Model for ModelWrapper {
             Self::WordPiece(t) => t.save(folder, name),
             Self::BPE(t) => t.save(folder, name),
             Self::Unigram(t) => t.save(folder, name),
+            Self::MaxMatch(t) => t.save(folder, name),
+            Self::Hybrid(t) => t.save(folder, name),
         }
     }
 
@@ -204,6 +247,41 @@ impl Model for ModelWrapper {
             Self::WordPiece(t) => t.get_trainer().into(),
             Self::BPE(t) => t.get_trainer().into(),
             Self::Unigram(t) => t.get_trainer().into(),
+            Self::MaxMatch(t) => t.get_trainer().into(),
+            Self::Hybrid(t) => t.get_trainer(),
+        }
+    }
+}
+
+impl SamplingModel for ModelWrapper {
+    fn tokenize_sampled(&self, sequence: &str, strength: f64) -> Result<Vec<Token>> {
+        match self {
+            Self::BPE(t) => t.tokenize_sampled(sequence, strength),
+            Self::Unigram(t) => t.tokenize_sampled(sequence, strength),
+            Self::WordPiece(_) => Err("WordPiece does not support sampling".into()),
+            Self::WordLevel(_) => Err("WordLevel does not support sampling".into()),
+            Self::MaxMatch(_) => Err("MaxMatch does not support sampling".into()),
+            Self::Hybrid(t) => match t.lexicon().get(sequence) {
+                Some(_) => t.tokenize(sequence),
+                None => t.fallback().tokenize_sampled(sequence, strength),
+            },
+        }
+    }
+}
+
+impl NBestModel for ModelWrapper {
+    fn tokenize_nbest(&self, sequence: &str, n: usize) -> Result<Vec<(Vec<Token>, f64)>> {
+        match self {
+            Self::Unigram(t) => t.tokenize_nbest(sequence, n),
+            Self::BPE(_) => Err("BPE does not support n-best tokenization".into()),
+            Self::WordPiece(_) => Err("WordPiece does not support n-best tokenization".into()),
+            // A word can only be tokenized one way
+            Self::WordLevel(t) => Ok(vec![(t.tokenize(sequence)?, 0.0)]),
+            Self::MaxMatch(t) => Ok(vec![(t.tokenize(sequence)?, 0.0)]),
+            Self::Hybrid(t) => match t.lexicon().get(sequence) {
+                Some(_) => Ok(vec![(t.tokenize(sequence)?, 0.0)]),
+                None => t.fallback().tokenize_nbest(sequence, n),
+            },
         }
     }
 }
@@ -213,6 +291,7 @@ impl ModelWrapper {
         match self {
             Self::Unigram(model) => model.clear_cache(),
             Self::BPE(model) => model.clear_cache(),
+            Self::Hybrid(model) => model.fallback_mut().clear_cache(),
             _ => (),
         }
     }
@@ -220,9 +299,27 @@ impl ModelWrapper {
         match self {
             Self::Unigram(model) => model.resize_cache(capacity),
             Self::BPE(model) => model.resize_cache(capacity),
+            Self::Hybrid(model) => model.fallback_mut().resize_cache(capacity),
             _ => (),
         }
     }
+
+    /// The content of the files written by [`save`](Model::save), to embed them elsewhere
+    pub fn files(&self) -> Result<Vec<ModelFile>> {
+        match self {
+            Self::WordLevel(t) => t.files(),
+            Self::WordPiece(t) => t.files(),
+            Self::BPE(t) => t.files(),
+            Self::Unigram(t) => t.files(),
+            Self::MaxMatch(t) => t.files(),
+            Self::Hybrid(t) => t.files(),
+        }
+    }
+
+    /// Save the model like [`save`](Model::save), with the given names for its files
+    pub fn save_with(&self, folder: &Path, naming: &FileNaming) -> Result<Vec<PathBuf>> {
+        write_files(&self.files()?, folder, naming)
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -231,6 +328,20 @@ pub enum TrainerWrapper {
     WordPieceTrainer(WordPieceTrainer),
     WordLevelTrainer(WordLevelTrainer),
     UnigramTrainer(UnigramTrainer),
+    MaxMatchTrainer(MaxMatchTrainer),
+}
+
+impl TrainerWrapper {
+    /// Set the callback reporting the progress of the training, replacing the progress bar
+    pub fn set_progress_callback(&mut self, callback: Option<ProgressCallback>) {
+        match self {
+            Self::BpeTrainer(t) => t.progress_callback = callback,
+            Self::WordPieceTrainer(t) => t.set_progress_callback(callback),
+            Self::WordLevelTrainer(t) => t.progress_callback = callback,
+            Self::UnigramTrainer(t) => t.progress_callback = callback,
+            Self::MaxMatchTrainer(t) => t.progress_callback = callback,
+        }
+    }
 }
 
 impl Trainer for TrainerWrapper {
@@ -242,10 +353,16 @@ impl Trainer for TrainerWrapper {
             Self::WordPieceTrainer(wpt) => wpt.should_show_progress(),
             Self::WordLevelTrainer(wpt) => wpt.should_show_progress(),
             Self::UnigramTrainer(wpt) => wpt.should_show_progress(),
+            Self::MaxMatchTrainer(wpt) => wpt.should_show_progress(),
         }
     }
 
     fn train(&self, model: &mut ModelWrapper) -> Result<Vec<AddedToken>> {
+        if let ModelWrapper::Hybrid(_) = model {
+            return Err(
+                "Hybrid cannot be trained, only its fallback model before building it".into(),
+            );
+        }
         match self {
             Self::BpeTrainer(t) => match model {
                 ModelWrapper::BPE(bpe) => t.train(bpe),
@@ -263,6 +380,10 @@ impl Trainer for TrainerWrapper {
                 ModelWrapper::Unigram(u) => t.train(u),
                 _ => Err("UnigramTrainer can only train a Unigram".into()),
             },
+            Self::MaxMatchTrainer(t) => match model {
+                ModelWrapper::MaxMatch(m) => t.train(m),
+                _ => Err("MaxMatchTrainer can only train a MaxMatch".into()),
+            },
         }
     }
 
@@ -277,6 +398,7 @@ impl Trainer for TrainerWrapper {
             Self::WordPieceTrainer(wpt) => wpt.feed(iterator, process),
             Self::WordLevelTrainer(wpt) => wpt.feed(iterator, process),
             Self::UnigramTrainer(wpt) => wpt.feed(iterator, process),
+            Self::MaxMatchTrainer(wpt) => wpt.feed(iterator, process),
         }
     }
 }
@@ -285,6 +407,7 @@ impl_enum_from!(BpeTrainer, TrainerWrapper, BpeTrainer);
 impl_enum_from!(WordPieceTrainer, TrainerWrapper, WordPieceTrainer);
 impl_enum_from!(UnigramTrainer, TrainerWrapper, UnigramTrainer);
 impl_enum_from!(WordLevelTrainer, TrainerWrapper, WordLevelTrainer);
+impl_enum_from!(MaxMatchTrainer, TrainerWrapper, MaxMatchTrainer);
 
 #[cfg(test)]
 mod tests {
@@ -300,6 +423,27 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn save_with() {
+        let folder = tempfile::tempdir().unwrap();
+        let model: ModelWrapper = WordPiece::default().into();
+        let naming = FileNaming {
+            suffix: Some("uncased".into()),
+            ..Default::default()
+        };
+        let paths = model.save_with(folder.path(), &naming).unwrap();
+        assert_eq!(paths, vec![folder.path().join("vocab-uncased.txt")]);
+        assert_eq!(
+            std::fs::read(&paths[0]).unwrap(),
+            model.files().unwrap()[0].content
+        );
+
+        let unigram = Unigram::default();
+        let files = unigram.files().unwrap();
+        assert_eq!(files[0].name, "unigram.json");
+        assert_eq!(Unigram::from_bytes(&files[0].content).unwrap(), unigram);
+    }
+
     #[test]
     fn incomplete_ordered_vocab() {
         let vocab_r: AHashMap<u32, String> =
@@ -1,11 +1,12 @@
 use dary_heap::QuaternaryHeap;
 use rand::distr::weighted::WeightedIndex;
 use rand::{prelude::*, rng};
+use serde::Serialize;
 use std::cell::RefCell;
 use std::cmp::{min, Ordering};
 use std::rc::Rc;
 
-type NodeRef = Rc<RefCell<Node>>;
+pub(crate) type NodeRef = Rc<RefCell<Node>>;
 type HypothesisRef = Rc<RefCell<Hypothesis>>;
 type Agenda = QuaternaryHeap<Hypothesis>;
 
@@ -92,7 +93,7 @@ pub struct Node {
     length: usize,
     prev: Option<NodeRef>,
     backtrace_score: f64,
-    score: f64,
+    pub(super) score: f64,
 }
 
 impl PartialEq for Node {
@@ -430,6 +431,132 @@ impl<'a> Lattice<'a> {
     }
 }
 
+/// A node of a [`LatticeGraph`]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LatticeNode {
+    /// Local lattice identifier, `0` and `1` being the BOS and EOS nodes
+    pub node_id: usize,
+    /// Vocabulary id
+    pub id: usize,
+    /// The part of the sentence covered by this node
+    pub piece: String,
+    /// Byte offsets of `piece` in the sentence
+    pub offsets: (usize, usize),
+    /// The score of this node in the model
+    pub score: f64,
+    /// The score of the best path from BOS to (and including) this node
+    pub backtrace_score: f64,
+    /// The previous node on the best path reaching this node
+    pub prev: Option<usize>,
+}
+
+/// A snapshot of a [`Lattice`], made to inspect or visualize how a sentence gets segmented.
+///
+/// Any node ending at a given position is connected to all the nodes starting at this same
+/// position.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LatticeGraph {
+    pub sentence: String,
+    pub nodes: Vec<LatticeNode>,
+    /// The `node_id`s of the Viterbi path, BOS and EOS excluded
+    pub best_path: Vec<usize>,
+}
+
+impl LatticeGraph {
+    /// Render this graph in the [DOT](https://graphviz.org/doc/info/lang.html) language,
+    /// highlighting the Viterbi path.
+    pub fn to_dot(&self) -> String {
+        let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+        let mut best = vec![false; self.nodes.len()];
+        // BOS and EOS are always part of the best path, when there is one
+        if !self.best_path.is_empty() {
+            best[0] = true;
+            best[1] = true;
+        }
+        for node_id in &self.best_path {
+            best[*node_id] = true;
+        }
+
+        let mut dot = String::from("digraph Lattice {\n    rankdir=LR;\n    node [shape=box];\n");
+        for node in &self.nodes {
+            let label = match node.node_id {
+                0 => "BOS".to_string(),
+                1 => "EOS".to_string(),
+                _ => format!(
+                    "{}\\n[{}, {})\\nid: {}\\nscore: {:.4}",
+                    escape(&node.piece),
+                    node.offsets.0,
+                    node.offsets.1,
+                    node.id,
+                    node.score
+                ),
+            };
+            let style = if best[node.node_id] {
+                ", color=red, penwidth=2"
+            } else {
+                ""
+            };
+            dot.push_str(&format!(
+                "    n{} [label=\"{}\"{}];\n",
+                node.node_id, label, style
+            ));
+        }
+
+        for left in self.nodes.iter().filter(|node| node.node_id != 1) {
+            for right in self.nodes.iter().filter(|right| {
+                // BOS starts where it ends, it must not be connected to itself
+                right.node_id != 0 && right.offsets.0 == left.offsets.1
+            }) {
+                let on_best_path = best[left.node_id] && best[right.node_id];
+                let style = if on_best_path && right.prev == Some(left.node_id) {
+                    " [color=red, penwidth=2]"
+                } else {
+                    ""
+                };
+                dot.push_str(&format!(
+                    "    n{} -> n{}{};\n",
+                    left.node_id, right.node_id, style
+                ));
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+impl Lattice<'_> {
+    /// Run the Viterbi algorithm, and return a [`LatticeGraph`] describing the whole lattice
+    pub fn to_graph(&mut self) -> LatticeGraph {
+        let best_path = self
+            .viterbi()
+            .iter()
+            .map(|node| node.borrow().node_id)
+            .collect();
+        let nodes = self
+            .nodes
+            .iter()
+            .map(|node| {
+                let node = node.borrow();
+                LatticeNode {
+                    node_id: node.node_id,
+                    id: node.id,
+                    piece: self.piece(&node),
+                    offsets: (node.pos, node.pos + node.length),
+                    score: node.score,
+                    backtrace_score: node.backtrace_score,
+                    prev: node.prev.as_ref().map(|prev| prev.borrow().node_id),
+                }
+            })
+            .collect();
+
+        LatticeGraph {
+            sentence: self.sentence.to_owned(),
+            nodes,
+            best_path,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -602,6 +729,45 @@ mod tests {
         assert_eq!(lattice.tokens(), ["ABC"]);
     }
 
+    #[test]
+    fn test_to_graph() {
+        let mut lattice = Lattice::from("AB", 1, 2);
+        lattice.insert(0, 1, 0.0, 3);
+        lattice.insert(1, 1, 0.0, 4);
+        lattice.insert(0, 2, 2.0, 5);
+
+        let graph = lattice.to_graph();
+        assert_eq!(graph.sentence, "AB");
+        assert_eq!(graph.best_path, vec![4]);
+        let pieces = graph
+            .nodes
+            .iter()
+            .map(|n| (n.node_id, n.id, n.piece.as_str(), n.offsets, n.prev))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            pieces,
+            vec![
+                (0, 1, "", (0, 0), None),
+                (1, 2, "", (2, 2), Some(4)),
+                (2, 3, "A", (0, 1), Some(0)),
+                (3, 4, "B", (1, 2), Some(2)),
+                (4, 5, "AB", (0, 2), Some(0)),
+            ]
+        );
+        assert_eq!(graph.nodes[1].backtrace_score, 2.0);
+
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph Lattice {"));
+        assert!(dot.contains("n4 [label=\"AB\\n[0, 2)\\nid: 5\\nscore: 2.0000\", color=red"));
+        assert!(dot.contains("n0 -> n4 [color=red, penwidth=2];"));
+        assert!(dot.contains("n4 -> n1 [color=red, penwidth=2];"));
+        assert!(dot.contains("n0 -> n2;"));
+        assert!(dot.contains("n2 -> n3;"));
+        assert!(dot.contains("n3 -> n1;"));
+        assert!(!dot.contains("n0 -> n0"));
+        assert_eq!(dot.matches("->").count(), 5);
+    }
+
     #[test]
     fn test_nbest() {
         let mut lattice = Lattice::from("ABC", 1, 2);
@@ -1,14 +1,15 @@
 use super::{
-    lattice::Lattice,
+    lattice::{Lattice, LatticeGraph, NodeRef},
     trainer::UnigramTrainer,
     trie::{Trie, TrieBuilder},
 };
-use crate::tokenizer::{Model, Result, Token};
+use crate::models::files::{write_files, FileNaming, ModelFile};
+use crate::models::vocab_store::{ids_with_prefix, MappedVocab, SharedVocab};
+use crate::tokenizer::{Model, NBestModel, Result, SamplingModel, Token};
 use crate::utils::cache::{Cache, MAX_LENGTH};
 use std::collections::HashMap;
 
 use ahash::AHashMap;
-use std::convert::TryInto;
 use std::fs::read_to_string;
 use std::path::{Path, PathBuf};
 
@@ -18,6 +19,8 @@ type Vocab = Vec<(String, f64)>;
 /// A `Unigram` model to encode sentences.
 pub struct Unigram {
     token_to_ids: TokenMap,
+    /// The sorted table replacing `token_to_ids`, if any
+    store: Option<SharedVocab>,
     pub(crate) vocab: Vocab,
     cache: Cache<String, Vec<String>>,
     trie: Trie<u8>,
@@ -29,6 +32,7 @@ pub struct Unigram {
     fuse_unk: bool,
     is_optimized: bool,
     byte_fallback: bool,
+    beam_width: Option<usize>,
 }
 impl PartialEq for Unigram {
     fn eq(&self, other: &Self) -> bool {
@@ -45,6 +49,7 @@ impl Clone for Unigram {
             vocab: self.vocab.clone(),
             cache: fresh_cache,
             token_to_ids: self.token_to_ids.clone(),
+            store: self.store.clone(),
             trie: self.trie.clone(),
             min_score: self.min_score,
             unk_id: self.unk_id,
@@ -53,6 +58,7 @@ impl Clone for Unigram {
             fuse_unk: self.fuse_unk,
             is_optimized: self.is_optimized,
             byte_fallback: self.byte_fallback,
+            beam_width: self.beam_width,
         }
     }
 }
@@ -63,6 +69,7 @@ impl std::fmt::Debug for Unigram {
             .field("vocab", &self.vocab.len())
             .field("unk_id", &self.unk_id)
             .field("byte_fallback", &self.byte_fallback)
+            .field("beam_width", &self.beam_width)
             .finish()
     }
 }
@@ -77,6 +84,19 @@ pub enum UnigramError {
     UnkIdNotInVocabulary,
     #[error("Encountered an unknown token but `unk_id` is missing")]
     MissingUnkId,
+    #[error("The `beam_width` must be strictly positive")]
+    InvalidBeamWidth,
+    #[error("The score of the token {token:?} (id {id}) is {score}, but only finite numbers are supported")]
+    InvalidScore {
+        id: usize,
+        token: String,
+        score: f64,
+    },
+}
+
+/// Whether the score can be handled by the lattice and serialized in JSON
+pub(super) fn is_valid_score(score: f64) -> bool {
+    score.is_finite()
 }
 
 impl Default for Unigram {
@@ -93,10 +113,33 @@ impl Unigram {
     /// unk_id, is the index within the vocabulary.
     /// For now `Unigram` *requires* at least `unk` because we might find a never seen char.
     /// Further versions might allow that part to be hidden.
+    /// The scores must be finite, see [`Unigram::from_lenient`] to clamp the other ones
+    /// instead.
     pub fn from(
         vocab: Vec<(String, f64)>,
         unk_id: Option<usize>,
         byte_fallback: bool,
+    ) -> Result<Self> {
+        if let Some((id, (token, score))) = vocab
+            .iter()
+            .enumerate()
+            .find(|(_, (_, score))| !is_valid_score(*score))
+        {
+            return Err(Box::new(UnigramError::InvalidScore {
+                id,
+                token: token.clone(),
+                score: *score,
+            }));
+        }
+        Self::from_unchecked(vocab, unk_id, byte_fallback)
+    }
+
+    /// Create a `Unigram` model without checking the scores, for the intermediate models of
+    /// the trainer where the score of `unk` is `NaN`.
+    pub(super) fn from_unchecked(
+        vocab: Vec<(String, f64)>,
+        unk_id: Option<usize>,
+        byte_fallback: bool,
     ) -> Result<Self> {
         let n = vocab.len();
         let mut token_to_ids: TokenMap = AHashMap::new();
@@ -128,6 +171,7 @@ impl Unigram {
         Ok(Self {
             vocab,
             token_to_ids,
+            store: None,
             trie,
             min_score,
             bos_id,
@@ -137,9 +181,35 @@ impl Unigram {
             cache: Cache::default(),
             is_optimized,
             byte_fallback,
+            beam_width: None,
         })
     }
 
+    /// Create a `Unigram` model like [`Unigram::from`], clamping the scores that aren't
+    /// finite, as found in some converted models:
+    ///  - `NaN` and `-inf` become the lowest finite score minus the penalty given to `unk`,
+    ///    so that these tokens are only used as a last resort.
+    ///  - `+inf` becomes the highest finite score.
+    ///
+    /// This is how the models are deserialized, with a warning when some scores get clamped.
+    pub fn from_lenient(
+        mut vocab: Vec<(String, f64)>,
+        unk_id: Option<usize>,
+        byte_fallback: bool,
+    ) -> Result<Self> {
+        let finite = || vocab.iter().map(|(_, s)| *s).filter(|s| is_valid_score(*s));
+        let lowest = finite().reduce(f64::min).unwrap_or(0.0) - K_UNK_PENALTY;
+        let highest = finite().reduce(f64::max).unwrap_or(0.0);
+        for (_, score) in vocab.iter_mut() {
+            if score.is_nan() || *score == f64::NEG_INFINITY {
+                *score = lowest;
+            } else if *score == f64::INFINITY {
+                *score = highest;
+            }
+        }
+        Self::from(vocab, unk_id, byte_fallback)
+    }
+
     #[cfg(test)]
     pub(super) fn set_fuse_unk(&mut self, fuse_unk: bool) {
         self.fuse_unk = fuse_unk;
@@ -153,10 +223,107 @@ impl Unigram {
     pub fn byte_fallback(&self) -> bool {
         self.byte_fallback
     }
+
+    /// The id of the unknown token, if any
+    pub fn unk_id(&self) -> Option<usize> {
+        self.unk_id
+    }
+
+    /// The maximum number of pieces starting at each position considered during the
+    /// segmentation, if any.
+    pub fn beam_width(&self) -> Option<usize> {
+        self.beam_width
+    }
+
+    /// Bound the number of pieces starting at each position considered during the
+    /// segmentation, to get a predictable latency on extremely long strings without spaces
+    /// (URLs, DNA, ...), where a lot of pieces can start at each position.
+    ///
+    /// The pieces with the best scores of their own are kept, along with the single character
+    /// piece (or `unk`) so that any sentence can still be segmented. This is a heuristic: the
+    /// pieces starting at a position end at different ones, so a piece with a worse score may
+    /// still be part of the best segmentation, which is then missed for a worse one.
+    pub fn set_beam_width(&mut self, beam_width: Option<usize>) -> Result<()> {
+        if beam_width == Some(0) {
+            return Err(Box::new(UnigramError::InvalidBeamWidth));
+        }
+        self.beam_width = beam_width;
+        self.cache = self.cache.fresh();
+        Ok(())
+    }
+
+    /// Replace the map from the tokens to their ids with a table sorted by token, answering
+    /// [`token_ids_with_prefix`](Model::token_ids_with_prefix) without a full scan, at the
+    /// cost of a binary search on each lookup.
+    ///
+    /// The pieces are still held by the vocab and the trie, so this does not make the model
+    /// noticeably smaller.
+    pub fn index_token_prefixes(&mut self) -> Result<()> {
+        if self.store.is_none() {
+            let store = MappedVocab::from_vocab(&self.token_to_ids)?;
+            self.store = Some(SharedVocab::new(store));
+            self.token_to_ids = AHashMap::new();
+        }
+        Ok(())
+    }
     pub(super) fn len(&self) -> usize {
         self.vocab.len()
     }
 
+    /// Call `f` with the length and id of each piece of the vocabulary that starts at
+    /// `starts_at`, within the limits of the `beam_width`. Returns whether a piece made
+    /// of a single character was found.
+    fn for_each_piece<F>(&self, sentence: &str, starts_at: usize, mblen: usize, mut f: F) -> bool
+    where
+        F: FnMut(usize, usize),
+    {
+        let pieces = self
+            .trie
+            .common_prefix_search(sentence.bytes().skip(starts_at))
+            .map(|tok_bytes| {
+                let token = String::from_utf8(tok_bytes).unwrap();
+                (token.len(), self.token_to_id(&token).unwrap() as usize)
+            });
+        match self.beam_width {
+            None => {
+                let mut has_single_node = false;
+                for (length, id) in pieces {
+                    f(length, id);
+                    has_single_node |= length == mblen;
+                }
+                has_single_node
+            }
+            Some(beam_width) => {
+                // Only pruned by the scores of the pieces: ending at different positions,
+                // they lead to paths that can't be compared here, so this may drop a piece of
+                // the best path
+                let score = |(_, id): &(usize, usize)| self.vocab[*id].1;
+                let mut single = None;
+                let mut kept: Vec<(usize, usize)> = Vec::with_capacity(beam_width + 1);
+                for piece in pieces {
+                    if piece.0 == mblen {
+                        single = Some(piece);
+                    }
+                    kept.push(piece);
+                    if kept.len() > beam_width {
+                        let worst = (0..kept.len())
+                            .min_by(|i, j| score(&kept[*i]).total_cmp(&score(&kept[*j])))
+                            .unwrap();
+                        kept.swap_remove(worst);
+                    }
+                }
+                // The single character piece is always kept, for any sentence to be segmented
+                if let Some(single) = single.filter(|single| !kept.contains(single)) {
+                    kept.push(single);
+                }
+                // From the shortest to the longest, like the trie gives them
+                kept.sort_unstable();
+                kept.into_iter().for_each(|(length, id)| f(length, id));
+                single.is_some()
+            }
+        }
+    }
+
     pub(super) fn populate_nodes(&self, lattice: &mut Lattice) {
         let unk_score = self.min_score - K_UNK_PENALTY;
 
@@ -170,24 +337,11 @@ impl Unigram {
                 .unwrap()
                 .len_utf8();
 
-            let mut has_single_node = false;
-
-            for bytes in self
-                .trie
-                .common_prefix_search(lattice.sentence.bytes().skip(begin_pos))
-            {
-                let n = bytes.len();
-                let tok = String::from_utf8(bytes).unwrap();
-                let id = *self.token_to_ids.get(&tok).unwrap();
-
-                let item = &self.vocab[id as usize];
-                assert_eq!(item.0, tok);
-                let score: f64 = item.1;
-                lattice.insert(begin_pos, n, score, id.try_into().unwrap());
-                if !has_single_node && n == mblen {
-                    has_single_node = true;
-                }
-            }
+            let sentence = lattice.sentence;
+            let has_single_node = self.for_each_piece(sentence, begin_pos, mblen, |n, id| {
+                let score: f64 = self.vocab[id].1;
+                lattice.insert(begin_pos, n, score, id);
+            });
 
             if !has_single_node {
                 if let Some(unk_id) = self.unk_id {
@@ -265,30 +419,19 @@ impl Unigram {
         let mut starts_at = 0;
         while starts_at < size {
             let best_path_score_till_here = best_path_ends_at[starts_at].best_path_score;
-            let mut has_single_node = false;
             let mblen = sentence[starts_at..].chars().next().unwrap().len_utf8();
-            for tok_bytes in self
-                .trie
-                .common_prefix_search(sentence.bytes().skip(starts_at))
-            {
-                let key_pos = starts_at + tok_bytes.len();
-                let token: String = String::from_utf8(tok_bytes).unwrap();
-                let target_node = &mut best_path_ends_at[key_pos];
-                let length = key_pos - starts_at;
-                let id = self.token_to_ids.get(&token).unwrap();
-                let score = self.vocab.get(*id as usize).unwrap().1;
+            let has_single_node = self.for_each_piece(sentence, starts_at, mblen, |length, id| {
+                let target_node = &mut best_path_ends_at[starts_at + length];
+                let score = self.vocab[id].1;
                 let candidate_best_path_score = score + best_path_score_till_here;
                 if target_node.starts_at.is_none()
                     || candidate_best_path_score > target_node.best_path_score
                 {
                     target_node.best_path_score = candidate_best_path_score;
                     target_node.starts_at = Some(starts_at);
-                    target_node.id = *id as usize;
-                }
-                if !has_single_node && length == mblen {
-                    has_single_node = true;
+                    target_node.id = id;
                 }
-            }
+            });
             if !has_single_node {
                 let target_node = &mut best_path_ends_at[starts_at + mblen];
                 let candidate_best_path_score = unk_score + best_path_score_till_here;
@@ -331,10 +474,64 @@ impl Unigram {
     fn encode_unoptimized(&self, sentence: &str) -> Result<Vec<String>> {
         let mut lattice = Lattice::from(sentence, self.bos_id, self.eos_id);
         self.populate_nodes(&mut lattice);
+        let path = lattice.viterbi();
+        self.path_to_pieces(&lattice, &path)
+    }
+
+    /// Sample a segmentation of the given sentence, instead of the best one. The lower
+    /// `alpha`, the closer to sampling uniformly amongst all the possible segmentations.
+    ///
+    /// The results are not cached.
+    pub fn encode_sample(&self, sentence: &str, alpha: f64) -> Result<Vec<String>> {
+        if sentence.is_empty() {
+            return Ok(vec![]);
+        }
+        let mut lattice = Lattice::from(sentence, self.bos_id, self.eos_id);
+        self.populate_nodes(&mut lattice);
+        let path = lattice.sample(alpha);
+        self.path_to_pieces(&lattice, &path)
+    }
+
+    /// The `n` best segmentations of the given sentence, best first, with their scores: the
+    /// sum of the log probabilities of their pieces.
+    ///
+    /// The results are not cached.
+    /// ```
+    /// use tokenizers::models::unigram::Unigram;
+    ///
+    /// let pieces = vec![
+    ///     ("<unk>".to_string(), 0.0),
+    ///     ("a".to_string(), -2.0),
+    ///     ("b".to_string(), -2.0),
+    ///     ("ab".to_string(), -1.0),
+    /// ];
+    /// let model = Unigram::from(pieces, Some(0), false).unwrap();
+    /// let nbest = model.encode_nbest("ab", 2).unwrap();
+    /// assert_eq!(nbest[0], (vec!["ab".to_string()], -1.0));
+    /// assert_eq!(nbest[1], (vec!["a".to_string(), "b".to_string()], -4.0));
+    /// ```
+    pub fn encode_nbest(&self, sentence: &str, n: usize) -> Result<Vec<(Vec<String>, f64)>> {
+        if sentence.is_empty() {
+            return Ok(vec![(vec![], 0.0)]);
+        }
+        let mut lattice = Lattice::from(sentence, self.bos_id, self.eos_id);
+        self.populate_nodes(&mut lattice);
+        lattice
+            .nbest(n)
+            .iter()
+            .map(|path| {
+                let score = path.iter().map(|node| node.borrow().score).sum();
+                Ok((self.path_to_pieces(&lattice, path)?, score))
+            })
+            .collect()
+    }
+
+    /// Convert a path of the lattice to the pieces, fusing the unknown ones if needed
+    fn path_to_pieces(&self, lattice: &Lattice, path: &[NodeRef]) -> Result<Vec<String>> {
         if self.fuse_unk {
             let mut results = vec![];
             let mut token = String::new();
-            for node in lattice.viterbi().iter() {
+            for node in path {
                 let item = lattice.piece(&node.borrow());
                 if node.borrow().id == self.unk_id.ok_or(UnigramError::MissingUnkId)? {
                     token.push_str(&item);
@@ -351,12 +548,89 @@ impl Unigram {
             }
             Ok(results)
         } else {
-            Ok(lattice.tokens())
+            Ok(path
+                .iter()
+                .map(|node| lattice.piece(&node.borrow()))
+                .collect())
+        }
+    }
+
+    /// Convert the pieces to tokens, with their ids and offsets
+    fn pieces_to_tokens(&self, str_tokens: Vec<String>) -> Result<Vec<Token>> {
+        let mut offset = 0;
+        let mut tokens = Vec::with_capacity(str_tokens.len());
+        for string in str_tokens {
+            let len = string.len();
+            let offsets = (offset, offset + len);
+            let id: u32 = match self.token_to_id(&string) {
+                Some(id) => id,
+                None => {
+                    if self.byte_fallback {
+                        let byte_tokens: Option<Vec<_>> = string
+                            .bytes()
+                            .map(|byte| -> Option<Token> {
+                                let byte_string = format!("<0x{byte:02X}>");
+                                let id = self.token_to_id(&byte_string);
+                                id.map(|id| Token::new(id, byte_string, (offset, offset + len)))
+                            })
+                            .collect();
+                        if let Some(byte_tokens) = byte_tokens {
+                            for token in byte_tokens {
+                                tokens.push(token);
+                            }
+                            offset += len;
+                            continue;
+                        }
+                    }
+                    self.unk_id.ok_or(UnigramError::MissingUnkId)? as u32
+                }
+            };
+            offset += len;
+            tokens.push(Token::new(id, string, offsets));
         }
+        Ok(tokens)
+    }
+
+    /// Build the segmentation lattice of the given sentence, with the score of each node and
+    /// the path selected by the Viterbi algorithm, to understand why a segmentation won.
+    ///
+    /// Unknown pieces appear as they are in the lattice, they are not fused together.
+    /// ```
+    /// use tokenizers::models::unigram::Unigram;
+    ///
+    /// let pieces = vec![
+    ///     ("<unk>".to_string(), 0.0),
+    ///     ("a".to_string(), -2.0),
+    ///     ("b".to_string(), -2.0),
+    ///     ("ab".to_string(), -1.0),
+    /// ];
+    /// let model = Unigram::from(pieces, Some(0), false).unwrap();
+    /// let lattice = model.lattice("ab");
+    /// // BOS, EOS, then "a", "ab" and "b"
+    /// assert_eq!(lattice.nodes.len(), 5);
+    /// let best = &lattice.nodes[lattice.best_path[0]];
+    /// assert_eq!((best.piece.as_str(), best.score), ("ab", -1.0));
+    /// ```
+    pub fn lattice(&self, sentence: &str) -> LatticeGraph {
+        let mut lattice = Lattice::from(sentence, self.bos_id, self.eos_id);
+        self.populate_nodes(&mut lattice);
+        lattice.to_graph()
+    }
+
+    /// Export the segmentation lattice of the given sentence in the DOT language, to be
+    /// rendered with Graphviz. See [`lattice`](Self::lattice)
+    pub fn lattice_dot(&self, sentence: &str) -> String {
+        self.lattice(sentence).to_dot()
+    }
+
+    /// Export the segmentation lattice of the given sentence as JSON.
+    /// See [`lattice`](Self::lattice)
+    pub fn lattice_json(&self, sentence: &str) -> Result<String> {
+        Ok(serde_json::to_string(&self.lattice(sentence))?)
     }
 
     /// Iterate of vocabulary of the model as a pair of `(token, score)`.
-    pub fn iter(&self) -> UnigramIterator {
+    pub fn iter(&self) -> UnigramIterator<'_> {
         UnigramIterator { model: self, i: 0 }
     }
 
@@ -373,6 +647,18 @@ impl Unigram {
         Ok(serde_json::from_str(&string)?)
     }
 
+    /// Load a `Unigram` model from the content of the `unigram.json` file written by
+    /// [`save`](Model::save)
+    pub fn from_bytes(bytes: &[u8]) -> Result<Unigram> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+
+    /// The content of the `unigram.json` file written by [`save`](Model::save)
+    pub fn files(&self) -> Result<Vec<ModelFile>> {
+        let string = serde_json::to_string_pretty(self)?;
+        Ok(vec![ModelFile::new("unigram.json", string)])
+    }
+
     /// Clears the internal cache
     pub fn clear_cache(&mut self) {
         self.cache.clear();
@@ -409,7 +695,10 @@ impl Model for Unigram {
     type Trainer = UnigramTrainer;
 
     fn get_vocab(&self) -> HashMap<String, u32> {
-        self.token_to_ids.clone().into_iter().collect()
+        match &self.store {
+            Some(store) => store.vocab().into_iter().collect(),
+            None => self.token_to_ids.clone().into_iter().collect(),
+        }
     }
 
     fn get_vocab_size(&self) -> usize {
@@ -417,43 +706,26 @@ impl Model for Unigram {
     }
 
     fn tokenize(&self, sentence: &str) -> Result<Vec<Token>> {
-        let str_tokens = self.encode(sentence)?;
-        let mut offset = 0;
-        let mut tokens = Vec::with_capacity(str_tokens.len());
-        for string in str_tokens {
-            let len = string.len();
-            let offsets = (offset, offset + len);
-            let id: u32 = match self.token_to_ids.get(&string) {
-                Some(id) => *id,
-                None => {
-                    if self.byte_fallback {
-                        let byte_tokens: Option<Vec<_>> = string
-                            .bytes()
-                            .map(|byte| -> Option<Token> {
-                                let byte_string = format!("<0x{byte:02X}>");
-                                let id = self.token_to_ids.get(&byte_string);
-                                id.map(|id| Token::new(*id, byte_string, (offset, offset + len)))
-                            })
-                            .collect();
-                        if let Some(byte_tokens) = byte_tokens {
-                            for token in byte_tokens {
-                                tokens.push(token);
-                            }
-                            offset += len;
-                            continue;
-                        }
-                    }
-                    self.unk_id.ok_or(UnigramError::MissingUnkId)? as u32
-                }
-            };
-            offset += len;
-            tokens.push(Token::new(id, string, offsets));
-        }
-        Ok(tokens)
+        self.pieces_to_tokens(self.encode(sentence)?)
     }
 
     fn token_to_id(&self, token: &str) -> Option<u32> {
-        self.token_to_ids.get(token).copied()
+        match &self.store {
+            Some(store) => store.token_to_id(token),
+            None => self.token_to_ids.get(token).copied(),
+        }
+    }
+
+    fn token_ids_with_prefix(&self, prefix: &[u8]) -> Vec<u32> {
+        match &self.store {
+            Some(store) => store.ids_with_prefix(prefix),
+            None => ids_with_prefix(
+                self.token_to_ids
+                    .iter()
+                    .map(|(token, id)| (token.as_str(), *id)),
+                prefix,
+            ),
+        }
     }
 
     fn id_to_token(&self, id: u32) -> Option<String> {
@@ -461,16 +733,7 @@ impl Model for Unigram {
     }
 
     fn save(&self, folder: &Path, name: Option<&str>) -> Result<Vec<PathBuf>> {
-        let name = match name {
-            Some(name) => format!("{name}-unigram.json"),
-            None => "unigram.json".to_string(),
-        };
-        let mut fullpath = PathBuf::new();
-        fullpath.push(folder);
-        fullpath.push(name);
-        let string = serde_json::to_string_pretty(self)?;
-        std::fs::write(&fullpath, string)?;
-        Ok(vec![fullpath])
+        write_files(&self.files()?, folder, &FileNaming::prefix(name))
     }
 
     fn get_trainer(&self) -> Self::Trainer {
@@ -478,6 +741,24 @@ impl Model for Unigram {
     }
 }
 
+impl SamplingModel for Unigram {
+    /// Tokenize with a sampled segmentation, using the smoothing parameter `alpha`.
+    /// See [`Unigram::encode_sample`]
+    fn tokenize_sampled(&self, sentence: &str, alpha: f64) -> Result<Vec<Token>> {
+        self.pieces_to_tokens(self.encode_sample(sentence, alpha)?)
+    }
+}
+
+impl NBestModel for Unigram {
+    /// See [`Unigram::encode_nbest`]
+    fn tokenize_nbest(&self, sentence: &str, n: usize) -> Result<Vec<(Vec<Token>, f64)>> {
+        self.encode_nbest(sentence, n)?
+            .into_iter()
+            .map(|(pieces, score)| Ok((self.pieces_to_tokens(pieces)?, score)))
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -607,6 +888,66 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_encode_beam_width() {
+        let sentencepieces = vec![
+            ("<unk>".to_string(), 0.0),
+            ("a".to_string(), -1.0),
+            ("b".to_string(), -1.0),
+            ("c".to_string(), -1.0),
+            ("d".to_string(), -1.0),
+            ("ab".to_string(), -1.5),
+            ("abc".to_string(), -1.8),
+            ("cd".to_string(), -0.5),
+        ];
+
+        let mut model = Unigram::from(sentencepieces, Some(0), false).unwrap();
+        assert!(model.set_beam_width(Some(0)).is_err());
+
+        for is_optimized in &[true, false] {
+            model.set_optimized(*is_optimized);
+
+            model.set_beam_width(None).unwrap();
+            assert_eq!(model.encode("abcd").unwrap(), vec!["ab", "cd"]);
+
+            // Only "a" is kept, the best one, while "ab" and "abc" are dropped
+            model.set_beam_width(Some(1)).unwrap();
+            assert_eq!(model.encode("abcd").unwrap(), vec!["a", "b", "cd"]);
+
+            // "a" and "ab" are kept, the longest "abc" is dropped
+            model.set_beam_width(Some(2)).unwrap();
+            assert_eq!(model.encode("abcd").unwrap(), vec!["ab", "cd"]);
+            // Even though "abc" makes the best path, -1.8 against -2.5 for "ab" then "c"
+            assert_eq!(model.encode("abc").unwrap(), vec!["ab", "c"]);
+            model.set_beam_width(None).unwrap();
+            assert_eq!(model.encode("abc").unwrap(), vec!["abc"]);
+        }
+    }
+
+    #[test]
+    fn test_encode_sample() {
+        let sentencepieces = vec![
+            ("<unk>".to_string(), 0.0),
+            ("a".to_string(), -1.0),
+            ("b".to_string(), -1.0),
+            ("ab".to_string(), -0.5),
+        ];
+        let model = Unigram::from(sentencepieces, Some(0), false).unwrap();
+
+        assert!(model.encode_sample("", 0.1).unwrap().is_empty());
+        let mut seen = std::collections::HashSet::new();
+        for _ in 0..100 {
+            let pieces = model.encode_sample("abq", 0.1).unwrap();
+            assert_eq!(pieces.concat(), "abq");
+            seen.insert(pieces);
+        }
+        let expected = [vec!["ab", "q"], vec!["a", "b", "q"]]
+            .iter()
+            .map(|pieces| pieces.iter().map(|p| p.to_string()).collect::<Vec<_>>())
+            .collect();
+        assert_eq!(seen, expected);
+    }
+
     #[test]
     fn test_unigram_bytefallback() {
         // In [97]: processor.encode_as_pieces("⅐⅛⅑ ")
@@ -637,4 +978,65 @@ mod tests {
         let tokens = unigram.tokenize("?é").unwrap();
         assert_eq!(tokens[0].id, 0);
     }
+
+    #[test]
+    fn test_index_token_prefixes() {
+        let sentencepieces = vec![
+            ("<unk>".to_string(), 0.0),
+            ("a".to_string(), -1.0),
+            ("ab".to_string(), -1.0),
+            ("b".to_string(), -2.0),
+            ("abc".to_string(), -3.0),
+            ("<0x63>".to_string(), -4.0),
+        ];
+        let unigram = Unigram::from(sentencepieces, Some(0), true).unwrap();
+        let mut indexed = unigram.clone();
+        indexed.index_token_prefixes().unwrap();
+
+        for sentence in ["abc", "abab", "ca", "é"] {
+            assert_eq!(
+                indexed.tokenize(sentence).unwrap(),
+                unigram.tokenize(sentence).unwrap()
+            );
+        }
+        assert_eq!(indexed.get_vocab(), unigram.get_vocab());
+        assert_eq!(indexed.token_to_id("abc"), Some(4));
+        assert_eq!(indexed.token_ids_with_prefix(b"ab"), vec![2, 4]);
+        assert_eq!(unigram.token_ids_with_prefix(b"ab"), vec![2, 4]);
+        assert_eq!(indexed, unigram);
+    }
+
+    #[test]
+    fn test_invalid_scores() {
+        let pieces = |scores: &[f64]| -> Vec<_> {
+            scores
+                .iter()
+                .enumerate()
+                .map(|(i, s)| (i.to_string(), *s))
+                .collect()
+        };
+        let vocab = pieces(&[
+            0.0,
+            -1.0,
+            f64::NEG_INFINITY,
+            f64::NAN,
+            f64::INFINITY,
+            1e-310,
+        ]);
+        for id in [2, 3, 4] {
+            let mut vocab = vocab.clone();
+            vocab.swap(2, id);
+            let err = Unigram::from(vocab, Some(0), false).unwrap_err();
+            assert!(err.to_string().contains("(id 2)"), "{}", err);
+        }
+
+        let unigram = Unigram::from_lenient(vocab, Some(0), false).unwrap();
+        let scores: Vec<_> = unigram.iter().map(|(_, s)| *s).collect();
+        assert_eq!(scores, vec![0.0, -1.0, -11.0, -11.0, 1e-310, 1e-310]);
+        let data = serde_json::to_string(&unigram).unwrap();
+        assert_eq!(serde_json::from_str::<Unigram>(&data).unwrap(), unigram);
+
+        let unigram = Unigram::from_lenient(pieces(&[f64::NAN]), Some(0), false).unwrap();
+        assert_eq!(unigram.iter().next().unwrap().1, -K_UNK_PENALTY);
+    }
 }
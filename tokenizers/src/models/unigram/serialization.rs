@@ -1,4 +1,5 @@
-use super::model::Unigram;
+use super::model::{is_valid_score, Unigram};
+use log::warn;
 use serde::{
     de::{Error, MapAccess, Visitor},
     ser::SerializeStruct,
@@ -16,6 +17,9 @@ impl Serialize for Unigram {
         model.serialize_field("unk_id", &self.unk_id)?;
         model.serialize_field("vocab", &self.vocab)?;
         model.serialize_field("byte_fallback", &self.byte_fallback())?;
+        if let Some(beam_width) = self.beam_width() {
+            model.serialize_field("beam_width", &beam_width)?;
+        }
 
         model.end()
     }
@@ -28,7 +32,7 @@ impl<'de> Deserialize<'de> for Unigram {
     {
         deserializer.deserialize_struct(
             "Unigram",
-            &["type", "vocab", "unk_id", "byte_fallback"],
+            &["type", "vocab", "unk_id", "byte_fallback", "beam_width"],
             UnigramVisitor,
         )
     }
@@ -46,15 +50,18 @@ impl<'de> Visitor<'de> for UnigramVisitor {
     where
         V: MapAccess<'de>,
     {
-        let mut vocab: Option<Vec<(String, f64)>> = None;
+        // serde_json writes the scores that aren't finite as `null`
+        let mut vocab: Option<Vec<(String, Option<f64>)>> = None;
         let mut unk_id: Option<usize> = None;
         let mut byte_fallback: bool = false;
+        let mut beam_width: Option<usize> = None;
         while let Some(key) = map.next_key::<String>()? {
             match key.as_ref() {
                 "unk_id" => {
                     unk_id = map.next_value()?;
                 }
                 "byte_fallback" => byte_fallback = map.next_value()?,
+                "beam_width" => beam_width = map.next_value()?,
                 "vocab" => vocab = Some(map.next_value()?),
                 "type" => match map.next_value()? {
                     "Unigram" => {}
@@ -69,8 +76,22 @@ impl<'de> Visitor<'de> for UnigramVisitor {
             }
         }
         match (vocab, unk_id, byte_fallback) {
-            (Some(vocab), unk_id, byte_fallback) => Ok(Unigram::from(vocab, unk_id, byte_fallback)
-                .map_err(|err| Error::custom(format!("Unable to load vocab {err:?}")))?),
+            (Some(vocab), unk_id, byte_fallback) => {
+                let vocab: Vec<_> = vocab
+                    .into_iter()
+                    .map(|(token, score)| (token, score.unwrap_or(f64::NAN)))
+                    .collect();
+                let clamped = vocab.iter().filter(|(_, s)| !is_valid_score(*s)).count();
+                if clamped > 0 {
+                    warn!("Clamping the {clamped} Unigram scores that are not finite numbers");
+                }
+                let mut model = Unigram::from_lenient(vocab, unk_id, byte_fallback)
+                    .map_err(|err| Error::custom(format!("Unable to load vocab: {err}")))?;
+                model
+                    .set_beam_width(beam_width)
+                    .map_err(|err| Error::custom(err.to_string()))?;
+                Ok(model)
+            }
             (None, _, _) => Err(Error::custom("Missing vocab")),
         }
     }
@@ -112,4 +133,34 @@ mod test {
 
         assert_eq!(model, reconstructed);
     }
+
+    #[test]
+    fn test_serialization_beam_width() {
+        let vocab = vec![("<unk>".to_string(), 0.0), ("a".to_string(), -0.5)];
+        let mut model = Unigram::from(vocab, Some(0), false).unwrap();
+        let data = serde_json::to_string(&model).unwrap();
+        assert!(!data.contains("beam_width"));
+
+        model.set_beam_width(Some(8)).unwrap();
+        let data = serde_json::to_string(&model).unwrap();
+        let reconstructed: Unigram = serde_json::from_str(&data).unwrap();
+        assert_eq!(reconstructed.beam_width(), Some(8));
+
+        let invalid = data.replace(r#""beam_width":8"#, r#""beam_width":0"#);
+        assert!(serde_json::from_str::<Unigram>(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_serialization_invalid_score() {
+        // The scores that aren't finite are clamped, like the ones written as `null`
+        let data = r#"{"type":"Unigram","unk_id":0,"vocab":[["<unk>",0.0],["a",-2.0],["b",null]]}"#;
+        let model = serde_json::from_str::<Unigram>(data).unwrap();
+        let scores: Vec<_> = model.iter().map(|(_, score)| *score).collect();
+        assert_eq!(scores, vec![0.0, -2.0, -12.0]);
+
+        // The subnormal numbers are valid scores
+        let data = r#"{"type":"Unigram","unk_id":0,"vocab":[["<unk>",0.0],["a",1e-310]]}"#;
+        let model = serde_json::from_str::<Unigram>(data).unwrap();
+        assert_eq!(model.iter().nth(1).unwrap().1, 1e-310);
+    }
 }
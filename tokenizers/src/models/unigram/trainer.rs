@@ -1,7 +1,7 @@
 use crate::models::unigram::{lattice::Lattice, model::Unigram};
 use crate::tokenizer::{AddedToken, Result, Trainer};
 use crate::utils::parallelism::*;
-use crate::utils::progress::{ProgressBar, ProgressStyle};
+use crate::utils::progress::{ProgressBar, ProgressCallback, ProgressStyle, TrainingProgress};
 use ahash::{AHashMap, AHashSet};
 use log::debug;
 use serde::{Deserialize, Serialize};
@@ -65,6 +65,14 @@ pub struct UnigramTrainer {
 
     #[builder(default = "16")]
     pub max_piece_length: usize,
+    /// The minimum frequency a word must have to be used for training
+    #[builder(default = "0")]
+    #[serde(default)]
+    pub min_frequency: u32,
+    /// An optional callback reporting the progress, replacing the progress bar
+    #[builder(default)]
+    #[serde(skip)]
+    pub progress_callback: Option<ProgressCallback>,
     #[builder(default = "1_000_000")]
     seed_size: usize,
     #[builder(default = "AHashMap::new()")]
@@ -84,7 +92,7 @@ impl UnigramTrainer {
 
     /// Setup a progress bar if asked to show progress
     fn setup_progress(&self) -> Option<ProgressBar> {
-        if self.show_progress {
+        if self.show_progress && self.progress_callback.is_none() {
             let p = ProgressBar::new(0);
             p.set_style(
                 ProgressStyle::default_bar()
@@ -97,6 +105,27 @@ impl UnigramTrainer {
         }
     }
 
+    /// Report the progress to the callback, if any. Returns whether the training should go on
+    fn report_progress(
+        &self,
+        phase: &'static str,
+        iteration: usize,
+        total: usize,
+        vocab_size: usize,
+        loss: Option<f64>,
+    ) -> Result<bool> {
+        match &self.progress_callback {
+            Some(callback) => callback.report(&TrainingProgress {
+                phase,
+                iteration,
+                total,
+                vocab_size,
+                loss,
+            }),
+            None => Ok(true),
+        }
+    }
+
     fn is_valid_sentencepiece(&self, char_string: &[char]) -> bool {
         // Checks string length
         // Space not in the substring, numbers, hiragana and more should be taken
@@ -543,6 +572,13 @@ impl UnigramTrainer {
         pieces.push(("<UNK>".into(), f64::NAN));
         pieces.extend(self.make_seed_sentence_pieces(&sentences, &progress));
         self.finalize_progress(&progress, sentences.len());
+        let mut keep_going = self.report_progress(
+            "Suffix array seeds",
+            sentences.len(),
+            sentences.len(),
+            pieces.len(),
+            None,
+        )?;
 
         // Useful to check compatibility with spm.
         debug!(
@@ -567,40 +603,52 @@ impl UnigramTrainer {
         if required_chars.len() as u32 > self.vocab_size {
             return Err(Box::new(UnigramTrainerError::VocabularyTooSmall));
         }
-        let mut new_model = Unigram::from(pieces.clone(), Some(0), false)?;
-        loop {
+        let mut new_model = Unigram::from_unchecked(pieces.clone(), Some(0), false)?;
+        let mut updates = 0;
+        while keep_going {
             // Sub-EM iteration.
             for _iter in 0..self.n_sub_iterations {
                 // Executes E step
-                let (_objective, _num_tokens, expected) = self.run_e_step(&new_model, &sentences);
+                let (objective, _num_tokens, expected) = self.run_e_step(&new_model, &sentences);
 
                 // Executes M step.
                 pieces = self.run_m_step(&pieces, &expected);
-                new_model = Unigram::from(pieces.clone(), Some(0), false)?;
+                new_model = Unigram::from_unchecked(pieces.clone(), Some(0), false)?;
 
                 // Useful comment for checking compatibility with spm
                 debug!(
                     "Em iter={} size={} obj={} num_tokens={} num_tokens/piece={}",
                     _iter,
                     new_model.len(),
-                    _objective,
+                    objective,
                     _num_tokens,
                     _num_tokens as f64 / model.len() as f64
                 );
                 if let Some(p) = &progress {
                     p.inc(1);
                 }
+                updates += 1;
+                keep_going = self.report_progress(
+                    "EM training",
+                    updates,
+                    expected_updates,
+                    new_model.len(),
+                    Some(objective),
+                )?;
+                if !keep_going {
+                    break;
+                }
             } // end of Sub EM iteration
 
             // Stops the iteration when the size of sentences reaches to the
             // desired symbol size.
-            if pieces.len() <= desired_vocab_size {
+            if !keep_going || pieces.len() <= desired_vocab_size {
                 break;
             }
 
             // Prunes pieces.
             pieces = self.prune_sentence_pieces(&new_model, &pieces, &sentences);
-            new_model = Unigram::from(pieces.clone(), Some(0), false)?;
+            new_model = Unigram::from_unchecked(pieces.clone(), Some(0), false)?;
         }
         self.finalize_progress(&progress, expected_updates);
 
@@ -616,7 +664,12 @@ impl Trainer for UnigramTrainer {
 
     /// Train a Unigram model
     fn train(&self, model: &mut Unigram) -> Result<Vec<AddedToken>> {
-        let sentences: Vec<_> = self.words.iter().map(|(s, i)| (s.to_owned(), *i)).collect();
+        let sentences: Vec<_> = self
+            .words
+            .iter()
+            .filter(|(_, count)| **count >= self.min_frequency)
+            .map(|(s, i)| (s.to_owned(), *i))
+            .collect();
         self.do_train(sentences, model)
     }
 
@@ -660,8 +713,10 @@ impl Trainer for UnigramTrainer {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::Model;
     use assert_approx_eq::assert_approx_eq;
     use std::iter::FromIterator;
+    use std::sync::{Arc, Mutex};
 
     #[test]
     fn test_unigram_chars() {
@@ -812,6 +867,72 @@ mod tests {
         assert_eq!(pieces.next(), Some(&("[CLS]".into(), 0.0)));
     }
 
+    #[test]
+    fn test_progress_callback() {
+        let reports = Arc::new(Mutex::new(vec![]));
+        let reports_ = reports.clone();
+        let trainer = UnigramTrainerBuilder::default()
+            .show_progress(false)
+            .progress_callback(Some(ProgressCallback::new(move |p| {
+                reports_.lock().unwrap().push(p.clone());
+                true
+            })))
+            .build()
+            .unwrap();
+
+        let mut unigram = Unigram::default();
+        trainer
+            .do_train(vec![("The".into(), 12), ("are".into(), 11)], &mut unigram)
+            .unwrap();
+
+        let reports = reports.lock().unwrap();
+        assert_eq!(reports[0].phase, "Suffix array seeds");
+        assert_eq!(reports.len(), 1 + trainer.n_sub_iterations as usize);
+        for (i, report) in reports[1..].iter().enumerate() {
+            assert_eq!(report.phase, "EM training");
+            assert_eq!(report.iteration, i + 1);
+            assert!(report.loss.is_some());
+        }
+
+        // Stop after the first EM iteration
+        let calls = Arc::new(Mutex::new(0));
+        let calls_ = calls.clone();
+        let trainer = UnigramTrainerBuilder::default()
+            .show_progress(false)
+            .progress_callback(Some(ProgressCallback::new(move |p| {
+                *calls_.lock().unwrap() += 1;
+                p.phase != "EM training"
+            })))
+            .build()
+            .unwrap();
+
+        let mut unigram = Unigram::default();
+        trainer
+            .do_train(vec![("The".into(), 12), ("are".into(), 11)], &mut unigram)
+            .unwrap();
+        assert_eq!(*calls.lock().unwrap(), 2);
+        assert!(unigram.token_to_id("e").is_some());
+    }
+
+    #[test]
+    fn test_min_frequency() {
+        let mut trainer = UnigramTrainerBuilder::default()
+            .show_progress(false)
+            .min_frequency(2)
+            .build()
+            .unwrap();
+        trainer
+            .feed(["The are", "are"].iter(), |s| {
+                Ok(s.split(' ').map(|w| w.to_owned()).collect())
+            })
+            .unwrap();
+
+        let mut unigram = Unigram::default();
+        trainer.train(&mut unigram).unwrap();
+        assert!(unigram.token_to_id("a").is_some());
+        assert!(unigram.token_to_id("T").is_none());
+    }
+
     #[test]
     fn test_to_log_prob() {
         let mut a = vec![("".to_string(), 1.0), ("".to_string(), 2.0)];
@@ -0,0 +1,424 @@
+//! Storage backends for the vocab of the models.
+//!
+//! The models keep their vocab in hash maps by default. A [`VocabStore`] can be used
+//! instead, to keep a very large vocab out of the heap: [`MappedVocab`] reads it in place
+//! from a memory-mapped file, whose pages are only loaded on access and shared by every
+//! process using the same file. It can also be built in memory, where its table sorted by
+//! token answers the prefix queries without a full scan.
+use crate::tokenizer::Result;
+use crate::utils::mmap::{read_u32, MappedFile};
+use ahash::AHashMap;
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::ops::Deref;
+use std::path::Path;
+use std::sync::Arc;
+
+const MAGIC: &[u8; 8] = b"TKZRVOC1";
+const INVALID: &str = "Invalid vocab table";
+
+/// Compute `base + index * size`, if it doesn't overflow
+pub(crate) fn at(base: usize, index: usize, size: usize) -> Option<usize> {
+    index.checked_mul(size)?.checked_add(base)
+}
+
+/// Append `word` as a little-endian `u32`
+pub(crate) fn push_u32(out: &mut Vec<u8>, word: usize) -> Result<()> {
+    let word =
+        u32::try_from(word).map_err(|_| "The vocab is too large to be written as a table")?;
+    out.extend(word.to_le_bytes());
+    Ok(())
+}
+
+/// The ids of the `(token, id)` pairs whose token starts with `prefix`, in ascending order
+pub(crate) fn ids_with_prefix<'a>(
+    vocab: impl Iterator<Item = (&'a str, u32)>,
+    prefix: &[u8],
+) -> Vec<u32> {
+    let mut ids = vocab
+        .filter(|(token, _)| token.as_bytes().starts_with(prefix))
+        .map(|(_, id)| id)
+        .collect::<Vec<_>>();
+    ids.sort_unstable();
+    ids
+}
+
+/// The storage of a vocab, mapping the tokens to their ids and back
+pub trait VocabStore: Send + Sync {
+    /// The id of the given token
+    fn token_to_id(&self, token: &str) -> Option<u32>;
+    /// The token of the given id
+    fn id_to_token(&self, id: u32) -> Option<&str>;
+    /// The number of tokens
+    fn len(&self) -> usize;
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Iterate over the `(token, id)` pairs, in any order
+    fn iter(&self) -> Box<dyn Iterator<Item = (&str, u32)> + '_>;
+    /// The ids of the tokens starting with the given bytes, in ascending order
+    fn ids_with_prefix(&self, prefix: &[u8]) -> Vec<u32> {
+        ids_with_prefix(self.iter(), prefix)
+    }
+}
+
+/// A [`VocabStore`] shared between the clones of a model
+#[derive(Clone)]
+pub struct SharedVocab(Arc<dyn VocabStore>);
+
+impl SharedVocab {
+    pub fn new<S: VocabStore + 'static>(store: S) -> Self {
+        Self(Arc::new(store))
+    }
+
+    /// Build the owned vocab, indexed by token
+    pub(crate) fn vocab(&self) -> AHashMap<String, u32> {
+        self.iter()
+            .map(|(token, id)| (token.to_owned(), id))
+            .collect()
+    }
+
+    /// Build the owned vocab, indexed by id
+    pub(crate) fn vocab_r(&self) -> AHashMap<u32, String> {
+        self.iter()
+            .map(|(token, id)| (id, token.to_owned()))
+            .collect()
+    }
+}
+
+impl Deref for SharedVocab {
+    type Target = dyn VocabStore;
+
+    fn deref(&self) -> &Self::Target {
+        self.0.as_ref()
+    }
+}
+
+impl std::fmt::Debug for SharedVocab {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SharedVocab")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+impl PartialEq for SharedVocab {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+impl Eq for SharedVocab {}
+
+/// A vocab read in place from the table of a memory-mapped file. Nothing is indexed when
+/// loading: the table is sorted, and searched on each lookup.
+///
+/// All the integers of the table are little-endian `u32`. It is made of:
+///   - the number of tokens, and the number of ids (the largest id + 1),
+///   - the ids, sorted by the bytes of their token,
+///   - the offsets of the token of each id in the strings, with a final one for the end.
+///     An id with an empty token is a hole in the vocab,
+///   - the strings of the tokens, concatenated by id, padded to a multiple of 4 bytes.
+#[derive(Clone)]
+pub struct MappedVocab {
+    file: Arc<MappedFile>,
+    start: usize,
+    len: usize,
+    ids: usize,
+    sorted_ids: usize,
+    offsets: usize,
+    strings: usize,
+    end: usize,
+}
+
+impl MappedVocab {
+    /// Open a vocab file written by [`MappedVocab::write_file`].
+    ///
+    /// # Safety
+    ///
+    /// The file is memory mapped: it must not be modified, nor truncated, while the vocab or
+    /// any of its clones is in use. A change of its content is undefined behavior, and reading
+    /// past a truncation kills the process with a `SIGBUS`.
+    pub unsafe fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        // SAFETY: The caller upholds the contract of the mapped files
+        let file = Arc::new(unsafe { MappedFile::open(path) }?);
+        if !file.starts_with(MAGIC) {
+            return Err(format!("{INVALID}: the magic bytes are missing").into());
+        }
+        let vocab = Self::parse(file.clone(), MAGIC.len())?;
+        if vocab.end() != file.len() {
+            return Err(INVALID.into());
+        }
+        Ok(vocab)
+    }
+
+    /// Build the table of the given vocab in memory
+    pub fn from_vocab(vocab: &AHashMap<String, u32>) -> Result<Self> {
+        let vocab_r = vocab
+            .iter()
+            .map(|(token, id)| (*id, token.clone()))
+            .collect();
+        let mut content = vec![];
+        Self::write(&vocab_r, &mut content)?;
+        Self::parse(Arc::new(MappedFile::from(content)), 0)
+    }
+
+    /// Write the given vocab to a file, to be opened with [`MappedVocab::from_file`]
+    pub fn write_file<P: AsRef<Path>>(vocab: &AHashMap<String, u32>, path: P) -> Result<()> {
+        let vocab_r = vocab
+            .iter()
+            .map(|(token, id)| (*id, token.clone()))
+            .collect();
+        let mut content = MAGIC.to_vec();
+        Self::write(&vocab_r, &mut content)?;
+        let mut file = BufWriter::new(File::create(path)?);
+        file.write_all(&content)?;
+        file.flush()?;
+        Ok(())
+    }
+
+    /// Read the table starting at `start` in `file`, checking that it fits in it
+    pub(crate) fn parse(file: Arc<MappedFile>, start: usize) -> Result<Self> {
+        let word = |offset: Option<usize>| {
+            offset
+                .and_then(|offset| read_u32(&file, offset))
+                .map(|word| word as usize)
+                .ok_or(INVALID)
+        };
+        let len = word(Some(start))?;
+        let ids = word(at(start, 1, 4))?;
+        let sorted_ids = at(start, 2, 4).ok_or(INVALID)?;
+        let offsets = at(sorted_ids, len, 4).ok_or(INVALID)?;
+        let strings = at(offsets, ids, 4)
+            .and_then(|end| end.checked_add(4))
+            .ok_or(INVALID)?;
+        let strings_len = word(at(offsets, ids, 4))?;
+        let end = strings
+            .checked_add(strings_len.next_multiple_of(4))
+            .ok_or(INVALID)?;
+        if end > file.len() {
+            return Err(INVALID.into());
+        }
+
+        Ok(Self {
+            file,
+            start,
+            len,
+            ids,
+            sorted_ids,
+            offsets,
+            strings,
+            end,
+        })
+    }
+
+    /// Write the table of the given vocab, in the format read by `parse`
+    pub(crate) fn write(vocab_r: &AHashMap<u32, String>, out: &mut Vec<u8>) -> Result<()> {
+        let ids = vocab_r.keys().max().map_or(0, |max| *max as usize + 1);
+        let mut sorted = vocab_r
+            .iter()
+            .filter(|(_, token)| !token.is_empty())
+            .collect::<Vec<_>>();
+        sorted.sort_unstable_by(|(_, l), (_, r)| l.as_bytes().cmp(r.as_bytes()));
+
+        push_u32(out, sorted.len())?;
+        push_u32(out, ids)?;
+        for (id, _) in &sorted {
+            push_u32(out, **id as usize)?;
+        }
+        let mut offset = 0;
+        for id in 0..ids as u32 {
+            push_u32(out, offset)?;
+            offset += vocab_r.get(&id).map_or(0, String::len);
+        }
+        push_u32(out, offset)?;
+        let strings = out.len();
+        for id in 0..ids as u32 {
+            if let Some(token) = vocab_r.get(&id) {
+                out.extend(token.as_bytes());
+            }
+        }
+        out.resize(strings + offset.next_multiple_of(4), 0);
+        Ok(())
+    }
+
+    /// The offset right after the table
+    pub(crate) fn end(&self) -> usize {
+        self.end
+    }
+
+    fn word(&self, offset: usize) -> u32 {
+        // Every offset we read from has been checked in `parse`
+        read_u32(&self.file, offset).unwrap_or_default()
+    }
+
+    fn token_bytes(&self, id: u32) -> Option<&[u8]> {
+        let id = id as usize;
+        if id >= self.ids {
+            return None;
+        }
+        let start = self.strings + self.word(self.offsets + id * 4) as usize;
+        let end = self.strings + self.word(self.offsets + id * 4 + 4) as usize;
+        self.file.get(start..end).filter(|token| !token.is_empty())
+    }
+
+    /// The id at `index` in the ids sorted by token, and its token
+    fn sorted_at(&self, index: usize) -> Option<(u32, &[u8])> {
+        let id = self.word(self.sorted_ids + index * 4);
+        Some((id, self.token_bytes(id)?))
+    }
+
+    /// The index in the ids sorted by token of the first token not lower than `bytes`
+    fn lower_bound(&self, bytes: &[u8]) -> usize {
+        let (mut low, mut high) = (0, self.len);
+        while low < high {
+            let middle = low + (high - low) / 2;
+            match self.sorted_at(middle) {
+                Some((_, token)) if token < bytes => low = middle + 1,
+                _ => high = middle,
+            }
+        }
+        low
+    }
+}
+
+impl VocabStore for MappedVocab {
+    fn token_to_id(&self, token: &str) -> Option<u32> {
+        self.sorted_at(self.lower_bound(token.as_bytes()))
+            .filter(|(_, other)| *other == token.as_bytes())
+            .map(|(id, _)| id)
+    }
+
+    fn id_to_token(&self, id: u32) -> Option<&str> {
+        std::str::from_utf8(self.token_bytes(id)?).ok()
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = (&str, u32)> + '_> {
+        Box::new((0..self.ids as u32).filter_map(move |id| Some((self.id_to_token(id)?, id))))
+    }
+
+    fn ids_with_prefix(&self, prefix: &[u8]) -> Vec<u32> {
+        let mut ids = (self.lower_bound(prefix)..self.len)
+            .map_while(|index| self.sorted_at(index))
+            .take_while(|(_, token)| token.starts_with(prefix))
+            .map(|(id, _)| id)
+            .collect::<Vec<_>>();
+        ids.sort_unstable();
+        ids
+    }
+}
+
+impl PartialEq for MappedVocab {
+    fn eq(&self, other: &Self) -> bool {
+        self.file[self.start..self.end] == other.file[other.start..other.end]
+    }
+}
+
+impl std::fmt::Debug for MappedVocab {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MappedVocab")
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn lookups() {
+        // With a hole at id 3
+        let vocab: AHashMap<String, u32> = [("b", 0), ("a", 1), ("ab", 2), ("é", 4)]
+            .iter()
+            .map(|(token, id)| (token.to_string(), *id))
+            .collect();
+        let file = NamedTempFile::new().unwrap();
+        MappedVocab::write_file(&vocab, file.path()).unwrap();
+        // SAFETY: The temporary file is not modified while mapped
+        let mapped = unsafe { MappedVocab::from_file(file.path()) }.unwrap();
+
+        assert_eq!(mapped.len(), 4);
+        for (token, id) in &vocab {
+            assert_eq!(mapped.token_to_id(token), Some(*id));
+            assert_eq!(mapped.id_to_token(*id), Some(token.as_str()));
+        }
+        assert_eq!(mapped.token_to_id("c"), None);
+        assert_eq!(mapped.token_to_id(""), None);
+        assert_eq!(mapped.id_to_token(3), None);
+        assert_eq!(mapped.id_to_token(5), None);
+        assert_eq!(SharedVocab::new(mapped).vocab(), vocab);
+    }
+
+    #[test]
+    fn prefix_queries() {
+        let vocab: AHashMap<String, u32> = [("ab", 0), ("a", 1), ("abc", 2), ("b", 3), ("é", 4)]
+            .iter()
+            .map(|(token, id)| (token.to_string(), *id))
+            .collect();
+        let mapped = MappedVocab::from_vocab(&vocab).unwrap();
+        assert_eq!(mapped.token_to_id("abc"), Some(2));
+        assert_eq!(mapped.ids_with_prefix(b"a"), vec![0, 1, 2]);
+        assert_eq!(mapped.ids_with_prefix(b"ab"), vec![0, 2]);
+        assert_eq!(mapped.ids_with_prefix(b"c"), Vec::<u32>::new());
+        assert_eq!(mapped.ids_with_prefix(&"é".as_bytes()[..1]), vec![4]);
+        assert_eq!(mapped.ids_with_prefix(b""), vec![0, 1, 2, 3, 4]);
+
+        // The same as the default implementation
+        struct Store(AHashMap<String, u32>);
+        impl VocabStore for Store {
+            fn token_to_id(&self, token: &str) -> Option<u32> {
+                self.0.get(token).copied()
+            }
+            fn id_to_token(&self, _: u32) -> Option<&str> {
+                None
+            }
+            fn len(&self) -> usize {
+                self.0.len()
+            }
+            fn iter(&self) -> Box<dyn Iterator<Item = (&str, u32)> + '_> {
+                Box::new(self.0.iter().map(|(token, id)| (token.as_str(), *id)))
+            }
+        }
+        let store = Store(vocab);
+        for prefix in ["", "a", "ab", "c", "é"] {
+            assert_eq!(
+                store.ids_with_prefix(prefix.as_bytes()),
+                mapped.ids_with_prefix(prefix.as_bytes())
+            );
+        }
+    }
+
+    #[test]
+    fn empty() {
+        let file = NamedTempFile::new().unwrap();
+        MappedVocab::write_file(&AHashMap::new(), file.path()).unwrap();
+        // SAFETY: The temporary file is not modified while mapped
+        let mapped = unsafe { MappedVocab::from_file(file.path()) }.unwrap();
+        assert!(mapped.is_empty());
+        assert_eq!(mapped.token_to_id("a"), None);
+        assert_eq!(mapped.iter().count(), 0);
+    }
+
+    #[test]
+    fn invalid_files() {
+        let file = NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), b"{}").unwrap();
+        // SAFETY: The temporary file is only modified while not mapped
+        assert!(unsafe { MappedVocab::from_file(file.path()) }
+            .unwrap_err()
+            .to_string()
+            .contains("the magic bytes are missing"));
+
+        let vocab = [("a".to_string(), 0)].iter().cloned().collect();
+        MappedVocab::write_file(&vocab, file.path()).unwrap();
+        let content = std::fs::read(file.path()).unwrap();
+        std::fs::write(file.path(), &content[..content.len() - 1]).unwrap();
+        assert!(unsafe { MappedVocab::from_file(file.path()) }.is_err());
+    }
+}
@@ -1,10 +1,11 @@
+use super::files::{write_files, FileNaming, ModelFile};
+use super::vocab_store::{ids_with_prefix, SharedVocab};
 use super::OrderedVocabIter;
 use crate::tokenizer::{Model, Result, Token};
 use ahash::AHashMap;
 use serde_json::Value;
+use std::borrow::Cow;
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufReader, Read, Write};
 use std::path::{Path, PathBuf};
 
 mod serialization;
@@ -26,6 +27,7 @@ pub enum Error {
 struct Config {
     files: Option<String>,
     vocab: AHashMap<String, u32>,
+    store: Option<SharedVocab>,
     unk_token: String,
 }
 
@@ -41,6 +43,7 @@ impl Default for WordLevelBuilder {
             config: Config {
                 files: None,
                 vocab: AHashMap::new(),
+                store: None,
                 unk_token: String::from("<unk>"),
             },
         }
@@ -67,6 +70,13 @@ impl WordLevelBuilder {
         self
     }
 
+    /// Set a storage for the vocab, used in place of the vocab mapping.
+    #[must_use]
+    pub fn vocab_store(mut self, store: SharedVocab) -> Self {
+        self.config.store = Some(store);
+        self
+    }
+
     /// The the `UNK` token for the vocab.
     #[must_use]
     pub fn unk_token(mut self, unk_token: String) -> Self {
@@ -79,6 +89,9 @@ impl WordLevelBuilder {
         if let Some(vocab) = self.config.files {
             self.config.vocab = WordLevel::read_file(&vocab)?;
         }
+        if self.config.store.is_some() {
+            self.config.vocab = AHashMap::new();
+        }
 
         let vocab_r = self
             .config
@@ -90,6 +103,7 @@ impl WordLevelBuilder {
         Ok(WordLevel {
             vocab: self.config.vocab,
             vocab_r,
+            store: self.config.store,
             unk_token: self.config.unk_token,
         })
     }
@@ -99,6 +113,8 @@ impl WordLevelBuilder {
 pub struct WordLevel {
     vocab: AHashMap<String, u32>,
     vocab_r: AHashMap<u32, String>,
+    /// The storage of the vocab, when it is used in place of the maps
+    store: Option<SharedVocab>,
     pub unk_token: String,
 }
 
@@ -106,7 +122,7 @@ impl std::fmt::Debug for WordLevel {
     fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
         fmt.debug_struct("WordLevel")
             .field("unk_token", &self.unk_token)
-            .field("vocab", &self.vocab.len())
+            .field("vocab", &self.get_vocab_size())
             .finish()
     }
 }
@@ -117,13 +133,13 @@ impl WordLevel {
     }
 
     pub fn read_file(vocab_path: &str) -> Result<Vocab> {
-        let vocab_file = File::open(vocab_path)?;
-        let mut vocab_file = BufReader::new(vocab_file);
-        let mut buffer = String::new();
-        let mut vocab = AHashMap::new();
+        Self::read_bytes(&std::fs::read(vocab_path)?)
+    }
 
-        vocab_file.read_to_string(&mut buffer)?;
-        let json: Value = serde_json::from_str(&buffer)?;
+    /// Extract the vocab from the content of a `vocab.json`
+    pub fn read_bytes(vocab_json: &[u8]) -> Result<Vocab> {
+        let mut vocab = AHashMap::new();
+        let json: Value = serde_json::from_slice(vocab_json)?;
 
         match json {
             Value::Object(m) => {
@@ -139,11 +155,34 @@ impl WordLevel {
         Ok(vocab)
     }
 
+    /// The content of the `vocab.json` file written by [`save`](Model::save)
+    pub fn files(&self) -> Result<Vec<ModelFile>> {
+        let vocab_r = self.owned_vocab_r();
+        let order_vocab_iter = OrderedVocabIter::new(&vocab_r);
+        let serialized = serde_json::to_string(&order_vocab_iter)?;
+        Ok(vec![ModelFile::new("vocab.json", serialized)])
+    }
+
     /// Initialize a WordLevel model from vocab and merges file.
     pub fn from_file(vocab_path: &str, unk_token: String) -> Result<WordLevel> {
         let vocab = WordLevel::read_file(vocab_path)?;
         Self::builder().vocab(vocab).unk_token(unk_token).build()
     }
+
+    /// Use the given storage for the vocab, in place of the maps
+    pub(crate) fn set_vocab_store(&mut self, store: SharedVocab) {
+        self.vocab = AHashMap::new();
+        self.vocab_r = AHashMap::new();
+        self.store = Some(store);
+    }
+
+    /// The reversed vocab, built from the storage if there is one
+    pub(crate) fn owned_vocab_r(&self) -> Cow<'_, AHashMap<u32, String>> {
+        match &self.store {
+            Some(store) => Cow::Owned(store.vocab_r()),
+            None => Cow::Borrowed(&self.vocab_r),
+        }
+    }
 }
 
 impl Default for WordLevel {
@@ -151,6 +190,7 @@ impl Default for WordLevel {
         Self {
             vocab: AHashMap::new(),
             vocab_r: AHashMap::new(),
+            store: None,
             unk_token: String::from("<unk>"),
         }
     }
@@ -160,13 +200,13 @@ impl Model for WordLevel {
     type Trainer = WordLevelTrainer;
 
     fn tokenize(&self, token: &str) -> Result<Vec<Token>> {
-        if let Some(&id) = self.vocab.get(token) {
+        if let Some(id) = self.token_to_id(token) {
             Ok(vec![Token {
                 id,
                 value: token.to_owned(),
                 offsets: (0, token.len()),
             }])
-        } else if let Some(&unk_id) = self.vocab.get(&self.unk_token) {
+        } else if let Some(unk_id) = self.token_to_id(&self.unk_token) {
             Ok(vec![Token {
                 id: unk_id,
                 value: self.unk_token.to_owned(),
@@ -178,37 +218,45 @@ impl Model for WordLevel {
     }
 
     fn token_to_id(&self, token: &str) -> Option<u32> {
-        self.vocab.get(token).copied()
+        match &self.store {
+            Some(store) => store.token_to_id(token),
+            None => self.vocab.get(token).copied(),
+        }
     }
 
     fn id_to_token(&self, id: u32) -> Option<String> {
-        self.vocab_r.get(&id).cloned()
+        match &self.store {
+            Some(store) => store.id_to_token(id).map(str::to_owned),
+            None => self.vocab_r.get(&id).cloned(),
+        }
+    }
+
+    fn token_ids_with_prefix(&self, prefix: &[u8]) -> Vec<u32> {
+        match &self.store {
+            Some(store) => store.ids_with_prefix(prefix),
+            None => ids_with_prefix(
+                self.vocab.iter().map(|(token, id)| (token.as_str(), *id)),
+                prefix,
+            ),
+        }
     }
 
     fn get_vocab(&self) -> HashMap<String, u32> {
-        self.vocab.clone().into_iter().collect()
+        match &self.store {
+            Some(store) => store.vocab().into_iter().collect(),
+            None => self.vocab.clone().into_iter().collect(),
+        }
     }
 
     fn get_vocab_size(&self) -> usize {
-        self.vocab.keys().len()
+        match &self.store {
+            Some(store) => store.len(),
+            None => self.vocab.keys().len(),
+        }
     }
 
     fn save(&self, folder: &Path, name: Option<&str>) -> Result<Vec<PathBuf>> {
-        let vocab_file_name = match name {
-            Some(name) => format!("{name}-vocab.json"),
-            None => "vocab.json".to_string(),
-        };
-
-        // Write vocab.json
-        let vocab_path: PathBuf = [folder, Path::new(vocab_file_name.as_str())]
-            .iter()
-            .collect();
-        let mut vocab_file = File::create(&vocab_path)?;
-        let order_vocab_iter = OrderedVocabIter::new(&self.vocab_r);
-        let serialized = serde_json::to_string(&order_vocab_iter)?;
-        vocab_file.write_all(serialized.as_bytes())?;
-
-        Ok(vec![vocab_path])
+        write_files(&self.files()?, folder, &FileNaming::prefix(name))
     }
 
     fn get_trainer(&self) -> Self::Trainer {
@@ -219,6 +267,7 @@ impl Model for WordLevel {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::vocab_store::MappedVocab;
 
     #[test]
     fn test_tokenize_unk() {
@@ -248,4 +297,40 @@ mod tests {
         let error = wordlevel.tokenize("c").err().unwrap();
         assert!(error.is::<Error>());
     }
+
+    #[test]
+    fn test_vocab_store() {
+        let vocab: Vocab = [("<unk>".into(), 0), ("a".into(), 1), ("b".into(), 2)]
+            .iter()
+            .cloned()
+            .collect();
+        let file = tempfile::NamedTempFile::new().unwrap();
+        MappedVocab::write_file(&vocab, file.path()).unwrap();
+        // SAFETY: The temporary file is not modified while mapped
+        let store = SharedVocab::new(unsafe { MappedVocab::from_file(file.path()) }.unwrap());
+        let stored = WordLevelBuilder::default()
+            .vocab_store(store)
+            .unk_token("<unk>".to_string())
+            .build()
+            .unwrap();
+        let wordlevel = WordLevelBuilder::default()
+            .vocab(vocab)
+            .unk_token("<unk>".to_string())
+            .build()
+            .unwrap();
+
+        for token in ["a", "b", "c"] {
+            assert_eq!(
+                stored.tokenize(token).unwrap(),
+                wordlevel.tokenize(token).unwrap()
+            );
+        }
+        assert_eq!(stored.id_to_token(2), Some("b".into()));
+        assert_eq!(stored.get_vocab(), wordlevel.get_vocab());
+        assert_eq!(stored.get_vocab_size(), 3);
+        assert_eq!(
+            serde_json::to_string(&stored).unwrap(),
+            serde_json::to_string(&wordlevel).unwrap()
+        );
+    }
 }
@@ -1,5 +1,6 @@
 use super::WordLevel;
 use crate::utils::parallelism::*;
+use crate::utils::progress::{ProgressCallback, TrainingProgress};
 use crate::{AddedToken, Result, Trainer};
 use ahash::AHashMap;
 use serde::{Deserialize, Serialize};
@@ -20,6 +21,10 @@ pub struct WordLevelTrainer {
     /// A list of special tokens that the model should know of
     #[builder(default)]
     pub special_tokens: Vec<AddedToken>,
+    /// An optional callback reporting the progress
+    #[builder(default)]
+    #[serde(skip)]
+    pub progress_callback: Option<ProgressCallback>,
 
     #[builder(default, private)]
     words: AHashMap<String, u64>,
@@ -73,9 +78,20 @@ impl WordLevelTrainer {
             )
             .build()?;
 
+        if let Some(callback) = &self.progress_callback {
+            callback.report_single_phase(&TrainingProgress {
+                phase: "Compute vocabulary",
+                iteration: 1,
+                total: 1,
+                vocab_size: word_level.vocab.len(),
+                loss: None,
+            })?;
+        }
+
         // Transfer the vocab
         model.vocab = word_level.vocab;
         model.vocab_r = word_level.vocab_r;
+        model.store = None;
 
         Ok(self.special_tokens.clone())
     }
@@ -179,4 +195,30 @@ mod tests {
 
         assert_eq!(model.vocab, expected_vocab);
     }
+
+    #[test]
+    fn test_cannot_stop_early() {
+        let word_counts: AHashMap<String, u64> = [("the".into(), 2), ("roses".into(), 1)]
+            .iter()
+            .cloned()
+            .collect();
+        let trainer = WordLevelTrainer::builder()
+            .progress_callback(Some(ProgressCallback::new(|_| false)))
+            .build()
+            .unwrap();
+
+        let mut model = WordLevel::default();
+        let err = trainer.do_train(&word_counts, &mut model).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("Cannot stop the training early"));
+        assert!(model.vocab.is_empty());
+
+        let trainer = WordLevelTrainer::builder()
+            .progress_callback(Some(ProgressCallback::new(|_| true)))
+            .build()
+            .unwrap();
+        trainer.do_train(&word_counts, &mut model).unwrap();
+        assert_eq!(model.vocab.len(), 2);
+    }
 }
@@ -2,14 +2,14 @@
 //! model.
 
 use crate::models::bpe::BPE;
+use crate::models::files::{write_files, FileNaming, ModelFile};
+use crate::models::vocab_store::{ids_with_prefix, MappedVocab, SharedVocab};
 use crate::tokenizer::{Model, Result, Token};
 use ahash::AHashMap;
 use std::collections::HashMap;
 use std::{
     borrow::Cow,
-    fs::File,
-    io::prelude::*,
-    io::{BufRead, BufReader},
+    io::BufRead,
     path::{Path, PathBuf},
 };
 
@@ -29,8 +29,11 @@ type VocabR = AHashMap<u32, String>;
 struct Config {
     files: Option<String>,
     vocab: Vocab,
+    store: Option<SharedVocab>,
+    compact_vocab: bool,
     unk_token: String,
     continuing_subword_prefix: String,
+    end_of_word_suffix: Option<String>,
     max_input_chars_per_word: usize,
 }
 
@@ -45,8 +48,11 @@ impl Default for WordPieceBuilder {
             config: Config {
                 files: None,
                 vocab: AHashMap::new(),
+                store: None,
+                compact_vocab: false,
                 unk_token: String::from("[UNK]"),
                 continuing_subword_prefix: String::from("##"),
+                end_of_word_suffix: None,
                 max_input_chars_per_word: 100,
             },
         }
@@ -73,6 +79,21 @@ impl WordPieceBuilder {
         self
     }
 
+    /// Set a storage for the vocab, used in place of the vocab mapping.
+    #[must_use]
+    pub fn vocab_store(mut self, store: SharedVocab) -> Self {
+        self.config.store = Some(store);
+        self
+    }
+
+    /// Keep the vocab in a table sorted by token instead of the maps. The table answers the
+    /// prefix queries without a full scan, at the cost of a binary search on each lookup.
+    #[must_use]
+    pub fn compact_vocab(mut self, compact_vocab: bool) -> Self {
+        self.config.compact_vocab = compact_vocab;
+        self
+    }
+
     /// The the `UNK` token for the vocab.
     #[must_use]
     pub fn unk_token(mut self, unk_token: String) -> Self {
@@ -87,6 +108,14 @@ impl WordPieceBuilder {
         self
     }
 
+    /// Set the suffix marking the last subword of each word, like the `</w>` of the
+    /// vocabularies trained with an `end_of_word_suffix`.
+    #[must_use]
+    pub fn end_of_word_suffix(mut self, end_of_word_suffix: String) -> Self {
+        self.config.end_of_word_suffix = Some(end_of_word_suffix);
+        self
+    }
+
     /// Set the maximum number of input characters per word.
     #[must_use]
     pub fn max_input_chars_per_word(mut self, max_input_chars_per_word: usize) -> Self {
@@ -99,6 +128,13 @@ impl WordPieceBuilder {
         if let Some(vocab) = self.config.files {
             self.config.vocab = WordPiece::read_file(&vocab)?;
         }
+        if self.config.compact_vocab && self.config.store.is_none() {
+            let store = MappedVocab::from_vocab(&self.config.vocab)?;
+            self.config.store = Some(SharedVocab::new(store));
+        }
+        if self.config.store.is_some() {
+            self.config.vocab = AHashMap::new();
+        }
 
         let vocab_r = self
             .config
@@ -110,8 +146,10 @@ impl WordPieceBuilder {
         Ok(WordPiece {
             vocab: self.config.vocab,
             vocab_r,
+            store: self.config.store,
             unk_token: self.config.unk_token,
             continuing_subword_prefix: self.config.continuing_subword_prefix,
+            end_of_word_suffix: self.config.end_of_word_suffix,
             max_input_chars_per_word: self.config.max_input_chars_per_word,
         })
     }
@@ -124,8 +162,12 @@ impl WordPieceBuilder {
 pub struct WordPiece {
     vocab: Vocab,
     vocab_r: VocabR,
+    /// The storage of the vocab, when it is used in place of the maps
+    store: Option<SharedVocab>,
     pub unk_token: String,
     pub continuing_subword_prefix: String,
+    /// The suffix added to the last subword of each word, if any
+    pub end_of_word_suffix: Option<String>,
     pub max_input_chars_per_word: usize,
 }
 
@@ -134,8 +176,9 @@ impl std::fmt::Debug for WordPiece {
         fmt.debug_struct("WordPiece")
             .field("unk_token", &self.unk_token)
             .field("continuing_subword_prefix", &self.continuing_subword_prefix)
+            .field("end_of_word_suffix", &self.end_of_word_suffix)
             .field("max_input_chars_per_word", &self.max_input_chars_per_word)
-            .field("vocab", &self.vocab.len())
+            .field("vocab", &self.get_vocab_size())
             .finish()
     }
 }
@@ -145,8 +188,10 @@ impl Default for WordPiece {
         Self {
             vocab: AHashMap::new(),
             vocab_r: AHashMap::new(),
+            store: None,
             unk_token: String::from("[UNK]"),
             continuing_subword_prefix: String::from("##"),
+            end_of_word_suffix: None,
             max_input_chars_per_word: 100,
         }
     }
@@ -160,16 +205,30 @@ impl WordPiece {
 
     /// Read the given files to extract the vocab
     pub fn read_file(vocab: &str) -> Result<Vocab> {
-        let file = File::open(vocab)?;
-        let file = BufReader::new(file);
+        Self::read_bytes(&std::fs::read(vocab)?)
+    }
 
-        let mut vocab = AHashMap::new();
-        for (index, line) in file.lines().enumerate() {
+    /// Extract the vocab from the content of a `vocab.txt`
+    pub fn read_bytes(vocab: &[u8]) -> Result<Vocab> {
+        let mut entries = AHashMap::new();
+        for (index, line) in vocab.lines().enumerate() {
             let line = line?;
-            vocab.insert(line.trim_end().to_owned(), index as u32);
+            entries.insert(line.trim_end().to_owned(), index as u32);
         }
 
-        Ok(vocab)
+        Ok(entries)
+    }
+
+    /// The content of the `vocab.txt` file written by [`save`](Model::save)
+    pub fn files(&self) -> Result<Vec<ModelFile>> {
+        let vocab_r = self.owned_vocab_r();
+        let mut vocab: Vec<(&u32, &String)> = vocab_r.iter().collect();
+        vocab.sort_unstable_by_key(|k| *k.0);
+        let content = vocab
+            .into_iter()
+            .flat_map(|(_, token)| format!("{token}\n").into_bytes())
+            .collect::<Vec<_>>();
+        Ok(vec![ModelFile::new("vocab.txt", content)])
     }
 
     /// Initialize a `WordPiece` model from a vocab mapping file.
@@ -189,19 +248,41 @@ impl WordPiece {
         if let Some(prefix) = bpe.get_continuing_subword_prefix() {
             prefix.clone_into(&mut wp.continuing_subword_prefix);
         }
+        wp.end_of_word_suffix = bpe.get_end_of_word_suffix().clone();
         wp
     }
+
+    /// Use the given storage for the vocab, in place of the maps
+    pub(crate) fn set_vocab_store(&mut self, store: SharedVocab) {
+        self.vocab = AHashMap::new();
+        self.vocab_r = AHashMap::new();
+        self.store = Some(store);
+    }
+
+    /// The reversed vocab, built from the storage if there is one
+    pub(crate) fn owned_vocab_r(&self) -> Cow<'_, VocabR> {
+        match &self.store {
+            Some(store) => Cow::Owned(store.vocab_r()),
+            None => Cow::Borrowed(&self.vocab_r),
+        }
+    }
 }
 
 impl Model for WordPiece {
     type Trainer = WordPieceTrainer;
 
     fn get_vocab(&self) -> HashMap<String, u32> {
-        self.vocab.clone().into_iter().collect()
+        match &self.store {
+            Some(store) => store.vocab().into_iter().collect(),
+            None => self.vocab.clone().into_iter().collect(),
+        }
     }
 
     fn get_vocab_size(&self) -> usize {
-        self.vocab.len()
+        match &self.store {
+            Some(store) => store.len(),
+            None => self.vocab.len(),
+        }
     }
 
     fn tokenize(&self, sequence: &str) -> Result<Vec<Token>> {
@@ -209,9 +290,8 @@ impl Model for WordPiece {
         if char_len > self.max_input_chars_per_word {
             return Ok(vec![Token {
                 value: self.unk_token.clone(),
-                id: *self
-                    .vocab
-                    .get(&self.unk_token)
+                id: self
+                    .token_to_id(&self.unk_token)
                     .ok_or(Error::MissingUnkToken)?,
                 offsets: (0, sequence.len()),
             }]);
@@ -231,15 +311,23 @@ impl Model for WordPiece {
                 if start > 0 {
                     substr = Cow::Owned(format!("{}{}", self.continuing_subword_prefix, substr));
                 }
-                if self.vocab.contains_key(substr.as_ref()) {
+                if end == sequence.len() {
+                    if let Some(suffix) = &self.end_of_word_suffix {
+                        substr = Cow::Owned(format!("{substr}{suffix}"));
+                    }
+                }
+                if let Some(id) = self.token_to_id(substr.as_ref()) {
                     cur_str = Some(Token {
-                        id: self.vocab[substr.as_ref()],
+                        id,
                         value: substr.to_string(),
                         offsets: (start, end),
                     });
                     break;
                 }
-                end -= substr.chars().last().map_or(1, |c| c.len_utf8());
+                end -= sequence[start..end]
+                    .chars()
+                    .last()
+                    .map_or(1, |c| c.len_utf8());
             }
 
             if cur_str.is_none() {
@@ -254,9 +342,8 @@ impl Model for WordPiece {
         if is_bad {
             Ok(vec![Token {
                 value: self.unk_token.clone(),
-                id: *self
-                    .vocab
-                    .get(&self.unk_token)
+                id: self
+                    .token_to_id(&self.unk_token)
                     .ok_or(Error::MissingUnkToken)?,
                 offsets: (0, sequence.len()),
             }])
@@ -266,34 +353,31 @@ impl Model for WordPiece {
     }
 
     fn token_to_id(&self, token: &str) -> Option<u32> {
-        self.vocab.get(token).copied()
+        match &self.store {
+            Some(store) => store.token_to_id(token),
+            None => self.vocab.get(token).copied(),
+        }
     }
 
     fn id_to_token(&self, id: u32) -> Option<String> {
-        self.vocab_r.get(&id).cloned()
+        match &self.store {
+            Some(store) => store.id_to_token(id).map(str::to_owned),
+            None => self.vocab_r.get(&id).cloned(),
+        }
     }
 
-    fn save(&self, folder: &Path, name: Option<&str>) -> Result<Vec<PathBuf>> {
-        let vocab_file_name = match name {
-            Some(name) => format!("{name}-vocab.txt"),
-            None => "vocab.txt".to_string(),
-        };
-
-        // Write vocab.txt
-        let vocab_path: PathBuf = [folder, Path::new(vocab_file_name.as_str())]
-            .iter()
-            .collect();
-        let mut vocab_file = File::create(&vocab_path)?;
-        let mut vocab: Vec<(&String, &u32)> = self.vocab.iter().collect();
-        vocab.sort_unstable_by_key(|k| *k.1);
-        vocab_file.write_all(
-            &vocab
-                .into_iter()
-                .flat_map(|(token, _)| format!("{token}\n").as_bytes().to_owned())
-                .collect::<Vec<_>>()[..],
-        )?;
+    fn token_ids_with_prefix(&self, prefix: &[u8]) -> Vec<u32> {
+        match &self.store {
+            Some(store) => store.ids_with_prefix(prefix),
+            None => ids_with_prefix(
+                self.vocab.iter().map(|(token, id)| (token.as_str(), *id)),
+                prefix,
+            ),
+        }
+    }
 
-        Ok(vec![vocab_path])
+    fn save(&self, folder: &Path, name: Option<&str>) -> Result<Vec<PathBuf>> {
+        write_files(&self.files()?, folder, &FileNaming::prefix(name))
     }
 
     fn get_trainer(&self) -> Self::Trainer {
@@ -309,4 +393,120 @@ mod tests {
     fn test_error_display() {
         assert!(format!("{}", Error::MissingUnkToken).contains("Missing [UNK] token"));
     }
+
+    #[test]
+    fn test_end_of_word_suffix() {
+        let vocab: Vocab = [
+            ("[UNK]", 0),
+            ("low", 1),
+            ("low</w>", 2),
+            ("##er</w>", 3),
+            ("##e", 4),
+            ("##st</w>", 5),
+        ]
+        .iter()
+        .map(|(token, id)| (token.to_string(), *id))
+        .collect();
+        let wordpiece = WordPiece::builder()
+            .vocab(vocab)
+            .end_of_word_suffix("</w>".into())
+            .build()
+            .unwrap();
+
+        let tokens = |word| -> Vec<_> {
+            wordpiece
+                .tokenize(word)
+                .unwrap()
+                .into_iter()
+                .map(|t| (t.value, t.offsets))
+                .collect()
+        };
+        assert_eq!(tokens("low"), vec![("low</w>".into(), (0, 3))]);
+        assert_eq!(
+            tokens("lower"),
+            vec![("low".into(), (0, 3)), ("##er</w>".into(), (3, 5))]
+        );
+        assert_eq!(
+            tokens("lowest"),
+            vec![
+                ("low".into(), (0, 3)),
+                ("##e".into(), (3, 4)),
+                ("##st</w>".into(), (4, 6))
+            ]
+        );
+        // The last subword must carry the suffix
+        assert_eq!(tokens("lowe"), vec![("[UNK]".into(), (0, 4))]);
+    }
+
+    #[test]
+    fn test_train_end_of_word_suffix() {
+        use crate::decoders::bpe::BPEDecoder;
+        use crate::{Decoder, Trainer};
+
+        let mut trainer = WordPieceTrainer::builder()
+            .show_progress(false)
+            .special_tokens(vec![crate::AddedToken::from("[UNK]", true)])
+            .end_of_word_suffix("</w>".into())
+            .build();
+        trainer
+            .feed(["low lower lowest newer"].iter(), |s| {
+                Ok(s.split(' ').map(|w| w.to_owned()).collect())
+            })
+            .unwrap();
+        let mut wordpiece = WordPiece::default();
+        trainer.train(&mut wordpiece).unwrap();
+        assert_eq!(wordpiece.end_of_word_suffix.as_deref(), Some("</w>"));
+
+        let decoder = BPEDecoder {
+            prefix: Some(wordpiece.continuing_subword_prefix.clone()),
+            ..Default::default()
+        };
+        let tokens = ["lowest", "newer", "low"]
+            .iter()
+            .flat_map(|word| wordpiece.tokenize(word).unwrap())
+            .map(|token| token.value)
+            .collect::<Vec<_>>();
+        assert!(tokens.iter().all(|token| token != "[UNK]"));
+        assert_eq!(decoder.decode(tokens).unwrap(), "lowest newer low");
+    }
+
+    #[test]
+    fn test_compact_vocab() {
+        let vocab: Vocab = [
+            ("[UNK]", 0),
+            ("he", 1),
+            ("##llo", 2),
+            ("##l", 3),
+            ("hell", 4),
+        ]
+        .iter()
+        .map(|(token, id)| (token.to_string(), *id))
+        .collect();
+        let wordpiece = WordPiece::builder().vocab(vocab.clone()).build().unwrap();
+        let compact = WordPiece::builder()
+            .vocab(vocab)
+            .compact_vocab(true)
+            .build()
+            .unwrap();
+
+        for word in ["hello", "hell", "help"] {
+            assert_eq!(
+                compact.tokenize(word).unwrap(),
+                wordpiece.tokenize(word).unwrap()
+            );
+        }
+        assert_eq!(compact.get_vocab(), wordpiece.get_vocab());
+        assert_eq!(compact.id_to_token(2), Some("##llo".into()));
+        for prefix in ["he", "##l", "x", ""] {
+            assert_eq!(
+                compact.token_ids_with_prefix(prefix.as_bytes()),
+                wordpiece.token_ids_with_prefix(prefix.as_bytes())
+            );
+        }
+        assert_eq!(compact.token_ids_with_prefix(b"##"), vec![2, 3]);
+        assert_eq!(
+            serde_json::to_string(&compact).unwrap(),
+            serde_json::to_string(&wordpiece).unwrap()
+        );
+    }
 }
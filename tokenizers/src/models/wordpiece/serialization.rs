@@ -17,10 +17,14 @@ impl Serialize for WordPiece {
         model.serialize_field("type", "WordPiece")?;
         model.serialize_field("unk_token", &self.unk_token)?;
         model.serialize_field("continuing_subword_prefix", &self.continuing_subword_prefix)?;
+        if let Some(suffix) = &self.end_of_word_suffix {
+            model.serialize_field("end_of_word_suffix", suffix)?;
+        }
         model.serialize_field("max_input_chars_per_word", &self.max_input_chars_per_word)?;
 
         // Then large ones
-        let ordered_vocab = OrderedVocabIter::new(&self.vocab_r);
+        let vocab_r = self.owned_vocab_r();
+        let ordered_vocab = OrderedVocabIter::new(&vocab_r);
         model.serialize_field("vocab", &ordered_vocab)?;
 
         model.end()
@@ -38,6 +42,7 @@ impl<'de> Deserialize<'de> for WordPiece {
                 "type",
                 "unk_token",
                 "continuing_subword_prefix",
+                "end_of_word_suffix",
                 "max_input_chars_per_word",
                 "vocab",
             ],
@@ -75,6 +80,11 @@ impl<'de> Visitor<'de> for WordPieceVisitor {
                 "continuing_subword_prefix" => {
                     builder = builder.continuing_subword_prefix(map.next_value()?)
                 }
+                "end_of_word_suffix" => {
+                    if let Some(suffix) = map.next_value()? {
+                        builder = builder.end_of_word_suffix(suffix)
+                    }
+                }
                 "max_input_chars_per_word" => {
                     builder = builder.max_input_chars_per_word(map.next_value()?)
                 }
@@ -125,6 +135,25 @@ mod tests {
         assert_eq!(serde_json::from_str::<WordPiece>(wp_s).unwrap(), wp);
     }
 
+    #[test]
+    fn serde_end_of_word_suffix() {
+        let wp = WordPiece::builder()
+            .end_of_word_suffix("</w>".into())
+            .build()
+            .unwrap();
+        let wp_s = "{\
+            \"type\":\"WordPiece\",\
+            \"unk_token\":\"[UNK]\",\
+            \"continuing_subword_prefix\":\"##\",\
+            \"end_of_word_suffix\":\"</w>\",\
+            \"max_input_chars_per_word\":100,\
+            \"vocab\":{}\
+        }";
+
+        assert_eq!(serde_json::to_string(&wp).unwrap(), wp_s);
+        assert_eq!(serde_json::from_str::<WordPiece>(wp_s).unwrap(), wp);
+    }
+
     #[test]
     fn deserialization_should_fail() {
         let missing_unk = "{\
@@ -3,6 +3,8 @@ use std::collections::HashSet;
 use super::WordPiece;
 use crate::models::bpe::{BpeTrainer, BpeTrainerBuilder, BPE};
 use crate::tokenizer::{AddedToken, Result, Trainer};
+use crate::utils::progress::ProgressCallback;
+use crate::utils::script_weights::ScriptWeights;
 use ahash::AHashSet;
 use serde::{Deserialize, Serialize};
 
@@ -54,6 +56,20 @@ impl WordPieceTrainerBuilder {
         self
     }
 
+    /// Set the weight of the words of each script in the counts of the pairs
+    #[must_use]
+    pub fn script_weights(mut self, weights: ScriptWeights) -> Self {
+        self.bpe_trainer_builder = self.bpe_trainer_builder.script_weights(weights);
+        self
+    }
+
+    /// Set the callback reporting the progress of the training
+    #[must_use]
+    pub fn progress_callback(mut self, callback: ProgressCallback) -> Self {
+        self.bpe_trainer_builder = self.bpe_trainer_builder.progress_callback(callback);
+        self
+    }
+
     /// Set whether to limit the alphabet
     #[must_use]
     pub fn limit_alphabet(mut self, limit: usize) -> Self {
@@ -120,6 +136,14 @@ impl WordPieceTrainer {
         self.bpe_trainer.show_progress = show_progress;
     }
 
+    pub fn progress_callback(&self) -> Option<&ProgressCallback> {
+        self.bpe_trainer.progress_callback.as_ref()
+    }
+
+    pub fn set_progress_callback(&mut self, callback: Option<ProgressCallback>) {
+        self.bpe_trainer.progress_callback = callback;
+    }
+
     pub fn special_tokens(&self) -> &[AddedToken] {
         &self.bpe_trainer.special_tokens
     }
@@ -162,6 +186,14 @@ impl WordPieceTrainer {
         self.bpe_trainer.end_of_word_suffix = suffix;
     }
 
+    pub fn script_weights(&self) -> &ScriptWeights {
+        &self.bpe_trainer.script_weights
+    }
+
+    pub fn set_script_weights(&mut self, weights: ScriptWeights) {
+        self.bpe_trainer.script_weights = weights;
+    }
+
     pub fn builder() -> WordPieceTrainerBuilder {
         WordPieceTrainerBuilder::default()
     }
@@ -174,8 +206,10 @@ impl WordPieceTrainer {
         // Transfer the vocab
         model.vocab = new_wordpiece.vocab;
         model.vocab_r = new_wordpiece.vocab_r;
-        // The continuing_subword_prefix is the only other option to be overridden by the trainer
+        model.store = None;
+        // The markers are the only other options to be overridden by the trainer
         model.continuing_subword_prefix = new_wordpiece.continuing_subword_prefix;
+        model.end_of_word_suffix = new_wordpiece.end_of_word_suffix;
 
         Ok(special_tokens)
     }
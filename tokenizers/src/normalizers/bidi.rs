@@ -0,0 +1,151 @@
+use crate::tokenizer::{NormalizedString, Normalizer, Result};
+use serde::{Deserialize, Serialize};
+
+/// Whether the given char is one of the invisible controls of the Unicode bidirectional
+/// algorithm: the marks (ALM, LRM, RLM), the embeddings and overrides (LRE, RLE, PDF, LRO,
+/// RLO), and the isolates (LRI, RLI, FSI, PDI).
+pub fn is_bidi_control(c: char) -> bool {
+    matches!(
+        c,
+        '\u{061C}' | '\u{200E}' | '\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}'
+    )
+}
+
+/// What [`BidiControls`] does with the bidi control chars
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BidiControlsHandling {
+    /// Remove them, joining the text around them
+    #[default]
+    Remove,
+    /// Replace each of them by a space, splitting the text around them like a whitespace
+    Space,
+}
+
+/// Handles the bidi control chars a right-to-left text (Arabic, Hebrew, ...) may contain,
+/// which would otherwise end up in its tokens, or be unknown to the model.
+///
+/// Like all the offsets, those of the tokens of a right-to-left text are in logical order:
+/// they are indices in the text as stored, where its first char is also its first char
+/// read, the rightmost one once displayed. Highlighting a span then only needs slicing the
+/// original text with its offsets, the display of the slice being left to the bidi
+/// algorithm. The offsets of the tokens never cover the removed controls, but those around
+/// them still belong to the original text, so a slice may drop the controls embedding it.
+#[derive(Copy, Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(tag = "type")]
+#[non_exhaustive]
+pub struct BidiControls {
+    #[serde(default)]
+    pub handling: BidiControlsHandling,
+}
+
+impl BidiControls {
+    pub fn new(handling: BidiControlsHandling) -> Self {
+        Self { handling }
+    }
+}
+
+impl Normalizer for BidiControls {
+    fn normalize(&self, normalized: &mut NormalizedString) -> Result<()> {
+        match self.handling {
+            BidiControlsHandling::Remove => {
+                normalized.filter(|c| !is_bidi_control(c));
+            }
+            BidiControlsHandling::Space => {
+                normalized.map(|c| if is_bidi_control(c) { ' ' } else { c });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::wordlevel::WordLevel;
+    use crate::pre_tokenizers::whitespace::WhitespaceSplit;
+    use crate::Tokenizer;
+
+    #[test]
+    fn remove() {
+        // An Arabic sentence, with a left-to-right isolated number and a right-to-left mark
+        let original = "مرحبا \u{2066}2024\u{2069} عالم\u{200F}";
+        let mut n = NormalizedString::from(original);
+        BidiControls::default().normalize(&mut n).unwrap();
+        assert_eq!(n.get(), "مرحبا 2024 عالم");
+        assert_eq!(n.len_original(), original.len());
+
+        let mut n = NormalizedString::from(original);
+        BidiControls::new(BidiControlsHandling::Space)
+            .normalize(&mut n)
+            .unwrap();
+        assert_eq!(n.get(), "مرحبا  2024  عالم ");
+    }
+
+    #[test]
+    fn logical_offsets() {
+        let vocab = [("שלום", 0), ("עולם", 1), ("ABC", 2), ("[UNK]", 3)]
+            .iter()
+            .map(|(token, id)| (token.to_string(), *id))
+            .collect();
+        let model = WordLevel::builder()
+            .vocab(vocab)
+            .unk_token("[UNK]".into())
+            .build()
+            .unwrap();
+        let mut tokenizer = Tokenizer::new(model);
+        tokenizer.with_pre_tokenizer(Some(WhitespaceSplit));
+
+        // A Hebrew sentence embedding a left-to-right word without separating it
+        let text = "שלום \u{200F}עולם\u{2066}ABC\u{2069}";
+        let spans = |tokenizer: &Tokenizer, char_offsets: bool| {
+            let encoding = match char_offsets {
+                true => tokenizer.encode_char_offsets(text, false),
+                false => tokenizer.encode(text, false),
+            }
+            .unwrap();
+            let chars: Vec<_> = text.chars().collect();
+            let spans: Vec<String> = encoding
+                .get_offsets()
+                .iter()
+                .map(|(start, end)| match char_offsets {
+                    true => chars[*start..*end].iter().collect(),
+                    false => text[*start..*end].to_string(),
+                })
+                .collect();
+            (encoding.get_tokens().to_vec(), spans)
+        };
+
+        // Without the normalizer, the controls stick to the words
+        assert_eq!(spans(&tokenizer, false).0, ["שלום", "[UNK]"]);
+
+        tokenizer.with_normalizer(Some(BidiControls::default()));
+        let (tokens, spans_bytes) = spans(&tokenizer, false);
+        assert_eq!(tokens, ["שלום", "[UNK]"]);
+        assert_eq!(spans_bytes, ["שלום", "עולם\u{2066}ABC"]);
+
+        tokenizer.with_normalizer(Some(BidiControls::new(BidiControlsHandling::Space)));
+        for char_offsets in [false, true] {
+            let (tokens, spans) = spans(&tokenizer, char_offsets);
+            assert_eq!(tokens, ["שלום", "עולם", "ABC"]);
+            // In logical order: the first word read comes first, whatever its display
+            assert_eq!(spans, tokens);
+        }
+    }
+
+    #[test]
+    fn serialization() {
+        let bidi = BidiControls::new(BidiControlsHandling::Space);
+        let json = serde_json::to_string(&bidi).unwrap();
+        assert_eq!(json, r#"{"type":"BidiControls","handling":"space"}"#);
+        let wrapper: crate::NormalizerWrapper = serde_json::from_str(&json).unwrap();
+        assert!(matches!(
+            wrapper,
+            crate::NormalizerWrapper::BidiControls(BidiControls {
+                handling: BidiControlsHandling::Space
+            })
+        ));
+        let bidi: BidiControls = serde_json::from_str(r#"{"type":"BidiControls"}"#).unwrap();
+        assert_eq!(bidi.handling, BidiControlsHandling::Remove);
+    }
+}
@@ -1,4 +1,5 @@
 pub mod bert;
+pub mod bidi;
 pub mod byte_level;
 pub mod precompiled;
 pub mod prepend;
@@ -7,6 +8,7 @@ pub mod strip;
 pub mod unicode;
 pub mod utils;
 pub use crate::normalizers::bert::BertNormalizer;
+pub use crate::normalizers::bidi::{BidiControls, BidiControlsHandling};
 pub use crate::normalizers::byte_level::ByteLevel;
 pub use crate::normalizers::precompiled::Precompiled;
 pub use crate::normalizers::prepend::Prepend;
@@ -36,6 +38,7 @@ pub enum NormalizerWrapper {
     Replace(Replace),
     Prepend(Prepend),
     ByteLevel(ByteLevel),
+    BidiControls(BidiControls),
 }
 
 impl<'de> Deserialize<'de> for NormalizerWrapper {
@@ -66,6 +69,7 @@ impl<'de> Deserialize<'de> for NormalizerWrapper {
             Replace,
             Prepend,
             ByteLevel,
+            BidiControls,
         }
 
         #[derive(Deserialize)]
@@ -151,6 +155,9 @@ impl<'de> Deserialize<'de> for NormalizerWrapper {
                     EnumType::ByteLevel => NormalizerWrapper::ByteLevel(
                         serde_json::from_value(values).map_err(serde::de::Error::custom)?,
                     ),
+                    EnumType::BidiControls => NormalizerWrapper::BidiControls(
+                        serde_json::from_value(values).map_err(serde::de::Error::custom)?,
+                    ),
                 }
             }
 
@@ -198,8 +205,38 @@ impl Normalizer for NormalizerWrapper {
             Self::Replace(lc) => lc.normalize(normalized),
             Self::Prepend(lc) => lc.normalize(normalized),
             Self::ByteLevel(lc) => lc.normalize(normalized),
+            Self::BidiControls(bidi) => bidi.normalize(normalized),
         }
     }
+
+    fn for_each_step(
+        &self,
+        f: &mut dyn FnMut(&str, &dyn Normalizer) -> crate::Result<()>,
+    ) -> crate::Result<()> {
+        let name = match self {
+            Self::Sequence(sequence) => {
+                for normalizer in sequence.as_ref() {
+                    normalizer.for_each_step(f)?;
+                }
+                return Ok(());
+            }
+            Self::BertNormalizer(_) => "BertNormalizer",
+            Self::StripNormalizer(_) => "Strip",
+            Self::StripAccents(_) => "StripAccents",
+            Self::NFC(_) => "NFC",
+            Self::NFD(_) => "NFD",
+            Self::NFKC(_) => "NFKC",
+            Self::NFKD(_) => "NFKD",
+            Self::Lowercase(_) => "Lowercase",
+            Self::Nmt(_) => "Nmt",
+            Self::Precompiled(_) => "Precompiled",
+            Self::Replace(_) => "Replace",
+            Self::Prepend(_) => "Prepend",
+            Self::ByteLevel(_) => "ByteLevel",
+            Self::BidiControls(_) => "BidiControls",
+        };
+        f(name, self)
+    }
 }
 
 impl_enum_from!(BertNormalizer, NormalizerWrapper, BertNormalizer);
@@ -216,6 +253,7 @@ impl_enum_from!(Precompiled, NormalizerWrapper, Precompiled);
 impl_enum_from!(Replace, NormalizerWrapper, Replace);
 impl_enum_from!(Prepend, NormalizerWrapper, Prepend);
 impl_enum_from!(ByteLevel, NormalizerWrapper, ByteLevel);
+impl_enum_from!(BidiControls, NormalizerWrapper, BidiControls);
 
 #[cfg(test)]
 mod tests {
@@ -1,6 +1,9 @@
 use crate::tokenizer::{NormalizedString, Normalizer, Result};
+use ahash::AHashSet;
 pub use spm_precompiled::Precompiled;
 use std::cmp::Ordering;
+use std::collections::{BTreeMap, VecDeque};
+use std::convert::TryFrom;
 use unicode_segmentation::UnicodeSegmentation;
 
 fn replace(transformations: &mut Vec<(char, isize)>, old_part: &str, new_part: &str) {
@@ -30,6 +33,223 @@ fn replace(transformations: &mut Vec<(char, isize)>, old_part: &str, new_part: &
     }
 }
 
+#[derive(thiserror::Error, Debug)]
+pub enum PrecompiledBuilderError {
+    /// A rule has an empty source
+    #[error("Precompiled rules can't have an empty source")]
+    EmptySource,
+    /// Null characters are used as terminators in the charsmap
+    #[error("Precompiled rule `{0:?}` contains a null character")]
+    NullCharacter(String),
+    /// The same source has been given different replacements
+    #[error("Conflicting precompiled rules for `{0}`")]
+    ConflictingRules(String),
+    /// The rules don't fit in the charsmap format
+    #[error("Too many precompiled rules to fit in a charsmap")]
+    TooManyRules,
+}
+
+/// A `PrecompiledBuilder` can be used to create a `Precompiled` normalizer from a list of
+/// `(source, replacement)` rules, instead of a `precompiled_charsmap` extracted from an
+/// existing SentencePiece model.
+///
+/// Just like with SentencePiece, the rules are looked up for each grapheme (if shorter than
+/// 6 bytes) first, and then for each char of the grapheme.
+///
+/// ```
+/// # use tokenizers::normalizers::precompiled::PrecompiledBuilder;
+/// let precompiled = PrecompiledBuilder::new()
+///     .rule("ﬁ", "fi")
+///     .rule("\u{3000}", " ")
+///     .build()
+///     .unwrap();
+/// assert_eq!(precompiled.normalize_string("ﬁne\u{3000}day"), "fine day");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PrecompiledBuilder {
+    rules: Vec<(String, String)>,
+}
+
+impl PrecompiledBuilder {
+    /// Constructs a new `PrecompiledBuilder`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a rule replacing `source` with `replacement`.
+    #[must_use]
+    pub fn rule<S: Into<String>, R: Into<String>>(mut self, source: S, replacement: R) -> Self {
+        self.rules.push((source.into(), replacement.into()));
+        self
+    }
+
+    /// Add all the given `(source, replacement)` rules.
+    #[must_use]
+    pub fn rules<I, S, R>(mut self, rules: I) -> Self
+    where
+        I: IntoIterator<Item = (S, R)>,
+        S: Into<String>,
+        R: Into<String>,
+    {
+        self.rules
+            .extend(rules.into_iter().map(|(s, r)| (s.into(), r.into())));
+        self
+    }
+
+    /// Build the binary `precompiled_charsmap`, in the same format as the one embedded in
+    /// SentencePiece models.
+    pub fn build_charsmap(&self) -> Result<Vec<u8>> {
+        // The replacements, each one terminated by a null character
+        let mut normalized = Vec::new();
+        let mut replacements = BTreeMap::new();
+        let mut keys: BTreeMap<&[u8], u32> = BTreeMap::new();
+        for (source, replacement) in &self.rules {
+            if source.is_empty() {
+                return Err(PrecompiledBuilderError::EmptySource.into());
+            }
+            if source.contains('\0') || replacement.contains('\0') {
+                return Err(PrecompiledBuilderError::NullCharacter(source.clone()).into());
+            }
+            let value = *replacements.entry(replacement.as_str()).or_insert_with(|| {
+                let value = normalized.len();
+                normalized.extend(replacement.as_bytes());
+                normalized.push(0);
+                value
+            });
+            let value = u32::try_from(value).map_err(|_| PrecompiledBuilderError::TooManyRules)?;
+            match keys.insert(source.as_bytes(), value) {
+                Some(previous) if previous != value => {
+                    return Err(PrecompiledBuilderError::ConflictingRules(source.clone()).into())
+                }
+                _ => {}
+            }
+        }
+
+        let trie = build_double_array(&keys)?;
+        let trie_size =
+            u32::try_from(trie.len() * 4).map_err(|_| PrecompiledBuilderError::TooManyRules)?;
+
+        let mut charsmap = Vec::with_capacity(4 + trie.len() * 4 + normalized.len());
+        charsmap.extend(trie_size.to_le_bytes());
+        trie.iter()
+            .for_each(|unit| charsmap.extend(unit.to_le_bytes()));
+        charsmap.extend(normalized);
+        Ok(charsmap)
+    }
+
+    /// Build the `Precompiled` normalizer.
+    pub fn build(&self) -> Result<Precompiled> {
+        Ok(Precompiled::from(&self.build_charsmap()?)?)
+    }
+}
+
+/// A node of the trie we lay out in the double array
+#[derive(Default)]
+struct TrieNode {
+    children: BTreeMap<u8, usize>,
+    value: Option<u32>,
+}
+
+const HAS_LEAF: u32 = 1 << 8;
+const IS_VALUE: u32 = 1 << 31;
+const BLOCK_SIZE: usize = 256;
+
+/// Encode the offset between a unit and its children, as done by Darts
+fn encode_offset(offset: usize) -> Option<u32> {
+    if offset < 1 << 21 {
+        Some((offset as u32) << 10)
+    } else if offset < 1 << 29 && offset & 0xFF == 0 {
+        Some(((offset as u32) << 2) | (1 << 9))
+    } else {
+        None
+    }
+}
+
+/// Lay out the given keys in a Darts-compatible double array. Each node lives at a
+/// position `pos` of the array, and its children with label `c` at `base ^ c`, where
+/// `base = pos ^ offset`. The value of a key lives in its terminal child, with label 0.
+fn build_double_array(keys: &BTreeMap<&[u8], u32>) -> Result<Vec<u32>> {
+    let mut nodes = vec![TrieNode::default()];
+    for (key, value) in keys {
+        let mut node = 0;
+        for byte in key.iter() {
+            node = match nodes[node].children.get(byte) {
+                Some(child) => *child,
+                None => {
+                    nodes.push(TrieNode::default());
+                    let child = nodes.len() - 1;
+                    nodes[node].children.insert(*byte, child);
+                    child
+                }
+            };
+        }
+        nodes[node].value = Some(*value);
+    }
+
+    // The lookups don't check bounds, so we always allocate whole blocks
+    let mut units = vec![0u32; BLOCK_SIZE];
+    let mut used = vec![false; BLOCK_SIZE];
+    used[0] = true;
+    let mut used_bases = AHashSet::new();
+    let mut first_free = 1;
+
+    let mut queue = VecDeque::from([(0, 0)]);
+    while let Some((node, pos)) = queue.pop_front() {
+        let node = &nodes[node];
+        let labels = node
+            .value
+            .map(|_| 0)
+            .into_iter()
+            .chain(node.children.keys().copied())
+            .collect::<Vec<_>>();
+        if labels.is_empty() {
+            continue;
+        }
+
+        let is_free = |p: usize| p >= used.len() || !used[p];
+        let mut candidate = first_free;
+        let (base, offset) = loop {
+            if is_free(candidate) {
+                let base = candidate ^ labels[0] as usize;
+                if !used_bases.contains(&base) && labels.iter().all(|l| is_free(base ^ *l as usize))
+                {
+                    if let Some(offset) = encode_offset(pos ^ base) {
+                        break (base, offset);
+                    }
+                }
+            }
+            candidate += 1;
+            if candidate >= 1 << 29 {
+                return Err(PrecompiledBuilderError::TooManyRules.into());
+            }
+        };
+
+        let needed = (base | (BLOCK_SIZE - 1)) + 1;
+        if units.len() < needed {
+            units.resize(needed, 0);
+            used.resize(needed, false);
+        }
+        used_bases.insert(base);
+        units[pos] |= offset;
+        if let Some(value) = node.value {
+            units[pos] |= HAS_LEAF;
+            units[base] = value | IS_VALUE;
+            used[base] = true;
+        }
+        for (label, child) in &node.children {
+            let child_pos = base ^ *label as usize;
+            units[child_pos] = *label as u32;
+            used[child_pos] = true;
+            queue.push_back((*child, child_pos));
+        }
+        while first_free < used.len() && used[first_free] {
+            first_free += 1;
+        }
+    }
+
+    Ok(units)
+}
+
 impl Normalizer for Precompiled {
     fn normalize(&self, normalized: &mut NormalizedString) -> Result<()> {
         let mut transformations = Vec::with_capacity(normalized.get().len());
@@ -86,4 +306,69 @@ mod tests {
 
         assert_eq!(n.get(), "TMg");
     }
+
+    #[test]
+    fn build_from_rules() {
+        let builder = PrecompiledBuilder::new()
+            .rule("™", "TM")
+            .rule("\x1e", "")
+            .rules(vec![("ﬀ", "ff"), ("e\u{301}", "é"), ("Ａ", "A")]);
+        let precompiled = builder.build().unwrap();
+
+        assert_eq!(precompiled.transform("™"), Some("TM"));
+        assert_eq!(precompiled.transform("\x1e"), Some(""));
+        assert_eq!(precompiled.transform("e\u{301}"), Some("é"));
+        assert_eq!(precompiled.transform("e"), None);
+        assert_eq!(precompiled.transform("T"), None);
+
+        let mut n = NormalizedString::from("™\x1eｅﬀＡe\u{301}");
+        precompiled.normalize(&mut n).unwrap();
+        assert_eq!(n.get(), "TMｅffAé");
+
+        // The charsmap can be loaded and serialized like any other
+        let charsmap = builder.build_charsmap().unwrap();
+        assert_eq!(Precompiled::from(&charsmap).unwrap(), precompiled);
+        let serialized = serde_json::to_string(&precompiled).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Precompiled>(&serialized).unwrap(),
+            precompiled
+        );
+    }
+
+    #[test]
+    fn build_many_rules() {
+        // Enough rules to span many blocks, sharing many prefixes
+        let rules = ('\u{00C0}'..'\u{0FFF}')
+            .map(|c| (c.to_string(), format!("<{}>", c as u32)))
+            .collect::<Vec<_>>();
+        let precompiled = PrecompiledBuilder::new()
+            .rules(rules.clone())
+            .build()
+            .unwrap();
+        for (source, replacement) in &rules {
+            assert_eq!(precompiled.transform(source), Some(replacement.as_str()));
+        }
+        assert_eq!(precompiled.transform("a"), None);
+        assert_eq!(
+            PrecompiledBuilder::new().build().unwrap().transform("a"),
+            None
+        );
+    }
+
+    #[test]
+    fn build_invalid_rules() {
+        assert!(PrecompiledBuilder::new().rule("", "a").build().is_err());
+        assert!(PrecompiledBuilder::new().rule("a\0", "a").build().is_err());
+        assert!(PrecompiledBuilder::new()
+            .rule("a", "b")
+            .rule("a", "c")
+            .build()
+            .is_err());
+        // Duplicated rules are fine if they agree
+        assert!(PrecompiledBuilder::new()
+            .rule("a", "b")
+            .rule("a", "b")
+            .build()
+            .is_ok());
+    }
 }
@@ -53,7 +53,11 @@ pub struct Replace {
 
 impl Clone for Replace {
     fn clone(&self) -> Self {
-        Self::new(self.pattern.clone(), &self.content).unwrap()
+        Self {
+            pattern: self.pattern.clone(),
+            content: self.content.clone(),
+            regex: self.regex.clone(),
+        }
     }
 }
 
@@ -77,6 +81,16 @@ impl Replace {
             regex,
         })
     }
+
+    /// Replace the matches of an already compiled regex, which may be shared with other
+    /// components instead of being compiled again
+    pub fn from_regex<C: Into<String>>(regex: SysRegex, content: C) -> Self {
+        Self {
+            pattern: ReplacePattern::Regex(regex.as_str().to_owned()),
+            content: content.into(),
+            regex,
+        }
+    }
 }
 
 impl Normalizer for Replace {
@@ -134,6 +148,22 @@ mod tests {
         assert_eq!(&n.get(), &normalized);
     }
 
+    #[test]
+    fn from_regex() {
+        let regex = SysRegex::new(r"\s+").unwrap();
+        let replace = Replace::from_regex(regex.clone(), ' ');
+        assert_eq!(
+            replace,
+            Replace::new(ReplacePattern::Regex(r"\s+".into()), ' ').unwrap()
+        );
+
+        // The same regex can be shared by another component
+        let mut n = NormalizedString::from("a   b");
+        replace.normalize(&mut n).unwrap();
+        Replace::from_regex(regex, "").normalize(&mut n).unwrap();
+        assert_eq!(n.get(), "ab");
+    }
+
     #[test]
     fn serialization() {
         let replace = Replace::new("Hello", "Hey").unwrap();
@@ -44,7 +44,7 @@ static RE: LazyLock<SysRegex> = LazyLock::new(|| {
     SysRegex::new(r"'s|'t|'re|'ve|'m|'ll|'d| ?\p{L}+| ?\p{N}+| ?[^\s\p{L}\p{N}]+|\s+(?!\S)|\s+")
         .unwrap()
 });
-static BYTES_CHAR: LazyLock<AHashMap<u8, char>> = LazyLock::new(bytes_char);
+pub(crate) static BYTES_CHAR: LazyLock<AHashMap<u8, char>> = LazyLock::new(bytes_char);
 static CHAR_BYTES: LazyLock<AHashMap<char, u8>> =
     LazyLock::new(|| bytes_char().into_iter().map(|(c, b)| (b, c)).collect());
 
@@ -154,7 +154,21 @@ impl PreTokenizer for ByteLevel {
 /// as String.
 impl Decoder for ByteLevel {
     fn decode_chain(&self, tokens: Vec<String>) -> Result<Vec<String>> {
-        let toks = tokens
+        Ok(vec![
+            String::from_utf8_lossy(&Self::to_bytes(tokens)).to_string()
+        ])
+    }
+
+    fn decode_bytes(&self, tokens: Vec<String>) -> Result<Vec<u8>> {
+        Ok(Self::to_bytes(tokens))
+    }
+}
+
+impl ByteLevel {
+    /// Convert the byte-level chars of the tokens back to their bytes. The tokens with other
+    /// chars, like the added tokens, are kept as is.
+    pub(crate) fn to_bytes(tokens: Vec<String>) -> Vec<u8> {
+        tokens
             .into_iter()
             .flat_map(|t| {
                 t.chars()
@@ -166,8 +180,7 @@ impl Decoder for ByteLevel {
                     })
                     .unwrap_or_else(|| t.as_bytes().to_vec())
             })
-            .collect::<Vec<u8>>();
-        Ok(vec![String::from_utf8_lossy(&toks).to_string()])
+            .collect()
     }
 }
 
@@ -550,6 +563,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn decode_bytes() {
+        let byte_level = ByteLevel::default();
+        // Some binary data, not valid UTF-8, along with an added token
+        let data = [0xFF, 0x00, b'a', 0xE5, 0x8F];
+        let mut tokens: Vec<String> = data.iter().map(|b| BYTES_CHAR[b].to_string()).collect();
+        tokens.push("[SEP]".into());
+
+        let mut expected = data.to_vec();
+        expected.extend_from_slice(b"[SEP]");
+        assert_eq!(byte_level.decode_bytes(tokens.clone()).unwrap(), expected);
+        assert_eq!(byte_level.decode(tokens.clone()).unwrap(), "�\0a�[SEP]");
+
+        let wrapper =
+            crate::DecoderWrapper::Sequence(crate::decoders::sequence::Sequence::new(vec![
+                byte_level.into(),
+            ]));
+        assert_eq!(wrapper.decode_bytes(tokens).unwrap(), expected);
+
+        // An added token with a real char from the end of the Supplementary Private Use Area-B
+        let tokens = vec![BYTES_CHAR[&0xE5].to_string(), "x\u{10FF41}".into()];
+        assert_eq!(
+            wrapper.decode_bytes(tokens).unwrap(),
+            b"\xE5x\xF4\x8F\xBD\x81".to_vec()
+        );
+    }
+
     #[test]
     fn decode_unknown_characters() {
         let byte_level = ByteLevel::default();
@@ -0,0 +1,196 @@
+use serde::{Deserialize, Serialize};
+
+use crate::normalizer::Range;
+use crate::tokenizer::{PreTokenizedString, PreTokenizer, Result};
+use crate::utils::macro_rules_attribute;
+
+/// The unit of indentation recognized by the [`Indentation`] pre-tokenizer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IndentUnit {
+    /// A single tab
+    Tab,
+    /// Two spaces
+    TwoSpaces,
+    /// Four spaces
+    FourSpaces,
+}
+
+impl std::fmt::Display for IndentUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.serialize(f)
+    }
+}
+
+impl IndentUnit {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Tab => "\t",
+            Self::TwoSpaces => "  ",
+            Self::FourSpaces => "    ",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// Splits each indentation unit found at the start of a line into its own piece, so that
+/// the model can represent the indentation of code with dedicated tokens. The rest of the
+/// text, including the leftover whitespace that doesn't make a full unit, is kept as is, to
+/// be split by the next pre-tokenizer.
+///
+/// The lines are tracked across the pieces it receives, so it can follow a pre-tokenizer
+/// that split on the newlines. The pre-tokenizers removing the whitespace, like
+/// `WhitespaceSplit`, would drop the indentation pieces when following it, which is why it
+/// is best followed by `ByteLevel` or `Metaspace`.
+#[non_exhaustive]
+#[macro_rules_attribute(impl_serde_type!)]
+pub struct Indentation {
+    pub unit: IndentUnit,
+}
+
+impl Indentation {
+    pub fn new(unit: IndentUnit) -> Self {
+        Self { unit }
+    }
+}
+
+impl Default for Indentation {
+    fn default() -> Self {
+        Self::new(IndentUnit::FourSpaces)
+    }
+}
+
+impl PreTokenizer for Indentation {
+    fn pre_tokenize(&self, pretokenized: &mut PreTokenizedString) -> Result<()> {
+        let unit = self.unit.as_str();
+        let mut at_line_start = true;
+        pretokenized.split(|_, normalized| {
+            let text = normalized.get();
+            let line_starts = at_line_start
+                .then_some(0)
+                .into_iter()
+                .chain(text.match_indices('\n').map(|(i, _)| i + 1));
+
+            let mut ranges = vec![];
+            let mut start = 0;
+            for line_start in line_starts {
+                let mut end = line_start;
+                while text[end..].starts_with(unit) {
+                    if start < end {
+                        ranges.push(start..end);
+                    }
+                    start = end;
+                    end += unit.len();
+                }
+                if end > line_start {
+                    ranges.push(start..end);
+                    start = end;
+                }
+            }
+            if start < text.len() {
+                ranges.push(start..text.len());
+            }
+            at_line_start = text.ends_with('\n') || (text.is_empty() && at_line_start);
+
+            ranges
+                .into_iter()
+                .map(|range| {
+                    normalized
+                        .slice(Range::Normalized(range))
+                        .ok_or_else(|| "Failed to slice normalized text".into())
+                })
+                .collect::<Result<Vec<_>>>()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pre_tokenizers::split::{Split, SplitPattern};
+    use crate::{OffsetReferential, OffsetType, SplitDelimiterBehavior};
+
+    fn splits(pretok: &impl PreTokenizer, s: &str) -> Vec<(String, (usize, usize))> {
+        let mut pretokenized = PreTokenizedString::from(s);
+        pretok.pre_tokenize(&mut pretokenized).unwrap();
+        pretokenized
+            .get_splits(OffsetReferential::Original, OffsetType::Byte)
+            .into_iter()
+            .map(|(s, o, _)| (s.to_owned(), o))
+            .collect()
+    }
+
+    #[test]
+    fn four_spaces() {
+        let pretok = Indentation::default();
+        assert_eq!(
+            splits(&pretok, "if x:\n        y\n   z"),
+            vec![
+                ("if x:\n".into(), (0, 6)),
+                ("    ".into(), (6, 10)),
+                ("    ".into(), (10, 14)),
+                ("y\n   z".into(), (14, 20)),
+            ]
+        );
+        assert_eq!(
+            splits(&pretok, "      a  b"),
+            vec![("    ".into(), (0, 4)), ("  a  b".into(), (4, 10))]
+        );
+        assert_eq!(splits(&pretok, ""), vec![]);
+    }
+
+    #[test]
+    fn other_units() {
+        let pretok = Indentation::new(IndentUnit::Tab);
+        assert_eq!(
+            splits(&pretok, "a\n\t\tb\n    c"),
+            vec![
+                ("a\n".into(), (0, 2)),
+                ("\t".into(), (2, 3)),
+                ("\t".into(), (3, 4)),
+                ("b\n    c".into(), (4, 11)),
+            ]
+        );
+
+        let pretok = Indentation::new(IndentUnit::TwoSpaces);
+        assert_eq!(
+            splits(&pretok, "   a"),
+            vec![("  ".into(), (0, 2)), (" a".into(), (2, 4))]
+        );
+    }
+
+    #[test]
+    fn follows_line_splits() {
+        let newlines = Split::new(
+            SplitPattern::String("\n".into()),
+            SplitDelimiterBehavior::Isolated,
+            false,
+        )
+        .unwrap();
+        let mut pretokenized = PreTokenizedString::from("    a    b\n    c");
+        newlines.pre_tokenize(&mut pretokenized).unwrap();
+        Indentation::default()
+            .pre_tokenize(&mut pretokenized)
+            .unwrap();
+        assert_eq!(
+            pretokenized
+                .get_splits(OffsetReferential::Original, OffsetType::Byte)
+                .into_iter()
+                .map(|(s, _, _)| s)
+                .collect::<Vec<_>>(),
+            vec!["    ", "a    b", "\n", "    ", "c"]
+        );
+    }
+
+    #[test]
+    fn serialization() {
+        let pretok = Indentation::new(IndentUnit::TwoSpaces);
+        let serialized = serde_json::to_string(&pretok).unwrap();
+        assert_eq!(serialized, r#"{"type":"Indentation","unit":"two_spaces"}"#);
+        assert_eq!(pretok.unit.to_string(), "two_spaces");
+        assert_eq!(
+            serde_json::from_str::<Indentation>(&serialized).unwrap(),
+            pretok
+        );
+    }
+}
@@ -1,5 +1,7 @@
 use crate::tokenizer::{Decoder, PreTokenizedString, PreTokenizer, Result, SplitDelimiterBehavior};
-use serde::{de, Deserialize, Deserializer, Serialize};
+use crate::utils::versioning::{serialize_schema_version, upgraded_fields, Versioned};
+use serde::{de, ser::SerializeStruct, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
 
 /// Enum representing options for the metaspace prepending scheme.
 #[derive(Debug, Clone, PartialEq, Serialize, Eq, Deserialize, Copy)]
@@ -19,18 +21,42 @@ impl std::fmt::Display for PrependScheme {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 /// Replaces all the whitespaces by the provided meta character and then
 /// splits on this character
-#[serde(tag = "type")]
 pub struct Metaspace {
     replacement: char,
     pub prepend_scheme: PrependScheme,
     pub split: bool,
-    #[serde(skip)]
     str_rep: String,
 }
 
+/// The schema didn't change since the versions were introduced. The fields of the releases
+/// before the `prepend_scheme`, with an `add_prefix_space` and no `split`, still are of the
+/// version 1, so that they are handled on deserialization.
+impl Versioned for Metaspace {
+    const SCHEMA_VERSION: u32 = 1;
+
+    fn upgrade(_: u32, _: &mut Map<String, Value>) -> std::result::Result<(), String> {
+        Ok(())
+    }
+}
+
+impl Serialize for Metaspace {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut metaspace = serializer.serialize_struct("Metaspace", 5)?;
+        metaspace.serialize_field("type", "Metaspace")?;
+        serialize_schema_version::<Self, _>(&mut metaspace)?;
+        metaspace.serialize_field("replacement", &self.replacement)?;
+        metaspace.serialize_field("prepend_scheme", &self.prepend_scheme)?;
+        metaspace.serialize_field("split", &self.split)?;
+        metaspace.end()
+    }
+}
+
 impl<'de> Deserialize<'de> for Metaspace {
     fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
     where
@@ -41,10 +67,6 @@ impl<'de> Deserialize<'de> for Metaspace {
             Metaspace,
         }
 
-        fn default_prepend_scheme_value() -> PrependScheme {
-            PrependScheme::Always
-        }
-
         #[derive(Deserialize)]
         pub struct MetaspaceHelper {
             #[serde(rename = "type")]
@@ -52,28 +74,30 @@ impl<'de> Deserialize<'de> for Metaspace {
             replacement: char,
 
             pub add_prefix_space: Option<bool>,
-            #[serde(default = "default_prepend_scheme_value")]
-            pub prepend_scheme: PrependScheme,
+            pub prepend_scheme: Option<PrependScheme>,
             pub split: Option<bool>,
             #[serde(rename = "str_rep")]
             _str_rep: Option<String>,
         }
 
-        let mut helper = MetaspaceHelper::deserialize(deserializer)?;
-        if let Some(false) = helper.add_prefix_space {
-            if helper.prepend_scheme != PrependScheme::Never {
+        let fields = upgraded_fields::<Self, _>(deserializer)?;
+        let helper: MetaspaceHelper =
+            serde_json::from_value(Value::Object(fields)).map_err(de::Error::custom)?;
+        // The files written before `prepend_scheme` only have `add_prefix_space`
+        let prepend_scheme = match (helper.add_prefix_space, helper.prepend_scheme) {
+            (Some(false), None | Some(PrependScheme::Never)) => PrependScheme::Never,
+            (Some(false), Some(_)) => {
                 return Err(de::Error::custom(
                     "add_prefix_space does not match declared prepend_scheme",
-                ));
+                ))
             }
-            helper.prepend_scheme = PrependScheme::Never;
-        }
-        let instance = Self::new(
+            (_, prepend_scheme) => prepend_scheme.unwrap_or(PrependScheme::Always),
+        };
+        Ok(Self::new(
             helper.replacement,
-            helper.prepend_scheme,
+            prepend_scheme,
             helper.split.unwrap_or(true),
-        );
-        Ok(instance)
+        ))
     }
 }
 
@@ -147,17 +171,27 @@ impl PreTokenizer for Metaspace {
     }
 }
 
-impl Decoder for Metaspace {
-    fn decode_chain(&self, tokens: Vec<String>) -> Result<Vec<String>> {
-        Ok(tokens
+impl Metaspace {
+    /// Replace the meta characters by spaces. Those of the first token are removed, unless
+    /// `preserve_whitespace` is set, in which case only its prefix one is.
+    pub(crate) fn decode_tokens(
+        &self,
+        tokens: Vec<String>,
+        preserve_whitespace: bool,
+    ) -> Vec<String> {
+        tokens
             .iter()
             .enumerate()
             .map(|(i, token)| {
                 token
                     .chars()
-                    .flat_map(|c| {
+                    .enumerate()
+                    .flat_map(|(j, c)| {
                         if c == self.replacement {
-                            if i == 0 && self.prepend_scheme != PrependScheme::Never {
+                            if i == 0
+                                && self.prepend_scheme != PrependScheme::Never
+                                && (j == 0 || !preserve_whitespace)
+                            {
                                 None
                             } else {
                                 Some(' ')
@@ -168,7 +202,17 @@ impl Decoder for Metaspace {
                     })
                     .collect::<String>()
             })
-            .collect())
+            .collect()
+    }
+}
+
+impl Decoder for Metaspace {
+    fn decode_chain(&self, tokens: Vec<String>) -> Result<Vec<String>> {
+        Ok(self.decode_tokens(tokens, false))
+    }
+
+    fn decode_preserving_whitespace(&self, tokens: Vec<String>) -> Result<String> {
+        Ok(self.decode_tokens(tokens, true).concat())
     }
 }
 
@@ -206,6 +250,28 @@ mod tests {
         )
         .unwrap();
         assert_eq!(metaspace_parsed, metaspace);
+
+        // The files written before the schema versions may already use the new fields
+        let metaspace_s =
+            r#"{"type":"Metaspace","replacement":"_","prepend_scheme":"never","split":false}"#;
+        assert_eq!(
+            serde_json::from_str::<Metaspace>(metaspace_s).unwrap(),
+            Metaspace::new('_', PrependScheme::Never, false)
+        );
+        let metaspace_s = r#"{"type":"Metaspace","replacement":"_","add_prefix_space":false}"#;
+        assert_eq!(
+            serde_json::from_str::<Metaspace>(metaspace_s).unwrap(),
+            Metaspace::new('_', PrependScheme::Never, true)
+        );
+
+        // The versions more recent than the current one are refused
+        let metaspace_s = r#"{"type":"Metaspace","schema_version":2,"replacement":"_","prepend_scheme":"always","split":true}"#;
+        assert!(serde_json::from_str::<Metaspace>(metaspace_s).is_err());
+        let metaspace_s = r#"{"type":"Metaspace","schema_version":1,"replacement":"_","prepend_scheme":"always","split":true}"#;
+        assert_eq!(
+            serde_json::from_str::<Metaspace>(metaspace_s).unwrap(),
+            metaspace
+        );
     }
 
     #[test]
@@ -367,4 +433,39 @@ mod tests {
             .unwrap();
         assert_eq!(res, vec![" Hey", " friend!"]);
     }
+
+    #[test]
+    fn decode_preserving_whitespace() {
+        let code = "def f(x):\n    if x:\n\treturn  x\n\n";
+        for split in [true, false] {
+            let metaspace = Metaspace::new('▁', PrependScheme::Always, split);
+            let mut pretokenized = PreTokenizedString::from(code);
+            metaspace.pre_tokenize(&mut pretokenized).unwrap();
+            let tokens = pretokenized
+                .get_splits(OffsetReferential::Original, OffsetType::Byte)
+                .into_iter()
+                .map(|(s, _, _)| s.to_string())
+                .collect::<Vec<_>>();
+            assert_eq!(
+                metaspace.decode_preserving_whitespace(tokens).unwrap(),
+                code
+            );
+        }
+
+        // Only the prefix space of the first token is removed
+        let decoder = Metaspace::new('▁', PrependScheme::Always, false);
+        let tokens = vec!["▁a▁▁b".to_string(), "▁c".to_string()];
+        assert_eq!(decoder.decode(tokens.clone()).unwrap(), "ab c");
+        assert_eq!(
+            decoder
+                .decode_preserving_whitespace(tokens.clone())
+                .unwrap(),
+            "a  b c"
+        );
+        let decoder = Metaspace::new('▁', PrependScheme::Never, false);
+        assert_eq!(
+            decoder.decode_preserving_whitespace(tokens).unwrap(),
+            " a  b c"
+        );
+    }
 }
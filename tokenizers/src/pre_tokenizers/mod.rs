@@ -3,11 +3,17 @@ pub mod byte_level;
 pub mod delimiter;
 pub mod digits;
 pub mod fixed_length;
+pub mod indentation;
 pub mod metaspace;
+pub mod model_split;
 pub mod punctuation;
+pub mod regex_split;
+pub mod run_length;
+pub mod script_split;
 pub mod sequence;
 pub mod split;
 pub mod unicode_scripts;
+pub mod unicode_words;
 pub mod whitespace;
 
 use serde::{Deserialize, Deserializer, Serialize};
@@ -17,11 +23,17 @@ use crate::pre_tokenizers::byte_level::ByteLevel;
 use crate::pre_tokenizers::delimiter::CharDelimiterSplit;
 use crate::pre_tokenizers::digits::Digits;
 use crate::pre_tokenizers::fixed_length::FixedLength;
+use crate::pre_tokenizers::indentation::Indentation;
 use crate::pre_tokenizers::metaspace::Metaspace;
+use crate::pre_tokenizers::model_split::ModelSplit;
 use crate::pre_tokenizers::punctuation::Punctuation;
+use crate::pre_tokenizers::regex_split::RegexSplit;
+use crate::pre_tokenizers::run_length::RunLength;
+use crate::pre_tokenizers::script_split::ScriptSplit;
 use crate::pre_tokenizers::sequence::Sequence;
 use crate::pre_tokenizers::split::Split;
 use crate::pre_tokenizers::unicode_scripts::UnicodeScripts;
+use crate::pre_tokenizers::unicode_words::UnicodeWords;
 use crate::pre_tokenizers::whitespace::{Whitespace, WhitespaceSplit};
 use crate::{PreTokenizedString, PreTokenizer};
 
@@ -40,6 +52,12 @@ pub enum PreTokenizerWrapper {
     Digits(Digits),
     UnicodeScripts(UnicodeScripts),
     FixedLength(FixedLength),
+    RegexSplit(RegexSplit),
+    Indentation(Indentation),
+    RunLength(RunLength),
+    UnicodeWords(UnicodeWords),
+    ScriptSplit(ScriptSplit),
+    ModelSplit(ModelSplit),
 }
 
 impl PreTokenizer for PreTokenizerWrapper {
@@ -57,6 +75,12 @@ impl PreTokenizer for PreTokenizerWrapper {
             Self::Digits(wspt) => wspt.pre_tokenize(normalized),
             Self::UnicodeScripts(us) => us.pre_tokenize(normalized),
             Self::FixedLength(fl) => fl.pre_tokenize(normalized),
+            Self::RegexSplit(rs) => rs.pre_tokenize(normalized),
+            Self::Indentation(ind) => ind.pre_tokenize(normalized),
+            Self::RunLength(rl) => rl.pre_tokenize(normalized),
+            Self::UnicodeWords(uw) => uw.pre_tokenize(normalized),
+            Self::ScriptSplit(ss) => ss.pre_tokenize(normalized),
+            Self::ModelSplit(ms) => ms.pre_tokenize(normalized),
         }
     }
 }
@@ -87,6 +111,12 @@ impl<'de> Deserialize<'de> for PreTokenizerWrapper {
             Digits,
             UnicodeScripts,
             FixedLength,
+            RegexSplit,
+            Indentation,
+            RunLength,
+            UnicodeWords,
+            ScriptSplit,
+            ModelSplit,
         }
 
         #[derive(Deserialize)]
@@ -111,6 +141,12 @@ impl<'de> Deserialize<'de> for PreTokenizerWrapper {
             Digits(Digits),
             UnicodeScripts(UnicodeScripts),
             FixedLength(FixedLength),
+            RegexSplit(RegexSplit),
+            Indentation(Indentation),
+            RunLength(RunLength),
+            UnicodeWords(UnicodeWords),
+            ScriptSplit(ScriptSplit),
+            ModelSplit(ModelSplit),
         }
 
         let helper = PreTokenizerHelper::deserialize(deserializer)?;
@@ -161,6 +197,24 @@ impl<'de> Deserialize<'de> for PreTokenizerWrapper {
                     EnumType::FixedLength => PreTokenizerWrapper::FixedLength(
                         serde_json::from_value(values).map_err(serde::de::Error::custom)?,
                     ),
+                    EnumType::RegexSplit => PreTokenizerWrapper::RegexSplit(
+                        serde_json::from_value(values).map_err(serde::de::Error::custom)?,
+                    ),
+                    EnumType::Indentation => PreTokenizerWrapper::Indentation(
+                        serde_json::from_value(values).map_err(serde::de::Error::custom)?,
+                    ),
+                    EnumType::RunLength => PreTokenizerWrapper::RunLength(
+                        serde_json::from_value(values).map_err(serde::de::Error::custom)?,
+                    ),
+                    EnumType::UnicodeWords => PreTokenizerWrapper::UnicodeWords(
+                        serde_json::from_value(values).map_err(serde::de::Error::custom)?,
+                    ),
+                    EnumType::ScriptSplit => PreTokenizerWrapper::ScriptSplit(
+                        serde_json::from_value(values).map_err(serde::de::Error::custom)?,
+                    ),
+                    EnumType::ModelSplit => PreTokenizerWrapper::ModelSplit(
+                        serde_json::from_value(values).map_err(serde::de::Error::custom)?,
+                    ),
                 }
             }
 
@@ -199,6 +253,24 @@ impl<'de> Deserialize<'de> for PreTokenizerWrapper {
                     PreTokenizerUntagged::FixedLength(fixed_length) => {
                         PreTokenizerWrapper::FixedLength(fixed_length)
                     }
+                    PreTokenizerUntagged::RegexSplit(regex_split) => {
+                        PreTokenizerWrapper::RegexSplit(regex_split)
+                    }
+                    PreTokenizerUntagged::Indentation(indentation) => {
+                        PreTokenizerWrapper::Indentation(indentation)
+                    }
+                    PreTokenizerUntagged::RunLength(run_length) => {
+                        PreTokenizerWrapper::RunLength(run_length)
+                    }
+                    PreTokenizerUntagged::UnicodeWords(unicode_words) => {
+                        PreTokenizerWrapper::UnicodeWords(unicode_words)
+                    }
+                    PreTokenizerUntagged::ScriptSplit(script_split) => {
+                        PreTokenizerWrapper::ScriptSplit(script_split)
+                    }
+                    PreTokenizerUntagged::ModelSplit(model_split) => {
+                        PreTokenizerWrapper::ModelSplit(model_split)
+                    }
                 }
             }
         })
@@ -217,6 +289,12 @@ impl_enum_from!(WhitespaceSplit, PreTokenizerWrapper, WhitespaceSplit);
 impl_enum_from!(Digits, PreTokenizerWrapper, Digits);
 impl_enum_from!(UnicodeScripts, PreTokenizerWrapper, UnicodeScripts);
 impl_enum_from!(FixedLength, PreTokenizerWrapper, FixedLength);
+impl_enum_from!(RegexSplit, PreTokenizerWrapper, RegexSplit);
+impl_enum_from!(Indentation, PreTokenizerWrapper, Indentation);
+impl_enum_from!(RunLength, PreTokenizerWrapper, RunLength);
+impl_enum_from!(UnicodeWords, PreTokenizerWrapper, UnicodeWords);
+impl_enum_from!(ScriptSplit, PreTokenizerWrapper, ScriptSplit);
+impl_enum_from!(ModelSplit, PreTokenizerWrapper, ModelSplit);
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,121 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::ModelWrapper;
+use crate::normalizer::Range;
+use crate::normalizers::NormalizerWrapper;
+use crate::tokenizer::{Model, Normalizer, PreTokenizedString, PreTokenizer, Result};
+use crate::utils::macro_rules_attribute;
+
+#[derive(Clone, Debug)]
+/// Splits each pre-token into the tokens found by a `model`, which lets a tokenizer feed the
+/// tokens of another one to its own model, like the morphemes found by a morphological
+/// model to a `BPE`. See [`Tokenizer::chain`](crate::Tokenizer::chain).
+///
+/// The pieces keep their offsets in the original text. The text the model leaves out of its
+/// tokens, if any, is kept in pieces of its own. Each piece is then normalized with the
+/// `normalizer`, if any.
+#[non_exhaustive]
+#[macro_rules_attribute(impl_serde_type!)]
+pub struct ModelSplit {
+    pub model: Box<ModelWrapper>,
+    pub normalizer: Option<NormalizerWrapper>,
+}
+
+impl ModelSplit {
+    pub fn new(model: impl Into<ModelWrapper>, normalizer: Option<NormalizerWrapper>) -> Self {
+        Self {
+            model: Box::new(model.into()),
+            normalizer,
+        }
+    }
+}
+
+impl PartialEq for ModelSplit {
+    fn eq(&self, other: &Self) -> bool {
+        // The normalizers can't be compared, but their serializations can
+        self.model == other.model
+            && serde_json::to_value(&self.normalizer).ok()
+                == serde_json::to_value(&other.normalizer).ok()
+    }
+}
+
+impl PreTokenizer for ModelSplit {
+    fn pre_tokenize(&self, pretokenized: &mut PreTokenizedString) -> Result<()> {
+        pretokenized.split(|_, normalized| {
+            let text = normalized.get();
+            // Several tokens can share the same offsets, like the bytes of a byte fallback
+            let mut boundaries = vec![0, text.len()];
+            for token in self.model.tokenize(text)? {
+                let (start, end) = token.offsets;
+                boundaries.extend([start.min(text.len()), end.min(text.len())]);
+            }
+            boundaries.sort_unstable();
+            boundaries.dedup();
+
+            boundaries
+                .windows(2)
+                .map(|range| {
+                    let mut piece = normalized
+                        .slice(Range::Normalized(range[0]..range[1]))
+                        .ok_or("Failed to slice normalized text")?;
+                    if let Some(normalizer) = &self.normalizer {
+                        normalizer.normalize(&mut piece)?;
+                    }
+                    Ok(piece)
+                })
+                .collect::<Result<Vec<_>>>()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::wordpiece::WordPiece;
+    use crate::normalizers::utils::Lowercase;
+    use crate::{OffsetReferential, OffsetType};
+
+    #[test]
+    fn split() {
+        let vocab = [("[UNK]", 0), ("un", 1), ("##do", 2), ("##ing", 3)]
+            .iter()
+            .map(|(token, id)| (token.to_string(), *id))
+            .collect::<ahash::AHashMap<_, _>>();
+        let model = WordPiece::builder().vocab(vocab).build().unwrap();
+        let pretok = ModelSplit::new(model, Some(Lowercase.into()));
+
+        let mut pretokenized = PreTokenizedString::from("UNDOING");
+        pretok.pre_tokenize(&mut pretokenized).unwrap();
+        // Tokenized on its own, the word is unknown
+        assert_eq!(
+            pretokenized
+                .get_splits(OffsetReferential::Original, OffsetType::Byte)
+                .into_iter()
+                .map(|(s, o, _)| (s, o))
+                .collect::<Vec<_>>(),
+            vec![("undoing", (0, 7))]
+        );
+
+        let mut pretokenized = PreTokenizedString::from("undoing");
+        pretok.pre_tokenize(&mut pretokenized).unwrap();
+        assert_eq!(
+            pretokenized
+                .get_splits(OffsetReferential::Original, OffsetType::Byte)
+                .into_iter()
+                .map(|(s, o, _)| (s, o))
+                .collect::<Vec<_>>(),
+            vec![("un", (0, 2)), ("do", (2, 4)), ("ing", (4, 7))]
+        );
+    }
+
+    #[test]
+    fn serialization() {
+        let pretok = ModelSplit::new(WordPiece::default(), None);
+        let serialized = serde_json::to_string(&pretok).unwrap();
+        assert!(serialized.starts_with(r#"{"type":"ModelSplit","model":{"type":"WordPiece""#));
+        assert_eq!(
+            serde_json::from_str::<ModelSplit>(&serialized).unwrap(),
+            pretok
+        );
+    }
+}
@@ -0,0 +1,256 @@
+use crate::normalizer::Range;
+use crate::tokenizer::{Offsets, PreTokenizedString, PreTokenizer, Result};
+use crate::utils::SysRegex;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::cmp::Reverse;
+
+/// Splits the text on the matches of a full regex pattern, just like `re.findall` would,
+/// which makes it possible to use the pre-tokenization patterns of GPT-4 or LLaMA 3 as is.
+///
+/// The pattern is compiled by the regex engine of the crate (Oniguruma, or `fancy-regex`
+/// with the `fancy-regex` feature), which supports lookarounds, inline flags and unicode
+/// classes like `\p{L}`.
+///
+/// Each match becomes a piece. When the pattern has capture groups, each group that
+/// participated in the match becomes a piece instead, and the rest of the match is removed.
+/// The nested groups, and those reaching out of the match through a lookaround, are
+/// ignored. Contrarily to `re.findall`, the text that doesn't match is never lost, and gets
+/// its own pieces.
+///
+/// ```
+/// use tokenizers::pre_tokenizers::regex_split::RegexSplit;
+/// use tokenizers::{OffsetReferential, OffsetType, PreTokenizedString, PreTokenizer};
+///
+/// let pieces = |pretok: &RegexSplit, text: &str| {
+///     let mut pretokenized = PreTokenizedString::from(text);
+///     pretok.pre_tokenize(&mut pretokenized).unwrap();
+///     pretokenized
+///         .get_splits(OffsetReferential::Original, OffsetType::Byte)
+///         .into_iter()
+///         .map(|(s, _, _)| s.to_owned())
+///         .collect::<Vec<_>>()
+/// };
+///
+/// let pretok = RegexSplit::new(r"'s| ?\p{L}+|\s+(?!\S)|\s+").unwrap();
+/// assert_eq!(pieces(&pretok, "it's  ok"), ["it", "'s", " ", " ok"]);
+///
+/// // Only the groups are kept
+/// let pretok = RegexSplit::new(r"(\w+)=").unwrap();
+/// assert_eq!(pieces(&pretok, "a=1"), ["a", "1"]);
+/// ```
+#[derive(Debug, Serialize)]
+#[serde(tag = "type")]
+pub struct RegexSplit {
+    pattern: String,
+    #[serde(skip)]
+    regex: SysRegex,
+}
+
+impl<'de> Deserialize<'de> for RegexSplit {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        enum Type {
+            RegexSplit,
+        }
+
+        #[derive(Deserialize)]
+        pub struct RegexSplitHelper {
+            #[serde(rename = "type")]
+            _type: Type,
+            pattern: String,
+        }
+
+        let helper = RegexSplitHelper::deserialize(deserializer)?;
+        Self::new(&helper.pattern).map_err(serde::de::Error::custom)
+    }
+}
+
+impl Clone for RegexSplit {
+    fn clone(&self) -> Self {
+        Self {
+            pattern: self.pattern.clone(),
+            regex: self.regex.clone(),
+        }
+    }
+}
+
+impl PartialEq for RegexSplit {
+    fn eq(&self, other: &Self) -> bool {
+        self.pattern == other.pattern
+    }
+}
+
+impl RegexSplit {
+    pub fn new(pattern: &str) -> Result<Self> {
+        Ok(Self {
+            pattern: pattern.to_owned(),
+            regex: SysRegex::new(pattern)?,
+        })
+    }
+
+    /// Split on the matches of an already compiled regex, which may be shared with other
+    /// components instead of being compiled again
+    pub fn from_regex(regex: SysRegex) -> Self {
+        Self {
+            pattern: regex.as_str().to_owned(),
+            regex,
+        }
+    }
+
+    pub fn get_pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// The offsets of the pieces of the given string
+    fn pieces(&self, inside: &str) -> Vec<Offsets> {
+        let has_groups = self.regex.captures_len() > 0;
+        let mut pieces = vec![];
+        let mut prev = 0;
+        for captures in self.regex.captures_iter(inside) {
+            let (start, end) = match captures[0] {
+                Some(offsets) => offsets,
+                None => continue,
+            };
+            if prev < start {
+                pieces.push((prev, start));
+            }
+            if has_groups {
+                let mut groups = captures[1..].iter().flatten().copied().collect::<Vec<_>>();
+                groups.sort_by_key(|(group_start, group_end)| (*group_start, Reverse(*group_end)));
+                let mut last = start;
+                for (group_start, group_end) in groups {
+                    if group_start >= last && group_start < group_end && group_end <= end {
+                        pieces.push((group_start, group_end));
+                        last = group_end;
+                    }
+                }
+            } else if start < end {
+                pieces.push((start, end));
+            }
+            prev = prev.max(end);
+        }
+        if prev < inside.len() {
+            pieces.push((prev, inside.len()));
+        }
+        pieces
+    }
+}
+
+impl PreTokenizer for RegexSplit {
+    fn pre_tokenize(&self, pretokenized: &mut PreTokenizedString) -> Result<()> {
+        pretokenized.split(|_, normalized| {
+            Ok(self
+                .pieces(normalized.get())
+                .into_iter()
+                .filter_map(|(start, end)| normalized.slice(Range::Normalized(start..end)))
+                .collect::<Vec<_>>())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pre_tokenizers::PreTokenizerWrapper;
+    use crate::{NormalizedString, OffsetReferential, OffsetType};
+
+    fn pieces(pretok: &RegexSplit, text: &str) -> Vec<(String, Offsets)> {
+        let mut pretokenized = PreTokenizedString::from(text);
+        pretok.pre_tokenize(&mut pretokenized).unwrap();
+        pretokenized
+            .get_splits(OffsetReferential::Original, OffsetType::Byte)
+            .into_iter()
+            .map(|(s, o, _)| (s.to_owned(), o))
+            .collect()
+    }
+
+    #[test]
+    fn llama3_pattern() {
+        let pretok = RegexSplit::new(
+            r"(?i:'s|'t|'re|'ve|'m|'ll|'d)|[^\r\n\p{L}\p{N}]?\p{L}+|\p{N}{1,3}| ?[^\s\p{L}\p{N}]+[\r\n]*|\s*[\r\n]+|\s+(?!\S)|\s+",
+        )
+        .unwrap();
+        assert_eq!(
+            pieces(&pretok, "I'M here  1234 ok?\n"),
+            vec![
+                ("I".into(), (0, 1)),
+                ("'M".into(), (1, 3)),
+                (" here".into(), (3, 8)),
+                (" ".into(), (8, 9)),
+                (" ".into(), (9, 10)),
+                ("123".into(), (10, 13)),
+                ("4".into(), (13, 14)),
+                (" ok".into(), (14, 17)),
+                ("?\n".into(), (17, 19)),
+            ]
+        );
+    }
+
+    #[test]
+    fn capture_groups() {
+        let pretok = RegexSplit::new(r"<(\w+)>").unwrap();
+        assert_eq!(
+            pieces(&pretok, "a<b>c"),
+            vec![
+                ("a".into(), (0, 1)),
+                ("b".into(), (2, 3)),
+                ("c".into(), (4, 5))
+            ]
+        );
+
+        // Nested groups, and groups out of the match, are ignored
+        let pretok = RegexSplit::new(r"((a)b)|(c)(?=(d))").unwrap();
+        assert_eq!(
+            pieces(&pretok, "abcd"),
+            vec![
+                ("ab".into(), (0, 2)),
+                ("c".into(), (2, 3)),
+                ("d".into(), (3, 4))
+            ]
+        );
+
+        // Groups that didn't participate, or are empty, produce nothing
+        let pretok = RegexSplit::new(r"(x)?(y*)z").unwrap();
+        assert_eq!(pieces(&pretok, "zz"), vec![]);
+    }
+
+    #[test]
+    fn normalized_offsets() {
+        let pretok = RegexSplit::new(r"\p{L}+").unwrap();
+        let mut normalized = NormalizedString::from("Héllo wörld");
+        normalized.lowercase();
+        let mut pretokenized = PreTokenizedString::from(normalized);
+        pretok.pre_tokenize(&mut pretokenized).unwrap();
+        assert_eq!(
+            pretokenized
+                .get_splits(OffsetReferential::Original, OffsetType::Char)
+                .into_iter()
+                .map(|(s, o, _)| (s, o))
+                .collect::<Vec<_>>(),
+            vec![("héllo", (0, 5)), (" ", (5, 6)), ("wörld", (6, 11))]
+        );
+    }
+
+    #[test]
+    fn invalid_pattern() {
+        assert!(RegexSplit::new(r"(?<!").is_err());
+    }
+
+    #[test]
+    fn serialization() {
+        let pretok = RegexSplit::new(r"\s+(?!\S)").unwrap();
+        let pretok_s = r#"{"type":"RegexSplit","pattern":"\\s+(?!\\S)"}"#;
+        assert_eq!(serde_json::to_string(&pretok).unwrap(), pretok_s);
+        assert_eq!(
+            serde_json::from_str::<RegexSplit>(pretok_s).unwrap(),
+            pretok
+        );
+        assert_eq!(
+            serde_json::from_str::<PreTokenizerWrapper>(pretok_s).unwrap(),
+            PreTokenizerWrapper::RegexSplit(pretok)
+        );
+    }
+}
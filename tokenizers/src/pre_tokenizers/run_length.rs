@@ -0,0 +1,221 @@
+use serde::{Deserialize, Serialize};
+
+use crate::normalizer::Range;
+use crate::tokenizer::{Decoder, PreTokenizedString, PreTokenizer, Result};
+use crate::utils::macro_rules_attribute;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// Collapses the runs of a same character longer than `max_run` into two pieces: the
+/// character itself, followed by a count piece made of the length of the run between two
+/// `marker`s, so that `"aaaaaaaaaa"` becomes `"a"`, `"⟳10⟳"`. This protects the model from
+/// the arbitrarily long runs found in adversarial inputs, while the count pieces stay few
+/// enough to be learned.
+///
+/// As a decoder, it expands the count pieces back to the original runs. The closing marker
+/// keeps the digits following a count apart from it. Any text made of a character followed
+/// by digits between two markers is expanded as well, the marker is thus best chosen out of
+/// the expected inputs.
+#[non_exhaustive]
+#[macro_rules_attribute(impl_serde_type!)]
+pub struct RunLength {
+    #[serde(default = "default_max_run")]
+    pub max_run: usize,
+    #[serde(default = "default_marker")]
+    pub marker: char,
+}
+
+fn default_max_run() -> usize {
+    8
+}
+
+fn default_marker() -> char {
+    '⟳'
+}
+
+impl RunLength {
+    pub fn new(max_run: usize, marker: char) -> Self {
+        Self { max_run, marker }
+    }
+}
+
+impl Default for RunLength {
+    fn default() -> Self {
+        Self::new(default_max_run(), default_marker())
+    }
+}
+
+/// The byte ranges of the runs of a same character longer than `max_run`, with the
+/// length of each run
+fn long_runs(text: &str, max_run: usize) -> Vec<(std::ops::Range<usize>, usize)> {
+    let mut runs = vec![];
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let mut end = start + c.len_utf8();
+        let mut len = 1;
+        while let Some((i, _)) = chars.next_if(|(_, next)| *next == c) {
+            end = i + c.len_utf8();
+            len += 1;
+        }
+        if len > max_run.max(1) {
+            runs.push((start..end, len));
+        }
+    }
+    runs
+}
+
+impl PreTokenizer for RunLength {
+    fn pre_tokenize(&self, pretokenized: &mut PreTokenizedString) -> Result<()> {
+        pretokenized.split(|_, normalized| {
+            let text = normalized.get();
+            let slice = |range| {
+                normalized
+                    .slice(Range::Normalized(range))
+                    .ok_or("Failed to slice normalized text")
+            };
+
+            let mut splits = vec![];
+            let mut start = 0;
+            for (run, len) in long_runs(text, self.max_run) {
+                if start < run.start {
+                    splits.push(slice(start..run.start)?);
+                }
+                let first_end = run.start + text[run.start..].chars().next().unwrap().len_utf8();
+                splits.push(slice(run.start..first_end)?);
+
+                let mut count = slice(first_end..run.end)?;
+                let repeated = count.get().to_owned();
+                count.replace(
+                    repeated.as_str(),
+                    &format!("{}{len}{}", self.marker, self.marker),
+                )?;
+                splits.push(count);
+                start = run.end;
+            }
+            if start < text.len() {
+                splits.push(slice(start..text.len())?);
+            }
+            Ok(splits)
+        })
+    }
+}
+
+impl Decoder for RunLength {
+    fn decode_chain(&self, tokens: Vec<String>) -> Result<Vec<String>> {
+        let text = tokens.concat();
+        let mut decoded = String::with_capacity(text.len());
+        let mut chars = text.chars();
+        while let Some(c) = chars.next() {
+            let previous = decoded.chars().last();
+            match previous {
+                Some(previous) if c == self.marker => {
+                    // A count, only when its digits are followed by the closing marker
+                    let rest = chars.as_str();
+                    let digits =
+                        rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+                    match rest[digits..].strip_prefix(self.marker) {
+                        Some(after) if digits > 0 => {
+                            let len: usize =
+                                rest[..digits].parse().map_err(|_| "Run length too long")?;
+                            decoded.extend(std::iter::repeat_n(previous, len.saturating_sub(1)));
+                            chars = after.chars();
+                        }
+                        _ => decoded.push(c),
+                    }
+                }
+                _ => decoded.push(c),
+            }
+        }
+        Ok(vec![decoded])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{OffsetReferential, OffsetType};
+
+    fn splits(pretok: &RunLength, s: &str) -> Vec<(String, (usize, usize))> {
+        let mut pretokenized = PreTokenizedString::from(s);
+        pretok.pre_tokenize(&mut pretokenized).unwrap();
+        pretokenized
+            .get_splits(OffsetReferential::Original, OffsetType::Byte)
+            .into_iter()
+            .map(|(s, o, _)| (s.to_owned(), o))
+            .collect()
+    }
+
+    #[test]
+    fn pre_tokenize() {
+        let pretok = RunLength::new(3, '#');
+        assert_eq!(
+            splits(&pretok, "aaab ccccccd"),
+            vec![
+                ("aaab ".into(), (0, 5)),
+                ("c".into(), (5, 6)),
+                ("#6#".into(), (6, 11)),
+                ("d".into(), (11, 12)),
+            ]
+        );
+        assert_eq!(
+            splits(&pretok, "éééé"),
+            vec![("é".into(), (0, 2)), ("#4#".into(), (2, 8))]
+        );
+        assert_eq!(splits(&pretok, ""), vec![]);
+    }
+
+    #[test]
+    fn decode() {
+        let decoder = RunLength::new(3, '#');
+        let tokens = ["aaab", " c", "#6", "#", "d", "#", "é#4#", "#12", "#", "#1"];
+        let decoded = decoder
+            .decode_chain(tokens.iter().map(|t| t.to_string()).collect())
+            .unwrap();
+        assert_eq!(decoded, vec![format!("aaab ccccccd#{}#1", "é".repeat(15))]);
+
+        assert!(decoder
+            .decode_chain(vec![format!("a#{}9#", usize::MAX)])
+            .is_err());
+    }
+
+    #[test]
+    fn round_trip() {
+        let pretok = RunLength::default();
+        let text = format!("wait...... what?!{} ok{}", "?".repeat(13), "=".repeat(20));
+        let tokens = splits(&pretok, &text)
+            .into_iter()
+            .map(|(s, _)| s)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tokens,
+            vec!["wait...... what?!", "?", "⟳13⟳", " ok", "=", "⟳20⟳"]
+        );
+        assert_eq!(pretok.decode(tokens).unwrap(), text);
+
+        // The digits following a run
+        for text in ["10000000001", "1000000000000", "7777777777 7"] {
+            let tokens = splits(&pretok, text)
+                .into_iter()
+                .map(|(s, _)| s)
+                .collect::<Vec<_>>();
+            assert_eq!(pretok.decode(tokens).unwrap(), text);
+        }
+    }
+
+    #[test]
+    fn serialization() {
+        let pretok = RunLength::new(4, '#');
+        let serialized = serde_json::to_string(&pretok).unwrap();
+        assert_eq!(
+            serialized,
+            r##"{"type":"RunLength","max_run":4,"marker":"#"}"##
+        );
+        assert_eq!(
+            serde_json::from_str::<RunLength>(&serialized).unwrap(),
+            pretok
+        );
+        assert_eq!(
+            serde_json::from_str::<RunLength>(r#"{"type":"RunLength"}"#).unwrap(),
+            RunLength::default()
+        );
+    }
+}
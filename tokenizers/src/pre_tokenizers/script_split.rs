@@ -0,0 +1,216 @@
+use serde::{Deserialize, Serialize};
+
+use crate::normalizer::Range;
+use crate::pre_tokenizers::unicode_scripts::{fixed_script, get_script, Script};
+use crate::tokenizer::{PreTokenizedString, PreTokenizer, Result};
+use crate::utils::macro_rules_attribute;
+
+/// Which scripts are told apart by the [`ScriptSplit`] pre-tokenizer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScriptGranularity {
+    /// Each Unicode script is told apart, including Han, Hiragana and Katakana
+    Script,
+    /// The scripts used together by a single writing system are kept together: Han,
+    /// Hiragana and Katakana for Japanese, like `UnicodeScripts` does
+    WritingSystem,
+}
+
+/// What the [`ScriptSplit`] pre-tokenizer does with the chars of the Common script, like the
+/// spaces, the punctuation, the digits and the symbols
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommonScript {
+    /// They join the script before them, or the one after them at the start of the text
+    Merged,
+    /// Each run of them is split on its own
+    Isolated,
+}
+
+impl std::fmt::Display for ScriptGranularity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.serialize(f)
+    }
+}
+
+impl std::fmt::Display for CommonScript {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.serialize(f)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// Splits the text where the Unicode script changes, so that no piece mixes several
+/// scripts. This keeps apart the words of different languages written without spaces
+/// between them, and stops the strings mixing Latin and Cyrillic homoglyphs, like
+/// `"pаypal"` with a Cyrillic `а`, from forming merges of their own.
+///
+/// The chars of the Inherited script, like the combining marks, always stay with the char
+/// before them. The chars of the Common script are handled according to `common`, and the
+/// scripts told apart according to `granularity`.
+#[non_exhaustive]
+#[macro_rules_attribute(impl_serde_type!)]
+pub struct ScriptSplit {
+    pub granularity: ScriptGranularity,
+    pub common: CommonScript,
+}
+
+impl ScriptSplit {
+    pub fn new(granularity: ScriptGranularity, common: CommonScript) -> Self {
+        Self {
+            granularity,
+            common,
+        }
+    }
+
+    /// The script of `c`, or `None` if it takes the script of the chars around it
+    fn script(&self, c: char) -> Option<Script> {
+        let script = match self.granularity {
+            ScriptGranularity::Script => get_script(c),
+            ScriptGranularity::WritingSystem => match fixed_script(c) {
+                Script::Any => Script::Common,
+                script => script,
+            },
+        };
+        match (script, self.common) {
+            (Script::Inherited, _) | (Script::Common, CommonScript::Merged) => None,
+            (script, _) => Some(script),
+        }
+    }
+
+    /// The byte offsets where the script changes in `text`
+    fn transitions(&self, text: &str) -> Vec<usize> {
+        let mut last = None;
+        text.char_indices()
+            .filter_map(|(i, c)| {
+                let script = self.script(c)?;
+                let transition = last.is_some_and(|last| last != script);
+                last = Some(script);
+                transition.then_some(i)
+            })
+            .collect()
+    }
+}
+
+impl Default for ScriptSplit {
+    fn default() -> Self {
+        Self::new(ScriptGranularity::WritingSystem, CommonScript::Merged)
+    }
+}
+
+impl PreTokenizer for ScriptSplit {
+    fn pre_tokenize(&self, pretokenized: &mut PreTokenizedString) -> Result<()> {
+        pretokenized.split(|_, normalized| {
+            let text = normalized.get();
+            let transitions = self.transitions(text);
+            std::iter::once(0)
+                .chain(transitions.iter().copied())
+                .zip(
+                    transitions
+                        .iter()
+                        .copied()
+                        .chain(std::iter::once(text.len())),
+                )
+                .filter(|(start, end)| start < end)
+                .map(|(start, end)| {
+                    normalized
+                        .slice(Range::Normalized(start..end))
+                        .ok_or_else(|| "Failed to slice normalized text".into())
+                })
+                .collect::<Result<Vec<_>>>()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{OffsetReferential, OffsetType};
+
+    fn splits(pretok: &ScriptSplit, s: &str) -> Vec<(String, (usize, usize))> {
+        let mut pretokenized = PreTokenizedString::from(s);
+        pretok.pre_tokenize(&mut pretokenized).unwrap();
+        pretokenized
+            .get_splits(OffsetReferential::Original, OffsetType::Byte)
+            .into_iter()
+            .map(|(s, o, _)| (s.to_owned(), o))
+            .collect()
+    }
+
+    fn values(pretok: &ScriptSplit, s: &str) -> Vec<String> {
+        splits(pretok, s).into_iter().map(|(s, _)| s).collect()
+    }
+
+    #[test]
+    fn homoglyphs() {
+        let pretok = ScriptSplit::default();
+        // With a Cyrillic "а"
+        assert_eq!(
+            splits(&pretok, "pаypal"),
+            vec![
+                ("p".into(), (0, 1)),
+                ("а".into(), (1, 3)),
+                ("ypal".into(), (3, 7))
+            ]
+        );
+        assert_eq!(values(&pretok, "paypal"), vec!["paypal"]);
+        assert_eq!(values(&pretok, ""), Vec::<String>::new());
+    }
+
+    #[test]
+    fn common() {
+        let pretok = ScriptSplit::default();
+        assert_eq!(
+            values(&pretok, "¿Hello, мир! 42"),
+            vec!["¿Hello, ", "мир! 42"]
+        );
+
+        let pretok = ScriptSplit::new(ScriptGranularity::WritingSystem, CommonScript::Isolated);
+        assert_eq!(
+            values(&pretok, "¿Hello, мир! 42"),
+            vec!["¿", "Hello", ", ", "мир", "! 42"]
+        );
+    }
+
+    #[test]
+    fn inherited() {
+        // The combining marks stay on the char before them, even when it is isolated
+        let pretok = ScriptSplit::default();
+        assert_eq!(values(&pretok, "e\u{301}д"), vec!["e\u{301}", "д"]);
+        let pretok = ScriptSplit::new(ScriptGranularity::Script, CommonScript::Isolated);
+        assert_eq!(values(&pretok, "a1\u{20e3}b"), vec!["a", "1\u{20e3}", "b"]);
+    }
+
+    #[test]
+    fn granularity() {
+        let pretok = ScriptSplit::default();
+        assert_eq!(
+            values(&pretok, "東京タワーへ行くTokyo"),
+            vec!["東京タワーへ行く", "Tokyo"]
+        );
+
+        let pretok = ScriptSplit::new(ScriptGranularity::Script, CommonScript::Merged);
+        assert_eq!(
+            values(&pretok, "東京タワーへ行くTokyo"),
+            vec!["東京", "タワー", "へ", "行", "く", "Tokyo"]
+        );
+    }
+
+    #[test]
+    fn serialization() {
+        let pretok = ScriptSplit::new(ScriptGranularity::Script, CommonScript::Isolated);
+        let serialized = serde_json::to_string(&pretok).unwrap();
+        assert_eq!(
+            serialized,
+            r#"{"type":"ScriptSplit","granularity":"script","common":"isolated"}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<ScriptSplit>(&serialized).unwrap(),
+            pretok
+        );
+        assert_eq!(
+            ScriptGranularity::WritingSystem.to_string(),
+            "writing_system"
+        );
+    }
+}
@@ -60,7 +60,12 @@ impl<'de> Deserialize<'de> for Split {
 
 impl Clone for Split {
     fn clone(&self) -> Self {
-        Self::new(self.pattern.clone(), self.behavior, self.invert).unwrap()
+        Self {
+            pattern: self.pattern.clone(),
+            regex: self.regex.clone(),
+            behavior: self.behavior,
+            invert: self.invert,
+        }
     }
 }
 
@@ -91,6 +96,17 @@ impl Split {
             invert,
         })
     }
+
+    /// Split on the matches of an already compiled regex, which may be shared with other
+    /// components instead of being compiled again
+    pub fn from_regex(regex: SysRegex, behavior: SplitDelimiterBehavior, invert: bool) -> Self {
+        Self {
+            pattern: SplitPattern::Regex(regex.as_str().to_owned()),
+            regex,
+            behavior,
+            invert,
+        }
+    }
 }
 
 impl PreTokenizer for Split {
@@ -214,6 +230,15 @@ mod tests {
             .unwrap();
 
         assert_eq!(pretok_str_for_regex, pretok_str_for_string);
+
+        let regex = SysRegex::new(r"\s+").unwrap();
+        let pretokenizer_shared = Split::from_regex(regex, SplitDelimiterBehavior::Removed, false);
+        assert_eq!(pretokenizer_shared, pretokenizer_regex);
+        let mut pretok_str_for_shared = PreTokenizedString::from("Hey, man!");
+        pretokenizer_shared
+            .pre_tokenize(&mut pretok_str_for_shared)
+            .unwrap();
+        assert_eq!(pretok_str_for_shared, pretok_str_for_string);
     }
 
     #[test]
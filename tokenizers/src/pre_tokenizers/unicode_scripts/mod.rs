@@ -2,4 +2,6 @@ mod pre_tokenizer;
 mod scripts;
 
 // Re-export the PreTokenizer
+pub(crate) use pre_tokenizer::fixed_script;
 pub use pre_tokenizer::UnicodeScripts;
+pub(crate) use scripts::{get_script, Script, SCRIPT_NAMES};
@@ -22,7 +22,7 @@ impl Default for UnicodeScripts {
 // It could be integrated directly within `get_script` but I
 // think it's kind of tricky to see those modifications later
 // I am guessing release mode will optimize this away anyway.
-fn fixed_script(c: char) -> Script {
+pub(crate) fn fixed_script(c: char) -> Script {
     let raw_script = get_script(c);
     if c as u32 == 0x30FC {
         Script::Han
@@ -144,6 +144,147 @@ pub enum Script {
     Yi,
 }
 
+/// The names of the scripts, as given by their `Debug` implementation, without `Any`
+pub const SCRIPT_NAMES: [&str; 137] = [
+    "Adlam",
+    "Ahom",
+    "AnatolianHieroglyphs",
+    "Arabic",
+    "Armenian",
+    "Avestan",
+    "Balinese",
+    "Bamum",
+    "BassaVah",
+    "Batak",
+    "Bengali",
+    "Bhaiksuki",
+    "Bopomofo",
+    "Brahmi",
+    "Braille",
+    "Buginese",
+    "Buhid",
+    "CanadianAboriginal",
+    "Carian",
+    "CaucasianAlbanian",
+    "Chakma",
+    "Cham",
+    "Cherokee",
+    "Common",
+    "Coptic",
+    "Cuneiform",
+    "Cypriot",
+    "Cyrillic",
+    "Deseret",
+    "Devanagari",
+    "Duployan",
+    "EgyptianHieroglyphs",
+    "Elbasan",
+    "Ethiopic",
+    "Georgian",
+    "Glagolitic",
+    "Gothic",
+    "Grantha",
+    "Greek",
+    "Gujarati",
+    "Gurmukhi",
+    "Han",
+    "Hangul",
+    "Hanunoo",
+    "Hatran",
+    "Hebrew",
+    "Hiragana",
+    "ImperialAramaic",
+    "Inherited",
+    "InscriptionalPahlavi",
+    "InscriptionalParthian",
+    "Javanese",
+    "Kaithi",
+    "Kannada",
+    "Katakana",
+    "KayahLi",
+    "Kharoshthi",
+    "Khmer",
+    "Khojki",
+    "Khudawadi",
+    "Lao",
+    "Latin",
+    "Lepcha",
+    "Limbu",
+    "LinearA",
+    "LinearB",
+    "Lisu",
+    "Lycian",
+    "Lydian",
+    "Mahajani",
+    "Malayalam",
+    "Mandaic",
+    "Manichaean",
+    "Marchen",
+    "MeeteiMayek",
+    "MendeKikakui",
+    "MeroiticCursive",
+    "MeroiticHieroglyphs",
+    "Miao",
+    "Modi",
+    "Mongolian",
+    "Mro",
+    "Multani",
+    "Myanmar",
+    "Nabataean",
+    "NewTaiLue",
+    "Newa",
+    "Nko",
+    "Ogham",
+    "OlChiki",
+    "OldHungarian",
+    "OldItalic",
+    "OldNorthArabian",
+    "OldPermic",
+    "OldPersian",
+    "OldSouthArabian",
+    "OldTurkic",
+    "Oriya",
+    "Osage",
+    "Osmanya",
+    "PahawhHmong",
+    "Palmyrene",
+    "PauCinHau",
+    "PhagsPa",
+    "Phoenician",
+    "PsalterPahlavi",
+    "Rejang",
+    "Runic",
+    "Samaritan",
+    "Saurashtra",
+    "Sharada",
+    "Shavian",
+    "Siddham",
+    "SignWriting",
+    "Sinhala",
+    "SoraSompeng",
+    "Sundanese",
+    "SylotiNagri",
+    "Syriac",
+    "Tagalog",
+    "Tagbanwa",
+    "TaiLe",
+    "TaiTham",
+    "TaiViet",
+    "Takri",
+    "Tamil",
+    "Tangut",
+    "Telugu",
+    "Thaana",
+    "Thai",
+    "Tibetan",
+    "Tifinagh",
+    "Tirhuta",
+    "Ugaritic",
+    "Vai",
+    "WarangCiti",
+    "Yi",
+];
+
 pub fn get_script(c: char) -> Script {
     match c as u32 {
         0x0000..=0x001F => Script::Common,
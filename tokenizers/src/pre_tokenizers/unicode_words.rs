@@ -0,0 +1,272 @@
+use serde::{Deserialize, Serialize};
+use std::ops::Range as StdRange;
+use std::sync::Arc;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::normalizer::Range;
+use crate::pre_tokenizers::unicode_scripts::{fixed_script, Script};
+use crate::tokenizer::{PreTokenizedString, PreTokenizer, Result};
+use crate::utils::macro_rules_attribute;
+
+/// A user-supplied dictionary segmenter, splitting the runs of CJK and Southeast Asian
+/// text into words for the [`UnicodeWords`] pre-tokenizer.
+///
+/// It receives a run, and returns the byte offsets of the boundaries between its words. The
+/// offsets may come in any order, and `0` or the length of the run are ignored, but each of
+/// them must be on a char boundary of the run.
+///
+/// ```
+/// use tokenizers::pre_tokenizers::unicode_words::{UnicodeWords, WordSegmenter};
+///
+/// // A segmenter splitting "東京都" into "東京" and "都"
+/// let segmenter = WordSegmenter::new(|run| {
+///     Ok(run.match_indices("東京").map(|(i, word)| i + word.len()).collect())
+/// });
+/// let pretok = UnicodeWords::new(true).with_segmenter(Some(segmenter));
+/// ```
+#[derive(Clone)]
+pub struct WordSegmenter(Arc<SegmenterFn>);
+
+type SegmenterFn = dyn Fn(&str) -> Result<Vec<usize>> + Send + Sync;
+
+impl WordSegmenter {
+    pub fn new<F>(segmenter: F) -> Self
+    where
+        F: Fn(&str) -> Result<Vec<usize>> + Send + Sync + 'static,
+    {
+        Self(Arc::new(segmenter))
+    }
+
+    /// The byte ranges of the words of `run`
+    fn segment(&self, run: &str) -> Result<Vec<StdRange<usize>>> {
+        let mut boundaries = (self.0)(run)?;
+        if let Some(offset) = boundaries.iter().find(|o| !run.is_char_boundary(**o)) {
+            return Err(format!(
+                "The word segmenter gave the offset {offset}, which is not a char boundary of \"{run}\""
+            )
+            .into());
+        }
+        boundaries.retain(|offset| 0 < *offset && *offset < run.len());
+        boundaries.sort_unstable();
+        boundaries.dedup();
+
+        Ok(std::iter::once(0)
+            .chain(boundaries.iter().copied())
+            .zip(boundaries.iter().copied().chain(std::iter::once(run.len())))
+            .map(|(start, end)| start..end)
+            .collect())
+    }
+}
+
+impl std::fmt::Debug for WordSegmenter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("WordSegmenter")
+    }
+}
+
+impl PartialEq for WordSegmenter {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// Whether `c` belongs to the scripts written without spaces between the words, for which
+/// UAX #29 falls back to one word per char
+fn is_dictionary_char(c: char) -> bool {
+    matches!(
+        fixed_script(c),
+        Script::Han | Script::Thai | Script::Lao | Script::Khmer | Script::Myanmar
+    )
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// Splits on the word boundaries of Unicode (UAX #29), removing the whitespace. Unlike
+/// `Whitespace` and `Punctuation`, the words stay whole across the scripts: the marks stay
+/// on their letters, `"can't"` and `"3.14"` stay one word, and so do the emoji sequences
+/// joined with ZWJ. Each punctuation char gets split on its own.
+///
+/// The scripts written without spaces (Chinese, Japanese, Thai, Lao, Khmer and Myanmar)
+/// have no word boundaries in UAX #29, which splits them char by char. With `cjk`, each run
+/// of these scripts is kept whole instead, or split into words by the `segmenter` when one
+/// is set. The `segmenter` is not serialized, and must be set again after loading.
+#[non_exhaustive]
+#[macro_rules_attribute(impl_serde_type!)]
+pub struct UnicodeWords {
+    #[serde(default)]
+    pub cjk: bool,
+    #[serde(skip)]
+    pub segmenter: Option<WordSegmenter>,
+}
+
+impl UnicodeWords {
+    pub fn new(cjk: bool) -> Self {
+        Self {
+            cjk,
+            segmenter: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_segmenter(mut self, segmenter: Option<WordSegmenter>) -> Self {
+        self.segmenter = segmenter;
+        self
+    }
+
+    /// The byte ranges of the words of `text`
+    fn words(&self, text: &str) -> Result<Vec<StdRange<usize>>> {
+        let mut words = vec![];
+        let mut run: Option<StdRange<usize>> = None;
+        let end_run = |run: &mut Option<StdRange<usize>>, words: &mut Vec<_>| -> Result<()> {
+            if let Some(run) = run.take() {
+                match &self.segmenter {
+                    Some(segmenter) => words.extend(
+                        segmenter
+                            .segment(&text[run.clone()])?
+                            .into_iter()
+                            .map(|word| run.start + word.start..run.start + word.end),
+                    ),
+                    None => words.push(run),
+                }
+            }
+            Ok(())
+        };
+
+        for (start, word) in text.split_word_bound_indices() {
+            let end = start + word.len();
+            if word.chars().all(char::is_whitespace) {
+                end_run(&mut run, &mut words)?;
+            } else if self.cjk && word.chars().all(is_dictionary_char) {
+                run = Some(run.map_or(start, |run| run.start)..end);
+            } else {
+                end_run(&mut run, &mut words)?;
+                words.push(start..end);
+            }
+        }
+        end_run(&mut run, &mut words)?;
+        Ok(words)
+    }
+}
+
+impl Default for UnicodeWords {
+    fn default() -> Self {
+        Self::new(false)
+    }
+}
+
+impl PreTokenizer for UnicodeWords {
+    fn pre_tokenize(&self, pretokenized: &mut PreTokenizedString) -> Result<()> {
+        pretokenized.split(|_, normalized| {
+            self.words(normalized.get())?
+                .into_iter()
+                .map(|range| {
+                    normalized
+                        .slice(Range::Normalized(range))
+                        .ok_or_else(|| "Failed to slice normalized text".into())
+                })
+                .collect::<Result<Vec<_>>>()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{OffsetReferential, OffsetType};
+
+    fn splits(pretok: &impl PreTokenizer, s: &str) -> Vec<(String, (usize, usize))> {
+        let mut pretokenized = PreTokenizedString::from(s);
+        pretok.pre_tokenize(&mut pretokenized).unwrap();
+        pretokenized
+            .get_splits(OffsetReferential::Original, OffsetType::Byte)
+            .into_iter()
+            .map(|(s, o, _)| (s.to_owned(), o))
+            .collect()
+    }
+
+    fn values(pretok: &impl PreTokenizer, s: &str) -> Vec<String> {
+        splits(pretok, s).into_iter().map(|(s, _)| s).collect()
+    }
+
+    #[test]
+    fn words() {
+        let pretok = UnicodeWords::default();
+        assert_eq!(
+            splits(&pretok, "Hey, it's 3.14!"),
+            vec![
+                ("Hey".into(), (0, 3)),
+                (",".into(), (3, 4)),
+                ("it's".into(), (5, 9)),
+                ("3.14".into(), (10, 14)),
+                ("!".into(), (14, 15)),
+            ]
+        );
+        assert_eq!(
+            splits(&pretok, "  café\tnaïve\n"),
+            vec![("café".into(), (2, 7)), ("naïve".into(), (8, 14))]
+        );
+        assert_eq!(splits(&pretok, ""), vec![]);
+    }
+
+    #[test]
+    fn emoji_sequences() {
+        let pretok = UnicodeWords::default();
+        assert_eq!(values(&pretok, "hi 👨‍👩‍👧👍🏽"), vec!["hi", "👨‍👩‍👧", "👍🏽"]);
+    }
+
+    #[test]
+    fn cjk() {
+        let pretok = UnicodeWords::default();
+        assert_eq!(values(&pretok, "東京都へ"), vec!["東", "京", "都", "へ"]);
+
+        let pretok = UnicodeWords::new(true);
+        assert_eq!(
+            splits(&pretok, "東京都へ, Tokyo ภาษาไทย"),
+            vec![
+                ("東京都へ".into(), (0, 12)),
+                (",".into(), (12, 13)),
+                ("Tokyo".into(), (14, 19)),
+                ("ภาษาไทย".into(), (20, 41)),
+            ]
+        );
+    }
+
+    #[test]
+    fn segmenter() {
+        let segmenter = WordSegmenter::new(|run| {
+            Ok(["東京", "ภาษา"]
+                .iter()
+                .flat_map(|word| run.match_indices(word).map(|(i, w)| i + w.len()))
+                .collect())
+        });
+        let pretok = UnicodeWords::new(true).with_segmenter(Some(segmenter));
+        assert_eq!(
+            splits(&pretok, "東京都 ภาษาไทย"),
+            vec![
+                ("東京".into(), (0, 6)),
+                ("都".into(), (6, 9)),
+                ("ภาษา".into(), (10, 22)),
+                ("ไทย".into(), (22, 31)),
+            ]
+        );
+
+        let invalid = WordSegmenter::new(|_| Ok(vec![1]));
+        let pretok = UnicodeWords::new(true).with_segmenter(Some(invalid));
+        let mut pretokenized = PreTokenizedString::from("東京");
+        assert!(pretok.pre_tokenize(&mut pretokenized).is_err());
+    }
+
+    #[test]
+    fn serialization() {
+        let pretok = UnicodeWords::new(true);
+        let serialized = serde_json::to_string(&pretok).unwrap();
+        assert_eq!(serialized, r#"{"type":"UnicodeWords","cjk":true}"#);
+        assert_eq!(
+            serde_json::from_str::<UnicodeWords>(&serialized).unwrap(),
+            pretok
+        );
+        assert_eq!(
+            serde_json::from_str::<UnicodeWords>(r#"{"type":"UnicodeWords"}"#).unwrap(),
+            UnicodeWords::default()
+        );
+    }
+}
@@ -0,0 +1,42 @@
+//! The building blocks of custom components, with a stable API.
+//!
+//! Everything re-exported here is what a library needs to write its own normalizers,
+//! pre-tokenizers, models, post-processors or decoders. These items follow semantic
+//! versioning: removing one of them, or changing it in a way that breaks the code using it,
+//! only happens in a major version, while only additions happen in the minor ones.
+//!
+//! The other items of the crate, and the paths used to reach them, like the modules of
+//! [`tokenizer`](crate::tokenizer), may still change between minor versions, so libraries
+//! should import these from here:
+//!
+//! ```
+//! use tokenizers::prelude::*;
+//!
+//! /// Split the digits from the rest of the text, keeping them apart
+//! struct DigitSplit;
+//!
+//! impl PreTokenizer for DigitSplit {
+//!     fn pre_tokenize(&self, pretokenized: &mut PreTokenizedString) -> Result<()> {
+//!         pretokenized.split(|_, normalized| {
+//!             normalized.split(|c: char| c.is_ascii_digit(), SplitDelimiterBehavior::Isolated)
+//!         })
+//!     }
+//! }
+//!
+//! let mut pretokenized = PreTokenizedString::from("Room 42b");
+//! DigitSplit.pre_tokenize(&mut pretokenized).unwrap();
+//! let splits: Vec<_> = pretokenized
+//!     .get_splits(OffsetReferential::Original, OffsetType::Byte)
+//!     .into_iter()
+//!     .map(|(s, offsets, _)| (s, offsets))
+//!     .collect();
+//! assert_eq!(splits, vec![("Room ", (0, 5)), ("4", (5, 6)), ("2", (6, 7)), ("b", (7, 8))]);
+//! ```
+
+pub use crate::tokenizer::normalizer::Range;
+pub use crate::tokenizer::pattern::{Invert, Pattern};
+pub use crate::tokenizer::{
+    Decoder, Encoding, Error, Model, NormalizedString, Normalizer, OffsetReferential, OffsetType,
+    Offsets, PostProcessor, PreTokenizedString, PreTokenizer, Result, SplitDelimiterBehavior,
+    Token, Trainer,
+};
@@ -0,0 +1,476 @@
+//! # Chat Template
+//!
+//! Provides a way to assemble conversations made of any number of role-tagged turns
+//! (`system`, `user`, `assistant`, ...), each one being surrounded by its own special tokens.
+//!
+//! Contrarily to [`TemplateProcessing`], which only knows about single and pair sequences,
+//! a [`ChatTemplate`] is defined by a list of [`ChatRole`]s. When encoding a conversation
+//! with [`TokenizerImpl::encode_chat`], each turn is processed using the template of its role,
+//! and the result also carries a loss mask identifying the tokens a model should be trained on.
+//!
+//! ## Example
+//!
+//! ```
+//! # use tokenizers::processors::chat::{ChatRole, ChatTemplate};
+//! let template = ChatTemplate::new(
+//!     vec!["<s>".into()],
+//!     vec![
+//!         ChatRole::new("system", vec!["<|system|>".into()], vec!["</s>".into()], 0, false),
+//!         ChatRole::new("user", vec!["<|user|>".into()], vec!["</s>".into()], 0, false),
+//!         ChatRole::new("assistant", vec!["<|assistant|>".into()], vec!["</s>".into()], 1, true),
+//!     ],
+//!     vec![("<s>", 1), ("</s>", 2), ("<|system|>", 3), ("<|user|>", 4), ("<|assistant|>", 5)],
+//! )
+//! .unwrap();
+//! ```
+//!
+//! When used as a regular `PostProcessor` (ie. through `encode`), the i-th input sequence
+//! uses the i-th role, wrapping around if there are more sequences than roles.
+//!
+//! [`TemplateProcessing`]: crate::processors::template::TemplateProcessing
+//! [`TokenizerImpl::encode_chat`]: crate::TokenizerImpl::encode_chat
+use crate::processors::template::{SpecialToken, Tokens};
+use crate::tokenizer::{Encoding, Error, PostProcessor, Result};
+use ahash::AHashMap;
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::iter::repeat_n;
+
+/// The template used for one role of a conversation.
+///
+/// Each turn with this role is encoded as `prefix + content + suffix`, where `prefix` and
+/// `suffix` are lists of identifiers of [`SpecialToken`]s registered on the [`ChatTemplate`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatRole {
+    /// The name used to refer to this role (eg. `"user"`)
+    pub name: String,
+    /// The special tokens added before the content of the turn
+    pub prefix: Vec<String>,
+    /// The special tokens added after the content of the turn
+    pub suffix: Vec<String>,
+    /// The type id used for every token of the turn
+    pub type_id: u32,
+    /// Whether the content (and suffix) of the turns with this role should be part of the loss
+    pub loss: bool,
+}
+
+impl ChatRole {
+    pub fn new<S: Into<String>>(
+        name: S,
+        prefix: Vec<String>,
+        suffix: Vec<String>,
+        type_id: u32,
+        loss: bool,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            prefix,
+            suffix,
+            type_id,
+            loss,
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ChatTemplateError {
+    #[error("ChatTemplate needs at least one role")]
+    NoRoles,
+    #[error("ChatTemplate: role `{0}` is defined more than once")]
+    DuplicateRole(String),
+    #[error("ChatTemplate: unknown role `{0}`")]
+    UnknownRole(String),
+    #[error("ChatTemplate: missing SpecialToken with id `{0}`")]
+    MissingSpecialToken(String),
+}
+
+/// The result of encoding a conversation with a [`ChatTemplate`]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ChatEncoding {
+    /// The merged encoding of the whole conversation. Each turn is its own sequence, so
+    /// `get_sequence_ids` gives the index of the turn containing each content token.
+    pub encoding: Encoding,
+    /// `1` for each token that should contribute to the loss, `0` otherwise
+    pub loss_mask: Vec<u32>,
+    /// For each token, the index in the template's roles of the role owning it, or `None`
+    /// for the tokens of the template's prefix
+    pub role_ids: Vec<Option<u32>>,
+}
+
+/// This PostProcessor assembles any number of role-tagged encodings, adding the
+/// special tokens configured for each role. See the [module level documentation](self).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", try_from = "ChatTemplateDeserializer")]
+pub struct ChatTemplate {
+    /// The special tokens added once, at the very beginning of the conversation
+    prefix: Vec<String>,
+    roles: Vec<ChatRole>,
+    special_tokens: Tokens,
+}
+
+/// We use this custom deserializer to validate the templates when loading them, like when
+/// built with [`ChatTemplate::new`]
+#[doc(hidden)]
+#[derive(Deserialize)]
+#[serde(tag = "type")]
+struct ChatTemplateDeserializer {
+    prefix: Vec<String>,
+    roles: Vec<ChatRole>,
+    special_tokens: Tokens,
+}
+impl TryFrom<ChatTemplateDeserializer> for ChatTemplate {
+    type Error = Error;
+
+    fn try_from(t: ChatTemplateDeserializer) -> Result<Self> {
+        Self::new(t.prefix, t.roles, t.special_tokens)
+    }
+}
+
+impl ChatTemplate {
+    pub fn new<T: Into<Tokens>>(
+        prefix: Vec<String>,
+        roles: Vec<ChatRole>,
+        special_tokens: T,
+    ) -> Result<Self> {
+        let template = Self {
+            prefix,
+            roles,
+            special_tokens: special_tokens.into(),
+        };
+        template.validate()?;
+        Ok(template)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.roles.is_empty() {
+            return Err(Box::new(ChatTemplateError::NoRoles));
+        }
+        for (i, role) in self.roles.iter().enumerate() {
+            if self.roles[..i].iter().any(|r| r.name == role.name) {
+                return Err(Box::new(ChatTemplateError::DuplicateRole(
+                    role.name.clone(),
+                )));
+            }
+        }
+        self.prefix
+            .iter()
+            .chain(
+                self.roles
+                    .iter()
+                    .flat_map(|r| r.prefix.iter().chain(&r.suffix)),
+            )
+            .try_for_each(|id| self.special_token(id).map(|_| ()))
+    }
+
+    pub fn get_prefix(&self) -> &[String] {
+        &self.prefix
+    }
+
+    pub fn get_roles(&self) -> &[ChatRole] {
+        &self.roles
+    }
+
+    pub fn get_special_tokens(&self) -> &Tokens {
+        &self.special_tokens
+    }
+
+    /// Find the index of the role with the given name
+    pub fn role_index(&self, name: &str) -> Result<usize> {
+        self.roles
+            .iter()
+            .position(|r| r.name == name)
+            .ok_or_else(|| Box::new(ChatTemplateError::UnknownRole(name.to_owned())).into())
+    }
+
+    fn special_token(&self, id: &str) -> Result<&SpecialToken> {
+        self.special_tokens
+            .0
+            .get(id)
+            .ok_or_else(|| Box::new(ChatTemplateError::MissingSpecialToken(id.to_owned())).into())
+    }
+
+    fn count_added(&self, ids: &[String]) -> usize {
+        ids.iter()
+            .map(|id| self.special_tokens.0.get(id).map_or(0, |s| s.ids.len()))
+            .sum()
+    }
+
+    fn special_encoding(&self, ids: &[String], type_id: u32) -> Result<Encoding> {
+        ids.iter()
+            .map(|id| {
+                let tok = self.special_token(id)?;
+                let len = tok.ids.len();
+                Ok(Encoding::new(
+                    tok.ids.clone(),
+                    repeat_n(type_id, len).collect(),
+                    tok.tokens.clone(),
+                    repeat_n(None, len).collect(),
+                    repeat_n((0, 0), len).collect(),
+                    repeat_n(1, len).collect(),
+                    repeat_n(1, len).collect(),
+                    vec![],
+                    AHashMap::new(),
+                ))
+            })
+            .collect()
+    }
+
+    /// Assemble the given turns, each one being identified by the index of its role.
+    pub fn process_turns(
+        &self,
+        turns: Vec<(usize, Encoding)>,
+        add_special_tokens: bool,
+    ) -> Result<ChatEncoding> {
+        let mut parts = Vec::with_capacity(turns.len() * 3 + 1);
+        let mut loss_mask = vec![];
+        let mut role_ids = vec![];
+
+        if add_special_tokens {
+            let prefix = self.special_encoding(&self.prefix, 0)?;
+            loss_mask.extend(repeat_n(0, prefix.len()));
+            role_ids.extend(repeat_n(None, prefix.len()));
+            parts.push(prefix);
+        }
+
+        for (i, (role_idx, mut encoding)) in turns.into_iter().enumerate() {
+            let role = self
+                .roles
+                .get(role_idx)
+                .ok_or_else(|| ChatTemplateError::UnknownRole(role_idx.to_string()))?;
+            let loss = u32::from(role.loss);
+            let role_id = Some(role_idx as u32);
+
+            if add_special_tokens {
+                let prefix = self.special_encoding(&role.prefix, role.type_id)?;
+                loss_mask.extend(repeat_n(0, prefix.len()));
+                role_ids.extend(repeat_n(role_id, prefix.len()));
+                parts.push(prefix);
+            }
+
+            encoding.set_type_ids(vec![role.type_id; encoding.len()]);
+            encoding.set_sequence_id(i);
+            loss_mask.extend(repeat_n(loss, encoding.len()));
+            role_ids.extend(repeat_n(role_id, encoding.len()));
+            parts.push(encoding);
+
+            if add_special_tokens {
+                let suffix = self.special_encoding(&role.suffix, role.type_id)?;
+                loss_mask.extend(repeat_n(loss, suffix.len()));
+                role_ids.extend(repeat_n(role_id, suffix.len()));
+                parts.push(suffix);
+            }
+        }
+
+        Ok(ChatEncoding {
+            encoding: Encoding::merge(parts, false),
+            loss_mask,
+            role_ids,
+        })
+    }
+}
+
+impl PostProcessor for ChatTemplate {
+    fn added_tokens(&self, is_pair: bool) -> usize {
+        let n_turns = if is_pair { 2 } else { 1 };
+        self.count_added(&self.prefix)
+            + self
+                .roles
+                .iter()
+                .cycle()
+                .take(n_turns)
+                .map(|r| self.count_added(&r.prefix) + self.count_added(&r.suffix))
+                .sum::<usize>()
+    }
+
+    fn process_encodings(
+        &self,
+        encodings: Vec<Encoding>,
+        add_special_tokens: bool,
+    ) -> Result<Vec<Encoding>> {
+        let n_roles = self.roles.len().max(1);
+        let turns = encodings
+            .into_iter()
+            .enumerate()
+            .map(|(i, encoding)| (i % n_roles, encoding))
+            .collect();
+        Ok(vec![
+            self.process_turns(turns, add_special_tokens)?.encoding,
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::processors::PostProcessorWrapper;
+    use crate::tokenizer::Token;
+
+    fn get_template() -> ChatTemplate {
+        ChatTemplate::new(
+            vec!["<s>".into()],
+            vec![
+                ChatRole::new("user", vec!["<u>".into()], vec!["</s>".into()], 0, false),
+                ChatRole::new(
+                    "assistant",
+                    vec!["<a>".into()],
+                    vec!["</s>".into()],
+                    1,
+                    true,
+                ),
+            ],
+            vec![("<s>", 1), ("</s>", 2), ("<u>", 3), ("<a>", 4)],
+        )
+        .unwrap()
+    }
+
+    fn get_encoding(tokens: &[(&str, u32, (usize, usize))]) -> Encoding {
+        Encoding::from_tokens(
+            tokens
+                .iter()
+                .map(|(value, id, offsets)| Token::new(*id, (*value).into(), *offsets))
+                .collect(),
+            0,
+        )
+    }
+
+    #[test]
+    fn serde() {
+        let template = get_template();
+        let serialized = serde_json::to_string(&template).unwrap();
+        assert!(serialized.starts_with(r#"{"type":"ChatTemplate","prefix":["<s>"],"roles":["#));
+        assert_eq!(
+            serde_json::from_str::<ChatTemplate>(&serialized).unwrap(),
+            template
+        );
+
+        // The templates get validated when loaded
+        let mut invalid: serde_json::Value = serde_json::from_str(&serialized).unwrap();
+        invalid["roles"][0]["prefix"] = serde_json::json!(["<x>"]);
+        let invalid = invalid.to_string();
+        let err = serde_json::from_str::<ChatTemplate>(&invalid).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "ChatTemplate: missing SpecialToken with id `<x>`"
+        );
+        assert!(serde_json::from_str::<PostProcessorWrapper>(&invalid).is_err());
+    }
+
+    #[test]
+    fn validation() {
+        let err = ChatTemplate::new(vec![], vec![], vec![("<s>", 1)]).unwrap_err();
+        assert_eq!(err.to_string(), "ChatTemplate needs at least one role");
+
+        let err = ChatTemplate::new(
+            vec![],
+            vec![ChatRole::new("user", vec!["<u>".into()], vec![], 0, false)],
+            vec![("<s>", 1)],
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "ChatTemplate: missing SpecialToken with id `<u>`"
+        );
+
+        let err = ChatTemplate::new(
+            vec![],
+            vec![
+                ChatRole::new("user", vec![], vec![], 0, false),
+                ChatRole::new("user", vec![], vec![], 0, false),
+            ],
+            Tokens::default(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "ChatTemplate: role `user` is defined more than once"
+        );
+    }
+
+    #[test]
+    fn process_turns() {
+        let template = get_template();
+        let user = get_encoding(&[("Hi", 10, (0, 2))]);
+        let assistant = get_encoding(&[("Hello", 11, (0, 5)), ("!", 12, (5, 6))]);
+        let user2 = get_encoding(&[("Bye", 13, (0, 3))]);
+
+        let chat = template
+            .process_turns(vec![(0, user), (1, assistant), (0, user2)], true)
+            .unwrap();
+        let encoding = &chat.encoding;
+        assert_eq!(encoding.get_ids(), &[1, 3, 10, 2, 4, 11, 12, 2, 3, 13, 2]);
+        assert_eq!(encoding.get_type_ids(), &[0, 0, 0, 0, 1, 1, 1, 1, 0, 0, 0]);
+        assert_eq!(
+            encoding.get_special_tokens_mask(),
+            &[1, 1, 0, 1, 1, 0, 0, 1, 1, 0, 1]
+        );
+        assert_eq!(
+            encoding.get_offsets(),
+            &[
+                (0, 0),
+                (0, 0),
+                (0, 2),
+                (0, 0),
+                (0, 0),
+                (0, 5),
+                (5, 6),
+                (0, 0),
+                (0, 0),
+                (0, 3),
+                (0, 0)
+            ]
+        );
+        assert_eq!(
+            encoding.get_sequence_ids(),
+            vec![
+                None,
+                None,
+                Some(0),
+                None,
+                None,
+                Some(1),
+                Some(1),
+                None,
+                None,
+                Some(2),
+                None
+            ]
+        );
+        assert_eq!(chat.loss_mask, vec![0, 0, 0, 0, 0, 1, 1, 1, 0, 0, 0]);
+        assert_eq!(
+            chat.role_ids,
+            vec![
+                None,
+                Some(0),
+                Some(0),
+                Some(0),
+                Some(1),
+                Some(1),
+                Some(1),
+                Some(1),
+                Some(0),
+                Some(0),
+                Some(0)
+            ]
+        );
+
+        let chat = template
+            .process_turns(vec![(1, get_encoding(&[("Ok", 14, (0, 2))]))], false)
+            .unwrap();
+        assert_eq!(chat.encoding.get_ids(), &[14]);
+        assert_eq!(chat.loss_mask, vec![1]);
+        assert_eq!(chat.role_ids, vec![Some(1)]);
+    }
+
+    #[test]
+    fn as_post_processor() {
+        let template = get_template();
+        assert_eq!(template.added_tokens(false), 3);
+        assert_eq!(template.added_tokens(true), 5);
+
+        let single = get_encoding(&[("Hi", 10, (0, 2))]);
+        let pair = get_encoding(&[("Hello", 11, (0, 5))]);
+        let encoding = template.process(single, Some(pair), true).unwrap();
+        assert_eq!(encoding.get_ids(), &[1, 3, 10, 2, 4, 11, 2]);
+        assert_eq!(encoding.get_type_ids(), &[0, 0, 0, 0, 1, 1, 1]);
+    }
+}
@@ -1,4 +1,5 @@
 pub mod bert;
+pub mod chat;
 pub mod roberta;
 pub mod sequence;
 pub mod template;
@@ -10,6 +11,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::pre_tokenizers::byte_level::ByteLevel;
 use crate::processors::bert::BertProcessing;
+use crate::processors::chat::ChatTemplate;
 use crate::processors::roberta::RobertaProcessing;
 use crate::processors::sequence::Sequence;
 use crate::processors::template::TemplateProcessing;
@@ -24,6 +26,7 @@ pub enum PostProcessorWrapper {
     ByteLevel(ByteLevel),
     Template(TemplateProcessing),
     Sequence(Sequence),
+    ChatTemplate(ChatTemplate),
 }
 
 impl PostProcessor for PostProcessorWrapper {
@@ -34,6 +37,7 @@ impl PostProcessor for PostProcessorWrapper {
             Self::Roberta(roberta) => roberta.added_tokens(is_pair),
             Self::Template(template) => template.added_tokens(is_pair),
             Self::Sequence(bl) => bl.added_tokens(is_pair),
+            Self::ChatTemplate(chat) => chat.added_tokens(is_pair),
         }
     }
 
@@ -48,6 +52,7 @@ impl PostProcessor for PostProcessorWrapper {
             Self::Roberta(roberta) => roberta.process_encodings(encodings, add_special_tokens),
             Self::Template(template) => template.process_encodings(encodings, add_special_tokens),
             Self::Sequence(bl) => bl.process_encodings(encodings, add_special_tokens),
+            Self::ChatTemplate(chat) => chat.process_encodings(encodings, add_special_tokens),
         }
     }
 }
@@ -57,6 +62,7 @@ impl_enum_from!(ByteLevel, PostProcessorWrapper, ByteLevel);
 impl_enum_from!(RobertaProcessing, PostProcessorWrapper, Roberta);
 impl_enum_from!(TemplateProcessing, PostProcessorWrapper, Template);
 impl_enum_from!(Sequence, PostProcessorWrapper, Sequence);
+impl_enum_from!(ChatTemplate, PostProcessorWrapper, ChatTemplate);
 
 #[cfg(test)]
 mod tests {
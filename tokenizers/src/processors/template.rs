@@ -194,9 +194,9 @@ pub struct SpecialToken {
     /// A unique id used to identify this SpecialToken in the template
     id: String,
     /// The list of associated ids
-    ids: Vec<u32>,
+    pub(crate) ids: Vec<u32>,
     /// The list of associated tokens
-    tokens: Vec<String>,
+    pub(crate) tokens: Vec<String>,
 }
 
 impl From<(String, u32)> for SpecialToken {
@@ -1,17 +1,21 @@
 use super::{
-    normalizer::Range, Model, NormalizedString, Normalizer, Offsets, PreTokenizedString, Token,
+    normalizer::Range, Model, NormalizedString, Normalizer, OffsetReferential, OffsetType, Offsets,
+    PreTokenizedString, Token, TraceStep,
 };
 use ahash::{AHashMap, AHashSet};
 use aho_corasick::{AhoCorasick, AhoCorasickBuilder, MatchKind};
-use regex::Regex;
+use regex::{Regex, RegexSet};
 use serde::{ser::SerializeSeq, Deserialize, Serialize, Serializer};
+use std::cmp::Reverse;
 use std::sync::LazyLock;
+use unicode_segmentation::UnicodeSegmentation;
 
 /// Represent a token added by the user on top of the existing Model vocabulary.
 /// AddedToken can be configured to specify the behavior they should have in various situations
 /// like:
 ///   - Whether they should only match single words
 ///   - Whether to include any whitespace on its left or right
+///   - Whether they represent a whole family of tokens, matched with a regex pattern
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct AddedToken {
     /// The content of the added token
@@ -26,6 +30,17 @@ pub struct AddedToken {
     pub normalized: bool,
     /// Whether this token is special
     pub special: bool,
+    /// Whether the content is a regex pattern, matching a family of tokens
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub pattern: bool,
+    /// Whether `single_word` relies on the Unicode word segmentation to find the word
+    /// boundaries, instead of looking for word characters around the match
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub unicode_boundaries: bool,
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
 }
 
 impl AddedToken {
@@ -73,6 +88,22 @@ impl AddedToken {
         self.special = special;
         self
     }
+    /// Specify whether the content is a regex pattern. Any text matching the pattern is
+    /// extracted as a single token, with the id of this text if it is part of the vocabulary,
+    /// or the id of the pattern token otherwise. Normalized patterns match the normalized
+    /// text as they are, without being normalized themselves.
+    #[must_use]
+    pub fn pattern(mut self, pattern: bool) -> Self {
+        self.pattern = pattern;
+        self
+    }
+    /// Specify whether `single_word` uses the Unicode word segmentation (UAX #29) to find
+    /// the word boundaries. This handles the scripts that do not separate words with spaces.
+    #[must_use]
+    pub fn unicode_boundaries(mut self, unicode_boundaries: bool) -> Self {
+        self.unicode_boundaries = unicode_boundaries;
+        self
+    }
 }
 impl Default for AddedToken {
     fn default() -> Self {
@@ -83,6 +114,8 @@ impl Default for AddedToken {
             rstrip: false,
             normalized: true,
             special: false,
+            pattern: false,
+            unicode_boundaries: false,
         }
     }
 }
@@ -93,13 +126,44 @@ impl std::hash::Hash for AddedToken {
     }
 }
 
-type MatchingSet = (AhoCorasick, Vec<u32>);
+/// A pattern added token, along with the tokens of the model vocabulary it matches
+#[derive(Clone, Debug)]
+struct PatternMatcher {
+    regex: Regex,
+    id: u32,
+    vocab: AHashMap<String, u32>,
+}
+
+type MatchingSet = (AhoCorasick, Vec<u32>, Vec<PatternMatcher>);
+
+/// The pattern of a pattern token, anchored to match whole tokens
+fn anchored(content: &str) -> String {
+    format!("^(?:{content})$")
+}
 
 static STARTS_WITH_WORD: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\w").unwrap());
 static ENDS_WITH_WORD: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\w$").unwrap());
 static RIGHTMOST_SPACE_AT_START: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\s*").unwrap());
 static LEFTMOST_SPACE_AT_END: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\s*$").unwrap());
 
+/// Whether the match `start..stop` falls on word boundaries, as defined by the Unicode word
+/// segmentation. The boundaries get computed once for each sentence.
+fn on_unicode_boundaries(
+    boundaries: &mut Option<AHashSet<usize>>,
+    sentence: &str,
+    start: usize,
+    stop: usize,
+) -> bool {
+    let boundaries = boundaries.get_or_insert_with(|| {
+        sentence
+            .split_word_bound_indices()
+            .map(|(i, _)| i)
+            .chain(std::iter::once(sentence.len()))
+            .collect()
+    });
+    boundaries.contains(&start) && boundaries.contains(&stop)
+}
+
 fn ends_with_word(sentence: &str) -> bool {
     ENDS_WITH_WORD.is_match(sentence)
 }
@@ -122,6 +186,30 @@ fn space_rightmost_at_start(sentence: &str) -> usize {
         0
     }
 }
+/// How the added tokens that are not part of the vocabulary yet get their ids
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IdAssignment {
+    /// The ids following the vocabulary, in the order the tokens are given
+    #[default]
+    Insertion,
+    /// The ids following the vocabulary, in the alphabetical order of the tokens added
+    /// together, so that the order they are given in does not matter
+    Alphabetical,
+    /// An id derived from a stable hash of the token, among the `range` ids following the
+    /// model's vocabulary. The ids then only depend on the tokens, not on the order or the
+    /// batches they get added in, unless two of them collide: the last one added gets the
+    /// next free id. The `range` should be much larger than the number of added tokens.
+    HashStable { range: u32 },
+}
+
+/// The 64 bits FNV-1a hash, which is stable across platforms and versions, unlike the
+/// hashers of the standard library
+fn stable_hash(content: &str) -> u64 {
+    content.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
 ///
 /// A vocabulary built on top of the Model
 ///
@@ -154,6 +242,8 @@ pub struct AddedVocabulary {
     /// A Set, containing all the special token for easy access while decoding. This let's
     /// us remove them easily with an O(1) complexity.
     special_tokens_set: AHashSet<String>,
+    /// The special patterns, anchored to match whole tokens while decoding
+    special_patterns: RegexSet,
 
     /// A RegexSet containing all the non-normalized patterns used to split on AddedTokens
     split_trie: MatchingSet,
@@ -180,8 +270,9 @@ impl AddedVocabulary {
             added_tokens: vec![],
             special_tokens: vec![],
             special_tokens_set: AHashSet::new(),
-            split_trie: (trie, vec![]),
-            split_normalized_trie: (normalized_trie, vec![]),
+            special_patterns: RegexSet::empty(),
+            split_trie: (trie, vec![], vec![]),
+            split_normalized_trie: (normalized_trie, vec![], vec![]),
             encode_special_tokens: false,
         }
     }
@@ -239,34 +330,76 @@ impl AddedVocabulary {
         self.encode_special_tokens
     }
 
-    /// Check if a token is a special token
+    /// Check if a token is a special token, or matches a special pattern
     pub fn is_special_token(&self, token: &str) -> bool {
-        self.special_tokens_set.contains(token)
+        self.special_tokens_set.contains(token) || self.special_patterns.is_match(token)
     }
 
-    /// Add some special tokens to the vocabulary
+    /// Add some special tokens to the vocabulary. This panics if the tokens can't be added,
+    /// see [`try_add_special_tokens`](Self::try_add_special_tokens)
     pub fn add_special_tokens<N: Normalizer>(
         &mut self,
         tokens: &[AddedToken],
         model: &impl Model,
         normalizer: Option<&N>,
     ) -> usize {
-        self.add_tokens(tokens, model, normalizer)
+        self.try_add_special_tokens(tokens, model, normalizer)
+            .expect("Failed to add the special tokens")
+    }
+
+    /// Add some special tokens to the vocabulary, failing like
+    /// [`add_tokens_with`](Self::add_tokens_with)
+    pub fn try_add_special_tokens<N: Normalizer>(
+        &mut self,
+        tokens: &[AddedToken],
+        model: &impl Model,
+        normalizer: Option<&N>,
+    ) -> crate::Result<usize> {
+        self.try_add_tokens(tokens, model, normalizer)
     }
 
-    /// Add some tokens to the vocabulary
+    /// Add some tokens to the vocabulary. This panics if the tokens can't be added, see
+    /// [`try_add_tokens`](Self::try_add_tokens)
     pub fn add_tokens<N: Normalizer>(
         &mut self,
         tokens: &[AddedToken],
         model: &impl Model,
         normalizer: Option<&N>,
     ) -> usize {
+        self.try_add_tokens(tokens, model, normalizer)
+            .expect("Failed to add the tokens")
+    }
+
+    /// Add some tokens to the vocabulary, failing like [`add_tokens_with`](Self::add_tokens_with)
+    pub fn try_add_tokens<N: Normalizer>(
+        &mut self,
+        tokens: &[AddedToken],
+        model: &impl Model,
+        normalizer: Option<&N>,
+    ) -> crate::Result<usize> {
+        self.add_tokens_with(tokens, model, normalizer, IdAssignment::default())
+    }
+
+    /// Add some tokens to the vocabulary, giving ids to the new ones with `assignment`. This
+    /// fails if the matching structures of the tokens can't be built, like when the
+    /// normalizer fails on one of them, or if there is no id left for a new token. Nothing is
+    /// added then.
+    pub fn add_tokens_with<N: Normalizer>(
+        &mut self,
+        tokens: &[AddedToken],
+        model: &impl Model,
+        normalizer: Option<&N>,
+        assignment: IdAssignment,
+    ) -> crate::Result<usize> {
+        let ids = self.ids_to_add(tokens, model, assignment)?;
+        // What the tokens change, undone if their matching structures can't be built
+        let special_len = self.special_tokens.len();
+        let added_len = self.added_tokens.len();
+        let mut replaced = vec![];
+
         // Handle special tokens (if any)
-        for token in tokens {
-            if token.special
-                && !token.content.is_empty()
-                && !self.special_tokens_set.contains(&token.content)
-            {
+        for (token, id) in tokens.iter().zip(&ids) {
+            if token.special && id.is_some() && !self.special_tokens_set.contains(&token.content) {
                 self.special_tokens.push(token.to_owned());
                 self.special_tokens_set.insert(token.content.clone());
             }
@@ -274,34 +407,19 @@ impl AddedVocabulary {
 
         // Then we delegate to `add_tokens`, that will take care of refreshing added tokens too.
         let mut ignored = 0;
-        for token in tokens {
-            if token.content.is_empty() || self.added_tokens_map_r.values().any(|val| val == token)
-            {
-                ignored += 1;
-                continue;
-            }
-            // If a token is already part of the vocabulary, we mark it as added
-            let new_id = if let Some(new_id) = self.token_to_id(&token.content, model) {
-                new_id
-            } else {
-                self.added_tokens_map.values().cloned().max().map_or(
-                    model.get_vocab_size() as u32,
-                    |max| {
-                        if (max >= model.get_vocab_size() as u32) || model.get_vocab_size() == 0 {
-                            max + 1
-                        } else {
-                            model.get_vocab_size() as u32
-                        }
-                    },
-                )
+        for (token, id) in tokens.iter().zip(ids) {
+            let new_id = match id {
+                Some(id) if !self.added_tokens_map_r.values().any(|val| val == token) => id,
+                _ => {
+                    ignored += 1;
+                    continue;
+                }
             };
             // Make sure we modify the previous entry
-            *self
-                .added_tokens_map
-                .entry(token.content.clone())
-                .or_default() = new_id;
+            let previous_id = self.added_tokens_map.insert(token.content.clone(), new_id);
             // Update the current revert operation
-            *self.added_tokens_map_r.entry(new_id).or_default() = token.clone();
+            let previous_token = self.added_tokens_map_r.insert(new_id, token.clone());
+            replaced.push((token, previous_id, new_id, previous_token));
             // Make sure to remove previous entry (if the token gets a new id)
 
             // Finally add the token to the classic set if special
@@ -310,17 +428,115 @@ impl AddedVocabulary {
             }
         }
 
-        self.refresh_added_tokens(model, normalizer);
+        if let Err(e) = self.refresh_added_tokens(model, normalizer) {
+            for token in self.special_tokens.drain(special_len..) {
+                self.special_tokens_set.remove(&token.content);
+            }
+            self.added_tokens.truncate(added_len);
+            // In reverse, so that a token given twice gets back its value from before both
+            for (token, previous_id, new_id, previous_token) in replaced.into_iter().rev() {
+                match previous_id {
+                    Some(id) => self.added_tokens_map.insert(token.content.clone(), id),
+                    None => self.added_tokens_map.remove(&token.content),
+                };
+                match previous_token {
+                    Some(previous) => self.added_tokens_map_r.insert(new_id, previous),
+                    None => self.added_tokens_map_r.remove(&new_id),
+                };
+            }
+            return Err(e);
+        }
 
         // Return the number of added tokens
-        tokens.len() - ignored
+        Ok(tokens.len() - ignored)
+    }
+
+    /// The ids the given tokens would have once added with `assignment`, without adding them:
+    /// the id of the tokens already in the vocabulary, or their new id. The tokens that would
+    /// be ignored, being empty or invalid patterns, have no id. This fails if a new token would
+    /// get an id past `u32::MAX`.
+    pub fn ids_to_add(
+        &self,
+        tokens: &[AddedToken],
+        model: &impl Model,
+        assignment: IdAssignment,
+    ) -> crate::Result<Vec<Option<u32>>> {
+        let mut order = tokens
+            .iter()
+            .enumerate()
+            .filter(|(_, token)| {
+                // Patterns that do not compile, as is or anchored, are ignored
+                let compiled = |content: &str| {
+                    Regex::new(content).and_then(|_| Regex::new(&anchored(content)))
+                };
+                match token.pattern.then(|| compiled(&token.content)) {
+                    Some(Err(e)) => {
+                        log::warn!("Ignoring the added token `{}`: {e}", token.content);
+                        false
+                    }
+                    _ => !token.content.is_empty(),
+                }
+            })
+            .collect::<Vec<_>>();
+        if assignment == IdAssignment::Alphabetical {
+            order.sort_by(|(_, a), (_, b)| a.content.cmp(&b.content));
+        }
+
+        let vocab_size = model.get_vocab_size() as u32;
+        let mut assigned: AHashMap<&str, u32> = AHashMap::new();
+        let mut taken: AHashSet<u32> = self.added_tokens_map_r.keys().copied().collect();
+        let mut ids = vec![None; tokens.len()];
+        for (i, token) in order {
+            // If a token is already part of the vocabulary, we mark it as added
+            let known = assigned
+                .get(token.content.as_str())
+                .copied()
+                .or_else(|| self.token_to_id(&token.content, model));
+            let id = match (known, assignment) {
+                (Some(id), _) => Some(id),
+                (None, IdAssignment::Insertion | IdAssignment::Alphabetical) => self
+                    .added_tokens_map
+                    .values()
+                    .chain(assigned.values())
+                    .max()
+                    .map_or(Some(vocab_size), |&max| {
+                        if max >= vocab_size || vocab_size == 0 {
+                            max.checked_add(1)
+                        } else {
+                            Some(vocab_size)
+                        }
+                    }),
+                (None, IdAssignment::HashStable { range }) => {
+                    let slot = stable_hash(&token.content) % u64::from(range.max(1));
+                    let mut id = vocab_size.checked_add(slot as u32);
+                    while let Some(taken_id) = id.filter(|id| taken.contains(id)) {
+                        id = taken_id.checked_add(1);
+                    }
+                    id
+                }
+            };
+            let id = id.ok_or_else(|| {
+                format!(
+                    "Cannot add the token `{}`, there is no id left for it",
+                    token.content
+                )
+            })?;
+            assigned.insert(&token.content, id);
+            taken.insert(id);
+            ids[i] = Some(id);
+        }
+        Ok(ids)
     }
 
     /// Reconstruct our internal RegexSet when new tokens are added to the vocabulary.
     ///
     /// We keep two different RegexSet, one that will take care of matching against the
     /// non-normalized string, and one matching against the normalized one.
-    fn refresh_added_tokens<N: Normalizer>(&mut self, model: &impl Model, normalizer: Option<&N>) {
+    fn refresh_added_tokens<N: Normalizer>(
+        &mut self,
+        model: &impl Model,
+        normalizer: Option<&N>,
+    ) -> crate::Result<()> {
         type TupleTokenId<'a> = (&'a AddedToken, u32);
         let (normalized, non_normalized): (Vec<TupleTokenId>, Vec<TupleTokenId>) = self
             .special_tokens
@@ -335,29 +551,75 @@ impl AddedVocabulary {
             })
             .partition(|(token, _)| token.normalized);
 
-        let (tokens, ids): (Vec<&AddedToken>, Vec<u32>) = non_normalized.into_iter().unzip();
+        // The patterns get matched separately, along with the model tokens they match
+        let has_patterns = self
+            .special_tokens
+            .iter()
+            .chain(self.added_tokens.iter())
+            .any(|token| token.pattern);
+        let model_vocab = if has_patterns {
+            model.get_vocab()
+        } else {
+            Default::default()
+        };
+        let build_patterns = |tokens: &[TupleTokenId]| {
+            tokens
+                .iter()
+                .filter(|(token, _)| token.pattern)
+                .map(|(token, id)| {
+                    let full_match = Regex::new(&anchored(&token.content))?;
+                    Ok(PatternMatcher {
+                        regex: Regex::new(&token.content)?,
+                        id: *id,
+                        vocab: model_vocab
+                            .iter()
+                            .filter(|(content, _)| full_match.is_match(content))
+                            .map(|(content, id)| (content.clone(), *id))
+                            .collect(),
+                    })
+                })
+                .collect::<crate::Result<Vec<_>>>()
+        };
+        let patterns = build_patterns(&non_normalized)?;
+        let npatterns = build_patterns(&normalized)?;
+        let special_patterns = RegexSet::new(
+            self.special_tokens
+                .iter()
+                .filter(|token| token.pattern)
+                .map(|token| anchored(&token.content)),
+        )?;
+
+        let (tokens, ids): (Vec<&AddedToken>, Vec<u32>) = non_normalized
+            .into_iter()
+            .filter(|(token, _)| !token.pattern)
+            .unzip();
         let trie = AhoCorasickBuilder::new()
             .match_kind(MatchKind::LeftmostLongest)
-            .build(tokens.iter().map(|token| &token.content))
-            .expect("Failed to build tried when refreshing tokens");
-        self.split_trie = (trie, ids);
+            .build(tokens.iter().map(|token| &token.content))?;
 
-        let (ntokens, nids): (Vec<&AddedToken>, Vec<u32>) = normalized.into_iter().unzip();
-        let patterns: Vec<_> = ntokens
+        let (ntokens, nids): (Vec<&AddedToken>, Vec<u32>) = normalized
+            .into_iter()
+            .filter(|(token, _)| !token.pattern)
+            .unzip();
+        let contents = ntokens
             .iter()
             .map(|token| {
                 let mut content = NormalizedString::from(token.content.as_ref());
                 if let Some(n) = normalizer {
-                    n.normalize(&mut content).unwrap();
+                    n.normalize(&mut content)?;
                 }
-                content
+                Ok(content)
             })
-            .collect();
+            .collect::<crate::Result<Vec<_>>>()?;
         let normalized_trie = AhoCorasickBuilder::new()
             .match_kind(MatchKind::LeftmostLongest)
-            .build(patterns.iter().map(|content| content.get()))
-            .expect("Failed to build tried when refreshing tokens (normalized)");
-        self.split_normalized_trie = (normalized_trie, nids);
+            .build(contents.iter().map(|content| content.get()))?;
+
+        // Only replaced once everything is built, so that the matchers stay whole on errors
+        self.special_patterns = special_patterns;
+        self.split_trie = (trie, ids, patterns);
+        self.split_normalized_trie = (normalized_trie, nids, npatterns);
+        Ok(())
     }
 
     /// Find any AddedToken in the given sentence, using the provided MatchingSet.
@@ -369,26 +631,63 @@ impl AddedVocabulary {
             return vec![(None, (0, 0))];
         }
 
+        let mut matches = split_re
+            .0
+            .find_iter(sentence)
+            .map(|mat| {
+                let id = split_re.1[mat.pattern()];
+                (mat.start(), mat.end(), id, &self.added_tokens_map_r[&id])
+            })
+            .collect::<Vec<_>>();
+        if !split_re.2.is_empty() {
+            for pattern in &split_re.2 {
+                let added_token = &self.added_tokens_map_r[&pattern.id];
+                for mat in pattern.regex.find_iter(sentence) {
+                    if mat.is_empty() {
+                        continue;
+                    }
+                    let id = self
+                        .added_tokens_map
+                        .get(mat.as_str())
+                        .or_else(|| pattern.vocab.get(mat.as_str()))
+                        .copied()
+                        .unwrap_or(pattern.id);
+                    matches.push((mat.start(), mat.end(), id, added_token));
+                }
+            }
+            // Keep the leftmost longest matches, like the trie does. The sort is stable, so
+            // the tokens win over the patterns matching the same text
+            matches.sort_by_key(|(start, stop, ..)| (*start, Reverse(*stop)));
+            let mut end = 0;
+            matches.retain(|(start, stop, ..)| {
+                let keep = *start >= end;
+                if keep {
+                    end = *stop;
+                }
+                keep
+            });
+        }
+
         let mut start_offset = 0;
         let mut splits = vec![];
+        let mut boundaries = None;
 
-        for mat in split_re.0.find_iter(sentence) {
-            let mut start = mat.start();
-            let mut stop = mat.end();
-            let aho_id = mat.pattern();
-            let id = split_re.1[aho_id];
-            let added_token = &self.added_tokens_map_r.get(&id).unwrap();
-
+        for (mut start, mut stop, id, added_token) in matches {
             if self.encode_special_tokens && self.special_tokens_set.contains(&added_token.content)
             {
                 continue;
             }
 
             if added_token.single_word {
-                let start_space = start == 0 || !ends_with_word(&sentence[..start]);
-                let stop_space = stop == sentence.len() || !starts_with_word(&sentence[stop..]);
+                let single_word = if added_token.unicode_boundaries {
+                    on_unicode_boundaries(&mut boundaries, sentence, start, stop)
+                } else {
+                    let start_space = start == 0 || !ends_with_word(&sentence[..start]);
+                    let stop_space = stop == sentence.len() || !starts_with_word(&sentence[stop..]);
+                    start_space && stop_space
+                };
 
-                if !stop_space || !start_space {
+                if !single_word {
                     // Discard not single word
                     continue;
                 }
@@ -494,6 +793,106 @@ impl AddedVocabulary {
 
         pretokenized
     }
+
+    /// Whether an added token could be extracted across the byte `cut` of the sequence, or
+    /// right against it: the parts of the sequence on each side of the cut then can't be
+    /// encoded separately. The extraction is tried from each position before the cut where
+    /// such a token could start, over the text it could reach, which is independent from the
+    /// rest of the sequence. The patterns are not bounded, any of them could cross the cut.
+    pub(crate) fn may_extract_across<N: Normalizer>(
+        &self,
+        normalizer: Option<&N>,
+        sequence: &str,
+        cut: usize,
+    ) -> bool {
+        let tokens = || self.special_tokens.iter().chain(self.added_tokens.iter());
+        if tokens().any(|token| token.pattern) {
+            return true;
+        }
+        let Some(longest) = tokens().map(|token| token.content.chars().count()).max() else {
+            return false;
+        };
+        // The length of a part of the sequence in chars, before or after the normalization,
+        // the tokens matching one or the other
+        let len = |part: &str| {
+            let chars = part.chars().count();
+            let Some(normalizer) = normalizer else {
+                return chars;
+            };
+            let mut normalized = NormalizedString::from(part);
+            match normalizer.normalize(&mut normalized) {
+                Ok(()) => chars.min(normalized.get().chars().count()),
+                Err(_) => 0,
+            }
+        };
+
+        // The spaces `lstrip` takes, a token and the char after it for `single_word`, then
+        // the spaces `rstrip` takes
+        let after_spaces = sequence.len() - sequence[cut..].trim_start().len();
+        let mut end = after_spaces;
+        let mut ends = sequence[end..]
+            .char_indices()
+            .map(|(i, c)| after_spaces + i + c.len_utf8());
+        while len(&sequence[after_spaces..end]) <= longest {
+            match ends.next() {
+                Some(next) => end = next,
+                None => break,
+            }
+        }
+        end = sequence.len() - sequence[end..].trim_start().len();
+
+        for (start, _) in sequence[..cut].char_indices().rev() {
+            // The token starts after the spaces `lstrip` takes, and can't reach the cut from
+            // further than its length
+            if len(sequence[start..cut].trim()) > longest {
+                break;
+            }
+            let cut = cut - start;
+            let crosses = self
+                .extract_and_normalize(normalizer, &sequence[start..end])
+                .get_splits(OffsetReferential::Original, OffsetType::Byte)
+                .into_iter()
+                .any(|(_, (from, to), tokens)| tokens.is_some() && from <= cut && to >= cut);
+            if crosses {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Same as `extract_and_normalize`, calling `on_stage` with the splits after each of
+    /// its steps. Each step of the normalizer is applied separately for this, and any
+    /// error it returns is propagated.
+    pub(super) fn extract_and_normalize_traced<N: Normalizer>(
+        &self,
+        normalizer: Option<&N>,
+        sequence: &str,
+        on_stage: &mut dyn FnMut(TraceStep, &PreTokenizedString),
+    ) -> crate::Result<PreTokenizedString> {
+        let mut pretokenized: PreTokenizedString = sequence.into();
+
+        pretokenized
+            .split(|_, sequence| Ok(self.split_with_indices(sequence, &self.split_trie)))?;
+        on_stage(TraceStep::AddedTokens, &pretokenized);
+
+        if let Some(normalizer) = normalizer {
+            normalizer.for_each_step(&mut |name, step| {
+                pretokenized.split(|_, mut sequence| {
+                    step.normalize(&mut sequence)?;
+                    Ok(vec![sequence])
+                })?;
+                on_stage(TraceStep::Normalizer(name.to_owned()), &pretokenized);
+                Ok(())
+            })?;
+        }
+
+        pretokenized.split(|_, sequence| {
+            Ok(self.split_with_indices(sequence, &self.split_normalized_trie))
+        })?;
+        on_stage(TraceStep::NormalizedAddedTokens, &pretokenized);
+
+        Ok(pretokenized)
+    }
 }
 
 impl Default for AddedVocabulary {
@@ -550,6 +949,8 @@ mod tests {
     struct ModelMock {
         vocab: AHashMap<String, u32>,
         vocab_r: AHashMap<u32, String>,
+        /// The vocab size to report, instead of the size of `vocab`
+        vocab_size: Option<usize>,
     }
     impl ModelMock {
         pub fn new<I>(iter: I) -> Self
@@ -566,6 +967,7 @@ mod tests {
                     .map(|(tok, id)| (*id, tok.to_owned()))
                     .collect(),
                 vocab,
+                vocab_size: None,
             }
         }
     }
@@ -620,7 +1022,7 @@ mod tests {
             self.vocab.clone().into_iter().collect()
         }
         fn get_vocab_size(&self) -> usize {
-            self.vocab.len()
+            self.vocab_size.unwrap_or(self.vocab.len())
         }
         fn save(&self, _folder: &Path, _name: Option<&str>) -> Result<Vec<PathBuf>> {
             unimplemented!()
@@ -674,6 +1076,156 @@ mod tests {
         assert_eq!(vocab.get_added_tokens_decoder()[&0], added_token);
     }
 
+    #[test]
+    fn id_assignment() {
+        let model = ModelMock::new(&[("test", 0), ("tost", 1)]);
+        let normalizer: Option<&NormalizerWrapper> = None;
+        let tokens = |contents: &[&str]| -> Vec<_> {
+            contents
+                .iter()
+                .map(|c| AddedToken::from(*c, false))
+                .collect()
+        };
+
+        let mut vocab = AddedVocabulary::new();
+        let added = tokens(&["c", "test", "a", "", "c", "b"]);
+        let ids = [Some(2), Some(0), Some(3), None, Some(2), Some(4)];
+        assert_eq!(
+            vocab
+                .ids_to_add(&added, &model, IdAssignment::Insertion)
+                .unwrap(),
+            ids
+        );
+        let ids = [Some(4), Some(0), Some(2), None, Some(4), Some(3)];
+        assert_eq!(
+            vocab
+                .ids_to_add(&added, &model, IdAssignment::Alphabetical)
+                .unwrap(),
+            ids
+        );
+        // A dry run adds nothing
+        assert!(vocab.is_empty());
+        assert_eq!(
+            vocab
+                .add_tokens_with(&added, &model, normalizer, IdAssignment::Alphabetical)
+                .unwrap(),
+            4
+        );
+        assert_eq!(vocab.token_to_id("a", &model), Some(2));
+        assert_eq!(vocab.token_to_id("c", &model), Some(4));
+
+        // The hashed ids do not depend on the batches, nor on the order of the tokens
+        let hash_stable = IdAssignment::HashStable { range: 1 << 20 };
+        let mut one = AddedVocabulary::new();
+        one.add_tokens_with(&tokens(&["x", "y", "z"]), &model, normalizer, hash_stable)
+            .unwrap();
+        let mut two = AddedVocabulary::new();
+        two.add_tokens_with(&tokens(&["z"]), &model, normalizer, hash_stable)
+            .unwrap();
+        two.add_tokens_with(&tokens(&["y", "x"]), &model, normalizer, hash_stable)
+            .unwrap();
+        assert_eq!(one.get_vocab(), two.get_vocab());
+        assert!(one
+            .get_vocab()
+            .values()
+            .all(|id| (2..2 + (1 << 20)).contains(id)));
+
+        // Colliding tokens get the next free id
+        let hash_stable = IdAssignment::HashStable { range: 1 };
+        let mut vocab = AddedVocabulary::new();
+        vocab
+            .add_tokens_with(&tokens(&["x", "y"]), &model, normalizer, hash_stable)
+            .unwrap();
+        assert_eq!(
+            vocab
+                .ids_to_add(&tokens(&["z", "x"]), &model, hash_stable)
+                .unwrap(),
+            [Some(4), Some(2)]
+        );
+
+        // Past the last id, the tokens cannot be added
+        let model = ModelMock {
+            vocab_size: Some(u32::MAX as usize),
+            ..ModelMock::new(&[])
+        };
+        let mut vocab = AddedVocabulary::new();
+        vocab
+            .add_tokens_with(&tokens(&["x"]), &model, normalizer, hash_stable)
+            .unwrap();
+        assert_eq!(vocab.token_to_id("x", &model), Some(u32::MAX));
+        for assignment in [
+            hash_stable,
+            IdAssignment::HashStable { range: u32::MAX },
+            IdAssignment::Insertion,
+        ] {
+            let err = vocab
+                .add_tokens_with(&tokens(&["y"]), &model, normalizer, assignment)
+                .unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "Cannot add the token `y`, there is no id left for it"
+            );
+        }
+        assert_eq!(vocab.len(), 1);
+    }
+
+    /// Fails on the content `bad`
+    struct FailingNormalizer;
+    impl Normalizer for FailingNormalizer {
+        fn normalize(&self, normalized: &mut NormalizedString) -> Result<()> {
+            if normalized.get() == "bad" {
+                return Err("Cannot normalize `bad`".into());
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn failed_add_changes_nothing() {
+        let model = ModelMock::new(&[("test", 0)]);
+        let normalizer = Some(&FailingNormalizer);
+        let mut vocab = AddedVocabulary::new();
+        vocab.add_tokens(
+            &[AddedToken::from("[A]", true), AddedToken::from("b", false)],
+            &model,
+            normalizer,
+        );
+        let before = vocab.get_vocab().clone();
+
+        let err = vocab
+            .try_add_tokens(
+                &[
+                    AddedToken::from("[C]", true),
+                    AddedToken::from("b", true),
+                    AddedToken::from("bad", false),
+                ],
+                &model,
+                normalizer,
+            )
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Cannot normalize `bad`");
+        assert_eq!(vocab.get_vocab(), &before);
+        assert_eq!(vocab.added_tokens.len(), 1);
+        assert_eq!(vocab.special_tokens.len(), 1);
+        assert!(!vocab.is_special_token("[C]"));
+        assert!(!vocab.is_special_token("b"));
+
+        // The previous tokens still match, and the next tokens can be added
+        assert_eq!(
+            vocab.add_tokens(&[AddedToken::from("[C]", true)], &model, normalizer),
+            1
+        );
+        let pretokenized = vocab.extract_and_normalize(normalizer, "[A]b[C]");
+        assert_eq!(
+            simplify_output(&pretokenized),
+            [
+                ("[A]", Some(vec![1])),
+                ("b", Some(vec![2])),
+                ("[C]", Some(vec![3]))
+            ]
+        );
+    }
+
     #[test]
     fn can_add_special_tokens() {
         let model = ModelMock::new(&[("test", 0), ("tost", 1)]);
@@ -1026,4 +1578,182 @@ mod tests {
             vec![("my", Some(vec![0])), ("ä»Ĭ", Some(vec![1])),]
         );
     }
+
+    #[test]
+    fn extract_across() {
+        let model = ModelMock::new(&[]);
+        let mut vocab = AddedVocabulary::new();
+        let normalizer: Option<&NormalizerWrapper> = None;
+        assert!(!vocab.may_extract_across(normalizer, "ab cd", 2));
+
+        vocab.add_tokens(
+            &[
+                AddedToken::from("b c", false),
+                AddedToken::from("[X]", false).lstrip(true).rstrip(true),
+                AddedToken::from("cd", false).single_word(true),
+            ],
+            &model,
+            normalizer,
+        );
+        let across = |sequence: &str, cut| vocab.may_extract_across(normalizer, sequence, cut);
+        assert!(across("ab cd", 2));
+        assert!(!across("ab ad", 2));
+        assert!(!across("a ab ad", 4));
+        // The spaces stripped by the tokens
+        assert!(across("a    [X] b", 2));
+        assert!(across("a [X]    b", 8));
+        assert!(!across("a [X] bc", 7));
+        // Right against the cut
+        assert!(across("a [X]b", 5));
+        // `single_word` looks at the char after the token
+        assert!(across("a cd", 2));
+        assert!(!across("a cde f", 6));
+
+        vocab.add_tokens(
+            &[AddedToken::from("x+", false).pattern(true)],
+            &model,
+            normalizer,
+        );
+        assert!(vocab.may_extract_across(normalizer, "a b", 1));
+    }
+
+    #[test]
+    fn pattern_tokens() {
+        let model = ModelMock::new(&[("<extra_id_0>", 0), ("<extra_id_1>", 1), ("hey", 2)]);
+        let mut vocab = AddedVocabulary::new();
+        let normalizer: Option<&NormalizerWrapper> = None;
+
+        assert_eq!(
+            vocab.add_special_tokens(
+                &[
+                    AddedToken::from(r"<extra_id_\d+>", true).pattern(true),
+                    AddedToken::from("<extra_id_10>", true),
+                ],
+                &model,
+                normalizer
+            ),
+            2
+        );
+        // The pattern token itself gets an id
+        assert_eq!(vocab.get_vocab()[r"<extra_id_\d+>"], 3);
+        assert_eq!(vocab.get_vocab()["<extra_id_10>"], 4);
+
+        let result = vocab.extract_and_normalize(
+            normalizer,
+            "Hey<extra_id_1> <extra_id_10><extra_id_42> <extra_id_x>",
+        );
+        assert_eq!(
+            simplify_output(&result),
+            vec![
+                ("Hey", None),
+                // The model token
+                ("<extra_id_1>", Some(vec![1])),
+                (" ", None),
+                // The added token
+                ("<extra_id_10>", Some(vec![4])),
+                // Unknown, so the pattern token
+                ("<extra_id_42>", Some(vec![3])),
+                (" <extra_id_x>", None),
+            ]
+        );
+
+        assert!(vocab.is_special_token("<extra_id_7>"));
+        assert!(!vocab.is_special_token("<extra_id_7>a"));
+        assert!(!vocab.is_special_token("hey"));
+
+        // Invalid patterns are ignored, even when only their anchored form is invalid, like
+        // with a comment eating the end of the anchor
+        assert_eq!(
+            vocab.add_tokens(
+                &[
+                    AddedToken::from("(", false).pattern(true),
+                    AddedToken::from("(?x)a#", true).pattern(true),
+                ],
+                &model,
+                normalizer
+            ),
+            0
+        );
+        assert!(vocab.is_special_token("<extra_id_7>"));
+    }
+
+    #[test]
+    fn pattern_tokens_leftmost_longest() {
+        let model = ModelMock::new(&[]);
+        let mut vocab = AddedVocabulary::new();
+        let normalizer = Lowercase;
+
+        vocab.add_tokens(
+            &[
+                AddedToken::from("ab", false),
+                AddedToken::from("a+b+", false).pattern(true),
+                AddedToken::from("[0-9]+", false)
+                    .pattern(true)
+                    .single_word(true),
+            ],
+            &model,
+            Some(&normalizer),
+        );
+        // Patterns match the normalized text
+        let result = vocab.extract_and_normalize(Some(&normalizer), "AB aabb Ab1 2");
+        assert_eq!(
+            simplify_output(&result),
+            vec![
+                ("ab", Some(vec![0])),
+                (" ", None),
+                ("aabb", Some(vec![1])),
+                (" ", None),
+                ("ab", Some(vec![0])),
+                ("1 ", None),
+                ("2", Some(vec![2])),
+            ]
+        );
+    }
+
+    #[test]
+    fn unicode_boundaries() {
+        let model = ModelMock::new(&[]);
+        let mut vocab = AddedVocabulary::new();
+        let normalizer: Option<&NormalizerWrapper> = None;
+
+        vocab.add_tokens(
+            &[
+                AddedToken::from("猫", false).single_word(true),
+                AddedToken::from("犬", false)
+                    .single_word(true)
+                    .unicode_boundaries(true),
+                AddedToken::from("cat", false)
+                    .single_word(true)
+                    .unicode_boundaries(true),
+            ],
+            &model,
+            normalizer,
+        );
+        let result = vocab.extract_and_normalize(normalizer, "我的猫和犬 cats cat");
+        assert_eq!(
+            simplify_output(&result),
+            vec![
+                // Without spaces, only the Unicode segmentation finds the words
+                ("我的猫和", None),
+                ("犬", Some(vec![1])),
+                (" cats ", None),
+                ("cat", Some(vec![2])),
+            ]
+        );
+    }
+
+    #[test]
+    fn serialize_new_options() {
+        let token = AddedToken::from("<mask>", true);
+        let json = serde_json::to_string(&token).unwrap();
+        assert_eq!(
+            json,
+            r#"{"content":"<mask>","single_word":false,"lstrip":false,"rstrip":false,"normalized":false,"special":true}"#
+        );
+
+        let token = token.pattern(true).unicode_boundaries(true);
+        let json = serde_json::to_string(&token).unwrap();
+        assert!(json.ends_with(r#""special":true,"pattern":true,"unicode_boundaries":true}"#));
+        assert_eq!(serde_json::from_str::<AddedToken>(&json).unwrap(), token);
+    }
 }
@@ -0,0 +1,387 @@
+use crate::Offsets;
+use std::iter::FromIterator;
+use std::ops::Range;
+
+/// The alignments of a `NormalizedString`: for each byte of the normalized string, the
+/// `(start, end)` range of the original string it comes from.
+///
+/// Most strings are never modified, or only modified in place (lowercasing ASCII for example).
+/// Their alignments map each byte `i` to `(i, i + 1)`, so we can keep them without allocating.
+/// Any other alignments are run-length encoded: consecutive bytes usually map either to the
+/// same original range (a char expanded into many), or to consecutive ranges of the same
+/// width (untouched chars), so we only keep a few words per run instead of 16 bytes per byte.
+#[derive(Clone)]
+pub(crate) struct Alignments(Repr);
+
+#[derive(Clone)]
+enum Repr {
+    /// The `n` bytes are aligned with themselves: byte `i` maps to `(i, i + 1)`
+    Identity(usize),
+    /// Any other alignments, as a list of runs covering `len` bytes
+    Runs { runs: Vec<Run>, len: usize },
+}
+
+/// A run of bytes, split in groups of `repeat` consecutive bytes. The bytes of a group all
+/// map to the same original range of size `width`, right after the range of the previous group.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Run {
+    /// Index of the first byte of the run
+    at: usize,
+    /// Start of the original range of the first group
+    start: usize,
+    /// Width of each original range
+    width: usize,
+    /// Number of bytes per group, `SINGLE_GROUP` when the whole run is a single group
+    repeat: usize,
+}
+
+impl Run {
+    const SINGLE_GROUP: usize = usize::MAX;
+
+    fn new(offsets: Offsets) -> Self {
+        Self {
+            at: 0,
+            start: offsets.0,
+            width: offsets.1 - offsets.0,
+            repeat: Self::SINGLE_GROUP,
+        }
+    }
+
+    /// The run aligning each byte with itself
+    fn identity() -> Self {
+        Self {
+            at: 0,
+            start: 0,
+            width: 1,
+            repeat: 1,
+        }
+    }
+
+    /// The alignment of the `k`-th byte of the run
+    fn get(&self, k: usize) -> Offsets {
+        let start = self.start + (k / self.repeat) * self.width;
+        (start, start + self.width)
+    }
+
+    /// Whether the `k`-th byte of the run starts a group
+    fn starts_group(&self, k: usize) -> bool {
+        self.repeat == Self::SINGLE_GROUP || k.is_multiple_of(self.repeat)
+    }
+
+    /// Whether the `count` first bytes of `other` continue this run, if placed `k` bytes
+    /// after its start
+    fn continues(&self, k: usize, other: &Run, count: usize) -> bool {
+        self.get(k) == other.get(0)
+            && (count == 1
+                || self.width == other.width
+                    && (self.repeat == other.repeat && self.starts_group(k)
+                        || self.repeat == Self::SINGLE_GROUP && other.repeat == Self::SINGLE_GROUP))
+    }
+}
+
+impl Alignments {
+    /// Build the alignments of an untouched string, where each byte maps to the char it belongs to
+    pub fn identity(s: &str) -> Self {
+        if s.is_ascii() {
+            Self(Repr::Identity(s.len()))
+        } else {
+            s.char_indices()
+                .flat_map(|(b, c)| {
+                    let len = c.len_utf8();
+                    (0..len).map(move |_| (b, b + len))
+                })
+                .collect()
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match &self.0 {
+            Repr::Identity(len) => *len,
+            Repr::Runs { len, .. } => *len,
+        }
+    }
+
+    /// Return the alignment of the byte at `idx`
+    pub fn get(&self, idx: usize) -> Option<Offsets> {
+        match &self.0 {
+            Repr::Identity(len) => (idx < *len).then_some((idx, idx + 1)),
+            Repr::Runs { runs, len } => {
+                if idx >= *len {
+                    None
+                } else {
+                    let run = &runs[runs.partition_point(|r| r.at <= idx) - 1];
+                    Some(run.get(idx - run.at))
+                }
+            }
+        }
+    }
+
+    /// Return the alignment of the byte at `idx`, panicking if out of bounds
+    pub fn at(&self, idx: usize) -> Offsets {
+        self.get(idx)
+            .unwrap_or_else(|| panic!("alignment index {idx} out of bounds (len: {})", self.len()))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Offsets> + '_ {
+        self.runs()
+            .flat_map(|(run, len)| (0..len).map(move |k| run.get(k)))
+    }
+
+    #[cfg(test)]
+    pub fn to_vec(&self) -> Vec<Offsets> {
+        self.iter().collect()
+    }
+
+    /// Iterate over the runs, along with their length
+    fn runs(&self) -> impl Iterator<Item = (Run, usize)> + '_ {
+        let (identity, runs) = match &self.0 {
+            Repr::Identity(0) => (None, None),
+            Repr::Identity(len) => (Some((Run::identity(), *len)), None),
+            Repr::Runs { runs, len } => (
+                None,
+                Some(runs.iter().enumerate().map(move |(i, run)| {
+                    let end = runs.get(i + 1).map_or(*len, |next| next.at);
+                    (*run, end - run.at)
+                })),
+            ),
+        };
+        identity.into_iter().chain(runs.into_iter().flatten())
+    }
+
+    /// Returns the original range covered by the given range of bytes, or `None` if the range
+    /// is empty or out of bounds
+    pub fn expand(&self, range: Range<usize>) -> Option<Range<usize>> {
+        if range.start >= range.end || range.end > self.len() {
+            None
+        } else {
+            Some(self.at(range.start).0..self.at(range.end - 1).1)
+        }
+    }
+
+    /// Extract the alignments of the given range of bytes, shifting their offsets to the left
+    /// by `shift`
+    pub fn slice(&self, range: Range<usize>, shift: usize) -> Option<Self> {
+        if range.start > range.end || range.end > self.len() {
+            return None;
+        }
+        let mut sliced = Self::default();
+        sliced.extend_from(self, range, shift);
+        Some(sliced)
+    }
+
+    /// Replace the given range of bytes with the given alignments
+    pub fn splice(&mut self, range: Range<usize>, alignments: &Alignments) {
+        let mut spliced = Self::default();
+        spliced.extend_from(self, 0..range.start, 0);
+        spliced.extend_from(alignments, 0..alignments.len(), 0);
+        spliced.extend_from(self, range.end..self.len(), 0);
+        *self = spliced;
+    }
+
+    /// Append the alignments of the given range of bytes of `other`, shifting their offsets
+    /// to the left by `shift`
+    pub fn extend_from(&mut self, other: &Alignments, range: Range<usize>, shift: usize) {
+        for (run, len) in other.runs() {
+            if run.at >= range.end {
+                break;
+            }
+            let from = range.start.max(run.at) - run.at;
+            let to = (range.end - run.at).min(len);
+
+            let mut k = from;
+            // The bytes in the middle of a group can't start a run on their own
+            while k < to && !run.starts_group(k) {
+                let (start, end) = run.get(k);
+                self.push((start - shift, end - shift));
+                k += 1;
+            }
+            if k < to {
+                let start = run.get(k).0 - shift;
+                self.push_run(Run { start, ..run }, to - k);
+            }
+        }
+    }
+
+    /// Append the alignment of one more byte
+    pub fn push(&mut self, offsets: Offsets) {
+        self.push_run(Run::new(offsets), 1)
+    }
+
+    /// Append the `count` first bytes of the given run, ignoring its `at`
+    fn push_run(&mut self, run: Run, count: usize) {
+        if count == 0 {
+            return;
+        }
+        if let Repr::Identity(len) = self.0 {
+            if Run::identity().continues(len, &run, count) {
+                self.0 = Repr::Identity(len + count);
+                return;
+            }
+            // We need to switch to the full representation
+            let runs = if len > 0 {
+                vec![Run::identity()]
+            } else {
+                vec![]
+            };
+            self.0 = Repr::Runs { runs, len };
+        }
+
+        if let Repr::Runs { runs, len } = &mut self.0 {
+            let at = *len;
+            *len += count;
+            if let Some(last) = runs.last_mut() {
+                let k = at - last.at;
+                if last.continues(k, &run, count) {
+                    return;
+                }
+                // A single group can still become a run of groups of its current size
+                if last.repeat == Run::SINGLE_GROUP
+                    && last.width > 0
+                    && last.width == run.width
+                    && run.start == last.start + last.width
+                    && (count == 1 || run.repeat == k)
+                {
+                    last.repeat = k;
+                    return;
+                }
+            }
+            runs.push(Run { at, ..run });
+        }
+    }
+}
+
+impl Default for Alignments {
+    fn default() -> Self {
+        Self(Repr::Identity(0))
+    }
+}
+
+impl Extend<Offsets> for Alignments {
+    fn extend<I: IntoIterator<Item = Offsets>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|offsets| self.push(offsets));
+    }
+}
+
+impl FromIterator<Offsets> for Alignments {
+    fn from_iter<I: IntoIterator<Item = Offsets>>(iter: I) -> Self {
+        let mut alignments = Self::default();
+        alignments.extend(iter);
+        alignments
+    }
+}
+
+impl From<Vec<Offsets>> for Alignments {
+    fn from(alignments: Vec<Offsets>) -> Self {
+        alignments.into_iter().collect()
+    }
+}
+
+impl PartialEq for Alignments {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.0, &other.0) {
+            (Repr::Identity(a), Repr::Identity(b)) => a == b,
+            _ => self.len() == other.len() && self.iter().eq(other.iter()),
+        }
+    }
+}
+
+impl Eq for Alignments {}
+
+impl std::fmt::Debug for Alignments {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn n_runs(alignments: &Alignments) -> usize {
+        match &alignments.0 {
+            Repr::Identity(_) => 0,
+            Repr::Runs { runs, .. } => runs.len(),
+        }
+    }
+
+    #[test]
+    fn identity() {
+        let ascii = Alignments::identity("abc");
+        assert!(matches!(ascii.0, Repr::Identity(3)));
+        assert_eq!(ascii.to_vec(), vec![(0, 1), (1, 2), (2, 3)]);
+
+        let unicode = Alignments::identity("aé");
+        assert_eq!(unicode.to_vec(), vec![(0, 1), (1, 3), (1, 3)]);
+        assert_eq!(unicode.expand(1..3), Some(1..3));
+        assert_eq!(unicode.expand(1..4), None);
+
+        assert_eq!(Alignments::from(vec![(0, 1), (1, 2), (2, 3)]), ascii);
+    }
+
+    #[test]
+    fn run_length_encoding() {
+        // Each 3-bytes char maps to itself, which makes a single run of groups of 3 bytes
+        let cjk = Alignments::identity("野口野口野口");
+        assert_eq!(n_runs(&cjk), 1);
+        assert_eq!(cjk.len(), 18);
+        assert_eq!(cjk.get(7), Some((6, 9)));
+        assert_eq!(cjk.get(18), None);
+
+        let alignments = vec![
+            (0, 0),
+            (0, 0),
+            (0, 2),
+            (0, 2),
+            (2, 3),
+            (3, 4),
+            (4, 5),
+            (4, 5),
+        ];
+        let encoded = Alignments::from(alignments.clone());
+        assert_eq!(encoded.to_vec(), alignments);
+        assert_eq!(n_runs(&encoded), 4);
+        assert_eq!(
+            (0..alignments.len())
+                .map(|i| encoded.at(i))
+                .collect::<Vec<_>>(),
+            alignments
+        );
+    }
+
+    #[test]
+    fn slice() {
+        let alignments = Alignments::identity("abcd");
+        let sliced = alignments.slice(1..3, 1).unwrap();
+        assert!(matches!(sliced.0, Repr::Identity(2)));
+        assert_eq!(sliced.to_vec(), vec![(0, 1), (1, 2)]);
+        assert!(alignments.slice(1..5, 1).is_none());
+
+        // Slicing in the middle of a group
+        let cjk = Alignments::identity("野口野");
+        let sliced = cjk.slice(4..9, 3).unwrap();
+        assert_eq!(
+            sliced.to_vec(),
+            vec![(0, 3), (0, 3), (3, 6), (3, 6), (3, 6)]
+        );
+        assert_eq!(n_runs(&sliced), 2);
+    }
+
+    #[test]
+    fn splice() {
+        let mut alignments = Alignments::identity("abcd");
+        alignments.splice(1..3, &vec![(1, 2), (2, 3)].into());
+        assert!(matches!(alignments.0, Repr::Identity(4)));
+
+        alignments.splice(1..3, &vec![(1, 3)].into());
+        assert_eq!(alignments.to_vec(), vec![(0, 1), (1, 3), (3, 4)]);
+        assert_eq!(n_runs(&alignments), 3);
+
+        // Long untouched parts stay compact whatever happens around them
+        let text = "a".repeat(1000);
+        let mut alignments = Alignments::identity(&text);
+        alignments.splice(500..501, &vec![(500, 501), (500, 501)].into());
+        assert_eq!(alignments.len(), 1001);
+        assert_eq!(n_runs(&alignments), 3);
+        assert_eq!(alignments.at(502), (501, 502));
+    }
+}
@@ -0,0 +1,162 @@
+use super::{InputSequence, Offsets};
+
+/// The tokenizer that left an artifact in a text. See [`find_tokenizer_artifacts`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ArtifactKind {
+    /// The `▁` replacing the spaces in SentencePiece and `Metaspace` tokens
+    Metaspace,
+    /// The `Ġ` and `Ċ` replacing the spaces and newlines in `ByteLevel` tokens
+    ByteLevel,
+    /// A byte token of a model with byte fallback, like `<0x0A>`
+    ByteFallback,
+}
+
+/// A part of a text that looks like it comes from tokens instead of the original text
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TokenizerArtifact {
+    pub kind: ArtifactKind,
+    /// The offsets of the artifact in the text, in bytes
+    pub offsets: Offsets,
+}
+
+/// Whether the byte-level space or newline char can follow `prev`. In regular text these
+/// chars (also Maltese letters) start the words, after a space or a hyphen, while in
+/// byte-level tokens they follow the end of the previous word.
+fn after_word(prev: Option<char>) -> bool {
+    prev.is_some_and(|c| c.is_alphanumeric() || ",.;:!?)\"".contains(c))
+}
+
+/// Find the parts of the text that look like tokenizer artifacts: the text was probably
+/// encoded to tokens upstream, and these tokens joined back into a string instead of being
+/// decoded. Tokenizing it again silently corrupts the data, each artifact being tokenized
+/// as a char of its own.
+///
+/// This is a heuristic: a `Ġ` or `Ċ` only counts when it follows the end of a word, as
+/// these are also letters of the Maltese alphabet.
+/// ```
+/// use tokenizers::{find_tokenizer_artifacts, ArtifactKind, TokenizerArtifact};
+///
+/// assert!(find_tokenizer_artifacts("Il-Ġermanja, ċentru").is_empty());
+/// assert_eq!(
+///     find_tokenizer_artifacts("HelloĠworld"),
+///     [TokenizerArtifact { kind: ArtifactKind::ByteLevel, offsets: (5, 7) }]
+/// );
+/// ```
+pub fn find_tokenizer_artifacts(text: &str) -> Vec<TokenizerArtifact> {
+    let mut artifacts = vec![];
+    let mut prev = None;
+    for (i, c) in text.char_indices() {
+        let kind = match c {
+            '▁' => Some(ArtifactKind::Metaspace),
+            'Ġ' | 'Ċ' if after_word(prev) => Some(ArtifactKind::ByteLevel),
+            '<' if is_byte_token(&text.as_bytes()[i..]) => Some(ArtifactKind::ByteFallback),
+            _ => None,
+        };
+        if let Some(kind) = kind {
+            let len = match kind {
+                ArtifactKind::ByteFallback => 6,
+                _ => c.len_utf8(),
+            };
+            artifacts.push(TokenizerArtifact {
+                kind,
+                offsets: (i, i + len),
+            });
+        }
+        prev = Some(c);
+    }
+    artifacts
+}
+
+/// Whether the bytes start with a byte token like `<0x0A>`
+fn is_byte_token(bytes: &[u8]) -> bool {
+    matches!(bytes, [b'<', b'0', b'x', h, l, b'>', ..] if h.is_ascii_hexdigit() && l.is_ascii_hexdigit())
+}
+
+/// The warning about the artifacts of the given text, if any
+pub fn artifacts_warning(text: &str) -> Option<String> {
+    let artifacts = find_tokenizer_artifacts(text);
+    let first = artifacts.first()?;
+    Some(format!(
+        "The input looks already tokenized: it contains {} {:?} artifact(s), the first one \
+         being `{}` at the bytes {:?}. It was probably encoded and joined back upstream.",
+        artifacts.len(),
+        first.kind,
+        &text[first.offsets.0..first.offsets.1],
+        first.offsets
+    ))
+}
+
+impl InputSequence<'_> {
+    /// The warning about the artifacts of this sequence, or of its first word with some
+    pub fn artifacts_warning(&self) -> Option<String> {
+        match self {
+            Self::Raw(text) => artifacts_warning(text),
+            Self::PreTokenized(words) => words.iter().find_map(|w| artifacts_warning(w)),
+            Self::PreTokenizedOwned(words) => words.iter().find_map(|w| artifacts_warning(w)),
+            Self::PreTokenizedCow(words) => words.iter().find_map(|w| artifacts_warning(w)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::wordlevel::WordLevel;
+    use crate::Tokenizer;
+
+    #[test]
+    fn artifacts() {
+        let kinds = |text| {
+            find_tokenizer_artifacts(text)
+                .into_iter()
+                .map(|a| (a.kind, a.offsets))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            kinds("▁Hello▁world"),
+            [
+                (ArtifactKind::Metaspace, (0, 3)),
+                (ArtifactKind::Metaspace, (8, 11))
+            ]
+        );
+        assert_eq!(
+            kinds("Hello,Ġworld!Ċ"),
+            [
+                (ArtifactKind::ByteLevel, (6, 8)),
+                (ArtifactKind::ByteLevel, (14, 16))
+            ]
+        );
+        assert_eq!(
+            kinds("a<0x0A>b <0xZZ> <0x4"),
+            [(ArtifactKind::ByteFallback, (1, 7))]
+        );
+        // Regular text, including Maltese
+        assert!(kinds("Hello world <b>").is_empty());
+        assert!(kinds("Ġenerali u l-Ġermanja").is_empty());
+
+        let words = InputSequence::from(vec!["Hello", "<0x0A>"]);
+        assert_eq!(
+            words.artifacts_warning().unwrap(),
+            "The input looks already tokenized: it contains 1 ByteFallback artifact(s), the \
+             first one being `<0x0A>` at the bytes (0, 6). It was probably encoded and joined back upstream."
+        );
+        assert!(InputSequence::from("Hello").artifacts_warning().is_none());
+    }
+
+    #[test]
+    fn warnings() {
+        let vocab = vec![("[UNK]".to_string(), 0)].into_iter().collect();
+        let model = WordLevel::builder()
+            .vocab(vocab)
+            .unk_token("[UNK]".into())
+            .build()
+            .unwrap();
+        let mut tokenizer = Tokenizer::new(model);
+        assert!(!tokenizer.get_artifact_warnings());
+        tokenizer.with_artifact_warnings(true);
+        assert!(tokenizer.get_artifact_warnings());
+        // Only warns, the encoding works as usual
+        tokenizer.encode("▁Hello", false).unwrap();
+        tokenizer.encode(vec!["▁Hello", "world"], false).unwrap();
+    }
+}
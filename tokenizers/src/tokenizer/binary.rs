@@ -0,0 +1,273 @@
+//! The binary format of [`TokenizerImpl::save_mmap`], made of:
+//!   - the magic bytes,
+//!   - the length of the JSON header, as a little-endian `u64`, and the header itself: the
+//!     usual JSON serialization of the tokenizer, without the tables of the model,
+//!   - padded to a multiple of 4 bytes, a little-endian `u32` telling which tables follow:
+//!     0 for none, 1 for the vocab and merges of a `BPE`, 2 for the vocab of a `WordLevel`
+//!     or a `WordPiece`.
+use super::{AddedTokenWithId, Result, Tokenizer, TokenizerImpl};
+use crate::models::bpe::{MappedBpe, BPE};
+use crate::models::vocab_store::{MappedVocab, SharedVocab};
+use crate::models::ModelWrapper;
+use crate::utils::mmap::{read_u32, MappedFile};
+use ahash::AHashMap;
+use serde::{Deserialize, Serialize};
+use std::convert::{TryFrom, TryInto};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+const MAGIC: &[u8; 8] = b"TKZRBIN1";
+const INVALID: &str = "Invalid binary tokenizer file";
+const NO_TABLES: u32 = 0;
+const BPE_TABLES: u32 = 1;
+const VOCAB_TABLE: u32 = 2;
+
+impl<M, N, PT, PP, D> TokenizerImpl<M, N, PT, PP, D>
+where
+    M: Serialize,
+    N: Serialize,
+    PT: Serialize,
+    PP: Serialize,
+    D: Serialize,
+{
+    /// Save the tokenizer in a binary file, to be loaded with [`Tokenizer::from_file_mmap`].
+    ///
+    /// The vocab of the `BPE`, `WordLevel` and `WordPiece` models, and the merges of a `BPE`,
+    /// are written as tables that can be read in place, everything else is written as JSON.
+    pub fn save_mmap<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let mut header = serde_json::to_value(self)?;
+        let model = &mut header["model"];
+        let mut tables = vec![];
+        let kind = match model["type"].as_str() {
+            Some("BPE") => {
+                let bpe = BPE::deserialize(&*model)?;
+                MappedBpe::write(&bpe.owned_vocab_r(), &bpe.owned_merges(), &mut tables)?;
+                model["merges"] = serde_json::json!([]);
+                BPE_TABLES
+            }
+            Some("WordLevel" | "WordPiece") => {
+                let vocab_r = AHashMap::<String, u32>::deserialize(&model["vocab"])?
+                    .into_iter()
+                    .map(|(token, id)| (id, token))
+                    .collect();
+                MappedVocab::write(&vocab_r, &mut tables)?;
+                VOCAB_TABLE
+            }
+            _ => NO_TABLES,
+        };
+        if kind != NO_TABLES {
+            model["vocab"] = serde_json::json!({});
+        }
+        let header = serde_json::to_vec(&header)?;
+
+        let mut out = BufWriter::new(File::create(path)?);
+        out.write_all(MAGIC)?;
+        out.write_all(&(header.len() as u64).to_le_bytes())?;
+        out.write_all(&header)?;
+        let header_end = MAGIC.len() + 8 + header.len();
+        out.write_all(&[0; 3][..header_end.next_multiple_of(4) - header_end])?;
+        out.write_all(&kind.to_le_bytes())?;
+        out.write_all(&tables)?;
+        out.flush()?;
+        Ok(())
+    }
+}
+
+impl Tokenizer {
+    /// Load a tokenizer saved with [`TokenizerImpl::save_mmap`].
+    ///
+    /// The file is memory mapped, and the tables of the model are looked up in place: nothing
+    /// is indexed when loading, and the pages are shared by every process using the same
+    /// file, including the workers forked after loading. The rest of the tokenizer is parsed
+    /// as usual.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified, nor truncated, while the tokenizer or any of its clones
+    /// is in use. A change of its content is undefined behavior, and reading past a
+    /// truncation kills the process with a `SIGBUS`.
+    pub unsafe fn from_file_mmap<P: AsRef<Path>>(path: P) -> Result<Self> {
+        // SAFETY: The caller upholds the contract of the mapped files
+        let file = Arc::new(unsafe { MappedFile::open(path) }?);
+        if !file.starts_with(MAGIC) {
+            return Err(format!("{INVALID}: the magic bytes are missing").into());
+        }
+        let header_start = MAGIC.len() + 8;
+        let header_len = file
+            .get(MAGIC.len()..header_start)
+            .and_then(|len| usize::try_from(u64::from_le_bytes(len.try_into().ok()?)).ok())
+            .ok_or(INVALID)?;
+        let header_end = header_start.checked_add(header_len).ok_or(INVALID)?;
+        let mut header: serde_json::Value =
+            serde_json::from_slice(file.get(header_start..header_end).ok_or(INVALID)?)?;
+
+        // The ids of the added tokens depend on the vocab, so they are added after the tables
+        let added_tokens = header
+            .as_object_mut()
+            .and_then(|header| header.remove("added_tokens"))
+            .unwrap_or_default();
+        let mut tokenizer: Tokenizer = serde_json::from_value(header)?;
+
+        let tables = header_end.next_multiple_of(4);
+        let kind = read_u32(&file, tables).ok_or(INVALID)?;
+        let vocab = || -> Result<SharedVocab> {
+            let vocab = MappedVocab::parse(file.clone(), tables + 4)?;
+            if vocab.end() != file.len() {
+                return Err(INVALID.into());
+            }
+            Ok(SharedVocab::new(vocab))
+        };
+        match (kind, &mut tokenizer.0.model) {
+            (NO_TABLES, _) => {}
+            (BPE_TABLES, ModelWrapper::BPE(bpe)) => {
+                let mapped = MappedBpe::parse(file.clone(), tables + 4)?;
+                if mapped.end() != file.len() {
+                    return Err(INVALID.into());
+                }
+                bpe.set_mapped(mapped);
+            }
+            (VOCAB_TABLE, ModelWrapper::WordLevel(model)) => model.set_vocab_store(vocab()?),
+            (VOCAB_TABLE, ModelWrapper::WordPiece(model)) => model.set_vocab_store(vocab()?),
+            _ => return Err(INVALID.into()),
+        }
+
+        if !added_tokens.is_null() {
+            let added_tokens: Vec<AddedTokenWithId> = serde_json::from_value(added_tokens)?;
+            let added_tokens: Vec<_> = added_tokens.into_iter().map(|t| t.token).collect();
+            tokenizer.try_add_tokens(&added_tokens)?;
+        }
+        Ok(tokenizer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::bpe::Vocab;
+    use crate::models::unigram::Unigram;
+    use crate::models::wordlevel::WordLevel;
+    use crate::models::wordpiece::WordPiece;
+    use crate::pre_tokenizers::whitespace::Whitespace;
+    use crate::AddedToken;
+    use tempfile::NamedTempFile;
+
+    fn bpe() -> BPE {
+        let vocab: Vocab = [
+            ("<unk>", 0),
+            ("a", 1),
+            ("b", 2),
+            ("c", 3),
+            ("ab", 4),
+            ("abc", 5),
+        ]
+        .iter()
+        .map(|(token, id)| (token.to_string(), *id))
+        .collect();
+        let merges = vec![
+            ("a".to_string(), "b".to_string()),
+            ("ab".to_string(), "c".to_string()),
+        ];
+        BPE::builder()
+            .vocab_and_merges(vocab, merges)
+            .unk_token("<unk>".into())
+            .build()
+            .unwrap()
+    }
+
+    fn round_trip(tokenizer: &Tokenizer) -> Tokenizer {
+        let file = NamedTempFile::new().unwrap();
+        tokenizer.save_mmap(file.path()).unwrap();
+        // SAFETY: The temporary file is not modified while mapped
+        unsafe { Tokenizer::from_file_mmap(file.path()) }.unwrap()
+    }
+
+    #[test]
+    fn bpe_round_trip() {
+        let mut tokenizer = Tokenizer::new(bpe());
+        tokenizer.with_pre_tokenizer(Some(Whitespace {}));
+        tokenizer.add_special_tokens(&[AddedToken::from("<unk>", true)]);
+        tokenizer.add_tokens(&[AddedToken::from("[NEW]", false)]);
+
+        let mapped = round_trip(&tokenizer);
+        assert_eq!(
+            mapped.to_string(false).unwrap(),
+            tokenizer.to_string(false).unwrap()
+        );
+        assert_eq!(mapped.get_vocab_size(true), tokenizer.get_vocab_size(true));
+        assert_eq!(mapped.token_to_id("abc"), Some(5));
+        assert_eq!(mapped.token_to_id("[NEW]"), Some(6));
+        assert_eq!(mapped.id_to_token(4), Some("ab".into()));
+
+        let input = "abcab [NEW] <unk> abd";
+        let expected = tokenizer.encode(input, true).unwrap();
+        let encoding = mapped.encode(input, true).unwrap();
+        assert_eq!(encoding.get_tokens(), expected.get_tokens());
+        assert_eq!(encoding.get_ids(), &[5, 4, 6, 0, 4, 0]);
+
+        // A mapped tokenizer can be saved again
+        let mapped = round_trip(&mapped);
+        assert_eq!(
+            mapped.encode(input, true).unwrap().get_ids(),
+            expected.get_ids()
+        );
+    }
+
+    #[test]
+    fn vocab_round_trip() {
+        let vocab: Vocab = [("<unk>", 0), ("[UNK]", 1), ("a", 2), ("##b", 3)]
+            .iter()
+            .map(|(token, id)| (token.to_string(), *id))
+            .collect();
+        let models: Vec<ModelWrapper> = vec![
+            WordLevel::builder()
+                .vocab(vocab.clone())
+                .unk_token("<unk>".into())
+                .build()
+                .unwrap()
+                .into(),
+            WordPiece::builder().vocab(vocab).build().unwrap().into(),
+        ];
+        for model in models {
+            let tokenizer = Tokenizer::new(model);
+            let mapped = round_trip(&tokenizer);
+            assert_eq!(
+                mapped.to_string(false).unwrap(),
+                tokenizer.to_string(false).unwrap()
+            );
+            assert_eq!(mapped.get_vocab(false), tokenizer.get_vocab(false));
+            assert_eq!(mapped.token_to_id("a"), Some(2));
+            assert_eq!(mapped.id_to_token(3), Some("##b".into()));
+            for input in ["a", "ab", "c"] {
+                assert_eq!(
+                    mapped.encode(input, false).unwrap().get_ids(),
+                    tokenizer.encode(input, false).unwrap().get_ids()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn other_models() {
+        let model = Unigram::from(vec![("<unk>".to_string(), 0.0)], Some(0), false).unwrap();
+        let tokenizer = round_trip(&Tokenizer::new(model.clone()));
+        assert_eq!(tokenizer.get_model(), &ModelWrapper::Unigram(model));
+    }
+
+    #[test]
+    fn invalid_files() {
+        let file = NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), b"{\"version\":\"1.0\"}").unwrap();
+        // SAFETY: The temporary file is only modified while not mapped
+        assert!(unsafe { Tokenizer::from_file_mmap(file.path()) }
+            .unwrap_err()
+            .to_string()
+            .contains("the magic bytes are missing"));
+
+        Tokenizer::new(bpe()).save_mmap(file.path()).unwrap();
+        let content = std::fs::read(file.path()).unwrap();
+        std::fs::write(file.path(), &content[..content.len() - 4]).unwrap();
+        assert!(unsafe { Tokenizer::from_file_mmap(file.path()) }.is_err());
+    }
+}
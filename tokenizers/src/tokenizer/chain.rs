@@ -0,0 +1,171 @@
+use super::Tokenizer;
+use crate::pre_tokenizers::model_split::ModelSplit;
+use crate::pre_tokenizers::sequence::Sequence;
+use crate::pre_tokenizers::PreTokenizerWrapper;
+
+impl Tokenizer {
+    /// Chain two tokenizers: the tokens found by the model of `first` become the pre-tokens
+    /// fed to the model of `second`, like the morphemes of a morphological tokenizer fed to a
+    /// `BPE`. The result is a single tokenizer, serialized as usual. The offsets of its tokens
+    /// refer to the original text, and each token of `first` is a word of its own.
+    ///
+    /// The text goes through:
+    ///   - the normalizer and the pre-tokenizer of `first`,
+    ///   - a [`ModelSplit`] with the model of `first`, which applies the normalizer of
+    ///     `second` to each of its tokens,
+    ///   - the pre-tokenizer and the model of `second`.
+    ///
+    /// Everything else comes from `second`: the added tokens, the post-processor, the
+    /// decoder, the padding and the truncation. Those of `first` are dropped.
+    pub fn chain(first: Tokenizer, second: Tokenizer) -> Tokenizer {
+        let first = first.into_inner();
+        let mut chained = second;
+
+        let mut pretokenizers = vec![];
+        pretokenizers.extend(first.pre_tokenizer);
+        pretokenizers.push(PreTokenizerWrapper::from(ModelSplit::new(
+            first.model,
+            chained.0.normalizer.take(),
+        )));
+        pretokenizers.extend(chained.0.pre_tokenizer.take());
+
+        chained.0.normalizer = first.normalizer;
+        chained.0.pre_tokenizer = Some(Sequence::new(pretokenizers).into());
+        chained
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::bpe::BPE;
+    use crate::models::wordpiece::WordPiece;
+    use crate::normalizers::utils::Lowercase;
+    use crate::pre_tokenizers::whitespace::Whitespace;
+    use crate::AddedToken;
+
+    fn vocab(tokens: &[&str]) -> ahash::AHashMap<String, u32> {
+        tokens
+            .iter()
+            .enumerate()
+            .map(|(id, token)| (token.to_string(), id as u32))
+            .collect()
+    }
+
+    /// Splits the words into morphemes
+    fn morphemes() -> Tokenizer {
+        let model = WordPiece::builder()
+            .vocab(vocab(&["[UNK]", "un", "##happi", "##ness"]))
+            .build()
+            .unwrap();
+        let mut first = Tokenizer::new(model);
+        first.with_normalizer(Some(Lowercase));
+        first.with_pre_tokenizer(Some(Whitespace {}));
+        first
+    }
+
+    fn bpe() -> Tokenizer {
+        let model = BPE::builder()
+            .vocab_and_merges(
+                vocab(&[
+                    "<unk>", "u", "n", "h", "a", "p", "i", "e", "s", "un", "pp", "ss", "in",
+                ]),
+                vec![
+                    ("u".into(), "n".into()),
+                    ("p".into(), "p".into()),
+                    ("s".into(), "s".into()),
+                    ("i".into(), "n".into()),
+                ],
+            )
+            .unk_token("<unk>".into())
+            .build()
+            .unwrap();
+        let mut second = Tokenizer::new(model);
+        second.add_special_tokens(&[AddedToken::from("<unk>", true)]);
+        second
+    }
+
+    #[test]
+    fn chain() {
+        let chained = Tokenizer::chain(morphemes(), bpe());
+        assert_eq!(
+            bpe().encode("unhappiness", false).unwrap().get_tokens(),
+            &["un", "h", "a", "pp", "in", "e", "ss"]
+        );
+        let encoding = chained.encode("Unhappiness", false).unwrap();
+        assert_eq!(
+            encoding.get_tokens(),
+            &["un", "h", "a", "pp", "i", "n", "e", "ss"]
+        );
+        // The merges never cross the morphemes
+        assert_eq!(
+            encoding.get_offsets(),
+            &[
+                (0, 2),
+                (2, 3),
+                (3, 4),
+                (4, 6),
+                (6, 7),
+                (7, 8),
+                (8, 9),
+                (9, 11)
+            ]
+        );
+        // Each morpheme is a word of its own
+        assert_eq!(
+            encoding.get_word_ids(),
+            &[
+                Some(0),
+                Some(1),
+                Some(1),
+                Some(1),
+                Some(1),
+                Some(2),
+                Some(2),
+                Some(2)
+            ]
+        );
+        assert_eq!(chained.get_vocab_size(true), 13);
+    }
+
+    #[test]
+    fn manual_glue() {
+        let (first, second) = (morphemes(), bpe());
+        let chained = Tokenizer::chain(first.clone(), second.clone());
+
+        // The offsets of the first encoding give the morphemes in the lowercased text
+        let input = "UNHAPPINESS un";
+        let lowercased = input.to_lowercase();
+        let tokens = first
+            .encode(input, false)
+            .unwrap()
+            .get_offsets()
+            .iter()
+            .flat_map(|(start, end)| {
+                let morpheme = &lowercased[*start..*end];
+                second
+                    .encode(morpheme, false)
+                    .unwrap()
+                    .get_tokens()
+                    .to_vec()
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            chained.encode(input, false).unwrap().get_tokens(),
+            tokens.as_slice()
+        );
+    }
+
+    #[test]
+    fn serialization() {
+        let chained = Tokenizer::chain(morphemes(), bpe());
+        let serialized = chained.to_string(false).unwrap();
+        let deserialized: Tokenizer = serialized.parse().unwrap();
+        assert_eq!(deserialized.to_string(false).unwrap(), serialized);
+        assert_eq!(
+            deserialized.encode("unhappiness", false).unwrap().get_ids(),
+            chained.encode("unhappiness", false).unwrap().get_ids()
+        );
+    }
+}
@@ -3,6 +3,9 @@ use crate::tokenizer::{Offsets, Token};
 use crate::utils::padding::PaddingDirection;
 use crate::utils::truncation::TruncationDirection;
 use ahash::AHashMap;
+use rand::seq::IndexedRandom;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
 use serde::{Deserialize, Serialize};
 use std::ops::Range;
 
@@ -28,6 +31,9 @@ pub struct Encoding {
     /// Ranges of tokens covered by each sequence. If this is empty we consider
     /// there is only one sequence in this Encoding, and that it covers the entire range.
     sequence_ranges: AHashMap<usize, Range<usize>>,
+    /// Metadata of the word associated to each token/ID, empty if none was attached
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    word_metadata: Vec<Option<Vec<f64>>>,
 }
 impl Encoding {
     #[allow(clippy::too_many_arguments)]
@@ -52,6 +58,7 @@ impl Encoding {
             attention_mask,
             overflowing,
             sequence_ranges,
+            word_metadata: vec![],
         }
     }
 
@@ -66,6 +73,7 @@ impl Encoding {
             attention_mask: Vec::with_capacity(len),
             overflowing: vec![],
             sequence_ranges: AHashMap::new(),
+            word_metadata: vec![],
         }
     }
 
@@ -95,6 +103,7 @@ impl Encoding {
             special_tokens_mask: vec![0; length],
             overflowing: vec![],
             sequence_ranges: AHashMap::new(),
+            word_metadata: vec![],
         }
     }
 
@@ -134,6 +143,29 @@ impl Encoding {
         &mut self.words
     }
 
+    /// The metadata of the word associated to each token, empty if none was attached with
+    /// [`set_word_metadata`](Self::set_word_metadata)
+    pub fn get_word_metadata(&self) -> &[Option<Vec<f64>>] {
+        &self.word_metadata
+    }
+
+    /// Attach its metadata to each token of a word, like the timestamps of the words in an
+    /// audio for forced alignment, `metadata` holding the metadata of each word of each
+    /// sequence. The overflowing encodings get their metadata too, and the metadata then
+    /// follow the tokens through the truncation, the padding and the merges.
+    pub fn set_word_metadata(&mut self, metadata: &[Vec<Vec<f64>>]) {
+        for overflowing in &mut self.overflowing {
+            overflowing.set_word_metadata(metadata);
+        }
+        let sequence_ids = self.get_sequence_ids();
+        self.word_metadata = self
+            .words
+            .iter()
+            .zip(sequence_ids)
+            .map(|(word, sequence)| metadata.get(sequence?)?.get((*word)? as usize).cloned())
+            .collect();
+    }
+
     pub fn get_sequence_ids(&self) -> Vec<Option<usize>> {
         let mut sequences = vec![None; self.len()];
         for seq_id in 0..self.n_sequences() {
@@ -188,6 +220,16 @@ impl Encoding {
         std::mem::take(&mut self.overflowing)
     }
 
+    /// Consume this `Encoding`, returning it followed by all its overflowing parts as a flat
+    /// list of windows. None of the returned windows has overflowing parts anymore.
+    pub fn into_windows(mut self) -> Vec<Encoding> {
+        let overflowing = self.take_overflowing();
+        let mut windows = Vec::with_capacity(overflowing.len() + 1);
+        windows.push(self);
+        windows.extend(overflowing.into_iter().flat_map(Encoding::into_windows));
+        windows
+    }
+
     pub(crate) fn process_tokens_with_offsets_mut<F>(&mut self, func: F)
     where
         F: FnMut((usize, (&String, &mut Offsets))),
@@ -282,6 +324,10 @@ impl Encoding {
     }
 
     /// Get the token that contains the given char.
+    ///
+    /// The chars inserted by the normalizer share the offsets of the char they were inserted
+    /// next to, so when several tokens contain the given char, the first one is returned.
+    /// The chars removed by the normalizer are not contained by any token.
     pub fn char_to_token(&self, pos: usize, sequence_id: usize) -> Option<usize> {
         let sequence_range = self.sequence_range(sequence_id);
 
@@ -292,6 +338,29 @@ impl Encoding {
             .map(|pos| sequence_range.start + pos)
     }
 
+    /// Get the encoded tokens overlapping the given span of chars in the input sequence,
+    /// with the form (start_token, end_token + 1)
+    ///
+    /// A token cut by the span is included entirely, just like the tokens made of the chars
+    /// inserted by the normalizer next to a char of the span. Returns `None` if no token
+    /// overlaps the span, which happens with an empty span, or when all its chars have been
+    /// removed by the normalizer.
+    pub fn span_to_tokens(&self, span: Offsets, sequence_id: usize) -> Option<(usize, usize)> {
+        if span.0 >= span.1 {
+            return None;
+        }
+        let sequence_range = self.sequence_range(sequence_id);
+        let offsets = self.offsets.get(sequence_range.clone())?;
+
+        let overlaps = |(start, end): &Offsets| *start < span.1 && span.0 < *end;
+        let first = offsets.iter().position(overlaps)?;
+        let last = offsets.iter().rposition(overlaps)?;
+        Some((
+            sequence_range.start + first,
+            sequence_range.start + last + 1,
+        ))
+    }
+
     /// Get the word that contains the given char.
     pub fn char_to_word(&self, pos: usize, sequence_id: usize) -> Option<u32> {
         Some(
@@ -301,10 +370,206 @@ impl Encoding {
         )
     }
 
+    /// Choose the words to mask for whole word masking, like BERT does for its masked
+    /// language modeling: `probability` of the words, rounded, and at least one, get all
+    /// their tokens masked. The special tokens, the padding, and the tokens without a word
+    /// are never masked.
+    ///
+    /// The words are chosen at random, the same ones for the same `seed`, whatever the
+    /// platform or the version of `rand`, as drawn by a ChaCha8 generator. The returned mask
+    /// has `1` for each masked token, and `0` for the others. The probability is clamped
+    /// between `0.0` and `1.0`, and no word gets masked with `0.0`.
+    /// ```
+    /// use tokenizers::{Encoding, Token};
+    ///
+    /// let token = |id, value: &str, offsets| Token::new(id, value.into(), offsets);
+    /// let mut encoding = Encoding::from_tokens(
+    ///     vec![token(0, "un", (0, 2)), token(1, "##able", (2, 6)), token(2, "to", (7, 9))],
+    ///     0,
+    /// );
+    /// encoding.get_word_ids_mut().copy_from_slice(&[Some(0), Some(0), Some(1)]);
+    /// let mask = encoding.whole_word_mask(0.5, 42);
+    /// assert!(mask == [1, 1, 0] || mask == [0, 0, 1]);
+    /// assert_eq!(encoding.whole_word_mask(0.5, 42), mask);
+    /// ```
+    pub fn whole_word_mask(&self, probability: f64, seed: u64) -> Vec<u32> {
+        let mut mask = vec![0; self.len()];
+        let probability = probability.clamp(0.0, 1.0);
+        if probability == 0.0 {
+            return mask;
+        }
+
+        // The tokens of each word, in order of appearance
+        let mut words: Vec<Vec<usize>> = vec![];
+        let mut word_index = AHashMap::new();
+        for (i, word) in self.words.iter().enumerate() {
+            let Some(word) = word else { continue };
+            if self.special_tokens_mask[i] == 1 || self.attention_mask[i] == 0 {
+                continue;
+            }
+            let key = (self.token_to_sequence(i), *word);
+            let index = *word_index.entry(key).or_insert_with(|| {
+                words.push(vec![]);
+                words.len() - 1
+            });
+            words[index].push(i);
+        }
+        if words.is_empty() {
+            return mask;
+        }
+
+        let n_masked = ((words.len() as f64 * probability).round() as usize).max(1);
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        for word in words.choose_multiple(&mut rng, n_masked) {
+            for &i in word {
+                mask[i] = 1;
+            }
+        }
+        mask
+    }
+
+    /// The [whole word masks](Encoding::whole_word_mask) of a batch of encodings, computed in
+    /// parallel. Each encoding gets its own seed, derived from `seed` and its index in the
+    /// batch, so the masks don't depend on the parallelism.
+    pub fn whole_word_mask_batch(
+        encodings: &[Encoding],
+        probability: f64,
+        seed: u64,
+    ) -> Vec<Vec<u32>> {
+        encodings
+            .maybe_par_iter()
+            .enumerate()
+            .map(|(i, encoding)| encoding.whole_word_mask(probability, seed.wrapping_add(i as u64)))
+            .collect()
+    }
+
+    /// Replace the tokens overlapping each of the given spans of the input sequence by the
+    /// tokens of the `placeholder`, like an encoding of `[REDACTED]`, to remove some personal
+    /// information while keeping the alignment with the original document.
+    ///
+    /// The tokens of each placeholder get the offsets of all the tokens they replace, and
+    /// form a single word, the following words of the sequence being renumbered. The spans
+    /// that overlap the same tokens get a single placeholder. Everything else is identical,
+    /// including the overflowing encodings, which get their own placeholders.
+    /// ```
+    /// use tokenizers::{Encoding, Token};
+    ///
+    /// let token = |id, value: &str, offsets| Token::new(id, value.into(), offsets);
+    /// let mut encoding = Encoding::from_tokens(
+    ///     vec![token(0, "Hi", (0, 2)), token(1, "John", (3, 7)), token(2, "Doe", (8, 11))],
+    ///     0,
+    /// );
+    /// let placeholder = Encoding::from_tokens(vec![token(9, "[PII]", (0, 5))], 0);
+    /// encoding.redact(&[(3, 11)], &placeholder, 0);
+    /// assert_eq!(encoding.get_tokens(), ["Hi", "[PII]"]);
+    /// assert_eq!(encoding.get_offsets(), [(0, 2), (3, 11)]);
+    /// ```
+    pub fn redact(&mut self, spans: &[Offsets], placeholder: &Encoding, sequence_id: usize) {
+        for overflowing in &mut self.overflowing {
+            overflowing.redact(spans, placeholder, sequence_id);
+        }
+
+        let mut ranges: Vec<(usize, usize)> = spans
+            .iter()
+            .filter_map(|span| self.span_to_tokens(*span, sequence_id))
+            .collect();
+        if ranges.is_empty() {
+            return;
+        }
+        ranges.sort_unstable();
+        let mut merged: Vec<(usize, usize)> = Vec::with_capacity(ranges.len());
+        for (start, end) in ranges {
+            match merged.last_mut() {
+                Some(last) if start < last.1 => last.1 = last.1.max(end),
+                _ => merged.push((start, end)),
+            }
+        }
+
+        let sequence_range = self.sequence_range(sequence_id);
+        let mut encoding = Encoding::with_capacity(self.len());
+        // The index of each token in the new encoding, to update the sequence ranges
+        let mut new_index = vec![0; self.len() + 1];
+        // The number of words merged so far, to shift the following words of the sequence
+        let mut shift = 0;
+        let keep =
+            |encoding: &mut Encoding, new_index: &mut [usize], range: Range<usize>, shift| {
+                for i in range {
+                    new_index[i] = encoding.len();
+                    encoding.ids.push(self.ids[i]);
+                    encoding.type_ids.push(self.type_ids[i]);
+                    encoding.tokens.push(self.tokens[i].clone());
+                    let in_sequence = sequence_range.contains(&i);
+                    encoding
+                        .words
+                        .push(self.words[i].map(|w| if in_sequence { w - shift } else { w }));
+                    encoding.offsets.push(self.offsets[i]);
+                    encoding
+                        .special_tokens_mask
+                        .push(self.special_tokens_mask[i]);
+                    encoding.attention_mask.push(self.attention_mask[i]);
+                    if let Some(metadata) = self.word_metadata.get(i) {
+                        encoding.word_metadata.push(metadata.clone());
+                    }
+                }
+            };
+        let mut next = 0;
+        for (start, end) in merged {
+            keep(&mut encoding, &mut new_index, next..start, shift);
+            // The redacted tokens all map to the first token of the placeholder
+            new_index[start..end].fill(encoding.len());
+            let words = self.words[start..end].iter().flatten();
+            let (first, last) = (words.clone().min().copied(), words.max().copied());
+            let word = first.map(|w| w - shift);
+            for (id, token) in placeholder.ids.iter().zip(&placeholder.tokens) {
+                encoding.ids.push(*id);
+                encoding.type_ids.push(self.type_ids[start]);
+                encoding.tokens.push(token.clone());
+                encoding.words.push(word);
+                encoding
+                    .offsets
+                    .push((self.offsets[start].0, self.offsets[end - 1].1));
+                encoding.special_tokens_mask.push(0);
+                encoding.attention_mask.push(1);
+                if !self.word_metadata.is_empty() {
+                    encoding.word_metadata.push(None);
+                }
+            }
+            if let (Some(first), Some(last)) = (first, last) {
+                shift += last - first;
+            }
+            next = end;
+        }
+        keep(&mut encoding, &mut new_index, next..self.len(), shift);
+        new_index[self.len()] = encoding.len();
+
+        encoding.sequence_ranges = self
+            .sequence_ranges
+            .iter()
+            .map(|(id, range)| (*id, new_index[range.start]..new_index[range.end]))
+            .collect();
+        encoding.overflowing = std::mem::take(&mut self.overflowing);
+        *self = encoding;
+    }
+
     /// Truncate the current `Encoding`.
     ///
     /// Panics if `stride >= max_len`
     pub fn truncate(&mut self, max_len: usize, stride: usize, direction: TruncationDirection) {
+        self.truncate_with_max_overflowing(max_len, stride, direction, None)
+    }
+
+    /// Truncate the current `Encoding`, keeping at most `max_overflowing` overflowing parts,
+    /// or all of them with `None`. The parts beyond are never built, so only the first windows
+    /// of a long sequence cost anything.
+    ///
+    /// Panics if `stride >= max_len`
+    pub fn truncate_with_max_overflowing(
+        &mut self,
+        max_len: usize,
+        stride: usize,
+        direction: TruncationDirection,
+        max_overflowing: Option<usize>,
+    ) {
         let encoding_len = self.ids.len();
         if max_len >= encoding_len {
             return;
@@ -312,7 +577,9 @@ impl Encoding {
 
         if max_len == 0 {
             let o = std::mem::replace(self, Encoding::with_capacity(0));
-            self.overflowing.push(o);
+            if max_overflowing != Some(0) {
+                self.overflowing.push(o);
+            }
             return;
         }
 
@@ -321,40 +588,28 @@ impl Encoding {
         // When truncating, we lose the `sequence_ranges` information.
         self.sequence_ranges.clear();
 
+        // The windows start from the truncated side, each one starting `offset` tokens after
+        // the previous one, until one reaches the end of the encoding
         let offset = max_len - stride;
-        let mut end = false;
-        let parts_ranges: Vec<(usize, usize)> = match direction {
-            TruncationDirection::Right => (0..encoding_len)
-                .step_by(offset)
-                .filter_map(|start| {
-                    if !end {
-                        let stop = std::cmp::min(start + max_len, encoding_len);
-                        end = stop == encoding_len;
-                        Some((start, stop))
-                    } else {
-                        None
-                    }
-                })
-                .collect(),
-            TruncationDirection::Left => (0..encoding_len)
-                .rev()
-                .step_by(offset)
-                .filter_map(|stop| {
-                    let stop = stop + 1;
-                    let start = stop.saturating_sub(max_len);
-                    if start < stop && !end {
-                        end = start == 0;
-                        Some((start, stop))
-                    } else {
-                        None
-                    }
-                })
-                .collect(),
-        };
+        let mut next = Some(match direction {
+            TruncationDirection::Right => 0,
+            TruncationDirection::Left => encoding_len,
+        });
+        let mut parts_ranges = std::iter::from_fn(move || {
+            let bound = next?;
+            let (start, stop) = match direction {
+                TruncationDirection::Right => (bound, std::cmp::min(bound + max_len, encoding_len)),
+                TruncationDirection::Left => (bound.saturating_sub(max_len), bound),
+            };
+            next = match direction {
+                TruncationDirection::Right if stop < encoding_len => Some(bound + offset),
+                TruncationDirection::Left if start > 0 => Some(bound - offset),
+                _ => None,
+            };
+            Some((start, stop))
+        });
 
-        let mut i = 0;
-        let (start, stop) = parts_ranges[i];
-        let mut new_encoding = Encoding {
+        let part = |(start, stop): (usize, usize)| Encoding {
             ids: self.ids[start..stop].to_vec(),
             type_ids: self.type_ids[start..stop].to_vec(),
             tokens: self.tokens[start..stop].to_vec(),
@@ -364,26 +619,17 @@ impl Encoding {
             attention_mask: self.attention_mask[start..stop].to_vec(),
             overflowing: vec![],
             sequence_ranges: AHashMap::new(),
+            word_metadata: self
+                .word_metadata
+                .get(start..stop)
+                .map_or(vec![], <[_]>::to_vec),
         };
-
-        loop {
-            if i == parts_ranges.len() - 1 {
-                break;
-            }
-            i += 1;
-            let (start, stop) = parts_ranges[i];
-            new_encoding.overflowing.push(Encoding {
-                ids: self.ids[start..stop].to_vec(),
-                type_ids: self.type_ids[start..stop].to_vec(),
-                tokens: self.tokens[start..stop].to_vec(),
-                words: self.words[start..stop].to_vec(),
-                offsets: self.offsets[start..stop].to_vec(),
-                special_tokens_mask: self.special_tokens_mask[start..stop].to_vec(),
-                attention_mask: self.attention_mask[start..stop].to_vec(),
-                overflowing: vec![],
-                sequence_ranges: AHashMap::new(),
-            });
-        }
+        // There is always a first window, as the encoding is not empty
+        let mut new_encoding = part(parts_ranges.next().unwrap());
+        new_encoding.overflowing = parts_ranges
+            .take(max_overflowing.unwrap_or(usize::MAX))
+            .map(part)
+            .collect();
         *self = new_encoding;
     }
 
@@ -459,6 +705,13 @@ impl Encoding {
         );
         self.special_tokens_mask.extend(pair.special_tokens_mask);
         self.attention_mask.extend(pair.attention_mask);
+        // The tokens without metadata get `None` when only one of the encodings has some
+        if !self.word_metadata.is_empty() || !pair.word_metadata.is_empty() {
+            let len = self.len();
+            self.word_metadata.resize(original_self_len, None);
+            self.word_metadata.extend(pair.word_metadata);
+            self.word_metadata.resize(len, None);
+        }
         self.overflowing = overflowings;
     }
 
@@ -512,6 +765,12 @@ impl Encoding {
                     .map(|_| (0, 0))
                     .chain(self.offsets.drain(..))
                     .collect();
+                if !self.word_metadata.is_empty() {
+                    self.word_metadata = (0..pad_length)
+                        .map(|_| None)
+                        .chain(self.word_metadata.drain(..))
+                        .collect();
+                }
                 self.sequence_ranges
                     .iter_mut()
                     .for_each(|(_seq_id, range)| {
@@ -527,6 +786,9 @@ impl Encoding {
                 self.attention_mask.extend((0..pad_length).map(|_| 0));
                 self.special_tokens_mask.extend((0..pad_length).map(|_| 1));
                 self.offsets.extend((0..pad_length).map(|_| (0, 0)));
+                if !self.word_metadata.is_empty() {
+                    self.word_metadata.extend((0..pad_length).map(|_| None));
+                }
             }
         }
     }
@@ -689,6 +951,198 @@ mod tests {
         );
     }
 
+    #[test]
+    fn into_windows() {
+        let mut enc = Encoding {
+            ids: vec![1, 2, 3, 4, 5],
+            type_ids: vec![0, 0, 0, 0, 0],
+            tokens: vec![
+                String::from("42"),
+                String::from("is"),
+                String::from("the"),
+                String::from("answer"),
+                String::from("!"),
+            ],
+            words: vec![Some(0), Some(1), Some(2), Some(3), Some(4)],
+            offsets: vec![(0, 2), (2, 4), (4, 7), (7, 13), (13, 14)],
+            special_tokens_mask: vec![0, 0, 0, 0, 0],
+            attention_mask: vec![1, 1, 1, 1, 1],
+            ..Default::default()
+        };
+        enc.truncate(2, 1, TruncationDirection::Right);
+
+        let windows = enc.into_windows();
+        assert_eq!(
+            windows.iter().map(|w| w.get_ids()).collect::<Vec<_>>(),
+            vec![&[1, 2][..], &[2, 3], &[3, 4], &[4, 5]]
+        );
+        // Offsets keep pointing into the original input
+        assert_eq!(windows[3].get_offsets(), &[(7, 13), (13, 14)]);
+        assert!(windows.iter().all(|w| w.get_overflowing().is_empty()));
+    }
+
+    #[test]
+    fn whole_word_mask() {
+        // [CLS] my name is john ny doe [SEP] ok [SEP] [PAD]
+        let enc = Encoding {
+            ids: vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 7, 9],
+            type_ids: vec![0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 0],
+            words: vec![
+                None,
+                Some(0),
+                Some(1),
+                Some(2),
+                Some(3),
+                Some(3),
+                Some(4),
+                None,
+                Some(0),
+                None,
+                None,
+            ],
+            special_tokens_mask: vec![1, 0, 0, 0, 0, 0, 0, 1, 0, 1, 1],
+            attention_mask: vec![1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 0],
+            sequence_ranges: AHashMap::from_iter(vec![(0, 1..7), (1, 8..9)]),
+            ..Default::default()
+        };
+
+        assert_eq!(enc.whole_word_mask(0.0, 0), vec![0; 11]);
+        assert_eq!(
+            enc.whole_word_mask(1.5, 0),
+            vec![0, 1, 1, 1, 1, 1, 1, 0, 1, 0, 0]
+        );
+        for seed in 0..20 {
+            // 2 out of the 6 words, "john" and "ny" going together
+            let mask = enc.whole_word_mask(0.3, seed);
+            assert_eq!(mask, enc.whole_word_mask(0.3, seed));
+            assert_eq!(mask[4], mask[5]);
+            assert_eq!(mask.iter().sum::<u32>(), 2 + mask[4]);
+            assert_eq!([mask[0], mask[7], mask[9], mask[10]], [0; 4]);
+            // At least one word
+            let mask = enc.whole_word_mask(0.01, seed);
+            assert_eq!(mask.iter().sum::<u32>(), 1 + mask[4]);
+        }
+
+        // The same words on every platform
+        assert_eq!(
+            enc.whole_word_mask(0.3, 42),
+            vec![0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 0]
+        );
+
+        let batch = Encoding::whole_word_mask_batch(&[enc.clone(), enc.clone()], 0.3, 7);
+        assert_eq!(
+            batch,
+            vec![enc.whole_word_mask(0.3, 7), enc.whole_word_mask(0.3, 8)]
+        );
+    }
+
+    #[test]
+    fn redact() {
+        let tokens = |tokens: &[&str]| tokens.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+        // [CLS] my name is johnny doe. [SEP] ok [SEP]
+        let mut enc = Encoding {
+            ids: vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 8],
+            type_ids: vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1],
+            tokens: tokens(&[
+                "[CLS]", "my", "name", "is", "john", "ny", "doe", ".", "[SEP]", "ok", "[SEP]",
+            ]),
+            words: vec![
+                None,
+                Some(0),
+                Some(1),
+                Some(2),
+                Some(3),
+                Some(3),
+                Some(4),
+                Some(5),
+                None,
+                Some(0),
+                None,
+            ],
+            offsets: vec![
+                (0, 0),
+                (0, 2),
+                (3, 7),
+                (8, 10),
+                (11, 15),
+                (15, 17),
+                (18, 21),
+                (21, 22),
+                (0, 0),
+                (0, 2),
+                (0, 0),
+            ],
+            special_tokens_mask: vec![1, 0, 0, 0, 0, 0, 0, 0, 1, 0, 1],
+            attention_mask: vec![1; 11],
+            sequence_ranges: AHashMap::from_iter(vec![(0, 1..8), (1, 9..10)]),
+            ..Default::default()
+        };
+        let placeholder = Encoding::from_tokens(
+            vec![
+                Token::new(90, "[".into(), (0, 1)),
+                Token::new(91, "PII]".into(), (1, 5)),
+            ],
+            0,
+        );
+        // The two last spans overlap "ny", and get a single placeholder
+        enc.redact(&[(14, 16), (0, 2), (16, 21)], &placeholder, 0);
+
+        assert_eq!(
+            enc,
+            Encoding {
+                ids: vec![0, 90, 91, 2, 3, 90, 91, 7, 8, 9, 8],
+                type_ids: vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1],
+                tokens: tokens(&[
+                    "[CLS]", "[", "PII]", "name", "is", "[", "PII]", ".", "[SEP]", "ok", "[SEP]",
+                ]),
+                words: vec![
+                    None,
+                    Some(0),
+                    Some(0),
+                    Some(1),
+                    Some(2),
+                    Some(3),
+                    Some(3),
+                    Some(4),
+                    None,
+                    Some(0),
+                    None,
+                ],
+                offsets: vec![
+                    (0, 0),
+                    (0, 2),
+                    (0, 2),
+                    (3, 7),
+                    (8, 10),
+                    (11, 21),
+                    (11, 21),
+                    (21, 22),
+                    (0, 0),
+                    (0, 2),
+                    (0, 0),
+                ],
+                special_tokens_mask: vec![1, 0, 0, 0, 0, 0, 0, 0, 1, 0, 1],
+                attention_mask: vec![1; 11],
+                sequence_ranges: AHashMap::from_iter(vec![(0, 1..8), (1, 9..10)]),
+                ..Default::default()
+            }
+        );
+
+        // The overflowing encodings get their own placeholders
+        let mut enc = Encoding::from_tokens(
+            vec![
+                Token::new(1, "hi".into(), (0, 2)),
+                Token::new(2, "john".into(), (3, 7)),
+            ],
+            0,
+        );
+        enc.truncate(1, 0, TruncationDirection::Right);
+        enc.redact(&[(3, 7)], &placeholder, 0);
+        assert_eq!(enc.get_ids(), [1]);
+        assert_eq!(enc.get_overflowing()[0].get_ids(), [90, 91]);
+        assert_eq!(enc.get_overflowing()[0].get_offsets(), [(3, 7), (3, 7)]);
+    }
+
     #[test]
     fn truncate_overflow_with_stride() {
         let mut enc = Encoding {
@@ -878,6 +1332,141 @@ mod tests {
         assert_eq!(encoding.char_to_word(23, 0), Some(3));
         assert_eq!(encoding.char_to_word(2, 1), Some(0));
         assert_eq!(encoding.char_to_word(9, 1), Some(2));
+
+        assert_eq!(encoding.span_to_tokens((0, 2), 0), Some((0, 1)));
+        assert_eq!(encoding.span_to_tokens((1, 8), 0), Some((0, 3)));
+        assert_eq!(encoding.span_to_tokens((5, 7), 0), None);
+        assert_eq!(encoding.span_to_tokens((3, 3), 0), None);
+        assert_eq!(encoding.span_to_tokens((0, 24), 0), Some((0, 7)));
+        assert_eq!(encoding.span_to_tokens((4, 11), 1), Some((8, 10)));
+    }
+
+    #[test]
+    fn mappings_with_normalizer() {
+        use crate::models::bpe::BPE;
+        use crate::normalizers::{Sequence, Strip, NFKC};
+        use crate::pre_tokenizers::metaspace::Metaspace;
+        use crate::Tokenizer;
+
+        let vocab: AHashMap<_, _> = ["▁", "f", "i", "x", "h"]
+            .iter()
+            .enumerate()
+            .map(|(i, t)| (t.to_string(), i as u32))
+            .collect();
+        let model = BPE::builder()
+            .vocab_and_merges(vocab, vec![])
+            .build()
+            .unwrap();
+        let mut tokenizer = Tokenizer::new(model);
+        tokenizer
+            .with_normalizer(Some(Sequence::new(vec![
+                Strip::new(true, true).into(),
+                NFKC.into(),
+            ])))
+            .with_pre_tokenizer(Some(Metaspace::default()));
+
+        // The spaces are stripped, "▁" is inserted before "ﬁ", which becomes "fi"
+        let encoding = tokenizer.encode_char_offsets("  ﬁx hi ", false).unwrap();
+        assert_eq!(encoding.get_tokens(), ["▁", "f", "i", "x", "▁", "h", "i"]);
+
+        assert_eq!(encoding.char_to_token(0, 0), None);
+        assert_eq!(encoding.char_to_token(2, 0), Some(0));
+        assert_eq!(encoding.char_to_token(3, 0), Some(3));
+        assert_eq!(encoding.char_to_token(7, 0), None);
+
+        assert_eq!(encoding.token_to_chars(0), Some((0, (2, 3))));
+        assert_eq!(encoding.token_to_chars(2), Some((0, (2, 3))));
+        assert_eq!(encoding.token_to_chars(4), Some((0, (4, 5))));
+
+        assert_eq!(encoding.span_to_tokens((0, 2), 0), None);
+        assert_eq!(encoding.span_to_tokens((0, 3), 0), Some((0, 3)));
+        assert_eq!(encoding.span_to_tokens((3, 6), 0), Some((3, 6)));
+        assert_eq!(encoding.span_to_tokens((6, 8), 0), Some((6, 7)));
+        assert_eq!(encoding.span_to_tokens((0, 8), 0), Some((0, 7)));
+    }
+
+    #[test]
+    fn word_metadata() {
+        use crate::models::bpe::BPE;
+        use crate::processors::bert::BertProcessing;
+        use crate::{OffsetType, PaddingParams, PaddingStrategy, Tokenizer, TruncationParams};
+
+        let vocab: AHashMap<_, _> = ["h", "e", "y", "o", "k"]
+            .iter()
+            .enumerate()
+            .map(|(i, t)| (t.to_string(), i as u32))
+            .collect();
+        let model = BPE::builder()
+            .vocab_and_merges(vocab, vec![])
+            .build()
+            .unwrap();
+        let mut tokenizer = Tokenizer::new(model);
+        tokenizer
+            .with_post_processor(Some(BertProcessing::default()))
+            .with_truncation(Some(TruncationParams {
+                max_length: 5,
+                ..Default::default()
+            }))
+            .unwrap()
+            .with_padding(Some(PaddingParams {
+                strategy: PaddingStrategy::Fixed(6),
+                ..Default::default()
+            }));
+
+        let timestamps = vec![vec![vec![0.0, 0.3], vec![0.5, 0.6]]];
+        let encoding = tokenizer
+            .encode_with_word_metadata(&["hey", "ok"][..], &timestamps, true, OffsetType::Byte)
+            .unwrap();
+        let hey = Some(vec![0.0, 0.3]);
+        let ok = Some(vec![0.5, 0.6]);
+        assert_eq!(
+            encoding.get_tokens(),
+            ["[CLS]", "h", "e", "y", "[SEP]", "[PAD]"]
+        );
+        assert_eq!(
+            encoding.get_word_metadata(),
+            [None, hey.clone(), hey.clone(), hey, None, None]
+        );
+        let overflowing = &encoding.get_overflowing()[0];
+        assert_eq!(
+            overflowing.get_tokens(),
+            ["[CLS]", "o", "k", "[SEP]", "[PAD]", "[PAD]"]
+        );
+        assert_eq!(
+            overflowing.get_word_metadata(),
+            [None, ok.clone(), ok, None, None, None]
+        );
+
+        // The metadata need a pre-tokenized input, with the metadata of each word
+        assert!(tokenizer
+            .encode_with_word_metadata("hey ok", &timestamps, true, OffsetType::Byte)
+            .is_err());
+        assert!(tokenizer
+            .encode_with_word_metadata(&["hey"][..], &timestamps, true, OffsetType::Byte)
+            .is_err());
+        assert!(tokenizer
+            .encode_with_word_metadata(&["hey", "ok"][..], &[], true, OffsetType::Byte)
+            .is_err());
+
+        // Without metadata, nothing changes
+        let encoding = tokenizer.encode(&["hey", "ok"][..], true).unwrap();
+        assert!(encoding.get_word_metadata().is_empty());
+        assert!(!serde_json::to_string(&encoding)
+            .unwrap()
+            .contains("word_metadata"));
+    }
+
+    #[test]
+    fn merge_word_metadata() {
+        let mut a = Encoding::from_tokens(vec![Token::new(1, "hi".into(), (0, 2))], 0);
+        a.get_word_ids_mut()[0] = Some(0);
+        a.set_word_metadata(&[vec![vec![1.0]]]);
+        // The tokens of the encodings without metadata get `None`
+        let b = Encoding::from_tokens(vec![Token::new(2, "[SEP]".into(), (0, 0))], 0);
+        let mut merged = b.clone();
+        merged.merge_with(a, false);
+        merged.merge_with(b, false);
+        assert_eq!(merged.get_word_metadata(), [None, Some(vec![1.0]), None]);
     }
 
     #[test]
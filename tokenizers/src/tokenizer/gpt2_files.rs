@@ -0,0 +1,367 @@
+use super::Tokenizer;
+use crate::decoders::DecoderWrapper;
+use crate::models::bpe::BPE;
+use crate::models::files::ModelFile;
+use crate::models::ModelWrapper;
+use crate::pre_tokenizers::byte_level::BYTES_CHAR;
+use crate::pre_tokenizers::PreTokenizerWrapper;
+use crate::tokenizer::Result;
+use ahash::{AHashMap, AHashSet};
+use std::collections::BTreeSet;
+use std::io::Write;
+
+/// How the tokens of the model are written
+#[derive(Clone, Copy, Debug)]
+enum TokensFormat {
+    /// With the chars of `ByteLevel`, like the GPT-2 files
+    ByteLevel,
+    /// As text, with the given char replacing the spaces, if any
+    Text { replacement: Option<char> },
+}
+
+fn pre_tokenizers<'a>(pretok: &'a PreTokenizerWrapper, all: &mut Vec<&'a PreTokenizerWrapper>) {
+    match pretok {
+        PreTokenizerWrapper::Sequence(seq) => {
+            seq.as_ref().iter().for_each(|p| pre_tokenizers(p, all))
+        }
+        pretok => all.push(pretok),
+    }
+}
+
+fn decoders<'a>(decoder: &'a DecoderWrapper, all: &mut Vec<&'a DecoderWrapper>) {
+    match decoder {
+        DecoderWrapper::Sequence(seq) => seq.get_decoders().iter().for_each(|d| decoders(d, all)),
+        decoder => all.push(decoder),
+    }
+}
+
+impl TokensFormat {
+    fn of(tokenizer: &Tokenizer) -> Self {
+        let mut pretoks = vec![];
+        if let Some(pretok) = tokenizer.get_pre_tokenizer() {
+            pre_tokenizers(pretok, &mut pretoks);
+        }
+        let mut decs = vec![];
+        if let Some(decoder) = tokenizer.get_decoder() {
+            decoders(decoder, &mut decs);
+        }
+
+        let byte_level = pretoks
+            .iter()
+            .any(|p| matches!(p, PreTokenizerWrapper::ByteLevel(_)))
+            || decs
+                .iter()
+                .any(|d| matches!(d, DecoderWrapper::ByteLevel(_)));
+        if byte_level {
+            return Self::ByteLevel;
+        }
+        let replacement = pretoks
+            .iter()
+            .find_map(|p| match p {
+                PreTokenizerWrapper::Metaspace(m) => Some(m.get_replacement()),
+                _ => None,
+            })
+            .or_else(|| {
+                decs.iter().find_map(|d| match d {
+                    DecoderWrapper::Metaspace(m) => Some(m.get_replacement()),
+                    _ => None,
+                })
+            });
+        Self::Text { replacement }
+    }
+
+    /// The token written with the chars of `ByteLevel`
+    fn byte_level(&self, token: &str) -> String {
+        match self {
+            Self::ByteLevel => token.to_owned(),
+            Self::Text { replacement } => token
+                .chars()
+                .map(|c| if Some(c) == *replacement { ' ' } else { c })
+                .collect::<String>()
+                .bytes()
+                .map(|b| BYTES_CHAR[&b])
+                .collect(),
+        }
+    }
+}
+
+/// The byte of a token like `<0x41>`, used by the `byte_fallback` of `BPE`
+fn fallback_byte(token: &str) -> Option<u8> {
+    if token.len() == 6 && token.starts_with("<0x") && token.ends_with('>') {
+        u8::from_str_radix(&token[3..5], 16).ok()
+    } else {
+        None
+    }
+}
+
+/// The tokens of the GPT-2 files, with the merges needed before the ones of the model
+struct Export {
+    /// The token written for each id, including the ones written as another id
+    tokens: AHashMap<u32, String>,
+    /// The ids written in `vocab.json`, in order
+    ids: Vec<u32>,
+    /// The merges building the multi-bytes chars, which are single symbols for the model
+    char_merges: Vec<(String, String)>,
+}
+
+impl Export {
+    fn new(tokenizer: &Tokenizer, bpe: &BPE, format: TokensFormat) -> Result<Self> {
+        let vocab_r = bpe.owned_vocab_r();
+        let mut raw: AHashMap<u32, &str> =
+            vocab_r.iter().map(|(id, t)| (*id, t.as_str())).collect();
+        let added = tokenizer.get_added_tokens_decoder();
+        for (id, token) in &added {
+            raw.entry(*id).or_insert(token.content.as_str());
+        }
+        let is_raw = |token: &str| {
+            added.values().any(|t| t.content == token) || bpe.unk_token.as_deref() == Some(token)
+        };
+        let mut ids: Vec<u32> = raw.keys().copied().collect();
+        ids.sort_unstable();
+
+        let mut export = Self {
+            tokens: AHashMap::with_capacity(ids.len()),
+            ids: Vec::with_capacity(ids.len()),
+            char_merges: vec![],
+        };
+        let mut written: AHashMap<String, u32> = AHashMap::with_capacity(ids.len());
+        let text = matches!(format, TokensFormat::Text { .. });
+
+        // The bytes of the fallback are only written when no other token has the same chars
+        let (fallbacks, regular): (Vec<u32>, Vec<u32>) = ids.iter().partition(|id| {
+            text && bpe.byte_fallback && !is_raw(raw[id]) && fallback_byte(raw[id]).is_some()
+        });
+        for id in regular {
+            let token = raw[&id];
+            let exported = if is_raw(token) {
+                token.to_owned()
+            } else {
+                format.byte_level(token)
+            };
+            if let Some(other) = written.insert(exported.clone(), id) {
+                return Err(format!(
+                    "The tokens {:?} and {:?} would both be written {:?} in the GPT-2 files",
+                    raw[&other], token, exported
+                )
+                .into());
+            }
+            export.tokens.insert(id, exported);
+        }
+        for id in fallbacks {
+            let exported = BYTES_CHAR[&fallback_byte(raw[&id]).unwrap()].to_string();
+            written.entry(exported.clone()).or_insert(id);
+            export.tokens.insert(id, exported);
+        }
+
+        if text {
+            // Each byte must be a token, and each multi-bytes char of the model must be
+            // built from its bytes, with new tokens after the existing ones
+            let mut next_id = ids.last().map_or(0, |id| id + 1);
+            let mut add = |export: &mut Self, token: String| {
+                written.entry(token.clone()).or_insert_with(|| {
+                    export.tokens.insert(next_id, token);
+                    next_id += 1;
+                    next_id - 1
+                });
+            };
+            for b in 0..=255u8 {
+                add(&mut export, BYTES_CHAR[&b].to_string());
+            }
+            let chars: BTreeSet<char> = vocab_r
+                .values()
+                .filter(|token| !is_raw(token) && fallback_byte(token).is_none())
+                .flat_map(|token| token.chars())
+                .filter(|c| c.len_utf8() > 1)
+                .collect();
+            let mut merges = AHashSet::new();
+            for c in chars {
+                let exported = format.byte_level(&c.to_string());
+                let chars: Vec<char> = exported.chars().collect();
+                for end in 2..=chars.len() {
+                    let merge = (
+                        chars[..end - 1].iter().collect::<String>(),
+                        chars[end - 1].to_string(),
+                    );
+                    add(&mut export, chars[..end].iter().collect());
+                    if merges.insert(merge.clone()) {
+                        export.char_merges.push(merge);
+                    }
+                }
+            }
+        }
+
+        export.ids = written.into_values().collect();
+        export.ids.sort_unstable();
+        Ok(export)
+    }
+}
+
+impl Tokenizer {
+    /// Write the `vocab.json` and `merges.txt` files used by GPT-2, still required by some
+    /// runtimes, for a tokenizer using a `BPE` without `continuing_subword_prefix` nor
+    /// `end_of_word_suffix`. The files are written as they go.
+    ///
+    /// The tokens of a tokenizer using `ByteLevel` are written as they are. The other ones
+    /// are converted to the chars of `ByteLevel` from their UTF-8 bytes, with the spaces
+    /// instead of the replacement char of a `Metaspace`:
+    ///   - The tokens of the `byte_fallback`, like `<0x41>`, become the char of their byte,
+    ///     unless another token already uses it. These ids are then left out.
+    ///   - Each multi-bytes char is built from its bytes by new merges coming before the
+    ///     ones of the model. The missing bytes and the tokens of these merges get new ids,
+    ///     after the existing ones.
+    ///
+    /// The added tokens and the `unk_token` are written as they are, with their ids.
+    pub fn write_gpt2_files<V: Write, M: Write>(&self, mut vocab: V, mut merges: M) -> Result<()> {
+        let bpe = match self.get_model() {
+            ModelWrapper::BPE(bpe) => bpe,
+            _ => return Err("Only the tokenizers using a BPE model have GPT-2 files".into()),
+        };
+        if bpe.continuing_subword_prefix.is_some() || bpe.end_of_word_suffix.is_some() {
+            return Err(
+                "The GPT-2 files don't support the `continuing_subword_prefix` nor the \
+                        `end_of_word_suffix` of BPE"
+                    .into(),
+            );
+        }
+        let format = TokensFormat::of(self);
+        let export = Export::new(self, bpe, format)?;
+
+        vocab.write_all(b"{")?;
+        for (i, id) in export.ids.iter().enumerate() {
+            if i > 0 {
+                vocab.write_all(b",")?;
+            }
+            serde_json::to_writer(&mut vocab, &export.tokens[id])?;
+            write!(vocab, ":{id}")?;
+        }
+        vocab.write_all(b"}")?;
+        vocab.flush()?;
+
+        merges.write_all(b"#version: 0.2\n")?;
+        for (a, b) in &export.char_merges {
+            writeln!(merges, "{a} {b}")?;
+        }
+        let model_merges = bpe.owned_merges();
+        let mut model_merges: Vec<_> = model_merges.iter().collect();
+        model_merges.sort_unstable_by_key(|(_, (rank, _))| *rank);
+        for ((a, b), _) in model_merges {
+            writeln!(merges, "{} {}", export.tokens[a], export.tokens[b])?;
+        }
+        merges.flush()?;
+        Ok(())
+    }
+
+    /// The content of the files written by [`write_gpt2_files`](Self::write_gpt2_files),
+    /// named `vocab.json` and `merges.txt`
+    pub fn gpt2_files(&self) -> Result<Vec<ModelFile>> {
+        let (mut vocab, mut merges) = (vec![], vec![]);
+        self.write_gpt2_files(&mut vocab, &mut merges)?;
+        Ok(vec![
+            ModelFile::new("vocab.json", vocab),
+            ModelFile::new("merges.txt", merges),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoders::byte_level::ByteLevel as ByteLevelDecoder;
+    use crate::models::bpe::Vocab;
+    use crate::pre_tokenizers::byte_level::ByteLevel;
+    use crate::pre_tokenizers::metaspace::{Metaspace, PrependScheme};
+    use crate::AddedToken;
+
+    fn vocab(tokens: &[&str]) -> Vocab {
+        tokens
+            .iter()
+            .enumerate()
+            .map(|(id, token)| (token.to_string(), id as u32))
+            .collect()
+    }
+
+    fn merges(merges: &[(&str, &str)]) -> Vec<(String, String)> {
+        merges
+            .iter()
+            .map(|(a, b)| (a.to_string(), b.to_string()))
+            .collect()
+    }
+
+    /// The tokenizer reading the GPT-2 files
+    fn gpt2(files: &[ModelFile]) -> Tokenizer {
+        let (vocab, merges) = BPE::read_bytes(&files[0].content, &files[1].content).unwrap();
+        let mut tokenizer = Tokenizer::new(BPE::new(vocab, merges));
+        tokenizer
+            .with_pre_tokenizer(Some(ByteLevel::default()))
+            .with_decoder(Some(ByteLevelDecoder::default()));
+        tokenizer
+    }
+
+    #[test]
+    fn byte_level() {
+        let bpe = BPE::new(
+            vocab(&["Ġ", "h", "i", "hi", "Ġhi"]),
+            merges(&[("h", "i"), ("Ġ", "hi")]),
+        );
+        let mut tokenizer = Tokenizer::new(bpe.clone());
+        tokenizer.with_pre_tokenizer(Some(ByteLevel::default()));
+        tokenizer.add_special_tokens(&[AddedToken::from("<|endoftext|>", true)]);
+
+        let files = tokenizer.gpt2_files().unwrap();
+        assert_eq!(files[1], bpe.files().unwrap()[1]);
+        assert_eq!(
+            String::from_utf8(files[0].content.clone()).unwrap(),
+            r#"{"Ġ":0,"h":1,"i":2,"hi":3,"Ġhi":4,"<|endoftext|>":5}"#
+        );
+    }
+
+    #[test]
+    fn text() {
+        let bpe = BPE::builder()
+            .vocab_and_merges(
+                vocab(&["<unk>", "h", "é", "▁", "hé", "▁h", "<0x41>", "<0x68>"]),
+                merges(&[("h", "é"), ("▁", "h")]),
+            )
+            .unk_token("<unk>".into())
+            .byte_fallback(true)
+            .build()
+            .unwrap();
+        let mut tokenizer = Tokenizer::new(bpe);
+        tokenizer.with_pre_tokenizer(Some(Metaspace::new('▁', PrependScheme::Always, true)));
+        tokenizer.add_special_tokens(&[AddedToken::from("<unk>", true)]);
+
+        let files = tokenizer.gpt2_files().unwrap();
+        assert_eq!(
+            String::from_utf8(files[1].content.clone()).unwrap(),
+            "#version: 0.2\nÃ ©\nh Ã©\nĠ h\n"
+        );
+        let exported = gpt2(&files);
+        assert_eq!(exported.token_to_id("<unk>"), Some(0));
+        assert_eq!(exported.token_to_id("A"), Some(6));
+        // `h` already has its token
+        assert_eq!(exported.token_to_id("h"), Some(1));
+        assert_eq!(exported.id_to_token(7), None);
+
+        for text in ["hé", "hé hé h", "A", "hA"] {
+            let ids = tokenizer.encode(text, false).unwrap().get_ids().to_vec();
+            let encoding = exported.encode(text, false).unwrap();
+            assert_eq!(encoding.get_ids(), ids, "{}", text);
+            assert_eq!(exported.decode(&ids, false).unwrap(), format!(" {text}"));
+        }
+        // The bytes missing from the model get new ids
+        assert!(exported.encode("z", false).unwrap().get_ids()[1] > 7);
+    }
+
+    #[test]
+    fn invalid() {
+        let mut tokenizer = Tokenizer::new(BPE::new(vocab(&["▁", " "]), vec![]));
+        tokenizer.with_pre_tokenizer(Some(Metaspace::default()));
+        assert!(tokenizer.gpt2_files().is_err());
+
+        let bpe = BPE::builder()
+            .continuing_subword_prefix("##".into())
+            .build()
+            .unwrap();
+        assert!(Tokenizer::new(bpe).gpt2_files().is_err());
+    }
+}
@@ -0,0 +1,483 @@
+use super::shared_prefix::{same_word, shift, slice};
+use super::{
+    Decoder, Encoding, InputSequence, Model, Normalizer, OffsetType, Offsets, PostProcessor,
+    PreTokenizer, Result, TokenizerImpl,
+};
+use std::ops::Range;
+
+/// The changes made to an [`Encoding`] by an edit of its text.
+/// See [`TokenizerImpl::encode_edit`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct EncodingPatch {
+    /// The range of the tokens replaced in the previous encoding
+    pub replaced: Range<usize>,
+    /// The range of the tokens replacing them in the new encoding
+    pub inserted: Range<usize>,
+    /// The encoding of the edited text
+    pub encoding: Encoding,
+}
+
+/// The tokens of a word of an encoding, and the span of the text they cover
+struct Word {
+    tokens: Range<usize>,
+    offsets: Offsets,
+}
+
+/// Split the encoding in words, or return `None` if some of its tokens have no word
+fn words(encoding: &Encoding) -> Option<Vec<Word>> {
+    let mut words: Vec<Word> = vec![];
+    let ids = encoding.get_word_ids();
+    for (i, (word, offsets)) in ids.iter().zip(encoding.get_offsets()).enumerate() {
+        word.as_ref()?;
+        match words.last_mut() {
+            Some(last) if ids[last.tokens.start] == *word => {
+                last.tokens.end = i + 1;
+                last.offsets.1 = last.offsets.1.max(offsets.1);
+            }
+            _ => words.push(Word {
+                tokens: i..i + 1,
+                offsets: *offsets,
+            }),
+        }
+    }
+    Some(words)
+}
+
+/// The length of the text, in the unit of the offsets
+fn text_len(text: &str, offsets_type: OffsetType) -> usize {
+    match offsets_type {
+        OffsetType::Char => text.chars().count(),
+        _ => text.len(),
+    }
+}
+
+/// The byte of the text at the given offset, if valid
+fn byte_offset(text: &str, offset: usize, offsets_type: OffsetType) -> Option<usize> {
+    match offsets_type {
+        OffsetType::Char => text
+            .char_indices()
+            .map(|(b, _)| b)
+            .chain(std::iter::once(text.len()))
+            .nth(offset),
+        _ => Some(offset).filter(|b| text.is_char_boundary(*b)),
+    }
+}
+
+impl<M, N, PT, PP, D> TokenizerImpl<M, N, PT, PP, D>
+where
+    M: Model,
+    N: Normalizer,
+    PT: PreTokenizer,
+    PP: PostProcessor,
+    D: Decoder,
+{
+    /// Encode the text after an edit replacing its `edit` range with `replacement`, reusing
+    /// its previous `encoding`, made by [`encode`](Self::encode) without special tokens, to
+    /// only tokenize again the words around the edit. This suits the editors, re-tokenizing
+    /// a document for each keystroke.
+    ///
+    /// The words touched by the edit are tokenized again with the word before and after
+    /// them, which must keep the same tokens to prove that the edit doesn't reach further,
+    /// and no added token may cross the edges of the tokenized part, like with
+    /// [`encode_batch_with_prefix`](Self::encode_batch_with_prefix). Otherwise, more words
+    /// get tokenized again, up to the whole text. The whole text is also encoded again when
+    /// the tokenizer truncates or pads the encodings.
+    ///
+    /// The `edit` range is in bytes, like the offsets of the encoding. The returned patch
+    /// has the encoding of the edited text, along with the smallest range of tokens that
+    /// changed in the previous encoding, and their replacement.
+    /// ```
+    /// # use tokenizers::models::wordlevel::WordLevel;
+    /// # use tokenizers::pre_tokenizers::whitespace::Whitespace;
+    /// # use tokenizers::Tokenizer;
+    /// # let vocab = vec!["[UNK]", "hello", "big", "small", "world"]
+    /// #     .into_iter()
+    /// #     .enumerate()
+    /// #     .map(|(i, t)| (t.to_string(), i as u32))
+    /// #     .collect();
+    /// # let model = WordLevel::builder().vocab(vocab).unk_token("[UNK]".into()).build().unwrap();
+    /// # let mut tokenizer = Tokenizer::new(model);
+    /// # tokenizer.with_pre_tokenizer(Some(Whitespace {}));
+    /// let text = "hello big world";
+    /// let encoding = tokenizer.encode(text, false).unwrap();
+    /// let patch = tokenizer.encode_edit(text, &encoding, 6..9, "small").unwrap();
+    /// assert_eq!(patch.replaced, 1..2);
+    /// assert_eq!(patch.inserted, 1..2);
+    /// assert_eq!(patch.encoding.get_tokens(), ["hello", "small", "world"]);
+    /// assert_eq!(patch.encoding.get_offsets()[2], (12, 17));
+    /// ```
+    pub fn encode_edit(
+        &self,
+        text: &str,
+        encoding: &Encoding,
+        edit: Range<usize>,
+        replacement: &str,
+    ) -> Result<EncodingPatch> {
+        self.encode_edit_with_offsets(text, encoding, edit, replacement, OffsetType::Byte)
+    }
+
+    /// Encode the text after an edit, reusing its previous encoding, made by
+    /// [`encode_char_offsets`](Self::encode_char_offsets). The `edit` range is then in chars.
+    /// See [`encode_edit`](Self::encode_edit)
+    pub fn encode_edit_char_offsets(
+        &self,
+        text: &str,
+        encoding: &Encoding,
+        edit: Range<usize>,
+        replacement: &str,
+    ) -> Result<EncodingPatch> {
+        self.encode_edit_with_offsets(text, encoding, edit, replacement, OffsetType::Char)
+    }
+
+    fn encode_edit_with_offsets(
+        &self,
+        text: &str,
+        encoding: &Encoding,
+        edit: Range<usize>,
+        replacement: &str,
+        offsets_type: OffsetType,
+    ) -> Result<EncodingPatch> {
+        let range = match (
+            byte_offset(text, edit.start, offsets_type),
+            byte_offset(text, edit.end, offsets_type),
+        ) {
+            (Some(start), Some(end)) if start <= end => start..end,
+            _ => {
+                return Err(format!(
+                    "Invalid edit range {edit:?} for a text of length {}",
+                    text_len(text, offsets_type)
+                )
+                .into())
+            }
+        };
+        let edited = format!(
+            "{}{replacement}{}",
+            &text[..range.start],
+            &text[range.end..]
+        );
+        let delta = text_len(replacement, offsets_type) as isize - edit.len() as isize;
+
+        let spliced = match words(encoding) {
+            Some(words)
+                if !words.is_empty()
+                    && self.truncation.is_none()
+                    && self.padding.is_none()
+                    && encoding.get_overflowing().is_empty() =>
+            {
+                self.splice_edit(encoding, &words, &edit, delta, &edited, offsets_type)?
+            }
+            _ => None,
+        };
+        let new = self.post_process(
+            match spliced {
+                Some(spliced) => spliced,
+                None => {
+                    self.encode_single_sequence(InputSequence::from(edited), 0, offsets_type)?
+                }
+            },
+            None,
+            false,
+        )?;
+
+        // Only keep the tokens that changed
+        let (old_ids, new_ids) = (encoding.get_ids(), new.get_ids());
+        let (old_offsets, new_offsets) = (encoding.get_offsets(), new.get_offsets());
+        let prefix = (0..old_ids.len().min(new_ids.len()))
+            .take_while(|&i| old_ids[i] == new_ids[i] && old_offsets[i] == new_offsets[i])
+            .count();
+        let suffix = (1..=(old_ids.len().min(new_ids.len()) - prefix))
+            .take_while(|&i| {
+                let (old, new) = (old_ids.len() - i, new_ids.len() - i);
+                let (s, e) = old_offsets[old];
+                old_ids[old] == new_ids[new]
+                    && ((s as isize + delta) as usize, (e as isize + delta) as usize)
+                        == new_offsets[new]
+            })
+            .count();
+        Ok(EncodingPatch {
+            replaced: prefix..old_ids.len() - suffix,
+            inserted: prefix..new_ids.len() - suffix,
+            encoding: new,
+        })
+    }
+
+    /// Tokenize the words around the edit again, with more and more words around them,
+    /// until the first and last ones keep their tokens. Returns `None` if the whole text
+    /// must be encoded
+    fn splice_edit(
+        &self,
+        encoding: &Encoding,
+        words: &[Word],
+        edit: &Range<usize>,
+        delta: isize,
+        edited: &str,
+        offsets_type: OffsetType,
+    ) -> Result<Option<Encoding>> {
+        // The words touched by the edit, or the ones around it
+        let first = words
+            .iter()
+            .position(|w| w.offsets.1 >= edit.start)
+            .unwrap_or(words.len() - 1);
+        let last = words
+            .iter()
+            .rposition(|w| w.offsets.0 <= edit.end)
+            .unwrap_or(0);
+        let (first, last) = (first.min(last), first.max(last));
+        let word_id = |w: &Word| encoding.get_word_ids()[w.tokens.start].unwrap_or(0) as i64;
+
+        let mut context = 1;
+        loop {
+            let before = first.saturating_sub(context);
+            let after = (last + context).min(words.len() - 1);
+            let has_before = before < first;
+            let has_after = after > last;
+            if !has_before && !has_after {
+                return Ok(None);
+            }
+            // The re-tokenized part goes from the start of the text, or of the word before,
+            // to the end of the text, or of the word after
+            let from = if has_before {
+                words[before].offsets.0
+            } else {
+                0
+            };
+            let to = if has_after {
+                (words[after].offsets.1 as isize + delta) as usize
+            } else {
+                text_len(edited, offsets_type)
+            };
+            let (Some(from_b), Some(to_b)) = (
+                byte_offset(edited, from, offsets_type),
+                byte_offset(edited, to, offsets_type),
+            ) else {
+                return Ok(None);
+            };
+            if from_b > to_b {
+                return Ok(None);
+            }
+            // An added token crossing the edges of the part is only found in the whole text
+            let across = |cut| {
+                self.added_vocabulary
+                    .may_extract_across(self.normalizer.as_ref(), edited, cut)
+            };
+            if has_before && across(from_b) || has_after && across(to_b) {
+                if before == 0 && after == words.len() - 1 {
+                    return Ok(None);
+                }
+                context *= 2;
+                continue;
+            }
+            let mut part = self.encode_single_sequence(
+                InputSequence::from(&edited[from_b..to_b]),
+                0,
+                offsets_type,
+            )?;
+
+            let first_word = if has_before { before } else { 0 };
+            let last_word = if has_after { after } else { words.len() - 1 };
+            let old_range = words[first_word].tokens.start..words[last_word].tokens.end;
+            let shift_before = -(from as isize);
+            let shift_after = shift_before + delta;
+            // The anchors can't overlap
+            let n_anchors = [(has_before, before), (has_after, after)]
+                .iter()
+                .filter(|(has, _)| *has)
+                .map(|(_, word)| words[*word].tokens.len())
+                .sum::<usize>();
+            let before_ok = !has_before
+                || same_word(
+                    encoding,
+                    words[before].tokens.clone(),
+                    &part,
+                    0,
+                    shift_before,
+                );
+            let after_ok = !has_after
+                || part.len() >= n_anchors
+                    && same_word(
+                        encoding,
+                        words[after].tokens.clone(),
+                        &part,
+                        part.len() - words[after].tokens.len(),
+                        shift_after,
+                    );
+            if before_ok && after_ok {
+                // Without a word before, the part starts with the text and has the same words
+                if has_before {
+                    let part_word = part.get_word_ids()[0].unwrap_or(0) as i64;
+                    shift(
+                        &mut part,
+                        from as isize,
+                        word_id(&words[before]) - part_word,
+                    );
+                }
+
+                let mut spliced = slice(encoding, 0..old_range.start);
+                let mut tail = slice(encoding, old_range.end..encoding.len());
+                if let (Some(new_last), Some(old_last)) = (
+                    part.get_word_ids().last().copied().flatten(),
+                    encoding.get_word_ids()[old_range.end - 1],
+                ) {
+                    shift(&mut tail, delta, new_last as i64 - old_last as i64);
+                }
+                spliced.merge_with(part, false);
+                spliced.merge_with(tail, false);
+                return Ok(Some(spliced));
+            }
+            if before == 0 && after == words.len() - 1 {
+                return Ok(None);
+            }
+            context *= 2;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::bpe::BPE;
+    use crate::models::wordlevel::WordLevel;
+    use crate::pre_tokenizers::byte_level::ByteLevel;
+    use crate::pre_tokenizers::whitespace::Whitespace;
+    use crate::tokenizer::{AddedToken, Tokenizer};
+    use crate::TruncationParams;
+
+    fn bpe() -> Tokenizer {
+        let vocab = [
+            "a", "b", "c", "d", " ", "Ġ", "ab", "cd", "abcd", "Ġa", "Ġab", "Ġabcd", "Ġd",
+        ];
+        let vocab: ahash::AHashMap<_, _> = vocab
+            .iter()
+            .enumerate()
+            .map(|(i, t)| (t.to_string(), i as u32))
+            .collect();
+        let merges = vec![
+            ("Ġ".into(), "a".into()),
+            ("Ġ".into(), "d".into()),
+            ("Ġa".into(), "b".into()),
+            ("a".into(), "b".into()),
+            ("c".into(), "d".into()),
+            ("ab".into(), "cd".into()),
+            ("Ġab".into(), "cd".into()),
+        ];
+        let bpe = BPE::builder()
+            .vocab_and_merges(vocab, merges)
+            .build()
+            .unwrap();
+        let mut tokenizer = Tokenizer::new(bpe);
+        tokenizer.with_pre_tokenizer(Some(ByteLevel::new(false, false, true)));
+        tokenizer
+    }
+
+    /// Check every edit of the text against encoding the edited text from scratch
+    fn check_edits(tokenizer: &Tokenizer, text: &str, replacements: &[&str]) {
+        let encoding = tokenizer.encode(text, false).unwrap();
+        let char_encoding = tokenizer.encode_char_offsets(text, false).unwrap();
+        let boundaries: Vec<_> = text
+            .char_indices()
+            .map(|(b, _)| b)
+            .chain(std::iter::once(text.len()))
+            .collect();
+        for (i, start) in boundaries.iter().enumerate() {
+            for (j, end) in boundaries.iter().enumerate().skip(i) {
+                for replacement in replacements {
+                    let edited = format!("{}{replacement}{}", &text[..*start], &text[*end..]);
+                    let expected = tokenizer.encode(edited.as_str(), false).unwrap();
+                    let patch = tokenizer
+                        .encode_edit(text, &encoding, *start..*end, replacement)
+                        .unwrap();
+                    assert_eq!(patch.encoding, expected, "{text:?} => {edited:?}");
+                    let (old, new) = (encoding.get_ids(), expected.get_ids());
+                    assert_eq!(old[..patch.replaced.start], new[..patch.inserted.start]);
+                    assert_eq!(old[patch.replaced.end..], new[patch.inserted.end..]);
+
+                    let expected = tokenizer.encode_char_offsets(edited, false).unwrap();
+                    let patch = tokenizer
+                        .encode_edit_char_offsets(text, &char_encoding, i..j, replacement)
+                        .unwrap();
+                    assert_eq!(patch.encoding, expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn same_as_encoding_again() {
+        let tokenizer = bpe();
+        for text in ["ab abcd ab", "éab  cd d", "", " ab", "abcd abcd abcd ab"] {
+            check_edits(&tokenizer, text, &["", "c", " ", "d ab", "é"]);
+        }
+
+        let vocab = ["[UNK]", "hello", "big", "world", ",", "!"]
+            .iter()
+            .enumerate()
+            .map(|(i, t)| (t.to_string(), i as u32))
+            .collect();
+        let model = WordLevel::builder()
+            .vocab(vocab)
+            .unk_token("[UNK]".into())
+            .build()
+            .unwrap();
+        let mut tokenizer = Tokenizer::new(model);
+        tokenizer.with_pre_tokenizer(Some(Whitespace {}));
+        tokenizer.add_tokens(&[AddedToken::from("big world", false)]);
+        check_edits(&tokenizer, "hello, big world!", &["", "big", " ", "o"]);
+    }
+
+    #[test]
+    fn added_token_across_the_part() {
+        let mut tokenizer = bpe();
+        tokenizer.add_tokens(&[AddedToken::from("b ab abcd ab", false)]);
+        let text = "ab ab abcd";
+        let encoding = tokenizer.encode(text, false).unwrap();
+        // The added token starts before the word before the edit
+        let patch = tokenizer
+            .encode_edit(text, &encoding, 10..10, " ab")
+            .unwrap();
+        assert_eq!(patch.encoding.get_tokens(), ["a", "b ab abcd ab"]);
+        check_edits(&tokenizer, text, &["", " ab", "b", " "]);
+        check_edits(&tokenizer, "ab ab abcd ab ab", &["", "c", " "]);
+    }
+
+    #[test]
+    fn minimal_patch() {
+        let tokenizer = bpe();
+        let text = "ab abcd ab cd ab";
+        let encoding = tokenizer.encode(text, false).unwrap();
+        assert_eq!(
+            encoding.get_tokens(),
+            ["ab", "Ġabcd", "Ġab", "Ġ", "cd", "Ġab"]
+        );
+        // "ab" becomes "abcd", the next words only get new offsets
+        let patch = tokenizer
+            .encode_edit(text, &encoding, 8..10, "abcd")
+            .unwrap();
+        assert_eq!(patch.replaced, 2..3);
+        assert_eq!(patch.inserted, 2..3);
+        assert_eq!(patch.encoding.get_tokens()[2], "Ġabcd");
+        assert_eq!(patch.encoding.get_offsets()[5], (15, 18));
+
+        let error = tokenizer
+            .encode_edit(text, &encoding, 4..20, "")
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid edit range 4..20 for a text of length 16"
+        );
+    }
+
+    #[test]
+    fn truncation() {
+        let mut tokenizer = bpe();
+        tokenizer
+            .with_truncation(Some(TruncationParams {
+                max_length: 2,
+                ..Default::default()
+            }))
+            .unwrap();
+        let encoding = tokenizer.encode("ab ab", false).unwrap();
+        let patch = tokenizer
+            .encode_edit("ab ab", &encoding, 0..0, "cd ")
+            .unwrap();
+        assert_eq!(patch.encoding, tokenizer.encode("cd ab ab", false).unwrap());
+    }
+}
@@ -0,0 +1,213 @@
+/// Hard caps on the intermediate products of the encoding, bounding the work and the memory
+/// of each request. Going over one of them fails the encoding with a [`LimitError`], instead
+/// of silently keeping a part of the result. No limit is set by default.
+/// ```
+/// # use tokenizers::Tokenizer;
+/// # use tokenizers::models::bpe::BPE;
+/// # use tokenizers::pre_tokenizers::whitespace::WhitespaceSplit;
+/// use tokenizers::{LimitError, StageLimits};
+///
+/// let mut tokenizer = Tokenizer::new(BPE::default());
+/// tokenizer.with_pre_tokenizer(Some(WhitespaceSplit));
+/// tokenizer.with_limits(StageLimits {
+///     max_pre_tokens: Some(2),
+///     ..Default::default()
+/// });
+///
+/// assert!(tokenizer.encode("two words", false).is_ok());
+/// let err = tokenizer.encode("now three words", false).unwrap_err();
+/// assert_eq!(
+///     err.downcast_ref::<LimitError>(),
+///     Some(&LimitError::TooManyPreTokens { count: 3, limit: 2 })
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StageLimits {
+    /// The maximum number of pre-tokens of a sequence, over all its parts when pre-tokenized.
+    /// This is checked while the pre-tokenizer splits, which stops as soon as it goes over.
+    pub max_pre_tokens: Option<usize>,
+    /// The maximum number of tokens the model splits a single pre-token into. The tokens of a
+    /// pre-token are only counted once the model has built them all, so this bounds the
+    /// building of the encoding, but not the work of the model on that pre-token.
+    pub max_splits_per_pre_token: Option<usize>,
+    /// The maximum number of overflowing parts of the final encoding. Contrarily to the
+    /// `max_overflowing` of the truncation, which keeps the first parts, this is an error.
+    pub max_overflowing: Option<usize>,
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum LimitError {
+    #[error("Limit exceeded: the sequence has at least {count} pre-tokens, the limit is {limit}")]
+    TooManyPreTokens { count: usize, limit: usize },
+    #[error("Limit exceeded: a pre-token is split into {count} tokens, the limit is {limit}")]
+    TooManySplits { count: usize, limit: usize },
+    #[error(
+        "Limit exceeded: the encoding has at least {count} overflowing parts, the limit is {limit}"
+    )]
+    TooManyOverflowing { count: usize, limit: usize },
+}
+
+/// Fail with the error built by `error` if `count` goes over the `limit`
+pub(crate) fn check_limit(
+    count: usize,
+    limit: Option<usize>,
+    error: fn(usize, usize) -> LimitError,
+) -> std::result::Result<(), LimitError> {
+    match limit {
+        Some(limit) if count > limit => Err(error(count, limit)),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::bpe::BPE;
+    use crate::pre_tokenizers::whitespace::WhitespaceSplit;
+    use crate::tokenizer::{PreTokenizedString, PreTokenizer, Result, Tokenizer};
+    use crate::TruncationParams;
+    use crate::{DecoderWrapper, NormalizerWrapper, PostProcessorWrapper, TokenizerImpl};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn tokenizer(limits: StageLimits) -> Tokenizer {
+        let vocab: ahash::AHashMap<_, _> = ["a", "b", "c"]
+            .iter()
+            .enumerate()
+            .map(|(i, t)| (t.to_string(), i as u32))
+            .collect();
+        let bpe = BPE::builder()
+            .vocab_and_merges(vocab, vec![])
+            .build()
+            .unwrap();
+        let mut tokenizer = Tokenizer::new(bpe);
+        tokenizer.with_pre_tokenizer(Some(WhitespaceSplit));
+        tokenizer.with_limits(limits);
+        tokenizer
+    }
+
+    fn limit_error<T: std::fmt::Debug>(result: Result<T>) -> LimitError {
+        result
+            .unwrap_err()
+            .downcast_ref::<LimitError>()
+            .unwrap()
+            .clone()
+    }
+
+    #[test]
+    fn pre_tokens() {
+        let tokenizer = tokenizer(StageLimits {
+            max_pre_tokens: Some(3),
+            ..Default::default()
+        });
+        assert!(tokenizer.encode("a b c", false).is_ok());
+        assert!(tokenizer.encode(("a b c", "a b c"), false).is_ok());
+        assert_eq!(
+            limit_error(tokenizer.encode("a b c a", false)),
+            LimitError::TooManyPreTokens { count: 4, limit: 3 }
+        );
+        // The parts of a pre-tokenized sequence count together
+        assert_eq!(
+            limit_error(tokenizer.encode(&["a b", "c a", "b"][..], false)),
+            LimitError::TooManyPreTokens { count: 4, limit: 3 }
+        );
+    }
+
+    /// Split on whitespaces, then count the splits seen by a second splitting
+    #[derive(Default)]
+    struct CountingPreTokenizer(AtomicUsize);
+
+    impl PreTokenizer for CountingPreTokenizer {
+        fn pre_tokenize(&self, pretokenized: &mut PreTokenizedString) -> Result<()> {
+            WhitespaceSplit.pre_tokenize(pretokenized)?;
+            pretokenized.split(|_, normalized| {
+                self.0.fetch_add(1, Ordering::Relaxed);
+                Ok(vec![normalized])
+            })
+        }
+    }
+
+    #[test]
+    fn pre_tokens_while_splitting() {
+        let mut tokenizer: TokenizerImpl<
+            _,
+            NormalizerWrapper,
+            CountingPreTokenizer,
+            PostProcessorWrapper,
+            DecoderWrapper,
+        > = TokenizerImpl::new(
+            tokenizer(Default::default())
+                .into_inner()
+                .get_model()
+                .clone(),
+        );
+        tokenizer.with_pre_tokenizer(Some(CountingPreTokenizer::default()));
+        tokenizer.with_limits(StageLimits {
+            max_pre_tokens: Some(3),
+            ..Default::default()
+        });
+        assert!(tokenizer.encode("a b c", false).is_ok());
+        let counted = || {
+            tokenizer
+                .get_pre_tokenizer()
+                .unwrap()
+                .0
+                .load(Ordering::Relaxed)
+        };
+        assert_eq!(counted(), 3);
+
+        // The first splitting goes over, the second one never runs
+        assert_eq!(
+            limit_error(tokenizer.encode("a b c a b c", false)),
+            LimitError::TooManyPreTokens { count: 6, limit: 3 }
+        );
+        assert_eq!(counted(), 3);
+    }
+
+    #[test]
+    fn splits() {
+        let tokenizer = tokenizer(StageLimits {
+            max_splits_per_pre_token: Some(2),
+            ..Default::default()
+        });
+        assert!(tokenizer.encode("ab ba c", false).is_ok());
+        assert_eq!(
+            limit_error(tokenizer.encode("ab abc", false)),
+            LimitError::TooManySplits { count: 3, limit: 2 }
+        );
+    }
+
+    #[test]
+    fn overflowing() {
+        let mut tokenizer = tokenizer(StageLimits {
+            max_overflowing: Some(2),
+            ..Default::default()
+        });
+        // No truncation, no overflowing parts
+        assert!(tokenizer.encode("a b c a b c a b", false).is_ok());
+
+        tokenizer
+            .with_truncation(Some(TruncationParams {
+                max_length: 2,
+                ..Default::default()
+            }))
+            .unwrap();
+        let encoding = tokenizer.encode("a b c a b c", false).unwrap();
+        assert_eq!(encoding.get_overflowing().len(), 2);
+        // Only one part over the limit is built
+        assert_eq!(
+            limit_error(tokenizer.encode(&"a ".repeat(1000)[..], false)),
+            LimitError::TooManyOverflowing { count: 3, limit: 2 }
+        );
+
+        // The parts dropped by the truncation do not count
+        tokenizer
+            .with_truncation(Some(TruncationParams {
+                max_length: 2,
+                max_overflowing: Some(1),
+                ..Default::default()
+            }))
+            .unwrap();
+        let encoding = tokenizer.encode(&"a ".repeat(1000)[..], false).unwrap();
+        assert_eq!(encoding.get_overflowing().len(), 1);
+    }
+}
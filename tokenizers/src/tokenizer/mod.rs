@@ -20,16 +20,37 @@ use std::{
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 
+use crate::processors::chat::{ChatEncoding, ChatTemplate};
 use crate::utils::iter::ResultShunt;
 use crate::utils::parallelism::*;
 use crate::utils::progress::{ProgressBar, ProgressStyle};
+use crate::utils::versioning;
 
 mod added_vocabulary;
+mod alignments;
+mod artifacts;
+mod binary;
+mod chain;
 mod encoding;
+mod gpt2_files;
+mod incremental;
+mod limits;
 pub mod normalizer;
+mod offset_conversion;
 pub mod pattern;
+mod pipeline_graph;
+mod pre_tokenize_stream;
 pub mod pre_tokenizer;
+mod sampling;
 mod serialization;
+mod shadow;
+mod shared_prefix;
+mod token_pattern;
+mod trace;
+mod training_estimate;
+mod training_tags;
+mod validation;
+mod vocab_suggestions;
 
 // Re-export wrappers
 pub use crate::decoders::DecoderWrapper;
@@ -41,12 +62,27 @@ pub use crate::processors::PostProcessorWrapper;
 pub use crate::utils::iter::LinesWithEnding;
 pub use crate::utils::padding::{pad_encodings, PaddingDirection, PaddingParams, PaddingStrategy};
 pub use crate::utils::truncation::{
-    truncate_encodings, TruncationDirection, TruncationParams, TruncationStrategy,
+    truncate_encodings, PairBudget, SpecialTokensTruncation, TruncationDirection, TruncationParams,
+    TruncationStrategy,
 };
 pub use added_vocabulary::*;
+pub use artifacts::*;
 pub use encoding::*;
+pub use incremental::*;
+pub use limits::*;
 pub use normalizer::{NormalizedString, OffsetReferential, SplitDelimiterBehavior};
+pub use pipeline_graph::*;
+pub use pre_tokenize_stream::*;
 pub use pre_tokenizer::*;
+pub use sampling::*;
+pub use shadow::*;
+pub use token_pattern::*;
+pub use trace::*;
+pub use training_estimate::*;
+pub use training_tags::TagStatistics;
+use training_tags::{TagCounter, TAG_CHUNK_SIZE};
+pub use validation::*;
+pub use vocab_suggestions::*;
 
 pub type Error = Box<dyn std::error::Error + Send + Sync>;
 pub type Result<T> = std::result::Result<T, Error>;
@@ -55,6 +91,17 @@ pub type Offsets = (usize, usize);
 /// Takes care of pre-processing strings.
 pub trait Normalizer {
     fn normalize(&self, normalized: &mut NormalizedString) -> Result<()>;
+
+    /// Calls `f` with each step of this normalizer, in order, along with its name. Only the
+    /// normalizers chaining others, like `Sequence`, have more than one step. This is what
+    /// [`TokenizerImpl::encode_with_trace`] uses to record each of them.
+    fn for_each_step(&self, f: &mut dyn FnMut(&str, &dyn Normalizer) -> Result<()>) -> Result<()>
+    where
+        Self: Sized,
+    {
+        let name = std::any::type_name::<Self>();
+        f(name.rsplit("::").next().unwrap_or(name), self)
+    }
 }
 
 /// The `PreTokenizer` is in charge of doing the pre-segmentation step. It splits the given string
@@ -80,6 +127,14 @@ pub trait Model {
     fn get_vocab(&self) -> HashMap<String, u32>;
     /// Retrieve the size of the vocabulary
     fn get_vocab_size(&self) -> usize;
+    /// Find the IDs of all the tokens starting with the given bytes, in ascending order
+    fn token_ids_with_prefix(&self, prefix: &[u8]) -> Vec<u32> {
+        let vocab = self.get_vocab();
+        crate::models::vocab_store::ids_with_prefix(
+            vocab.iter().map(|(token, id)| (token.as_str(), *id)),
+            prefix,
+        )
+    }
     /// Save the current `Model` in the given folder, using the given `prefix` for the various
     /// files that need to be saved.
     fn save(&self, folder: &Path, prefix: Option<&str>) -> Result<Vec<PathBuf>>;
@@ -156,6 +211,18 @@ pub trait Decoder {
         Ok(results.join(""))
     }
     fn decode_chain(&self, tokens: Vec<String>) -> Result<Vec<String>>;
+    /// Decode the given tokens to raw bytes. The decoders that can produce invalid UTF-8
+    /// (like [`ByteFallback`](crate::decoders::byte_fallback::ByteFallback)) keep the
+    /// invalid bytes as is, instead of applying their policy.
+    fn decode_bytes(&self, tokens: Vec<String>) -> Result<Vec<u8>> {
+        Ok(self.decode(tokens)?.into_bytes())
+    }
+    /// Decode the given tokens, reconstructing their whitespace exactly. The decoders that
+    /// alter the spaces with some heuristics (like the `Metaspace` and `WordPiece` ones)
+    /// leave them untouched, only removing the prefix space added by the pre-tokenizer.
+    fn decode_preserving_whitespace(&self, tokens: Vec<String>) -> Result<String> {
+        self.decode(tokens)
+    }
 }
 
 /// A `Trainer` has the responsibility to train a model. We feed it with lines/sentences
@@ -174,6 +241,26 @@ pub trait Trainer {
         I: Iterator<Item = S> + Send,
         S: AsRef<str> + Send,
         F: Fn(&str) -> Result<Vec<String>> + Sync;
+    /// Estimate the peak memory, the number of unique words and the duration of a training
+    /// on a corpus of `corpus_bytes` bytes from a sample of this corpus, before committing to
+    /// the full run. A copy of `model` gets trained on the sample with a copy of this trainer,
+    /// `process` pre-processing the sequences like with [`feed`](Self::feed).
+    fn estimate<I, S, F>(
+        &self,
+        model: &Self::Model,
+        sample: I,
+        corpus_bytes: u64,
+        process: F,
+    ) -> Result<TrainingEstimate>
+    where
+        Self: Clone + Sized,
+        Self::Model: Clone,
+        I: Iterator<Item = S>,
+        S: AsRef<str>,
+        F: Fn(&str) -> Result<Vec<String>> + Sync,
+    {
+        training_estimate::estimate_training(self, model, sample, corpus_bytes, process)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -188,6 +275,16 @@ impl Token {
     }
 }
 
+/// The constraint to apply on the next generated token when doing token healing.
+/// See [`TokenizerImpl::encode_with_prefix_constraint`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrefixConstraint {
+    /// The token that was removed from the end of the input
+    pub token: Token,
+    /// The ids of every token of the vocabulary starting with `token.value`, sorted
+    pub allowed_ids: Vec<u32>,
+}
+
 use std::borrow::Cow;
 use std::collections::HashMap;
 
@@ -275,6 +372,35 @@ where
     }
 }
 
+/// The parameters of a single call to [`TokenizerImpl::encode_with_options`], used instead of
+/// those of the tokenizer.
+///
+/// This lets one tokenizer serve inputs of different shapes without being mutated, for
+/// example when it is shared behind an `Arc`. [`TokenizerImpl::encode_options`] gives the
+/// options matching the tokenizer, to override only some of them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EncodeOptions {
+    /// The padding parameters, `None` to disable the padding
+    pub padding: Option<PaddingParams>,
+    /// The truncation parameters, `None` to disable the truncation
+    pub truncation: Option<TruncationParams>,
+    /// Whether to add the special tokens, using the post-processor
+    pub add_special_tokens: bool,
+    /// The type of the offsets of the encodings
+    pub offsets: OffsetType,
+}
+
+impl Default for EncodeOptions {
+    fn default() -> Self {
+        Self {
+            padding: None,
+            truncation: None,
+            add_special_tokens: true,
+            offsets: OffsetType::Byte,
+        }
+    }
+}
+
 #[derive(thiserror::Error, Debug)]
 #[error("{0}")]
 pub struct BuilderError(String);
@@ -293,6 +419,8 @@ pub struct TokenizerBuilder<M, N, PT, PP, D> {
 
     truncation: Option<TruncationParams>,
     padding: Option<PaddingParams>,
+
+    parallelism: Parallelism,
 }
 
 impl<M, N, PT, PP, D> Default for TokenizerBuilder<M, N, PT, PP, D>
@@ -327,6 +455,7 @@ where
             added_vocabulary: AddedVocabulary::new(),
             truncation: None,
             padding: None,
+            parallelism: Parallelism::default(),
         }
     }
 
@@ -347,6 +476,9 @@ where
             added_vocabulary: self.added_vocabulary,
             truncation: self.truncation,
             padding: self.padding,
+            parallelism: self.parallelism,
+            artifact_warnings: false,
+            limits: StageLimits::default(),
         })
     }
 
@@ -404,6 +536,13 @@ where
         self.padding = padding;
         self
     }
+
+    /// Set where the batch methods run.
+    #[must_use]
+    pub fn with_parallelism(mut self, parallelism: Parallelism) -> Self {
+        self.parallelism = parallelism;
+        self
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -444,6 +583,19 @@ impl Tokenizer {
         let tokenizer = serde_json::from_slice(bytes.as_ref())?;
         Ok(tokenizer)
     }
+    /// Load a subword-nmt `codes` file, with the `BPE` of [`BPE::from_subword_nmt`]. Like
+    /// subword-nmt, the tokenizer splits the words on whitespace, and its decoder removes the
+    /// `</w>` suffix.
+    ///
+    /// [`BPE::from_subword_nmt`]: crate::models::bpe::BPE::from_subword_nmt
+    pub fn from_subword_nmt(codes: &str) -> Result<Self> {
+        let bpe = crate::models::bpe::BPE::from_subword_nmt(codes)?.build()?;
+        let mut tokenizer = Self::new(bpe);
+        tokenizer
+            .with_pre_tokenizer(Some(crate::pre_tokenizers::whitespace::WhitespaceSplit))
+            .with_decoder(Some(crate::decoders::bpe::BPEDecoder::default()));
+        Ok(tokenizer)
+    }
     #[cfg(feature = "http")]
     pub fn from_pretrained<S: AsRef<str>>(
         identifier: S,
@@ -480,6 +632,9 @@ where
             added_vocabulary: t.added_vocabulary,
             padding: t.padding,
             truncation: t.truncation,
+            parallelism: t.parallelism,
+            artifact_warnings: t.artifact_warnings,
+            limits: t.limits,
         })
     }
 }
@@ -524,6 +679,11 @@ pub struct TokenizerImpl<M, N, PT, PP, D> {
     // General processing parameters
     truncation: Option<TruncationParams>,
     padding: Option<PaddingParams>,
+
+    // Runtime configuration, not serialized
+    parallelism: Parallelism,
+    artifact_warnings: bool,
+    limits: StageLimits,
 }
 
 impl<M, N, PT, PP, D> TokenizerImpl<M, N, PT, PP, D>
@@ -547,6 +707,10 @@ where
 
             truncation: None,
             padding: None,
+
+            parallelism: Parallelism::default(),
+            artifact_warnings: false,
+            limits: StageLimits::default(),
         }
     }
 
@@ -620,17 +784,31 @@ where
     /// Fails if `stride` is too high relative to `max_length` and `post_processor.added_tokens()`
     pub fn with_truncation(&mut self, trunc: Option<TruncationParams>) -> Result<&mut Self> {
         if let Some(trunc_params) = &trunc {
-            let n_added_tokens = self.get_n_added_tokens(false);
-            let effective_max_length = trunc_params.max_length - n_added_tokens;
-            if effective_max_length < trunc_params.stride {
+            self.check_truncation(trunc_params)?;
+        }
+        self.truncation = trunc;
+        Ok(self)
+    }
+
+    /// Make sure the given truncation parameters leave room for the stride, once the
+    /// special tokens are added
+    fn check_truncation(&self, trunc_params: &TruncationParams) -> Result<()> {
+        let n_added_tokens = self.get_n_reserved_tokens(trunc_params, false);
+        let effective_max_length = trunc_params.max_length.saturating_sub(n_added_tokens);
+        if effective_max_length < trunc_params.stride {
+            return Err(Box::new(TruncationParamError(format!(
+                "tokenizer stride set to {}, which is greater than or equal to its effective max length of {} (= {} original max length - {} added special tokens), ",
+                trunc_params.stride, effective_max_length, trunc_params.max_length, n_added_tokens
+            ))));
+        }
+        if let TruncationStrategy::Budget(PairBudget::Ratio(ratio)) = trunc_params.strategy {
+            if !(0.0..=1.0).contains(&ratio) {
                 return Err(Box::new(TruncationParamError(format!(
-                    "tokenizer stride set to {}, which is greater than or equal to its effective max length of {} (= {} original max length - {} added special tokens), ",
-                    trunc_params.stride, effective_max_length, trunc_params.max_length, n_added_tokens
+                    "truncation budget ratio set to {ratio}, which is not between 0 and 1"
                 ))));
             }
         }
-        self.truncation = trunc;
-        Ok(self)
+        Ok(())
     }
 
     /// Get the currently set truncation parameters
@@ -659,6 +837,41 @@ where
         self.padding.as_mut()
     }
 
+    /// Set where the batch methods run
+    pub fn with_parallelism(&mut self, parallelism: Parallelism) -> &mut Self {
+        self.parallelism = parallelism;
+        self
+    }
+
+    /// Get where the batch methods run
+    pub fn get_parallelism(&self) -> &Parallelism {
+        &self.parallelism
+    }
+
+    /// Set whether to warn about the inputs that look already tokenized, a debug mode
+    /// catching the double encoding of some data upstream. The warnings are logged, see
+    /// [`find_tokenizer_artifacts`]
+    pub fn with_artifact_warnings(&mut self, enabled: bool) -> &mut Self {
+        self.artifact_warnings = enabled;
+        self
+    }
+
+    /// Get whether to warn about the inputs that look already tokenized
+    pub fn get_artifact_warnings(&self) -> bool {
+        self.artifact_warnings
+    }
+
+    /// Set the hard caps on the intermediate products of the encoding, see [`StageLimits`]
+    pub fn with_limits(&mut self, limits: StageLimits) -> &mut Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Get the hard caps on the intermediate products of the encoding
+    pub fn get_limits(&self) -> &StageLimits {
+        &self.limits
+    }
+
     // Get the vocabulary as a plain HashMap for bindings compatibility
     pub fn get_vocab(&self, with_added_tokens: bool) -> HashMap<String, u32> {
         let mut final_vocab = self.model.get_vocab();
@@ -704,6 +917,26 @@ where
             .or_else(|| self.model.id_to_token(id))
     }
 
+    /// Get the ids of all the tokens of the vocabulary (added tokens included) starting with the
+    /// given prefix, in ascending order.
+    ///
+    /// The prefix is compared with the bytes of each token as it appears in the vocabulary, so
+    /// it must use the same representation (`Ġ` for spaces with byte-level models, `##` for
+    /// WordPiece continuations, ...). It does not have to be valid UTF-8.
+    pub fn token_ids_with_prefix(&self, prefix: &[u8]) -> Vec<u32> {
+        let mut ids = self.model.token_ids_with_prefix(prefix);
+        ids.extend(
+            self.added_vocabulary
+                .get_vocab()
+                .iter()
+                .filter(|(token, _)| token.as_bytes().starts_with(prefix))
+                .map(|(_, id)| *id),
+        );
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+
     /// set the added vocab's splitting scheme
     pub fn set_encode_special_tokens(&mut self, value: bool) {
         self.added_vocabulary.set_encode_special_tokens(value);
@@ -721,14 +954,40 @@ where
         type_id: u32,
         offsets_type: OffsetType,
     ) -> Result<Encoding> {
-        let encode = |is_pre_tokenized, subseq_idx, subseq| -> Result<Encoding> {
-            let normalized:PreTokenizedString = self
+        self.encode_single_sequence_with(sequence, type_id, offsets_type, &|split| {
+            self.model.tokenize(split)
+        })
+    }
+
+    /// Encode a single sequence, tokenizing each split with the given function instead of
+    /// the model
+    fn encode_single_sequence_with<F>(
+        &self,
+        sequence: InputSequence,
+        type_id: u32,
+        offsets_type: OffsetType,
+        tokenize: &F,
+    ) -> Result<Encoding>
+    where
+        F: Fn(&str) -> Result<Vec<Token>>,
+    {
+        let mut pre_tokens = 0;
+        let mut encode = |is_pre_tokenized, subseq_idx, subseq| -> Result<Encoding> {
+            if self.artifact_warnings {
+                if let Some(warning) = artifacts_warning(subseq) {
+                    log::warn!("{warning}");
+                }
+            }
+            let mut normalized = self
                 .added_vocabulary
                 .extract_and_normalize(self.normalizer.as_ref(), subseq);
-            println!("728:{:?}", normalized);
+            normalized.limit_pre_tokens(pre_tokens, self.limits.max_pre_tokens);
             let pre_tokenized = self.do_pre_tokenize(normalized)?;
-            println!("730:{:?}", pre_tokenized);
-            let subseq_encoding = self.do_tokenize(
+            pre_tokens += pre_tokenized.len();
+            check_limit(pre_tokens, self.limits.max_pre_tokens, |count, limit| {
+                LimitError::TooManyPreTokens { count, limit }
+            })?;
+            let subseq_encoding = self.do_tokenize_with(
                 pre_tokenized,
                 type_id,
                 if is_pre_tokenized {
@@ -737,8 +996,8 @@ where
                     None
                 },
                 offsets_type,
+                tokenize,
             )?;
-            println!("741:{:?}", subseq_encoding);
 
             Ok(subseq_encoding)
         };
@@ -839,7 +1098,6 @@ where
 
         // Encode each sequence
         let encoding = self.encode_single_sequence(sequence, 0, OffsetType::Byte)?;
-        println!("839:{:?}", encoding.get_tokens());
         let pair_encoding = pair
             .map(|sequence| self.encode_single_sequence(sequence, 1, OffsetType::Byte))
             .transpose()?;
@@ -891,10 +1149,550 @@ where
         self.post_process(encoding, pair_encoding, add_special_tokens)
     }
 
+    /// Encode the given pre-tokenized input, attaching to the tokens of each word its
+    /// metadata, like the timestamps of the words in an audio for forced alignment.
+    /// `word_metadata` holds the metadata of each word of each sequence, and the metadata
+    /// follow the tokens through the truncation, the padding and the overflowing encodings.
+    /// See [`Encoding::get_word_metadata`]
+    /// ```
+    /// # use tokenizers::models::wordlevel::WordLevel;
+    /// # use tokenizers::{OffsetType, Tokenizer};
+    /// let vocab = vec![("[UNK]".to_string(), 0), ("hello".to_string(), 1)];
+    /// let model = WordLevel::builder()
+    ///     .vocab(vocab.into_iter().collect())
+    ///     .unk_token("[UNK]".into())
+    ///     .build()
+    ///     .unwrap();
+    /// let tokenizer = Tokenizer::new(model);
+    ///
+    /// let timestamps = vec![vec![0.0, 0.4], vec![0.5, 1.2]];
+    /// let encoding = tokenizer
+    ///     .encode_with_word_metadata(&["hello", "there"][..], &[timestamps], false, OffsetType::Byte)
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     encoding.get_word_metadata(),
+    ///     [Some(vec![0.0, 0.4]), Some(vec![0.5, 1.2])]
+    /// );
+    /// ```
+    pub fn encode_with_word_metadata<'s, E>(
+        &self,
+        input: E,
+        word_metadata: &[Vec<Vec<f64>>],
+        add_special_tokens: bool,
+        offsets_type: OffsetType,
+    ) -> Result<Encoding>
+    where
+        E: Into<EncodeInput<'s>>,
+    {
+        let input = input.into();
+        let sequences = match &input {
+            EncodeInput::Single(s1) => vec![s1],
+            EncodeInput::Dual(s1, s2) => vec![s1, s2],
+        };
+        if sequences.len() != word_metadata.len() {
+            return Err(format!(
+                "Got the word metadata of {} sequences for {} sequences",
+                word_metadata.len(),
+                sequences.len()
+            )
+            .into());
+        }
+        for (i, (sequence, metadata)) in sequences.into_iter().zip(word_metadata).enumerate() {
+            let n_words = match sequence {
+                InputSequence::Raw(_) => {
+                    return Err("The word metadata needs a pre-tokenized input".into())
+                }
+                InputSequence::PreTokenized(words) => words.len(),
+                InputSequence::PreTokenizedOwned(words) => words.len(),
+                InputSequence::PreTokenizedCow(words) => words.len(),
+            };
+            if n_words != metadata.len() {
+                return Err(format!(
+                    "Got the metadata of {} words for the {} words of the sequence {}",
+                    metadata.len(),
+                    n_words,
+                    i
+                )
+                .into());
+            }
+        }
+
+        let mut encoding = self.encode_with_params(
+            input,
+            add_special_tokens,
+            offsets_type,
+            self.truncation.as_ref(),
+            self.padding.as_ref(),
+        )?;
+        encoding.set_word_metadata(word_metadata);
+        Ok(encoding)
+    }
+
+    /// Encode the given input like [`encode`](Self::encode), using the given truncation
+    /// and padding parameters instead of the ones of the tokenizer
+    fn encode_with_params<'s, E>(
+        &self,
+        input: E,
+        add_special_tokens: bool,
+        offsets_type: OffsetType,
+        truncation: Option<&TruncationParams>,
+        padding: Option<&PaddingParams>,
+    ) -> Result<Encoding>
+    where
+        E: Into<EncodeInput<'s>>,
+    {
+        let (sequence, pair) = match input.into() {
+            EncodeInput::Single(s1) => (s1, None),
+            EncodeInput::Dual(s1, s2) => (s1, Some(s2)),
+        };
+
+        let encoding = self.encode_single_sequence(sequence, 0, offsets_type)?;
+        let pair_encoding = pair
+            .map(|sequence| self.encode_single_sequence(sequence, 1, offsets_type))
+            .transpose()?;
+
+        self.post_process_with_params(
+            encoding,
+            pair_encoding,
+            add_special_tokens,
+            truncation,
+            padding,
+        )
+    }
+
+    /// Encode the given input using the given options instead of the truncation, padding and
+    /// other parameters of the tokenizer, which is left untouched.
+    /// ```
+    /// # use tokenizers::Tokenizer;
+    /// # use tokenizers::models::bpe::BPE;
+    /// # use tokenizers::{AddedToken, EncodeOptions, TruncationParams};
+    /// # let mut tokenizer = Tokenizer::new(BPE::default());
+    /// # tokenizer.add_tokens(&["my", "name", "is", "john"].map(|t| AddedToken::from(t, false)));
+    /// let options = EncodeOptions {
+    ///     truncation: Some(TruncationParams {
+    ///         max_length: 2,
+    ///         ..Default::default()
+    ///     }),
+    ///     ..tokenizer.encode_options()
+    /// };
+    /// let encoding = tokenizer.encode_with_options("my name is john", &options).unwrap();
+    /// assert_eq!(encoding.get_tokens(), ["my", "name"]);
+    /// assert!(tokenizer.get_truncation().is_none());
+    /// ```
+    pub fn encode_with_options<'s, E>(&self, input: E, options: &EncodeOptions) -> Result<Encoding>
+    where
+        E: Into<EncodeInput<'s>>,
+    {
+        if let Some(trunc) = &options.truncation {
+            self.check_truncation(trunc)?;
+        }
+        self.encode_with_params(
+            input,
+            options.add_special_tokens,
+            options.offsets,
+            options.truncation.as_ref(),
+            options.padding.as_ref(),
+        )
+    }
+
+    /// Encode the given input like `encode`, recording the output of each stage of the
+    /// pipeline along the way: the extraction of the added tokens, each normalizer (the
+    /// members of a `Sequence` one by one), the pre-tokenizer and the model, for each
+    /// sequence. This is meant to debug a pipeline, by showing how each stage transforms the
+    /// input and its alignments with the original one. The offsets are in bytes.
+    /// ```
+    /// # use tokenizers::Tokenizer;
+    /// # use tokenizers::models::bpe::BPE;
+    /// # use tokenizers::normalizers::{Lowercase, Sequence, Strip};
+    /// # use tokenizers::pre_tokenizers::whitespace::Whitespace;
+    /// # use tokenizers::TraceStep;
+    /// let mut tokenizer = Tokenizer::new(BPE::default());
+    /// tokenizer.with_normalizer(Some(Sequence::new(vec![
+    ///     Strip::new(true, true).into(),
+    ///     Lowercase.into(),
+    /// ])));
+    /// tokenizer.with_pre_tokenizer(Some(Whitespace {}));
+    ///
+    /// let trace = tokenizer.encode_with_trace(" Hello There", false).unwrap();
+    /// let stages = &trace.sequences[0].stages;
+    /// assert_eq!(
+    ///     stages.iter().map(|stage| &stage.step).collect::<Vec<_>>(),
+    ///     vec![
+    ///         &TraceStep::AddedTokens,
+    ///         &TraceStep::Normalizer("Strip".into()),
+    ///         &TraceStep::Normalizer("Lowercase".into()),
+    ///         &TraceStep::NormalizedAddedTokens,
+    ///         &TraceStep::PreTokenizer,
+    ///     ]
+    /// );
+    /// assert_eq!(stages[2].splits[0].value, "hello there");
+    /// assert_eq!(stages[4].splits[1].value, "there");
+    /// assert_eq!(stages[4].splits[1].offsets, (7, 12));
+    /// ```
+    pub fn encode_with_trace<'s, E>(
+        &self,
+        input: E,
+        add_special_tokens: bool,
+    ) -> Result<EncodeTrace>
+    where
+        E: Into<EncodeInput<'s>>,
+    {
+        let (sequence, pair) = match input.into() {
+            EncodeInput::Single(s1) => (s1, None),
+            EncodeInput::Dual(s1, s2) => (s1, Some(s2)),
+        };
+
+        let mut sequences = vec![];
+        let encoding = self.trace_single_sequence(sequence, 0, &mut sequences)?;
+        let pair_encoding = pair
+            .map(|sequence| self.trace_single_sequence(sequence, 1, &mut sequences))
+            .transpose()?;
+
+        let encoding = self.post_process(encoding, pair_encoding, add_special_tokens)?;
+        Ok(EncodeTrace {
+            sequences,
+            encoding,
+        })
+    }
+
+    /// Encode a single sequence, pushing the trace of each of its words to `traces`
+    fn trace_single_sequence(
+        &self,
+        sequence: InputSequence,
+        type_id: u32,
+        traces: &mut Vec<SequenceTrace>,
+    ) -> Result<Encoding> {
+        let mut encode = |word_idx: Option<usize>, subseq: &str| -> Result<Encoding> {
+            let word_idx = word_idx.map(|i| i as u32);
+            let mut stages = vec![];
+            let normalized = self.added_vocabulary.extract_and_normalize_traced(
+                self.normalizer.as_ref(),
+                subseq,
+                &mut |step, pretokenized| {
+                    stages.push(TraceStage {
+                        step,
+                        splits: pretokenized.trace_splits(),
+                    })
+                },
+            )?;
+            let pre_tokenized = self.do_pre_tokenize(normalized)?;
+            if self.pre_tokenizer.is_some() {
+                stages.push(TraceStage {
+                    step: TraceStep::PreTokenizer,
+                    splits: pre_tokenized.trace_splits(),
+                });
+            }
+            let encoding = self.do_tokenize_with(
+                pre_tokenized,
+                type_id,
+                word_idx,
+                OffsetType::Byte,
+                &|split| self.model.tokenize(split),
+            )?;
+
+            traces.push(SequenceTrace {
+                type_id,
+                word_idx,
+                stages,
+                encoding: encoding.clone(),
+            });
+            Ok(encoding)
+        };
+
+        match sequence {
+            InputSequence::PreTokenized(seq) => seq
+                .iter()
+                .enumerate()
+                .map(|(i, sequence)| encode(Some(i), sequence))
+                .collect(),
+            InputSequence::PreTokenizedOwned(seq) => seq
+                .iter()
+                .enumerate()
+                .map(|(i, sequence)| encode(Some(i), sequence))
+                .collect(),
+            InputSequence::PreTokenizedCow(seq) => seq
+                .iter()
+                .enumerate()
+                .map(|(i, sequence)| encode(Some(i), sequence))
+                .collect(),
+            InputSequence::Raw(seq) => encode(None, seq.as_ref()),
+        }
+    }
+
+    /// The options matching the current parameters of the tokenizer, with byte offsets and
+    /// special tokens, to use with [`encode_with_options`](Self::encode_with_options)
+    pub fn encode_options(&self) -> EncodeOptions {
+        EncodeOptions {
+            padding: self.padding.clone(),
+            truncation: self.truncation.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// Encode a conversation, made of any number of `(role, content)` turns, using the given
+    /// [`ChatTemplate`]. The offsets of each turn are relative to its own content.
+    ///
+    /// Truncation is not applied to conversations, but padding is, in which case the
+    /// `loss_mask` and `role_ids` are padded with `0` and `None` respectively.
+    ///
+    /// ```
+    /// # use tokenizers::Tokenizer;
+    /// # use tokenizers::models::bpe::BPE;
+    /// # use tokenizers::processors::chat::{ChatRole, ChatTemplate};
+    /// # let tokenizer = Tokenizer::new(BPE::default());
+    /// let template = ChatTemplate::new(
+    ///     vec![],
+    ///     vec![
+    ///         ChatRole::new("user", vec!["<u>".into()], vec![], 0, false),
+    ///         ChatRole::new("assistant", vec!["<a>".into()], vec!["</s>".into()], 0, true),
+    ///     ],
+    ///     vec![("<u>", 0), ("<a>", 1), ("</s>", 2)],
+    /// )
+    /// .unwrap();
+    /// let chat = tokenizer
+    ///     .encode_chat(&template, vec![("user", "Hi"), ("assistant", "Hello")], true)
+    ///     .unwrap();
+    /// assert_eq!(chat.encoding.get_ids(), &[0, 1, 2]);
+    /// assert_eq!(chat.loss_mask, vec![0, 0, 1]);
+    /// ```
+    pub fn encode_chat<'s, R, S>(
+        &self,
+        template: &ChatTemplate,
+        turns: Vec<(R, S)>,
+        add_special_tokens: bool,
+    ) -> Result<ChatEncoding>
+    where
+        R: AsRef<str>,
+        S: Into<InputSequence<'s>>,
+    {
+        self.encode_chat_with_offsets(template, turns, add_special_tokens, OffsetType::Byte)
+    }
+
+    /// Encode a conversation, using offsets relative to chars instead of bytes.
+    /// See [`encode_chat`](Self::encode_chat)
+    pub fn encode_chat_char_offsets<'s, R, S>(
+        &self,
+        template: &ChatTemplate,
+        turns: Vec<(R, S)>,
+        add_special_tokens: bool,
+    ) -> Result<ChatEncoding>
+    where
+        R: AsRef<str>,
+        S: Into<InputSequence<'s>>,
+    {
+        self.encode_chat_with_offsets(template, turns, add_special_tokens, OffsetType::Char)
+    }
+
+    fn encode_chat_with_offsets<'s, R, S>(
+        &self,
+        template: &ChatTemplate,
+        turns: Vec<(R, S)>,
+        add_special_tokens: bool,
+        offsets_type: OffsetType,
+    ) -> Result<ChatEncoding>
+    where
+        R: AsRef<str>,
+        S: Into<InputSequence<'s>>,
+    {
+        let turns = turns
+            .into_iter()
+            .map(|(role, content)| {
+                let role_idx = template.role_index(role.as_ref())?;
+                let type_id = template.get_roles()[role_idx].type_id;
+                let encoding =
+                    self.encode_single_sequence(content.into(), type_id, offsets_type)?;
+                Ok((role_idx, encoding))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut chat = template.process_turns(turns, add_special_tokens)?;
+
+        if let Some(params) = &self.padding {
+            let original_len = chat.encoding.len();
+            pad_encodings(std::slice::from_mut(&mut chat.encoding), params)?;
+            let pad_length = chat.encoding.len() - original_len;
+            match params.direction {
+                PaddingDirection::Left => {
+                    chat.loss_mask
+                        .splice(0..0, std::iter::repeat_n(0, pad_length));
+                    chat.role_ids
+                        .splice(0..0, std::iter::repeat_n(None, pad_length));
+                }
+                PaddingDirection::Right => {
+                    chat.loss_mask.extend(std::iter::repeat_n(0, pad_length));
+                    chat.role_ids.extend(std::iter::repeat_n(None, pad_length));
+                }
+            }
+        }
+
+        Ok(chat)
+    }
+
+    /// Encode the given text for token healing.
+    ///
+    /// When the text stops in the middle of what would be a single token, its last token is not
+    /// the one a model would have produced for the complete text, and constraining the
+    /// generation on it degrades its output. Instead, the last token gets removed from the
+    /// returned [`Encoding`], and the [`PrefixConstraint`] lists all the tokens of the
+    /// vocabulary the generation can start with to extend it.
+    ///
+    /// No token gets removed, and no constraint is returned, if the last token does not reach
+    /// the end of the text (when a trailing space got stripped for example).
+    ///
+    /// ```
+    /// # use tokenizers::Tokenizer;
+    /// # use tokenizers::models::bpe::BPE;
+    /// # use std::collections::HashMap;
+    /// # use std::iter::FromIterator;
+    /// let vocab = HashMap::from_iter(
+    ///     ["a", "b", "c", "ab", "abc", "ca"].iter().enumerate().map(|(i, t)| (t.to_string(), i as u32)),
+    /// );
+    /// let merges = vec![("a".into(), "b".into()), ("ab".into(), "c".into()), ("c".into(), "a".into())];
+    /// let bpe = BPE::builder().vocab_and_merges(vocab, merges).build().unwrap();
+    /// let tokenizer = Tokenizer::new(bpe);
+    ///
+    /// let (encoding, constraint) = tokenizer.encode_with_prefix_constraint("caab", false).unwrap();
+    /// assert_eq!(encoding.get_tokens(), &["ca"]);
+    /// let constraint = constraint.unwrap();
+    /// assert_eq!(constraint.token.value, "ab");
+    /// assert_eq!(constraint.token.offsets, (2, 4));
+    /// assert_eq!(constraint.allowed_ids, vec![3, 4]);
+    /// ```
+    pub fn encode_with_prefix_constraint<'s, S>(
+        &self,
+        sequence: S,
+        add_special_tokens: bool,
+    ) -> Result<(Encoding, Option<PrefixConstraint>)>
+    where
+        S: Into<Cow<'s, str>>,
+    {
+        self.encode_with_prefix_constraint_and_offsets(
+            sequence.into(),
+            add_special_tokens,
+            OffsetType::Byte,
+        )
+    }
+
+    /// Encode the given text for token healing, using offsets relative to chars instead of
+    /// bytes. See [`encode_with_prefix_constraint`](Self::encode_with_prefix_constraint)
+    pub fn encode_with_prefix_constraint_char_offsets<'s, S>(
+        &self,
+        sequence: S,
+        add_special_tokens: bool,
+    ) -> Result<(Encoding, Option<PrefixConstraint>)>
+    where
+        S: Into<Cow<'s, str>>,
+    {
+        self.encode_with_prefix_constraint_and_offsets(
+            sequence.into(),
+            add_special_tokens,
+            OffsetType::Char,
+        )
+    }
+
+    fn encode_with_prefix_constraint_and_offsets(
+        &self,
+        sequence: Cow<'_, str>,
+        add_special_tokens: bool,
+        offsets_type: OffsetType,
+    ) -> Result<(Encoding, Option<PrefixConstraint>)> {
+        let end = match offsets_type {
+            OffsetType::Char => sequence.chars().count(),
+            _ => sequence.len(),
+        };
+        let mut encoding =
+            self.encode_single_sequence(InputSequence::Raw(sequence), 0, offsets_type)?;
+
+        let n_tokens = encoding.len();
+        let constraint = match n_tokens.checked_sub(1) {
+            Some(last) if encoding.get_offsets()[last].1 == end => {
+                let token = Token::new(
+                    encoding.get_ids()[last],
+                    encoding.get_tokens()[last].clone(),
+                    encoding.get_offsets()[last],
+                );
+                encoding.truncate(last, 0, TruncationDirection::Right);
+                encoding.take_overflowing();
+                let allowed_ids = self.token_ids_with_prefix(token.value.as_bytes());
+                Some(PrefixConstraint { token, allowed_ids })
+            }
+            _ => None,
+        };
+
+        let encoding = self.post_process(encoding, None, add_special_tokens)?;
+        Ok((encoding, constraint))
+    }
+
     /// Decode the given ids, back to a String
     pub fn decode(&self, ids: &[u32], skip_special_tokens: bool) -> Result<String> {
-        let tokens = ids
-            .iter()
+        let tokens = self.ids_to_decoder_tokens(ids, skip_special_tokens);
+
+        if let Some(decoder) = &self.decoder {
+            decoder.decode(tokens)
+        } else {
+            Ok(tokens.join(" "))
+        }
+    }
+
+    /// Decode the given ids, back to raw bytes
+    ///
+    /// Contrarily to [`decode`](Self::decode), the byte tokens that don't form valid UTF-8
+    /// are returned as is, whatever the policy of the decoder. This lets the caller handle
+    /// partial byte sequences, when the ids have been generated up to the middle of a char,
+    /// and the models of binary data round-trip their content. Both the `ByteLevel` and
+    /// `ByteFallback` decoders support it.
+    pub fn decode_bytes(&self, ids: &[u32], skip_special_tokens: bool) -> Result<Vec<u8>> {
+        let tokens = self.ids_to_decoder_tokens(ids, skip_special_tokens);
+
+        if let Some(decoder) = &self.decoder {
+            decoder.decode_bytes(tokens)
+        } else {
+            Ok(tokens.join(" ").into_bytes())
+        }
+    }
+
+    /// Decode the given ids back to a String, reconstructing the whitespace exactly.
+    ///
+    /// This is useful for code, where [`decode`](Self::decode) can break the indentation: the
+    /// `Metaspace` decoder removes all the spaces of the first token, and the cleanup of the
+    /// `WordPiece` decoder removes some spaces before punctuation. See
+    /// [`Decoder::decode_preserving_whitespace`].
+    ///
+    /// The tabs, newlines and spaces are all kept, except for the leading space the
+    /// pre-tokenizer takes as its prefix space, when the input starts with one.
+    /// ```
+    /// # use tokenizers::Tokenizer;
+    /// # use tokenizers::models::wordlevel::WordLevel;
+    /// # use tokenizers::pre_tokenizers::metaspace::{Metaspace, PrependScheme};
+    /// let vocab = vec![("▁def▁f():\n▁▁▁▁return▁1".to_string(), 0)].into_iter().collect();
+    /// let model = WordLevel::builder().vocab(vocab).build().unwrap();
+    /// let metaspace = Metaspace::new('▁', PrependScheme::Always, false);
+    /// let mut tokenizer = Tokenizer::new(model);
+    /// tokenizer.with_pre_tokenizer(Some(metaspace.clone()));
+    /// tokenizer.with_decoder(Some(metaspace));
+    ///
+    /// let code = "def f():\n    return 1";
+    /// let ids = tokenizer.encode(code, false).unwrap().get_ids().to_vec();
+    /// assert_eq!(tokenizer.decode(&ids, false).unwrap(), "deff():\nreturn1");
+    /// assert_eq!(tokenizer.decode_preserving_whitespace(&ids, false).unwrap(), code);
+    /// ```
+    pub fn decode_preserving_whitespace(
+        &self,
+        ids: &[u32],
+        skip_special_tokens: bool,
+    ) -> Result<String> {
+        let tokens = self.ids_to_decoder_tokens(ids, skip_special_tokens);
+
+        if let Some(decoder) = &self.decoder {
+            decoder.decode_preserving_whitespace(tokens)
+        } else {
+            Ok(tokens.join(" "))
+        }
+    }
+
+    /// Convert the given ids to the tokens to be given to the decoder
+    fn ids_to_decoder_tokens(&self, ids: &[u32], skip_special_tokens: bool) -> Vec<String> {
+        ids.iter()
             .filter_map(|id| {
                 self.added_vocabulary
                     .simple_id_to_token(*id)
@@ -903,13 +1701,7 @@ where
                         !skip_special_tokens || !self.added_vocabulary.is_special_token(token)
                     })
             })
-            .collect::<Vec<_>>();
-
-        if let Some(decoder) = &self.decoder {
-            decoder.decode(tokens)
-        } else {
-            Ok(tokens.join(" "))
-        }
+            .collect()
     }
 
     /// Decode the given ids, back to a String
@@ -947,7 +1739,8 @@ where
 /// Returning `None` means the given id is not enough to produce a chunk.
 /// This typically happens with `byte_fallback` options where some tokens do
 /// not represent valid utf-8, and only follow-up token_ids will help produce
-/// a valid chunk.
+/// a valid chunk. The ids are decoded to bytes, so the bytes that can't form valid utf-8
+/// anymore are replaced with �, whatever the policy of the decoder.
 /// ```
 /// use tokenizers::{Tokenizer, TokenizerBuilder, models::bpe::BPE, decoders::byte_fallback::ByteFallback, pre_tokenizers::byte_level::ByteLevel, normalizers::unicode::NFC};
 /// use std::collections::HashMap;
@@ -1096,8 +1889,20 @@ where
     PP: PostProcessor,
     D: Decoder,
 {
+    // The ids are decoded once, to bytes, the invalid ones being replaced in the string
+    let decode = |ids: &[u32]| -> Result<(bool, String)> {
+        let bytes = tokenizer.decode_bytes(ids, skip_special_tokens)?;
+        let incomplete = bytes.utf8_chunks().last().is_some_and(|chunk| {
+            std::str::from_utf8(chunk.invalid()).is_err_and(|e| e.error_len().is_none())
+        });
+        Ok((incomplete, String::from_utf8_lossy(&bytes).into_owned()))
+    };
     ids.push(id);
-    let string = tokenizer.decode(ids.as_slice(), skip_special_tokens)?;
+    let (incomplete, string) = decode(ids)?;
+    if incomplete {
+        // The last char is incomplete, whatever the decoder would make of it
+        return Ok(None);
+    }
     if string.len() > prefix.len() && !string.ends_with('�') {
         if !(string.starts_with(&*prefix)) {
             return Err(Box::new(DecodeStreamError::InvalidPrefix));
@@ -1105,7 +1910,7 @@ where
         let new_text = &string[prefix.len()..].to_string();
         let new_prefix_index = ids.len() - *prefix_index;
         *ids = ids.drain(*prefix_index..).collect();
-        *prefix = tokenizer.decode(ids, skip_special_tokens)?;
+        *prefix = decode(ids)?.1;
         *prefix_index = new_prefix_index;
         Ok(Some(new_text.to_string()))
     } else {
@@ -1119,15 +1924,28 @@ where
 {
     /// Tokenization logic, makes the bridge between the pre-tokenization phase and the real
     /// tokenization phase, and converting offsets back to the original referential.
-    fn do_tokenize<P: Into<PreTokenizedString>>(
+    fn do_tokenize_with<P, F>(
         &self,
         pretokenized: P,
         type_id: u32,
         word_idx: Option<u32>,
         offsets_type: OffsetType,
-    ) -> Result<Encoding> {
+        tokenize: &F,
+    ) -> Result<Encoding>
+    where
+        P: Into<PreTokenizedString>,
+        F: Fn(&str) -> Result<Vec<Token>>,
+    {
         let mut pretokenized: PreTokenizedString = pretokenized.into();
-        pretokenized.tokenize(|normalized| self.model.tokenize(normalized.get()))?;
+        pretokenized.tokenize(|normalized| {
+            let tokens = tokenize(normalized.get())?;
+            check_limit(
+                tokens.len(),
+                self.limits.max_splits_per_pre_token,
+                |count, limit| LimitError::TooManySplits { count, limit },
+            )?;
+            Ok(tokens)
+        })?;
         pretokenized.into_encoding(word_idx, type_id, offsets_type)
     }
 }
@@ -1161,11 +1979,67 @@ where
             .add_special_tokens(tokens, &self.model, self.normalizer.as_ref())
     }
 
+    /// Register the given tokens as special tokens, failing if they can't be added, like when
+    /// the normalizer fails on one of them
+    pub fn try_add_special_tokens(&mut self, tokens: &[AddedToken]) -> Result<usize> {
+        self.added_vocabulary
+            .try_add_special_tokens(tokens, &self.model, self.normalizer.as_ref())
+    }
+
     /// Add the given tokens to the added vocabulary
     pub fn add_tokens(&mut self, tokens: &[AddedToken]) -> usize {
         self.added_vocabulary
             .add_tokens(tokens, &self.model, self.normalizer.as_ref())
     }
+
+    /// Add the given tokens to the added vocabulary, failing if they can't be added, like when
+    /// the normalizer fails on one of them
+    pub fn try_add_tokens(&mut self, tokens: &[AddedToken]) -> Result<usize> {
+        self.added_vocabulary
+            .try_add_tokens(tokens, &self.model, self.normalizer.as_ref())
+    }
+
+    /// Add the given tokens to the added vocabulary, giving ids to the new ones with
+    /// `assignment`. Tokenizers built separately then agree on the ids of their added tokens,
+    /// whatever the order they were given in with [`IdAssignment::Alphabetical`], and even
+    /// when added in different batches with [`IdAssignment::HashStable`].
+    /// ```
+    /// # use tokenizers::Tokenizer;
+    /// # use tokenizers::models::bpe::BPE;
+    /// # use tokenizers::{AddedToken, IdAssignment};
+    /// let mut tokenizer = Tokenizer::new(BPE::default());
+    /// let tokens = ["<b>", "<a>"].map(|t| AddedToken::from(t, true));
+    /// assert_eq!(
+    ///     tokenizer.ids_to_add(&tokens, IdAssignment::Alphabetical)?,
+    ///     [Some(1), Some(0)]
+    /// );
+    /// tokenizer.add_tokens_with(&tokens, IdAssignment::Alphabetical)?;
+    /// assert_eq!(tokenizer.token_to_id("<a>"), Some(0));
+    /// # Ok::<(), tokenizers::Error>(())
+    /// ```
+    pub fn add_tokens_with(
+        &mut self,
+        tokens: &[AddedToken],
+        assignment: IdAssignment,
+    ) -> Result<usize> {
+        self.added_vocabulary.add_tokens_with(
+            tokens,
+            &self.model,
+            self.normalizer.as_ref(),
+            assignment,
+        )
+    }
+
+    /// The ids the given tokens would have once added with `assignment`, without adding them.
+    /// See [`AddedVocabulary::ids_to_add`]
+    pub fn ids_to_add(
+        &self,
+        tokens: &[AddedToken],
+        assignment: IdAssignment,
+    ) -> Result<Vec<Option<u32>>> {
+        self.added_vocabulary
+            .ids_to_add(tokens, &self.model, assignment)
+    }
 }
 
 impl<M, N, PT, PP, D> TokenizerImpl<M, N, PT, PP, D>
@@ -1197,12 +2071,49 @@ where
         pair_encoding: Option<Encoding>,
         add_special_tokens: bool,
     ) -> Result<Encoding> {
-        // 1. First we truncate if needed
+        self.post_process_with_params(
+            encoding,
+            pair_encoding,
+            add_special_tokens,
+            self.truncation.as_ref(),
+            self.padding.as_ref(),
+        )
+    }
+
+    fn post_process_with_params(
+        &self,
+        encoding: Encoding,
+        pair_encoding: Option<Encoding>,
+        add_special_tokens: bool,
+        truncation: Option<&TruncationParams>,
+        padding: Option<&PaddingParams>,
+    ) -> Result<Encoding> {
+        // 1. First we truncate if needed, building at most one overflowing part over the limit
+        let limited;
+        let truncation = match (truncation, self.limits.max_overflowing) {
+            (Some(trunc), Some(limit)) => {
+                let limit = limit.saturating_add(1);
+                limited = TruncationParams {
+                    max_overflowing: Some(
+                        trunc.max_overflowing.map_or(limit, |max| max.min(limit)),
+                    ),
+                    ..trunc.clone()
+                };
+                Some(&limited)
+            }
+            _ => truncation,
+        };
         let (encoding, pair_encoding) = {
-            if let Some(trunc) = &self.truncation {
-                let n_added_tokens = self.get_n_added_tokens(pair_encoding.is_some());
+            if let Some(trunc) = truncation {
+                let n_added_tokens = self.get_n_reserved_tokens(trunc, pair_encoding.is_some());
 
                 if add_special_tokens && n_added_tokens > 0 {
+                    if n_added_tokens > trunc.max_length {
+                        return Err(Box::new(TruncationParamError(format!(
+                            "truncation max length set to {}, which cannot fit the {} added special tokens",
+                            trunc.max_length, n_added_tokens
+                        ))));
+                    }
                     let params = TruncationParams {
                         max_length: trunc.max_length - n_added_tokens,
                         ..*trunc
@@ -1232,9 +2143,14 @@ where
             }
             encodings.pop().unwrap()
         };
+        check_limit(
+            final_encoding.get_overflowing().len(),
+            self.limits.max_overflowing,
+            |count, limit| LimitError::TooManyOverflowing { count, limit },
+        )?;
 
         // 3. Then we pad if needed
-        let [final_encoding] = if let Some(params) = &self.padding {
+        let [final_encoding] = if let Some(params) = padding {
             let mut arr = [final_encoding];
             pad_encodings(&mut arr, params)?;
             arr
@@ -1245,13 +2161,37 @@ where
         Ok(final_encoding)
     }
 
-    fn get_n_added_tokens(&self, is_pair: bool) -> usize {
+    /// The number of special tokens the post-processor adds to a single sequence, or to a pair
+    /// of sequences. With a [`Sequence`](crate::processors::sequence::Sequence) of
+    /// post-processors, this is the total added by all of them.
+    ///
+    /// This is how much room the truncation reserves when adding the special tokens.
+    /// ```
+    /// # use tokenizers::Tokenizer;
+    /// # use tokenizers::models::bpe::BPE;
+    /// # use tokenizers::processors::bert::BertProcessing;
+    /// let mut tokenizer = Tokenizer::new(BPE::default());
+    /// assert_eq!(tokenizer.num_special_tokens_to_add(false), 0);
+    ///
+    /// tokenizer.with_post_processor(Some(BertProcessing::default()));
+    /// assert_eq!(tokenizer.num_special_tokens_to_add(false), 2);
+    /// assert_eq!(tokenizer.num_special_tokens_to_add(true), 3);
+    /// ```
+    pub fn num_special_tokens_to_add(&self, is_pair: bool) -> usize {
         if let Some(processor) = &self.post_processor {
             processor.added_tokens(is_pair)
         } else {
             0
         }
     }
+
+    /// The number of special tokens the truncation must leave room for
+    fn get_n_reserved_tokens(&self, trunc: &TruncationParams, is_pair: bool) -> usize {
+        match trunc.special_tokens {
+            SpecialTokensTruncation::Reserve => self.num_special_tokens_to_add(is_pair),
+            SpecialTokensTruncation::Exempt => 0,
+        }
+    }
 }
 
 impl<M, N, PT, PP, D> TokenizerImpl<M, N, PT, PP, D>
@@ -1271,17 +2211,19 @@ where
     where
         E: Into<EncodeInput<'s>> + Send,
     {
-        let mut encodings = inputs
-            .into_maybe_par_iter()
-            .map(|input| self.encode(input, add_special_tokens))
-            .collect::<Result<Vec<Encoding>>>()?;
-
-        if let Some(params) = &self.padding {
-            // We do the padding here to make sure we handle the batch padding
-            pad_encodings(&mut encodings, params)?;
-        }
+        self.parallelism.install(|parallel| {
+            let mut encodings = inputs
+                .into_maybe_par_iter_cond(parallel)
+                .map(|input| self.encode(input, add_special_tokens))
+                .collect::<Result<Vec<Encoding>>>()?;
+
+            if let Some(params) = &self.padding {
+                // We do the padding here to make sure we handle the batch padding
+                pad_encodings(&mut encodings, params)?;
+            }
 
-        Ok(encodings)
+            Ok(encodings)
+        })
     }
 
     /// Encode all the sentences in parallel, using multiple threads.
@@ -1294,17 +2236,183 @@ where
     where
         E: Into<EncodeInput<'s>> + Send,
     {
-        let mut encodings = inputs
-            .into_maybe_par_iter()
-            .map(|input| self.encode_char_offsets(input, add_special_tokens))
-            .collect::<Result<Vec<Encoding>>>()?;
+        self.parallelism.install(|parallel| {
+            let mut encodings = inputs
+                .into_maybe_par_iter_cond(parallel)
+                .map(|input| self.encode_char_offsets(input, add_special_tokens))
+                .collect::<Result<Vec<Encoding>>>()?;
+
+            if let Some(params) = &self.padding {
+                // We do the padding here to make sure we handle the batch padding
+                pad_encodings(&mut encodings, params)?;
+            }
 
-        if let Some(params) = &self.padding {
-            // We do the padding here to make sure we handle the batch padding
-            pad_encodings(&mut encodings, params)?;
+            Ok(encodings)
+        })
+    }
+
+    /// Encode all the sentences in parallel, using multiple threads, with the given options
+    /// instead of the parameters of the tokenizer.
+    /// See [`encode_with_options`](Self::encode_with_options)
+    /// ```
+    /// # use tokenizers::Tokenizer;
+    /// # use tokenizers::models::bpe::BPE;
+    /// # use tokenizers::{AddedToken, EncodeOptions, PaddingParams};
+    /// # let mut tokenizer = Tokenizer::new(BPE::default());
+    /// # tokenizer.add_tokens(&["my", "name", "is", "john"].map(|t| AddedToken::from(t, false)));
+    /// let options = EncodeOptions {
+    ///     padding: Some(PaddingParams::default()),
+    ///     ..Default::default()
+    /// };
+    /// let encodings = tokenizer
+    ///     .encode_batch_with_options(vec!["my name", "is"], &options)
+    ///     .unwrap();
+    /// assert_eq!(encodings[1].get_tokens(), ["is", "[PAD]"]);
+    /// assert!(tokenizer.get_padding().is_none());
+    /// ```
+    pub fn encode_batch_with_options<'s, E>(
+        &self,
+        inputs: Vec<E>,
+        options: &EncodeOptions,
+    ) -> Result<Vec<Encoding>>
+    where
+        E: Into<EncodeInput<'s>> + Send,
+    {
+        if let Some(trunc) = &options.truncation {
+            self.check_truncation(trunc)?;
+        }
+        self.parallelism.install(|parallel| {
+            let mut encodings = inputs
+                .into_maybe_par_iter_cond(parallel)
+                .map(|input| {
+                    self.encode_with_params(
+                        input,
+                        options.add_special_tokens,
+                        options.offsets,
+                        options.truncation.as_ref(),
+                        options.padding.as_ref(),
+                    )
+                })
+                .collect::<Result<Vec<Encoding>>>()?;
+
+            if let Some(params) = &options.padding {
+                // We do the padding here to make sure we handle the batch padding
+                pad_encodings(&mut encodings, params)?;
+            }
+
+            Ok(encodings)
+        })
+    }
+
+    /// Encode all the sentences in parallel, using multiple threads, truncating each of them
+    /// to its own max length.
+    ///
+    /// Each max length, when specified, replaces the `max_length` of the truncation
+    /// parameters for the matching input, which is truncated even if the truncation is not
+    /// enabled (using the default parameters otherwise). Padding applies to the whole batch
+    /// as usual.
+    /// ```
+    /// # use tokenizers::Tokenizer;
+    /// # use tokenizers::models::bpe::BPE;
+    /// # use tokenizers::AddedToken;
+    /// # let mut tokenizer = Tokenizer::new(BPE::default());
+    /// # tokenizer.add_tokens(&["my", "name", "is", "john"].map(|t| AddedToken::from(t, false)));
+    /// let encodings = tokenizer
+    ///     .encode_batch_with_max_lengths(
+    ///         vec!["my name is john", "my name is john"],
+    ///         &[Some(2), None],
+    ///         false,
+    ///     )
+    ///     .unwrap();
+    /// assert_eq!(encodings[0].get_tokens(), ["my", "name"]);
+    /// assert_eq!(encodings[1].get_tokens(), ["my", "name", "is", "john"]);
+    /// ```
+    pub fn encode_batch_with_max_lengths<'s, E>(
+        &self,
+        inputs: Vec<E>,
+        max_lengths: &[Option<usize>],
+        add_special_tokens: bool,
+    ) -> Result<Vec<Encoding>>
+    where
+        E: Into<EncodeInput<'s>> + Send,
+    {
+        self.encode_batch_truncated(inputs, max_lengths, add_special_tokens, OffsetType::Byte)
+    }
+
+    /// Encode all the sentences in parallel, using multiple threads, truncating each of them
+    /// to its own max length. See [`encode_batch_with_max_lengths`](Self::encode_batch_with_max_lengths).
+    /// The offsets on each `Encoding` will be relative to chars instead of bytes.
+    pub fn encode_batch_char_offsets_with_max_lengths<'s, E>(
+        &self,
+        inputs: Vec<E>,
+        max_lengths: &[Option<usize>],
+        add_special_tokens: bool,
+    ) -> Result<Vec<Encoding>>
+    where
+        E: Into<EncodeInput<'s>> + Send,
+    {
+        self.encode_batch_truncated(inputs, max_lengths, add_special_tokens, OffsetType::Char)
+    }
+
+    fn encode_batch_truncated<'s, E>(
+        &self,
+        inputs: Vec<E>,
+        max_lengths: &[Option<usize>],
+        add_special_tokens: bool,
+        offsets_type: OffsetType,
+    ) -> Result<Vec<Encoding>>
+    where
+        E: Into<EncodeInput<'s>> + Send,
+    {
+        if max_lengths.len() != inputs.len() {
+            return Err(Box::new(TruncationParamError(format!(
+                "Got {} max lengths for {} inputs",
+                max_lengths.len(),
+                inputs.len()
+            ))));
         }
+        // Check every distinct length once, before encoding anything
+        let base = self.truncation.clone().unwrap_or_default();
+        let mut params = AHashMap::new();
+        for max_length in max_lengths.iter().flatten() {
+            if !params.contains_key(max_length) {
+                let trunc = TruncationParams {
+                    max_length: *max_length,
+                    ..base
+                };
+                self.check_truncation(&trunc)?;
+                params.insert(*max_length, trunc);
+            }
+        }
+
+        self.parallelism.install(|parallel| {
+            let mut encodings = inputs
+                .into_iter()
+                .zip(max_lengths)
+                .collect::<Vec<_>>()
+                .into_maybe_par_iter_cond(parallel)
+                .map(|(input, max_length)| {
+                    let truncation = match max_length {
+                        Some(max_length) => params.get(max_length),
+                        None => self.truncation.as_ref(),
+                    };
+                    self.encode_with_params(
+                        input,
+                        add_special_tokens,
+                        offsets_type,
+                        truncation,
+                        self.padding.as_ref(),
+                    )
+                })
+                .collect::<Result<Vec<Encoding>>>()?;
+
+            if let Some(params) = &self.padding {
+                // We do the padding here to make sure we handle the batch padding
+                pad_encodings(&mut encodings, params)?;
+            }
 
-        Ok(encodings)
+            Ok(encodings)
+        })
     }
 
     /// Encode all the sentences in parallel, using multiple threads
@@ -1316,17 +2424,85 @@ where
     where
         E: Into<EncodeInput<'s>> + Send,
     {
-        let mut encodings = inputs
-            .into_maybe_par_iter()
-            .map(|input| self.encode_fast(input, add_special_tokens))
-            .collect::<Result<Vec<Encoding>>>()?;
+        self.parallelism.install(|parallel| {
+            let mut encodings = inputs
+                .into_maybe_par_iter_cond(parallel)
+                .map(|input| self.encode_fast(input, add_special_tokens))
+                .collect::<Result<Vec<Encoding>>>()?;
+
+            if let Some(params) = &self.padding {
+                // We do the padding here to make sure we handle the batch padding
+                pad_encodings(&mut encodings, params)?;
+            }
+
+            Ok(encodings)
+        })
+    }
+
+    /// Encode all the inputs in parallel, returning every window of every input as a single
+    /// list, along with the index of the input each window comes from.
+    ///
+    /// When truncation is enabled, each input is chunked into windows of at most `max_length`
+    /// tokens, consecutive windows overlapping by `stride` tokens. The windows of an input
+    /// immediately follow each other, and their offsets all refer to the original input, which
+    /// makes it possible to map any token of any window back to the document it comes from.
+    /// Padding, if enabled, is applied across all the windows.
+    pub fn encode_batch_windows<'s, E>(
+        &self,
+        inputs: Vec<E>,
+        add_special_tokens: bool,
+    ) -> Result<(Vec<Encoding>, Vec<usize>)>
+    where
+        E: Into<EncodeInput<'s>> + Send,
+    {
+        self.parallelism.install(|parallel| {
+            let encodings = inputs
+                .into_maybe_par_iter_cond(parallel)
+                .map(|input| self.encode(input, add_special_tokens))
+                .collect::<Result<Vec<Encoding>>>()?;
+
+            self.flatten_windows(encodings)
+        })
+    }
+
+    /// Encode all the inputs in parallel, returning every window of every input as a single
+    /// list, along with the index of the input each window comes from.
+    /// The offsets on each `Encoding` will be relative to chars instead of bytes.
+    pub fn encode_batch_windows_char_offsets<'s, E>(
+        &self,
+        inputs: Vec<E>,
+        add_special_tokens: bool,
+    ) -> Result<(Vec<Encoding>, Vec<usize>)>
+    where
+        E: Into<EncodeInput<'s>> + Send,
+    {
+        self.parallelism.install(|parallel| {
+            let encodings = inputs
+                .into_maybe_par_iter_cond(parallel)
+                .map(|input| self.encode_char_offsets(input, add_special_tokens))
+                .collect::<Result<Vec<Encoding>>>()?;
+
+            self.flatten_windows(encodings)
+        })
+    }
+
+    /// Flatten the given encodings and their overflowing parts, building the
+    /// `overflow_to_sample_mapping` on the way
+    fn flatten_windows(&self, encodings: Vec<Encoding>) -> Result<(Vec<Encoding>, Vec<usize>)> {
+        let mut windows = vec![];
+        let mut overflow_to_sample_mapping = vec![];
+        for (sample, encoding) in encodings.into_iter().enumerate() {
+            let encoding_windows = encoding.into_windows();
+            overflow_to_sample_mapping.extend(std::iter::repeat_n(sample, encoding_windows.len()));
+            windows.extend(encoding_windows);
+        }
 
         if let Some(params) = &self.padding {
             // We do the padding here to make sure we handle the batch padding
-            pad_encodings(&mut encodings, params)?;
+            pad_encodings(&mut windows, params)?;
         }
 
-        Ok(encodings)
+        Ok((windows, overflow_to_sample_mapping))
     }
 
     /// Decode all sentences in parallel
@@ -1338,10 +2514,146 @@ where
     where
         M: Send + Sync,
     {
-        sentences
-            .into_maybe_par_iter()
-            .map(|sentence| self.decode(sentence, skip_special_tokens))
-            .collect()
+        self.parallelism.install(|parallel| {
+            sentences
+                .into_maybe_par_iter_cond(parallel)
+                .map(|sentence| self.decode(sentence, skip_special_tokens))
+                .collect()
+        })
+    }
+
+    /// Convert all the given ids to their corresponding tokens, in parallel
+    pub fn ids_to_tokens(&self, ids: &[u32]) -> Vec<Option<String>> {
+        self.parallelism.install(|parallel| {
+            ids.into_maybe_par_iter_cond(parallel)
+                .map(|id| self.id_to_token(*id))
+                .collect()
+        })
+    }
+
+    /// Normalize all the given texts in parallel, without encoding them. Only the normalizer
+    /// runs, on the whole texts, so the added tokens get normalized like everything else.
+    /// ```
+    /// # use tokenizers::Tokenizer;
+    /// # use tokenizers::models::bpe::BPE;
+    /// # use tokenizers::normalizers::utils::Lowercase;
+    /// let mut tokenizer = Tokenizer::new(BPE::default());
+    /// tokenizer.with_normalizer(Some(Lowercase));
+    /// let normalized = tokenizer.normalize_batch(&["Hello", "THERE"]).unwrap();
+    /// assert_eq!(normalized[1].get(), "there");
+    /// assert_eq!(normalized[1].get_original(), "THERE");
+    /// ```
+    pub fn normalize_batch<S>(&self, texts: &[S]) -> Result<Vec<NormalizedString>>
+    where
+        S: AsRef<str> + Sync,
+    {
+        self.parallelism.install(|parallel| {
+            texts
+                .into_maybe_par_iter_cond(parallel)
+                .map(|text| self.do_normalize(text.as_ref()))
+                .collect()
+        })
+    }
+
+    /// Normalize and pre-tokenize all the given texts in parallel, without encoding them.
+    /// The pre-tokens are the splits the model would get when encoding, added tokens included,
+    /// with their offsets of type `offset_type` in the original texts.
+    /// ```
+    /// # use tokenizers::Tokenizer;
+    /// # use tokenizers::models::bpe::BPE;
+    /// # use tokenizers::pre_tokenizers::whitespace::Whitespace;
+    /// # use tokenizers::{AddedToken, OffsetType};
+    /// let mut tokenizer = Tokenizer::new(BPE::default());
+    /// tokenizer.with_pre_tokenizer(Some(Whitespace {}));
+    /// tokenizer.add_special_tokens(&[AddedToken::from("[SEP]", true)]);
+    /// let pre_tokens = tokenizer
+    ///     .pre_tokenize_batch(&["Hé there[SEP]"], OffsetType::Char)
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     pre_tokens[0],
+    ///     vec![
+    ///         ("Hé".to_string(), (0, 2)),
+    ///         ("there".to_string(), (3, 8)),
+    ///         ("[SEP]".to_string(), (8, 13)),
+    ///     ]
+    /// );
+    /// ```
+    pub fn pre_tokenize_batch<S>(
+        &self,
+        texts: &[S],
+        offset_type: OffsetType,
+    ) -> Result<Vec<Vec<(String, Offsets)>>>
+    where
+        S: AsRef<str> + Sync,
+    {
+        self.parallelism.install(|parallel| {
+            texts
+                .into_maybe_par_iter_cond(parallel)
+                .map(|text| {
+                    Ok(self
+                        .extract_and_pre_tokenize(text.as_ref())?
+                        .get_splits(OffsetReferential::Original, offset_type)
+                        .into_iter()
+                        .map(|(split, offsets, _)| (split.to_owned(), offsets))
+                        .collect())
+                })
+                .collect()
+        })
+    }
+
+    /// Extract the added tokens of the given sequence, then normalize and pre-tokenize the rest
+    fn extract_and_pre_tokenize(&self, sequence: &str) -> Result<PreTokenizedString> {
+        let normalized = self
+            .added_vocabulary
+            .extract_and_normalize(self.normalizer.as_ref(), sequence);
+        self.do_pre_tokenize(normalized)
+    }
+
+    /// Normalize and pre-tokenize the given sequence into the words fed to the trainers
+    fn pre_tokenize_for_training(&self, sequence: &str) -> Result<Vec<String>> {
+        Ok(self
+            .extract_and_pre_tokenize(sequence)?
+            .get_splits(OffsetReferential::Original, OffsetType::Byte)
+            .into_iter()
+            .map(|(s, _, _)| s.to_owned())
+            .collect())
+    }
+
+    /// Estimate the resources needed to train our Model with the given Trainer on a corpus of
+    /// `corpus_bytes` bytes, from a sample of this corpus. Nothing gets trained, see
+    /// [`Trainer::estimate`]
+    /// ```
+    /// # use tokenizers::models::bpe::{BpeTrainer, BPE};
+    /// # use tokenizers::models::TrainerWrapper;
+    /// # use tokenizers::Tokenizer;
+    /// let tokenizer = Tokenizer::new(BPE::default());
+    /// let trainer: TrainerWrapper = BpeTrainer::builder().show_progress(false).build().into();
+    /// let sample = ["A first sequence", "and a second one"];
+    /// let estimate = tokenizer
+    ///     .estimate_training(&trainer, sample.iter(), 1_000_000_000_000)
+    ///     .unwrap();
+    /// println!(
+    ///     "{} unique words, {} GB, {:?}",
+    ///     estimate.unique_words,
+    ///     estimate.peak_memory / 1_000_000_000,
+    ///     estimate.training_time
+    /// );
+    /// ```
+    pub fn estimate_training<T, I, S>(
+        &self,
+        trainer: &T,
+        sample: I,
+        corpus_bytes: u64,
+    ) -> Result<TrainingEstimate>
+    where
+        T: Trainer<Model = M> + Clone,
+        M: Clone,
+        I: Iterator<Item = S>,
+        S: AsRef<str>,
+    {
+        trainer.estimate(&self.model, sample, corpus_bytes, |seq| {
+            self.pre_tokenize_for_training(seq)
+        })
     }
 
     /// Train our Model from files
@@ -1392,24 +2704,14 @@ where
                             progress.inc(s.len() as u64)
                         }
                     }),
-                    |seq| {
-                        let normalized = self
-                            .added_vocabulary
-                            .extract_and_normalize(self.normalizer.as_ref(), seq.as_ref());
-                        let pre_tokenized = self.do_pre_tokenize(normalized)?;
-                        Ok(pre_tokenized
-                            .get_splits(OffsetReferential::Original, OffsetType::Byte)
-                            .into_iter()
-                            .map(|(s, _, _)| s.to_owned())
-                            .collect())
-                    },
+                    |seq| self.pre_tokenize_for_training(seq),
                 )?;
 
                 if let Some(pbar) = progress {
                     pbar.finish();
                 }
                 let special_tokens = trainer.train(&mut self.model)?;
-                self.add_special_tokens(&special_tokens);
+                self.try_add_special_tokens(&special_tokens)?;
 
                 Ok(())
             },
@@ -1424,20 +2726,7 @@ where
         I: Iterator<Item = S> + Send,
         S: AsRef<str> + Send,
     {
-        let (lower, upper) = sequences.size_hint();
-        let len = upper.unwrap_or(lower) as u64;
-        let progress = if trainer.should_show_progress() {
-            let progress = ProgressBar::new(len);
-            progress.set_style(
-                ProgressStyle::default_bar()
-                    .template("[{elapsed_precise}] {msg:<30!} {wide_bar} {pos:<9!}/{len:>9!}")
-                    .expect("Invalid progress template"),
-            );
-            progress.set_message("Pre-processing sequences");
-            Some(progress)
-        } else {
-            None
-        };
+        let progress = sequences_progress(trainer, sequences.size_hint());
 
         trainer.feed(
             sequences.inspect(|_s| {
@@ -1445,27 +2734,107 @@ where
                     progress.inc(1)
                 }
             }),
-            |seq| {
-                let normalized = self
-                    .added_vocabulary
-                    .extract_and_normalize(self.normalizer.as_ref(), seq.as_ref());
-                let pre_tokenized = self.do_pre_tokenize(normalized)?;
-                Ok(pre_tokenized
-                    .get_splits(OffsetReferential::Original, OffsetType::Byte)
-                    .into_iter()
-                    .map(|(s, _, _)| s.to_owned())
-                    .collect())
-            },
+            |seq| self.pre_tokenize_for_training(seq),
         )?;
         if let Some(pbar) = progress {
             pbar.finish();
         }
 
         let special_tokens = trainer.train(&mut self.model)?;
-        self.add_special_tokens(&special_tokens);
+        self.try_add_special_tokens(&special_tokens)?;
 
         Ok(self)
     }
+
+    /// Train our Model on sequences carrying an optional tag, like their language or their
+    /// domain, and report what the sequences of each tag contributed to the trained model, to
+    /// balance the corpora in the mix. The statistics come in order of first appearance of
+    /// the tags.
+    ///
+    /// The sequences get pre-processed in parallel by chunks, counting their words by tag,
+    /// and these words are then fed to the trainer.
+    /// ```
+    /// # use tokenizers::models::bpe::{BpeTrainer, BPE};
+    /// # use tokenizers::models::TrainerWrapper;
+    /// # use tokenizers::pre_tokenizers::whitespace::Whitespace;
+    /// # use tokenizers::Tokenizer;
+    /// let mut tokenizer = Tokenizer::new(BPE::default());
+    /// tokenizer.with_pre_tokenizer(Some(Whitespace {}));
+    /// let mut trainer: TrainerWrapper = BpeTrainer::builder().show_progress(false).build().into();
+    /// let sequences = vec![
+    ///     (Some("en"), "the cat and the hat"),
+    ///     (Some("fr"), "le chat et le chapeau"),
+    ///     (None, "the chat"),
+    /// ];
+    /// let statistics = tokenizer
+    ///     .train_tagged(&mut trainer, sequences.into_iter())
+    ///     .unwrap();
+    /// assert_eq!(statistics[0].tag.as_deref(), Some("en"));
+    /// assert_eq!(statistics[1].words, 5);
+    /// assert_eq!(statistics[2].tag, None);
+    /// ```
+    pub fn train_tagged<T, I, G, S>(
+        &mut self,
+        trainer: &mut T,
+        sequences: I,
+    ) -> Result<Vec<TagStatistics>>
+    where
+        T: Trainer<Model = M> + Sync,
+        I: Iterator<Item = (Option<G>, S)> + Send,
+        G: AsRef<str> + Send,
+        S: AsRef<str> + Send,
+    {
+        let progress = sequences_progress(trainer, sequences.size_hint());
+
+        let mut counter = TagCounter::default();
+        let tokenizer = &*self;
+        let mut sequences = sequences;
+        ResultShunt::process(
+            std::iter::from_fn(|| {
+                let chunk: Vec<_> = sequences.by_ref().take(TAG_CHUNK_SIZE).collect();
+                if chunk.is_empty() {
+                    return None;
+                }
+                if let Some(progress) = &progress {
+                    progress.inc(chunk.len() as u64)
+                }
+                let processed = TagCounter::process(chunk, |sequence| {
+                    tokenizer.pre_tokenize_for_training(sequence)
+                });
+                Some(processed.map(|(chunk_counter, words)| {
+                    counter.merge(chunk_counter);
+                    words
+                }))
+            }),
+            |words| trainer.feed(words.flatten(), |word| Ok(vec![word.to_owned()])),
+        )??;
+        if let Some(pbar) = progress {
+            pbar.finish();
+        }
+
+        let special_tokens = trainer.train(&mut self.model)?;
+        self.try_add_special_tokens(&special_tokens)?;
+
+        counter.statistics(&self.model)
+    }
+}
+
+/// The progress bar of the pre-processing of the training sequences, if the trainer shows one
+fn sequences_progress<T: Trainer>(
+    trainer: &T,
+    (lower, upper): (usize, Option<usize>),
+) -> Option<ProgressBar> {
+    if !trainer.should_show_progress() {
+        return None;
+    }
+    let progress = ProgressBar::new(upper.unwrap_or(lower) as u64);
+    progress.set_style(
+        ProgressStyle::default_bar()
+            .template("[{elapsed_precise}] {msg:<30!} {wide_bar} {pos:<9!}/{len:>9!}")
+            .expect("Invalid progress template"),
+    );
+    progress.set_message("Pre-processing sequences");
+    Some(progress)
 }
 
 impl<M, N, PT, PP, D> std::str::FromStr for TokenizerImpl<M, N, PT, PP, D>
@@ -1564,4 +2933,33 @@ where
 
         Ok(())
     }
+
+    /// Serialize the current tokenizer as a pretty String that only depends on its content,
+    /// which keeps the diffs minimal when storing it in a version control system.
+    ///
+    /// The keys of every object are sorted, including the tokens of the vocabularies, and a
+    /// `schema_version` is embedded. The result can be loaded like any other serialization.
+    pub fn to_string_pretty_stable(&self) -> Result<String> {
+        let mut value = serde_json::to_value(self)?;
+        if let serde_json::Value::Object(map) = &mut value {
+            map.insert(
+                versioning::SCHEMA_VERSION.into(),
+                <Self as versioning::Versioned>::SCHEMA_VERSION.into(),
+            );
+        }
+        let mut serialized = serde_json::to_string_pretty(&serialization::sort_keys(value))?;
+        serialized.push('\n');
+        Ok(serialized)
+    }
+
+    /// Save the current tokenizer at the given path, using
+    /// [`to_string_pretty_stable`](Self::to_string_pretty_stable)
+    pub fn save_pretty_stable<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let serialized = self.to_string_pretty_stable()?;
+
+        let mut file = File::create(path)?;
+        file.write_all(serialized.as_bytes())?;
+
+        Ok(())
+    }
 }
@@ -1,7 +1,13 @@
+use super::alignments::Alignments;
 use crate::pattern::Pattern;
 use crate::{Offsets, Result};
+use compact_str::CompactString;
 use std::ops::{Bound, RangeBounds};
 use unicode_normalization_alignments::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
+
+// Re-exported for backward compatibility, these now live in `utils::offsets`
+pub use crate::utils::offsets::{bytes_to_char, char_to_bytes, get_range_of};
 
 use serde::{Deserialize, Serialize};
 
@@ -104,12 +110,12 @@ impl std::fmt::Display for SplitDelimiterBehavior {
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
 pub struct NormalizedString {
     /// The original version of the string, before any modification
-    original: String,
+    original: CompactString,
     /// The normalized version of the string, after all modifications
-    normalized: String,
+    normalized: CompactString,
     /// Mapping from normalized string to original one: (start, end) for each
     /// byte of the normalized string
-    alignments: Vec<(usize, usize)>,
+    alignments: Alignments,
     /// If this NormalizedString is a slice of a bigger one, we keep the track
     /// of the missing part, so that we can still give offsets from this original
     /// string.
@@ -125,9 +131,9 @@ impl NormalizedString {
         original_shift: usize,
     ) -> Self {
         Self {
-            original,
-            normalized,
-            alignments,
+            original: original.into(),
+            normalized: normalized.into(),
+            alignments: alignments.into(),
             original_shift,
         }
     }
@@ -210,7 +216,7 @@ impl NormalizedString {
                 _ => None,
             }
         } else {
-            self.alignments.get(target).and_then(expand_alignments)
+            self.alignments.expand(target)
         }
     }
 
@@ -293,13 +299,7 @@ impl NormalizedString {
                 .unwrap_or_default()
                 .into(),
             normalized: self.get_range(full_range).unwrap_or_default().into(),
-            alignments: self
-                .alignments
-                .get(normalized_range)?
-                .to_vec()
-                .iter()
-                .map(|(start, end)| (start - n_shift, end - n_shift))
-                .collect(),
+            alignments: self.alignments.slice(normalized_range, n_shift)?,
             original_shift: self.original_shift + original_range.start,
         })
     }
@@ -343,7 +343,7 @@ impl NormalizedString {
             .sum();
 
         let mut offset = (initial_removed + n_range.start) as isize;
-        let mut alignments = Vec::with_capacity(n_range.len());
+        let mut alignments = Alignments::default();
         trace!("=> Applying transformations");
         let normalized = dest
             .into_iter()
@@ -368,10 +368,10 @@ impl NormalizedString {
                     } else {
                         // This is a newly inserted character, so it shares the same alignment
                         // than the previous one
-                        self.alignments[idx - 1]
+                        self.alignments.at(idx - 1)
                     }
                 } else {
-                    self.alignments[idx]
+                    self.alignments.at(idx)
                 };
 
                 // If we are replacing a character, find it and compute the change in size
@@ -412,20 +412,8 @@ impl NormalizedString {
             })
             .collect::<String>();
 
-        self.alignments.splice(n_range.clone(), alignments);
-
-        // This bounds check already happens above (`self.normalized[n_range.clone()]`), but future
-        // code could change to mutate `self` or `self.normalized` in the interim.
-        // Perform it again and hope the optimizer collapses it.
-        assert!(self.normalized.get(n_range.clone()).is_some());
-        unsafe {
-            self.normalized
-                // Safety: This is safe as long as we do not splice across a
-                // UTF-8 character, and we only add UTF-8 text. `normalized` is a String
-                // so the latter is trivially true, and we assert for the former above.
-                .as_mut_vec()
-                .splice(n_range, normalized.bytes());
-        }
+        self.alignments.splice(n_range.clone(), &alignments);
+        self.normalized.replace_range(n_range, &normalized);
     }
 
     /// Applies transformations to the current normalized version of the string,
@@ -472,30 +460,21 @@ impl NormalizedString {
 
     /// Applies filtering over our characters
     pub fn filter<F: Fn(char) -> bool>(&mut self, keep: F) -> &mut Self {
-        let mut removed: isize = 0;
-        let mut removed_start: usize = 0;
-
-        let mut transforms = Vec::with_capacity(self.normalized.len());
-        let mut last_c = None;
-        for c in self.normalized.chars() {
-            if keep(c) {
-                match last_c {
-                    Some(lc) => {
-                        transforms.push((lc, -removed));
-                    }
-                    None => {
-                        removed_start = removed as usize;
-                    }
-                }
-                last_c = Some(c);
-                removed = 0;
-            } else {
-                removed += 1;
-            }
-        }
-        if let Some(lc) = last_c {
-            transforms.push((lc, -removed));
-        }
+        let (transforms, removed_start) =
+            filter_transforms(self.normalized.chars().map(|c| (c, keep(c))));
+        self.transform(transforms, removed_start);
+        self
+    }
+
+    /// Applies filtering over our grapheme clusters, as defined by UAX #29. Unlike
+    /// [`filter`](Self::filter), a cluster is either kept or removed as a whole, so a combining
+    /// mark can't lose its base character.
+    pub fn filter_graphemes<F: Fn(&str) -> bool>(&mut self, keep: F) -> &mut Self {
+        let (transforms, removed_start) =
+            filter_transforms(self.normalized.graphemes(true).flat_map(|grapheme| {
+                let keep = keep(grapheme);
+                grapheme.chars().map(move |c| (c, keep))
+            }));
         self.transform(transforms, removed_start);
         self
     }
@@ -537,6 +516,27 @@ impl NormalizedString {
         self
     }
 
+    /// Map our grapheme clusters, as defined by UAX #29. Each cluster can be replaced by any
+    /// number of characters, which are all aligned with the whole original cluster.
+    pub fn map_graphemes<F: Fn(&str) -> String>(&mut self, map: F) -> &mut Self {
+        let mut new_normalized = CompactString::with_capacity(self.normalized.len());
+        let mut new_alignments = Alignments::default();
+        for (start, grapheme) in self.normalized.grapheme_indices(true) {
+            let range = start..start + grapheme.len();
+            let mapped = map(grapheme);
+            if mapped == grapheme {
+                new_alignments.extend_from(&self.alignments, range, 0);
+            } else if let Some(aligned) = self.alignments.expand(range) {
+                new_alignments.extend((0..mapped.len()).map(|_| (aligned.start, aligned.end)));
+            }
+            new_normalized.push_str(&mapped);
+        }
+
+        self.normalized = new_normalized;
+        self.alignments = new_alignments;
+        self
+    }
+
     /// Calls the given function for each characters
     pub fn for_each<F: FnMut(char)>(&self, foreach: F) -> &Self {
         self.normalized.chars().for_each(foreach);
@@ -569,8 +569,8 @@ impl NormalizedString {
 
     /// Replace anything that matches the pattern with the given content.
     pub fn replace<P: Pattern>(&mut self, pattern: P, content: &str) -> Result<()> {
-        let mut new_normalized = String::with_capacity(self.normalized.len()); // Initially allocate for the input size
-        let mut new_alignments: Vec<(usize, usize)> = Vec::with_capacity(self.alignments.len());
+        let mut new_normalized = CompactString::with_capacity(self.normalized.len()); // Initially allocate for the input size
+        let mut new_alignments = Alignments::default();
         let mut last_end = 0; // Keep track of the last end position
 
         pattern
@@ -595,7 +595,7 @@ impl NormalizedString {
 
                     // Copy the part of the string that is before the match
                     new_normalized.push_str(&self.normalized[last_end..start]);
-                    new_alignments.extend(self.alignments[last_end..start].iter().cloned());
+                    new_alignments.extend_from(&self.alignments, last_end..start, 0);
 
                     let n_range = Range::Normalized(range).into_full_range(self.len());
 
@@ -625,10 +625,10 @@ impl NormalizedString {
                                 } else {
                                     // This is a newly inserted character, so it shares the same alignment
                                     // than the previous one
-                                    self.alignments[idx - 1]
+                                    self.alignments.at(idx - 1)
                                 }
                             } else {
-                                self.alignments[idx]
+                                self.alignments.at(idx)
                             };
 
                             // If we are replacing a character, find it and compute the change in size
@@ -667,7 +667,7 @@ impl NormalizedString {
 
         // Copy the remaining part of the input
         new_normalized.push_str(&self.normalized[last_end..]);
-        new_alignments.extend(&self.alignments[last_end..]);
+        new_alignments.extend_from(&self.alignments, last_end..self.alignments.len(), 0);
 
         self.normalized = new_normalized;
         self.alignments = new_alignments;
@@ -858,15 +858,15 @@ impl NormalizedString {
         let mut alignments_original = Vec::with_capacity(self.original.len());
 
         // Eventual gap before first group
-        let start = self.alignments[0].0;
+        let start = self.alignments.at(0).0;
         if start != 0 {
             alignments_original.extend(vec![(0, 0); start]);
         }
 
-        let mut last = (&self.alignments[0].0, &self.alignments[0].1);
+        let mut last = self.alignments.at(0);
         let mut offset = 0;
         let mut length = 0;
-        for (start, end) in &self.alignments {
+        for (start, end) in self.alignments.iter() {
             if last == (start, end) {
                 // This is the same group
                 length += 1;
@@ -902,113 +902,45 @@ impl NormalizedString {
     }
 }
 
-/// Returns the range covered by a slice of alignments
-fn expand_alignments(alignments: &[(usize, usize)]) -> Option<std::ops::Range<usize>> {
-    if alignments.is_empty() {
-        None
-    } else {
-        let start = alignments[0].0;
-        let end = alignments[alignments.len() - 1].1;
-        Some(start..end)
-    }
-}
-
-/// Returns a range of the given string slice, by indexing chars instead of bytes
-pub fn get_range_of<T: RangeBounds<usize>>(s: &str, range: T) -> Option<&str> {
-    let len = s.chars().count();
-    let start = match range.start_bound() {
-        Bound::Unbounded => 0,
-        Bound::Included(i) => *i,
-        Bound::Excluded(i) => *i + 1,
-    };
-    let end = match range.end_bound() {
-        Bound::Unbounded => len,
-        Bound::Included(i) => *i + 1,
-        Bound::Excluded(i) => *i,
-    };
-
-    if start == 0 && end == 0 {
-        Some(&s[0..0])
-    } else if start >= len || end > len || start >= end {
-        None
-    } else {
-        let start_b = s.char_indices().map(|(i, _)| i).nth(start).unwrap_or(0);
-        let end_b = s.char_indices().map(|(i, _)| i).nth(end).unwrap_or(s.len());
-        Some(&s[start_b..end_b])
-    }
-}
-
-/// Convert the given range from bytes to char
-pub fn bytes_to_char(s: &str, range: std::ops::Range<usize>) -> Option<std::ops::Range<usize>> {
-    let (mut start, mut end) = if range == (0..0) {
-        (Some(0), Some(0))
-    } else {
-        (None, None)
-    };
-
-    s.char_indices()
-        .enumerate()
-        .take_while(|(_, (b, _))| *b <= range.end)
-        .filter(|(_, (b, _))| *b >= range.start)
-        .for_each(|(i, (b, c))| {
-            if b == range.start {
-                start = Some(i);
-            }
-            if b == range.end {
-                end = Some(i);
-            }
-            if b + c.len_utf8() == range.end {
-                end = Some(i + 1);
-            }
-        });
-
-    Some(start?..end?)
-}
-
-/// Convert the given range from char to bytes
-pub fn char_to_bytes(s: &str, range: std::ops::Range<usize>) -> Option<std::ops::Range<usize>> {
-    let (mut start, mut end) = if range == (0..0) {
-        (Some(0), Some(0))
-    } else {
-        (None, None)
-    };
-
-    if range.start == range.end {
-        s.char_indices()
-            .skip(range.start)
-            .take(1)
-            .for_each(|(b, _)| {
-                start = Some(b);
-                end = Some(b);
-            });
-    } else {
-        s.char_indices()
-            .skip(range.start)
-            .take(range.end - range.start)
-            .for_each(|(b, c)| {
-                if start.is_none() {
-                    start = Some(b);
+/// Build the transformations removing the chars that must not be kept, along with the number of
+/// chars removed at the very beginning
+fn filter_transforms<I>(chars: I) -> (Vec<(char, isize)>, usize)
+where
+    I: Iterator<Item = (char, bool)>,
+{
+    let mut removed: isize = 0;
+    let mut removed_start: usize = 0;
+
+    let mut transforms = Vec::with_capacity(chars.size_hint().0);
+    let mut last_c = None;
+    for (c, keep) in chars {
+        if keep {
+            match last_c {
+                Some(lc) => {
+                    transforms.push((lc, -removed));
                 }
-                end = Some(b + c.len_utf8());
-            });
+                None => {
+                    removed_start = removed as usize;
+                }
+            }
+            last_c = Some(c);
+            removed = 0;
+        } else {
+            removed += 1;
+        }
     }
-
-    Some(start?..end?)
+    if let Some(lc) = last_c {
+        transforms.push((lc, -removed));
+    }
+    (transforms, removed_start)
 }
 
 impl From<String> for NormalizedString {
     fn from(s: String) -> Self {
-        let alignments = s
-            .char_indices()
-            .flat_map(|(b, c)| {
-                let len = c.len_utf8();
-                (0..len).map(move |_| (b, b + len))
-            })
-            .collect::<Vec<_>>();
         Self {
-            original: s.clone(),
-            normalized: s,
-            alignments,
+            original: s.as_str().into(),
+            alignments: Alignments::identity(&s),
+            normalized: s.into(),
             original_shift: 0,
         }
     }
@@ -1016,7 +948,13 @@ impl From<String> for NormalizedString {
 
 impl From<&str> for NormalizedString {
     fn from(s: &str) -> Self {
-        Self::from(s.to_owned())
+        // Short strings are stored inline, so this does not allocate for them
+        Self {
+            original: s.into(),
+            normalized: s.into(),
+            alignments: Alignments::identity(s),
+            original_shift: 0,
+        }
     }
 }
 
@@ -1040,12 +978,22 @@ mod tests {
         assert_eq!(len, Some(4)); // 7 - 3 = 4
     }
 
+    #[test]
+    fn short_strings_stay_inline() {
+        let mut n = NormalizedString::from("Query");
+        n.lowercase();
+        assert_eq!(n.get(), "query");
+        assert!(!n.original.is_heap_allocated());
+        assert!(!n.normalized.is_heap_allocated());
+        assert_eq!(n.alignments, Alignments::identity("query"));
+    }
+
     #[test]
     fn nfd_adds_new_chars() {
         let mut n = NormalizedString::from("élégant");
         n.nfd();
         assert_eq!(
-            &n.alignments,
+            &n.alignments.to_vec(),
             &[
                 (0, 2),
                 (0, 2),
@@ -1084,7 +1032,7 @@ mod tests {
         assert_eq!(n.get(), "elegant");
 
         assert_eq!(
-            &n.alignments,
+            &n.alignments.to_vec(),
             &[(0, 2), (2, 3), (3, 5), (5, 6), (6, 7), (7, 8), (8, 9)]
         );
         assert_eq!(
@@ -1109,7 +1057,7 @@ mod tests {
         n.filter(|c| c != 'n');
         assert_eq!(n.get(), "élégat");
         assert_eq!(
-            &n.alignments,
+            &n.alignments.to_vec(),
             &[
                 (0, 2),
                 (0, 2),
@@ -1138,13 +1086,71 @@ mod tests {
         );
     }
 
+    #[test]
+    fn remove_graphemes() {
+        let mut n = NormalizedString::from("e\u{301}te\u{301}s");
+        n.filter_graphemes(|g| g != "e\u{301}");
+        assert_eq!(n.get(), "ts");
+        assert_eq!(n.get_range_original(Range::Normalized(0..1)), Some("t"));
+        assert_eq!(n.get_range_original(Range::Normalized(1..2)), Some("s"));
+
+        // Filtering the chars would leave the orphan marks
+        let mut n = NormalizedString::from("e\u{301}te\u{301}s");
+        n.filter(|c| c != 'e');
+        assert_eq!(n.get(), "\u{301}t\u{301}s");
+    }
+
+    #[test]
+    fn map_graphemes() {
+        let mut n = NormalizedString::from("e\u{301}te\u{301}");
+        n.map_graphemes(|g| match g {
+            "e\u{301}" => "\u{e9}".into(),
+            g => g.repeat(2),
+        });
+        assert_eq!(n.get(), "\u{e9}tt\u{e9}");
+        assert_eq!(
+            n.get_range_original(Range::Normalized(0..2)),
+            Some("e\u{301}")
+        );
+        assert_eq!(n.get_range_original(Range::Normalized(2..4)), Some("t"));
+        assert_eq!(
+            n.get_range_original(Range::Normalized(4..6)),
+            Some("e\u{301}")
+        );
+
+        let mut n = NormalizedString::from("e\u{301}te\u{301}");
+        n.map_graphemes(|g| if g == "t" { "".into() } else { "e".into() });
+        assert_eq!(n.get(), "ee");
+        assert_eq!(
+            n.get_range_original(Range::Normalized(0..1)),
+            Some("e\u{301}")
+        );
+        // Like with `filter`, the removed clusters are skipped
+        assert_eq!(
+            n.get_range_original(Range::Normalized(0..2)),
+            Some("e\u{301}te\u{301}")
+        );
+        assert_eq!(
+            n.get_range_original(Range::Normalized(1..2)),
+            Some("e\u{301}")
+        );
+
+        let mut n = NormalizedString::from("te\u{301}");
+        n.map_graphemes(|g| if g == "t" { "".into() } else { g.into() });
+        assert_eq!(n.get(), "e\u{301}");
+        assert_eq!(
+            n.get_range_original(Range::Normalized(..)),
+            Some("e\u{301}")
+        );
+    }
+
     #[test]
     fn mixed_addition_and_removal() {
         let mut n = NormalizedString::from("élégant");
         n.nfd().filter(|c| !c.is_mark_nonspacing() && c != 'n');
         assert_eq!(n.get(), "elegat");
         assert_eq!(
-            &n.alignments,
+            &n.alignments.to_vec(),
             &[(0, 2), (2, 3), (3, 5), (5, 6), (6, 7), (8, 9)]
         );
         assert_eq!(
@@ -1265,7 +1271,8 @@ mod tests {
                     (6, 7),
                     (7, 8),
                     (8, 9)
-                ],
+                ]
+                .into(),
                 original_shift: 0
             }
         );
@@ -1376,7 +1383,7 @@ mod tests {
         n.prepend("Hey ");
         assert_eq!(&n.normalized, "Hey there");
         assert_eq!(
-            n.alignments,
+            n.alignments.to_vec(),
             vec![
                 (0, 1),
                 (0, 1),
@@ -1398,7 +1405,7 @@ mod tests {
         n.append(" there");
         assert_eq!(&n.normalized, "Hey there");
         assert_eq!(
-            n.alignments,
+            n.alignments.to_vec(),
             vec![
                 (0, 1),
                 (1, 2),
@@ -1417,13 +1424,6 @@ mod tests {
         );
     }
 
-    #[test]
-    fn get_range() {
-        let s = String::from("Hello my name is John 👋");
-        assert_eq!(get_range_of(&s, ..), Some(&s[..]));
-        assert_eq!(get_range_of(&s, 17..), Some("John 👋"));
-    }
-
     #[test]
     fn slice() {
         let mut s = NormalizedString::from("𝔾𝕠𝕠𝕕 𝕞𝕠𝕣𝕟𝕚𝕟𝕘");
@@ -1529,7 +1529,8 @@ mod tests {
                     (9, 10),
                     (10, 11),
                     (11, 12)
-                ],
+                ]
+                .into(),
                 original_shift: 0,
             }
         );
@@ -1573,7 +1574,8 @@ mod tests {
                     (7, 8),
                     (10, 11),
                     (11, 12)
-                ],
+                ]
+                .into(),
                 original_shift: 0,
             }
         );
@@ -1604,7 +1606,7 @@ mod tests {
             NormalizedString {
                 original: "Hello friend".into(),
                 normalized: "Hello_F".into(),
-                alignments: vec![(0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (5, 6), (6, 7)],
+                alignments: vec![(0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (5, 6), (6, 7)].into(),
                 original_shift: 0,
             }
         );
@@ -1648,7 +1650,8 @@ mod tests {
                     (9, 10),
                     (10, 11),
                     (11, 12)
-                ],
+                ]
+                .into(),
                 original_shift: 0,
             }
         );
@@ -1691,7 +1694,8 @@ mod tests {
                     (9, 10),
                     (10, 11),
                     (11, 12)
-                ],
+                ]
+                .into(),
                 original_shift: 0,
             }
         );
@@ -1734,7 +1738,8 @@ mod tests {
                     (9, 10),
                     (10, 11),
                     (11, 12)
-                ],
+                ]
+                .into(),
                 original_shift: 0,
             }
         );
@@ -1785,7 +1790,8 @@ mod tests {
                     (9, 10),
                     (10, 11),
                     (11, 12)
-                ],
+                ]
+                .into(),
                 original_shift: 0,
             }
         );
@@ -1830,7 +1836,8 @@ mod tests {
                     (11, 12),
                     (11, 12),
                     (11, 12)
-                ],
+                ]
+                .into(),
                 original_shift: 0,
             }
         );
@@ -1875,7 +1882,8 @@ mod tests {
                     (12, 16),
                     (12, 16),
                     (12, 16)
-                ],
+                ]
+                .into(),
                 original_shift: 0,
             }
         );
@@ -1929,7 +1937,8 @@ mod tests {
                     (12, 16),
                     (12, 16),
                     (12, 16)
-                ],
+                ]
+                .into(),
                 original_shift: 0,
             }
         );
@@ -1978,7 +1987,8 @@ mod tests {
                     (8, 12),
                     (12, 16),
                     (12, 16)
-                ],
+                ]
+                .into(),
                 original_shift: 0,
             }
         );
@@ -2009,7 +2019,8 @@ mod tests {
                     (12, 16),
                     (12, 16),
                     (12, 16)
-                ],
+                ]
+                .into(),
                 original_shift: 0,
             }
         );
@@ -2071,7 +2082,8 @@ mod tests {
                     (12, 16),
                     (12, 16),
                     (12, 16)
-                ],
+                ]
+                .into(),
                 original_shift: 0,
             }
         );
@@ -2133,7 +2145,8 @@ mod tests {
                     (12, 16),
                     (12, 16),
                     (12, 16)
-                ],
+                ]
+                .into(),
                 original_shift: 0,
             }
         );
@@ -2201,7 +2214,8 @@ mod tests {
                     (12, 16),
                     (12, 16),
                     (12, 16)
-                ],
+                ]
+                .into(),
                 original_shift: 0,
             }
         );
@@ -2253,7 +2267,8 @@ mod tests {
                     (12, 16),
                     (12, 16),
                     (12, 16)
-                ],
+                ]
+                .into(),
                 original_shift: 0,
             }
         );
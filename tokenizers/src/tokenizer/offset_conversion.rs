@@ -0,0 +1,181 @@
+use super::{
+    normalizer::Range, Decoder, Model, Normalizer, OffsetReferential, OffsetType, Offsets,
+    PostProcessor, PreTokenizer, Result, TokenizerImpl,
+};
+use crate::utils::offsets::OffsetConverter;
+use crate::utils::parallelism::*;
+
+impl<M, N, PT, PP, D> TokenizerImpl<M, N, PT, PP, D>
+where
+    M: Model + Send + Sync,
+    N: Normalizer + Send + Sync,
+    PT: PreTokenizer + Send + Sync,
+    PP: PostProcessor + Send + Sync,
+    D: Decoder + Send + Sync,
+{
+    /// Convert the offsets of the spans of each sequence between the original sequence and
+    /// the sequence normalized by the normalizer of this tokenizer, from the referential
+    /// `from` to the other one.
+    ///
+    /// This lets span-annotation pipelines match the spans found on the normalized text with
+    /// the original one, or the other way around, for a whole batch in a single call. The
+    /// offsets count the chars of the sequences with [`OffsetType::Char`], and their bytes
+    /// otherwise. The spans that are out of range, or that don't fall on char boundaries,
+    /// give `None`.
+    /// ```
+    /// use tokenizers::models::wordlevel::WordLevel;
+    /// use tokenizers::normalizers::unicode::NFKC;
+    /// use tokenizers::{OffsetReferential, OffsetType, Tokenizer};
+    ///
+    /// let mut tokenizer = Tokenizer::new(WordLevel::default());
+    /// tokenizer.with_normalizer(Some(NFKC));
+    ///
+    /// // "ﬁx" gets normalized to "fix", the "x" spans (1, 2) in the original sequence
+    /// let offsets = tokenizer
+    ///     .convert_offsets_batch(
+    ///         &["ﬁx"],
+    ///         &[vec![(2, 3)]],
+    ///         OffsetReferential::Normalized,
+    ///         OffsetType::Char,
+    ///     )
+    ///     .unwrap();
+    /// assert_eq!(offsets, [[Some((1, 2))]]);
+    /// ```
+    pub fn convert_offsets_batch<S>(
+        &self,
+        sequences: &[S],
+        offsets: &[Vec<Offsets>],
+        from: OffsetReferential,
+        offset_type: OffsetType,
+    ) -> Result<Vec<Vec<Option<Offsets>>>>
+    where
+        S: AsRef<str> + Sync,
+    {
+        if sequences.len() != offsets.len() {
+            return Err(format!(
+                "Got {} sequences but the offsets of {}",
+                sequences.len(),
+                offsets.len()
+            )
+            .into());
+        }
+        self.parallelism.install(|parallel| {
+            sequences
+                .into_maybe_par_iter_cond(parallel)
+                .zip(offsets)
+                .map(|(sequence, offsets)| {
+                    self.convert_offsets(sequence.as_ref(), offsets, from, offset_type)
+                })
+                .collect()
+        })
+    }
+
+    /// Convert the offsets of the spans of a single sequence
+    fn convert_offsets(
+        &self,
+        sequence: &str,
+        offsets: &[Offsets],
+        from: OffsetReferential,
+        offset_type: OffsetType,
+    ) -> Result<Vec<Option<Offsets>>> {
+        let normalized = self.do_normalize(sequence)?;
+        let (source, target) = match from {
+            OffsetReferential::Original => (normalized.get_original(), normalized.get()),
+            OffsetReferential::Normalized => (normalized.get(), normalized.get_original()),
+        };
+        let converters = match offset_type {
+            OffsetType::Char => Some((OffsetConverter::new(source), OffsetConverter::new(target))),
+            OffsetType::Byte | OffsetType::None => None,
+        };
+
+        Ok(offsets
+            .iter()
+            .map(|offsets| {
+                let (start, end) = match &converters {
+                    Some((source, _)) => source.char_to_bytes(*offsets)?,
+                    None => *offsets,
+                };
+                if start > end || end > source.len() {
+                    return None;
+                }
+                let range = match from {
+                    OffsetReferential::Original => Range::Original(start..end),
+                    OffsetReferential::Normalized => Range::Normalized(start..end),
+                };
+                let converted = normalized.convert_offsets(range)?;
+                let converted = (converted.start, converted.end);
+                match &converters {
+                    Some((_, target)) => target.bytes_to_char(converted),
+                    None => Some(converted),
+                }
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::wordlevel::WordLevel;
+    use crate::normalizers::{unicode::NFKC, utils::Sequence, Lowercase, Strip};
+    use crate::{OffsetReferential, OffsetType, Tokenizer};
+
+    #[test]
+    fn convert_offsets() {
+        let mut tokenizer = Tokenizer::new(WordLevel::default());
+        tokenizer.with_normalizer(Some(Sequence::new(vec![
+            Strip::new(true, true).into(),
+            NFKC.into(),
+            Lowercase.into(),
+        ])));
+
+        let sequences = ["  Ça ﬁxe ", "Hello"];
+        // "ça fixe" and "hello"
+        let normalized = vec![vec![(0, 2), (3, 7), (5, 7), (6, 9)], vec![(1, 5)]];
+        let original = tokenizer
+            .convert_offsets_batch(
+                &sequences,
+                &normalized,
+                OffsetReferential::Normalized,
+                OffsetType::Char,
+            )
+            .unwrap();
+        assert_eq!(
+            original,
+            [
+                vec![Some((2, 4)), Some((5, 8)), Some((6, 8)), None],
+                vec![Some((1, 5))]
+            ]
+        );
+
+        // The same in bytes, "Ç" and "ﬁ" taking 2 and 3 bytes
+        let bytes = tokenizer
+            .convert_offsets_batch(
+                &sequences,
+                &[vec![(0, 3), (4, 8)], vec![]],
+                OffsetReferential::Normalized,
+                OffsetType::Byte,
+            )
+            .unwrap();
+        assert_eq!(bytes, [vec![Some((2, 5)), Some((6, 11))], vec![]]);
+
+        // And back, the "ﬁ" char expanding to both "f" and "i"
+        let back = tokenizer
+            .convert_offsets_batch(
+                &sequences,
+                &[vec![(2, 4), (5, 6)], vec![(0, 5)]],
+                OffsetReferential::Original,
+                OffsetType::Char,
+            )
+            .unwrap();
+        assert_eq!(back, [vec![Some((0, 2)), Some((3, 5))], vec![Some((0, 5))]]);
+
+        assert!(tokenizer
+            .convert_offsets_batch(
+                &sequences,
+                &[],
+                OffsetReferential::Original,
+                OffsetType::Char
+            )
+            .is_err());
+    }
+}
@@ -0,0 +1,344 @@
+use super::{Model, Result, Tokenizer};
+use crate::utils::versioning::SCHEMA_VERSION;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::fmt::Write;
+
+/// The stage of the pipeline a [`PipelineNode`] belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PipelineStage {
+    AddedTokens,
+    Normalizer,
+    PreTokenizer,
+    Model,
+    Truncation,
+    PostProcessor,
+    Padding,
+    Decoder,
+}
+
+impl std::fmt::Display for PipelineStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.serialize(f)
+    }
+}
+
+/// A component of a [`PipelineGraph`]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PipelineNode {
+    /// The index of the node in [`PipelineGraph::nodes`]
+    pub id: usize,
+    pub stage: PipelineStage,
+    /// The type of the component, like `BPE` or `Lowercase`
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// The parameters of the component, as serialized in a `tokenizer.json`, without the
+    /// components it contains. The vocab and merges of the model are replaced by their
+    /// sizes, `vocab_size` and `num_merges`.
+    pub params: Map<String, Value>,
+    /// The `Sequence` containing this component, if any
+    pub parent: Option<usize>,
+}
+
+/// A description of the components of a [`Tokenizer`] and their parameters, as returned by
+/// [`Tokenizer::to_pipeline_graph`].
+///
+/// The nodes are in execution order: the encoding runs from the added tokens to the padding,
+/// and the decoding from the model to the decoder. The `Sequence` components are nodes of
+/// their own, the parent of the components they contain, which are the ones actually linked
+/// by the edges.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PipelineGraph {
+    pub nodes: Vec<PipelineNode>,
+    /// The edges from each component to the next one to run, as pairs of node ids
+    pub edges: Vec<(usize, usize)>,
+}
+
+/// The fields of the `Sequence` components holding the components they contain
+const SEQUENCE_FIELDS: [&str; 4] = ["normalizers", "pretokenizers", "processors", "decoders"];
+
+#[derive(Default)]
+struct GraphBuilder {
+    nodes: Vec<PipelineNode>,
+    edges: Vec<(usize, usize)>,
+}
+
+impl GraphBuilder {
+    /// Add the given component, and the ones it contains, after the node `previous`,
+    /// returning the last node added
+    fn add(
+        &mut self,
+        stage: PipelineStage,
+        component: Value,
+        parent: Option<usize>,
+        previous: Option<usize>,
+    ) -> Option<usize> {
+        let mut params = match component {
+            Value::Object(params) => params,
+            _ => Map::new(),
+        };
+        let kind = match params.remove("type") {
+            Some(Value::String(kind)) => kind,
+            _ => stage.to_string(),
+        };
+        params.remove(SCHEMA_VERSION);
+        let children = SEQUENCE_FIELDS
+            .iter()
+            .find_map(|field| match params.get(*field) {
+                Some(Value::Array(_)) => params.remove(*field),
+                _ => None,
+            });
+
+        let id = self.nodes.len();
+        self.nodes.push(PipelineNode {
+            id,
+            stage,
+            kind,
+            params,
+            parent,
+        });
+        match children {
+            Some(Value::Array(children)) => {
+                children.into_iter().fold(previous, |previous, child| {
+                    self.add(stage, child, Some(id), previous).or(previous)
+                })
+            }
+            _ => {
+                if let Some(previous) = previous {
+                    self.edges.push((previous, id));
+                }
+                Some(id)
+            }
+        }
+    }
+}
+
+impl Tokenizer {
+    /// Describe the components of this tokenizer and their parameters, in execution order,
+    /// as a graph that can be exported to JSON, with `serde`, or to DOT, with
+    /// [`PipelineGraph::to_dot`]. Unlike the `tokenizer.json`, its structure is the same for
+    /// all the components, which makes pipelines easy to render or compare.
+    /// ```
+    /// # use tokenizers::models::bpe::BPE;
+    /// # use tokenizers::normalizers::{Lowercase, Sequence, NFKC};
+    /// # use tokenizers::{PipelineStage, Tokenizer};
+    /// let mut tokenizer = Tokenizer::new(BPE::default());
+    /// tokenizer.with_normalizer(Some(Sequence::new(vec![NFKC.into(), Lowercase.into()])));
+    ///
+    /// let graph = tokenizer.to_pipeline_graph().unwrap();
+    /// let kinds: Vec<_> = graph.nodes.iter().map(|n| n.kind.as_str()).collect();
+    /// assert_eq!(kinds, ["Sequence", "NFKC", "Lowercase", "BPE"]);
+    /// assert_eq!(graph.nodes[3].stage, PipelineStage::Model);
+    /// assert_eq!(graph.edges, [(1, 2), (2, 3)]);
+    /// ```
+    pub fn to_pipeline_graph(&self) -> Result<PipelineGraph> {
+        let mut graph = GraphBuilder::default();
+        let mut previous = None;
+
+        let added_tokens = self.get_added_tokens_decoder();
+        if !added_tokens.is_empty() {
+            let special = added_tokens.values().filter(|t| t.special).count();
+            let params = serde_json::json!({
+                "count": added_tokens.len(),
+                "special": special,
+            });
+            previous = graph.add(PipelineStage::AddedTokens, params, None, previous);
+        }
+        if let Some(normalizer) = self.get_normalizer() {
+            let normalizer = serde_json::to_value(normalizer)?;
+            previous = graph.add(PipelineStage::Normalizer, normalizer, None, previous);
+        }
+        if let Some(pre_tokenizer) = self.get_pre_tokenizer() {
+            let pre_tokenizer = serde_json::to_value(pre_tokenizer)?;
+            previous = graph.add(PipelineStage::PreTokenizer, pre_tokenizer, None, previous);
+        }
+
+        let model = self.get_model();
+        let mut params = serde_json::to_value(model)?;
+        if let Value::Object(params) = &mut params {
+            params.remove("vocab");
+            params.insert("vocab_size".into(), model.get_vocab_size().into());
+            if let Some(Value::Array(merges)) = params.remove("merges") {
+                params.insert("num_merges".into(), merges.len().into());
+            }
+        }
+        let model = graph.add(PipelineStage::Model, params, None, previous);
+        previous = model;
+
+        if let Some(truncation) = self.get_truncation() {
+            let truncation = serde_json::to_value(truncation)?;
+            previous = graph.add(PipelineStage::Truncation, truncation, None, previous);
+        }
+        if let Some(post_processor) = self.get_post_processor() {
+            let post_processor = serde_json::to_value(post_processor)?;
+            previous = graph.add(PipelineStage::PostProcessor, post_processor, None, previous);
+        }
+        if let Some(padding) = self.get_padding() {
+            let padding = serde_json::to_value(padding)?;
+            graph.add(PipelineStage::Padding, padding, None, previous);
+        }
+        if let Some(decoder) = self.get_decoder() {
+            let decoder = serde_json::to_value(decoder)?;
+            graph.add(PipelineStage::Decoder, decoder, None, model);
+        }
+
+        Ok(PipelineGraph {
+            nodes: graph.nodes,
+            edges: graph.edges,
+        })
+    }
+}
+
+impl PipelineGraph {
+    /// Export this graph in the DOT language of Graphviz. Each node is labeled with its
+    /// stage, its type and its parameters, and the `Sequence` components are clusters.
+    pub fn to_dot(&self) -> String {
+        let escape = |label: &str| label.replace('\\', "\\\\").replace('"', "\\\"");
+        let label = |node: &PipelineNode| {
+            let mut label = format!("{}: {}", node.stage, node.kind);
+            for (key, value) in &node.params {
+                let _ = write!(label, "\\n{}={}", escape(key), escape(&value.to_string()));
+            }
+            label
+        };
+
+        let mut dot = String::from("digraph pipeline {\n  rankdir=LR;\n  node [shape=box];\n");
+        // The `Sequence` nodes are drawn as the clusters of their components, and skipped
+        // when empty, as they are linked to no other node
+        let is_sequence = |node: &PipelineNode| node.kind == "Sequence";
+        let write_nodes = |dot: &mut String, parent: Option<usize>, indent: &str| {
+            let nodes = self.nodes.iter().filter(|node| node.parent == parent);
+            for node in nodes.filter(|node| !is_sequence(node)) {
+                let _ = writeln!(dot, "{indent}{} [label=\"{}\"];", node.id, label(node));
+            }
+        };
+        write_nodes(&mut dot, None, "  ");
+        let sequences = self.nodes.iter().filter(|node| {
+            is_sequence(node) && self.nodes.iter().any(|n| n.parent == Some(node.id))
+        });
+        for sequence in sequences {
+            let _ = writeln!(dot, "  subgraph cluster_{} {{", sequence.id);
+            let _ = writeln!(dot, "    label=\"{}\";", label(sequence));
+            write_nodes(&mut dot, Some(sequence.id), "    ");
+            dot.push_str("  }\n");
+        }
+        for (from, to) in &self.edges {
+            let _ = writeln!(dot, "  {from} -> {to};");
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoders::byte_level::ByteLevel;
+    use crate::models::bpe::BPE;
+    use crate::normalizers::{Lowercase, Sequence, Strip};
+    use crate::pre_tokenizers::{metaspace::Metaspace, sequence::Sequence as PreSequence};
+    use crate::processors::bert::BertProcessing;
+    use crate::{AddedToken, PaddingParams};
+
+    fn tokenizer() -> Tokenizer {
+        let vocab = [("a".into(), 0), ("b".into(), 1), ("ab".into(), 2)];
+        let bpe = BPE::builder()
+            .vocab_and_merges(vocab, vec![("a".into(), "b".into())])
+            .build()
+            .unwrap();
+        let mut tokenizer = Tokenizer::new(bpe);
+        tokenizer.with_normalizer(Some(Sequence::new(vec![
+            Strip::new(true, true).into(),
+            Lowercase.into(),
+        ])));
+        tokenizer.with_pre_tokenizer(Some(PreSequence::new(vec![Metaspace::default().into()])));
+        tokenizer.with_post_processor(Some(BertProcessing::default()));
+        tokenizer.with_padding(Some(PaddingParams::default()));
+        tokenizer.with_decoder(Some(ByteLevel::default()));
+        tokenizer.add_special_tokens(&[AddedToken::from("[CLS]", true)]);
+        tokenizer
+    }
+
+    #[test]
+    fn graph() {
+        let graph = tokenizer().to_pipeline_graph().unwrap();
+        let nodes: Vec<_> = graph
+            .nodes
+            .iter()
+            .map(|n| (n.stage, n.kind.as_str(), n.parent))
+            .collect();
+        assert_eq!(
+            nodes,
+            [
+                (PipelineStage::AddedTokens, "added_tokens", None),
+                (PipelineStage::Normalizer, "Sequence", None),
+                (PipelineStage::Normalizer, "Strip", Some(1)),
+                (PipelineStage::Normalizer, "Lowercase", Some(1)),
+                (PipelineStage::PreTokenizer, "Sequence", None),
+                (PipelineStage::PreTokenizer, "Metaspace", Some(4)),
+                (PipelineStage::Model, "BPE", None),
+                (PipelineStage::PostProcessor, "BertProcessing", None),
+                (PipelineStage::Padding, "padding", None),
+                (PipelineStage::Decoder, "ByteLevel", None),
+            ]
+        );
+        assert_eq!(
+            graph.edges,
+            [(0, 2), (2, 3), (3, 5), (5, 6), (6, 7), (7, 8), (6, 9)]
+        );
+
+        let model = &graph.nodes[6].params;
+        assert_eq!(model["vocab_size"], 3);
+        assert_eq!(model["num_merges"], 1);
+        assert!(!model.contains_key("vocab"));
+        assert!(!graph.nodes[5].params.contains_key(SCHEMA_VERSION));
+        assert_eq!(graph.nodes[0].params["special"], 1);
+        assert!(graph.nodes[1].params.is_empty());
+
+        let json = serde_json::to_value(&graph).unwrap();
+        assert_eq!(json["nodes"][3]["type"], "Lowercase");
+        assert_eq!(json["nodes"][3]["stage"], "normalizer");
+        assert_eq!(json["edges"][0], serde_json::json!([0, 2]));
+    }
+
+    #[test]
+    fn dot() {
+        let mut tokenizer = Tokenizer::new(BPE::default());
+        tokenizer.with_normalizer(Some(Sequence::new(vec![
+            Strip::new(true, false).into(),
+            Lowercase.into(),
+        ])));
+        let dot = tokenizer.to_pipeline_graph().unwrap().to_dot();
+        let expected = r#"digraph pipeline {
+  rankdir=LR;
+  node [shape=box];
+  3 [label="model: BPE\nbyte_fallback=false\ncontinuing_subword_prefix=null\ndropout=null\nend_of_word_suffix=null\nfuse_unk=false\nignore_merges=false\nnum_merges=0\nunk_token=null\nvocab_size=0"];
+  subgraph cluster_0 {
+    label="normalizer: Sequence";
+    1 [label="normalizer: Strip\nstrip_left=true\nstrip_right=false"];
+    2 [label="normalizer: Lowercase"];
+  }
+  1 -> 2;
+  2 -> 3;
+}
+"#;
+        assert_eq!(dot, expected);
+    }
+
+    #[test]
+    fn dot_empty_sequence() {
+        let mut tokenizer = Tokenizer::new(BPE::default());
+        tokenizer.with_normalizer(Some(Sequence::new(vec![])));
+        tokenizer.with_pre_tokenizer(Some(PreSequence::new(vec![Metaspace::default().into()])));
+        let graph = tokenizer.to_pipeline_graph().unwrap();
+        assert_eq!(graph.nodes[0].kind, "Sequence");
+        assert_eq!(graph.edges, [(2, 3)]);
+
+        let dot = graph.to_dot();
+        assert!(!dot.contains("  0 ["));
+        assert!(dot.contains("  3 [label=\"model: BPE"));
+        assert!(dot.contains("subgraph cluster_1 {"));
+        assert!(dot.ends_with("  2 -> 3;\n}\n"));
+    }
+}
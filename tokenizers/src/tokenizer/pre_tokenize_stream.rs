@@ -0,0 +1,222 @@
+use super::{OffsetReferential, OffsetType, Offsets, PreTokenizedString, PreTokenizer, Result};
+
+/// The pre-tokens of a text too long to be pre-tokenized at once, like a whole book.
+///
+/// The text is cut in chunks of about `chunk_size` bytes, pre-tokenized one after the other
+/// as they get consumed, so only the `PreTokenizedString` of a single chunk is ever in
+/// memory. Each chunk ends right before some whitespace, preferably a run of spaces, which
+/// goes with the following word as it would in the whole text, so the pre-tokens are the same
+/// as with a single `PreTokenizedString` for the pre-tokenizers splitting on whitespace, like
+/// `Whitespace`, `ByteLevel` or `Metaspace`. A chunk without any whitespace gets cut in the
+/// middle of a word.
+///
+/// A chunk starting elsewhere than before a space, like before a newline or in the middle of
+/// a word, is pre-tokenized as the start of a sequence: the pre-tokenizers prefixing the
+/// sequences with a space, like `ByteLevel` with `add_prefix_space` or `Metaspace`, add one
+/// there too.
+///
+/// The offsets refer to the whole text.
+///
+/// ```
+/// use tokenizers::pre_tokenizers::whitespace::Whitespace;
+/// use tokenizers::{OffsetType, PreTokenizeStream, Result};
+///
+/// let stream = PreTokenizeStream::new(Whitespace {}, "Hello there, friend", 8, OffsetType::Byte);
+/// let pre_tokens = stream.collect::<Result<Vec<_>>>().unwrap();
+/// assert_eq!(
+///     pre_tokens,
+///     vec![
+///         ("Hello".to_string(), (0, 5)),
+///         ("there".to_string(), (6, 11)),
+///         (",".to_string(), (11, 12)),
+///         ("friend".to_string(), (13, 19)),
+///     ]
+/// );
+/// ```
+pub struct PreTokenizeStream<P, S> {
+    pre_tokenizer: P,
+    text: S,
+    chunk_size: usize,
+    offset_type: OffsetType,
+    /// The start of the next chunk, in bytes and in chars
+    position: usize,
+    char_position: usize,
+    /// The pre-tokens of the current chunk not yielded yet
+    pending: std::vec::IntoIter<(String, Offsets)>,
+    failed: bool,
+}
+
+impl<P: PreTokenizer, S: AsRef<str>> PreTokenizeStream<P, S> {
+    /// Stream the pre-tokens of `text`, pre-tokenized by chunks of about `chunk_size` bytes,
+    /// with offsets of type `offset_type`
+    pub fn new(pre_tokenizer: P, text: S, chunk_size: usize, offset_type: OffsetType) -> Self {
+        Self {
+            pre_tokenizer,
+            text,
+            chunk_size: chunk_size.max(1),
+            offset_type,
+            position: 0,
+            char_position: 0,
+            pending: vec![].into_iter(),
+            failed: false,
+        }
+    }
+
+    /// The end of the chunk starting at the current position
+    fn chunk_end(&self) -> usize {
+        let rest = &self.text.as_ref()[self.position..];
+        if rest.len() <= self.chunk_size {
+            return self.position + rest.len();
+        }
+        let mut end = self.chunk_size;
+        while !rest.is_char_boundary(end) {
+            end += 1;
+        }
+        // Some pre-tokenizers prefix the sequences not starting with a space, and merge the
+        // runs of spaces, so we prefer cutting before a run of spaces than anywhere else
+        let window = &rest[..end];
+        let spaces = window.rfind(' ').map(|last| {
+            let run = window[..last].trim_end_matches(' ').len();
+            if run > 0 {
+                run
+            } else {
+                last
+            }
+        });
+        let cut = spaces
+            .filter(|&i| i > 0)
+            .or_else(|| window.rfind(char::is_whitespace).filter(|&i| i > 0))
+            .unwrap_or(end);
+        self.position + cut
+    }
+}
+
+impl<P: PreTokenizer, S: AsRef<str>> Iterator for PreTokenizeStream<P, S> {
+    type Item = Result<(String, Offsets)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(pre_token) = self.pending.next() {
+                return Some(Ok(pre_token));
+            }
+            if self.failed || self.position >= self.text.as_ref().len() {
+                return None;
+            }
+
+            let end = self.chunk_end();
+            let chunk = &self.text.as_ref()[self.position..end];
+            let mut pretokenized = PreTokenizedString::from(chunk);
+            if let Err(e) = self.pre_tokenizer.pre_tokenize(&mut pretokenized) {
+                self.failed = true;
+                return Some(Err(e));
+            }
+            let shift = match self.offset_type {
+                OffsetType::Char => self.char_position,
+                OffsetType::Byte | OffsetType::None => self.position,
+            };
+            self.pending = pretokenized
+                .get_splits(OffsetReferential::Original, self.offset_type)
+                .into_iter()
+                .map(|(s, (start, end), _)| (s.to_owned(), (start + shift, end + shift)))
+                .collect::<Vec<_>>()
+                .into_iter();
+            if self.offset_type == OffsetType::Char {
+                self.char_position += chunk.chars().count();
+            }
+            self.position = end;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pre_tokenizers::byte_level::ByteLevel;
+    use crate::pre_tokenizers::metaspace::Metaspace;
+    use crate::pre_tokenizers::whitespace::Whitespace;
+
+    fn whole<P: PreTokenizer>(pre_tokenizer: &P, text: &str) -> Vec<(String, Offsets)> {
+        let mut pretokenized = PreTokenizedString::from(text);
+        pre_tokenizer.pre_tokenize(&mut pretokenized).unwrap();
+        pretokenized
+            .get_splits(OffsetReferential::Original, OffsetType::Char)
+            .into_iter()
+            .map(|(s, o, _)| (s.to_owned(), o))
+            .collect()
+    }
+
+    fn streamed<P: PreTokenizer>(
+        pre_tokenizer: P,
+        text: &str,
+        size: usize,
+    ) -> Vec<(String, Offsets)> {
+        PreTokenizeStream::new(pre_tokenizer, text, size, OffsetType::Char)
+            .collect::<Result<_>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn same_as_whole() {
+        // Any chunk longer than the words ends before a whitespace
+        let text = "Le café  est\nservi, à   l'heure\t où  les élèves arrivent.";
+        for size in 10..text.len() + 2 {
+            assert_eq!(
+                streamed(Whitespace {}, text, size),
+                whole(&Whitespace {}, text)
+            );
+        }
+
+        // Without newlines, they all start with a space
+        let text = "Le café  est servi, à   l'heure où  les élèves arrivent.";
+        for size in 10..text.len() + 2 {
+            assert_eq!(
+                streamed(ByteLevel::default(), text, size),
+                whole(&ByteLevel::default(), text)
+            );
+            assert_eq!(
+                streamed(Metaspace::default(), text, size),
+                whole(&Metaspace::default(), text)
+            );
+        }
+    }
+
+    #[test]
+    fn prefix_space() {
+        // Cut before the newline, the second chunk gets its own prefix space
+        let text = "aaaa\nbbbb";
+        let pre_tokens = |pre_tokenizer: ByteLevel| -> Vec<_> {
+            streamed(pre_tokenizer, text, 6)
+                .into_iter()
+                .map(|(pre_token, _)| pre_token)
+                .collect()
+        };
+        assert_eq!(
+            pre_tokens(ByteLevel::default()),
+            ["Ġaaaa", "Ġ", "Ċ", "bbbb"]
+        );
+        assert_eq!(
+            whole(&ByteLevel::default(), text)
+                .into_iter()
+                .map(|(pre_token, _)| pre_token)
+                .collect::<Vec<_>>(),
+            ["Ġaaaa", "Ċ", "bbbb"]
+        );
+        // Nothing changes without it
+        let no_prefix = ByteLevel::default().add_prefix_space(false);
+        assert_eq!(pre_tokens(no_prefix), ["aaaa", "Ċ", "bbbb"]);
+    }
+
+    #[test]
+    fn long_words() {
+        // Without whitespace, the chunks cut the words
+        assert_eq!(
+            streamed(Whitespace {}, "abcdé fg", 3),
+            vec![
+                ("abc".to_string(), (0, 3)),
+                ("dé".to_string(), (3, 5)),
+                ("fg".to_string(), (6, 8)),
+            ]
+        );
+        assert!(streamed(Whitespace {}, "", 3).is_empty());
+    }
+}
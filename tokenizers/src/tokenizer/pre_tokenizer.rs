@@ -1,3 +1,4 @@
+use super::limits::{check_limit, LimitError};
 use crate::{
     normalizer::Range, Encoding, NormalizedString, OffsetReferential, Offsets, Result, Token,
 };
@@ -54,6 +55,9 @@ impl From<(NormalizedString, Option<Vec<Token>>)> for Split {
 pub struct PreTokenizedString {
     original: String,
     splits: Vec<Split>,
+    /// The number of pre-tokens of the previous parts of the sequence, and the limit on the
+    /// pre-tokens checked while splitting
+    pre_token_limit: Option<(usize, usize)>,
 }
 
 impl PreTokenizedString {
@@ -95,12 +99,24 @@ impl PreTokenizedString {
                         }
                     }),
             );
+            if let Some((previous, limit)) = self.pre_token_limit {
+                check_limit(previous + new_splits.len(), Some(limit), |count, limit| {
+                    LimitError::TooManyPreTokens { count, limit }
+                })?;
+            }
         }
         self.splits = new_splits;
 
         Ok(())
     }
 
+    /// Fail the splitting as soon as the splits, added to the `previous` pre-tokens of the
+    /// sequence, go over the `limit`, instead of once all of them are built. See
+    /// [`StageLimits::max_pre_tokens`](super::StageLimits::max_pre_tokens)
+    pub(crate) fn limit_pre_tokens(&mut self, previous: usize, limit: Option<usize>) {
+        self.pre_token_limit = limit.map(|limit| (previous, limit));
+    }
+
     /// Normalized all the splits that do not have attached `Tokens`, using the provided
     /// `normalize` function.
     pub fn normalize<F>(&mut self, normalize: F) -> Result<()>
@@ -126,6 +142,37 @@ impl PreTokenizedString {
         Ok(())
     }
 
+    /// The number of splits, tokenized or not
+    pub(crate) fn len(&self) -> usize {
+        self.splits.len()
+    }
+
+    /// The current splits, as recorded in an [`EncodeTrace`](super::EncodeTrace)
+    pub(crate) fn trace_splits(&self) -> Vec<super::TraceSplit> {
+        self.splits
+            .iter()
+            .map(|split| {
+                let normalized = &split.normalized;
+                let offsets = normalized.offsets_original();
+                let alignments = normalized
+                    .get()
+                    .char_indices()
+                    .map(|(i, c)| {
+                        normalized
+                            .convert_offsets(Range::Normalized(i..i + c.len_utf8()))
+                            .map_or(offsets, |r| (offsets.0 + r.start, offsets.0 + r.end))
+                    })
+                    .collect();
+                super::TraceSplit {
+                    value: normalized.get().to_owned(),
+                    offsets,
+                    alignments,
+                    tokens: split.tokens.clone(),
+                }
+            })
+            .collect()
+    }
+
     /// Transform the current `PreTokenizedString` into an `Encoding`.
     ///
     /// If a `word_idx` is provided, any word in the generated `Encoding`
@@ -246,6 +293,7 @@ impl From<NormalizedString> for PreTokenizedString {
                 normalized: s,
                 tokens: None,
             }],
+            pre_token_limit: None,
         }
     }
 }
@@ -0,0 +1,512 @@
+use super::{
+    Decoder, EncodeInput, Encoding, Model, Normalizer, OffsetType, PostProcessor, PreTokenizer,
+    Result, Token, TokenizerImpl,
+};
+use crate::utils::parallelism::*;
+use ahash::AHashSet;
+use serde::Serialize;
+use std::cell::{Cell, RefCell};
+
+/// A [`Model`] able to sample a segmentation instead of always returning the best one, as
+/// used for subword regularization.
+pub trait SamplingModel: Model {
+    /// Tokenize the given sequence, sampling its segmentation.
+    ///
+    /// The meaning of `strength` depends on the model: it is the merges dropout probability
+    /// for BPE (the higher, the more diverse the segmentations), and the smoothing parameter
+    /// `alpha` for Unigram (the lower, the more diverse).
+    fn tokenize_sampled(&self, sequence: &str, strength: f64) -> Result<Vec<Token>>;
+}
+
+/// A [`Model`] able to give several segmentations of a sequence, with their scores.
+pub trait NBestModel: Model {
+    /// Tokenize the given sequence, returning its `n` best segmentations (or fewer when
+    /// there are not as many), best first, with their scores.
+    ///
+    /// The scores are log probabilities: the sum of the scores of the pieces for Unigram.
+    fn tokenize_nbest(&self, sequence: &str, n: usize) -> Result<Vec<(Vec<Token>, f64)>>;
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum SamplingError {
+    #[error("The number of samples must be strictly positive")]
+    NoSamples,
+    #[error("The number of segmentations must be strictly positive")]
+    NoSegmentations,
+}
+
+/// Statistics comparing the deterministic and sampled segmentations of a corpus.
+/// See [`TokenizerImpl::audit_sampling`]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SamplingAudit {
+    /// The number of sequences in the corpus
+    pub sequences: usize,
+    /// The number of segmentations sampled for each sequence
+    pub samples: usize,
+    /// The total number of tokens of the deterministic segmentation
+    pub tokens: usize,
+    /// The fraction of samples whose segmentation differs from the deterministic one
+    pub divergence_rate: f64,
+    /// The mean Jaccard similarity between the token boundaries of the samples and those
+    /// of the deterministic segmentation. `1.0` means the boundaries are identical
+    pub boundary_agreement: f64,
+    /// The mean number of sampled tokens for each deterministic token
+    pub length_ratio: f64,
+    /// The mean number of distinct segmentations sampled for each sequence
+    pub unique_segmentations: f64,
+}
+
+/// The statistics of a single sequence, summed over its samples
+#[derive(Default)]
+struct SequenceStats {
+    tokens: usize,
+    sampled_tokens: usize,
+    diverging: usize,
+    agreement: f64,
+    unique: usize,
+}
+
+fn boundaries(encoding: &Encoding) -> AHashSet<usize> {
+    encoding
+        .get_offsets()
+        .iter()
+        .flat_map(|(start, end)| [*start, *end])
+        .collect()
+}
+
+fn ratio(num: f64, den: f64) -> f64 {
+    if den == 0.0 {
+        0.0
+    } else {
+        num / den
+    }
+}
+
+impl<M, N, PT, PP, D> TokenizerImpl<M, N, PT, PP, D>
+where
+    M: SamplingModel + Send + Sync,
+    N: Normalizer + Send + Sync,
+    PT: PreTokenizer + Send + Sync,
+    PP: PostProcessor + Send + Sync,
+    D: Decoder + Send + Sync,
+{
+    /// Compare the deterministic segmentation of each sequence with `samples` sampled
+    /// segmentations, using the given `strength` (see [`SamplingModel::tokenize_sampled`]).
+    ///
+    /// This helps picking the dropout or alpha values used for subword regularization:
+    /// the sampled segmentations should differ enough to regularize the training, without
+    /// drifting too far from the deterministic one seen at inference time.
+    ///
+    /// No special tokens are added, and the added tokens are never sampled.
+    /// ```
+    /// use tokenizers::models::bpe::BPE;
+    /// use tokenizers::Tokenizer;
+    ///
+    /// let vocab = [("a".into(), 0), ("b".into(), 1), ("ab".into(), 2)];
+    /// let model = BPE::builder()
+    ///     .vocab_and_merges(vocab, vec![("a".into(), "b".into())])
+    ///     .build()
+    ///     .unwrap();
+    /// let tokenizer = Tokenizer::new(model);
+    ///
+    /// let audit = tokenizer.audit_sampling(&["ab", "abab"], 1.0, 5).unwrap();
+    /// // With a dropout of 1.0, no merge ever happens
+    /// assert_eq!(audit.tokens, 3);
+    /// assert_eq!(audit.divergence_rate, 1.0);
+    /// assert_eq!(audit.length_ratio, 2.0);
+    /// assert_eq!(audit.unique_segmentations, 1.0);
+    /// ```
+    pub fn audit_sampling<S>(
+        &self,
+        sequences: &[S],
+        strength: f64,
+        samples: usize,
+    ) -> Result<SamplingAudit>
+    where
+        S: AsRef<str> + Sync,
+    {
+        if samples == 0 {
+            return Err(Box::new(SamplingError::NoSamples));
+        }
+
+        let stats = self.parallelism.install(|parallel| {
+            sequences
+                .into_maybe_par_iter_cond(parallel)
+                .map(|sequence| self.audit_sequence(sequence.as_ref(), strength, samples))
+                .collect::<Result<Vec<_>>>()
+        })?;
+
+        let pairs = (sequences.len() * samples) as f64;
+        let sum = |f: fn(&SequenceStats) -> f64| stats.iter().map(f).sum::<f64>();
+        let tokens = stats.iter().map(|s| s.tokens).sum::<usize>();
+        Ok(SamplingAudit {
+            sequences: sequences.len(),
+            samples,
+            tokens,
+            divergence_rate: ratio(sum(|s| s.diverging as f64), pairs),
+            boundary_agreement: ratio(sum(|s| s.agreement), pairs),
+            length_ratio: ratio(sum(|s| s.sampled_tokens as f64), (tokens * samples) as f64),
+            unique_segmentations: ratio(sum(|s| s.unique as f64), sequences.len() as f64),
+        })
+    }
+
+    /// Encode the given input like [`encode`](TokenizerImpl::encode), sampling the
+    /// segmentation of each word with the given `strength` (see
+    /// [`SamplingModel::tokenize_sampled`]), as used for subword regularization.
+    ///
+    /// The added tokens are never sampled.
+    /// ```
+    /// use tokenizers::models::bpe::BPE;
+    /// use tokenizers::Tokenizer;
+    ///
+    /// let vocab = [("a".into(), 0), ("b".into(), 1), ("ab".into(), 2)];
+    /// let model = BPE::builder()
+    ///     .vocab_and_merges(vocab, vec![("a".into(), "b".into())])
+    ///     .build()
+    ///     .unwrap();
+    /// let tokenizer = Tokenizer::new(model);
+    ///
+    /// assert_eq!(tokenizer.encode_sample("ab", 0.0, false).unwrap().get_ids(), [2]);
+    /// assert_eq!(tokenizer.encode_sample("ab", 1.0, false).unwrap().get_ids(), [0, 1]);
+    /// ```
+    pub fn encode_sample<'s, E>(
+        &self,
+        input: E,
+        strength: f64,
+        add_special_tokens: bool,
+    ) -> Result<Encoding>
+    where
+        E: Into<EncodeInput<'s>>,
+    {
+        self.encode_sample_with_offsets(input, strength, add_special_tokens, OffsetType::Byte)
+    }
+
+    /// Encode the given input like [`encode_sample`](Self::encode_sample), using offsets
+    /// relative to chars instead of bytes
+    pub fn encode_sample_char_offsets<'s, E>(
+        &self,
+        input: E,
+        strength: f64,
+        add_special_tokens: bool,
+    ) -> Result<Encoding>
+    where
+        E: Into<EncodeInput<'s>>,
+    {
+        self.encode_sample_with_offsets(input, strength, add_special_tokens, OffsetType::Char)
+    }
+
+    fn encode_sample_with_offsets<'s, E>(
+        &self,
+        input: E,
+        strength: f64,
+        add_special_tokens: bool,
+        offsets_type: OffsetType,
+    ) -> Result<Encoding>
+    where
+        E: Into<EncodeInput<'s>>,
+    {
+        let (sequence, pair) = match input.into() {
+            EncodeInput::Single(s1) => (s1, None),
+            EncodeInput::Dual(s1, s2) => (s1, Some(s2)),
+        };
+        let sample = |split: &str| self.model.tokenize_sampled(split, strength);
+
+        let encoding = self.encode_single_sequence_with(sequence, 0, offsets_type, &sample)?;
+        let pair_encoding = pair
+            .map(|sequence| self.encode_single_sequence_with(sequence, 1, offsets_type, &sample))
+            .transpose()?;
+        self.post_process(encoding, pair_encoding, add_special_tokens)
+    }
+
+    fn audit_sequence(
+        &self,
+        sequence: &str,
+        strength: f64,
+        samples: usize,
+    ) -> Result<SequenceStats> {
+        let encode = |sampled: bool| -> Result<Encoding> {
+            let normalized = self
+                .added_vocabulary
+                .extract_and_normalize(self.normalizer.as_ref(), sequence);
+            let mut pre_tokenized = self.do_pre_tokenize(normalized)?;
+            pre_tokenized.tokenize(|normalized| {
+                if sampled {
+                    self.model.tokenize_sampled(normalized.get(), strength)
+                } else {
+                    self.model.tokenize(normalized.get())
+                }
+            })?;
+            pre_tokenized.into_encoding(None, 0, OffsetType::Byte)
+        };
+
+        let reference = encode(false)?;
+        let reference_boundaries = boundaries(&reference);
+        let mut stats = SequenceStats {
+            tokens: reference.len(),
+            ..Default::default()
+        };
+        let mut segmentations = AHashSet::new();
+        for _ in 0..samples {
+            let sample = encode(true)?;
+            stats.sampled_tokens += sample.len();
+            if sample.get_ids() != reference.get_ids()
+                || sample.get_offsets() != reference.get_offsets()
+            {
+                stats.diverging += 1;
+            }
+
+            let sample_boundaries = boundaries(&sample);
+            let union = reference_boundaries.union(&sample_boundaries).count();
+            let intersection = reference_boundaries
+                .intersection(&sample_boundaries)
+                .count();
+            stats.agreement += if union == 0 {
+                1.0
+            } else {
+                intersection as f64 / union as f64
+            };
+
+            segmentations.insert((sample.get_ids().to_vec(), sample.get_offsets().to_vec()));
+        }
+        stats.unique = segmentations.len();
+        Ok(stats)
+    }
+}
+
+impl<M, N, PT, PP, D> TokenizerImpl<M, N, PT, PP, D>
+where
+    M: NBestModel,
+    N: Normalizer,
+    PT: PreTokenizer,
+    PP: PostProcessor,
+    D: Decoder,
+{
+    /// Encode the given input like [`encode`](TokenizerImpl::encode), returning its `n`
+    /// best segmentations (or fewer when there are not as many), best first, with their
+    /// scores (see [`NBestModel::tokenize_nbest`]).
+    ///
+    /// The score of a segmentation is the sum of the scores of its words, whose own n-best
+    /// segmentations are combined. The added tokens have a score of 0.
+    /// ```
+    /// use tokenizers::models::unigram::Unigram;
+    /// use tokenizers::Tokenizer;
+    ///
+    /// let pieces = vec![
+    ///     ("<unk>".to_string(), 0.0),
+    ///     ("a".to_string(), -2.0),
+    ///     ("b".to_string(), -2.0),
+    ///     ("ab".to_string(), -1.0),
+    /// ];
+    /// let tokenizer = Tokenizer::new(Unigram::from(pieces, Some(0), false).unwrap());
+    ///
+    /// let nbest = tokenizer.encode_nbest("ab", 3, false).unwrap();
+    /// assert_eq!(nbest.len(), 2);
+    /// assert_eq!(nbest[0].0.get_tokens(), ["ab"]);
+    /// assert_eq!(nbest[0].1, -1.0);
+    /// assert_eq!(nbest[1].0.get_tokens(), ["a", "b"]);
+    /// assert_eq!(nbest[1].1, -4.0);
+    /// ```
+    pub fn encode_nbest<'s, E>(
+        &self,
+        input: E,
+        n: usize,
+        add_special_tokens: bool,
+    ) -> Result<Vec<(Encoding, f64)>>
+    where
+        E: Into<EncodeInput<'s>>,
+    {
+        self.encode_nbest_with_offsets(input, n, add_special_tokens, OffsetType::Byte)
+    }
+
+    /// Encode the given input like [`encode_nbest`](Self::encode_nbest), using offsets
+    /// relative to chars instead of bytes
+    pub fn encode_nbest_char_offsets<'s, E>(
+        &self,
+        input: E,
+        n: usize,
+        add_special_tokens: bool,
+    ) -> Result<Vec<(Encoding, f64)>>
+    where
+        E: Into<EncodeInput<'s>>,
+    {
+        self.encode_nbest_with_offsets(input, n, add_special_tokens, OffsetType::Char)
+    }
+
+    fn encode_nbest_with_offsets<'s, E>(
+        &self,
+        input: E,
+        n: usize,
+        add_special_tokens: bool,
+        offsets_type: OffsetType,
+    ) -> Result<Vec<(Encoding, f64)>>
+    where
+        E: Into<EncodeInput<'s>>,
+    {
+        if n == 0 {
+            return Err(Box::new(SamplingError::NoSegmentations));
+        }
+        let (sequence, pair) = match input.into() {
+            EncodeInput::Single(s1) => (s1, None),
+            EncodeInput::Dual(s1, s2) => (s1, Some(s2)),
+        };
+
+        // Gather the n best segmentations of every split, in the order they get tokenized
+        let splits = RefCell::new(vec![]);
+        let gather = |split: &str| -> Result<Vec<Token>> {
+            let mut nbest = self.model.tokenize_nbest(split, n)?;
+            if nbest.is_empty() {
+                nbest.push((vec![], 0.0));
+            }
+            let best = nbest[0].0.clone();
+            splits.borrow_mut().push(nbest);
+            Ok(best)
+        };
+        self.encode_single_sequence_with(sequence.clone(), 0, offsets_type, &gather)?;
+        if let Some(pair) = &pair {
+            self.encode_single_sequence_with(pair.clone(), 1, offsets_type, &gather)?;
+        }
+        let splits = splits.into_inner();
+
+        // The n best combinations of these segmentations: keeping the n best partial ones
+        // after each split is enough, since the segmentations of each split are sorted
+        let mut combinations: Vec<(f64, Vec<usize>)> = vec![(0.0, vec![])];
+        for nbest in &splits {
+            let mut next = combinations
+                .iter()
+                .flat_map(|(score, choices)| {
+                    nbest.iter().enumerate().map(move |(i, (_, split_score))| {
+                        let mut choices = choices.clone();
+                        choices.push(i);
+                        (score + split_score, choices)
+                    })
+                })
+                .collect::<Vec<_>>();
+            next.sort_by(|a, b| b.0.total_cmp(&a.0));
+            next.truncate(n);
+            combinations = next;
+        }
+
+        combinations
+            .into_iter()
+            .map(|(score, choices)| {
+                let split = Cell::new(0);
+                let choose = |_: &str| -> Result<Vec<Token>> {
+                    let i = split.get();
+                    split.set(i + 1);
+                    Ok(splits[i][choices[i]].0.clone())
+                };
+                let encoding =
+                    self.encode_single_sequence_with(sequence.clone(), 0, offsets_type, &choose)?;
+                let pair_encoding = pair
+                    .clone()
+                    .map(|pair| self.encode_single_sequence_with(pair, 1, offsets_type, &choose))
+                    .transpose()?;
+                Ok((
+                    self.post_process(encoding, pair_encoding, add_special_tokens)?,
+                    score,
+                ))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::bpe::BPE;
+    use crate::models::unigram::Unigram;
+    use crate::pre_tokenizers::whitespace::WhitespaceSplit;
+    use crate::{Encoding, Tokenizer};
+
+    fn tokenizer() -> Tokenizer {
+        let pieces = [("<unk>", 0.0), ("a", -1.0), ("b", -1.0), ("ab", -0.5)]
+            .iter()
+            .map(|(p, s)| (p.to_string(), *s))
+            .collect();
+        let mut tokenizer = Tokenizer::new(Unigram::from(pieces, Some(0), false).unwrap());
+        tokenizer.with_pre_tokenizer(Some(WhitespaceSplit));
+        tokenizer
+    }
+
+    #[test]
+    fn audit() {
+        let tokenizer = tokenizer();
+        let audit = tokenizer
+            .audit_sampling(&["ab ab", "a b"], 0.1, 50)
+            .unwrap();
+        assert_eq!(audit.sequences, 2);
+        assert_eq!(audit.samples, 50);
+        assert_eq!(audit.tokens, 4);
+        // "a b" has a single segmentation, "ab ab" has four
+        assert!(audit.divergence_rate > 0.0 && audit.divergence_rate < 0.5);
+        assert!(audit.boundary_agreement > 0.5 && audit.boundary_agreement < 1.0);
+        assert!(audit.length_ratio > 1.0 && audit.length_ratio < 1.5);
+        assert!(audit.unique_segmentations > 1.0 && audit.unique_segmentations <= 2.5);
+    }
+
+    #[test]
+    fn audit_degenerate() {
+        let tokenizer = tokenizer();
+        assert!(tokenizer.audit_sampling(&["ab"], 0.1, 0).is_err());
+
+        let audit = tokenizer.audit_sampling::<&str>(&[], 0.1, 3).unwrap();
+        assert_eq!(audit.tokens, 0);
+        assert_eq!(audit.divergence_rate, 0.0);
+        assert_eq!(audit.length_ratio, 0.0);
+
+        let audit = tokenizer.audit_sampling(&[""], 0.1, 3).unwrap();
+        assert_eq!(audit.divergence_rate, 0.0);
+        assert_eq!(audit.boundary_agreement, 1.0);
+        assert_eq!(audit.unique_segmentations, 1.0);
+    }
+
+    #[test]
+    fn encode_nbest() {
+        let tokenizer = tokenizer();
+        let tokens = |nbest: &[(Encoding, f64)]| {
+            nbest
+                .iter()
+                .map(|(encoding, score)| (encoding.get_tokens().join(" "), *score))
+                .collect::<Vec<_>>()
+        };
+
+        // The segmentations of the words get combined
+        let nbest = tokenizer.encode_nbest("ab ab", 3, false).unwrap();
+        assert_eq!(
+            tokens(&nbest),
+            [
+                ("ab ab".to_string(), -1.0),
+                ("ab a b".to_string(), -2.5),
+                ("a b ab".to_string(), -2.5)
+            ]
+        );
+        assert_eq!(nbest[2].0.get_offsets(), [(0, 1), (1, 2), (3, 5)]);
+        assert_eq!(nbest[2].0.get_word_ids(), [Some(0), Some(0), Some(1)]);
+
+        // Along with those of the pair
+        let nbest = tokenizer.encode_nbest(("ab", "b"), 5, false).unwrap();
+        assert_eq!(
+            tokens(&nbest),
+            [("ab b".to_string(), -1.5), ("a b b".to_string(), -3.0)]
+        );
+        assert_eq!(nbest[1].0.get_type_ids(), [0, 0, 1]);
+
+        assert_eq!(tokenizer.encode_nbest("", 2, false).unwrap().len(), 1);
+        assert!(tokenizer.encode_nbest("ab", 0, false).is_err());
+
+        let tokenizer = Tokenizer::new(BPE::default());
+        assert!(tokenizer.encode_nbest("ab", 2, false).is_err());
+    }
+
+    #[test]
+    fn encode_sample() {
+        let tokenizer = tokenizer();
+        let best = tokenizer.encode("ab ab", false).unwrap();
+        let samples = (0..50)
+            .map(|_| tokenizer.encode_sample("ab ab", 0.1, false).unwrap())
+            .collect::<Vec<_>>();
+        assert!(samples.iter().any(|s| s.get_ids() != best.get_ids()));
+        assert!(samples
+            .iter()
+            .all(|s| s.get_tokens().concat() == best.get_tokens().concat()));
+    }
+}
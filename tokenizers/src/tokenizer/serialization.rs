@@ -8,10 +8,44 @@ use serde::{
 };
 
 use super::{added_vocabulary::AddedTokenWithId, TokenizerImpl};
+use crate::utils::versioning::{schema_version, Versioned, SCHEMA_VERSION};
 use crate::{Decoder, Model, Normalizer, PostProcessor, PreTokenizer, TokenizerBuilder};
 
 static SERIALIZATION_VERSION: &str = "1.0";
 
+/// The layout didn't change since the versions were introduced. Its version is embedded by
+/// [`TokenizerImpl::to_string_pretty_stable`](super::TokenizerImpl::to_string_pretty_stable),
+/// the files with a more recent schema are refused.
+impl<M, N, PT, PP, D> Versioned for TokenizerImpl<M, N, PT, PP, D> {
+    const SCHEMA_VERSION: u32 = 1;
+
+    fn upgrade(_: u32, _: &mut serde_json::Map<String, serde_json::Value>) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Canonicalize the given JSON value, by sorting the keys of every object.
+///
+/// Arrays keep their order, as it is meaningful (merges ranks, Unigram ids, ...).
+pub(crate) fn sort_keys(value: serde_json::Value) -> serde_json::Value {
+    use serde_json::Value;
+
+    match value {
+        Value::Object(map) => {
+            let mut entries = map.into_iter().collect::<Vec<_>>();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, sort_keys(value)))
+                    .collect(),
+            )
+        }
+        Value::Array(values) => Value::Array(values.into_iter().map(sort_keys).collect()),
+        value => value,
+    }
+}
+
 impl<M, N, PT, PP, D> Serialize for TokenizerImpl<M, N, PT, PP, D>
 where
     M: Serialize,
@@ -119,6 +153,11 @@ where
                         return Err(Error::custom(format!("Unknown tokenizer version '{v}'")));
                     }
                 }
+                SCHEMA_VERSION => {
+                    let version: serde_json::Value = map.next_value()?;
+                    schema_version::<TokenizerImpl<M, N, PT, PP, D>>(Some(&version))
+                        .map_err(Error::custom)?;
+                }
                 "truncation" => {
                     builder = builder.with_truncation(map.next_value()?);
                 }
@@ -165,7 +204,9 @@ where
             }
         }
         let added_tokens: Vec<_> = tokens.into_iter().map(|token| token.token).collect();
-        tokenizer.add_tokens(&added_tokens[..]);
+        tokenizer
+            .try_add_tokens(&added_tokens[..])
+            .map_err(Error::custom)?;
 
         Ok(tokenizer)
     }
@@ -173,7 +214,12 @@ where
 
 #[cfg(test)]
 mod tests {
+    use crate::models::bpe::BPE;
+    use crate::normalizers::{Sequence as NormalizerSequence, Strip, NFKC};
+    use crate::pre_tokenizers::byte_level::ByteLevel;
+    use crate::processors::template::TemplateProcessing;
     use crate::tokenizer::Tokenizer;
+    use crate::{AddedToken, PaddingParams};
     use std::str::FromStr;
 
     #[test]
@@ -230,6 +276,80 @@ mod tests {
         assert_eq!(tok_str, tok_json);
     }
 
+    #[test]
+    fn test_pretty_stable() {
+        let vocab = [
+            ("a".into(), 0),
+            ("b".into(), 1),
+            ("ab".into(), 2),
+            ("Ġ".into(), 3),
+        ];
+        let model = BPE::builder()
+            .vocab_and_merges(vocab, vec![("a".into(), "b".into())])
+            .build()
+            .unwrap();
+        let mut tokenizer = Tokenizer::new(model);
+        tokenizer.with_normalizer(Some(NormalizerSequence::new(vec![
+            Strip::new(true, true).into(),
+            NFKC.into(),
+        ])));
+        tokenizer.with_pre_tokenizer(Some(ByteLevel::default()));
+        tokenizer.with_decoder(Some(ByteLevel::default()));
+        tokenizer.with_post_processor(Some(
+            TemplateProcessing::builder()
+                .try_single("[CLS] $0")
+                .unwrap()
+                .special_tokens(vec![("[CLS]", 4)])
+                .build()
+                .unwrap(),
+        ));
+        tokenizer.add_special_tokens(&[AddedToken::from("[CLS]", true)]);
+        tokenizer.with_padding(Some(PaddingParams::default()));
+
+        let stable = tokenizer.to_string_pretty_stable().unwrap();
+        assert!(stable.ends_with("}\n"));
+        let value: serde_json::Value = serde_json::from_str(&stable).unwrap();
+        assert_eq!(value["schema_version"], 1);
+        let keys = stable
+            .lines()
+            .filter(|line| line.starts_with("  \""))
+            .map(|line| line.trim().split('"').nth(1).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            keys,
+            [
+                "added_tokens",
+                "decoder",
+                "model",
+                "normalizer",
+                "padding",
+                "post_processor",
+                "pre_tokenizer",
+                "schema_version",
+                "truncation",
+                "version"
+            ]
+        );
+        let vocab = stable.find(r#""vocab": {"#).unwrap();
+        let tokens =
+            ["\"a\"", "\"ab\"", "\"b\"", "\"Ġ\""].map(|token| stable[vocab..].find(token).unwrap());
+        assert!(tokens.windows(2).all(|w| w[0] < w[1]));
+
+        // It loads back to the same tokenizer, and stays stable
+        let loaded = Tokenizer::from_str(&stable).unwrap();
+        assert_eq!(
+            loaded.to_string(false).unwrap(),
+            tokenizer.to_string(false).unwrap()
+        );
+        assert_eq!(loaded.to_string_pretty_stable().unwrap(), stable);
+
+        let newer = stable.replace(r#""schema_version": 1"#, r#""schema_version": 2"#);
+        assert!(Tokenizer::from_str(&newer)
+            .unwrap_err()
+            .to_string()
+            .contains("The version 2 of the schema is more recent"));
+    }
+
     #[cfg(feature = "http")]
     #[test]
     fn test_from_pretrained() {
@@ -0,0 +1,535 @@
+use super::{
+    Decoder, DecoderWrapper, EncodeInput, EncodeOptions, Encoding, InputSequence, Model,
+    ModelWrapper, Normalizer, NormalizerWrapper, OffsetType, PostProcessor, PostProcessorWrapper,
+    PreTokenizer, PreTokenizerWrapper, Result, TokenizerImpl,
+};
+use std::borrow::Cow;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+
+/// An input encoded differently by the two tokenizers of a [`ShadowTokenizer`]
+#[derive(Debug, Clone)]
+pub struct ShadowDivergence {
+    pub input: EncodeInput<'static>,
+    /// The encoding returned by the primary tokenizer
+    pub primary: Encoding,
+    /// The encoding of the candidate tokenizer, or the error it returned
+    pub candidate: std::result::Result<Encoding, String>,
+}
+
+/// What a [`ShadowTokenizer`] found so far
+#[derive(Debug, Clone, Default)]
+pub struct ShadowReport {
+    /// The number of inputs encoded by both tokenizers
+    pub compared: usize,
+    /// The number of inputs for which the candidate tokenizer gave other ids, or failed
+    pub diverged: usize,
+    /// The number of inputs not compared because the candidate tokenizer was lagging behind
+    pub skipped: usize,
+    /// The first divergences, up to the limit given to the `ShadowTokenizer`
+    pub examples: Vec<ShadowDivergence>,
+    /// The message of the panic of the candidate tokenizer, if any. It stops the comparisons,
+    /// all the inputs since then being skipped
+    pub candidate_panic: Option<String>,
+}
+
+impl ShadowReport {
+    /// The share of the compared inputs that diverged
+    pub fn divergence_rate(&self) -> f64 {
+        if self.compared == 0 {
+            0.0
+        } else {
+            self.diverged as f64 / self.compared as f64
+        }
+    }
+}
+
+enum Job {
+    Compare {
+        inputs: Vec<EncodeInput<'static>>,
+        encodings: Vec<Encoding>,
+        add_special_tokens: bool,
+        offsets: OffsetType,
+    },
+    Flush(mpsc::Sender<()>),
+}
+
+/// Run a candidate tokenizer in the shadow of the primary one, to check an upgrade on the
+/// real traffic before switching to it.
+///
+/// Everything gets encoded by the primary tokenizer, whose results are returned as usual.
+/// The same inputs are then encoded by the candidate tokenizer on a background thread, and
+/// the inputs getting other ids are recorded in a [`ShadowReport`]. The candidate never
+/// slows the primary down: when it lags behind by more than `queue_size` calls, the new
+/// inputs are skipped instead of waiting for it. Once the `ShadowTokenizer` gets dropped,
+/// the background thread stops after comparing the inputs still queued. It also stops if the
+/// candidate panics, which is recorded in the report.
+///
+/// ```
+/// # use tokenizers::models::wordlevel::WordLevel;
+/// # use tokenizers::normalizers::utils::Lowercase;
+/// # use tokenizers::pre_tokenizers::whitespace::Whitespace;
+/// # use tokenizers::{ShadowTokenizer, Tokenizer};
+/// let vocab = vec![("[UNK]".to_string(), 0), ("hello".to_string(), 1)];
+/// let vocab = vocab.into_iter().collect();
+/// let model = WordLevel::builder().vocab(vocab).unk_token("[UNK]".into()).build().unwrap();
+/// let mut primary = Tokenizer::new(model);
+/// primary.with_pre_tokenizer(Some(Whitespace {}));
+/// let mut candidate = primary.clone();
+/// candidate.with_normalizer(Some(Lowercase));
+///
+/// let shadow = ShadowTokenizer::new(primary.into_inner(), candidate.into_inner());
+/// let encoding = shadow.encode("hello Hello", false).unwrap();
+/// assert_eq!(encoding.get_ids(), [1, 0]);
+///
+/// shadow.flush();
+/// let report = shadow.report();
+/// assert_eq!((report.compared, report.diverged), (1, 1));
+/// assert_eq!(report.examples[0].candidate.as_ref().unwrap().get_ids(), [1, 1]);
+/// ```
+pub struct ShadowTokenizer<
+    M = ModelWrapper,
+    N = NormalizerWrapper,
+    PT = PreTokenizerWrapper,
+    PP = PostProcessorWrapper,
+    D = DecoderWrapper,
+> {
+    primary: Arc<TokenizerImpl<M, N, PT, PP, D>>,
+    candidate: Arc<TokenizerImpl<M, N, PT, PP, D>>,
+    report: Arc<Mutex<ShadowReport>>,
+    /// The queue of the background thread, which stops once it gets dropped
+    sender: SyncSender<Job>,
+    /// The number of calls waiting in the queue, checked before copying the inputs
+    pending: Arc<AtomicUsize>,
+    queue_size: usize,
+}
+
+impl<M, N, PT, PP, D> ShadowTokenizer<M, N, PT, PP, D>
+where
+    M: Model + Send + Sync + 'static,
+    N: Normalizer + Send + Sync + 'static,
+    PT: PreTokenizer + Send + Sync + 'static,
+    PP: PostProcessor + Send + Sync + 'static,
+    D: Decoder + Send + Sync + 'static,
+{
+    /// Compare `candidate` to `primary`, with up to 1024 pending calls and 100 examples
+    pub fn new<T>(primary: T, candidate: T) -> Self
+    where
+        T: Into<Arc<TokenizerImpl<M, N, PT, PP, D>>>,
+    {
+        Self::with_limits(primary, candidate, 1024, 100)
+    }
+
+    /// Compare `candidate` to `primary`, with up to `queue_size` calls waiting for the
+    /// candidate, and keeping the first `max_examples` divergences
+    pub fn with_limits<T>(primary: T, candidate: T, queue_size: usize, max_examples: usize) -> Self
+    where
+        T: Into<Arc<TokenizerImpl<M, N, PT, PP, D>>>,
+    {
+        let primary = primary.into();
+        let candidate = candidate.into();
+        let report = Arc::new(Mutex::new(ShadowReport::default()));
+        let (sender, receiver) = mpsc::sync_channel(queue_size);
+        let pending = Arc::new(AtomicUsize::new(0));
+        {
+            let candidate = candidate.clone();
+            let report = report.clone();
+            let pending = pending.clone();
+            std::thread::spawn(move || {
+                compare(&candidate, &report, &pending, max_examples, receiver)
+            });
+        }
+        Self {
+            primary,
+            candidate,
+            report,
+            sender,
+            pending,
+            queue_size,
+        }
+    }
+
+    pub fn primary(&self) -> &TokenizerImpl<M, N, PT, PP, D> {
+        &self.primary
+    }
+
+    pub fn candidate(&self) -> &TokenizerImpl<M, N, PT, PP, D> {
+        &self.candidate
+    }
+
+    /// Encode the input with the primary tokenizer, like [`Tokenizer::encode`], and queue it
+    /// for the candidate
+    pub fn encode<'s, E>(&self, input: E, add_special_tokens: bool) -> Result<Encoding>
+    where
+        E: Into<EncodeInput<'s>>,
+    {
+        let encodings = self.shadowed(
+            vec![input.into()],
+            add_special_tokens,
+            OffsetType::Byte,
+            |mut inputs| {
+                Ok(vec![self
+                    .primary
+                    .encode(inputs.remove(0), add_special_tokens)?])
+            },
+        )?;
+        Ok(encodings.into_iter().next().unwrap())
+    }
+
+    /// Like [`encode`](Self::encode), with offsets relative to chars instead of bytes
+    pub fn encode_char_offsets<'s, E>(&self, input: E, add_special_tokens: bool) -> Result<Encoding>
+    where
+        E: Into<EncodeInput<'s>>,
+    {
+        let encodings = self.shadowed(
+            vec![input.into()],
+            add_special_tokens,
+            OffsetType::Char,
+            |mut inputs| {
+                Ok(vec![self.primary.encode_char_offsets(
+                    inputs.remove(0),
+                    add_special_tokens,
+                )?])
+            },
+        )?;
+        Ok(encodings.into_iter().next().unwrap())
+    }
+
+    /// Encode the inputs with the primary tokenizer, like [`Tokenizer::encode_batch`], and
+    /// queue them for the candidate, which encodes them as a batch too
+    pub fn encode_batch<'s, E>(
+        &self,
+        inputs: Vec<E>,
+        add_special_tokens: bool,
+    ) -> Result<Vec<Encoding>>
+    where
+        E: Into<EncodeInput<'s>> + Send,
+    {
+        let inputs: Vec<EncodeInput> = inputs.into_iter().map(|input| input.into()).collect();
+        self.shadowed(inputs, add_special_tokens, OffsetType::Byte, |inputs| {
+            self.primary.encode_batch(inputs, add_special_tokens)
+        })
+    }
+
+    /// Like [`encode_batch`](Self::encode_batch), with offsets relative to chars instead of
+    /// bytes
+    pub fn encode_batch_char_offsets<'s, E>(
+        &self,
+        inputs: Vec<E>,
+        add_special_tokens: bool,
+    ) -> Result<Vec<Encoding>>
+    where
+        E: Into<EncodeInput<'s>> + Send,
+    {
+        let inputs: Vec<EncodeInput> = inputs.into_iter().map(|input| input.into()).collect();
+        self.shadowed(inputs, add_special_tokens, OffsetType::Char, |inputs| {
+            self.primary
+                .encode_batch_char_offsets(inputs, add_special_tokens)
+        })
+    }
+
+    /// Encode the inputs with `encode`, and queue them with their encodings for the
+    /// candidate. When the queue is full, the inputs are skipped before being copied.
+    fn shadowed<'s, F>(
+        &self,
+        inputs: Vec<EncodeInput<'s>>,
+        add_special_tokens: bool,
+        offsets: OffsetType,
+        encode: F,
+    ) -> Result<Vec<Encoding>>
+    where
+        F: FnOnce(Vec<EncodeInput<'s>>) -> Result<Vec<Encoding>>,
+    {
+        let count = inputs.len();
+        if self.pending.fetch_add(1, Ordering::AcqRel) >= self.queue_size {
+            self.pending.fetch_sub(1, Ordering::AcqRel);
+            self.report.lock().unwrap().skipped += count;
+            return encode(inputs);
+        }
+
+        let owned = inputs.iter().cloned().map(into_owned).collect();
+        let encodings = match encode(inputs) {
+            Ok(encodings) => encodings,
+            Err(e) => {
+                self.pending.fetch_sub(1, Ordering::AcqRel);
+                return Err(e);
+            }
+        };
+        let job = Job::Compare {
+            inputs: owned,
+            encodings: encodings.clone(),
+            add_special_tokens,
+            offsets,
+        };
+        // The queue can still be full of `flush` calls, or the candidate can have panicked
+        if let Err(TrySendError::Full(_) | TrySendError::Disconnected(_)) =
+            self.sender.try_send(job)
+        {
+            self.pending.fetch_sub(1, Ordering::AcqRel);
+            self.report.lock().unwrap().skipped += count;
+        }
+        Ok(encodings)
+    }
+
+    /// Wait for the candidate to compare all the inputs queued so far
+    pub fn flush(&self) {
+        let (done, wait) = mpsc::channel();
+        if self.sender.send(Job::Flush(done)).is_ok() {
+            let _ = wait.recv();
+        }
+    }
+
+    /// A copy of the report so far. The inputs still waiting for the candidate are not part
+    /// of it, unless [`flush`](Self::flush) was called first.
+    pub fn report(&self) -> ShadowReport {
+        self.report.lock().unwrap().clone()
+    }
+
+    /// Start a new report, returning the current one
+    pub fn take_report(&self) -> ShadowReport {
+        std::mem::take(&mut *self.report.lock().unwrap())
+    }
+}
+
+/// The loop of the background thread, comparing the jobs of `receiver` until it gets closed,
+/// or until the candidate panics
+fn compare<M, N, PT, PP, D>(
+    candidate: &TokenizerImpl<M, N, PT, PP, D>,
+    report: &Mutex<ShadowReport>,
+    pending: &AtomicUsize,
+    max_examples: usize,
+    receiver: Receiver<Job>,
+) where
+    M: Model + Send + Sync,
+    N: Normalizer + Send + Sync,
+    PT: PreTokenizer + Send + Sync,
+    PP: PostProcessor + Send + Sync,
+    D: Decoder + Send + Sync,
+{
+    for job in receiver {
+        let (inputs, encodings, add_special_tokens, offsets) = match job {
+            Job::Compare {
+                inputs,
+                encodings,
+                add_special_tokens,
+                offsets,
+            } => {
+                pending.fetch_sub(1, Ordering::AcqRel);
+                (inputs, encodings, add_special_tokens, offsets)
+            }
+            Job::Flush(done) => {
+                let _ = done.send(());
+                continue;
+            }
+        };
+
+        let options = EncodeOptions {
+            add_special_tokens,
+            offsets,
+            ..candidate.encode_options()
+        };
+        let encoded = panic::catch_unwind(AssertUnwindSafe(|| {
+            match candidate.encode_batch_with_options(inputs.clone(), &options) {
+                Ok(candidates) => candidates.into_iter().map(Ok).collect(),
+                // Find which inputs failed
+                Err(_) => inputs
+                    .iter()
+                    .map(|input| {
+                        candidate
+                            .encode_with_options(input.clone(), &options)
+                            .map_err(|e| e.to_string())
+                    })
+                    .collect(),
+            }
+        }));
+
+        let mut report = report.lock().unwrap();
+        let candidates: Vec<std::result::Result<Encoding, String>> = match encoded {
+            Ok(candidates) => candidates,
+            // The candidate may be left in any state, it is not used anymore. Dropping the
+            // receiver makes the next inputs get skipped right away
+            Err(payload) => {
+                report.skipped += inputs.len();
+                report.candidate_panic = Some(
+                    payload
+                        .downcast_ref::<&str>()
+                        .map(|message| message.to_string())
+                        .or_else(|| payload.downcast_ref::<String>().cloned())
+                        .unwrap_or_else(|| "unknown panic".into()),
+                );
+                return;
+            }
+        };
+        for ((input, primary), candidate) in inputs.into_iter().zip(encodings).zip(candidates) {
+            report.compared += 1;
+            let same = matches!(&candidate, Ok(c) if c.get_ids() == primary.get_ids());
+            if !same {
+                report.diverged += 1;
+                if report.examples.len() < max_examples {
+                    report.examples.push(ShadowDivergence {
+                        input,
+                        primary,
+                        candidate,
+                    });
+                }
+            }
+        }
+    }
+}
+
+fn into_owned(input: EncodeInput) -> EncodeInput<'static> {
+    fn sequence(sequence: InputSequence) -> InputSequence<'static> {
+        match sequence {
+            InputSequence::Raw(s) => InputSequence::Raw(Cow::Owned(s.into_owned())),
+            InputSequence::PreTokenized(words) => InputSequence::PreTokenizedOwned(Cow::Owned(
+                words.iter().map(|word| word.to_string()).collect(),
+            )),
+            InputSequence::PreTokenizedOwned(words) => {
+                InputSequence::PreTokenizedOwned(Cow::Owned(words.into_owned()))
+            }
+            InputSequence::PreTokenizedCow(words) => InputSequence::PreTokenizedOwned(Cow::Owned(
+                words.iter().map(|word| word.to_string()).collect(),
+            )),
+        }
+    }
+    match input {
+        EncodeInput::Single(s) => EncodeInput::Single(sequence(s)),
+        EncodeInput::Dual(s, pair) => EncodeInput::Dual(sequence(s), sequence(pair)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::wordlevel::WordLevel;
+    use crate::pre_tokenizers::whitespace::Whitespace;
+    use crate::{PreTokenizedString, Tokenizer};
+
+    fn tokenizer(words: &[&str]) -> Tokenizer {
+        let vocab = words
+            .iter()
+            .enumerate()
+            .map(|(id, word)| (word.to_string(), id as u32))
+            .collect();
+        let model = WordLevel::builder()
+            .vocab(vocab)
+            .unk_token("[UNK]".into())
+            .build()
+            .unwrap();
+        let mut tokenizer = Tokenizer::new(model);
+        tokenizer.with_pre_tokenizer(Some(Whitespace {}));
+        tokenizer
+    }
+
+    fn shadow(primary: &[&str], candidate: &[&str], max_examples: usize) -> ShadowTokenizer {
+        ShadowTokenizer::with_limits(
+            tokenizer(primary).into_inner(),
+            tokenizer(candidate).into_inner(),
+            16,
+            max_examples,
+        )
+    }
+
+    #[test]
+    fn report() {
+        let shadow = shadow(&["[UNK]", "a", "b"], &["[UNK]", "a", "c"], 1);
+        let encodings = shadow
+            .encode_batch(vec!["a", "a b", "b b", "c"], false)
+            .unwrap();
+        assert_eq!(encodings[1].get_ids(), [1, 2]);
+        let words: &[&str] = &["a", "c"];
+        let encoding = shadow.encode_char_offsets(words, false).unwrap();
+        assert_eq!(encoding.get_ids(), [1, 0]);
+
+        shadow.flush();
+        let report = shadow.take_report();
+        assert_eq!(report.compared, 5);
+        assert_eq!(report.diverged, 4);
+        assert_eq!(report.skipped, 0);
+        assert_eq!(report.divergence_rate(), 0.8);
+        assert_eq!(report.examples.len(), 1);
+        let example = &report.examples[0];
+        assert!(matches!(
+            &example.input,
+            EncodeInput::Single(InputSequence::Raw(s)) if s == "a b"
+        ));
+        assert_eq!(example.candidate.as_ref().unwrap().get_ids(), [1, 0]);
+
+        assert_eq!(shadow.report().compared, 0);
+        assert_eq!(ShadowReport::default().divergence_rate(), 0.0);
+    }
+
+    #[test]
+    fn candidate_errors() {
+        // Without `[UNK]`, the candidate fails on the unknown words
+        let shadow = shadow(&["[UNK]", "a"], &["x", "a"], 100);
+        shadow.encode_batch(vec!["a", "b"], false).unwrap();
+        shadow.encode("a", false).unwrap();
+
+        shadow.flush();
+        let report = shadow.report();
+        assert_eq!((report.compared, report.diverged), (3, 1));
+        assert!(report.examples[0].candidate.is_err());
+        assert_eq!(report.examples[0].primary.get_ids(), [0]);
+    }
+
+    #[test]
+    fn full_queue() {
+        // Without any room in the queue, every input gets skipped
+        let shadow = ShadowTokenizer::with_limits(
+            tokenizer(&["[UNK]", "a"]).into_inner(),
+            tokenizer(&["[UNK]", "b"]).into_inner(),
+            0,
+            100,
+        );
+        let encodings = shadow.encode_batch(vec!["a", "a b"], false).unwrap();
+        assert_eq!(encodings[1].get_ids(), [1, 0]);
+        assert_eq!(shadow.encode("a", false).unwrap().get_ids(), [1]);
+
+        shadow.flush();
+        let report = shadow.report();
+        assert_eq!((report.compared, report.skipped), (0, 3));
+    }
+
+    /// Split on whitespaces, or panic
+    struct PanickingPreTokenizer(bool);
+
+    impl PreTokenizer for PanickingPreTokenizer {
+        fn pre_tokenize(&self, pretokenized: &mut PreTokenizedString) -> Result<()> {
+            assert!(!self.0, "the candidate panicked");
+            Whitespace {}.pre_tokenize(pretokenized)
+        }
+    }
+
+    #[test]
+    fn candidate_panic() {
+        let tokenizer = |panics| {
+            let mut tokenizer: TokenizerImpl<
+                _,
+                NormalizerWrapper,
+                PanickingPreTokenizer,
+                PostProcessorWrapper,
+                DecoderWrapper,
+            > = TokenizerImpl::new(tokenizer(&["[UNK]", "a"]).into_inner().get_model().clone());
+            tokenizer.with_pre_tokenizer(Some(PanickingPreTokenizer(panics)));
+            tokenizer
+        };
+        let shadow = ShadowTokenizer::with_limits(tokenizer(false), tokenizer(true), 2, 100);
+        shadow.encode_batch(vec!["a", "a b"], false).unwrap();
+        shadow.flush();
+        // Past the size of the queue, the inputs still get encoded and skipped
+        for _ in 0..4 {
+            assert_eq!(shadow.encode("a", false).unwrap().get_ids(), [1]);
+        }
+        shadow.flush();
+
+        let report = shadow.report();
+        assert_eq!((report.compared, report.skipped), (0, 6));
+        assert_eq!(
+            report.candidate_panic.as_deref(),
+            Some("the candidate panicked")
+        );
+    }
+}
@@ -0,0 +1,370 @@
+use super::{
+    Decoder, Encoding, InputSequence, Model, Normalizer, OffsetType, Offsets, PostProcessor,
+    PreTokenizer, Result, TokenizerImpl, TruncationDirection,
+};
+use crate::utils::padding::pad_encodings;
+use crate::utils::parallelism::*;
+use std::ops::Range;
+
+/// Extract the tokens in the given range
+pub(super) fn slice(encoding: &Encoding, range: Range<usize>) -> Encoding {
+    let mut sliced = encoding.clone();
+    sliced.truncate_with_max_overflowing(range.end, 0, TruncationDirection::Right, Some(0));
+    sliced.truncate_with_max_overflowing(range.len(), 0, TruncationDirection::Left, Some(0));
+    sliced
+}
+
+/// Shift the offsets and the word ids of the encoding
+pub(super) fn shift(encoding: &mut Encoding, offsets: isize, words: i64) {
+    for (start, end) in encoding.get_offsets_mut() {
+        *start = (*start as isize + offsets) as usize;
+        *end = (*end as isize + offsets) as usize;
+    }
+    for word in encoding.get_word_ids_mut().iter_mut().flatten() {
+        *word = (*word as i64 + words) as u32;
+    }
+}
+
+/// Whether the tokens of a word of `encoding` are found again as a whole word of the
+/// re-encoded `part` from `start`, with the same ids and their offsets shifted by `offset`.
+/// This proves that re-encoding the text around the word did not change it.
+pub(super) fn same_word(
+    encoding: &Encoding,
+    word: Range<usize>,
+    part: &Encoding,
+    start: usize,
+    offset: isize,
+) -> bool {
+    let end = start + word.len();
+    let words = part.get_word_ids();
+    let shifted = |(s, e): &Offsets| {
+        (
+            (*s as isize + offset) as usize,
+            (*e as isize + offset) as usize,
+        )
+    };
+    part.get_ids().get(start..end) == encoding.get_ids().get(word.clone())
+        && part.get_offsets().get(start..end).is_some_and(|offsets| {
+            encoding.get_offsets()[word.clone()]
+                .iter()
+                .map(shifted)
+                .eq(offsets.iter().copied())
+        })
+        && words.get(start).copied().flatten().is_some()
+        && (start == 0 || words.get(start) != words.get(start - 1))
+        && words.get(end) != words.get(end - 1)
+}
+
+/// The encoding of a prefix shared by a batch, split where the suffixes start re-encoding it
+struct SharedPrefix {
+    /// The tokens of the prefix before its last two words, which no suffix can change
+    head: Encoding,
+    /// The tokens of the word before the last one, which must not change when re-encoded
+    /// with a suffix, proving that the suffix does not reach back into the head
+    anchor: Encoding,
+    /// Where the re-encoded part of the prefix starts, in bytes
+    cut: usize,
+    /// The offset of the anchor in the prefix, and the id of its word
+    offset: usize,
+    word: u32,
+}
+
+impl SharedPrefix {
+    /// Split the encoding of the prefix, or return `None` when its last two words are not
+    /// both made of regular tokens, and no part of it can be reused
+    fn new(prefix: &str, encoding: Encoding, offsets_type: OffsetType) -> Option<Self> {
+        let words = encoding.get_word_ids();
+        let word_start = |end: usize| {
+            let word = words.get(end.checked_sub(1)?).copied()??;
+            let start = words[..end].iter().rposition(|w| *w != Some(word));
+            Some((start.map_or(0, |s| s + 1), word))
+        };
+        let (last_start, _) = word_start(words.len())?;
+        let (anchor_start, word) = word_start(last_start)?;
+
+        let offset = encoding.get_offsets()[anchor_start].0;
+        let cut = match offsets_type {
+            OffsetType::Char => prefix.char_indices().nth(offset)?.0,
+            _ => offset,
+        };
+        // The tokens on both sides of the cut must split the prefix there
+        if anchor_start > 0 && encoding.get_offsets()[anchor_start - 1].1 > offset {
+            return None;
+        }
+
+        Some(Self {
+            head: slice(&encoding, 0..anchor_start),
+            anchor: slice(&encoding, anchor_start..last_start),
+            cut,
+            offset,
+            word,
+        })
+    }
+
+    /// Append the encoding of the rest of the prefix followed by the suffix to the head,
+    /// or return `None` if the suffix changed the tokens of the anchor
+    fn splice(&self, mut tail: Encoding) -> Option<Encoding> {
+        let first_word = tail.get_word_ids().first().copied()??;
+        // The last word of the prefix, and the suffix, come after the anchor
+        if tail.len() <= self.anchor.len()
+            || !same_word(
+                &self.anchor,
+                0..self.anchor.len(),
+                &tail,
+                0,
+                -(self.offset as isize),
+            )
+        {
+            return None;
+        }
+
+        shift(
+            &mut tail,
+            self.offset as isize,
+            self.word as i64 - first_word as i64,
+        );
+        let mut encoding = self.head.clone();
+        encoding.merge_with(tail, false);
+        Some(encoding)
+    }
+}
+
+impl<M, N, PT, PP, D> TokenizerImpl<M, N, PT, PP, D>
+where
+    M: Model + Send + Sync,
+    N: Normalizer + Send + Sync,
+    PT: PreTokenizer + Send + Sync,
+    PP: PostProcessor + Send + Sync,
+    D: Decoder + Send + Sync,
+{
+    /// Encode the prefix followed by each of the suffixes, in parallel, like
+    /// [`encode_batch`](Self::encode_batch) would encode the concatenated sequences.
+    ///
+    /// The prefix is tokenized once: only its last two words are tokenized again with each
+    /// suffix, as the suffix may merge with the last one. When the word before it does not
+    /// keep the same tokens, the suffix reaches further back, and the whole sequence gets
+    /// encoded instead, like when an added token could be extracted across the cut. This
+    /// makes long prompts shared by a batch, like few-shot examples, cost nothing but their
+    /// last words.
+    /// ```
+    /// # use tokenizers::Tokenizer;
+    /// # use tokenizers::models::bpe::BPE;
+    /// # use tokenizers::pre_tokenizers::whitespace::Whitespace;
+    /// # use tokenizers::AddedToken;
+    /// # let mut tokenizer = Tokenizer::new(BPE::default());
+    /// # tokenizer.with_pre_tokenizer(Some(Whitespace {}));
+    /// # tokenizer.add_tokens(&["my", "name", "is", "john"].map(|t| AddedToken::from(t, false)));
+    /// let encodings = tokenizer
+    ///     .encode_batch_with_prefix("my name", vec![" is john", " is"], false)
+    ///     .unwrap();
+    /// assert_eq!(encodings[0].get_tokens(), ["my", "name", "is", "john"]);
+    /// assert_eq!(encodings[1].get_offsets()[2], (8, 10));
+    /// ```
+    pub fn encode_batch_with_prefix<S>(
+        &self,
+        prefix: &str,
+        suffixes: Vec<S>,
+        add_special_tokens: bool,
+    ) -> Result<Vec<Encoding>>
+    where
+        S: AsRef<str> + Send,
+    {
+        self.encode_batch_with_prefix_and_offsets(
+            prefix,
+            suffixes,
+            add_special_tokens,
+            OffsetType::Byte,
+        )
+    }
+
+    /// Encode the prefix followed by each of the suffixes, in parallel, using offsets
+    /// relative to chars instead of bytes.
+    /// See [`encode_batch_with_prefix`](Self::encode_batch_with_prefix)
+    pub fn encode_batch_char_offsets_with_prefix<S>(
+        &self,
+        prefix: &str,
+        suffixes: Vec<S>,
+        add_special_tokens: bool,
+    ) -> Result<Vec<Encoding>>
+    where
+        S: AsRef<str> + Send,
+    {
+        self.encode_batch_with_prefix_and_offsets(
+            prefix,
+            suffixes,
+            add_special_tokens,
+            OffsetType::Char,
+        )
+    }
+
+    fn encode_batch_with_prefix_and_offsets<S>(
+        &self,
+        prefix: &str,
+        suffixes: Vec<S>,
+        add_special_tokens: bool,
+        offsets_type: OffsetType,
+    ) -> Result<Vec<Encoding>>
+    where
+        S: AsRef<str> + Send,
+    {
+        let encoding = self.encode_single_sequence(InputSequence::from(prefix), 0, offsets_type)?;
+        let shared = SharedPrefix::new(prefix, encoding, offsets_type);
+
+        self.parallelism.install(|parallel| {
+            let mut encodings = suffixes
+                .into_maybe_par_iter_cond(parallel)
+                .map(|suffix| {
+                    let sequence = format!("{prefix}{}", suffix.as_ref());
+                    let spliced = match &shared {
+                        // An added token crossing the cut is only found in the whole sequence
+                        Some(shared)
+                            if !self.added_vocabulary.may_extract_across(
+                                self.normalizer.as_ref(),
+                                &sequence,
+                                shared.cut,
+                            ) =>
+                        {
+                            shared.splice(self.encode_single_sequence(
+                                InputSequence::from(&sequence[shared.cut..]),
+                                0,
+                                offsets_type,
+                            )?)
+                        }
+                        _ => None,
+                    };
+                    let encoding = match spliced {
+                        Some(encoding) => encoding,
+                        None => self.encode_single_sequence(
+                            InputSequence::from(sequence),
+                            0,
+                            offsets_type,
+                        )?,
+                    };
+                    self.post_process(encoding, None, add_special_tokens)
+                })
+                .collect::<Result<Vec<Encoding>>>()?;
+
+            if let Some(params) = &self.padding {
+                // We do the padding here to make sure we handle the batch padding
+                pad_encodings(&mut encodings, params)?;
+            }
+
+            Ok(encodings)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::bpe::BPE;
+    use crate::pre_tokenizers::byte_level::ByteLevel;
+    use crate::tokenizer::{AddedToken, Tokenizer};
+    use crate::{PaddingParams, TruncationParams};
+
+    fn tokenizer() -> Tokenizer {
+        let vocab = [
+            "a", "b", "c", "d", " ", "Ġ", "ab", "cd", "abcd", "Ġa", "Ġab", "Ġabcd", "Ġd",
+        ];
+        let vocab: ahash::AHashMap<_, _> = vocab
+            .iter()
+            .enumerate()
+            .map(|(i, t)| (t.to_string(), i as u32))
+            .collect();
+        let merges = vec![
+            ("Ġ".into(), "a".into()),
+            ("Ġ".into(), "d".into()),
+            ("Ġa".into(), "b".into()),
+            ("a".into(), "b".into()),
+            ("c".into(), "d".into()),
+            ("ab".into(), "cd".into()),
+            ("Ġab".into(), "cd".into()),
+        ];
+        let bpe = BPE::builder()
+            .vocab_and_merges(vocab, merges)
+            .build()
+            .unwrap();
+        let mut tokenizer = Tokenizer::new(bpe);
+        tokenizer.with_pre_tokenizer(Some(ByteLevel::new(false, false, true)));
+        tokenizer
+    }
+
+    #[test]
+    fn same_as_concatenated() {
+        let mut tokenizer = tokenizer();
+        let suffixes = vec!["", "cd", " ab", "cd abcd", "dd", " ", "é"];
+        for prefix in ["ab abcd ab", "éab abcd ab", "ab  ab", "", "ab", " ", "ab "] {
+            let expected = |char_offsets: bool| {
+                suffixes
+                    .iter()
+                    .map(|s| {
+                        let sequence = format!("{prefix}{s}");
+                        match char_offsets {
+                            true => tokenizer.encode_char_offsets(sequence, false),
+                            false => tokenizer.encode(sequence, false),
+                        }
+                        .unwrap()
+                    })
+                    .collect::<Vec<_>>()
+            };
+            let encodings = tokenizer
+                .encode_batch_with_prefix(prefix, suffixes.clone(), false)
+                .unwrap();
+            assert_eq!(encodings, expected(false));
+            let encodings = tokenizer
+                .encode_batch_char_offsets_with_prefix(prefix, suffixes.clone(), false)
+                .unwrap();
+            assert_eq!(encodings, expected(true));
+        }
+        let encodings = tokenizer
+            .encode_batch_with_prefix("ab abcd ab", vec!["cd abcd"], false)
+            .unwrap();
+        assert_eq!(encodings[0].get_tokens(), ["ab", "Ġabcd", "Ġabcd", "Ġabcd"]);
+
+        // The added token reaches back into the anchor, the whole sequence is encoded again
+        tokenizer.add_tokens(&[AddedToken::from("b abcd ab", false)]);
+        let encodings = tokenizer
+            .encode_batch_with_prefix("ab ab abcd", vec![" ab"], false)
+            .unwrap();
+        assert_eq!(encodings[0].get_tokens(), ["ab", "Ġa", "b abcd ab"]);
+        assert_eq!(
+            encodings[0],
+            tokenizer.encode("ab ab abcd ab", false).unwrap()
+        );
+
+        // The added token starts in the head, before the re-encoded words
+        let mut tokenizer = self::tokenizer();
+        tokenizer.add_tokens(&[AddedToken::from("b ab abcd ab", false)]);
+        let encodings = tokenizer
+            .encode_batch_with_prefix("ab ab abcd", vec![" ab", " abcd"], false)
+            .unwrap();
+        assert_eq!(encodings[0].get_tokens(), ["a", "b ab abcd ab"]);
+        assert_eq!(
+            encodings,
+            tokenizer
+                .encode_batch(vec!["ab ab abcd ab", "ab ab abcd abcd"], false)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn post_processing() {
+        let mut tokenizer = tokenizer();
+        tokenizer
+            .with_truncation(Some(TruncationParams {
+                max_length: 3,
+                ..Default::default()
+            }))
+            .unwrap();
+        tokenizer.with_padding(Some(PaddingParams::default()));
+        let encodings = tokenizer
+            .encode_batch_with_prefix("ab ab", vec![" abcd abcd", ""], true)
+            .unwrap();
+        assert_eq!(
+            encodings,
+            tokenizer
+                .encode_batch(vec!["ab ab abcd abcd", "ab ab"], true)
+                .unwrap()
+        );
+        assert_eq!(encodings[1].get_attention_mask(), [1, 1, 0]);
+    }
+}
@@ -0,0 +1,427 @@
+use super::{AddedVocabulary, Encoding, Offsets, Result};
+use crate::utils::parallelism::*;
+use ahash::AHashSet;
+use std::ops::Range;
+
+#[derive(thiserror::Error, Debug)]
+pub enum TokenPatternError {
+    #[error("A token pattern must match at least one token")]
+    EmptyMatch,
+    #[error("Invalid gap in the token pattern, its minimum {min} is above its maximum {max}")]
+    InvalidGap { min: usize, max: usize },
+}
+
+/// One element of a [`TokenPattern`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenMatcher {
+    /// The token with this id
+    Id(u32),
+    /// Any token with one of these ids, like a class of added tokens
+    OneOf(Vec<u32>),
+    /// Any token
+    Any,
+    /// Any special token, as given by the special tokens mask of the encoding. This
+    /// includes the tokens added by the post-processor
+    Special,
+    /// Between `min` and `max` tokens, whatever they are. The gaps are lazy: the shortest
+    /// one leading to a match is used
+    Gap { min: usize, max: usize },
+}
+
+impl TokenMatcher {
+    /// The class of the added tokens of `vocabulary`, either the special ones or the others
+    pub fn added_tokens(vocabulary: &AddedVocabulary, special: bool) -> Self {
+        Self::OneOf(
+            vocabulary
+                .get_added_tokens_decoder()
+                .iter()
+                .filter(|(_, token)| token.special == special)
+                .map(|(id, _)| *id)
+                .collect(),
+        )
+    }
+
+    /// Whether the token at `index` of the encoding matches, for all but the gaps
+    fn matches(&self, encoding: &Encoding, index: usize) -> bool {
+        match self {
+            Self::Id(id) => encoding.get_ids()[index] == *id,
+            Self::OneOf(ids) => ids.binary_search(&encoding.get_ids()[index]).is_ok(),
+            Self::Any => true,
+            Self::Special => encoding.get_special_tokens_mask()[index] == 1,
+            Self::Gap { .. } => unreachable!("the gaps don't match single tokens"),
+        }
+    }
+}
+
+/// An occurrence of a [`TokenPattern`] in an [`Encoding`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenMatch {
+    /// The indices of the matched tokens
+    pub tokens: Range<usize>,
+    /// The input sequence of the matched tokens, or `None` if they were all added by the
+    /// post-processor
+    pub sequence: Option<usize>,
+    /// The span of the input sequence covered by the matched tokens, in the same unit as
+    /// the offsets of the encoding. `None` if they were all added by the post-processor
+    pub offsets: Option<Offsets>,
+}
+
+/// A pattern of token ids, to find in the encodings without decoding them, like the
+/// tokens of some forbidden words.
+///
+/// Each token of a match belongs to the same input sequence, or was added by the
+/// post-processor, and the padding tokens never match.
+/// ```
+/// use tokenizers::{Encoding, Token, TokenMatcher, TokenPattern};
+///
+/// let token = |id, value: &str, offsets| Token::new(id, value.into(), offsets);
+/// let encoding = Encoding::from_tokens(
+///     vec![
+///         token(0, "drop", (0, 4)),
+///         token(1, "the", (5, 8)),
+///         token(2, "table", (9, 14)),
+///         token(0, "drop", (15, 19)),
+///         token(2, "table", (20, 25)),
+///     ],
+///     0,
+/// );
+/// let pattern = TokenPattern::new(vec![
+///     TokenMatcher::Id(0),
+///     TokenMatcher::Gap { min: 0, max: 1 },
+///     TokenMatcher::OneOf(vec![2, 3]),
+/// ])
+/// .unwrap();
+///
+/// let matches = pattern.find_all(&encoding);
+/// assert_eq!(matches.len(), 2);
+/// assert_eq!(matches[0].tokens, 0..3);
+/// assert_eq!(matches[0].offsets, Some((0, 14)));
+/// assert_eq!(matches[1].tokens, 3..5);
+/// assert_eq!(matches[1].offsets, Some((15, 25)));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenPattern {
+    matchers: Vec<TokenMatcher>,
+}
+
+impl TokenPattern {
+    /// Build a pattern matching these elements one after the other. The pattern must match
+    /// at least one token, and its gaps must have `min <= max`
+    pub fn new(mut matchers: Vec<TokenMatcher>) -> Result<Self> {
+        let mut min_len = 0;
+        for matcher in &mut matchers {
+            match matcher {
+                TokenMatcher::OneOf(ids) => {
+                    ids.sort_unstable();
+                    ids.dedup();
+                    min_len += 1;
+                }
+                TokenMatcher::Gap { min, max } => {
+                    if min > max {
+                        return Err(TokenPatternError::InvalidGap {
+                            min: *min,
+                            max: *max,
+                        }
+                        .into());
+                    }
+                    min_len += *min;
+                }
+                _ => min_len += 1,
+            }
+        }
+        if min_len == 0 {
+            return Err(TokenPatternError::EmptyMatch.into());
+        }
+        Ok(Self { matchers })
+    }
+
+    /// The elements of the pattern
+    pub fn matchers(&self) -> &[TokenMatcher] {
+        &self.matchers
+    }
+
+    /// Find all the occurrences of the pattern in the encoding, from left to right and
+    /// without overlaps. Its overflowing encodings are ignored
+    pub fn find_all(&self, encoding: &Encoding) -> Vec<TokenMatch> {
+        let mut matches = vec![];
+        let mut failed = AHashSet::new();
+        let mut start = 0;
+        while start < encoding.len() {
+            match self.match_at(encoding, &mut failed, 0, start, None) {
+                Some((end, sequence)) => {
+                    matches.push(Self::token_match(encoding, start..end, sequence));
+                    start = end;
+                }
+                None => start += 1,
+            }
+        }
+        matches
+    }
+
+    /// Whether the pattern occurs in the encoding
+    pub fn is_match(&self, encoding: &Encoding) -> bool {
+        let mut failed = AHashSet::new();
+        (0..encoding.len()).any(|start| {
+            self.match_at(encoding, &mut failed, 0, start, None)
+                .is_some()
+        })
+    }
+
+    /// The [occurrences](TokenPattern::find_all) of the pattern in each encoding of a batch,
+    /// computed in parallel
+    pub fn find_all_batch(&self, encodings: &[Encoding]) -> Vec<Vec<TokenMatch>> {
+        encodings
+            .maybe_par_iter()
+            .map(|encoding| self.find_all(encoding))
+            .collect()
+    }
+
+    /// Try to match the matchers from `matcher` on, at the token `index`, with the tokens
+    /// already matched belonging to `sequence`. Returns the end of the match and its sequence.
+    ///
+    /// These states only depend on the encoding, so the ones in `failed` are known not to
+    /// match, which keeps the backtracking of the gaps polynomial.
+    fn match_at(
+        &self,
+        encoding: &Encoding,
+        failed: &mut AHashSet<(usize, usize, Option<usize>)>,
+        matcher: usize,
+        index: usize,
+        sequence: Option<usize>,
+    ) -> Option<(usize, Option<usize>)> {
+        if matcher == self.matchers.len() {
+            return Some((index, sequence));
+        }
+        if failed.contains(&(matcher, index, sequence)) {
+            return None;
+        }
+        let found = self.match_from(encoding, failed, matcher, index, sequence);
+        if found.is_none() {
+            failed.insert((matcher, index, sequence));
+        }
+        found
+    }
+
+    /// Match the `matcher`, then the following ones, see [`TokenPattern::match_at`]
+    fn match_from(
+        &self,
+        encoding: &Encoding,
+        failed: &mut AHashSet<(usize, usize, Option<usize>)>,
+        matcher: usize,
+        index: usize,
+        sequence: Option<usize>,
+    ) -> Option<(usize, Option<usize>)> {
+        let current = &self.matchers[matcher];
+
+        // The sequence of the match, if the token at `index` can be part of it
+        let next_sequence = |index: usize, sequence: Option<usize>| -> Option<Option<usize>> {
+            if index >= encoding.len() || encoding.get_attention_mask()[index] == 0 {
+                return None;
+            }
+            match (sequence, encoding.token_to_sequence(index)) {
+                (Some(sequence), Some(other)) if sequence != other => None,
+                (sequence, other) => Some(sequence.or(other)),
+            }
+        };
+
+        match current {
+            TokenMatcher::Gap { min, max } => {
+                let start = index;
+                let mut sequence = sequence;
+                for index in index..=index.saturating_add(*max) {
+                    if index - start >= *min {
+                        let found = self.match_at(encoding, failed, matcher + 1, index, sequence);
+                        if found.is_some() {
+                            return found;
+                        }
+                    }
+                    sequence = next_sequence(index, sequence)?;
+                }
+                None
+            }
+            current => {
+                let sequence = next_sequence(index, sequence)?;
+                if current.matches(encoding, index) {
+                    self.match_at(encoding, failed, matcher + 1, index + 1, sequence)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    fn token_match(
+        encoding: &Encoding,
+        tokens: Range<usize>,
+        sequence: Option<usize>,
+    ) -> TokenMatch {
+        let offsets = sequence.and_then(|sequence| {
+            let mut offsets = tokens
+                .clone()
+                .filter(|i| encoding.token_to_sequence(*i) == Some(sequence))
+                .map(|i| encoding.get_offsets()[i]);
+            let first = offsets.next()?;
+            Some(offsets.fold(first, |(start, end), (s, e)| (start.min(s), end.max(e))))
+        });
+        TokenMatch {
+            tokens,
+            sequence,
+            offsets,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::wordlevel::WordLevel;
+    use crate::normalizers::NormalizerWrapper;
+    use crate::AddedToken;
+    use ahash::AHashMap;
+    use std::iter::FromIterator;
+
+    // [CLS] a b [SEP] a b [SEP] [PAD]
+    fn encoding() -> Encoding {
+        Encoding::new(
+            vec![100, 1, 2, 101, 1, 2, 101, 0],
+            vec![0, 0, 0, 0, 1, 1, 1, 0],
+            vec![],
+            vec![None, Some(0), Some(1), None, Some(0), Some(1), None, None],
+            vec![
+                (0, 0),
+                (0, 1),
+                (2, 3),
+                (0, 0),
+                (0, 1),
+                (1, 2),
+                (0, 0),
+                (0, 0),
+            ],
+            vec![1, 0, 0, 1, 0, 0, 1, 1],
+            vec![1, 1, 1, 1, 1, 1, 1, 0],
+            vec![],
+            AHashMap::from_iter(vec![(0, 1..3), (1, 4..6)]),
+        )
+    }
+
+    fn spans(pattern: &TokenPattern) -> Vec<(Range<usize>, Option<usize>, Option<Offsets>)> {
+        pattern
+            .find_all(&encoding())
+            .into_iter()
+            .map(|m| (m.tokens, m.sequence, m.offsets))
+            .collect()
+    }
+
+    #[test]
+    fn find_all() {
+        let pattern = TokenPattern::new(vec![TokenMatcher::Id(1), TokenMatcher::Id(2)]).unwrap();
+        assert_eq!(
+            spans(&pattern),
+            vec![(1..3, Some(0), Some((0, 3))), (4..6, Some(1), Some((0, 2)))]
+        );
+
+        // The special tokens go with any sequence, but not the padding
+        let pattern = TokenPattern::new(vec![TokenMatcher::Special, TokenMatcher::Any]).unwrap();
+        assert_eq!(
+            spans(&pattern),
+            vec![(0..2, Some(0), Some((0, 1))), (3..5, Some(1), Some((0, 1)))]
+        );
+        let pattern = TokenPattern::new(vec![TokenMatcher::Special]).unwrap();
+        assert_eq!(
+            spans(&pattern),
+            vec![(0..1, None, None), (3..4, None, None), (6..7, None, None)]
+        );
+
+        // A match can't cover two sequences
+        let pattern = TokenPattern::new(vec![
+            TokenMatcher::Id(2),
+            TokenMatcher::Special,
+            TokenMatcher::Id(1),
+        ])
+        .unwrap();
+        assert!(!pattern.is_match(&encoding()));
+        assert!(pattern.find_all(&encoding()).is_empty());
+    }
+
+    #[test]
+    fn gaps() {
+        let pattern = TokenPattern::new(vec![
+            TokenMatcher::Id(100),
+            TokenMatcher::Gap { min: 0, max: 10 },
+            TokenMatcher::OneOf(vec![101, 2]),
+        ])
+        .unwrap();
+        assert_eq!(spans(&pattern), vec![(0..3, Some(0), Some((0, 3)))]);
+
+        let pattern = TokenPattern::new(vec![
+            TokenMatcher::Id(1),
+            TokenMatcher::Gap { min: 2, max: 3 },
+            TokenMatcher::Id(1),
+        ])
+        .unwrap();
+        assert!(!pattern.is_match(&encoding()));
+
+        let pattern = TokenPattern::new(vec![
+            TokenMatcher::Id(101),
+            TokenMatcher::Gap { min: 1, max: 1 },
+        ])
+        .unwrap();
+        assert_eq!(spans(&pattern), vec![(3..5, Some(1), Some((0, 1)))]);
+
+        // Many gaps failing to match stay fast
+        let tokens = (0..200)
+            .map(|i| crate::Token::new(1, "a".into(), (i, i + 1)))
+            .collect();
+        let long = Encoding::from_tokens(tokens, 0);
+        let mut matchers = vec![TokenMatcher::Gap { min: 0, max: 30 }; 8];
+        matchers.push(TokenMatcher::Id(2));
+        let pattern = TokenPattern::new(matchers).unwrap();
+        assert!(!pattern.is_match(&long));
+        assert!(pattern.find_all(&long).is_empty());
+    }
+
+    #[test]
+    fn invalid_patterns() {
+        let error = TokenPattern::new(vec![]).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<TokenPatternError>(),
+            Some(TokenPatternError::EmptyMatch)
+        ));
+        let error = TokenPattern::new(vec![TokenMatcher::Gap { min: 0, max: 2 }]).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<TokenPatternError>(),
+            Some(TokenPatternError::EmptyMatch)
+        ));
+        let error = TokenPattern::new(vec![TokenMatcher::Gap { min: 3, max: 1 }]).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<TokenPatternError>(),
+            Some(TokenPatternError::InvalidGap { min: 3, max: 1 })
+        ));
+    }
+
+    #[test]
+    fn added_tokens() {
+        let vocab = [("a".into(), 0)].iter().cloned().collect();
+        let model = WordLevel::builder().vocab(vocab).build().unwrap();
+        let mut vocabulary = AddedVocabulary::new();
+        vocabulary.add_tokens(
+            &[
+                AddedToken::from("[CLS]", true),
+                AddedToken::from("<name>", false),
+                AddedToken::from("[SEP]", true),
+            ],
+            &model,
+            None::<&NormalizerWrapper>,
+        );
+
+        let mut special = TokenMatcher::added_tokens(&vocabulary, true);
+        if let TokenMatcher::OneOf(ids) = &mut special {
+            ids.sort_unstable();
+        }
+        assert_eq!(special, TokenMatcher::OneOf(vec![1, 3]));
+        assert_eq!(
+            TokenMatcher::added_tokens(&vocabulary, false),
+            TokenMatcher::OneOf(vec![2])
+        );
+    }
+}
@@ -0,0 +1,170 @@
+use crate::{Encoding, Offsets, Token};
+
+/// A step of the pipeline recorded by [`TokenizerImpl::encode_with_trace`](super::TokenizerImpl::encode_with_trace)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TraceStep {
+    /// The extraction of the added tokens matching the original input
+    AddedTokens,
+    /// A normalizer, with its name. Each normalizer of a `Sequence` is a step of its own.
+    Normalizer(String),
+    /// The extraction of the added tokens matching the normalized input
+    NormalizedAddedTokens,
+    /// The pre-tokenizer
+    PreTokenizer,
+}
+
+/// A split of the input, as it was after a step
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceSplit {
+    /// The content of the split
+    pub value: String,
+    /// The offsets of the split in the original input, in bytes
+    pub offsets: Offsets,
+    /// The offsets in the original input of each char of `value`, in bytes
+    pub alignments: Vec<Offsets>,
+    /// The tokens of the split, when it is an added token
+    pub tokens: Option<Vec<Token>>,
+}
+
+/// The result of a step, with the splits it produced
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceStage {
+    pub step: TraceStep,
+    pub splits: Vec<TraceSplit>,
+}
+
+/// The trace of the encoding of one sequence, or one word of a pre-tokenized sequence
+#[derive(Debug, Clone, PartialEq)]
+pub struct SequenceTrace {
+    /// The type id of the sequence, `0` for the first one and `1` for the pair
+    pub type_id: u32,
+    /// The index of the word in a pre-tokenized sequence
+    pub word_idx: Option<u32>,
+    /// The stages before the model, in the order they ran
+    pub stages: Vec<TraceStage>,
+    /// The output of the model, before any post-processing
+    pub encoding: Encoding,
+}
+
+/// The trace of each stage of the pipeline, returned by
+/// [`TokenizerImpl::encode_with_trace`](super::TokenizerImpl::encode_with_trace)
+#[derive(Debug, Clone, PartialEq)]
+pub struct EncodeTrace {
+    pub sequences: Vec<SequenceTrace>,
+    /// The final output of the post-processor, as returned by `encode`
+    pub encoding: Encoding,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::wordlevel::WordLevel;
+    use crate::normalizers::{Replace, Sequence, NFKC};
+    use crate::pre_tokenizers::whitespace::Whitespace;
+    use crate::{AddedToken, Tokenizer};
+
+    fn tokenizer() -> Tokenizer {
+        let vocab = [("[UNK]", 0), ("fine_", 1), ("day", 2)]
+            .iter()
+            .map(|(token, id)| (token.to_string(), *id))
+            .collect();
+        let model = WordLevel::builder()
+            .vocab(vocab)
+            .unk_token("[UNK]".into())
+            .build()
+            .unwrap();
+        let mut tokenizer = Tokenizer::new(model);
+        tokenizer.with_normalizer(Some(Sequence::new(vec![
+            NFKC.into(),
+            Replace::new(" ", "_").unwrap().into(),
+        ])));
+        tokenizer.with_pre_tokenizer(Some(Whitespace {}));
+        tokenizer.add_tokens(&[AddedToken::from("[X]", false).normalized(false)]);
+        tokenizer
+    }
+
+    fn values(stage: &TraceStage) -> Vec<&str> {
+        stage.splits.iter().map(|s| s.value.as_str()).collect()
+    }
+
+    #[test]
+    fn stages() {
+        let tokenizer = tokenizer();
+        let trace = tokenizer.encode_with_trace("ﬁne [X]day", false).unwrap();
+        assert_eq!(
+            trace.encoding,
+            tokenizer.encode("ﬁne [X]day", false).unwrap()
+        );
+        assert_eq!(trace.encoding.get_tokens(), ["fine_", "[X]", "day"]);
+
+        assert_eq!(trace.sequences.len(), 1);
+        let sequence = &trace.sequences[0];
+        assert_eq!((sequence.type_id, sequence.word_idx), (0, None));
+        assert_eq!(sequence.encoding.get_ids(), [1, 3, 2]);
+
+        let stages = &sequence.stages;
+        assert_eq!(
+            stages.iter().map(|s| s.step.clone()).collect::<Vec<_>>(),
+            vec![
+                TraceStep::AddedTokens,
+                TraceStep::Normalizer("NFKC".into()),
+                TraceStep::Normalizer("Replace".into()),
+                TraceStep::NormalizedAddedTokens,
+                TraceStep::PreTokenizer,
+            ]
+        );
+        assert_eq!(values(&stages[0]), vec!["ﬁne ", "[X]", "day"]);
+        assert_eq!(values(&stages[1]), vec!["fine ", "[X]", "day"]);
+        assert_eq!(values(&stages[2]), vec!["fine_", "[X]", "day"]);
+        assert_eq!(values(&stages[4]), vec!["fine_", "[X]", "day"]);
+
+        let added = &stages[0].splits[1];
+        assert_eq!(added.offsets, (6, 9));
+        assert_eq!(added.tokens.as_ref().unwrap()[0].id, 3);
+        assert!(stages[0].splits[0].tokens.is_none());
+
+        let fine = &stages[1].splits[0];
+        assert_eq!(fine.offsets, (0, 6));
+        assert_eq!(
+            fine.alignments,
+            vec![(0, 3), (0, 3), (3, 4), (4, 5), (5, 6)]
+        );
+        let day = &stages[2].splits[2];
+        assert_eq!(day.alignments, vec![(9, 10), (10, 11), (11, 12)]);
+    }
+
+    #[test]
+    fn pre_tokenized_pair() {
+        let tokenizer = tokenizer();
+        let input = (&["fine ", "day"][..], "day");
+        let trace = tokenizer.encode_with_trace(input, true).unwrap();
+        assert_eq!(trace.encoding, tokenizer.encode(input, true).unwrap());
+        assert_eq!(
+            trace
+                .sequences
+                .iter()
+                .map(|s| (s.type_id, s.word_idx, s.encoding.get_tokens().to_vec()))
+                .collect::<Vec<_>>(),
+            vec![
+                (0, Some(0), vec!["fine_".to_string()]),
+                (0, Some(1), vec!["day".to_string()]),
+                (1, None, vec!["day".to_string()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn without_components() {
+        let tokenizer = Tokenizer::new(tokenizer().get_model().clone());
+        let trace = tokenizer.encode_with_trace("day", false).unwrap();
+        let steps = trace.sequences[0]
+            .stages
+            .iter()
+            .map(|s| s.step.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            steps,
+            vec![TraceStep::AddedTokens, TraceStep::NormalizedAddedTokens]
+        );
+    }
+}
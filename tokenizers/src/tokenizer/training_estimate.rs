@@ -0,0 +1,161 @@
+use super::{Result, Trainer};
+use ahash::AHashSet;
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+/// The estimated memory used by each unique word besides its content: its entry in the word
+/// counts, and the copy made by the trainer
+const WORD_OVERHEAD: u64 = 2 * std::mem::size_of::<(String, u64)>() as u64;
+/// The estimated memory used by each char of a unique word while training: its symbol, and
+/// its share of the pair counts and of their positions
+const CHAR_OVERHEAD: u64 = 32;
+
+/// The resources needed to train on a whole corpus, extrapolated from a sample of this
+/// corpus. See [`Trainer::estimate`]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TrainingEstimate {
+    /// The number of bytes of the sample
+    pub sample_bytes: u64,
+    /// The number of unique words in the sample
+    pub sample_unique_words: usize,
+    /// The expected number of unique words in the corpus
+    pub unique_words: u64,
+    /// The expected peak memory of the training, in bytes
+    pub peak_memory: u64,
+    /// The expected duration of the training, including the time spent feeding the corpus
+    pub training_time: Duration,
+}
+
+/// Estimate the training on a corpus of `corpus_bytes` bytes from the given sample, by
+/// training a copy of `model` with a copy of `trainer` on it.
+///
+/// The number of unique words follows Heaps' law, `V = K * n^β`, `β` being measured between
+/// the first half of the sample and the whole sample. The memory grows with the unique
+/// words, like the duration of the training itself, while the time spent feeding the corpus
+/// grows with its size.
+pub(crate) fn estimate_training<T, I, S, F>(
+    trainer: &T,
+    model: &T::Model,
+    sample: I,
+    corpus_bytes: u64,
+    process: F,
+) -> Result<TrainingEstimate>
+where
+    T: Trainer + Clone,
+    T::Model: Clone,
+    I: Iterator<Item = S>,
+    S: AsRef<str>,
+    F: Fn(&str) -> Result<Vec<String>> + Sync,
+{
+    let sample: Vec<String> = sample.map(|s| s.as_ref().to_owned()).collect();
+    let sample_bytes: u64 = sample.iter().map(|s| s.len() as u64).sum();
+    if sample_bytes == 0 {
+        return Err("Cannot estimate the training from an empty sample".into());
+    }
+
+    // The unique words of the sample, and those seen in its first half with their bytes
+    let mut words = AHashSet::new();
+    let mut half = None;
+    let mut bytes = 0;
+    for sequence in &sample {
+        words.extend(process(sequence)?);
+        bytes += sequence.len() as u64;
+        if half.is_none() && 2 * bytes >= sample_bytes {
+            half = Some((bytes, words.len()));
+        }
+    }
+    let sample_unique_words = words.len();
+    let exponent = match half {
+        Some((half_bytes, half_words)) if half_bytes < sample_bytes && half_words > 0 => {
+            let words_ratio = sample_unique_words as f64 / half_words as f64;
+            let bytes_ratio = sample_bytes as f64 / half_bytes as f64;
+            (words_ratio.ln() / bytes_ratio.ln()).clamp(0.0, 1.0)
+        }
+        // Without any point of comparison, we expect the worst
+        _ => 1.0,
+    };
+    let scale = (corpus_bytes as f64 / sample_bytes as f64).max(1.0);
+    let unique_words = (sample_unique_words as f64 * scale.powf(exponent)).round() as u64;
+
+    let (word_bytes, word_chars) = words.iter().fold((0, 0), |(bytes, chars), word| {
+        (
+            bytes + word.len() as u64,
+            chars + word.chars().count() as u64,
+        )
+    });
+    let per_word = if sample_unique_words > 0 {
+        let n = sample_unique_words as f64;
+        WORD_OVERHEAD as f64 + (word_bytes as f64 + (word_chars * CHAR_OVERHEAD) as f64) / n
+    } else {
+        0.0
+    };
+    let peak_memory = (unique_words as f64 * per_word).round() as u64;
+
+    let mut dry_run = trainer.clone();
+    let start = Instant::now();
+    dry_run.feed(sample.iter(), &process)?;
+    let feed_time = start.elapsed();
+    let start = Instant::now();
+    dry_run.train(&mut model.clone())?;
+    let train_time = start.elapsed();
+    let words_scale = if sample_unique_words > 0 {
+        unique_words as f64 / sample_unique_words as f64
+    } else {
+        1.0
+    };
+
+    Ok(TrainingEstimate {
+        sample_bytes,
+        sample_unique_words,
+        unique_words,
+        peak_memory,
+        training_time: feed_time.mul_f64(scale) + train_time.mul_f64(words_scale),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::bpe::{BpeTrainer, BPE};
+    use crate::models::TrainerWrapper;
+    use crate::pre_tokenizers::whitespace::WhitespaceSplit;
+    use crate::{Tokenizer, Trainer};
+
+    #[test]
+    fn estimate() {
+        let mut tokenizer = Tokenizer::new(BPE::default());
+        tokenizer.with_pre_tokenizer(Some(WhitespaceSplit));
+        let trainer: TrainerWrapper = BpeTrainer::builder().show_progress(false).build().into();
+
+        // 4 unique words in each half: linear growth
+        let sample = ["a b c d", "e f g h"];
+        let estimate = tokenizer
+            .estimate_training(&trainer, sample.iter(), 1500)
+            .unwrap();
+        assert_eq!(estimate.sample_bytes, 14);
+        assert_eq!(estimate.sample_unique_words, 8);
+        assert_eq!(estimate.unique_words, 857);
+        assert!(estimate.peak_memory > 857 * 32);
+
+        // The same words in each half: no growth
+        let sample = ["a b c d", "a b c d"];
+        let estimate = tokenizer
+            .estimate_training(&trainer, sample.iter(), 1500)
+            .unwrap();
+        assert_eq!(estimate.unique_words, 4);
+
+        // Nothing was trained
+        assert_eq!(tokenizer.get_vocab_size(true), 0);
+        let empty: [&str; 0] = [];
+        assert!(tokenizer
+            .estimate_training(&trainer, empty.iter(), 1)
+            .is_err());
+
+        // A trainer can estimate on its own
+        let estimate = trainer
+            .estimate(tokenizer.get_model(), sample.iter(), 14, |s| {
+                Ok(s.split(' ').map(String::from).collect())
+            })
+            .unwrap();
+        assert_eq!(estimate.unique_words, 4);
+    }
+}
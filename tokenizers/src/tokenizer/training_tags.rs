@@ -0,0 +1,242 @@
+use super::{Model, Result};
+use crate::utils::parallelism::*;
+use ahash::{AHashMap, AHashSet};
+use serde::Serialize;
+
+/// The number of training sequences pre-processed together, in parallel
+pub(crate) const TAG_CHUNK_SIZE: usize = 8192;
+
+/// What the training sequences sharing a tag, like a language or a domain, contributed to
+/// the trained model. See [`TokenizerImpl::train_tagged`](super::TokenizerImpl::train_tagged)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct TagStatistics {
+    /// The tag, `None` for the untagged sequences
+    pub tag: Option<String>,
+    /// The number of sequences
+    pub sequences: u64,
+    /// The number of words, as fed to the trainer
+    pub words: u64,
+    /// The number of unique words
+    pub unique_words: usize,
+    /// The number of tokens of these words once encoded with the trained model. Compared to
+    /// `words`, this tells how well the vocabulary fits the tag
+    pub tokens: u64,
+    /// The number of distinct tokens covering more than one char used by these words
+    pub multi_char_tokens: usize,
+    /// Among the `multi_char_tokens`, those used by no other tag, the part of the vocabulary
+    /// brought by this tag alone
+    pub exclusive_tokens: usize,
+}
+
+/// The words of the training sequences, counted by tag
+#[derive(Debug, Default)]
+pub(crate) struct TagCounter {
+    /// The tags, in order of appearance
+    tags: Vec<Option<String>>,
+    /// The number of sequences and the word counts of each tag
+    counts: Vec<(u64, AHashMap<String, u64>)>,
+    indices: AHashMap<Option<String>, usize>,
+}
+
+impl TagCounter {
+    /// Pre-process a chunk of tagged sequences in parallel with `process`, returning their
+    /// words counted by tag, along with the words themselves
+    pub(crate) fn process<G, S, F>(
+        chunk: Vec<(Option<G>, S)>,
+        process: F,
+    ) -> Result<(Self, Vec<String>)>
+    where
+        G: AsRef<str> + Send,
+        S: AsRef<str> + Send,
+        F: Fn(&str) -> Result<Vec<String>> + Sync,
+    {
+        chunk
+            .into_maybe_par_iter()
+            .map(|(tag, sequence)| {
+                let words = process(sequence.as_ref())?;
+                let mut counter = Self::default();
+                counter.add(tag.as_ref().map(AsRef::as_ref), &words);
+                Ok((counter, words))
+            })
+            .reduce(
+                || Ok(Default::default()),
+                |acc, other| {
+                    let (mut counter, mut words) = acc?;
+                    let (other_counter, other_words) = other?;
+                    counter.merge(other_counter);
+                    words.extend(other_words);
+                    Ok((counter, words))
+                },
+            )
+    }
+
+    /// Count the words of a sequence with the given tag
+    pub(crate) fn add(&mut self, tag: Option<&str>, words: &[String]) {
+        let index = self.index(tag.map(str::to_owned));
+        let (sequences, words_count) = &mut self.counts[index];
+        *sequences += 1;
+        for word in words {
+            *words_count.entry(word.clone()).or_default() += 1;
+        }
+    }
+
+    /// Add the counts of `other`, whose new tags come after ours
+    pub(crate) fn merge(&mut self, other: Self) {
+        for (tag, (other_sequences, other_count)) in other.tags.into_iter().zip(other.counts) {
+            let index = self.index(tag);
+            let (sequences, words_count) = &mut self.counts[index];
+            *sequences += other_sequences;
+            for (word, count) in other_count {
+                *words_count.entry(word).or_default() += count;
+            }
+        }
+    }
+
+    /// The index of the given tag, added if new
+    fn index(&mut self, tag: Option<String>) -> usize {
+        if let Some(index) = self.indices.get(&tag) {
+            return *index;
+        }
+        self.indices.insert(tag.clone(), self.tags.len());
+        self.tags.push(tag);
+        self.counts.push(Default::default());
+        self.tags.len() - 1
+    }
+
+    /// The statistics of each tag, in order of appearance, the words being encoded with the
+    /// trained `model`
+    pub(crate) fn statistics<M: Model>(self, model: &M) -> Result<Vec<TagStatistics>> {
+        // The number of tokens and the multi-char tokens of each word, shared by all the tags
+        let mut encoded: AHashMap<&str, (u64, Vec<u32>)> = AHashMap::new();
+        let mut statistics = Vec::with_capacity(self.tags.len());
+        let mut multi_char: Vec<AHashSet<u32>> = Vec::with_capacity(self.tags.len());
+        for (tag, (sequences, words_count)) in self.tags.into_iter().zip(&self.counts) {
+            let mut stats = TagStatistics {
+                tag,
+                sequences: *sequences,
+                unique_words: words_count.len(),
+                ..Default::default()
+            };
+            let mut tag_multi_char = AHashSet::new();
+            for (word, count) in words_count {
+                if !encoded.contains_key(word.as_str()) {
+                    let tokens = model.tokenize(word)?;
+                    let word_multi_char = tokens
+                        .iter()
+                        .filter(|token| {
+                            word.get(token.offsets.0..token.offsets.1)
+                                .is_some_and(|token| token.chars().nth(1).is_some())
+                        })
+                        .map(|token| token.id)
+                        .collect();
+                    encoded.insert(word, (tokens.len() as u64, word_multi_char));
+                }
+                let (tokens, word_multi_char) = &encoded[word.as_str()];
+                stats.words += count;
+                stats.tokens += tokens * count;
+                tag_multi_char.extend(word_multi_char);
+            }
+            stats.multi_char_tokens = tag_multi_char.len();
+            statistics.push(stats);
+            multi_char.push(tag_multi_char);
+        }
+
+        let mut users: AHashMap<u32, usize> = AHashMap::new();
+        for id in multi_char.iter().flatten() {
+            *users.entry(*id).or_default() += 1;
+        }
+        for (stats, tag_multi_char) in statistics.iter_mut().zip(&multi_char) {
+            stats.exclusive_tokens = tag_multi_char.iter().filter(|id| users[id] == 1).count();
+        }
+        Ok(statistics)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::bpe::{BpeTrainer, BPE};
+    use crate::models::TrainerWrapper;
+    use crate::pre_tokenizers::whitespace::Whitespace;
+    use crate::tokenizer::Tokenizer;
+
+    fn untrained() -> (Tokenizer, TrainerWrapper) {
+        let mut tokenizer = Tokenizer::new(BPE::default());
+        tokenizer.with_pre_tokenizer(Some(Whitespace {}));
+        let trainer = BpeTrainer::builder().show_progress(false).build().into();
+        (tokenizer, trainer)
+    }
+
+    #[test]
+    fn statistics() {
+        let sequences = vec![
+            (Some("a"), "aaaa ab"),
+            (None, "c"),
+            (Some("b"), "bbbb ab"),
+            (Some("a"), "aaaa"),
+        ];
+        let (mut tokenizer, mut trainer) = untrained();
+        let statistics = tokenizer
+            .train_tagged(&mut trainer, sequences.clone().into_iter())
+            .unwrap();
+        assert_eq!(
+            statistics,
+            [
+                TagStatistics {
+                    tag: Some("a".into()),
+                    sequences: 2,
+                    words: 3,
+                    unique_words: 2,
+                    tokens: 3,
+                    multi_char_tokens: 2,
+                    exclusive_tokens: 1,
+                },
+                TagStatistics {
+                    tag: None,
+                    sequences: 1,
+                    words: 1,
+                    unique_words: 1,
+                    tokens: 1,
+                    multi_char_tokens: 0,
+                    exclusive_tokens: 0,
+                },
+                TagStatistics {
+                    tag: Some("b".into()),
+                    sequences: 1,
+                    words: 2,
+                    unique_words: 2,
+                    tokens: 2,
+                    multi_char_tokens: 2,
+                    exclusive_tokens: 1,
+                },
+            ]
+        );
+
+        // The same model as without the tags
+        let (mut untagged, mut trainer) = untrained();
+        untagged
+            .train(&mut trainer, sequences.iter().map(|(_, sequence)| sequence))
+            .unwrap();
+        assert_eq!(tokenizer.get_vocab(true), untagged.get_vocab(true));
+        assert_eq!(
+            tokenizer.encode("aaaa ab bbbb", false).unwrap().get_ids(),
+            untagged.encode("aaaa ab bbbb", false).unwrap().get_ids()
+        );
+    }
+
+    #[test]
+    fn merge() {
+        let words = |words: &[&str]| words.iter().map(|w| w.to_string()).collect::<Vec<_>>();
+        let mut counter = TagCounter::default();
+        counter.add(Some("a"), &words(&["x", "y"]));
+        let mut other = TagCounter::default();
+        other.add(None, &words(&["x"]));
+        other.add(Some("a"), &words(&["x"]));
+        counter.merge(other);
+
+        assert_eq!(counter.tags, [Some("a".to_string()), None]);
+        assert_eq!(counter.counts[0].0, 2);
+        assert_eq!(counter.counts[0].1["x"], 2);
+        assert_eq!(counter.counts[1].1["x"], 1);
+    }
+}
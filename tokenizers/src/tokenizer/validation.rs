@@ -0,0 +1,692 @@
+use super::{Model, Tokenizer};
+use crate::models::ModelWrapper;
+use crate::processors::PostProcessorWrapper;
+
+/// An inconsistency found by [`Tokenizer::validate`]
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum ValidationIssue {
+    /// Two tokens of the model share the same id, so one of them can't be decoded
+    #[error("The tokens `{token}` and `{other}` share the id {id}")]
+    DuplicateId {
+        id: u32,
+        token: String,
+        other: String,
+    },
+    /// A token of the model has several ids, so all but one of them are never produced
+    #[error("The token `{token}` has both the ids {id} and {other_id}")]
+    DuplicateToken {
+        token: String,
+        id: u32,
+        other_id: u32,
+    },
+    /// A merge references an id that is not part of the vocabulary
+    #[error("Merge #{rank} references the id {id}, which is not in the vocabulary")]
+    UnknownMergeId { rank: u32, id: u32 },
+    /// A merge produces a token that is not the concatenation of its parts
+    #[error("Merge #{rank} of `{left}` and `{right}` produces `{merged}`")]
+    InconsistentMerge {
+        rank: u32,
+        left: String,
+        right: String,
+        merged: String,
+    },
+    /// An added token uses an id that already belongs to another token
+    #[error("The added token `{token}` has the id {id}, which already belongs to `{other}`")]
+    AddedTokenIdCollision {
+        id: u32,
+        token: String,
+        other: String,
+    },
+    /// The unknown token of the model is not part of its vocabulary
+    #[error("The unknown token `{token}` is not in the vocabulary")]
+    MissingUnkToken { token: String },
+    /// A special token used by the post-processor or the padding is not in the vocabulary
+    #[error("The special token `{token}` (id {id}) is not in the vocabulary")]
+    MissingSpecialToken { token: String, id: u32 },
+    /// A special token used by the post-processor or the padding has another id in the
+    /// vocabulary
+    #[error("The special token `{token}` is used with the id {id}, but its id is {actual}")]
+    SpecialTokenIdMismatch { token: String, id: u32, actual: u32 },
+}
+
+fn post_processor_special_tokens(
+    processor: &PostProcessorWrapper,
+    tokens: &mut Vec<(String, u32)>,
+) {
+    match processor {
+        PostProcessorWrapper::Bert(bert) => tokens.extend([bert.cls.clone(), bert.sep.clone()]),
+        PostProcessorWrapper::Roberta(roberta) => {
+            tokens.extend([roberta.cls.clone(), roberta.sep.clone()])
+        }
+        PostProcessorWrapper::Template(template) => {
+            for special in template.get_special_tokens().0.values() {
+                tokens.extend(
+                    special
+                        .tokens
+                        .iter()
+                        .cloned()
+                        .zip(special.ids.iter().copied()),
+                );
+            }
+        }
+        PostProcessorWrapper::ChatTemplate(chat) => {
+            for special in chat.get_special_tokens().0.values() {
+                tokens.extend(
+                    special
+                        .tokens
+                        .iter()
+                        .cloned()
+                        .zip(special.ids.iter().copied()),
+                );
+            }
+        }
+        PostProcessorWrapper::Sequence(sequence) => {
+            let mut i = 0;
+            while let Some(processor) = sequence.get(i) {
+                post_processor_special_tokens(processor, tokens);
+                i += 1;
+            }
+        }
+        PostProcessorWrapper::ByteLevel(_) => {}
+    }
+}
+
+impl Tokenizer {
+    /// Check the internal consistency of this tokenizer, returning all the issues found,
+    /// from the most fundamental to the least.
+    ///
+    /// This verifies that:
+    ///  - each id of the model vocabulary belongs to a single token
+    ///  - the BPE merges reference known ids, and produce the concatenation of their parts
+    ///  - the added tokens don't reuse the id of another token
+    ///  - the unknown token, and the special tokens used by the post-processor and the
+    ///    padding, are in the vocabulary with the expected ids
+    ///
+    /// Such issues don't always prevent the tokenizer from loading, but they make it encode
+    /// or decode silently wrong, and usually come from a corrupted (or hand-edited) file.
+    /// ```
+    /// use tokenizers::models::bpe::BPE;
+    /// use tokenizers::processors::bert::BertProcessing;
+    /// use tokenizers::{AddedToken, Tokenizer, ValidationIssue};
+    ///
+    /// let vocab = [("a".into(), 0), ("b".into(), 1), ("ab".into(), 2)];
+    /// let model = BPE::builder()
+    ///     .vocab_and_merges(vocab, vec![("a".into(), "b".into())])
+    ///     .build()
+    ///     .unwrap();
+    /// let mut tokenizer = Tokenizer::new(model);
+    /// assert!(tokenizer.validate().is_empty());
+    ///
+    /// // The post-processor uses ids that don't match the vocabulary
+    /// tokenizer.add_special_tokens(&[AddedToken::from("[CLS]", true)]);
+    /// tokenizer.with_post_processor(Some(BertProcessing::new(
+    ///     ("[SEP]".into(), 1),
+    ///     ("[CLS]".into(), 0),
+    /// )));
+    /// assert_eq!(
+    ///     tokenizer.validate(),
+    ///     vec![
+    ///         ValidationIssue::SpecialTokenIdMismatch {
+    ///             token: "[CLS]".into(),
+    ///             id: 0,
+    ///             actual: 3,
+    ///         },
+    ///         ValidationIssue::MissingSpecialToken {
+    ///             token: "[SEP]".into(),
+    ///             id: 1,
+    ///         },
+    ///     ]
+    /// );
+    /// ```
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        let mut issues = vec![];
+        let model = self.get_model();
+
+        let mut vocab = model.get_vocab().into_iter().collect::<Vec<_>>();
+        vocab.sort_unstable_by(|(a, a_id), (b, b_id)| a_id.cmp(b_id).then(a.cmp(b)));
+        for (token, id) in &vocab {
+            if let Some(other) = model.id_to_token(*id).filter(|other| other != token) {
+                issues.push(ValidationIssue::DuplicateId {
+                    id: *id,
+                    token: token.clone(),
+                    other,
+                });
+            }
+        }
+        // The other way around, as with duplicated Unigram pieces
+        for id in 0..model.get_vocab_size() as u32 {
+            if let Some(token) = model.id_to_token(id) {
+                if let Some(other_id) = model.token_to_id(&token).filter(|other| *other != id) {
+                    issues.push(ValidationIssue::DuplicateToken {
+                        token,
+                        id,
+                        other_id,
+                    });
+                }
+            }
+        }
+
+        if let ModelWrapper::BPE(bpe) = model {
+            let prefix_len = bpe
+                .continuing_subword_prefix
+                .as_ref()
+                .map_or(0, |prefix| prefix.len());
+            // Through the tables of a mapped model as well
+            let (merges, vocab_r) = (bpe.owned_merges(), bpe.owned_vocab_r());
+            let mut merges = merges.iter().collect::<Vec<_>>();
+            merges.sort_unstable_by_key(|(_, (rank, _))| *rank);
+            for ((left, right), (rank, merged)) in merges {
+                let tokens = [*left, *right, *merged]
+                    .iter()
+                    .map(|id| {
+                        vocab_r.get(id).ok_or(ValidationIssue::UnknownMergeId {
+                            rank: *rank,
+                            id: *id,
+                        })
+                    })
+                    .collect::<Result<Vec<_>, _>>();
+                match tokens {
+                    Err(issue) => issues.push(issue),
+                    Ok(tokens) => {
+                        let expected = format!(
+                            "{}{}",
+                            tokens[0],
+                            tokens[1].get(prefix_len..).unwrap_or_default()
+                        );
+                        if *tokens[2] != expected {
+                            issues.push(ValidationIssue::InconsistentMerge {
+                                rank: *rank,
+                                left: tokens[0].clone(),
+                                right: tokens[1].clone(),
+                                merged: tokens[2].clone(),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        let added_vocabulary = self.get_added_vocabulary();
+        let mut added = added_vocabulary.get_vocab().iter().collect::<Vec<_>>();
+        added.sort_unstable_by(|(a, a_id), (b, b_id)| a_id.cmp(b_id).then(a.cmp(b)));
+        for (token, id) in added {
+            let other = added_vocabulary
+                .get_added_tokens_decoder()
+                .get(id)
+                .map(|other| other.content.clone())
+                .filter(|other| other != token)
+                .or_else(|| model.id_to_token(*id).filter(|other| other != token));
+            if let Some(other) = other {
+                issues.push(ValidationIssue::AddedTokenIdCollision {
+                    id: *id,
+                    token: token.clone(),
+                    other,
+                });
+            }
+        }
+
+        // The unknown token of a hybrid model comes from its fallback
+        let mut unk_model = model;
+        while let ModelWrapper::Hybrid(hybrid) = unk_model {
+            unk_model = hybrid.fallback();
+        }
+        let unk_token = match unk_model {
+            ModelWrapper::BPE(bpe) => bpe.unk_token.as_ref(),
+            ModelWrapper::WordPiece(wordpiece) => Some(&wordpiece.unk_token),
+            ModelWrapper::WordLevel(wordlevel) => Some(&wordlevel.unk_token),
+            ModelWrapper::MaxMatch(maxmatch) => Some(&maxmatch.unk_token),
+            ModelWrapper::Unigram(_) | ModelWrapper::Hybrid(_) => None,
+        };
+        if let Some(token) = unk_token.filter(|token| model.token_to_id(token).is_none()) {
+            issues.push(ValidationIssue::MissingUnkToken {
+                token: token.clone(),
+            });
+        }
+
+        let mut special_tokens = vec![];
+        if let Some(processor) = self.get_post_processor() {
+            post_processor_special_tokens(processor, &mut special_tokens);
+        }
+        if let Some(padding) = self.get_padding() {
+            special_tokens.push((padding.pad_token.clone(), padding.pad_id));
+        }
+        special_tokens.sort_unstable_by(|(a, a_id), (b, b_id)| a_id.cmp(b_id).then(a.cmp(b)));
+        special_tokens.dedup();
+        for (token, id) in special_tokens {
+            match self.token_to_id(&token) {
+                None => issues.push(ValidationIssue::MissingSpecialToken { token, id }),
+                Some(actual) if actual != id => {
+                    issues.push(ValidationIssue::SpecialTokenIdMismatch { token, id, actual })
+                }
+                Some(_) => {}
+            }
+        }
+
+        issues
+    }
+}
+
+/// The integer type storing the ids fed to an embedding table. See [`EmbeddingTable`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdDtype {
+    Int8,
+    UInt8,
+    Int16,
+    UInt16,
+    Int32,
+    UInt32,
+    Int64,
+}
+
+impl IdDtype {
+    /// The largest id this type can store
+    pub fn max_id(&self) -> u64 {
+        match self {
+            Self::Int8 => i8::MAX as u64,
+            Self::UInt8 => u8::MAX as u64,
+            Self::Int16 => i16::MAX as u64,
+            Self::UInt16 => u16::MAX as u64,
+            Self::Int32 => i32::MAX as u64,
+            Self::UInt32 => u32::MAX as u64,
+            Self::Int64 => i64::MAX as u64,
+        }
+    }
+}
+
+/// The embedding table of a model, to check against a tokenizer with
+/// [`Tokenizer::check_embeddings`]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct EmbeddingTable {
+    /// The number of rows of the table, the valid ids going from 0 to `size - 1`
+    pub size: usize,
+    /// The integer type storing the ids, like `UInt16` for compact datasets
+    pub id_dtype: Option<IdDtype>,
+    /// The rows reserved for something else, that no token may use
+    pub reserved_ids: Vec<u32>,
+    /// The row of the padding, like the `padding_idx` of a PyTorch `Embedding`
+    pub padding_idx: Option<u32>,
+}
+
+/// A mismatch between a tokenizer and an embedding table, found by
+/// [`Tokenizer::check_embeddings`]
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum EmbeddingIssue {
+    /// The ids of the model go beyond the table
+    #[error(
+        "The model uses the ids up to {max_id}, beyond the {size} rows of the embeddings: \
+         resize them to at least {} rows",
+        max_id + 1
+    )]
+    ModelIdsOutOfRange { max_id: u32, size: usize },
+    /// An added token has an id beyond the table
+    #[error(
+        "The added token `{token}` has the id {id}, beyond the {size} rows of the embeddings: \
+         resize them to at least {} rows",
+        id + 1
+    )]
+    AddedTokenOutOfRange { token: String, id: u32, size: usize },
+    /// The ids don't fit in the integer type storing them
+    #[error("The id {id} of `{token}` doesn't fit in {dtype:?}, which stores the ids up to {}", dtype.max_id())]
+    IdOverflow {
+        token: String,
+        id: u32,
+        dtype: IdDtype,
+    },
+    /// A token uses a reserved row
+    #[error("The token `{token}` uses the reserved id {id}: move it, or free this row")]
+    ReservedIdUsed { token: String, id: u32 },
+    /// The padding of the tokenizer is beyond the table
+    #[error(
+        "The padding id {pad_id} is beyond the {size} rows of the embeddings: resize them, or \
+         pad with another id"
+    )]
+    PaddingIdOutOfRange { pad_id: u32, size: usize },
+    /// The padding of the tokenizer is not the padding row of the table, so the padding
+    /// tokens get trained, and the padding row never used
+    #[error(
+        "The tokenizer pads with the id {pad_id}, but the padding row of the embeddings is \
+         {padding_idx}: pad with {padding_idx}, or set the padding row to {pad_id}"
+    )]
+    PaddingIdMismatch { pad_id: u32, padding_idx: u32 },
+    /// A token other than the padding uses the padding row of the table, which never gets
+    /// trained
+    #[error(
+        "The token `{token}` uses the padding row {padding_idx} of the embeddings, which never \
+         gets trained: move it, or use another padding row"
+    )]
+    PaddingRowUsed { token: String, padding_idx: u32 },
+}
+
+impl Tokenizer {
+    /// Check that the given embedding table matches this tokenizer, returning all the issues
+    /// found, before training a model with a mismatched pair.
+    ///
+    /// This verifies that:
+    ///  - the ids of the model and of the added tokens, which come after the base vocabulary,
+    ///    all have a row in the table, and fit in the integer type storing them
+    ///  - no token uses the reserved rows
+    ///  - the padding id has a row, which is the padding row of the table if it has one
+    ///
+    /// A table with more rows than ids is fine, as they are often rounded up.
+    /// ```
+    /// use tokenizers::models::wordlevel::WordLevel;
+    /// use tokenizers::{AddedToken, EmbeddingIssue, EmbeddingTable, Tokenizer};
+    ///
+    /// let vocab = vec![("a".to_string(), 0), ("b".to_string(), 1)];
+    /// let model = WordLevel::builder()
+    ///     .vocab(vocab.into_iter().collect())
+    ///     .unk_token("a".into())
+    ///     .build()
+    ///     .unwrap();
+    /// let mut tokenizer = Tokenizer::new(model);
+    /// let table = EmbeddingTable {
+    ///     size: 2,
+    ///     ..Default::default()
+    /// };
+    /// assert!(tokenizer.check_embeddings(&table).is_empty());
+    ///
+    /// tokenizer.add_special_tokens(&[AddedToken::from("[PAD]", true)]);
+    /// assert_eq!(
+    ///     tokenizer.check_embeddings(&table),
+    ///     [EmbeddingIssue::AddedTokenOutOfRange {
+    ///         token: "[PAD]".into(),
+    ///         id: 2,
+    ///         size: 2
+    ///     }]
+    /// );
+    /// ```
+    pub fn check_embeddings(&self, table: &EmbeddingTable) -> Vec<EmbeddingIssue> {
+        let mut issues = vec![];
+        let size = table.size;
+        let in_range = |id: u32| (id as usize) < size;
+
+        let mut vocab = self.get_model().get_vocab().into_iter().collect::<Vec<_>>();
+        vocab.sort_unstable_by(|(a, a_id), (b, b_id)| a_id.cmp(b_id).then(a.cmp(b)));
+        let added_vocabulary = self.get_added_vocabulary();
+        let mut added = added_vocabulary
+            .get_vocab()
+            .iter()
+            .filter(|(token, id)| self.get_model().id_to_token(**id).as_ref() != Some(*token))
+            .map(|(token, id)| (token.clone(), *id))
+            .collect::<Vec<_>>();
+        added.sort_unstable_by(|(a, a_id), (b, b_id)| a_id.cmp(b_id).then(a.cmp(b)));
+
+        if let Some(max_id) = vocab.last().map(|(_, id)| *id).filter(|id| !in_range(*id)) {
+            issues.push(EmbeddingIssue::ModelIdsOutOfRange { max_id, size });
+        }
+        for (token, id) in added.iter().filter(|(_, id)| !in_range(*id)) {
+            issues.push(EmbeddingIssue::AddedTokenOutOfRange {
+                token: token.clone(),
+                id: *id,
+                size,
+            });
+        }
+        if let Some(dtype) = table.id_dtype {
+            let max = vocab
+                .iter()
+                .chain(&added)
+                .max_by_key(|(_, id)| *id)
+                .filter(|(_, id)| *id as u64 > dtype.max_id());
+            if let Some((token, id)) = max {
+                issues.push(EmbeddingIssue::IdOverflow {
+                    token: token.clone(),
+                    id: *id,
+                    dtype,
+                });
+            }
+        }
+
+        let mut reserved = table.reserved_ids.clone();
+        reserved.sort_unstable();
+        reserved.dedup();
+        for id in reserved {
+            if let Some(token) = self.id_to_token(id) {
+                issues.push(EmbeddingIssue::ReservedIdUsed { token, id });
+            }
+        }
+
+        if let Some(padding) = self.get_padding() {
+            let pad_id = padding.pad_id;
+            if !in_range(pad_id) {
+                issues.push(EmbeddingIssue::PaddingIdOutOfRange { pad_id, size });
+            }
+            match table.padding_idx {
+                Some(padding_idx) if padding_idx != pad_id => {
+                    issues.push(EmbeddingIssue::PaddingIdMismatch {
+                        pad_id,
+                        padding_idx,
+                    })
+                }
+                _ => {}
+            }
+        }
+        if let Some(padding_idx) = table.padding_idx {
+            let pad_token = self.get_padding().map(|padding| &padding.pad_token);
+            if let Some(token) = self
+                .id_to_token(padding_idx)
+                .filter(|token| Some(token) != pad_token)
+            {
+                issues.push(EmbeddingIssue::PaddingRowUsed { token, padding_idx });
+            }
+        }
+
+        issues
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::bpe::{MappedBpe, BPE};
+    use crate::models::unigram::Unigram;
+    use crate::processors::template::TemplateProcessing;
+    use crate::utils::mmap::MappedFile;
+    use crate::{AddedToken, PaddingParams};
+    use ahash::AHashMap;
+    use std::sync::Arc;
+
+    fn bpe(vocab: &[(&str, u32)]) -> BPE {
+        let vocab = vocab
+            .iter()
+            .map(|(token, id)| (token.to_string(), *id))
+            .collect::<AHashMap<_, _>>();
+        BPE::builder()
+            .vocab_and_merges(vocab, vec![("a".into(), "b".into())])
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn model_vocab() {
+        let tokenizer = Tokenizer::new(bpe(&[("a", 0), ("b", 1), ("ab", 2)]));
+        assert_eq!(tokenizer.validate(), vec![]);
+
+        let model = bpe(&[("a", 0), ("b", 1), ("ab", 2), ("c", 3), ("d", 3)]);
+        let kept = model.id_to_token(3).unwrap();
+        let dropped = if kept == "c" { "d" } else { "c" };
+        assert_eq!(
+            Tokenizer::new(model).validate(),
+            vec![ValidationIssue::DuplicateId {
+                id: 3,
+                token: dropped.into(),
+                other: kept,
+            }]
+        );
+
+        let pieces = [("<unk>", 0.0), ("a", -1.0), ("a", -2.0)]
+            .iter()
+            .map(|(piece, score)| (piece.to_string(), *score))
+            .collect();
+        let unigram = Unigram::from(pieces, Some(0), false).unwrap();
+        let issues = Tokenizer::new(unigram).validate();
+        assert_eq!(issues.len(), 1);
+        assert!(matches!(
+            issues[0],
+            ValidationIssue::DuplicateToken { ref token, .. } if token == "a"
+        ));
+    }
+
+    #[test]
+    fn merges() {
+        let mut model = bpe(&[("a", 0), ("b", 1), ("ab", 2), ("ba", 3)]);
+        model.merges.insert((1, 0), (1, 2));
+        model.merges.insert((1, 1), (2, 7));
+        model.unk_token = Some("<unk>".into());
+        assert_eq!(
+            Tokenizer::new(model).validate(),
+            vec![
+                ValidationIssue::InconsistentMerge {
+                    rank: 1,
+                    left: "b".into(),
+                    right: "a".into(),
+                    merged: "ab".into(),
+                },
+                ValidationIssue::UnknownMergeId { rank: 2, id: 7 },
+                ValidationIssue::MissingUnkToken {
+                    token: "<unk>".into()
+                },
+            ]
+        );
+
+        // The same through the tables of a mapped model
+        let mut model = bpe(&[("a", 0), ("b", 1), ("ab", 2), ("ba", 3)]);
+        model.merges.insert((1, 0), (1, 2));
+        let mut tables = vec![];
+        MappedBpe::write(&model.owned_vocab_r(), &model.owned_merges(), &mut tables).unwrap();
+        model.set_mapped(MappedBpe::parse(Arc::new(MappedFile::from(tables)), 0).unwrap());
+        assert!(model.merges.is_empty());
+        assert_eq!(
+            Tokenizer::new(model).validate(),
+            vec![ValidationIssue::InconsistentMerge {
+                rank: 1,
+                left: "b".into(),
+                right: "a".into(),
+                merged: "ab".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn added_tokens() {
+        let mut tokenizer = Tokenizer::new(bpe(&[("a", 0), ("b", 1), ("ab", 2)]));
+        tokenizer.add_tokens(&[AddedToken::from("ab", false), AddedToken::from("c", false)]);
+        assert_eq!(tokenizer.validate(), vec![]);
+
+        // Replacing the model after adding tokens makes their ids overlap
+        tokenizer.with_model(bpe(&[("a", 0), ("b", 1), ("ab", 2), ("d", 3)]));
+        assert_eq!(
+            tokenizer.validate(),
+            vec![ValidationIssue::AddedTokenIdCollision {
+                id: 3,
+                token: "c".into(),
+                other: "d".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn special_tokens() {
+        let mut tokenizer = Tokenizer::new(bpe(&[("a", 0), ("b", 1), ("ab", 2)]));
+        tokenizer.add_special_tokens(&[AddedToken::from("[CLS]", true)]);
+        let template = TemplateProcessing::builder()
+            .try_single("[CLS] $0 [SEP]")
+            .unwrap()
+            .special_tokens(vec![("[CLS]", 3), ("[SEP]", 4)])
+            .build()
+            .unwrap();
+        tokenizer.with_post_processor(Some(template));
+        tokenizer.with_padding(Some(PaddingParams {
+            pad_id: 1,
+            pad_token: "[CLS]".into(),
+            ..Default::default()
+        }));
+        assert_eq!(
+            tokenizer.validate(),
+            vec![
+                ValidationIssue::SpecialTokenIdMismatch {
+                    token: "[CLS]".into(),
+                    id: 1,
+                    actual: 3,
+                },
+                ValidationIssue::MissingSpecialToken {
+                    token: "[SEP]".into(),
+                    id: 4,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn embeddings() {
+        let mut tokenizer = Tokenizer::new(bpe(&[("a", 0), ("b", 1), ("ab", 2)]));
+        tokenizer.add_special_tokens(&[AddedToken::from("[PAD]", true)]);
+        tokenizer.with_padding(Some(PaddingParams {
+            pad_id: 3,
+            pad_token: "[PAD]".into(),
+            ..Default::default()
+        }));
+        let table = EmbeddingTable {
+            size: 3,
+            reserved_ids: vec![7, 1],
+            padding_idx: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(
+            tokenizer.check_embeddings(&table),
+            vec![
+                EmbeddingIssue::AddedTokenOutOfRange {
+                    token: "[PAD]".into(),
+                    id: 3,
+                    size: 3,
+                },
+                EmbeddingIssue::ReservedIdUsed {
+                    token: "b".into(),
+                    id: 1,
+                },
+                EmbeddingIssue::PaddingIdOutOfRange { pad_id: 3, size: 3 },
+                EmbeddingIssue::PaddingIdMismatch {
+                    pad_id: 3,
+                    padding_idx: 0,
+                },
+                EmbeddingIssue::PaddingRowUsed {
+                    token: "a".into(),
+                    padding_idx: 0,
+                },
+            ]
+        );
+        assert_eq!(
+            tokenizer.check_embeddings(&table)[0].to_string(),
+            "The added token `[PAD]` has the id 3, beyond the 3 rows of the embeddings: resize \
+             them to at least 4 rows"
+        );
+
+        let table = EmbeddingTable {
+            size: 8,
+            id_dtype: Some(IdDtype::UInt8),
+            reserved_ids: vec![7],
+            padding_idx: Some(3),
+        };
+        assert_eq!(tokenizer.check_embeddings(&table), vec![]);
+
+        let tokenizer = Tokenizer::new(bpe(&[("a", 0), ("b", 1), ("ab", 2), ("c", 200)]));
+        let table = EmbeddingTable {
+            size: 100,
+            id_dtype: Some(IdDtype::Int8),
+            ..Default::default()
+        };
+        assert_eq!(
+            tokenizer.check_embeddings(&table),
+            vec![
+                EmbeddingIssue::ModelIdsOutOfRange {
+                    max_id: 200,
+                    size: 100,
+                },
+                EmbeddingIssue::IdOverflow {
+                    token: "c".into(),
+                    id: 200,
+                    dtype: IdDtype::Int8,
+                },
+            ]
+        );
+    }
+}
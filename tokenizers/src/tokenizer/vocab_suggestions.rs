@@ -0,0 +1,185 @@
+use super::{
+    AddedToken, Decoder, InputSequence, Model, Normalizer, OffsetType, PostProcessor, PreTokenizer,
+    Result, TokenizerImpl,
+};
+use crate::utils::parallelism::*;
+use ahash::AHashMap;
+use serde::Serialize;
+
+/// A word that the tokenizer splits in several tokens, worth adding to its vocabulary.
+/// See [`TokenizerImpl::suggest_added_tokens`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TokenSuggestion {
+    /// The word, as it appears in the corpus
+    pub content: String,
+    /// The number of occurrences of the word in the corpus
+    pub count: usize,
+    /// The total number of tokens of these occurrences, with the current vocabulary
+    pub tokens: usize,
+    /// The number of tokens that adding the word would save on the corpus, `tokens - count`
+    pub saved: usize,
+}
+
+impl TokenSuggestion {
+    /// The [`AddedToken`] matching this word, only as a whole word of the original text
+    pub fn to_added_token(&self) -> AddedToken {
+        AddedToken::from(self.content.clone(), false)
+            .single_word(true)
+            .normalized(false)
+    }
+}
+
+impl<M, N, PT, PP, D> TokenizerImpl<M, N, PT, PP, D>
+where
+    M: Model + Send + Sync,
+    N: Normalizer + Send + Sync,
+    PT: PreTokenizer + Send + Sync,
+    PP: PostProcessor + Send + Sync,
+    D: Decoder + Send + Sync,
+{
+    /// Scan a corpus, like a new domain to adapt the tokenizer to, and suggest the `n` words
+    /// worth adding to the vocabulary the most, as [`AddedToken`]s with
+    /// [`TokenSuggestion::to_added_token`].
+    ///
+    /// The words are the ones produced by the pre-tokenizer and split by the model in
+    /// several tokens. They are ranked by the number of tokens they would save on the
+    /// corpus, their frequency times the number of tokens they'd save on each occurrence.
+    /// The sequences are encoded without truncation nor special tokens, and the words
+    /// containing added tokens are ignored.
+    /// ```
+    /// use tokenizers::models::bpe::BPE;
+    /// use tokenizers::pre_tokenizers::whitespace::Whitespace;
+    /// use tokenizers::Tokenizer;
+    ///
+    /// let vocab = [("a".into(), 0), ("b".into(), 1), ("c".into(), 2), ("ab".into(), 3)];
+    /// let model = BPE::builder()
+    ///     .vocab_and_merges(vocab, vec![("a".into(), "b".into())])
+    ///     .build()
+    ///     .unwrap();
+    /// let mut tokenizer = Tokenizer::new(model);
+    /// tokenizer.with_pre_tokenizer(Some(Whitespace {}));
+    ///
+    /// let suggestions = tokenizer
+    ///     .suggest_added_tokens(&["abc ab cc", "cc abc cc"], 10)
+    ///     .unwrap();
+    /// let ranked: Vec<_> = suggestions.iter().map(|s| (s.content.as_str(), s.saved)).collect();
+    /// assert_eq!(ranked, [("cc", 3), ("abc", 2)]);
+    ///
+    /// let added: Vec<_> = suggestions.iter().map(|s| s.to_added_token()).collect();
+    /// tokenizer.add_tokens(&added);
+    /// assert_eq!(tokenizer.encode("abc cc", false).unwrap().len(), 2);
+    /// ```
+    pub fn suggest_added_tokens<S>(&self, sequences: &[S], n: usize) -> Result<Vec<TokenSuggestion>>
+    where
+        S: AsRef<str> + Sync,
+    {
+        let counts = self.parallelism.install(|parallel| {
+            sequences
+                .into_maybe_par_iter_cond(parallel)
+                .map(|sequence| self.count_split_words(sequence.as_ref()))
+                .collect::<Result<Vec<_>>>()
+        })?;
+
+        let mut words = AHashMap::<String, (usize, usize)>::new();
+        for (word, (count, tokens)) in counts.into_iter().flatten() {
+            let entry = words.entry(word).or_default();
+            entry.0 += count;
+            entry.1 += tokens;
+        }
+        let mut suggestions: Vec<_> = words
+            .into_iter()
+            .map(|(content, (count, tokens))| TokenSuggestion {
+                content,
+                count,
+                tokens,
+                saved: tokens - count,
+            })
+            .collect();
+        suggestions.sort_unstable_by(|a, b| {
+            b.saved
+                .cmp(&a.saved)
+                .then_with(|| a.content.cmp(&b.content))
+        });
+        suggestions.truncate(n);
+        Ok(suggestions)
+    }
+
+    /// Count the occurrences and tokens of the words of the sequence split in several tokens
+    fn count_split_words(&self, sequence: &str) -> Result<AHashMap<String, (usize, usize)>> {
+        let encoding =
+            self.encode_single_sequence(InputSequence::from(sequence), 0, OffsetType::Byte)?;
+        let mut words = AHashMap::new();
+        let mut add = |range: std::ops::Range<usize>| {
+            let ids = &encoding.get_ids()[range.clone()];
+            if ids.len() < 2
+                || ids
+                    .iter()
+                    .any(|id| self.added_vocabulary.simple_id_to_token(*id).is_some())
+            {
+                return;
+            }
+            let offsets = &encoding.get_offsets()[range];
+            let content = sequence[offsets[0].0..offsets[offsets.len() - 1].1].trim();
+            if !content.is_empty() {
+                let entry = words.entry(content.to_owned()).or_insert((0, 0));
+                entry.0 += 1;
+                entry.1 += ids.len();
+            }
+        };
+
+        let word_ids = encoding.get_word_ids();
+        let mut start = 0;
+        for end in 1..=word_ids.len() {
+            if end == word_ids.len() || word_ids[end] != word_ids[start] {
+                add(start..end);
+                start = end;
+            }
+        }
+        Ok(words)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::bpe::BPE;
+    use crate::normalizers::Lowercase;
+    use crate::pre_tokenizers::whitespace::Whitespace;
+    use crate::tokenizer::{AddedToken, Tokenizer};
+
+    #[test]
+    fn suggestions() {
+        let vocab = "helowrdi".chars().enumerate();
+        let vocab: ahash::AHashMap<_, _> = vocab.map(|(i, c)| (c.to_string(), i as u32)).collect();
+        let bpe = BPE::builder()
+            .vocab_and_merges(vocab, vec![])
+            .build()
+            .unwrap();
+        let mut tokenizer = Tokenizer::new(bpe);
+        tokenizer.with_normalizer(Some(Lowercase));
+        tokenizer.with_pre_tokenizer(Some(Whitespace {}));
+        tokenizer.add_tokens(&[AddedToken::from("z", false)]);
+        let corpus = ["Hello world hello", "World wide hello", "z zz zz"];
+
+        // The words are taken from the original text, before normalization
+        let suggestions = tokenizer.suggest_added_tokens(&corpus, 3).unwrap();
+        let ranked: Vec<_> = suggestions
+            .iter()
+            .map(|s| (s.content.as_str(), s.count, s.tokens, s.saved))
+            .collect();
+        assert_eq!(
+            ranked,
+            [("hello", 2, 10, 8), ("Hello", 1, 5, 4), ("World", 1, 5, 4)]
+        );
+        // The words made of added tokens are ignored
+        let suggestions = tokenizer.suggest_added_tokens(&corpus, 10).unwrap();
+        assert_eq!(suggestions.len(), 5);
+        assert_eq!(suggestions[4].content, "wide");
+        assert!(tokenizer
+            .suggest_added_tokens(&corpus, 0)
+            .unwrap()
+            .is_empty());
+
+        let added = suggestions[0].to_added_token();
+        assert!(added.single_word && !added.normalized && !added.special);
+    }
+}
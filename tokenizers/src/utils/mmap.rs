@@ -0,0 +1,161 @@
+//! Read-only memory mapping of files, letting the large tables of a tokenizer be read in
+//! place instead of being parsed into owned structures.
+
+use std::convert::{TryFrom, TryInto};
+use std::fs::File;
+use std::ops::Deref;
+use std::path::Path;
+
+/// The read-only content of a file. On unix, it is memory mapped: the pages are loaded
+/// lazily on access, and shared through the page cache by every process mapping the same
+/// file, including the workers forked after loading. Elsewhere, the file is simply read.
+/// It can also be built from bytes already in memory, to read them the same way.
+///
+/// See [`open`](Self::open) for the contract of the mapped files.
+pub struct MappedFile {
+    #[cfg(unix)]
+    map: Option<(std::ptr::NonNull<libc::c_void>, usize)>,
+    content: Vec<u8>,
+}
+
+// The mapping is read-only, so it can be shared between threads like a `&[u8]`
+unsafe impl Send for MappedFile {}
+unsafe impl Sync for MappedFile {}
+
+impl MappedFile {
+    /// Map the file at `path`.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified, nor truncated, for as long as the `MappedFile` lives.
+    /// The content is shared with the file: a change would be seen through the `&[u8]` of the
+    /// readers, which is undefined behavior, and reading past a truncation kills the process
+    /// with a `SIGBUS`.
+    #[cfg(unix)]
+    pub unsafe fn open<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        use std::os::unix::io::AsRawFd;
+
+        let file = File::open(path)?;
+        let len = usize::try_from(file.metadata()?.len()).map_err(std::io::Error::other)?;
+        if len == 0 {
+            // An empty mapping is invalid
+            return Ok(Self::from(vec![]));
+        }
+        // SAFETY: We map a file we just opened, read-only, and check the result. The mapping
+        // stays valid after the file is closed, until `munmap` in `Drop`, as long as the file
+        // stays untouched, which the caller guarantees.
+        let ptr = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                len,
+                libc::PROT_READ,
+                libc::MAP_SHARED,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(Self {
+            map: std::ptr::NonNull::new(ptr).map(|ptr| (ptr, len)),
+            content: vec![],
+        })
+    }
+
+    /// Read the file at `path`.
+    ///
+    /// # Safety
+    ///
+    /// The file is simply read on this platform, but the caller must uphold the contract of
+    /// the memory mapped files, for the same code to be sound everywhere.
+    #[cfg(not(unix))]
+    pub unsafe fn open<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        use std::io::Read;
+
+        let mut content = vec![];
+        File::open(path)?.read_to_end(&mut content)?;
+        Ok(Self::from(content))
+    }
+}
+
+impl From<Vec<u8>> for MappedFile {
+    fn from(content: Vec<u8>) -> Self {
+        Self {
+            #[cfg(unix)]
+            map: None,
+            content,
+        }
+    }
+}
+
+impl Deref for MappedFile {
+    type Target = [u8];
+
+    #[cfg(unix)]
+    fn deref(&self) -> &[u8] {
+        match self.map {
+            // SAFETY: The mapping is `len` bytes long, and lives as long as `self`
+            Some((ptr, len)) => unsafe {
+                std::slice::from_raw_parts(ptr.as_ptr() as *const u8, len)
+            },
+            None => &self.content,
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn deref(&self) -> &[u8] {
+        &self.content
+    }
+}
+
+#[cfg(unix)]
+impl Drop for MappedFile {
+    fn drop(&mut self) {
+        if let Some((ptr, len)) = self.map {
+            // SAFETY: This is the mapping created in `open`, no longer borrowed
+            unsafe {
+                libc::munmap(ptr.as_ptr(), len);
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for MappedFile {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.debug_struct("MappedFile")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+/// Read the little-endian `u32` starting at `offset`, if `bytes` is long enough
+pub(crate) fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    let end = offset.checked_add(4)?;
+    Some(u32::from_le_bytes(bytes.get(offset..end)?.try_into().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn open() {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(b"\x01\x00\x00\x00hello").unwrap();
+        // SAFETY: The temporary files are not modified while mapped in these tests
+        let mapped = unsafe { MappedFile::open(file.path()) }.unwrap();
+        assert_eq!(&mapped[..], b"\x01\x00\x00\x00hello");
+        assert_eq!(read_u32(&mapped, 0), Some(1));
+        assert_eq!(read_u32(&mapped, 6), None);
+
+        let empty = NamedTempFile::new().unwrap();
+        assert!(unsafe { MappedFile::open(empty.path()) }
+            .unwrap()
+            .is_empty());
+        assert_eq!(&MappedFile::from(b"hello".to_vec())[..], b"hello");
+        assert!(unsafe { MappedFile::open("/a/file/that/does/not/exist") }.is_err());
+    }
+}
@@ -1,6 +1,7 @@
 pub(crate) mod cache;
 #[cfg(feature = "http")]
 pub(crate) mod from_pretrained;
+pub(crate) mod mmap;
 
 #[cfg(all(feature = "fancy-regex", not(feature = "onig")))]
 mod fancy;
@@ -15,10 +16,13 @@ pub use crate::utils::onig::SysRegex;
 compile_error!("One of the `onig`, or `fancy-regex` features must be enabled");
 
 pub mod iter;
+pub mod offsets;
 pub mod padding;
 pub mod parallelism;
-pub(crate) mod progress;
+pub mod progress;
+pub mod script_weights;
 pub mod truncation;
+pub mod versioning;
 
 use ahash::AHashMap;
 use serde::{Serialize, Serializer};
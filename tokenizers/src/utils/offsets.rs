@@ -0,0 +1,265 @@
+//! # Offsets
+//!
+//! Helpers to convert offsets between bytes and chars.
+//!
+//! Rust strings are indexed by bytes, and that's what the offsets returned by
+//! [`TokenizerImpl::encode`](crate::TokenizerImpl::encode) refer to. Other languages (Python,
+//! JavaScript, ...) index strings by chars, which is why
+//! [`TokenizerImpl::encode_char_offsets`](crate::TokenizerImpl::encode_char_offsets) exists.
+//! When some offsets need to be sliced from a string in the other referential, these helpers
+//! do the conversion and refuse any offset that does not fall on a char boundary.
+//!
+//! ```
+//! use tokenizers::utils::offsets::{bytes_to_char, char_to_bytes};
+//!
+//! let text = "Hello 👋 there";
+//! assert_eq!(char_to_bytes(text, 6..7), Some(6..10));
+//! assert_eq!(bytes_to_char(text, 11..16), Some(8..13));
+//! // Not a char boundary
+//! assert_eq!(bytes_to_char(text, 7..10), None);
+//! ```
+//!
+//! Each of these functions walks the string, so when converting many offsets of the same
+//! string (all the offsets of an [`Encoding`](crate::Encoding) for example), an
+//! [`OffsetConverter`] should be preferred.
+use crate::Offsets;
+use std::ops::{Bound, Range, RangeBounds};
+
+/// Returns a range of the given string slice, by indexing chars instead of bytes
+pub fn get_range_of<T: RangeBounds<usize>>(s: &str, range: T) -> Option<&str> {
+    let len = s.chars().count();
+    let start = match range.start_bound() {
+        Bound::Unbounded => 0,
+        Bound::Included(i) => *i,
+        Bound::Excluded(i) => *i + 1,
+    };
+    let end = match range.end_bound() {
+        Bound::Unbounded => len,
+        Bound::Included(i) => *i + 1,
+        Bound::Excluded(i) => *i,
+    };
+
+    if start == 0 && end == 0 {
+        Some(&s[0..0])
+    } else if start >= len || end > len || start >= end {
+        None
+    } else {
+        let start_b = s.char_indices().map(|(i, _)| i).nth(start).unwrap_or(0);
+        let end_b = s.char_indices().map(|(i, _)| i).nth(end).unwrap_or(s.len());
+        Some(&s[start_b..end_b])
+    }
+}
+
+/// Convert the given range from bytes to char
+///
+/// Returns `None` if the range does not start and end on char boundaries.
+pub fn bytes_to_char(s: &str, range: Range<usize>) -> Option<Range<usize>> {
+    let (mut start, mut end) = if range == (0..0) {
+        (Some(0), Some(0))
+    } else {
+        (None, None)
+    };
+
+    s.char_indices()
+        .enumerate()
+        .take_while(|(_, (b, _))| *b <= range.end)
+        .filter(|(_, (b, _))| *b >= range.start)
+        .for_each(|(i, (b, c))| {
+            if b == range.start {
+                start = Some(i);
+            }
+            if b == range.end {
+                end = Some(i);
+            }
+            if b + c.len_utf8() == range.end {
+                end = Some(i + 1);
+            }
+        });
+
+    Some(start?..end?)
+}
+
+/// Convert the given range from char to bytes
+///
+/// Returns `None` if the range starts past the end of the string. A range that ends past
+/// the end of the string gets clamped.
+pub fn char_to_bytes(s: &str, range: Range<usize>) -> Option<Range<usize>> {
+    let (mut start, mut end) = if range == (0..0) {
+        (Some(0), Some(0))
+    } else {
+        (None, None)
+    };
+
+    if range.start == range.end {
+        s.char_indices()
+            .skip(range.start)
+            .take(1)
+            .for_each(|(b, _)| {
+                start = Some(b);
+                end = Some(b);
+            });
+    } else {
+        s.char_indices()
+            .skip(range.start)
+            .take(range.end - range.start)
+            .for_each(|(b, c)| {
+                if start.is_none() {
+                    start = Some(b);
+                }
+                end = Some(b + c.len_utf8());
+            });
+    }
+
+    Some(start?..end?)
+}
+
+/// Converts offsets between bytes and chars for a given string, in constant time.
+///
+/// The char boundaries are computed once, when building the converter, which then doesn't
+/// need to keep the string around. ASCII strings, for which both referentials are the same,
+/// don't allocate anything.
+///
+/// Contrarily to [`bytes_to_char`] and [`char_to_bytes`], an empty range at the very end of
+/// the string is considered valid.
+///
+/// ```
+/// use tokenizers::utils::offsets::OffsetConverter;
+///
+/// let converter = OffsetConverter::new("Hello 👋 there");
+/// assert_eq!(converter.char_to_byte(7), Some(10));
+/// assert_eq!(converter.byte_to_char(8), None);
+/// assert_eq!(converter.bytes_to_char((11, 16)), Some((8, 13)));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OffsetConverter {
+    /// The byte position of each char, followed by the length of the string.
+    /// `None` when the string is ASCII.
+    char_starts: Option<Vec<usize>>,
+    len: usize,
+}
+
+impl OffsetConverter {
+    pub fn new(s: &str) -> Self {
+        let char_starts = (!s.is_ascii()).then(|| {
+            s.char_indices()
+                .map(|(b, _)| b)
+                .chain(std::iter::once(s.len()))
+                .collect()
+        });
+        Self {
+            char_starts,
+            len: s.len(),
+        }
+    }
+
+    /// The length of the string, in bytes
+    pub fn len_bytes(&self) -> usize {
+        self.len
+    }
+
+    /// The length of the string, in chars
+    pub fn len_chars(&self) -> usize {
+        self.char_starts
+            .as_ref()
+            .map_or(self.len, |starts| starts.len() - 1)
+    }
+
+    /// Convert the given byte index to a char index.
+    ///
+    /// Returns `None` if the index is not on a char boundary.
+    pub fn byte_to_char(&self, byte: usize) -> Option<usize> {
+        match &self.char_starts {
+            None => (byte <= self.len).then_some(byte),
+            Some(starts) => starts.binary_search(&byte).ok(),
+        }
+    }
+
+    /// Convert the given char index to a byte index.
+    ///
+    /// Returns `None` if the index is past the end of the string.
+    pub fn char_to_byte(&self, char: usize) -> Option<usize> {
+        match &self.char_starts {
+            None => (char <= self.len).then_some(char),
+            Some(starts) => starts.get(char).copied(),
+        }
+    }
+
+    /// Convert the given offsets from bytes to chars
+    pub fn bytes_to_char(&self, offsets: Offsets) -> Option<Offsets> {
+        if offsets.0 > offsets.1 {
+            return None;
+        }
+        Some((self.byte_to_char(offsets.0)?, self.byte_to_char(offsets.1)?))
+    }
+
+    /// Convert the given offsets from chars to bytes
+    pub fn char_to_bytes(&self, offsets: Offsets) -> Option<Offsets> {
+        if offsets.0 > offsets.1 {
+            return None;
+        }
+        Some((self.char_to_byte(offsets.0)?, self.char_to_byte(offsets.1)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_range() {
+        let s = String::from("Hello my name is John 👋");
+        assert_eq!(get_range_of(&s, ..), Some(&s[..]));
+        assert_eq!(get_range_of(&s, 17..), Some("John 👋"));
+    }
+
+    #[test]
+    fn converter_matches_functions() {
+        let s = "Ça va? 👋 悪いね";
+        let converter = OffsetConverter::new(s);
+        assert_eq!(converter.len_bytes(), s.len());
+        assert_eq!(converter.len_chars(), s.chars().count());
+
+        let boundaries = s
+            .char_indices()
+            .map(|(b, _)| b)
+            .chain(std::iter::once(s.len()))
+            .collect::<Vec<_>>();
+        for start in 0..boundaries.len() - 1 {
+            for end in start + 1..boundaries.len() {
+                let bytes = boundaries[start]..boundaries[end];
+                assert_eq!(bytes_to_char(s, bytes.clone()), Some(start..end));
+                assert_eq!(char_to_bytes(s, start..end), Some(bytes.clone()));
+                assert_eq!(
+                    converter.bytes_to_char((bytes.start, bytes.end)),
+                    Some((start, end))
+                );
+                assert_eq!(
+                    converter.char_to_bytes((start, end)),
+                    Some((bytes.start, bytes.end))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn converter_invalid() {
+        let converter = OffsetConverter::new("a👋b");
+        assert_eq!(converter.byte_to_char(2), None);
+        assert_eq!(converter.byte_to_char(7), None);
+        assert_eq!(converter.char_to_byte(4), None);
+        assert_eq!(converter.bytes_to_char((5, 1)), None);
+        assert_eq!(converter.char_to_bytes((1, 4)), None);
+        // Empty ranges are fine, including at the end
+        assert_eq!(converter.bytes_to_char((6, 6)), Some((3, 3)));
+        assert_eq!(converter.char_to_bytes((3, 3)), Some((6, 6)));
+    }
+
+    #[test]
+    fn converter_ascii() {
+        let converter = OffsetConverter::new("hello");
+        assert!(converter.char_starts.is_none());
+        assert_eq!(converter.len_chars(), 5);
+        assert_eq!(converter.bytes_to_char((1, 5)), Some((1, 5)));
+        assert_eq!(converter.char_to_bytes((1, 6)), None);
+    }
+}
@@ -2,10 +2,13 @@ use crate::tokenizer::pattern::Pattern;
 use crate::{Offsets, Result};
 use onig::Regex;
 use std::error::Error;
+use std::sync::Arc;
 
-#[derive(Debug)]
+/// A compiled regex. Its clones share the compiled regex, which is only compiled once.
+#[derive(Debug, Clone)]
 pub struct SysRegex {
-    regex: Regex,
+    regex: Arc<Regex>,
+    pattern: String,
 }
 
 impl SysRegex {
@@ -13,13 +16,40 @@ impl SysRegex {
         self.regex.find_iter(inside)
     }
 
+    /// The number of capture groups of the pattern, not counting the whole match
+    pub fn captures_len(&self) -> usize {
+        self.regex.captures_len()
+    }
+
+    /// Iterate over the matches, with the offsets of each capture group, the whole match
+    /// first. Groups that didn't participate in the match are `None`.
+    pub fn captures_iter<'a>(
+        &'a self,
+        inside: &'a str,
+    ) -> impl Iterator<Item = Vec<Option<Offsets>>> + 'a {
+        self.regex
+            .captures_iter(inside)
+            .map(|captures| (0..captures.len()).map(|i| captures.pos(i)).collect())
+    }
+
     pub fn new(
         regex_str: &str,
     ) -> std::result::Result<Self, Box<dyn Error + Send + Sync + 'static>> {
         Ok(Self {
-            regex: Regex::new(regex_str)?,
+            regex: Arc::new(Regex::new(regex_str)?),
+            pattern: regex_str.to_owned(),
         })
     }
+
+    /// The pattern this regex was compiled from
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Escape the given text, so it gets matched literally once used in a pattern
+    pub fn escape(text: &str) -> String {
+        regex::escape(text)
+    }
 }
 
 impl Pattern for &Regex {
@@ -4,10 +4,12 @@
 
 use rayon::iter::IterBridge;
 use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuildError, ThreadPoolBuilder};
 use rayon_cond::CondIterator;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::AtomicU8;
 use std::sync::atomic::Ordering;
+use std::sync::Arc;
 
 // Re-export rayon current_num_threads
 pub use rayon::current_num_threads;
@@ -61,6 +63,60 @@ pub fn set_parallelism(val: bool) {
     PARALLELISM.store(if val { 2 } else { 1 }, Ordering::SeqCst);
 }
 
+/// Where the batch methods of a `Tokenizer` (`encode_batch`, `decode_batch`, ...) run.
+///
+/// Whatever the choice, the `TOKENIZERS_PARALLELISM` environment variable (or
+/// [`set_parallelism`]) keeps the final word: when it disables parallelism, everything runs
+/// serially on the calling thread.
+#[derive(Debug, Clone, Default)]
+pub enum Parallelism {
+    /// Use the global rayon thread pool, shared with the rest of the process
+    #[default]
+    Global,
+    /// Always run serially, on the calling thread
+    Disabled,
+    /// Use a thread pool dedicated to this tokenizer (and its clones)
+    Dedicated(Arc<ThreadPool>),
+}
+
+impl Parallelism {
+    /// Build a new dedicated thread pool with the given number of threads. `0` lets rayon
+    /// pick the number of threads, as for the global pool.
+    pub fn dedicated(num_threads: usize) -> Result<Self, ThreadPoolBuildError> {
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .thread_name(|i| format!("tokenizers-{i}"))
+            .build()?;
+        Ok(Self::Dedicated(Arc::new(pool)))
+    }
+
+    /// The maximum number of threads used by the batch methods
+    pub fn num_threads(&self) -> usize {
+        match self {
+            Self::Global => current_num_threads(),
+            Self::Disabled => 1,
+            Self::Dedicated(pool) => pool.current_num_threads(),
+        }
+    }
+
+    /// Run `op` in the configured thread pool. The given `bool` tells whether `op` is allowed
+    /// to run in parallel, and is meant to be used with the `*_cond` methods of the
+    /// `MaybeParallel*` traits.
+    pub fn install<OP, R>(&self, op: OP) -> R
+    where
+        OP: FnOnce(bool) -> R + Send,
+        R: Send,
+    {
+        match self {
+            Self::Global => op(true),
+            Self::Disabled => op(false),
+            // No need to wake the pool up if it can't be used anyway
+            Self::Dedicated(pool) if get_parallelism() => pool.install(|| op(true)),
+            Self::Dedicated(_) => op(false),
+        }
+    }
+}
+
 /// Allows to convert into an iterator that can be executed either parallelly or serially.
 ///
 /// The choice is made according to the currently set `TOKENIZERS_PARALLELISM` environment variable.
@@ -267,6 +323,24 @@ mod tests {
         assert_eq!(v.into_maybe_par_iter().sum::<u32>(), 42);
     }
 
+    #[test]
+    fn test_parallelism_install() {
+        let v = vec![1u32, 2, 3, 4, 5, 6];
+        let sum = |parallel: bool| v.maybe_par_iter_cond(parallel).sum::<u32>();
+
+        let disabled = Parallelism::Disabled;
+        assert_eq!(disabled.num_threads(), 1);
+        assert!(!disabled.install(|parallel| parallel));
+        assert_eq!(disabled.install(sum), 21);
+
+        let dedicated = Parallelism::dedicated(2).unwrap();
+        assert_eq!(dedicated.num_threads(), 2);
+        assert_eq!(dedicated.install(sum), 21);
+        if get_parallelism() {
+            assert!(dedicated.install(|_| rayon::current_thread_index().is_some()));
+        }
+    }
+
     #[test]
     fn test_maybe_parallel_slice() {
         let v = [1, 2, 3, 4, 5];
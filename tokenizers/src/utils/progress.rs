@@ -1,3 +1,12 @@
+//! # Progress
+//!
+//! Trainers show a progress bar in the terminal by default. A [`ProgressCallback`] can be
+//! provided instead, to surface the progress anywhere else (notebooks, services, GUIs, ...)
+//! or to stop the training early.
+use crate::Result;
+use std::fmt;
+use std::sync::Arc;
+
 #[cfg(feature = "progressbar")]
 pub(crate) use indicatif::{ProgressBar, ProgressStyle};
 
@@ -30,3 +39,98 @@ mod progressbar {
 }
 #[cfg(not(feature = "progressbar"))]
 pub(crate) use progressbar::{ProgressBar, ProgressStyle};
+
+/// A snapshot of the state of a training, as given to a [`ProgressCallback`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrainingProgress {
+    /// The current phase of the training ("Count pairs", "EM training", ...)
+    pub phase: &'static str,
+    /// The number of steps done in the current phase
+    pub iteration: usize,
+    /// The expected number of steps of the current phase
+    pub total: usize,
+    /// The current size of the vocabulary
+    pub vocab_size: usize,
+    /// The current loss, for the trainers optimizing one
+    pub loss: Option<f64>,
+}
+
+/// A user-supplied callback, reporting the progress of a training.
+///
+/// It gets called at the end of each phase, and after each step of the iterative
+/// phases (merges, EM iterations). Returning `false` stops the training early: the model
+/// then gets built from what has been learned so far. The trainers learning everything in
+/// a single phase, like the `WordLevelTrainer`, cannot stop early and fail instead, leaving
+/// the model untouched. A [`fallible`](ProgressCallback::fallible) callback can also fail
+/// the training with its own error.
+///
+/// When a callback is set on a trainer, it replaces the progress bar.
+///
+/// ```
+/// use tokenizers::models::bpe::{BpeTrainer, BPE};
+/// use tokenizers::utils::progress::ProgressCallback;
+/// use tokenizers::{Model, Trainer};
+///
+/// let mut trainer = BpeTrainer::builder()
+///     .vocab_size(1000)
+///     .progress_callback(ProgressCallback::new(|p| p.phase != "Compute merges"))
+///     .build();
+/// trainer.feed(["hello hello"].iter(), |s| Ok(vec![s.to_owned()])).unwrap();
+///
+/// let mut model = BPE::default();
+/// trainer.train(&mut model).unwrap();
+/// // The first merge stopped the training
+/// assert_eq!(model.get_vocab_size(), 6);
+/// ```
+#[derive(Clone)]
+pub struct ProgressCallback(Arc<Callback>);
+
+type Callback = dyn Fn(&TrainingProgress) -> Result<bool> + Send + Sync;
+
+impl ProgressCallback {
+    pub fn new<F>(callback: F) -> Self
+    where
+        F: Fn(&TrainingProgress) -> bool + Send + Sync + 'static,
+    {
+        Self::fallible(move |progress| Ok(callback(progress)))
+    }
+
+    /// A callback whose errors stop the training, which then fails with this error
+    pub fn fallible<F>(callback: F) -> Self
+    where
+        F: Fn(&TrainingProgress) -> Result<bool> + Send + Sync + 'static,
+    {
+        Self(Arc::new(callback))
+    }
+
+    /// Report the given progress, returning whether the training should go on
+    pub fn report(&self, progress: &TrainingProgress) -> Result<bool> {
+        (self.0)(progress)
+    }
+
+    /// Report the end of the only phase of a training, which cannot stop early
+    pub(crate) fn report_single_phase(&self, progress: &TrainingProgress) -> Result<()> {
+        if self.report(progress)? {
+            Ok(())
+        } else {
+            Err(format!(
+                "Cannot stop the training early, it learns everything in the single phase \"{}\"",
+                progress.phase
+            )
+            .into())
+        }
+    }
+}
+
+impl fmt::Debug for ProgressCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ProgressCallback")
+    }
+}
+
+impl PartialEq for ProgressCallback {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+impl Eq for ProgressCallback {}
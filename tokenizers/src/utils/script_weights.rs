@@ -0,0 +1,161 @@
+//! Weights balancing the languages of a multilingual corpus in the training of a vocabulary,
+//! by the Unicode script of its words.
+use crate::pre_tokenizers::byte_level::ByteLevel;
+use crate::pre_tokenizers::unicode_scripts::{get_script, Script, SCRIPT_NAMES};
+use crate::Result;
+use ahash::AHashMap;
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+/// The weight of the words of each Unicode script in the counts a trainer bases its merges
+/// on, like `{"Latin": 0.5}` to make English merges look half as frequent as they are.
+///
+/// A vocabulary trained on a multilingual corpus is otherwise dominated by its
+/// highest-resource language: its merges win over those of the other languages, which then
+/// need more tokens for each word. Lowering the weight of its script, or raising that of the
+/// others, balances the compression across the languages.
+///
+/// The script of a word is the one of most of its chars, ignoring those common to all the
+/// scripts, like the digits or the punctuation, which give the `Common` script when alone.
+/// The names are those of the Unicode scripts, like `Latin`, `Cyrillic`, `Arabic`, `Han`,
+/// `Hiragana` or `Katakana`. The words of the scripts without weight keep their counts. The
+/// words mapped to the chars of the byte-level alphabet, by a `ByteLevel` pre-tokenizer, get
+/// the script of the text they represent.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "AHashMap<String, f64>", into = "AHashMap<String, f64>")]
+pub struct ScriptWeights(AHashMap<String, f64>);
+
+// The weights are always finite
+impl Eq for ScriptWeights {}
+
+impl ScriptWeights {
+    /// Build the weights, which must all be finite and positive or zero, for the scripts
+    /// with a known name
+    pub fn new<I, S>(weights: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = (S, f64)>,
+        S: Into<String>,
+    {
+        let weights = weights
+            .into_iter()
+            .map(|(script, weight)| (script.into(), weight))
+            .collect::<AHashMap<String, f64>>();
+        if let Some((script, weight)) = weights.iter().find(|(_, w)| !w.is_finite() || **w < 0.0) {
+            return Err(format!(
+                "The weight of the script {script} must be finite and positive, got {weight}"
+            )
+            .into());
+        }
+        if let Some(script) = weights.keys().find(|s| !SCRIPT_NAMES.contains(&s.as_str())) {
+            return Err(format!(
+                "Unknown script {script}, expected a Unicode script name like Latin or Han"
+            )
+            .into());
+        }
+        Ok(Self(weights))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn get(&self, script: &str) -> Option<f64> {
+        self.0.get(script).copied()
+    }
+
+    /// The scripts with a weight, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, f64)> {
+        self.0
+            .iter()
+            .map(|(script, weight)| (script.as_str(), *weight))
+    }
+
+    /// Weigh the count of the given word, by the weight of its script
+    pub fn weigh(&self, word: &str, count: u64) -> u64 {
+        if self.0.is_empty() {
+            return count;
+        }
+        match self.get(&format!("{:?}", word_script(word))) {
+            Some(weight) => (count as f64 * weight).round() as u64,
+            None => count,
+        }
+    }
+}
+
+impl TryFrom<AHashMap<String, f64>> for ScriptWeights {
+    type Error = crate::Error;
+
+    fn try_from(weights: AHashMap<String, f64>) -> Result<Self> {
+        Self::new(weights)
+    }
+}
+
+impl From<ScriptWeights> for AHashMap<String, f64> {
+    fn from(weights: ScriptWeights) -> Self {
+        weights.0
+    }
+}
+
+/// The script of most of the chars of the word, ignoring the common and inherited ones. A
+/// word made of byte-level chars is first turned back to its text.
+fn word_script(word: &str) -> Script {
+    let decoded = String::from_utf8(ByteLevel::to_bytes(vec![word.to_owned()]));
+    let word = decoded.as_deref().unwrap_or(word);
+    let mut counts: Vec<(Script, usize)> = vec![];
+    for c in word.chars() {
+        let script = get_script(c);
+        if matches!(script, Script::Common | Script::Inherited | Script::Any) {
+            continue;
+        }
+        match counts.iter_mut().find(|(s, _)| *s == script) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((script, 1)),
+        }
+    }
+    // The first script wins the ties
+    counts
+        .into_iter()
+        .rev()
+        .max_by_key(|(_, count)| *count)
+        .map_or(Script::Common, |(script, _)| script)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scripts() {
+        assert_eq!(word_script("hello"), Script::Latin);
+        assert_eq!(word_script("Ġпривет!"), Script::Cyrillic);
+        assert_eq!(word_script("42."), Script::Common);
+        assert_eq!(word_script("ab"), Script::Latin);
+        assert_eq!(word_script("aб"), Script::Latin);
+        assert_eq!(word_script("aбв"), Script::Cyrillic);
+        // Byte-level words
+        assert_eq!(word_script("ÐŁÑĢÐ¸Ð²ÐµÑĤ"), Script::Cyrillic);
+        assert_eq!(word_script("ĠÐ°Ð±"), Script::Cyrillic);
+        assert_eq!(word_script("café"), Script::Latin);
+    }
+
+    #[test]
+    fn weigh() {
+        let weights = ScriptWeights::new([("Latin", 0.5), ("Common", 0.0)]).unwrap();
+        assert_eq!(weights.weigh("hello", 5), 3);
+        assert_eq!(weights.weigh("привет", 5), 5);
+        assert_eq!(weights.weigh("42", 5), 0);
+        assert_eq!(ScriptWeights::default().weigh("hello", 5), 5);
+
+        assert!(ScriptWeights::new([("Latin", -1.0)]).is_err());
+        assert!(ScriptWeights::new([("Latin", f64::NAN)]).is_err());
+        assert!(ScriptWeights::new([("latin", 1.0)]).is_err());
+        assert!(ScriptWeights::new([("Any", 1.0)]).is_err());
+
+        let json = serde_json::to_string(&weights).unwrap();
+        assert_eq!(
+            serde_json::from_str::<ScriptWeights>(&json).unwrap(),
+            weights
+        );
+        assert!(serde_json::from_str::<ScriptWeights>(r#"{"Latin":-1.0}"#).is_err());
+    }
+}
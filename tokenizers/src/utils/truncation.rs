@@ -19,13 +19,19 @@ impl std::convert::AsRef<str> for TruncationDirection {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TruncationParams {
     #[serde(default)]
     pub direction: TruncationDirection,
     pub max_length: usize,
     pub strategy: TruncationStrategy,
     pub stride: usize,
+    #[serde(default, skip_serializing_if = "SpecialTokensTruncation::is_default")]
+    pub special_tokens: SpecialTokensTruncation,
+    /// The maximum number of overflowing parts kept for each sequence, all of them by
+    /// default. The parts beyond are never built.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_overflowing: Option<usize>,
 }
 
 impl Default for TruncationParams {
@@ -35,6 +41,36 @@ impl Default for TruncationParams {
             strategy: TruncationStrategy::default(),
             stride: 0,
             direction: TruncationDirection::default(),
+            special_tokens: SpecialTokensTruncation::default(),
+            max_overflowing: None,
+        }
+    }
+}
+
+/// How the special tokens added by the post-processor count against the `max_length`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Eq, Default)]
+pub enum SpecialTokensTruncation {
+    /// The special tokens are part of the `max_length`: their room is reserved before
+    /// truncating, for single sequences, pairs and overflowing windows alike, so that the
+    /// final encodings never exceed the `max_length`
+    #[default]
+    Reserve,
+    /// The special tokens are not counted: the sequences are truncated to the `max_length`,
+    /// and the special tokens get added on top of it
+    Exempt,
+}
+
+impl SpecialTokensTruncation {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl std::convert::AsRef<str> for SpecialTokensTruncation {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Reserve => "reserve",
+            Self::Exempt => "exempt",
         }
     }
 }
@@ -47,19 +83,44 @@ pub enum TruncationError {
     /// We cannot truncate the target sequence enough to respect the provided max length.
     #[error("Truncation error: Sequence to truncate too short to respect the provided max_length")]
     SequenceTooShort,
+    /// A sequence must be truncated to a length that does not leave room for the stride.
+    #[error("Truncation error: stride {stride} must be strictly less than the length {length} a sequence gets truncated to")]
+    StrideTooLong { stride: usize, length: usize },
+}
+
+/// How [`TruncationStrategy::Budget`] shares the `max_length` between the sequences of a pair
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PairBudget {
+    /// The first sequence gets at most this number of tokens
+    Fixed(usize),
+    /// The first sequence gets at most this fraction of the `max_length`, between 0 and 1
+    Ratio(f64),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Eq)]
+// A valid ratio is never NaN
+impl Eq for PairBudget {}
+
+impl PairBudget {
+    /// The maximum length of the first sequence, out of the given `max_length`
+    pub fn first_length(&self, max_length: usize) -> usize {
+        match self {
+            Self::Fixed(length) => cmp::min(*length, max_length),
+            // A NaN ratio gives 0
+            Self::Ratio(ratio) => (ratio.clamp(0.0, 1.0) * max_length as f64) as usize,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Eq, Default)]
 pub enum TruncationStrategy {
+    #[default]
     LongestFirst,
     OnlyFirst,
     OnlySecond,
-}
-
-impl Default for TruncationStrategy {
-    fn default() -> Self {
-        Self::LongestFirst
-    }
+    /// The first sequence of a pair gets truncated to its budget, and the second one gets
+    /// the rest, giving back to the first one the room it doesn't use. A single sequence is
+    /// simply truncated to the `max_length`
+    Budget(PairBudget),
 }
 
 impl std::convert::AsRef<str> for TruncationStrategy {
@@ -68,19 +129,37 @@ impl std::convert::AsRef<str> for TruncationStrategy {
             Self::LongestFirst => "longest_first",
             Self::OnlyFirst => "only_first",
             Self::OnlySecond => "only_second",
+            Self::Budget(_) => "budget",
         }
     }
 }
 
+/// Truncate the encoding to `length`, making sure the stride fits
+fn truncate(encoding: &mut Encoding, length: usize, params: &TruncationParams) -> Result<()> {
+    if length > 0 && length < encoding.len() && params.stride >= length {
+        return Err(Box::new(TruncationError::StrideTooLong {
+            stride: params.stride,
+            length,
+        }));
+    }
+    encoding.truncate_with_max_overflowing(
+        length,
+        params.stride,
+        params.direction,
+        params.max_overflowing,
+    );
+    Ok(())
+}
+
 pub fn truncate_encodings(
     mut encoding: Encoding,
     mut pair_encoding: Option<Encoding>,
     params: &TruncationParams,
 ) -> Result<(Encoding, Option<Encoding>)> {
     if params.max_length == 0 {
-        encoding.truncate(0, params.stride, params.direction);
+        truncate(&mut encoding, 0, params)?;
         if let Some(other_encoding) = pair_encoding.as_mut() {
-            other_encoding.truncate(0, params.stride, params.direction);
+            truncate(other_encoding, 0, params)?;
         }
         return Ok((encoding, pair_encoding));
     }
@@ -140,10 +219,24 @@ pub fn truncate_encodings(
                 if swap {
                     mem::swap(&mut n1, &mut n2);
                 }
-                encoding.truncate(n1, params.stride, params.direction);
-                other_encoding.truncate(n2, params.stride, params.direction);
+                truncate(&mut encoding, n1, params)?;
+                truncate(other_encoding, n2, params)?;
             } else {
-                encoding.truncate(total_length - to_remove, params.stride, params.direction);
+                truncate(&mut encoding, total_length - to_remove, params)?;
+            }
+        }
+        TruncationStrategy::Budget(budget) => {
+            if let Some(other_encoding) = pair_encoding.as_mut() {
+                // The room left by a second sequence shorter than the rest goes to the first one
+                let rest = params.max_length.saturating_sub(other_encoding.len());
+                let n1 = cmp::min(
+                    encoding.len(),
+                    cmp::max(budget.first_length(params.max_length), rest),
+                );
+                truncate(&mut encoding, n1, params)?;
+                truncate(other_encoding, params.max_length - n1, params)?;
+            } else {
+                truncate(&mut encoding, params.max_length, params)?;
             }
         }
         TruncationStrategy::OnlyFirst | TruncationStrategy::OnlySecond => {
@@ -157,7 +250,7 @@ pub fn truncate_encodings(
 
             let target_len = target.get_ids().len();
             if target_len > to_remove {
-                target.truncate(target_len - to_remove, params.stride, params.direction);
+                truncate(target, target_len - to_remove, params)?;
             } else {
                 return Err(Box::new(TruncationError::SequenceTooShort));
             }
@@ -283,12 +376,16 @@ mod tests {
             strategy: TruncationStrategy::LongestFirst,
             stride: 0,
             direction: TruncationDirection::Right,
+            ..Default::default()
         };
 
         truncate_and_assert(get_empty(), get_empty(), &params, 0, 0);
         truncate_and_assert(get_empty(), get_short(), &params, 0, 2);
         truncate_and_assert(get_empty(), get_medium(), &params, 0, 4);
         truncate_and_assert(get_empty(), get_long(), &params, 0, 7);
+        // The second sequence is shorter than the rest
+        truncate_and_assert(get_long(), get_short(), &params, 5, 2);
+        truncate_and_assert(get_long(), get_empty(), &params, 7, 0);
 
         truncate_and_assert(get_short(), get_empty(), &params, 2, 0);
         truncate_and_assert(get_short(), get_short(), &params, 2, 2);
@@ -313,6 +410,7 @@ mod tests {
             strategy: TruncationStrategy::LongestFirst,
             stride: 0,
             direction: TruncationDirection::Right,
+            ..Default::default()
         };
 
         truncate_and_assert(get_empty(), get_short(), &params, 0, 0);
@@ -320,6 +418,134 @@ mod tests {
         truncate_and_assert(get_long(), get_long(), &params, 0, 0);
     }
 
+    #[test]
+    fn truncate_encodings_budget() {
+        let mut params = TruncationParams {
+            max_length: 7,
+            strategy: TruncationStrategy::Budget(PairBudget::Fixed(3)),
+            stride: 0,
+            direction: TruncationDirection::Right,
+            ..Default::default()
+        };
+
+        truncate_and_assert(get_short(), get_medium(), &params, 2, 4);
+        truncate_and_assert(get_short(), get_long(), &params, 2, 5);
+        truncate_and_assert(get_medium(), get_short(), &params, 4, 2);
+        truncate_and_assert(get_medium(), get_long(), &params, 3, 4);
+        truncate_and_assert(get_long(), get_long(), &params, 3, 4);
+        truncate_and_assert(get_empty(), get_long(), &params, 0, 7);
+        // The second sequence is shorter than the rest
+        truncate_and_assert(get_long(), get_short(), &params, 5, 2);
+        truncate_and_assert(get_long(), get_empty(), &params, 7, 0);
+
+        params.strategy = TruncationStrategy::Budget(PairBudget::Ratio(0.75));
+        truncate_and_assert(get_long(), get_long(), &params, 5, 2);
+        truncate_and_assert(get_long(), get_short(), &params, 5, 2);
+        truncate_and_assert(get_short(), get_long(), &params, 2, 5);
+
+        params.strategy = TruncationStrategy::Budget(PairBudget::Fixed(100));
+        truncate_and_assert(get_long(), get_medium(), &params, 7, 0);
+
+        // A single sequence gets the whole max length
+        params.strategy = TruncationStrategy::Budget(PairBudget::Fixed(3));
+        let (encoding, _) = truncate_encodings(get_long(), None, &params).unwrap();
+        assert_eq!(encoding.len(), 7);
+    }
+
+    #[test]
+    fn truncate_encodings_stride_too_long() {
+        let params = TruncationParams {
+            max_length: 7,
+            strategy: TruncationStrategy::OnlySecond,
+            stride: 2,
+            direction: TruncationDirection::Right,
+            ..Default::default()
+        };
+        // The second sequence only has room for 3 tokens, with a stride of 2
+        let (_, pair) = truncate_encodings(get_medium(), Some(get_long()), &params).unwrap();
+        let pair = pair.unwrap();
+        assert_eq!(pair.len(), 3);
+        assert_eq!(pair.get_overflowing().len(), 5);
+
+        // Only 1 token left, which cannot fit the stride
+        let err = truncate_encodings(
+            get_medium(),
+            Some(get_short()),
+            &TruncationParams {
+                max_length: 5,
+                ..params
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Truncation error: stride 2 must be strictly less than the length 1 a sequence gets truncated to"
+        );
+    }
+
+    #[test]
+    fn truncate_encodings_max_overflowing() {
+        let mut params = TruncationParams {
+            max_length: 2,
+            stride: 1,
+            max_overflowing: Some(2),
+            ..Default::default()
+        };
+        let (encoding, _) = truncate_encodings(get_long(), None, &params).unwrap();
+        assert_eq!(encoding.get_ids(), [7, 8]);
+        let overflowing: Vec<_> = encoding
+            .get_overflowing()
+            .iter()
+            .map(|o| o.get_ids().to_vec())
+            .collect();
+        assert_eq!(overflowing, [vec![8, 9], vec![9, 10]]);
+
+        params.direction = TruncationDirection::Left;
+        let (encoding, _) = truncate_encodings(get_long(), None, &params).unwrap();
+        assert_eq!(encoding.get_ids(), [13, 14]);
+        assert_eq!(encoding.get_overflowing()[1].get_ids(), [11, 12]);
+
+        params.max_overflowing = Some(0);
+        params.stride = 0;
+        let (encoding, pair) = truncate_encodings(get_long(), Some(get_long()), &params).unwrap();
+        assert!(encoding.get_overflowing().is_empty());
+        assert!(pair.unwrap().get_overflowing().is_empty());
+
+        params.max_length = 0;
+        let (encoding, _) = truncate_encodings(get_long(), None, &params).unwrap();
+        assert!(encoding.is_empty() && encoding.get_overflowing().is_empty());
+
+        // The default keeps them all
+        let (encoding, _) = truncate_encodings(get_long(), None, &Default::default()).unwrap();
+        assert_eq!(encoding.len(), 8);
+        let params = TruncationParams {
+            max_length: 2,
+            stride: 1,
+            ..Default::default()
+        };
+        let (encoding, _) = truncate_encodings(get_long(), None, &params).unwrap();
+        assert_eq!(encoding.get_overflowing().len(), 6);
+    }
+
+    #[test]
+    fn test_serialize_budget() {
+        let strategy = TruncationStrategy::Budget(PairBudget::Fixed(64));
+        let json = serde_json::to_string(&strategy).unwrap();
+        assert_eq!(json, r#"{"Budget":{"Fixed":64}}"#);
+        assert_eq!(
+            serde_json::from_str::<TruncationStrategy>(&json).unwrap(),
+            strategy
+        );
+
+        let strategy = TruncationStrategy::Budget(PairBudget::Ratio(0.25));
+        let json = serde_json::to_string(&strategy).unwrap();
+        assert_eq!(json, r#"{"Budget":{"Ratio":0.25}}"#);
+        assert_eq!(
+            serde_json::from_str::<TruncationStrategy>(&json).unwrap(),
+            strategy
+        );
+    }
+
     #[test]
     fn test_deserialize_defaults() {
         let old_truncation_params = r#"{"max_length":256,"strategy":"LongestFirst","stride":0}"#;
@@ -327,5 +553,21 @@ mod tests {
         let params: TruncationParams = serde_json::from_str(old_truncation_params).unwrap();
 
         assert_eq!(params.direction, TruncationDirection::Right);
+        assert_eq!(params.special_tokens, SpecialTokensTruncation::Reserve);
+    }
+
+    #[test]
+    fn test_serialize_special_tokens() {
+        let mut params = TruncationParams::default();
+        let json = serde_json::to_string(&params).unwrap();
+        assert!(!json.contains("special_tokens"));
+
+        params.special_tokens = SpecialTokensTruncation::Exempt;
+        let json = serde_json::to_string(&params).unwrap();
+        assert!(json.contains(r#""special_tokens":"Exempt""#));
+        assert_eq!(
+            serde_json::from_str::<TruncationParams>(&json).unwrap(),
+            params
+        );
     }
 }
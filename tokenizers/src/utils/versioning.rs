@@ -0,0 +1,159 @@
+//! Versions of the serialization of the components, so that their fields can evolve without
+//! breaking the files written before, nor guessing their version from the fields they have.
+use serde::de::{Deserialize, Deserializer, Error};
+use serde::ser::SerializeStruct;
+use serde_json::{Map, Value};
+use std::convert::TryFrom;
+
+/// The field holding the version of the schema of a component. It's only written by the
+/// components whose schema evolved, the serializations without it use the version 1.
+pub const SCHEMA_VERSION: &str = "schema_version";
+
+/// A component whose schema may evolve, with the hooks upgrading its older serializations
+pub trait Versioned {
+    /// The version of the schema written by this version of the library, the version 1
+    /// being the one written before the versions were introduced
+    const SCHEMA_VERSION: u32;
+
+    /// Upgrade the fields of a serialization from the version `from` to the next one
+    fn upgrade(from: u32, fields: &mut Map<String, Value>) -> Result<(), String>;
+}
+
+/// The version of a serialization of `T`, read from its [`SCHEMA_VERSION`] if any, refusing
+/// the versions more recent than the ones supported
+pub fn schema_version<T: Versioned>(version: Option<&Value>) -> Result<u32, String> {
+    let version = match version {
+        None => 1,
+        Some(version) => version
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .filter(|v| *v >= 1)
+            .ok_or_else(|| format!("Invalid `{SCHEMA_VERSION}`: {version}"))?,
+    };
+    if version > T::SCHEMA_VERSION {
+        return Err(format!(
+            "The version {version} of the schema is more recent than the ones supported (up to {}), \
+             the file was written by a newer version of the library",
+            T::SCHEMA_VERSION
+        ));
+    }
+    Ok(version)
+}
+
+/// Write the [`SCHEMA_VERSION`] of `T` in its serialization, once its schema evolved from the
+/// version 1. The serializations of the other components stay the same as before the versions.
+pub fn serialize_schema_version<T, S>(serialization: &mut S) -> Result<(), S::Error>
+where
+    T: Versioned,
+    S: SerializeStruct,
+{
+    if T::SCHEMA_VERSION > 1 {
+        serialization.serialize_field(SCHEMA_VERSION, &T::SCHEMA_VERSION)
+    } else {
+        serialization.skip_field(SCHEMA_VERSION)
+    }
+}
+
+/// Deserialize the fields of a serialization of `T`, upgraded to its current schema by
+/// [`Versioned::upgrade`], without the [`SCHEMA_VERSION`].
+pub fn upgraded_fields<'de, T, D>(deserializer: D) -> Result<Map<String, Value>, D::Error>
+where
+    T: Versioned,
+    D: Deserializer<'de>,
+{
+    let mut fields = Map::deserialize(deserializer)?;
+    let version =
+        schema_version::<T>(fields.remove(SCHEMA_VERSION).as_ref()).map_err(D::Error::custom)?;
+    for from in version..T::SCHEMA_VERSION {
+        T::upgrade(from, &mut fields).map_err(D::Error::custom)?;
+    }
+    Ok(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Renamed `size` to `length` in its version 2, and made it a string in its version 3
+    struct Component;
+
+    impl Versioned for Component {
+        const SCHEMA_VERSION: u32 = 3;
+
+        fn upgrade(from: u32, fields: &mut Map<String, Value>) -> Result<(), String> {
+            match from {
+                1 => {
+                    let size = fields.remove("size").ok_or("missing field `size`")?;
+                    fields.insert("length".into(), size);
+                }
+                _ => {
+                    let length = fields["length"].to_string();
+                    fields.insert("length".into(), length.into());
+                }
+            }
+            Ok(())
+        }
+    }
+
+    fn upgrade(json: &str) -> Result<Value, serde_json::Error> {
+        let mut deserializer = serde_json::Deserializer::from_str(json);
+        upgraded_fields::<Component, _>(&mut deserializer).map(Value::Object)
+    }
+
+    #[test]
+    fn upgrades() {
+        let upgraded = serde_json::json!({ "length": "3" });
+        assert_eq!(upgrade(r#"{"size":3}"#).unwrap(), upgraded);
+        assert_eq!(
+            upgrade(r#"{"schema_version":2,"length":3}"#).unwrap(),
+            upgraded
+        );
+        assert_eq!(
+            upgrade(r#"{"schema_version":3,"length":"3"}"#).unwrap(),
+            upgraded
+        );
+
+        assert!(upgrade(r#"{"length":3}"#)
+            .unwrap_err()
+            .to_string()
+            .contains("missing field `size`"));
+        assert!(upgrade(r#"{"schema_version":4}"#)
+            .unwrap_err()
+            .to_string()
+            .contains("newer version"));
+        assert!(upgrade(r#"{"schema_version":0}"#).is_err());
+        assert!(upgrade(r#"{"schema_version":"2"}"#).is_err());
+    }
+
+    #[test]
+    fn serialized_only_once_evolved() {
+        struct Unchanged;
+        impl Versioned for Unchanged {
+            const SCHEMA_VERSION: u32 = 1;
+            fn upgrade(_: u32, _: &mut Map<String, Value>) -> Result<(), String> {
+                Ok(())
+            }
+        }
+        fn serialize<T: Versioned>() -> String {
+            struct Serialized<T>(std::marker::PhantomData<T>);
+            impl<T: Versioned> serde::Serialize for Serialized<T> {
+                fn serialize<S: serde::Serializer>(
+                    &self,
+                    serializer: S,
+                ) -> Result<S::Ok, S::Error> {
+                    let mut serialization = serializer.serialize_struct("Serialized", 2)?;
+                    serialize_schema_version::<T, _>(&mut serialization)?;
+                    serialization.serialize_field("length", &3)?;
+                    serialization.end()
+                }
+            }
+            serde_json::to_string(&Serialized::<T>(std::marker::PhantomData)).unwrap()
+        }
+
+        assert_eq!(serialize::<Unchanged>(), r#"{"length":3}"#);
+        assert_eq!(
+            serialize::<Component>(),
+            r#"{"schema_version":3,"length":3}"#
+        );
+    }
+}